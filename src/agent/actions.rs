@@ -1,8 +1,19 @@
-use tracing::debug;
+use std::path::Path;
+use std::time::Instant;
+
+use tracing::{debug, warn};
 
 use crate::error::Result;
+use crate::security::SandboxedFs;
 use crate::tools::{ToolCall, ToolOutput, ToolRegistry, ToolContext};
 
+/// Sandbox directory where the full text of truncated tool output is saved.
+const TOOL_OUTPUT_DIR: &str = "tool-output";
+
+/// Saved outputs kept in [`TOOL_OUTPUT_DIR`]; older ones are deleted when a
+/// new one is saved.
+const MAX_SAVED_OUTPUTS: usize = 20;
+
 /// Parse a ToolCall from the approval queue's stored JSON.
 pub fn parse_tool_call(value: &serde_json::Value) -> Result<ToolCall> {
     let tool = value
@@ -24,20 +35,210 @@ pub fn parse_tool_call(value: &serde_json::Value) -> Result<ToolCall> {
     })
 }
 
-/// Execute a tool call through the registry.
+/// Execute a tool call through the registry, recording its duration and
+/// outcome in the registry's metrics.
 pub async fn execute_tool_call(
     registry: &ToolRegistry,
     ctx: &ToolContext,
     call: &ToolCall,
 ) -> Result<ToolOutput> {
     debug!(tool = %call.tool, "executing tool call");
-    registry.execute(&call.tool, call.params.clone(), ctx).await
+    let started = Instant::now();
+    let result = registry.execute(&call.tool, call.params.clone(), ctx).await;
+    // Unknown tool names come from the LLM; keep them out of the label set.
+    if registry.get(&call.tool).is_some() {
+        let success = result.as_ref().is_ok_and(|o| o.success);
+        registry.metrics().record(&call.tool, started.elapsed(), success);
+    }
+    let output = result?;
+    Ok(match registry.output_limit(&call.tool) {
+        // Paging through a saved output must not save yet another copy.
+        Some(limit) if reads_saved_output(call) => cap_output(output, limit, None, &call.tool),
+        Some(limit) => cap_output(output, limit, Some(&ctx.sandbox), &call.tool),
+        None => output,
+    })
+}
+
+/// Whether `call` reads a file previously saved by [`cap_output`].
+fn reads_saved_output(call: &ToolCall) -> bool {
+    call.tool == "read_file"
+        && call.params["path"]
+            .as_str()
+            .is_some_and(|p| Path::new(p).starts_with(TOOL_OUTPUT_DIR))
+}
+
+/// Truncate output longer than `limit` bytes so it can't blow up the LLM
+/// context.  With a sandbox, the full text is saved there so the agent can
+/// page through it with `read_file`.
+fn cap_output(mut output: ToolOutput, limit: usize, sandbox: Option<&SandboxedFs>, tool: &str) -> ToolOutput {
+    if output.output.len() <= limit {
+        return output;
+    }
+    let cut = floor_char_boundary(&output.output, limit);
+    let omitted = output.output.len() - cut;
+
+    let saved = match sandbox {
+        Some(sandbox) => save_output(sandbox, tool, &output.output, limit),
+        None => ", read a smaller range with offset/limit".to_string(),
+    };
+
+    debug!(tool, limit, omitted, "tool output truncated");
+    output.output.truncate(cut);
+    output
+        .output
+        .push_str(&format!("\n... (truncated, {omitted} bytes omitted{saved})"));
+    output
+}
+
+/// Save `text` under [`TOOL_OUTPUT_DIR`] and describe where it went.
+///
+/// Lines longer than `limit` are split once into `limit`-sized lines, so
+/// every page `read_file` returns with offset/limit fits under the cap.
+fn save_output(sandbox: &SandboxedFs, tool: &str, text: &str, limit: usize) -> String {
+    let name: String = tool
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    let rel = format!("{TOOL_OUTPUT_DIR}/{name}-{}.txt", uuid::Uuid::new_v4());
+
+    let mut wrapped = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let mut rest = line;
+        while rest.trim_end_matches('\n').len() > limit {
+            let cut = floor_char_boundary(rest, limit).max(rest.chars().next().map_or(1, char::len_utf8));
+            wrapped.push_str(&rest[..cut]);
+            wrapped.push('\n');
+            rest = &rest[cut..];
+        }
+        wrapped.push_str(rest);
+    }
+
+    prune_saved_outputs(sandbox);
+    match sandbox.write(Path::new(&rel), wrapped.as_bytes()) {
+        Ok(()) => format!("; full output saved to {rel}, page through it with read_file offset/limit"),
+        Err(e) => {
+            warn!(tool, err = %e, "failed to save full tool output");
+            String::new()
+        }
+    }
+}
+
+/// Delete the oldest saved outputs so at most `MAX_SAVED_OUTPUTS - 1`
+/// remain before a new one is written.
+fn prune_saved_outputs(sandbox: &SandboxedFs) {
+    let Ok(entries) = std::fs::read_dir(sandbox.root().join(TOOL_OUTPUT_DIR)) else {
+        return;
+    };
+    let mut files: Vec<(std::time::SystemTime, std::path::PathBuf)> = entries
+        .flatten()
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            Some((meta.modified().ok()?, e.path()))
+        })
+        .collect();
+    if files.len() < MAX_SAVED_OUTPUTS {
+        return;
+    }
+    files.sort();
+    let excess = files.len() + 1 - MAX_SAVED_OUTPUTS;
+    for (_, path) in files.into_iter().take(excess) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!(path = %path.display(), err = %e, "failed to delete saved tool output");
+        }
+    }
+}
+
+/// The largest char boundary in `s` at or below `index`.
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut cut = index.min(s.len());
+    while !s.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    cut
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn sandbox() -> (tempfile::TempDir, SandboxedFs) {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = SandboxedFs::new(dir.path().to_path_buf()).unwrap();
+        (dir, sandbox)
+    }
+
+    #[test]
+    fn oversized_output_is_truncated_to_cap() {
+        let (_dir, sandbox) = sandbox();
+        let full = "x".repeat(10_000);
+        let capped = cap_output(ToolOutput::ok(full.clone()), 1000, Some(&sandbox), "exec");
+
+        assert!(capped.success);
+        let (kept, marker) = capped.output.split_once("\n... (truncated").unwrap();
+        assert_eq!(kept.len(), 1000);
+        assert!(marker.starts_with(", 9000 bytes omitted; full output saved to tool-output/exec-"));
+
+        let rel = marker.split("saved to ").nth(1).unwrap().split(',').next().unwrap();
+        // Saved in full, with the single long line split to fit the cap.
+        let saved = sandbox.read_to_string(Path::new(rel)).unwrap();
+        assert_eq!(saved.lines().collect::<String>(), full);
+    }
+
+    #[test]
+    fn output_within_cap_is_untouched() {
+        let (_dir, sandbox) = sandbox();
+        let capped = cap_output(ToolOutput::error("short"), 1000, Some(&sandbox), "exec");
+        assert!(!capped.success);
+        assert_eq!(capped.output, "short");
+    }
+
+    #[test]
+    fn truncation_respects_char_boundaries() {
+        let (_dir, sandbox) = sandbox();
+        let capped = cap_output(ToolOutput::ok("é".repeat(10)), 5, Some(&sandbox), "read_file");
+        assert!(capped.output.starts_with("éé\n... (truncated, 16 bytes omitted"));
+    }
+
+    #[test]
+    fn saved_single_line_output_pages_under_cap() {
+        let (_dir, sandbox) = sandbox();
+        let capped = cap_output(ToolOutput::ok("y".repeat(2500)), 1000, Some(&sandbox), "exec");
+        let rel = capped.output.split("saved to ").nth(1).unwrap().split(',').next().unwrap();
+
+        let saved = sandbox.read_to_string(Path::new(rel)).unwrap();
+        let lines: Vec<&str> = saved.lines().collect();
+        assert_eq!(lines.iter().map(|l| l.len()).collect::<Vec<_>>(), vec![1000, 1000, 500]);
+    }
+
+    #[test]
+    fn reading_saved_output_is_not_saved_again() {
+        let (_dir, sandbox) = sandbox();
+        let call = |path: &str| ToolCall {
+            tool: "read_file".into(),
+            params: serde_json::json!({"path": path}),
+            reasoning: String::new(),
+        };
+        assert!(reads_saved_output(&call("tool-output/exec-1.txt")));
+        assert!(!reads_saved_output(&call("notes/tool-output.txt")));
+
+        let capped = cap_output(ToolOutput::ok("z".repeat(2000)), 1000, None, "read_file");
+        assert!(capped.output.ends_with("(truncated, 1000 bytes omitted, read a smaller range with offset/limit)"));
+        assert!(!sandbox.root().join(TOOL_OUTPUT_DIR).exists());
+    }
+
+    #[test]
+    fn old_saved_outputs_are_pruned() {
+        let (_dir, sandbox) = sandbox();
+        for _ in 0..MAX_SAVED_OUTPUTS + 5 {
+            cap_output(ToolOutput::ok("x".repeat(20)), 10, Some(&sandbox), "exec");
+        }
+        let kept = std::fs::read_dir(sandbox.root().join(TOOL_OUTPUT_DIR)).unwrap().count();
+        assert_eq!(kept, MAX_SAVED_OUTPUTS);
+    }
+
     #[test]
     fn parse_tool_call_full() {
         let json = serde_json::json!({
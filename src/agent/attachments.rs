@@ -0,0 +1,411 @@
+//! Images in chat messages.
+//!
+//! Messaging handlers save photos users send into the sandbox's `inbox/`
+//! and pass them to [`Agent::handle_message_with_attachments`].  Images
+//! over [`MAX_INBOUND_IMAGE_BYTES`] are refused, and the tick deletes
+//! inbox files after a week.  When a
+//! backend in the failover chain can see images, each one is captioned
+//! first and the caption stored in archival memory, so later turns can
+//! recall what was sent; the image itself also goes along with the
+//! message.  Otherwise the message only carries a note that an image was
+//! attached but not analyzed.
+//!
+//! In the other direction, images tools report as artifacts (see
+//! [`ToolOutput::with_image`](crate::tools::ToolOutput::with_image)) are
+//! sent back to the chat the message came from, ahead of the text reply.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tracing::{info, warn};
+
+use crate::error::{Result, SafeAgentError};
+use crate::llm::context::UNANALYZED_IMAGE_NOTE;
+use crate::llm::{Attachment, GenerateContext};
+
+use super::Agent;
+
+/// Sandbox directory inbound attachments are saved to.
+const INBOX_DIR: &str = "inbox";
+
+/// Largest inbound image accepted.
+pub const MAX_INBOUND_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Inbox files older than this are deleted; their captions stay in
+/// archival memory.
+const INBOX_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const CAPTION_PROMPT: &str = "Describe this image in one or two sentences, \
+     including any readable text.  Reply with the description only.";
+
+/// The chat a message came from.
+#[derive(Debug, Clone, Copy)]
+pub struct Origin<'a> {
+    pub platform: &'a str,
+    pub channel: &'a str,
+}
+
+impl Origin<'_> {
+    /// Key the chat's conversation history is stored under, so separate
+    /// chats don't share context.
+    pub fn conversation_key(&self) -> String {
+        format!("{}:{}", self.platform, self.channel)
+    }
+}
+
+impl Agent {
+    /// Save an inbound image into the sandbox, named after the platform and
+    /// the time it arrived.
+    pub fn save_inbound_image(&self, platform: &str, data: &[u8], mime_type: &str) -> Result<Attachment> {
+        if data.len() > MAX_INBOUND_IMAGE_BYTES {
+            return Err(SafeAgentError::QuotaExceeded(format!(
+                "{platform} image is {} bytes; the limit is {MAX_INBOUND_IMAGE_BYTES}",
+                data.len()
+            )));
+        }
+        let ext = match mime_type {
+            "image/png" => "png",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            _ => "jpg",
+        };
+        let name = format!(
+            "{platform}-{}-{}.{ext}",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8],
+        );
+        let relative = Path::new(INBOX_DIR).join(name);
+        let sandbox = &self.ctx.sandbox;
+        std::fs::create_dir_all(sandbox.resolve(Path::new(INBOX_DIR))?)?;
+        sandbox.write(&relative, data)?;
+        Ok(Attachment::new(sandbox.resolve(&relative)?, mime_type))
+    }
+
+    /// Delete inbox files older than [`INBOX_RETENTION`], returning how
+    /// many were removed.
+    pub(crate) fn prune_inbox(&self) -> Result<usize> {
+        let dir = self.ctx.sandbox.resolve(Path::new(INBOX_DIR))?;
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let Some(cutoff) = SystemTime::now().checked_sub(INBOX_RETENTION) else {
+            return Ok(0);
+        };
+        let mut removed = 0;
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else { continue };
+            if !meta.is_file() || meta.modified().is_ok_and(|t| t >= cutoff) {
+                continue;
+            }
+            match std::fs::remove_file(entry.path()) {
+                Ok(()) => removed += 1,
+                Err(e) => warn!(err = %e, file = %entry.path().display(), "failed to delete old inbox file"),
+            }
+        }
+        Ok(removed)
+    }
+
+    /// The user's message with a line per attachment: its caption when the
+    /// LLM could describe it, or [`UNANALYZED_IMAGE_NOTE`].
+    pub(crate) async fn describe_attachments(&self, user_message: &str, attachments: &[Attachment]) -> String {
+        let vision = self.llm.supports_vision();
+        let mut text = user_message.to_string();
+        for attachment in attachments {
+            let name = self.attachment_name(&attachment.path);
+            let caption = if vision { self.caption(attachment).await } else { None };
+            let line = match caption {
+                Some(caption) => {
+                    let entry = format!("Image {name} sent by the user: {caption}");
+                    if let Err(e) = self.memory.archival.insert(&entry, "image").await {
+                        warn!(err = %e, "failed to store image caption");
+                    }
+                    format!("[Image {name}: {caption}]")
+                }
+                None => format!("[Image {name}] {UNANALYZED_IMAGE_NOTE}"),
+            };
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&line);
+        }
+        text
+    }
+
+    /// A short description of the image, or `None` if the LLM failed.
+    async fn caption(&self, attachment: &Attachment) -> Option<String> {
+        let gen_ctx = GenerateContext {
+            message: CAPTION_PROMPT,
+            tools: None,
+            prompt_skills: &[],
+            personality: None,
+            persona: None,
+            attachments: std::slice::from_ref(attachment),
+        };
+        match self.llm.generate(&gen_ctx).await {
+            Ok(caption) => {
+                info!(image = %attachment.path.display(), "image captioned");
+                Some(caption.trim().to_string())
+            }
+            Err(e) => {
+                warn!(err = %e, image = %attachment.path.display(), "image captioning failed");
+                None
+            }
+        }
+    }
+
+    /// Send images tools produced (sandbox-relative path, MIME type) to the
+    /// chat the message came from.  Failures are logged; the text reply
+    /// still goes out.
+    pub(crate) async fn deliver_images(&self, origin: Origin<'_>, images: &[(String, String)]) {
+        for (path, mime_type) in images {
+            let path = Path::new(path);
+            let data = match self.ctx.sandbox.resolve(path).and_then(|p| Ok(std::fs::read(p)?)) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!(err = %e, image = %path.display(), "cannot read image artifact");
+                    continue;
+                }
+            };
+            let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("image");
+            match self
+                .ctx
+                .messaging
+                .send_file_to(origin.platform, origin.channel, &data, filename, mime_type)
+                .await
+            {
+                Ok(()) => info!(platform = origin.platform, image = %path.display(), "sent image artifact"),
+                Err(e) => warn!(platform = origin.platform, err = %e, "failed to send image artifact"),
+            }
+        }
+    }
+
+    /// The attachment's path relative to the sandbox, which is how tools
+    /// refer to it.
+    fn attachment_name(&self, path: &Path) -> String {
+        let root = std::fs::canonicalize(self.ctx.sandbox.root()).unwrap_or_else(|_| PathBuf::from(self.ctx.sandbox.root()));
+        path.strip_prefix(&root)
+            .or_else(|_| path.strip_prefix(self.ctx.sandbox.root()))
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use serde_json::Value;
+
+    use crate::llm::{LlmBackend, LlmEngine};
+    use crate::messaging::{MessagingBackend, MessagingManager};
+    use crate::tools::{Tool, ToolContext, ToolOutput, ToolRegistry};
+
+    use super::*;
+
+    /// Captions anything it is shown; records the attachment count of
+    /// every request.
+    struct StubLlm {
+        vision: bool,
+        attachments_seen: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl LlmBackend for StubLlm {
+        fn name(&self) -> &str {
+            "stub"
+        }
+
+        fn supports_vision(&self) -> bool {
+            self.vision
+        }
+
+        async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
+            self.attachments_seen.lock().unwrap().push(ctx.attachments.len());
+            let reply = if ctx.attachments.is_empty() {
+                "I can't see it."
+            } else if ctx.message == CAPTION_PROMPT {
+                "A ginger cat asleep on a keyboard."
+            } else {
+                "That's a cat."
+            };
+            Ok(reply.into())
+        }
+    }
+
+    async fn agent_with(dir: &Path, tools: ToolRegistry, messaging: MessagingManager) -> Agent {
+        let mut config = crate::config::Config::default();
+        config.memory.auto_extract = false;
+        config.auto_approve_tools = vec!["draw".into()];
+        crate::agent::test_agent_with_tools(dir, config, tools, messaging).await
+    }
+
+    async fn agent(dir: &Path, vision: bool) -> (Agent, Arc<StubLlm>) {
+        let mut agent = agent_with(dir, ToolRegistry::new(), MessagingManager::new()).await;
+        let llm = Arc::new(StubLlm { vision, attachments_seen: Mutex::new(Vec::new()) });
+        agent.llm = LlmEngine::with_backend("stub", llm.clone());
+        (agent, llm)
+    }
+
+    #[tokio::test]
+    async fn vision_backend_sees_image_and_caption_is_remembered() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, llm) = agent(dir.path(), true).await;
+        let image = agent.save_inbound_image("telegram", b"jpeg bytes", "image/jpeg").unwrap();
+        assert!(image.path.starts_with(dir.path()) && image.path.exists());
+
+        let reply = agent
+            .handle_message_with_attachments("what is this?", std::slice::from_ref(&image), None, None)
+            .await
+            .unwrap();
+        assert_eq!(reply, "That's a cat.");
+        // The caption request and the reply both carried the image
+        assert_eq!(*llm.attachments_seen.lock().unwrap(), vec![1, 1]);
+
+        let stored = agent.memory.archival.search("ginger", 5).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].content.contains("inbox/telegram-"), "{}", stored[0].content);
+    }
+
+    #[tokio::test]
+    async fn text_only_backend_gets_a_note_instead() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, llm) = agent(dir.path(), false).await;
+        let image = agent.save_inbound_image("whatsapp", b"png bytes", "image/png").unwrap();
+
+        let reply = agent
+            .handle_message_with_attachments("", std::slice::from_ref(&image), None, None)
+            .await
+            .unwrap();
+        assert_eq!(reply, "I can't see it.");
+        assert_eq!(*llm.attachments_seen.lock().unwrap(), vec![0]);
+        assert!(agent.memory.archival.list(0, 10).await.unwrap().is_empty());
+
+        let prompts = agent.recent_prompts(1).await;
+        let context = prompts[0]["context"].as_str().unwrap();
+        assert!(context.contains("(image attached, not analyzed)"), "{context}");
+    }
+
+    #[tokio::test]
+    async fn oversized_images_are_refused_and_old_ones_pruned() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, _) = agent(dir.path(), false).await;
+        let huge = vec![0u8; MAX_INBOUND_IMAGE_BYTES + 1];
+        let err = agent.save_inbound_image("telegram", &huge, "image/jpeg").unwrap_err();
+        assert!(matches!(err, SafeAgentError::QuotaExceeded(_)), "{err}");
+        assert_eq!(agent.prune_inbox().unwrap(), 0);
+
+        let old = agent.save_inbound_image("telegram", b"old", "image/jpeg").unwrap();
+        let fresh = agent.save_inbound_image("telegram", b"new", "image/jpeg").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&old.path)
+            .unwrap()
+            .set_modified(SystemTime::now() - INBOX_RETENTION - Duration::from_secs(60))
+            .unwrap();
+
+        assert_eq!(agent.prune_inbox().unwrap(), 1);
+        assert!(!old.path.exists());
+        assert!(fresh.path.exists());
+    }
+
+    /// Writes a PNG into the sandbox and reports it as an artifact.
+    struct DrawTool;
+
+    #[async_trait]
+    impl Tool for DrawTool {
+        fn name(&self) -> &str {
+            "draw"
+        }
+
+        fn description(&self) -> &str {
+            "draws a picture"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, _params: Value, ctx: &ToolContext) -> Result<ToolOutput> {
+            ctx.sandbox.write(Path::new("cat.png"), b"\x89PNG")?;
+            Ok(ToolOutput::ok("saved cat.png").with_image("cat.png", "image/png"))
+        }
+    }
+
+    /// (channel, filename, mime) per file sent.
+    type SentFiles = Arc<Mutex<Vec<(String, String, String)>>>;
+
+    struct ChatBackend {
+        files: SentFiles,
+    }
+
+    #[async_trait]
+    impl MessagingBackend for ChatBackend {
+        fn platform_name(&self) -> &str {
+            "chat"
+        }
+
+        fn max_message_length(&self) -> usize {
+            4096
+        }
+
+        async fn send_message(&self, _channel: &str, _text: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send_typing(&self, _channel: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send_file(&self, channel: &str, _data: &[u8], filename: &str, mime: &str) -> Result<()> {
+            self.files.lock().unwrap().push((channel.into(), filename.into(), mime.into()));
+            Ok(())
+        }
+    }
+
+    /// Replays canned responses in order.
+    struct ScriptedLlm(Mutex<Vec<&'static str>>);
+
+    #[async_trait]
+    impl LlmBackend for ScriptedLlm {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn generate(&self, _ctx: &GenerateContext<'_>) -> Result<String> {
+            Ok(self.0.lock().unwrap().remove(0).to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn generated_image_is_sent_to_the_originating_chat() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let files: SentFiles = Arc::default();
+        let mut messaging = MessagingManager::new();
+        messaging.register(Arc::new(ChatBackend { files: files.clone() }), "primary".into());
+        let mut tools = ToolRegistry::new();
+        tools.register(Box::new(DrawTool));
+        let mut agent = agent_with(dir.path(), tools, messaging).await;
+        agent.llm = LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm(Mutex::new(vec![
+                "```tool_call\n{\"tool\": \"draw\", \"params\": {}, \"reasoning\": \"asked for a cat\"}\n```",
+                "Here's your cat.",
+            ]))),
+        );
+
+        let origin = Origin { platform: "chat", channel: "room-42" };
+        let reply = agent
+            .handle_message_with_attachments("draw me a cat", &[], Some(origin), None)
+            .await
+            .unwrap();
+        assert_eq!(reply, "Here's your cat.");
+        assert_eq!(
+            *files.lock().unwrap(),
+            vec![("room-42".to_string(), "cat.png".to_string(), "image/png".to_string())]
+        );
+    }
+}
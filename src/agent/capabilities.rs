@@ -0,0 +1,157 @@
+//! Capability manifest.
+//!
+//! A single machine-readable answer to "what can you do?": the tools the
+//! agent may call, its skills, the LLM backends in the failover chain and
+//! whether it is paused or in safe mode.  Served at `/api/capabilities`
+//! and, through the `capabilities` tool, to the agent itself in chat.
+
+use serde::Serialize;
+
+use super::Agent;
+
+/// Everything the agent can currently do.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityManifest {
+    pub agent_name: String,
+    pub version: &'static str,
+    /// Registered tools not blocked by the security policy, sorted by name.
+    pub tools: Vec<ToolCapability>,
+    pub skills: Vec<SkillCapability>,
+    /// The failover chain, primary first.
+    pub backends: Vec<BackendCapability>,
+    pub paused: bool,
+    pub safe_mode: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCapability {
+    pub name: String,
+    pub description: String,
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillCapability {
+    pub name: String,
+    pub description: String,
+    /// `"prompt"` for prompt skills, otherwise the process skill's type.
+    pub kind: String,
+    /// Running for process skills, enabled for prompt skills.
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendCapability {
+    pub key: String,
+    pub name: String,
+    pub vision: bool,
+}
+
+impl Agent {
+    /// Build the capability manifest from the live registries.
+    pub async fn capabilities(&self) -> CapabilityManifest {
+        let tools = self
+            .tools
+            .list()
+            .into_iter()
+            .filter(|(name, _)| !self.capability_checker.is_blocked(name))
+            .map(|(name, description)| ToolCapability {
+                name: name.to_string(),
+                description: description.to_string(),
+                read_only: self.tools.is_read_only(name),
+            })
+            .collect();
+
+        let mut skills: Vec<SkillCapability> = self
+            .skill_manager
+            .lock()
+            .await
+            .list()
+            .into_iter()
+            .map(|s| SkillCapability {
+                name: s.name,
+                description: s.description,
+                kind: s.skill_type,
+                active: s.running,
+            })
+            .collect();
+        skills.extend(self.prompt_skills.iter().map(|s| SkillCapability {
+            name: s.name.clone(),
+            description: s.description.clone(),
+            kind: "prompt".into(),
+            active: s.enabled,
+        }));
+
+        let backends = self
+            .llm
+            .chain()
+            .map(|(key, backend)| BackendCapability {
+                key: key.to_string(),
+                name: backend.name().to_string(),
+                vision: backend.supports_vision(),
+            })
+            .collect();
+
+        CapabilityManifest {
+            agent_name: self.config.agent_name.clone(),
+            version: env!("CARGO_PKG_VERSION"),
+            tools,
+            skills,
+            backends,
+            paused: self.is_paused(),
+            safe_mode: self.is_safe_mode(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::messaging::MessagingManager;
+    use crate::tools::ToolRegistry;
+
+    use super::*;
+
+    async fn agent(dir: &std::path::Path) -> Agent {
+        let mut tools = ToolRegistry::new();
+        tools.register(Box::new(crate::tools::capabilities::CapabilitiesTool));
+        tools.register(Box::new(crate::tools::file::ReadFileTool));
+        crate::agent::test_agent_with_tools(dir, Config::default(), tools, MessagingManager::new()).await
+    }
+
+    #[tokio::test]
+    async fn manifest_lists_enabled_tools() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agent = agent(dir.path()).await;
+
+        let manifest = agent.capabilities().await;
+        let names: Vec<_> = manifest.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["capabilities", "read_file"]);
+        assert!(manifest.tools.iter().all(|t| t.read_only && !t.description.is_empty()));
+        assert!(!manifest.backends.is_empty());
+        assert!(!manifest.paused);
+        assert!(!manifest.safe_mode);
+    }
+
+    #[tokio::test]
+    async fn manifest_reflects_pause_and_safe_mode() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agent = agent(dir.path()).await;
+        agent.pause();
+        agent.set_safe_mode(true);
+
+        let manifest = agent.capabilities().await;
+        assert!(manifest.paused);
+        assert!(manifest.safe_mode);
+
+        let call = crate::tools::ToolCall {
+            tool: "capabilities".into(),
+            params: serde_json::json!({}),
+            reasoning: String::new(),
+        };
+        let output = agent.execute_tool(&call).await.unwrap();
+        let reported: serde_json::Value = serde_json::from_str(&output.output).unwrap();
+        assert_eq!(reported["paused"], true);
+        assert_eq!(reported["safe_mode"], true);
+    }
+}
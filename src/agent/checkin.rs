@@ -0,0 +1,194 @@
+//! Proactive check-ins.
+//!
+//! With `[check_in]` enabled, a tick may message the user unprompted: once
+//! after they've been quiet for `idle_hours`, or when an active goal has gone
+//! `stalled_goal_hours` without task progress.  Check-ins are spaced at
+//! least `min_interval_hours` apart and never sent during quiet hours.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::info;
+
+use crate::error::Result;
+use crate::goals::GoalManager;
+use crate::messaging::Urgency;
+
+use super::Agent;
+
+/// Interaction and check-in times the decision is based on.
+pub(crate) struct CheckInState {
+    /// The last user message (or startup).
+    last_interaction: DateTime<Utc>,
+    last_check_in: Option<DateTime<Utc>>,
+    /// Stalled goals already asked about, with the progress time they were
+    /// stuck at, so each stall is raised only once.
+    asked_about: HashMap<String, DateTime<Utc>>,
+}
+
+impl CheckInState {
+    pub(crate) fn new(now: DateTime<Utc>) -> Self {
+        Self { last_interaction: now, last_check_in: None, asked_about: HashMap::new() }
+    }
+}
+
+fn hours(h: u64) -> Duration {
+    Duration::hours(h.min(i64::MAX as u64 / 3600) as i64)
+}
+
+impl Agent {
+    /// Restart the idle clock; called for every user message.
+    pub(crate) fn touch_interaction(&self) {
+        self.check_in.lock().unwrap().last_interaction = Utc::now();
+    }
+
+    /// Send a check-in if one is due at `now`.  Returns the message sent.
+    pub async fn check_in(&self, now: DateTime<Utc>) -> Result<Option<String>> {
+        let cfg = &self.config.check_in;
+        if !cfg.enabled {
+            return Ok(None);
+        }
+        // A malformed window is reported by config validation
+        let tz: chrono_tz::Tz = self.config.timezone.parse().unwrap_or(chrono_tz::UTC);
+        let local = now.with_timezone(&tz).time();
+        if cfg.quiet_hours().ok().flatten().is_some_and(|q| q.contains(local)) {
+            return Ok(None);
+        }
+
+        let idle = {
+            let st = self.check_in.lock().unwrap();
+            if st.last_check_in.is_some_and(|t| now - t < hours(cfg.min_interval_hours)) {
+                return Ok(None);
+            }
+            now - st.last_interaction >= hours(cfg.idle_hours)
+                && st.last_check_in.is_none_or(|t| t < st.last_interaction)
+        };
+        let message = if idle {
+            Some(cfg.message.clone())
+        } else {
+            self.stalled_goal_message(now).await?
+        };
+        let Some(message) = message else {
+            return Ok(None);
+        };
+
+        self.check_in.lock().unwrap().last_check_in = Some(now);
+        info!(idle, "sending check-in");
+        self.ctx.messaging.send_all(&message, Urgency::Normal).await;
+        self.memory
+            .log_activity("check_in", &message, None, "ok")
+            .await
+            .ok();
+        Ok(Some(message))
+    }
+
+    /// A question about the first active goal that has stalled and hasn't
+    /// been asked about yet.
+    async fn stalled_goal_message(&self, now: DateTime<Utc>) -> Result<Option<String>> {
+        let stall = self.config.check_in.stalled_goal_hours;
+        if stall == 0 {
+            return Ok(None);
+        }
+        let goals = GoalManager::new(self.ctx.db.clone())
+            .list_goals(Some("active"), 100, 0)
+            .await?;
+
+        let mut st = self.check_in.lock().unwrap();
+        for summary in goals {
+            let goal = summary.goal;
+            let Some(since) = goal.progressed_at().filter(|t| now - *t >= hours(stall)) else {
+                continue;
+            };
+            if st.asked_about.get(&goal.id) == Some(&since) {
+                continue;
+            }
+            st.asked_about.insert(goal.id.clone(), since);
+            return Ok(Some(format!(
+                "Goal \"{}\" hasn't made progress in {} hours. Should I try a different approach, or drop it?",
+                goal.title,
+                (now - since).num_hours(),
+            )));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    async fn agent(dir: &std::path::Path, check_in: crate::config::CheckInConfig) -> Agent {
+        let config = crate::config::Config { check_in, ..Default::default() };
+        crate::agent::test_agent_with_tools(
+            dir,
+            config,
+            crate::tools::ToolRegistry::new(),
+            crate::messaging::MessagingManager::new(),
+        )
+        .await
+    }
+
+    fn enabled() -> crate::config::CheckInConfig {
+        crate::config::CheckInConfig {
+            enabled: true,
+            idle_hours: 2,
+            stalled_goal_hours: 0,
+            min_interval_hours: 1,
+            quiet_hours_start: "22:00".into(),
+            quiet_hours_end: "07:00".into(),
+            ..Default::default()
+        }
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn check_in_fires_once_after_idle_threshold() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agent = agent(dir.path(), enabled()).await;
+        agent.check_in.lock().unwrap().last_interaction = at(1, 9);
+
+        assert_eq!(agent.check_in(at(1, 10)).await.unwrap(), None);
+        let sent = agent.check_in(at(1, 11)).await.unwrap();
+        assert_eq!(sent.as_deref(), Some("Anything you'd like me to work on?"));
+        // Not again until the user has said something
+        assert_eq!(agent.check_in(at(1, 15)).await.unwrap(), None);
+
+        agent.check_in.lock().unwrap().last_interaction = at(1, 16);
+        assert!(agent.check_in(at(1, 18)).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn check_in_waits_out_quiet_hours() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agent = agent(dir.path(), enabled()).await;
+        agent.check_in.lock().unwrap().last_interaction = at(1, 19);
+
+        assert_eq!(agent.check_in(at(1, 23)).await.unwrap(), None);
+        assert_eq!(agent.check_in(at(2, 3)).await.unwrap(), None);
+        assert!(agent.check_in(at(2, 7)).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn stalled_goal_is_raised_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agent = agent(
+            dir.path(),
+            crate::config::CheckInConfig { idle_hours: 1000, stalled_goal_hours: 48, ..enabled() },
+        )
+        .await;
+        GoalManager::new(agent.ctx.db.clone())
+            .create_goal("Tidy the inbox", "", 0, None)
+            .await
+            .unwrap();
+
+        let noon = |days: i64| (Utc::now() + Duration::days(days)).date_naive().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        assert_eq!(agent.check_in(noon(1)).await.unwrap(), None);
+        let sent = agent.check_in(noon(3)).await.unwrap().unwrap();
+        assert!(sent.contains("\"Tidy the inbox\" hasn't made progress"), "{sent}");
+        assert_eq!(agent.check_in(noon(4)).await.unwrap(), None);
+    }
+}
@@ -9,6 +9,7 @@ use chrono::{DateTime, Utc};
 use tracing::{debug, error, info, warn};
 
 use crate::error::Result;
+use crate::messaging::Urgency;
 use crate::tools::ToolCall;
 
 use super::Agent;
@@ -59,7 +60,7 @@ impl Agent {
                         };
 
                         let result =
-                            super::actions::execute_tool_call(&self.tools, &self.ctx, &tc).await;
+                            self.execute_tool(&tc).await;
 
                         match result {
                             Ok(output) => {
@@ -80,15 +81,19 @@ impl Agent {
                                     "cron_job": job.name,
                                 }));
 
-                                // Send proactive notification for non-trivial results
-                                if !output.output.is_empty() && output.output.len() > 5 {
+                                // Send proactive notification for non-trivial results.
+                                // Reminders deliver their own message.
+                                if tool != crate::tools::reminder::TOOL_NAME
+                                    && !output.output.is_empty()
+                                    && output.output.len() > 5
+                                {
                                     let msg = format!(
                                         "[Cron: {}] {}: {}",
                                         job.name,
                                         status,
                                         super::truncate_preview(&output.output, 500),
                                     );
-                                    self.ctx.messaging.send_all(&msg).await;
+                                    self.ctx.messaging.send_all(&msg, Urgency::Normal).await;
                                 }
                             }
                             Err(e) => {
@@ -111,6 +116,10 @@ impl Agent {
                 }
 
                 self.update_cron_last_run(&job.id, now).await.ok();
+                if next_occurrence(&job.schedule, now).is_none() {
+                    info!(job_id = %job.id, "cron job has no further occurrences; disabling");
+                    self.disable_cron_job(&job.id).await.ok();
+                }
             }
         }
 
@@ -188,6 +197,17 @@ impl Agent {
         )?;
         Ok(())
     }
+
+    async fn disable_cron_job(&self, job_id: &str) -> Result<()> {
+        let db = self.ctx.db.lock().await;
+        db.execute("UPDATE cron_jobs SET enabled = 0 WHERE id = ?1", [job_id])?;
+        Ok(())
+    }
+}
+
+/// The next time `schedule` fires after `after`, if ever.
+fn next_occurrence(schedule: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    cron::Schedule::from_str(schedule).ok()?.after(&after).next()
 }
 
 /// Parse a datetime string in various common formats.
@@ -0,0 +1,115 @@
+//! Graceful shutdown: stop taking new work, let in-flight work finish.
+//!
+//! Ticks, incoming messages and approved-action runs each hold a
+//! [`WorkGuard`] while they execute.  [`Drain::drain`] flips the draining
+//! flag — after which [`Drain::start`] refuses new work — and waits for the
+//! guards still held to drop, up to a grace period.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+#[derive(Debug, Default)]
+pub struct Drain {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+/// Marks one unit of work as in flight until dropped.
+pub struct WorkGuard<'a> {
+    drain: &'a Drain,
+}
+
+impl Drop for WorkGuard<'_> {
+    fn drop(&mut self) {
+        if self.drain.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.drain.idle.notify_waiters();
+        }
+    }
+}
+
+impl Drain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Register a unit of work.  `None` once draining has begun.
+    pub fn start(&self) -> Option<WorkGuard<'_>> {
+        // Count first, then check: `drain` sets the flag before reading the
+        // count, so either it sees this work or this sees the flag.
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = WorkGuard { drain: self };
+        if self.is_draining() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Stop accepting work and wait up to `grace` for in-flight work to
+    /// finish.  Returns `false` if the grace period ran out first.
+    pub async fn drain(&self, grace: Duration) -> bool {
+        self.draining.store(true, Ordering::SeqCst);
+        let wait_idle = async {
+            loop {
+                let idle = self.idle.notified();
+                if self.in_flight.load(Ordering::SeqCst) == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        tokio::time::timeout(grace, wait_idle).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn in_progress_work_completes_and_no_new_work_starts() {
+        let drain = Arc::new(Drain::new());
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let task = {
+            let drain = drain.clone();
+            let finished = finished.clone();
+            tokio::spawn(async move {
+                let _work = drain.start().unwrap();
+                started_tx.send(()).unwrap();
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                finished.store(true, Ordering::SeqCst);
+            })
+        };
+        started_rx.await.unwrap();
+
+        assert!(drain.drain(Duration::from_secs(5)).await);
+        assert!(finished.load(Ordering::SeqCst));
+        assert!(drain.start().is_none());
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn exceeding_the_grace_period_forces_shutdown() {
+        let drain = Drain::new();
+        let _stuck = drain.start().unwrap();
+
+        assert!(!drain.drain(Duration::from_millis(20)).await);
+        assert!(drain.is_draining());
+    }
+
+    #[tokio::test]
+    async fn draining_with_nothing_in_flight_returns_immediately() {
+        let drain = Drain::new();
+        assert!(drain.drain(Duration::ZERO).await);
+        assert_eq!(drain.in_flight.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,97 @@
+//! Replayable event history for the SSE stream.
+//!
+//! Every structured event gets a monotonic sequence id, sent as the SSE
+//! `id` and as a `seq` field in the JSON.  The last
+//! `dashboard.event_buffer_size` events are kept so a client reconnecting
+//! with `Last-Event-ID` can be sent what it missed before live events
+//! resume.
+
+use std::collections::VecDeque;
+
+/// One message on the SSE channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseMessage {
+    /// Sequence id of a structured event; `None` for bare notifications
+    /// like `update`, which are never replayed.
+    pub id: Option<u64>,
+    pub data: String,
+}
+
+/// Bounded, sequence-numbered history of emitted events.
+#[derive(Debug)]
+pub struct EventLog {
+    capacity: usize,
+    next_seq: u64,
+    events: VecDeque<(u64, serde_json::Value)>,
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), next_seq: 1, events: VecDeque::new() }
+    }
+
+    /// Assign the next sequence id to `event`, store it (evicting the
+    /// oldest past capacity) and return the message to broadcast.
+    pub fn push(&mut self, mut event: serde_json::Value) -> SseMessage {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        if let Some(obj) = event.as_object_mut() {
+            obj.insert("seq".into(), seq.into());
+        }
+        let data = event.to_string();
+        self.events.push_back((seq, event));
+        while self.events.len() > self.capacity {
+            self.events.pop_front();
+        }
+        SseMessage { id: Some(seq), data }
+    }
+
+    /// Buffered events with a sequence id greater than `last_id`, oldest
+    /// first.  If `last_id` has already been evicted, everything still
+    /// buffered is returned.
+    pub fn after(&self, last_id: u64) -> Vec<SseMessage> {
+        self.events
+            .iter()
+            .filter(|(seq, _)| *seq > last_id)
+            .map(|(seq, event)| SseMessage { id: Some(*seq), data: event.to_string() })
+            .collect()
+    }
+
+    /// The last `limit` events, newest last.
+    pub fn recent(&self, limit: usize) -> Vec<serde_json::Value> {
+        let start = self.events.len().saturating_sub(limit);
+        self.events.iter().skip(start).map(|(_, event)| event.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_ids_increase_and_survive_eviction() {
+        let mut log = EventLog::new(2);
+        let ids: Vec<_> = (0..4)
+            .map(|i| log.push(serde_json::json!({ "type": "thinking", "n": i })).id.unwrap())
+            .collect();
+        assert_eq!(ids, [1, 2, 3, 4]);
+
+        let recent = log.recent(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0]["seq"], 3);
+        assert_eq!(recent[1]["n"], 3);
+    }
+
+    #[test]
+    fn replay_returns_only_newer_events() {
+        let mut log = EventLog::new(10);
+        for t in ["a", "b", "c", "d"] {
+            log.push(serde_json::json!({ "type": t }));
+        }
+        let replay = log.after(2);
+        assert_eq!(replay.iter().map(|m| m.id.unwrap()).collect::<Vec<_>>(), [3, 4]);
+        assert!(replay[0].data.contains(r#""type":"c""#));
+        assert!(log.after(4).is_empty());
+        assert_eq!(log.after(0).len(), 4);
+    }
+}
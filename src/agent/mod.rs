@@ -1,45 +1,78 @@
 pub mod actions;
+pub mod attachments;
+pub mod capabilities;
+pub mod checkin;
 pub mod cron_runner;
+pub mod drain;
+pub mod events;
+pub mod presets;
 pub mod reasoning;
 pub mod tick;
 pub mod tool_parse;
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use rusqlite::Connection;
 use tokio::sync::{broadcast, Mutex};
 use tracing::{debug, error, info, warn};
 
+use crate::approval::policy::AutoApprovePolicy;
 use crate::approval::ApprovalQueue;
-use crate::config::Config;
-use crate::error::Result;
-use crate::llm::LlmEngine;
+use crate::config::{Config, ConfigReload};
+use crate::error::{Result, SafeAgentError};
+use crate::goals::GoalManager;
+use crate::llm::{GenerateContext, LlmEngine};
+use crate::memory::consolidation::ConsolidationScheduler;
 use crate::memory::MemoryManager;
 use crate::messaging::MessagingManager;
 use crate::security::audit::AuditLogger;
+use reasoning::{ReasoningTrace, ReasoningTraceStore, ToolDisposition, TraceToolCall};
 use crate::security::capabilities::CapabilityChecker;
 use crate::security::cost_tracker::CostTracker;
 use crate::security::pii::PiiScanner;
 use crate::security::rate_limiter::RateLimiter;
 use crate::security::twofa::TwoFactorManager;
-use crate::skills::{PluginRegistry, PromptSkill, SkillManager};
-use crate::tools::{ToolContext, ToolRegistry};
+use crate::skills::{PluginRegistry, PromptSkill, SkillManager, SkillTokenStore};
+use crate::tools::{ToolCall, ToolContext, ToolOutput, ToolRegistry};
 use crate::trash::TrashManager;
 use crate::tunnel::TunnelUrl;
-use crate::federation::FederationManager;
+use crate::federation::{FederationManager, NodeKey};
 use crate::security::SandboxedFs;
 use crate::crypto::FieldEncryptor;
 use crate::users::{UserContext, UserManager};
+use drain::Drain;
+
+/// What became of a tool call made outside the LLM loop.
+#[derive(Debug)]
+pub enum ExternalOutcome {
+    /// The call ran; the output says whether it succeeded.
+    Done(ToolOutput),
+    /// The call waits in the approval queue under this id.
+    Queued(String),
+    /// The call waits for this 2FA challenge to be confirmed.
+    Challenge(String),
+}
 
 pub struct Agent {
+    /// Config as loaded at startup.  Settings changed by
+    /// [`Agent::reload_config`] live in the subsystems that use them.
     pub config: Config,
     pub memory: MemoryManager,
+    /// Gates memory consolidation runs (interval + no overlap).
+    pub consolidation: ConsolidationScheduler,
     pub approval_queue: ApprovalQueue,
+    /// Decides which tool calls skip the approval queue.
+    pub auto_approve: std::sync::RwLock<AutoApprovePolicy>,
     pub tools: ToolRegistry,
     pub llm: LlmEngine,
     pub ctx: ToolContext,
     pub skill_manager: Mutex<SkillManager>,
+    /// Tokens issued to running skills, shared with the extension API.
+    pub skill_tokens: Arc<SkillTokenStore>,
     /// Prompt skills loaded from all plugins at startup.  Read-only after
     /// construction — trigger matching borrows a filtered slice per message.
     pub prompt_skills: Vec<PromptSkill>,
@@ -48,6 +81,13 @@ pub struct Agent {
     /// to avoid re-filtering and cloning every tick.
     always_on_skills: Vec<PromptSkill>,
     pub audit: AuditLogger,
+    /// Per-message reasoning traces, linked to audit entries by `turn_id`.
+    pub traces: ReasoningTraceStore,
+    /// Named runtime-settings presets (see [`presets`]).
+    pub presets: presets::PresetStore,
+    /// When the user last spoke and the agent last checked in (see
+    /// [`checkin`]).
+    check_in: std::sync::Mutex<checkin::CheckInState>,
     pub cost_tracker: CostTracker,
     pub rate_limiter: RateLimiter,
     pub capability_checker: CapabilityChecker,
@@ -55,14 +95,36 @@ pub struct Agent {
     pub twofa: TwoFactorManager,
     pub federation: FederationManager,
     pub user_manager: UserManager,
+    /// Current tick interval; starts at `config.tick_interval_secs`.
+    tick_interval_secs: AtomicU64,
     paused: AtomicBool,
-    sse_tx: broadcast::Sender<String>,
-    /// In-memory ring buffer of recent tool progress events for hydrating the
-    /// dashboard on page reload.
-    recent_events: Mutex<Vec<serde_json::Value>>,
+    /// Set by [`Agent::kill_switch`]; refuses all tool execution until resumed.
+    halted: AtomicBool,
+    /// Set by [`Agent::set_safe_mode`]; side-effecting tools need approval.
+    safe_mode: AtomicBool,
+    /// Tracks in-flight ticks and messages so shutdown can wait for them.
+    drain: Drain,
+    /// Fired by the kill switch to cancel in-flight tool calls.
+    kill_tx: broadcast::Sender<()>,
+    sse_tx: broadcast::Sender<events::SseMessage>,
+    /// Sequence-numbered ring buffer of recent events, for hydrating the
+    /// dashboard on page reload and replaying to reconnecting SSE clients.
+    recent_events: std::sync::Mutex<events::EventLog>,
+    /// The last few system prompts and contexts sent to the LLM, for the
+    /// dashboard's prompt debugger.
+    recent_prompts: Mutex<Vec<serde_json::Value>>,
 }
 
-const MAX_BUFFERED_EVENTS: usize = 50;
+const MAX_BUFFERED_PROMPTS: usize = 10;
+
+/// Reply when a message runs out of tool turns and the summary call fails.
+const EXHAUSTED_TURNS_REPLY: &str =
+    "I ran out of tool-call turns. Here's what I have so far — please let me know if you need more.";
+
+/// Appended to the context for the summary call after the tool turns run out.
+const SUMMARIZE_UNFINISHED_PROMPT: &str = "You have used all your tool-call turns for this message and \
+     cannot call any more tools. Using only the results above, give the user a coherent answer: \
+     what you found or did, and what is still unfinished.";
 
 impl Agent {
     pub async fn new(
@@ -83,13 +145,21 @@ impl Agent {
         } else {
             &config.memory.embedding_host
         };
-        memory.init_embeddings(embed_host, &config.memory.embedding_model);
+        memory.init_embeddings(embed_host, &config.memory);
+        if let Some(ref engine) = memory.embeddings {
+            engine.verify_dim().await?;
+        }
+        let consolidation = ConsolidationScheduler::new(config.memory.consolidation_interval_secs);
 
         // Initialize approval queue
         let approval_queue = ApprovalQueue::new(db.clone(), config.approval_expiry_secs);
+        let auto_approve = AutoApprovePolicy::new(&config.auto_approve_tools, &config.auto_approve_rules)?;
 
         // Initialize LLM engine (Claude CLI or local GGUF)
         let llm = LlmEngine::new(&config)?;
+        if let Some(persona) = memory.core.active_persona().await? {
+            llm.set_persona(Some(&persona.prompt));
+        }
 
         // Build tool context
         let http_client = reqwest::Client::builder()
@@ -107,11 +177,13 @@ impl Agent {
 
         // Initialize skill manager
         let skills_dir = sandbox.root().join("skills");
-        let bot_token = std::env::var("TELEGRAM_BOT_TOKEN").ok();
+        let bot_token = config.telegram_bot_token().await.ok();
         let telegram_chat_id = messaging
             .primary_channel("telegram")
             .and_then(|s| s.parse::<i64>().ok());
         let mut skill_manager = SkillManager::new(skills_dir, bot_token, telegram_chat_id);
+        skill_manager.set_rhai_limits(config.rhai.clone());
+        skill_manager.set_registry(crate::skills::registry::SkillRegistry::from_config(&config.skill_registry));
 
         // Initialize plugin registry and load prompt skills + subprocess dirs
         let prompt_skills = {
@@ -174,17 +246,22 @@ impl Agent {
 
         // Security subsystems
         let audit = AuditLogger::new(db.clone());
-        let cost_tracker = CostTracker::new(db.clone(), config.security.daily_cost_limit_usd);
+        let cost_tracker = CostTracker::new(db.clone(), config.security.daily_cost_limit_usd)
+            .with_user_daily_limit(config.security.user_daily_cost_limit_usd);
         let rate_limiter = RateLimiter::new(
             config.security.rate_limit_per_minute,
             config.security.rate_limit_per_hour,
         );
         let capability_checker = CapabilityChecker::new(&config.security);
         let pii_scanner = PiiScanner::new(config.security.pii_detection);
-        let twofa = TwoFactorManager::new(config.security.require_2fa.clone());
+        let twofa = TwoFactorManager::new(config.security.require_2fa.clone())
+            .with_challenge_ttl(Duration::from_secs(config.security.twofa_ttl_secs))
+            .with_require_totp(config.security.twofa_require_totp);
 
         // SSE broadcast channel
         let (sse_tx, _) = broadcast::channel(64);
+        let recent_events = events::EventLog::new(config.dashboard.event_buffer_size);
+        let (kill_tx, _) = broadcast::channel(1);
 
         // Federation
         let fed_name = if config.federation.node_name.is_empty() {
@@ -197,22 +274,35 @@ impl Agent {
         } else {
             config.federation.advertise_address.clone()
         };
-        let federation = FederationManager::new(fed_name, &fed_addr, config.federation.enabled);
+        let federation = FederationManager::new(fed_name, &fed_addr, config.federation.enabled)
+            .with_key(NodeKey::ensure(sandbox.root())?)
+            .with_trusted_keys(config.federation.trusted_peer_keys.iter().cloned())
+            .with_health_checks(
+                config.federation.health_check_interval_secs,
+                config.federation.health_failure_threshold,
+            );
 
         // User management
         let user_manager = UserManager::new(db.clone(), encryptor);
 
         Ok(Self {
+            tick_interval_secs: AtomicU64::new(config.tick_interval_secs),
             config,
             memory,
+            consolidation,
             approval_queue,
+            auto_approve: std::sync::RwLock::new(auto_approve),
             tools,
             llm,
             ctx,
+            skill_tokens: skill_manager.tokens(),
             skill_manager: Mutex::new(skill_manager),
             prompt_skills,
             always_on_skills,
             audit,
+            traces: ReasoningTraceStore::new(db.clone()),
+            presets: presets::PresetStore::new(db.clone()),
+            check_in: std::sync::Mutex::new(checkin::CheckInState::new(chrono::Utc::now())),
             cost_tracker,
             rate_limiter,
             capability_checker,
@@ -221,16 +311,19 @@ impl Agent {
             federation,
             user_manager,
             paused: AtomicBool::new(false),
+            halted: AtomicBool::new(false),
+            safe_mode: AtomicBool::new(false),
+            drain: Drain::new(),
+            kill_tx,
             sse_tx,
-            recent_events: Mutex::new(Vec::with_capacity(MAX_BUFFERED_EVENTS)),
+            recent_events: std::sync::Mutex::new(recent_events),
+            recent_prompts: Mutex::new(Vec::with_capacity(MAX_BUFFERED_PROMPTS)),
         })
     }
 
     /// Run the agent loop until shutdown.
     pub async fn run(&self, mut shutdown: broadcast::Receiver<()>) {
-        let tick_interval = tokio::time::Duration::from_secs(self.config.tick_interval_secs);
-
-        info!(interval_secs = self.config.tick_interval_secs, "agent loop starting");
+        info!(interval_secs = self.tick_interval_secs(), "agent loop starting");
 
         // Initial skill reconciliation on startup
         {
@@ -241,33 +334,38 @@ impl Agent {
         }
 
         loop {
-            // Execute any approved actions first
-            if let Err(e) = self.execute_approved().await {
-                error!("error executing approved actions: {e}");
-            }
+            // Nothing new starts once shutdown has begun draining; the
+            // shutdown signal that ends the loop follows.
+            if let Some(_work) = self.drain.start() {
+                // Execute any approved actions first
+                if let Err(e) = self.execute_approved().await {
+                    error!("error executing approved actions: {e}");
+                }
 
-            // Run a tick if not paused
-            if !self.is_paused() {
-                if let Err(e) = self.tick().await {
-                    error!("tick error: {e}");
-                    self.memory
-                        .log_activity("tick", "tick failed", Some(&e.to_string()), "error")
-                        .await
-                        .ok();
+                // Run a tick if not paused
+                if !self.is_paused() {
+                    if let Err(e) = self.tick().await {
+                        error!("tick error: {e}");
+                        self.memory
+                            .log_activity("tick", "tick failed", Some(&e.to_string()), "error")
+                            .await
+                            .ok();
+                    }
                 }
             }
 
-            // Reconcile skills every tick
-            {
-                let mut sm = self.skill_manager.lock().await;
-                if let Err(e) = sm.reconcile().await {
+            // Reconcile skills every tick (not while halted — that would
+            // restart the skills the kill switch just stopped)
+            if !self.is_halted() && !self.is_draining() {
+                let reconciled = SkillManager::reconcile_probed(&self.skill_manager).await;
+                if let Err(e) = reconciled {
                     error!("skill reconciliation failed: {e}");
                 }
             }
 
             // Wait for tick interval or shutdown
             tokio::select! {
-                _ = tokio::time::sleep(tick_interval) => {}
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(self.tick_interval_secs())) => {}
                 _ = shutdown.recv() => {
                     info!("agent loop shutting down");
                     break;
@@ -284,13 +382,53 @@ impl Agent {
 
     /// Force an immediate tick (from dashboard or Telegram).
     pub async fn force_tick(&self) -> Result<()> {
+        let Some(_work) = self.drain.start() else {
+            return Err(SafeAgentError::Messaging("agent is shutting down".into()));
+        };
         self.tick().await
     }
 
+    /// Whether shutdown has begun: no new ticks or messages are accepted.
+    pub fn is_draining(&self) -> bool {
+        self.drain.is_draining()
+    }
+
+    /// Stop accepting ticks and messages, then wait up to `grace` for the
+    /// ones in progress to finish.  Returns `false` if the grace period ran
+    /// out and work is being abandoned.
+    pub async fn drain(&self, grace: Duration) -> bool {
+        info!(grace_secs = grace.as_secs(), "draining in-flight work");
+        let drained = self.drain.drain(grace).await;
+        if !drained {
+            warn!("shutdown grace period expired with work still in flight");
+        }
+        drained
+    }
+
     /// Handle a message with an explicit user context (multi-user mode).
     /// If `user_ctx` is None, the message is treated as coming from the
-    /// default/system user (backward-compatible single-user mode).
+    /// default/system user (backward-compatible single-user mode).  The
+    /// default conversation history is used.
     pub async fn handle_message_as(&self, user_message: &str, user_ctx: Option<&UserContext>) -> Result<String> {
+        self.handle_message_with_attachments(user_message, &[], None, user_ctx).await
+    }
+
+    /// Like [`handle_message_as`](Self::handle_message_as), for a message
+    /// from a chat that may have come with images.  Images tools produce
+    /// are sent back to `origin`, and the chat keeps its own conversation
+    /// history (see [`attachments::Origin::conversation_key`]).  See
+    /// [`attachments`] for details.
+    pub async fn handle_message_with_attachments(
+        &self,
+        user_message: &str,
+        attachments: &[crate::llm::Attachment],
+        origin: Option<attachments::Origin<'_>>,
+        user_ctx: Option<&UserContext>,
+    ) -> Result<String> {
+        let Some(_work) = self.drain.start() else {
+            return Err(SafeAgentError::Messaging("agent is shutting down".into()));
+        };
+
         // Permission check: viewers cannot send messages
         if let Some(ctx) = user_ctx {
             if !ctx.role.can_chat() {
@@ -302,25 +440,40 @@ impl Agent {
             self.user_manager.touch(&ctx.user_id).await;
         }
 
+        self.touch_interaction();
         let user_id = user_ctx.map(|c| c.user_id.as_str());
 
+        // Captions (or "not analyzed" notes) become part of the message
+        let described;
+        let user_message = if attachments.is_empty() {
+            user_message
+        } else {
+            described = self.describe_attachments(user_message, attachments).await;
+            described.as_str()
+        };
+        let attachments = if self.llm.supports_vision() { attachments } else { &[] };
+
+        // Each chat has its own history; without an origin the default
+        // conversation is used
+        let channel = origin.map(|o| o.conversation_key());
+        let channel = channel.as_deref();
+
         // Store the user message in conversation history
         self.memory
             .conversation
-            .append_with_user("user", user_message, user_id)
+            .append_in("user", user_message, user_id, channel)
             .await?;
 
         let max_turns = self.config.max_tool_turns;
-        let auto_approve: std::collections::HashSet<&str> = self
-            .config
-            .auto_approve_tools
-            .iter()
-            .map(|s| s.as_str())
-            .collect();
 
         // Build the initial context: the user's message plus recent conversation
-        let mut context = self.build_llm_context(user_message).await;
+        let mut context = self.build_llm_context(user_message, channel).await;
         let mut final_text = String::new();
+        let mut trace = ReasoningTrace::new(user_message, user_id);
+        // Images produced by tools, to send along with the reply
+        let mut images: Vec<(String, String)> = Vec::new();
+        // How often each (tool, params) call has come up this message
+        let mut seen_calls: HashMap<u64, usize> = HashMap::new();
 
         // Resolve which prompt skills to inject for this user message.
         // Skills without triggers are always-on; others match by phrase.
@@ -347,11 +500,20 @@ impl Agent {
                 message: &context,
                 tools: Some(&self.tools),
                 prompt_skills: &active_skills,
+                personality: None,
+                persona: None,
+                attachments,
             };
-            let raw_response = self.llm.generate(&gen_ctx).await?;
+            self.cost_tracker.check_budget(user_id).await?;
+            let (response, sent) =
+                crate::llm::prompts::capture_sent(self.llm.generate_response(&gen_ctx)).await;
+            self.record_prompt(sent, &context, user_id, turn).await;
+            let response = response?;
+            self.record_usage(user_id, &context, &response.text).await;
 
-            // Parse tool_call blocks from the response
-            let parsed = tool_parse::parse_llm_response(&raw_response);
+            // Use native tool calls, or parse tool_call blocks from the text
+            let parsed = tool_parse::parse_response(response);
+            trace.begin_turn(turn, &context, &parsed.text);
 
             // If no tool calls, this is the final reply
             if parsed.tool_calls.is_empty() {
@@ -377,10 +539,36 @@ impl Agent {
             let mut pending_approvals: Vec<String> = Vec::new();
 
             for call in &parsed.tool_calls {
+                // --- Loop detection: the same call over and over ---
+                let repeats = seen_calls.entry(call_fingerprint(call)).or_default();
+                *repeats += 1;
+                let limit = self.config.max_repeated_tool_calls;
+                if limit > 0 && *repeats > limit {
+                    let msg = looping_notice(call, *repeats - 1);
+                    warn!(tool = %call.tool, repeats = *repeats, "refusing repeated tool call");
+                    trace.record(TraceToolCall::new(call, ToolDisposition::Looping).with_result(&msg, false));
+                    tool_results.push(format!(
+                        "[Tool result: {} (looping)]\n{}",
+                        call.tool, msg
+                    ));
+                    self.emit_event(serde_json::json!({
+                        "type": "tool_loop",
+                        "tool": call.tool,
+                        "repeats": *repeats,
+                        "turn": turn,
+                    }));
+                    continue;
+                }
+
                 // --- Security gate: blocked tools / capability check ---
                 if self.capability_checker.is_blocked(&call.tool) {
                     let msg = format!("tool '{}' is blocked by security policy", call.tool);
-                    self.audit.log_permission_denied(&call.tool, &msg, "agent").await;
+                    let audit_id = self.audit.log_permission_denied(&call.tool, &msg, "agent").await;
+                    trace.record(
+                        TraceToolCall::new(call, ToolDisposition::Blocked)
+                            .with_result(&msg, false)
+                            .with_audit(audit_id),
+                    );
                     tool_results.push(format!(
                         "[Tool result: {} (blocked)]\n{}",
                         call.tool, msg
@@ -396,7 +584,12 @@ impl Agent {
 
                 if let Err(e) = self.capability_checker.check_or_error(&call.tool, &call.params) {
                     let msg = e.to_string();
-                    self.audit.log_permission_denied(&call.tool, &msg, "agent").await;
+                    let audit_id = self.audit.log_permission_denied(&call.tool, &msg, "agent").await;
+                    trace.record(
+                        TraceToolCall::new(call, ToolDisposition::CapabilityDenied)
+                            .with_result(&msg, false)
+                            .with_audit(audit_id),
+                    );
                     tool_results.push(format!(
                         "[Tool result: {} (capability denied)]\n{}",
                         call.tool, msg
@@ -407,7 +600,12 @@ impl Agent {
                 // --- Security gate: rate limiter ---
                 if let Err(e) = self.rate_limiter.check_and_record() {
                     let msg = e.to_string();
-                    self.audit.log_rate_limit(&call.tool, "agent").await;
+                    let audit_id = self.audit.log_rate_limit(&call.tool, "agent").await;
+                    trace.record(
+                        TraceToolCall::new(call, ToolDisposition::RateLimited)
+                            .with_result(&msg, false)
+                            .with_audit(audit_id),
+                    );
                     tool_results.push(format!(
                         "[Tool result: {} (rate limited)]\n{}",
                         call.tool, msg
@@ -420,16 +618,22 @@ impl Agent {
                     continue;
                 }
 
-                if auto_approve.contains(call.tool.as_str()) {
+                if self.auto_approves(call) {
                     // --- Security gate: 2FA for dangerous auto-approved tools ---
-                    if self.twofa.requires_2fa(&call.tool) {
+                    let gated = crate::security::gate_name(&call.tool, &call.params);
+                    if self.twofa.requires_2fa(gated) {
                         use crate::security::twofa::TwoFactorVerdict;
-                        match self.twofa.check(&call.tool, &call.params, &call.reasoning, "agent") {
+                        match self.twofa.check(gated, &call.params, &call.reasoning, "agent") {
                             TwoFactorVerdict::NotRequired => {
                                 // Should not happen since we checked requires_2fa above
                             }
                             TwoFactorVerdict::ChallengeCreated(id) => {
-                                self.audit.log_2fa(&call.tool, "challenge_created", "agent").await;
+                                let audit_id = self.audit.log_2fa(&call.tool, "challenge_created", "agent").await;
+                                trace.record(
+                                    TraceToolCall::new(call, ToolDisposition::TwoFactor)
+                                        .with_approval(id.clone())
+                                        .with_audit(audit_id),
+                                );
                                 pending_approvals.push(format!(
                                     "{} (2FA required, challenge {}): {}",
                                     call.tool, id, call.reasoning
@@ -464,21 +668,27 @@ impl Agent {
 
                     // Auto-approve: execute immediately
                     debug!(tool = %call.tool, "auto-executing tool call");
-                    match actions::execute_tool_call(&self.tools, &self.ctx, call).await {
+                    match self.execute_tool(call).await {
                         Ok(output) => {
                             let status = if output.success { "success" } else { "error" };
                             let preview = truncate_preview(&output.output, 200);
 
                             // Audit trail
-                            self.audit.log_tool_call(
+                            let audit_id = self.audit.log_tool_call(
                                 &call.tool, &call.params, &preview, output.success,
                                 "agent", &call.reasoning, user_message,
                             ).await;
+                            trace.record(
+                                TraceToolCall::new(call, ToolDisposition::AutoApproved)
+                                    .with_result(&output.output, output.success)
+                                    .with_audit(audit_id),
+                            );
 
                             tool_results.push(format!(
                                 "[Tool result: {} ({})]\n{}",
                                 call.tool, status, output.output
                             ));
+                            images.extend(output.image_artifacts());
                             info!(
                                 tool = %call.tool,
                                 success = output.success,
@@ -498,10 +708,15 @@ impl Agent {
                         Err(e) => {
                             let err_str = e.to_string();
                             let preview = truncate_preview(&err_str, 200);
-                            self.audit.log_tool_call(
+                            let audit_id = self.audit.log_tool_call(
                                 &call.tool, &call.params, &preview, false,
                                 "agent", &call.reasoning, user_message,
                             ).await;
+                            trace.record(
+                                TraceToolCall::new(call, ToolDisposition::AutoApproved)
+                                    .with_result(&err_str, false)
+                                    .with_audit(audit_id),
+                            );
 
                             tool_results.push(format!(
                                 "[Tool result: {} (error)]\n{}",
@@ -532,7 +747,12 @@ impl Agent {
                         .await
                     {
                         Ok(id) => {
-                            self.audit.log_approval(&call.tool, "propose", &call.reasoning, "agent").await;
+                            let audit_id = self.audit.log_approval(&call.tool, "propose", &call.reasoning, "agent").await;
+                            trace.record(
+                                TraceToolCall::new(call, ToolDisposition::Queued)
+                                    .with_approval(id.clone())
+                                    .with_audit(audit_id),
+                            );
                             info!(tool = %call.tool, id = %id, "proposed tool call for approval");
                             pending_approvals.push(format!(
                                 "{} ({}): {}",
@@ -599,16 +819,21 @@ impl Agent {
             break;
         }
 
-        // If we exhausted max_turns without a clean finish, use whatever we have
+        // If we exhausted max_turns without a clean finish, summarise what
+        // the tool results so far amount to
         if final_text.is_empty() {
-            final_text = "I ran out of tool-call turns. Here's what I have so far — please let me know if you need more.".to_string();
+            let summary = self.summarize_unfinished(&context, user_id).await;
             self.emit_event(serde_json::json!({
                 "type": "turn_complete",
                 "turns_used": max_turns,
-                "has_reply": false,
+                "has_reply": summary.is_some(),
                 "exhausted": true,
                 "tool_calls_total": 0,
             }));
+            final_text = summary.unwrap_or_else(|| EXHAUSTED_TURNS_REPLY.to_string());
+            if self.config.continue_unfinished_as_goal {
+                final_text.push_str(&self.continue_as_goal(user_message, &final_text).await);
+            }
         }
 
         // PII detection: scan the final response before sending
@@ -635,12 +860,21 @@ impl Agent {
             );
         }
 
+        trace.reply = Some(final_text.clone());
+        if let Err(e) = self.traces.save(&trace).await {
+            warn!(err = %e, "failed to store reasoning trace");
+        }
+
         // Store the assistant reply
         self.memory
             .conversation
-            .append("assistant", &final_text)
+            .append_in("assistant", &final_text, None, channel)
             .await?;
 
+        if let Some(origin) = origin {
+            self.deliver_images(origin, &images).await;
+        }
+
         // Reconcile skills after every message so newly created or deleted
         // skills are picked up immediately instead of waiting for the next tick.
         {
@@ -668,6 +902,7 @@ impl Agent {
                 &[],
                 "completed",
                 user_id,
+                None,
             ).await {
                 warn!(err = %e, "failed to record episode");
             }
@@ -693,11 +928,22 @@ impl Agent {
         Ok(final_text)
     }
 
+    /// Build the context string sent to the LLM within the configured
+    /// `context_max_tokens` budget.
+    async fn build_llm_context(&self, user_message: &str, channel: Option<&str>) -> String {
+        self.build_llm_context_budgeted(user_message, channel, self.config.context_max_tokens)
+            .await
+    }
+
     /// Build the context string sent to the LLM.
     ///
-    /// Includes: user profile, relevant archival memories, recent conversation,
-    /// and the current message.
-    async fn build_llm_context(&self, user_message: &str) -> String {
+    /// Includes: user profile, relevant archival memories, recent conversation
+    /// in `channel` (the default conversation if `None`), and the current
+    /// message.  With a non-zero `max_tokens`, conversation
+    /// history is trimmed from the oldest end until the estimated size of
+    /// the context plus the core personality fits; the current message is
+    /// always kept.
+    async fn build_llm_context_budgeted(&self, user_message: &str, channel: Option<&str>, max_tokens: usize) -> String {
         let mut ctx = String::new();
 
         // Inject user profile if available
@@ -720,18 +966,118 @@ impl Agent {
         }
 
         // Recent conversation history
-        if let Ok(messages) = self.memory.conversation.recent().await {
-            if !messages.is_empty() {
-                for msg in &messages {
-                    ctx.push_str(&format!("{}: {}\n", capitalize(&msg.role), msg.content));
-                }
+        let mut history: Vec<String> = self
+            .memory
+            .conversation
+            .recent_in(channel)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(|msg| format!("{}: {}\n", capitalize(&msg.role), msg.content))
+            .collect();
+        let current = format!("User: {}", user_message);
+
+        if max_tokens > 0 {
+            use crate::llm::context::estimate_tokens;
+
+            let personality = self
+                .llm
+                .personality()
+                .unwrap_or_else(|| self.config.core_personality.clone());
+            let fixed = estimate_tokens(&personality) + estimate_tokens(&ctx) + estimate_tokens(&current);
+            let mut available = max_tokens.saturating_sub(fixed);
+            let mut keep_from = history.len();
+            while keep_from > 0 && estimate_tokens(&history[keep_from - 1]) <= available {
+                keep_from -= 1;
+                available -= estimate_tokens(&history[keep_from]);
+            }
+            if keep_from > 0 {
+                debug!(dropped = keep_from, max_tokens, "trimmed conversation history to fit token budget");
+                history.drain(..keep_from);
             }
         }
 
-        ctx.push_str(&format!("User: {}", user_message));
+        for line in &history {
+            ctx.push_str(line);
+        }
+        ctx.push_str(&current);
         ctx
     }
 
+    /// Switch the persona layered on the core personality, or drop it with
+    /// `None`.  Takes effect from the next LLM request.
+    pub async fn set_active_persona(&self, name: Option<&str>) -> Result<Option<crate::memory::core::Persona>> {
+        let persona = self.memory.core.set_active_persona(name).await?;
+        self.llm.set_persona(persona.as_ref().map(|p| p.prompt.as_str()));
+        info!(persona = ?name, "active persona changed");
+        self.memory
+            .log_activity("persona", &format!("active persona: {}", name.unwrap_or("none")), None, "ok")
+            .await
+            .ok();
+        Ok(persona)
+    }
+
+    /// Pick up edits to, or deletion of, the active persona.
+    pub async fn reload_persona(&self) -> Result<()> {
+        let persona = self.memory.core.active_persona().await?;
+        self.llm.set_persona(persona.as_ref().map(|p| p.prompt.as_str()));
+        Ok(())
+    }
+
+    /// Seconds between agent ticks.
+    pub fn tick_interval_secs(&self) -> u64 {
+        self.tick_interval_secs.load(Ordering::Relaxed)
+    }
+
+    /// Re-read the config file and apply the settings that can change at
+    /// runtime (see [`crate::config::HOT_RELOADABLE`]).  Changes to other
+    /// settings are reported in `restart_required` and left alone.
+    ///
+    /// Both lists are relative to the startup config, so a setting that
+    /// needs a restart stays flagged until the agent is restarted.
+    pub async fn reload_config(&self) -> Result<ConfigReload> {
+        let reload = self.config.reload()?;
+        let new = &reload.config;
+
+        // Validate before touching anything so a bad rule leaves the
+        // running settings intact.
+        let auto_approve = AutoApprovePolicy::new(&new.auto_approve_tools, &new.auto_approve_rules)?;
+        let quiet_hours = new
+            .quiet_hours
+            .window()
+            .map_err(|e| SafeAgentError::Config(format!("quiet_hours: {e}")))?;
+
+        self.tick_interval_secs.store(new.tick_interval_secs, Ordering::Relaxed);
+        self.rate_limiter.set_limits(
+            new.security.rate_limit_per_minute,
+            new.security.rate_limit_per_hour,
+        );
+        *self.auto_approve.write().unwrap() = auto_approve;
+        self.capability_checker.set_blocked_tools(&new.security.blocked_tools);
+        self.llm.set_personality(&new.core_personality);
+        let tz = self.config.timezone.parse().unwrap_or(chrono_tz::UTC);
+        self.ctx.messaging.set_quiet_hours(quiet_hours, tz);
+
+        info!(
+            applied = ?reload.applied,
+            restart_required = ?reload.restart_required,
+            "config reloaded"
+        );
+        self.memory
+            .log_activity(
+                "config",
+                "config reloaded",
+                Some(&format!(
+                    "applied: {:?}; restart required: {:?}",
+                    reload.applied, reload.restart_required
+                )),
+                "ok",
+            )
+            .await
+            .ok();
+        Ok(reload)
+    }
+
     pub fn is_paused(&self) -> bool {
         self.paused.load(Ordering::Relaxed)
     }
@@ -743,24 +1089,378 @@ impl Agent {
 
     pub fn resume(&self) {
         self.paused.store(false, Ordering::Relaxed);
+        self.halted.store(false, Ordering::Relaxed);
         info!("agent resumed");
     }
 
+    /// Whether the kill switch has been engaged since the last resume.
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::Relaxed)
+    }
+
+    /// Whether safe mode is on.
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode.load(Ordering::Relaxed)
+    }
+
+    /// Turn safe mode on or off.  While it is on, every tool that isn't
+    /// read-only goes to the approval queue regardless of
+    /// `auto_approve_tools`, and unattended callers such as cron jobs and
+    /// goal tasks are refused, so the agent can reason and answer but not
+    /// act.
+    pub fn set_safe_mode(&self, enabled: bool) {
+        self.safe_mode.store(enabled, Ordering::Relaxed);
+        info!(enabled, "safe mode changed");
+    }
+
+    /// Whether `call` may run without human approval: the policy allows it,
+    /// the tool doesn't demand approval for these parameters, and safe mode
+    /// doesn't hold it back.
+    fn auto_approves(&self, call: &ToolCall) -> bool {
+        if self.is_safe_mode() && !self.tools.is_read_only(&call.tool) {
+            return false;
+        }
+        !self.tools.requires_approval(call) && self.auto_approve.read().unwrap().allows(call)
+    }
+
+    /// Execute a tool call unless the kill switch is engaged, or safe mode is
+    /// on and the tool isn't read-only.  A call that is already running when
+    /// the switch fires is cancelled.
+    pub async fn execute_tool(&self, call: &ToolCall) -> Result<ToolOutput> {
+        if self.is_safe_mode() && !self.tools.is_read_only(&call.tool) {
+            return Err(SafeAgentError::PermissionDenied(format!(
+                "safe mode: {} needs approval",
+                call.tool
+            )));
+        }
+        self.execute_approved_tool(call).await
+    }
+
+    /// Execute a tool call a human approved, so safe mode doesn't hold it
+    /// back; the kill switch still does.
+    pub async fn execute_approved_tool(&self, call: &ToolCall) -> Result<ToolOutput> {
+        let mut kill_rx = self.kill_tx.subscribe();
+        if self.is_halted() {
+            return Err(SafeAgentError::PermissionDenied(format!(
+                "kill switch engaged: refusing to run {}",
+                call.tool
+            )));
+        }
+        tokio::select! {
+            result = self.dispatch_tool(call) => result,
+            _ = kill_rx.recv() => {
+                warn!(tool = %call.tool, "tool call cancelled by kill switch");
+                Err(SafeAgentError::PermissionDenied(format!(
+                    "kill switch engaged: {} was cancelled",
+                    call.tool
+                )))
+            }
+        }
+    }
+
+    /// Re-run a tool call recorded in audit entry `original_id`, without
+    /// the LLM.  The blocked-tool, capability, rate-limit and 2FA gates
+    /// still apply and a draining agent refuses; the operator asking for
+    /// the replay stands in for approval.  Returns the new audit entry's id
+    /// and the fresh output.  Gate refusals are errors; tool failures come
+    /// back as unsuccessful output.
+    pub async fn replay_tool_call(
+        &self,
+        original_id: i64,
+        call: &ToolCall,
+        source: &str,
+    ) -> Result<(Option<i64>, ToolOutput)> {
+        let Some(_work) = self.drain.start() else {
+            return Err(SafeAgentError::Messaging("agent is shutting down".into()));
+        };
+        self.check_tool_gates(call, source).await?;
+        if let Some(id) = self.pending_2fa(call, source).await {
+            return Err(SafeAgentError::PermissionDenied(format!(
+                "2FA required for {}: confirm challenge {id}, then replay again",
+                call.tool
+            )));
+        }
+
+        info!(tool = %call.tool, original_id, "replaying audited tool call");
+        let output = self
+            .execute_approved_tool(call)
+            .await
+            .unwrap_or_else(|e| ToolOutput::error(e.to_string()));
+        let preview = truncate_preview(&output.output, 200);
+        let audit_id = self
+            .audit
+            .log_replay(original_id, &call.tool, &call.params, &preview, output.success, source)
+            .await;
+        Ok((audit_id, output))
+    }
+
+    /// Run a tool call for an external client such as an MCP session.  The
+    /// gates of the LLM loop apply: blocked tools, capabilities and the
+    /// rate limit refuse outright, calls that aren't auto-approved go to
+    /// the approval queue, and 2FA tools wait for their challenge.
+    pub async fn call_tool_external(&self, call: &ToolCall, source: &str) -> Result<ExternalOutcome> {
+        let Some(_work) = self.drain.start() else {
+            return Err(SafeAgentError::Messaging("agent is shutting down".into()));
+        };
+        self.check_tool_gates(call, source).await?;
+
+        if !self.auto_approves(call) {
+            let action_json = serde_json::json!({
+                "tool": call.tool,
+                "params": call.params,
+                "reasoning": call.reasoning,
+            });
+            let id = self.approval_queue.propose(action_json, &call.reasoning, source).await?;
+            self.audit.log_approval(&call.tool, "propose", &call.reasoning, source).await;
+            info!(tool = %call.tool, id = %id, source, "proposed external tool call for approval");
+            self.emit_event(serde_json::json!({
+                "type": "approval_needed",
+                "tool": call.tool,
+                "id": id,
+                "reasoning": call.reasoning,
+            }));
+            return Ok(ExternalOutcome::Queued(id));
+        }
+
+        if let Some(id) = self.pending_2fa(call, source).await {
+            return Ok(ExternalOutcome::Challenge(id));
+        }
+
+        let output = self
+            .execute_tool(call)
+            .await
+            .unwrap_or_else(|e| ToolOutput::error(e.to_string()));
+        let preview = truncate_preview(&output.output, 200);
+        self.audit
+            .log_tool_call(&call.tool, &call.params, &preview, output.success, source, &call.reasoning, "")
+            .await;
+        Ok(ExternalOutcome::Done(output))
+    }
+
+    /// The 2FA gate for a call made outside the LLM loop: the id of the
+    /// challenge still waiting for confirmation, or `None` once the call
+    /// may run.
+    async fn pending_2fa(&self, call: &ToolCall, source: &str) -> Option<String> {
+        use crate::security::twofa::TwoFactorVerdict;
+
+        let gated = crate::security::gate_name(&call.tool, &call.params);
+        if !self.twofa.requires_2fa(gated) {
+            return None;
+        }
+        match self.twofa.check(gated, &call.params, &call.reasoning, source) {
+            TwoFactorVerdict::ChallengeCreated(id) => {
+                self.audit.log_2fa(&call.tool, "challenge_created", source).await;
+                self.emit_event(serde_json::json!({
+                    "type": "2fa_challenge",
+                    "tool": call.tool,
+                    "challenge_id": id,
+                    "reasoning": call.reasoning,
+                }));
+                Some(id)
+            }
+            TwoFactorVerdict::Confirmed => {
+                self.audit.log_2fa(&call.tool, "confirmed", source).await;
+                None
+            }
+            TwoFactorVerdict::NotRequired => None,
+        }
+    }
+
+    /// The blocked-tool, capability and rate-limit gates, audited as
+    /// coming from `source`.
+    async fn check_tool_gates(&self, call: &ToolCall, source: &str) -> Result<()> {
+        if self.capability_checker.is_blocked(&call.tool) {
+            let msg = format!("tool '{}' is blocked by security policy", call.tool);
+            self.audit.log_permission_denied(&call.tool, &msg, source).await;
+            return Err(SafeAgentError::PermissionDenied(msg));
+        }
+        if let Err(e) = self.capability_checker.check_or_error(&call.tool, &call.params) {
+            self.audit.log_permission_denied(&call.tool, &e.to_string(), source).await;
+            return Err(e);
+        }
+        if let Err(e) = self.rate_limiter.check_and_record() {
+            self.audit.log_rate_limit(&call.tool, source).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn dispatch_tool(&self, call: &ToolCall) -> Result<ToolOutput> {
+        // Goal decomposition needs the LLM, which tools can't reach.
+        let action = call.params.get("action").and_then(|v| v.as_str());
+        if call.tool == "goal" && action == Some("decompose") && self.tools.get("goal").is_some() {
+            return self.decompose_goal(&call.params).await;
+        }
+        // The manifest is built from registries tools can't reach either.
+        if call.tool == "capabilities" && self.tools.get("capabilities").is_some() {
+            let manifest = self.capabilities().await;
+            return Ok(ToolOutput::ok(serde_json::to_string_pretty(&manifest)?));
+        }
+        actions::execute_tool_call(&self.tools, &self.ctx, call).await
+    }
+
+    /// One last LLM call, without tools, turning the progress in `context`
+    /// into an answer.  `None` if the call fails or comes back empty.
+    async fn summarize_unfinished(&self, context: &str, user_id: Option<&str>) -> Option<String> {
+        let prompt = format!("{context}\n\n{SUMMARIZE_UNFINISHED_PROMPT}");
+        let gen_ctx = GenerateContext {
+            message: &prompt,
+            tools: None,
+            prompt_skills: &[],
+            personality: None,
+            persona: None,
+            attachments: &[],
+        };
+        if let Err(e) = self.cost_tracker.check_budget(user_id).await {
+            warn!(err = %e, "skipping summary of unfinished work");
+            return None;
+        }
+        match self.llm.generate(&gen_ctx).await {
+            Ok(response) => {
+                self.record_usage(user_id, &prompt, &response).await;
+                // Any tool calls it proposes anyway are dropped
+                let text = tool_parse::parse_llm_response(&response).text;
+                (!text.is_empty()).then_some(text)
+            }
+            Err(e) => {
+                warn!(err = %e, "summary of unfinished work failed");
+                None
+            }
+        }
+    }
+
+    /// Create a goal to carry on work a message ran out of turns for.
+    /// Returns a note for the reply, empty if the goal couldn't be created.
+    async fn continue_as_goal(&self, user_message: &str, progress: &str) -> String {
+        let title: String = user_message.chars().take(80).collect();
+        let description = format!("Continue this request:\n{user_message}\n\nProgress so far:\n{progress}");
+        match GoalManager::new(self.ctx.db.clone()).create_goal(&title, &description, 0, None).await {
+            Ok(id) => {
+                info!(goal_id = %id, "continuing unfinished request as a goal");
+                format!("\n\nI'll keep working on the rest in the background (goal {id}).")
+            }
+            Err(e) => {
+                warn!(err = %e, "could not create goal for unfinished request");
+                String::new()
+            }
+        }
+    }
+
+    /// Ask the LLM to break an objective into a goal with ordered tasks and
+    /// persist the result.
+    async fn decompose_goal(&self, params: &serde_json::Value) -> Result<ToolOutput> {
+        use crate::goals::decompose;
+
+        let objective = params
+            .get("objective")
+            .or_else(|| params.get("description"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .trim();
+        if objective.is_empty() {
+            return Ok(ToolOutput::error("objective is required for decompose"));
+        }
+        let priority = params.get("priority").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+
+        let settings = &self.config.goals;
+        let mgr = GoalManager::new(self.ctx.db.clone());
+        let past = mgr.similar_reflections(objective, 3).await?;
+        let prompt = decompose::with_lessons(
+            decompose::build_prompt(&settings.decompose_prompt, objective, settings.max_decomposed_tasks),
+            &past,
+        );
+        let gen_ctx = GenerateContext {
+            message: &prompt,
+            tools: None,
+            prompt_skills: &[],
+            personality: None,
+            persona: None,
+            attachments: &[],
+        };
+        let response = self.llm.generate(&gen_ctx).await?;
+        let plan = match decompose::parse_plan(&response, settings.max_decomposed_tasks) {
+            Ok(plan) => plan,
+            Err(e) => return Ok(ToolOutput::error(format!("could not decompose objective: {e}"))),
+        };
+
+        let (goal_id, task_ids) = decompose::persist_plan(&mgr, &plan, priority).await?;
+
+        let mut out = format!("Created goal: {} ({} tasks)\n", plan.title, plan.tasks.len());
+        for (i, task) in plan.tasks.iter().enumerate() {
+            out.push_str(&format!("  {}. {}", i + 1, task.title));
+            if !task.depends_on.is_empty() {
+                let deps: Vec<String> = task.depends_on.iter().map(|d| (d + 1).to_string()).collect();
+                out.push_str(&format!(" (after {})", deps.join(", ")));
+            }
+            out.push('\n');
+        }
+        Ok(ToolOutput::ok_with_meta(
+            out,
+            serde_json::json!({ "goal_id": goal_id, "task_ids": task_ids }),
+        ))
+    }
+
+    /// Emergency stop: pause the loop, cancel in-flight tool calls, stop all
+    /// skill subprocesses and reject every pending approval.  Stays in effect
+    /// until [`Agent::resume`].  Returns the number of approvals rejected.
+    pub async fn kill_switch(&self, source: &str) -> Result<u64> {
+        warn!(source, "kill switch engaged");
+        self.paused.store(true, Ordering::Relaxed);
+        self.halted.store(true, Ordering::Relaxed);
+        let _ = self.kill_tx.send(());
+
+        self.skill_manager.lock().await.shutdown().await;
+        let rejected = self.approval_queue.reject_all().await?;
+
+        self.audit
+            .log(
+                "kill_switch",
+                None,
+                Some("halt"),
+                None,
+                None,
+                None,
+                Some(&format!("skills stopped, {rejected} pending approvals rejected")),
+                Some(true),
+                source,
+            )
+            .await;
+        self.emit_event(serde_json::json!({
+            "type": "kill_switch",
+            "source": source,
+            "rejected": rejected,
+        }));
+        self.notify_update();
+
+        Ok(rejected)
+    }
+
     /// Subscribe to SSE updates.
-    pub fn subscribe_sse(&self) -> broadcast::Receiver<String> {
+    pub fn subscribe_sse(&self) -> broadcast::Receiver<events::SseMessage> {
         self.sse_tx.subscribe()
     }
 
+    /// Subscribe to SSE updates, first replaying buffered events newer
+    /// than `last_id`.  Subscribing before reading the buffer means nothing
+    /// emitted in between is lost; the caller drops live messages with ids
+    /// the replay already covered.
+    pub fn subscribe_sse_after(&self, last_id: u64) -> (Vec<events::SseMessage>, broadcast::Receiver<events::SseMessage>) {
+        let rx = self.sse_tx.subscribe();
+        let replay = self.recent_events.lock().unwrap().after(last_id);
+        (replay, rx)
+    }
+
     /// Notify SSE subscribers of a generic update (backward-compatible).
     pub fn notify_update(&self) {
-        let _ = self.sse_tx.send("update".to_string());
+        let _ = self.sse_tx.send(events::SseMessage { id: None, data: "update".to_string() });
     }
 
     /// Emit a structured JSON event to SSE subscribers.
     ///
-    /// Events have a `type` field and a `timestamp`, plus type-specific data.
-    /// The dashboard parses these for the real-time activity feed.
-    /// Also buffers the event in memory for REST hydration on page reload.
+    /// Events have a `type` field, a `timestamp` and a sequence id (`seq`),
+    /// plus type-specific data.  The dashboard parses these for the
+    /// real-time activity feed.  Also buffers the event in memory for REST
+    /// hydration on page reload and SSE replay.
     pub fn emit_event(&self, event: serde_json::Value) {
         let mut evt = event;
         if let Some(obj) = evt.as_object_mut() {
@@ -768,23 +1468,84 @@ impl Agent {
                 .or_insert_with(|| serde_json::Value::String(chrono::Utc::now().to_rfc3339()));
         }
 
-        // Buffer the event for REST hydration
-        if let Ok(mut buf) = self.recent_events.try_lock() {
-            buf.push(evt.clone());
-            if buf.len() > MAX_BUFFERED_EVENTS {
-                let excess = buf.len() - MAX_BUFFERED_EVENTS;
-                buf.drain(0..excess);
-            }
-        }
-
-        let _ = self.sse_tx.send(evt.to_string());
+        // Send while holding the buffer so subscribers see ids in order
+        let mut buf = self.recent_events.lock().unwrap();
+        let msg = buf.push(evt);
+        let _ = self.sse_tx.send(msg);
     }
 
     /// Return the last N buffered tool progress events (newest last).
     pub async fn recent_tool_events(&self, limit: usize) -> Vec<serde_json::Value> {
-        let buf = self.recent_events.lock().await;
+        self.recent_events.lock().unwrap().recent(limit)
+    }
+
+    /// Record an LLM turn against the cost budgets.  Backends don't report
+    /// usage uniformly, so tokens are estimated from the text and priced at
+    /// `security.cost_per_1k_tokens_usd`.
+    async fn record_usage(&self, user_id: Option<&str>, prompt: &str, completion: &str) {
+        let prompt_tokens = crate::llm::context::estimate_tokens(prompt) as u32;
+        let completion_tokens = crate::llm::context::estimate_tokens(completion) as u32;
+        let usage = crate::security::cost_tracker::Usage {
+            backend: &self.config.llm.backend,
+            model: "",
+            prompt_tokens,
+            completion_tokens,
+            estimated_cost: f64::from(prompt_tokens + completion_tokens) / 1000.0
+                * self.config.security.cost_per_1k_tokens_usd,
+            context: "message",
+        };
+        if let Err(e) = self.cost_tracker.record(user_id, &usage).await {
+            warn!("failed to record LLM usage: {e}");
+        }
+    }
+
+    /// Buffer the prompt the backend actually sent.  A backend that builds
+    /// no system prompt (a plugin, a test double) is recorded with the
+    /// message alone.
+    async fn record_prompt(
+        &self,
+        sent: Option<crate::llm::prompts::SentPrompt>,
+        message: &str,
+        user_id: Option<&str>,
+        turn: usize,
+    ) {
+        let (system_prompt, context) = match sent {
+            Some(sent) => (Some(sent.system_prompt), sent.message),
+            None => (None, message.to_string()),
+        };
+
+        let mut buf = self.recent_prompts.lock().await;
+        buf.push(serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "user_id": user_id,
+            "turn": turn,
+            "system_prompt": system_prompt,
+            "context": context,
+        }));
+        if buf.len() > MAX_BUFFERED_PROMPTS {
+            let excess = buf.len() - MAX_BUFFERED_PROMPTS;
+            buf.drain(0..excess);
+        }
+    }
+
+    /// Return the last N prompts sent to the LLM (newest last), with
+    /// secret-looking values redacted.
+    pub async fn recent_prompts(&self, limit: usize) -> Vec<serde_json::Value> {
+        let buf = self.recent_prompts.lock().await;
         let start = buf.len().saturating_sub(limit);
-        buf[start..].to_vec()
+        buf[start..]
+            .iter()
+            .map(|entry| {
+                let mut entry = entry.clone();
+                if let Some(serde_json::Value::String(text)) = entry.get_mut("system_prompt") {
+                    *text = crate::security::redact_secret_values(text);
+                }
+                if let Some(serde_json::Value::String(text)) = entry.get_mut("context") {
+                    *text = crate::security::redact_secrets(text);
+                }
+                entry
+            })
+            .collect()
     }
 
     /// Provide the ngrok tunnel URL to the skill manager so it can inject
@@ -803,6 +1564,25 @@ fn capitalize(s: &str) -> String {
     }
 }
 
+/// Identifies a tool call by tool name and parameters.  Object keys are
+/// sorted by `serde_json`, so key order in the LLM output doesn't matter.
+fn call_fingerprint(call: &ToolCall) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    call.tool.hash(&mut hasher);
+    call.params.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// What the model is told instead of a result when it repeats a call.
+fn looping_notice(call: &ToolCall, times: usize) -> String {
+    format!(
+        "Not run: you are looping. You already called {} with exactly these parameters {times} time(s) \
+         for this message. Repeating it won't give a different result. Change your approach: try \
+         different parameters or another tool, or answer with what you have.",
+        call.tool
+    )
+}
+
 /// Truncate a string to `max_len` chars, appending "…" if truncated.
 fn truncate_preview(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
@@ -815,3 +1595,803 @@ fn truncate_preview(s: &str, max_len: usize) -> String {
         format!("{}…", &s[..end])
     }
 }
+
+/// An agent over a throwaway database with its sandbox, trash and key
+/// under `dir`, for tests.
+#[cfg(test)]
+pub(crate) async fn test_agent_with_tools(
+    dir: &std::path::Path,
+    config: Config,
+    tools: ToolRegistry,
+    messaging: MessagingManager,
+) -> Agent {
+    Agent::new(
+        config,
+        crate::db::test_db(),
+        SandboxedFs::new(dir.join("sandbox")).unwrap(),
+        tools,
+        Arc::new(messaging),
+        Arc::new(TrashManager::new(dir).unwrap()),
+        FieldEncryptor::ensure_key(dir).unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    use crate::tools::Tool;
+
+    /// Counts executions; sleeps for `delay` before returning.
+    struct CountingTool {
+        calls: Arc<AtomicUsize>,
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl Tool for CountingTool {
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        fn description(&self) -> &str {
+            "counts calls"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, _params: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+            tokio::time::sleep(self.delay).await;
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ToolOutput::ok("counted"))
+        }
+    }
+
+    /// Replays canned LLM responses in order, building a system prompt for
+    /// each like a real backend.
+    struct ScriptedLlm {
+        responses: std::sync::Mutex<std::collections::VecDeque<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::llm::LlmBackend for ScriptedLlm {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn generate(&self, ctx: &crate::llm::GenerateContext<'_>) -> Result<String> {
+            crate::llm::prompts::SystemPrompt::new("safe-agent", "").build(ctx);
+            Ok(self.responses.lock().unwrap().pop_front().unwrap_or_default())
+        }
+    }
+
+    /// Like [`ScriptedLlm`], also keeping every prompt it was sent.
+    struct RecordingLlm {
+        responses: std::sync::Mutex<std::collections::VecDeque<String>>,
+        prompts: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl RecordingLlm {
+        fn new(script: Vec<String>) -> Self {
+            Self { responses: std::sync::Mutex::new(script.into()), prompts: std::sync::Mutex::new(Vec::new()) }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl crate::llm::LlmBackend for RecordingLlm {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn generate(&self, ctx: &crate::llm::GenerateContext<'_>) -> Result<String> {
+            self.prompts.lock().unwrap().push(ctx.message.to_string());
+            Ok(self.responses.lock().unwrap().pop_front().unwrap_or_default())
+        }
+    }
+
+    /// Keeps calling `counter` until asked to summarise, then answers with
+    /// `summary` or fails if there is none.
+    struct LoopingLlm {
+        summary: Option<&'static str>,
+        summary_requests: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::llm::LlmBackend for LoopingLlm {
+        fn name(&self) -> &str {
+            "looping"
+        }
+
+        async fn generate(&self, ctx: &crate::llm::GenerateContext<'_>) -> Result<String> {
+            if !ctx.message.ends_with(SUMMARIZE_UNFINISHED_PROMPT) {
+                return Ok("```tool_call\n{\"tool\": \"counter\", \"params\": {}}\n```".into());
+            }
+            self.summary_requests.fetch_add(1, Ordering::SeqCst);
+            assert!(ctx.tools.is_none());
+            self.summary.map(str::to_string).ok_or_else(|| SafeAgentError::Llm("backend down".into()))
+        }
+    }
+
+    async fn exhausting_agent(dir: &std::path::Path, summary: Option<&'static str>) -> (Agent, Arc<LoopingLlm>) {
+        let mut config = Config {
+            auto_approve_tools: vec!["counter".into()],
+            max_tool_turns: 2,
+            continue_unfinished_as_goal: true,
+            ..Default::default()
+        };
+        config.memory.auto_extract = false;
+        let (mut agent, _) = test_agent_with_config(dir, Duration::ZERO, config).await;
+        let llm = Arc::new(LoopingLlm { summary, summary_requests: AtomicUsize::new(0) });
+        agent.llm = crate::llm::LlmEngine::with_backend("looping", llm.clone());
+        (agent, llm)
+    }
+
+    async fn test_agent(dir: &std::path::Path, delay: Duration) -> (Agent, Arc<AtomicUsize>) {
+        test_agent_with_config(dir, delay, Config::default()).await
+    }
+
+    async fn test_agent_with_config(
+        dir: &std::path::Path,
+        delay: Duration,
+        config: Config,
+    ) -> (Agent, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut tools = ToolRegistry::new();
+        tools.register(Box::new(CountingTool { calls: calls.clone(), delay }));
+
+        let agent = super::test_agent_with_tools(dir, config, tools, MessagingManager::new()).await;
+        (agent, calls)
+    }
+
+    fn counter_call() -> ToolCall {
+        ToolCall {
+            tool: "counter".into(),
+            params: serde_json::json!({}),
+            reasoning: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn kill_switch_blocks_tools_and_clears_approvals() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, calls) = test_agent(dir.path(), Duration::ZERO).await;
+
+        agent.execute_tool(&counter_call()).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        agent
+            .approval_queue
+            .propose(serde_json::json!({ "tool": "counter", "params": {} }), "test", "test")
+            .await
+            .unwrap();
+
+        let rejected = agent.kill_switch("test").await.unwrap();
+        assert_eq!(rejected, 1);
+        assert!(agent.is_paused());
+        assert!(agent.approval_queue.list_pending().await.unwrap().is_empty());
+
+        assert!(agent.execute_tool(&counter_call()).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        agent.resume();
+        agent.execute_tool(&counter_call()).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn tool_calls_are_recorded_in_metrics() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, _) = test_agent(dir.path(), Duration::ZERO).await;
+
+        agent.execute_tool(&counter_call()).await.unwrap();
+        agent.execute_tool(&counter_call()).await.unwrap();
+        let missing = ToolCall { tool: "nope".into(), ..counter_call() };
+        assert!(agent.execute_tool(&missing).await.is_err());
+
+        let stats = agent.tools.metrics().get("counter").unwrap();
+        assert_eq!((stats.duration.count(), stats.successes, stats.failures), (2, 2, 0));
+        assert!(agent.tools.metrics().get("nope").is_none());
+
+        let text = agent.tools.metrics().render_prometheus();
+        assert!(text.contains("safe_agent_tool_duration_seconds_count{tool=\"counter\"} 2\n"));
+        assert!(text.contains("safe_agent_tool_duration_seconds_bucket{tool=\"counter\",le=\"+Inf\"} 2\n"));
+    }
+
+    #[tokio::test]
+    async fn kill_switch_cancels_in_flight_tool() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, calls) = test_agent(dir.path(), Duration::from_secs(30)).await;
+        let agent = Arc::new(agent);
+
+        let running = {
+            let agent = agent.clone();
+            tokio::spawn(async move { agent.execute_tool(&counter_call()).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        agent.kill_switch("test").await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), running)
+            .await
+            .expect("tool call was not cancelled")
+            .unwrap();
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn goal_decompose_persists_llm_plan() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (mut agent, _) = test_agent(dir.path(), Duration::ZERO).await;
+        agent.tools.register(Box::new(crate::tools::goal::GoalTool::new()));
+        let plan = r#"{"title": "Move house", "tasks": [
+            {"title": "Book movers"}, {"title": "Pack"}, {"title": "Move", "depends_on": [0, 1]}
+        ]}"#;
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm { responses: std::sync::Mutex::new(vec![plan.to_string()].into()) }),
+        );
+
+        let call = ToolCall {
+            tool: "goal".into(),
+            params: serde_json::json!({"action": "decompose", "objective": "move to Leeds in May"}),
+            reasoning: String::new(),
+        };
+        let out = agent.execute_tool(&call).await.unwrap();
+        assert!(out.success, "{}", out.output);
+        assert!(out.output.contains("3. Move (after 1, 2)"));
+
+        let meta = out.metadata.unwrap();
+        let tasks = GoalManager::new(agent.ctx.db.clone())
+            .get_tasks(meta["goal_id"].as_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[2].depends_on, vec![tasks[0].id.clone(), tasks[1].id.clone()]);
+    }
+
+    #[tokio::test]
+    async fn context_budget_trims_oldest_history() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            conversation_window: 50,
+            ..Default::default()
+        };
+        let (agent, _) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+        for i in 0..20 {
+            agent
+                .memory
+                .conversation
+                .append("user", &format!("turn {i:02} {}", "x".repeat(92)))
+                .await
+                .unwrap();
+        }
+
+        let full = agent.build_llm_context_budgeted("latest question", None, 0).await;
+        assert!(full.contains("turn 00"));
+
+        // Each history line is ~26 tokens; 150 leaves room for a few.
+        let ctx = agent.build_llm_context_budgeted("latest question", None, 150).await;
+        assert!(ctx.ends_with("User: latest question"));
+        assert!(crate::llm::context::estimate_tokens(&ctx) <= 150);
+        assert!(!ctx.contains("turn 00"));
+        assert!(ctx.contains("turn 19"));
+        let kept: Vec<&str> = ctx.lines().filter(|l| l.contains("turn ")).collect();
+        assert!(!kept.is_empty() && kept.len() < 20);
+        assert!(kept.last().unwrap().contains("turn 19"));
+
+        // A budget too small for any history still keeps the message.
+        let ctx = agent.build_llm_context_budgeted("latest question", None, 1).await;
+        assert_eq!(ctx, "User: latest question");
+    }
+
+    #[tokio::test]
+    async fn finished_goal_is_reflected_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (mut agent, _) = test_agent(dir.path(), Duration::ZERO).await;
+        let script = vec![
+            "Draft written.".to_string(),
+            "Worked well; outline first next time.".to_string(),
+            "A second reflection.".to_string(),
+        ];
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm { responses: std::sync::Mutex::new(script.into()) }),
+        );
+
+        let mgr = GoalManager::new(agent.ctx.db.clone());
+        let goal_id = mgr.create_goal("Write report", "", 0, None).await.unwrap();
+        mgr.add_task(&goal_id, "Draft", "", None, &[], 0).await.unwrap();
+
+        agent.tick().await.unwrap();
+        let goal = mgr.get_goal(&goal_id).await.unwrap();
+        assert_eq!(goal.status, crate::goals::GoalStatus::Completed);
+        assert_eq!(goal.reflection.as_deref(), Some("Worked well; outline first next time."));
+
+        agent.reflect_if_finished(&mgr, &goal_id).await.unwrap();
+        let goal = mgr.get_goal(&goal_id).await.unwrap();
+        assert_eq!(goal.reflection.as_deref(), Some("Worked well; outline first next time."));
+    }
+
+    #[tokio::test]
+    async fn safe_mode_queues_auto_approved_exec() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            auto_approve_tools: vec!["exec".into(), "counter".into()],
+            ..Default::default()
+        };
+        config.memory.auto_extract = false;
+        let (mut agent, _) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+        agent.tools.register(Box::new(crate::tools::exec::ExecTool::new(10)));
+
+        let exec_call = "```tool_call\n{\"tool\": \"exec\", \"params\": {\"command\": \"touch ran\"}, \"reasoning\": \"test\"}\n```";
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm {
+                responses: std::sync::Mutex::new(vec![exec_call.to_string(), "Queued.".to_string()].into()),
+            }),
+        );
+
+        assert!(!agent.is_safe_mode());
+        agent.set_safe_mode(true);
+        agent.handle_message_as("create the file", None).await.unwrap();
+
+        assert!(!dir.path().join("sandbox/ran").exists());
+        let pending = agent.approval_queue.list_pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].action["tool"], "exec");
+
+        let trace = agent.traces.recent(1).await.unwrap().remove(0);
+        let dispositions: Vec<_> = trace.tool_calls().map(|c| c.disposition).collect();
+        assert_eq!(dispositions, vec![ToolDisposition::Queued]);
+
+        // Leaving safe mode restores the auto-approve policy.
+        agent.set_safe_mode(false);
+        assert!(agent.auto_approves(&counter_call()));
+    }
+
+    #[tokio::test]
+    async fn safe_mode_refuses_unattended_tool_calls() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, calls) = test_agent(dir.path(), Duration::ZERO).await;
+        agent.set_safe_mode(true);
+
+        // Cron jobs and goal tasks call execute_tool without an approval step
+        let err = agent.execute_tool(&counter_call()).await.unwrap_err();
+        assert!(err.to_string().contains("safe mode"), "{err}");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // An approved call still runs
+        agent.execute_approved_tool(&counter_call()).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn draining_finishes_in_flight_message_and_refuses_new_work() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            auto_approve_tools: vec!["counter".into()],
+            ..Default::default()
+        };
+        config.memory.auto_extract = false;
+        let (mut agent, calls) = test_agent_with_config(dir.path(), Duration::from_millis(200), config).await;
+        let call = "```tool_call\n{\"tool\": \"counter\", \"params\": {}, \"reasoning\": \"test\"}\n```";
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm {
+                responses: std::sync::Mutex::new(vec![call.to_string(), "Counted.".to_string()].into()),
+            }),
+        );
+        let agent = Arc::new(agent);
+
+        let in_flight = {
+            let agent = agent.clone();
+            tokio::spawn(async move { agent.handle_message_as("count", None).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert!(agent.drain(Duration::from_secs(5)).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(in_flight.await.unwrap().unwrap(), "Counted.");
+
+        assert!(agent.is_draining());
+        assert!(agent.force_tick().await.is_err());
+        assert!(agent.handle_message_as("again", None).await.is_err());
+        assert_eq!(agent.memory.get_stats().await.unwrap().total_ticks, 0);
+    }
+
+    #[tokio::test]
+    async fn user_over_cost_budget_is_refused_while_others_chat() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.memory.auto_extract = false;
+        config.security.user_daily_cost_limit_usd = 1.0;
+        let (mut agent, _calls) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm {
+                responses: std::sync::Mutex::new(vec!["Hi Bob.".to_string()].into()),
+            }),
+        );
+        let user = |id: &str| crate::users::UserContext {
+            user_id: id.into(),
+            username: id.into(),
+            display_name: id.into(),
+            role: crate::users::UserRole::User,
+            source: "api".into(),
+        };
+        let spend = crate::security::cost_tracker::Usage { estimated_cost: 1.5, ..Default::default() };
+        agent.cost_tracker.record(Some("alice"), &spend).await.unwrap();
+
+        let err = agent.handle_message_as("hello", Some(&user("alice"))).await.unwrap_err();
+        assert!(matches!(err, SafeAgentError::QuotaExceeded(_)));
+        assert_eq!(agent.handle_message_as("hello", Some(&user("bob"))).await.unwrap(), "Hi Bob.");
+    }
+
+    #[tokio::test]
+    async fn message_trace_records_tool_calls_in_order() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            auto_approve_tools: vec!["counter".into()],
+            ..Default::default()
+        };
+        config.memory.auto_extract = false;
+        let (mut agent, calls) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+
+        let tool_call = |tool: &str| {
+            format!("```tool_call\n{{\"tool\": \"{tool}\", \"params\": {{}}, \"reasoning\": \"try {tool}\"}}\n```")
+        };
+        let script = vec![
+            format!("Checking.\n{}\n{}", tool_call("counter"), tool_call("deploy")),
+            format!("Once more.\n{}", tool_call("counter")),
+            "All done.".to_string(),
+        ];
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm { responses: std::sync::Mutex::new(script.into()) }),
+        );
+
+        let reply = agent.handle_message_as("count twice and deploy", None).await.unwrap();
+        assert_eq!(reply, "All done.");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let trace = agent.traces.recent(1).await.unwrap().remove(0);
+        assert_eq!(trace.message, "count twice and deploy");
+        assert_eq!(trace.turns.len(), 3);
+        assert_eq!(trace.reply.as_deref(), Some("All done."));
+
+        let order: Vec<(&str, ToolDisposition)> =
+            trace.tool_calls().map(|c| (c.tool.as_str(), c.disposition)).collect();
+        assert_eq!(
+            order,
+            vec![
+                ("counter", ToolDisposition::AutoApproved),
+                ("deploy", ToolDisposition::Queued),
+                ("counter", ToolDisposition::AutoApproved),
+            ]
+        );
+        assert!(trace.tool_calls().all(|c| c.audit_id.is_some()));
+        assert!(trace.turns[0].tool_calls[1].approval_id.is_some());
+        assert_eq!(trace.turns[1].tool_calls[0].result.as_deref(), Some("counted"));
+
+        let audit_id = trace.turns[0].tool_calls[0].audit_id.unwrap();
+        let linked = agent.traces.for_audit(audit_id).await.unwrap().unwrap();
+        assert_eq!(linked.turn_id, trace.turn_id);
+    }
+
+    #[tokio::test]
+    async fn message_prompt_is_buffered_with_secrets_redacted() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.memory.auto_extract = false;
+        let (mut agent, _) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm { responses: std::sync::Mutex::new(vec!["Noted.".to_string()].into()) }),
+        );
+        assert!(agent.recent_prompts(1).await.is_empty());
+
+        agent
+            .handle_message_as("my GITHUB_TOKEN=ghp_abcdef1234567890 please", None)
+            .await
+            .unwrap();
+
+        let prompts = agent.recent_prompts(1).await;
+        assert_eq!(prompts.len(), 1);
+        let system_prompt = prompts[0]["system_prompt"].as_str().unwrap();
+        assert!(system_prompt.contains("== SAFETY RULES =="), "{system_prompt}");
+        // Tool schemas are not mistaken for secret assignments
+        assert!(!system_prompt.contains("[REDACTED]"), "{system_prompt}");
+        let context = prompts[0]["context"].as_str().unwrap();
+        assert!(context.contains("GITHUB_TOKEN=[REDACTED] please"), "{context}");
+        assert!(!context.contains("ghp_abcdef"));
+    }
+
+    #[tokio::test]
+    async fn active_persona_shapes_the_system_prompt() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.memory.auto_extract = false;
+        let (mut agent, _) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+        agent.llm = crate::llm::LlmEngine::with_backend(
+            "scripted",
+            Arc::new(ScriptedLlm { responses: std::sync::Mutex::new(vec!["Ok.".to_string(); 3].into()) }),
+        );
+        agent.memory.core.save_persona("code-reviewer", "Point out bugs before style.").await.unwrap();
+        let system_prompt = |prompts: Vec<serde_json::Value>| prompts[0]["system_prompt"].as_str().unwrap().to_string();
+
+        agent.set_active_persona(Some("code-reviewer")).await.unwrap();
+        agent.handle_message_as("review this", None).await.unwrap();
+        assert!(system_prompt(agent.recent_prompts(1).await).contains("Point out bugs before style."));
+
+        // Edits to the active persona apply once reloaded
+        agent.memory.core.save_persona("code-reviewer", "Only flag security issues.").await.unwrap();
+        agent.reload_persona().await.unwrap();
+        agent.handle_message_as("and this", None).await.unwrap();
+        assert!(system_prompt(agent.recent_prompts(1).await).contains("Only flag security issues."));
+
+        assert!(agent.set_active_persona(Some("missing")).await.is_err());
+        agent.set_active_persona(None).await.unwrap();
+        agent.handle_message_as("thanks", None).await.unwrap();
+        assert!(!system_prompt(agent.recent_prompts(1).await).contains("ACTIVE PERSONA"));
+    }
+
+    #[tokio::test]
+    async fn reload_config_updates_running_agent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "tick_interval_secs = 120\nauto_approve_tools = [\"counter\"]\n").unwrap();
+        let config = Config::load(Some(&path)).unwrap();
+        let (agent, _) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+        assert_eq!(agent.tick_interval_secs(), 120);
+        assert!(agent.auto_approve.read().unwrap().allows(&counter_call()));
+
+        std::fs::write(
+            &path,
+            "tick_interval_secs = 10\n\
+             auto_approve_tools = []\n\
+             conversation_window = 3\n\
+             [security]\nrate_limit_per_minute = 1\nblocked_tools = [\"deploy\"]\n\
+             [quiet_hours]\nstart = \"22:00\"\nend = \"07:00\"\n",
+        )
+        .unwrap();
+        let reload = agent.reload_config().await.unwrap();
+        assert_eq!(reload.restart_required, vec!["conversation_window"]);
+
+        assert_eq!(agent.tick_interval_secs(), 10);
+        assert_eq!(agent.rate_limiter.status().limit_per_minute, 1);
+        assert!(agent.capability_checker.is_blocked("deploy"));
+        assert!(!agent.auto_approve.read().unwrap().allows(&counter_call()));
+        assert!(reload.applied.contains(&"quiet_hours"));
+        assert!(agent.ctx.messaging.quiet_hours().is_some());
+
+        assert!(agent.rate_limiter.check_and_record().is_ok());
+        assert!(agent.rate_limiter.check_and_record().is_err());
+
+        // A bad file leaves the running settings alone.
+        std::fs::write(&path, "tick_interval_secs = \"soon\"\n").unwrap();
+        assert!(agent.reload_config().await.is_err());
+        assert_eq!(agent.tick_interval_secs(), 10);
+    }
+
+    #[tokio::test]
+    async fn replay_reruns_audited_tool_call() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, calls) = test_agent(dir.path(), Duration::ZERO).await;
+        let call = ToolCall {
+            params: serde_json::json!({ "n": 3 }),
+            reasoning: "count once".into(),
+            ..counter_call()
+        };
+        let original_id = agent
+            .audit
+            .log_tool_call("counter", &call.params, "counted", true, "agent", &call.reasoning, "count")
+            .await
+            .unwrap();
+
+        let recorded = agent.audit.get(original_id).await.unwrap().tool_call().unwrap();
+        assert_eq!(recorded.tool, "counter");
+        assert_eq!(recorded.params, call.params);
+
+        let (audit_id, output) = agent.replay_tool_call(original_id, &recorded, "dashboard").await.unwrap();
+        assert!(output.success);
+        assert_eq!(output.output, "counted");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let replay = agent.audit.get(audit_id.unwrap()).await.unwrap();
+        assert_ne!(replay.id, original_id);
+        assert_eq!(replay.tool.as_deref(), Some("counter"));
+        assert_eq!(replay.action.as_deref(), Some("replay"));
+        assert_eq!(replay.source, "dashboard");
+        assert_eq!(
+            replay.user_context.as_deref(),
+            Some(format!("replay of audit entry #{original_id}").as_str())
+        );
+        assert_eq!(replay.tool_call().unwrap().params, call.params);
+    }
+
+    #[tokio::test]
+    async fn replay_respects_blocked_tools() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.security.blocked_tools = vec!["counter".into()];
+        let (agent, calls) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+
+        let err = agent.replay_tool_call(1, &counter_call(), "dashboard").await.unwrap_err();
+        assert!(matches!(err, SafeAgentError::PermissionDenied(_)));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn replay_waits_for_2fa() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.security.require_2fa = vec!["counter".into()];
+        let (agent, calls) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+
+        let err = agent.replay_tool_call(1, &counter_call(), "dashboard").await.unwrap_err();
+        assert!(matches!(err, SafeAgentError::PermissionDenied(_)));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let challenge = agent.twofa.pending().pop().unwrap();
+        agent.twofa.confirm(&challenge.id, None).unwrap();
+        agent.replay_tool_call(1, &counter_call(), "dashboard").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn process_start_takes_the_exec_2fa_gate() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            auto_approve_tools: vec!["exec".into()],
+            ..Default::default()
+        };
+        config.security.require_2fa = vec!["exec".into()];
+        let (agent, _) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+
+        let start = ToolCall {
+            tool: "process".into(),
+            params: serde_json::json!({ "action": "start", "command": "sleep 1" }),
+            reasoning: String::new(),
+        };
+        let outcome = agent.call_tool_external(&start, "mcp").await.unwrap();
+        assert!(matches!(outcome, ExternalOutcome::Challenge(_)));
+        assert_eq!(agent.twofa.pending().pop().unwrap().tool, "exec");
+    }
+
+    #[tokio::test]
+    async fn replay_refused_while_draining() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, calls) = test_agent(dir.path(), Duration::ZERO).await;
+        assert!(agent.drain(Duration::from_secs(1)).await);
+
+        assert!(agent.replay_tool_call(1, &counter_call(), "dashboard").await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn exhausted_turns_end_with_a_summary() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, llm) = exhausting_agent(dir.path(), Some("Counted twice; the total is still pending.")).await;
+
+        let reply = agent.handle_message_as("count everything", None).await.unwrap();
+        assert_eq!(llm.summary_requests.load(Ordering::SeqCst), 1);
+        assert!(reply.starts_with("Counted twice; the total is still pending."), "{reply}");
+        assert!(!reply.contains(EXHAUSTED_TURNS_REPLY));
+
+        let goals = GoalManager::new(agent.ctx.db.clone()).list_goals(None, 10, 0).await.unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].goal.title, "count everything");
+        assert!(reply.contains(&goals[0].goal.id));
+    }
+
+    #[tokio::test]
+    async fn canned_reply_only_when_summary_fails() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (agent, llm) = exhausting_agent(dir.path(), None).await;
+
+        let reply = agent.handle_message_as("count everything", None).await.unwrap();
+        assert_eq!(llm.summary_requests.load(Ordering::SeqCst), 1);
+        assert!(reply.starts_with(EXHAUSTED_TURNS_REPLY), "{reply}");
+    }
+
+    #[tokio::test]
+    async fn repeated_identical_call_is_refused_as_looping() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            auto_approve_tools: vec!["counter".into()],
+            max_repeated_tool_calls: 2,
+            ..Default::default()
+        };
+        config.memory.auto_extract = false;
+        let (mut agent, calls) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+
+        let same = "```tool_call\n{\"tool\": \"counter\", \"params\": {\"a\": 1, \"b\": 2}}\n```";
+        let reordered = "```tool_call\n{\"tool\": \"counter\", \"params\": {\"b\": 2, \"a\": 1}}\n```";
+        let different = "```tool_call\n{\"tool\": \"counter\", \"params\": {\"a\": 2}}\n```";
+        let script = vec![
+            same.to_string(),
+            reordered.to_string(),
+            format!("{same}\n{different}"),
+            "Switched approach.".to_string(),
+        ];
+        let llm = Arc::new(RecordingLlm::new(script));
+        agent.llm = crate::llm::LlmEngine::with_backend("scripted", llm.clone());
+
+        let reply = agent.handle_message_as("count", None).await.unwrap();
+        assert_eq!(reply, "Switched approach.");
+        // Two identical runs plus the different call; the third repeat is refused
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let last_context = llm.prompts.lock().unwrap().last().cloned().unwrap();
+        assert!(last_context.contains("[Tool result: counter (looping)]"));
+        assert!(last_context.contains("you are looping"));
+
+        let trace = agent.traces.recent(1).await.unwrap().remove(0);
+        let dispositions: Vec<_> = trace.tool_calls().map(|c| c.disposition).collect();
+        assert_eq!(
+            dispositions,
+            [
+                ToolDisposition::AutoApproved,
+                ToolDisposition::AutoApproved,
+                ToolDisposition::Looping,
+                ToolDisposition::AutoApproved,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn zero_repeat_limit_disables_loop_detection() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            auto_approve_tools: vec!["counter".into()],
+            max_repeated_tool_calls: 0,
+            ..Default::default()
+        };
+        config.memory.auto_extract = false;
+        let (mut agent, calls) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+
+        let same = "```tool_call\n{\"tool\": \"counter\", \"params\": {\"a\": 1}}\n```";
+        let script = vec![same.to_string(), same.to_string(), same.to_string(), "Done.".to_string()];
+        agent.llm = crate::llm::LlmEngine::with_backend("scripted", Arc::new(RecordingLlm::new(script)));
+
+        assert_eq!(agent.handle_message_as("count", None).await.unwrap(), "Done.");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn each_chat_gets_its_own_conversation_context() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.memory.auto_extract = false;
+        let (mut agent, _) = test_agent_with_config(dir.path(), Duration::ZERO, config).await;
+        let script = ["noted A", "noted B", "default reply", "A again", "default again"];
+        let llm = Arc::new(RecordingLlm::new(script.map(String::from).to_vec()));
+        agent.llm = crate::llm::LlmEngine::with_backend("scripted", llm.clone());
+
+        let chat_a = attachments::Origin { platform: "telegram", channel: "1" };
+        let chat_b = attachments::Origin { platform: "telegram", channel: "2" };
+        agent.handle_message_with_attachments("secret plan for A", &[], Some(chat_a), None).await.unwrap();
+        agent.handle_message_with_attachments("hello from B", &[], Some(chat_b), None).await.unwrap();
+        agent.handle_message_as("dashboard question", None).await.unwrap();
+        agent.handle_message_with_attachments("and again A", &[], Some(chat_a), None).await.unwrap();
+        agent.handle_message_as("dashboard follow-up", None).await.unwrap();
+
+        let prompts = llm.prompts.lock().unwrap().clone();
+        // B's first turn sees nothing from A
+        assert!(!prompts[1].contains("secret plan"));
+        // The default conversation is untouched by either chat
+        assert!(!prompts[2].contains("secret plan") && !prompts[2].contains("hello from B"));
+        // A's second turn sees A's history only
+        assert!(prompts[3].contains("User: secret plan for A\nAssistant: noted A"));
+        assert!(!prompts[3].contains("hello from B") && !prompts[3].contains("dashboard"));
+        // The default path still sees its own history
+        assert!(prompts[4].contains("User: dashboard question\nAssistant: default reply"));
+        assert!(!prompts[4].contains("and again A"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Named configuration presets.
+//!
+//! A preset bundles the runtime settings that usually change together when
+//! switching between, say, a permissive "development" setup and a strict
+//! "production" one: tick interval, tool rate limits, auto-approved tools,
+//! blocked tools and safe mode.  Presets are stored in the database and
+//! applied to the running agent with [`Agent::apply_preset`]; the config
+//! file is left untouched, so a later config reload takes precedence again.
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::approval::policy::AutoApprovePolicy;
+use crate::error::{Result, SafeAgentError};
+
+use super::Agent;
+
+/// The settings a preset carries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetSettings {
+    pub tick_interval_secs: u64,
+    /// Tool calls per minute (0 = unlimited).
+    pub rate_limit_per_minute: u32,
+    /// Tool calls per hour (0 = unlimited).
+    pub rate_limit_per_hour: u32,
+    pub auto_approve_tools: Vec<String>,
+    pub blocked_tools: Vec<String>,
+    pub safe_mode: bool,
+}
+
+impl PresetSettings {
+    fn validate(&self) -> Result<()> {
+        if self.tick_interval_secs == 0 {
+            return Err(SafeAgentError::Config("preset tick_interval_secs must be at least 1".into()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigPreset {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: PresetSettings,
+    pub updated_at: String,
+}
+
+/// SQLite-backed storage for presets.
+pub struct PresetStore {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl PresetStore {
+    pub fn new(db: Arc<Mutex<Connection>>) -> Self {
+        Self { db }
+    }
+
+    /// Create or replace the preset `name`.
+    pub async fn save(&self, name: &str, settings: &PresetSettings) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(SafeAgentError::Config("preset name must not be empty".into()));
+        }
+        settings.validate()?;
+        let json = serde_json::to_string(settings)?;
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT INTO config_presets (name, settings_json, updated_at) VALUES (?1, ?2, datetime('now'))
+             ON CONFLICT(name) DO UPDATE SET settings_json = excluded.settings_json, updated_at = excluded.updated_at",
+            rusqlite::params![name, json],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get(&self, name: &str) -> Result<Option<ConfigPreset>> {
+        let db = self.db.lock().await;
+        let row: Option<(String, String)> = db
+            .query_row(
+                "SELECT settings_json, updated_at FROM config_presets WHERE name = ?1",
+                [name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        row.map(|(json, updated_at)| {
+            Ok(ConfigPreset { name: name.to_string(), settings: serde_json::from_str(&json)?, updated_at })
+        })
+        .transpose()
+    }
+
+    /// All presets, by name.
+    pub async fn list(&self) -> Result<Vec<ConfigPreset>> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare("SELECT name, settings_json, updated_at FROM config_presets ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+        let mut presets = Vec::new();
+        for row in rows {
+            let (name, json, updated_at) = row?;
+            presets.push(ConfigPreset { name, settings: serde_json::from_str(&json)?, updated_at });
+        }
+        Ok(presets)
+    }
+
+    /// Returns whether a preset was deleted.
+    pub async fn delete(&self, name: &str) -> Result<bool> {
+        let db = self.db.lock().await;
+        Ok(db.execute("DELETE FROM config_presets WHERE name = ?1", [name])? > 0)
+    }
+}
+
+impl Agent {
+    /// Apply the stored preset `name` to the running agent.  Everything is
+    /// validated before any setting changes, so a bad preset leaves the
+    /// current settings intact.  Auto-approve rules from the config file
+    /// stay in force alongside the preset's tool list.
+    pub async fn apply_preset(&self, name: &str) -> Result<PresetSettings> {
+        let preset = self
+            .presets
+            .get(name)
+            .await?
+            .ok_or_else(|| SafeAgentError::NotFound(format!("config preset '{name}'")))?;
+        let s = preset.settings;
+        s.validate()?;
+        let auto_approve = AutoApprovePolicy::new(&s.auto_approve_tools, &self.config.auto_approve_rules)?;
+
+        self.tick_interval_secs.store(s.tick_interval_secs, Ordering::Relaxed);
+        self.rate_limiter.set_limits(s.rate_limit_per_minute, s.rate_limit_per_hour);
+        *self.auto_approve.write().unwrap() = auto_approve;
+        self.capability_checker.set_blocked_tools(&s.blocked_tools);
+        self.set_safe_mode(s.safe_mode);
+
+        info!(preset = %name, "config preset applied");
+        self.memory
+            .log_activity("config", &format!("preset '{name}' applied"), None, "ok")
+            .await
+            .ok();
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolCall;
+
+    fn strict() -> PresetSettings {
+        PresetSettings {
+            tick_interval_secs: 300,
+            rate_limit_per_minute: 5,
+            rate_limit_per_hour: 50,
+            auto_approve_tools: Vec::new(),
+            blocked_tools: vec!["exec".into()],
+            safe_mode: true,
+        }
+    }
+
+    fn permissive() -> PresetSettings {
+        PresetSettings {
+            tick_interval_secs: 10,
+            rate_limit_per_minute: 0,
+            rate_limit_per_hour: 0,
+            auto_approve_tools: vec!["exec".into()],
+            blocked_tools: Vec::new(),
+            safe_mode: false,
+        }
+    }
+
+    async fn agent(dir: &std::path::Path) -> Agent {
+        crate::agent::test_agent_with_tools(
+            dir,
+            crate::config::Config::default(),
+            crate::tools::ToolRegistry::new(),
+            crate::messaging::MessagingManager::new(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn presets_are_saved_listed_and_replaced() {
+        let store = PresetStore::new(crate::db::test_db());
+        store.save("production", &strict()).await.unwrap();
+        store.save("development", &strict()).await.unwrap();
+        store.save("development", &permissive()).await.unwrap();
+
+        let presets = store.list().await.unwrap();
+        let names: Vec<&str> = presets.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["development", "production"]);
+        assert_eq!(presets[0].settings, permissive());
+
+        assert!(store.save(" ", &strict()).await.is_err());
+        assert!(store.save("broken", &PresetSettings { tick_interval_secs: 0, ..strict() }).await.is_err());
+        assert!(store.delete("production").await.unwrap());
+        assert!(store.get("production").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn applying_a_preset_changes_running_settings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let agent = agent(dir.path()).await;
+        let exec = ToolCall { tool: "exec".into(), params: serde_json::json!({}), reasoning: String::new() };
+        agent.presets.save("production", &strict()).await.unwrap();
+        agent.presets.save("development", &permissive()).await.unwrap();
+
+        agent.apply_preset("production").await.unwrap();
+        assert_eq!(agent.tick_interval_secs(), 300);
+        assert_eq!(agent.rate_limiter.status().limit_per_minute, 5);
+        assert_eq!(agent.rate_limiter.status().limit_per_hour, 50);
+        assert!(agent.capability_checker.is_blocked("exec"));
+        assert!(!agent.auto_approve.read().unwrap().allows(&exec));
+        assert!(agent.is_safe_mode());
+
+        agent.apply_preset("development").await.unwrap();
+        assert_eq!(agent.tick_interval_secs(), 10);
+        assert!(!agent.capability_checker.is_blocked("exec"));
+        assert!(agent.auto_approve.read().unwrap().allows(&exec));
+        assert!(!agent.is_safe_mode());
+
+        assert!(matches!(agent.apply_preset("staging").await, Err(SafeAgentError::NotFound(_))));
+        assert_eq!(agent.tick_interval_secs(), 10);
+    }
+}
@@ -1,5 +1,308 @@
-// Reasoning module — retained for future use.
-//
-// With the Claude CLI backend, reasoning happens inside Claude itself.
-// This module is kept as a placeholder for any future structured
-// reasoning pipelines that might wrap the CLI output.
+//! Structured reasoning traces.
+//!
+//! One [`ReasoningTrace`] is recorded per user message handled by
+//! `Agent::handle_message_as`.  It captures every LLM turn — the context
+//! sent, the response text, and each proposed tool call with how it was
+//! handled (auto-approved, queued for approval, blocked, ...) and its
+//! result.  Traces are keyed by a `turn_id` that is also written to the
+//! matching `audit_log` rows, so the dashboard can go from an audit entry
+//! to the full "why did the agent do this" timeline.
+
+use std::sync::Arc;
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::tools::ToolCall;
+
+use super::truncate_preview;
+
+/// Longest context stored per LLM turn.
+const MAX_CONTEXT_CHARS: usize = 16_000;
+/// Longest tool result stored per call.
+const MAX_RESULT_CHARS: usize = 2_000;
+/// Traces kept before the oldest are pruned.
+const MAX_TRACES: i64 = 1_000;
+
+/// How a proposed tool call was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolDisposition {
+    /// Executed immediately under the auto-approval policy.
+    AutoApproved,
+    /// Proposed to the approval queue.
+    Queued,
+    /// Waiting on a second-channel 2FA confirmation.
+    TwoFactor,
+    /// Tool is blocked by security policy.
+    Blocked,
+    /// Rejected by the capability checker.
+    CapabilityDenied,
+    /// Dropped by the rate limiter.
+    RateLimited,
+    /// Not run: the same call already repeated too often this message.
+    Looping,
+}
+
+/// One tool call proposed by the LLM.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceToolCall {
+    pub tool: String,
+    pub params: serde_json::Value,
+    pub reasoning: String,
+    pub disposition: ToolDisposition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_id: Option<i64>,
+}
+
+impl TraceToolCall {
+    pub fn new(call: &ToolCall, disposition: ToolDisposition) -> Self {
+        Self {
+            tool: call.tool.clone(),
+            params: call.params.clone(),
+            reasoning: call.reasoning.clone(),
+            disposition,
+            approval_id: None,
+            result: None,
+            success: None,
+            audit_id: None,
+        }
+    }
+
+    pub fn with_result(mut self, result: &str, success: bool) -> Self {
+        self.result = Some(truncate_preview(result, MAX_RESULT_CHARS));
+        self.success = Some(success);
+        self
+    }
+
+    pub fn with_approval(mut self, id: impl Into<String>) -> Self {
+        self.approval_id = Some(id.into());
+        self
+    }
+
+    pub fn with_audit(mut self, audit_id: Option<i64>) -> Self {
+        self.audit_id = audit_id;
+        self
+    }
+}
+
+/// One LLM round-trip within a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceTurn {
+    pub turn: usize,
+    /// Context sent to the LLM (truncated).
+    pub context: String,
+    /// Response text with tool-call blocks stripped.
+    pub response: String,
+    pub tool_calls: Vec<TraceToolCall>,
+}
+
+/// Full trace of one handled user message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningTrace {
+    pub turn_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    pub message: String,
+    pub turns: Vec<TraceTurn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply: Option<String>,
+    pub created_at: String,
+}
+
+impl ReasoningTrace {
+    pub fn new(message: &str, user_id: Option<&str>) -> Self {
+        Self {
+            turn_id: Uuid::new_v4().to_string(),
+            user_id: user_id.map(|s| s.to_string()),
+            message: message.to_string(),
+            turns: Vec::new(),
+            reply: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Start recording a new LLM turn.
+    pub fn begin_turn(&mut self, turn: usize, context: &str, response: &str) {
+        self.turns.push(TraceTurn {
+            turn,
+            context: truncate_preview(context, MAX_CONTEXT_CHARS),
+            response: response.to_string(),
+            tool_calls: Vec::new(),
+        });
+    }
+
+    /// Record a tool call against the current turn.
+    pub fn record(&mut self, call: TraceToolCall) {
+        if let Some(turn) = self.turns.last_mut() {
+            turn.tool_calls.push(call);
+        }
+    }
+
+    /// All tool calls in the order they were proposed.
+    pub fn tool_calls(&self) -> impl Iterator<Item = &TraceToolCall> {
+        self.turns.iter().flat_map(|t| t.tool_calls.iter())
+    }
+
+    /// The trace with secret-looking values redacted, for showing to a
+    /// user.  The stored copy keeps them so the agent can replay calls.
+    pub fn redacted(mut self) -> Self {
+        use crate::security::{redact_secrets, redact_secrets_json};
+
+        self.message = redact_secrets(&self.message);
+        self.reply = self.reply.map(|r| redact_secrets(&r));
+        for turn in &mut self.turns {
+            turn.context = redact_secrets(&turn.context);
+            turn.response = redact_secrets(&turn.response);
+            for call in &mut turn.tool_calls {
+                redact_secrets_json(&mut call.params);
+                call.reasoning = redact_secrets(&call.reasoning);
+                call.result = call.result.take().map(|r| redact_secrets(&r));
+            }
+        }
+        self
+    }
+}
+
+/// SQLite-backed storage for reasoning traces.
+pub struct ReasoningTraceStore {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl ReasoningTraceStore {
+    pub fn new(db: Arc<Mutex<Connection>>) -> Self {
+        Self { db }
+    }
+
+    /// Persist a trace and tag its audit entries with the trace's `turn_id`.
+    pub async fn save(&self, trace: &ReasoningTrace) -> Result<()> {
+        let json = serde_json::to_string(trace)?;
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT OR REPLACE INTO reasoning_traces (turn_id, user_id, message, trace_json)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![trace.turn_id, trace.user_id, trace.message, json],
+        )?;
+        for audit_id in trace.tool_calls().filter_map(|c| c.audit_id) {
+            db.execute(
+                "UPDATE audit_log SET turn_id = ?1 WHERE id = ?2",
+                rusqlite::params![trace.turn_id, audit_id],
+            )?;
+        }
+        db.execute(
+            "DELETE FROM reasoning_traces WHERE turn_id NOT IN
+             (SELECT turn_id FROM reasoning_traces ORDER BY created_at DESC, rowid DESC LIMIT ?1)",
+            [MAX_TRACES],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get(&self, turn_id: &str) -> Result<Option<ReasoningTrace>> {
+        let db = self.db.lock().await;
+        let json: Option<String> = db
+            .query_row(
+                "SELECT trace_json FROM reasoning_traces WHERE turn_id = ?1",
+                [turn_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(json.map(|j| serde_json::from_str(&j)).transpose()?)
+    }
+
+    /// The trace that produced a given audit entry, if any.
+    pub async fn for_audit(&self, audit_id: i64) -> Result<Option<ReasoningTrace>> {
+        let turn_id: Option<String> = {
+            let db = self.db.lock().await;
+            db.query_row(
+                "SELECT turn_id FROM audit_log WHERE id = ?1",
+                [audit_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten()
+        };
+        match turn_id {
+            Some(id) => self.get(&id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Most recent traces, newest first.
+    pub async fn recent(&self, limit: usize) -> Result<Vec<ReasoningTrace>> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT trace_json FROM reasoning_traces ORDER BY created_at DESC, rowid DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([limit as i64], |row| row.get::<_, String>(0))?;
+        let mut traces = Vec::new();
+        for json in rows {
+            traces.push(serde_json::from_str(&json?)?);
+        }
+        Ok(traces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool: &str) -> ToolCall {
+        ToolCall {
+            tool: tool.into(),
+            params: serde_json::json!({}),
+            reasoning: format!("use {tool}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn save_links_audit_entries() {
+        let db = crate::db::test_db();
+        let audit = crate::security::audit::AuditLogger::new(db.clone());
+        let store = ReasoningTraceStore::new(db);
+
+        let audit_id = audit
+            .log_tool_call("exec", &serde_json::json!({}), "ok", true, "agent", "", "")
+            .await;
+
+        let mut trace = ReasoningTrace::new("list files", None);
+        trace.begin_turn(0, "User: list files", "");
+        trace.record(
+            TraceToolCall::new(&call("exec"), ToolDisposition::AutoApproved)
+                .with_result("a.txt", true)
+                .with_audit(audit_id),
+        );
+        store.save(&trace).await.unwrap();
+
+        let loaded = store.for_audit(audit_id.unwrap()).await.unwrap().unwrap();
+        assert_eq!(loaded.turn_id, trace.turn_id);
+        assert_eq!(loaded.turns[0].tool_calls[0].result.as_deref(), Some("a.txt"));
+        assert_eq!(store.recent(10).await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn redacted_hides_secrets_in_context_and_params() {
+        let mut trace = ReasoningTrace::new("deploy", None);
+        trace.begin_turn(0, "User: deploy\nAPI_TOKEN=abcd1234efgh", "");
+        let call = ToolCall {
+            tool: "http".into(),
+            params: serde_json::json!({ "url": "https://example.com", "headers": { "auth_token": "s3cr3t-value" } }),
+            reasoning: String::new(),
+        };
+        trace.record(TraceToolCall::new(&call, ToolDisposition::AutoApproved).with_result("ok", true));
+
+        let shown = trace.redacted();
+        assert!(!shown.turns[0].context.contains("abcd1234efgh"), "{}", shown.turns[0].context);
+        let params = &shown.turns[0].tool_calls[0].params;
+        assert_eq!(params["url"], "https://example.com");
+        assert_eq!(params["headers"]["auth_token"], "[REDACTED]");
+    }
+}
@@ -1,14 +1,21 @@
+use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
 use crate::error::Result;
 use crate::goals::{GoalManager, GoalStatus, TaskStatus};
 use crate::llm::GenerateContext;
+use crate::memory::consolidation::ConsolidationOutcome;
+use crate::messaging::Urgency;
 use crate::tools::ToolCall;
 
 use super::{truncate_preview, Agent};
 
+/// Maximum number of memories embedded per tick.
+const EMBED_INDEX_LIMIT: usize = 512;
+
 impl Agent {
-    /// Maintenance tick: expire stale actions, run cron jobs, process goals.
+    /// Maintenance tick: expire stale actions, run cron jobs, process goals,
+    /// check in with the user.
     pub async fn tick(&self) -> Result<()> {
         // Expire stale pending actions
         let expired = self.approval_queue.expire_stale().await?;
@@ -16,19 +23,59 @@ impl Agent {
             info!(count = expired, "expired stale actions");
         }
 
+        // Auto-reject 2FA challenges nobody answered
+        for challenge in self.twofa.expire_stale() {
+            self.audit.log_2fa(&challenge.tool, "expired", &challenge.source).await;
+            self.emit_event(serde_json::json!({
+                "type": "2fa_expired",
+                "tool": challenge.tool,
+                "challenge_id": challenge.id,
+            }));
+        }
+
         // Run due cron jobs
         if let Err(e) = self.run_due_cron_jobs().await {
             error!(err = %e, "cron job execution failed");
         }
 
+        // Reap orphaned sub-sessions
+        if self.config.sessions.enabled {
+            match crate::tools::sessions::reap_idle_sessions(
+                &self.ctx.db,
+                self.config.sessions.max_idle_hours,
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(count) => info!(count, "reaped idle sessions"),
+                Err(e) => error!(err = %e, "idle session reaping failed"),
+            }
+        }
+
+        // Drop inbound images past their retention
+        match self.prune_inbox() {
+            Ok(0) => {}
+            Ok(count) => info!(count, "pruned old inbox files"),
+            Err(e) => warn!(err = %e, "inbox cleanup failed"),
+        }
+
+        // Embed memories added since the last tick
+        if let Some(ref engine) = self.memory.embeddings {
+            match engine.index_pending(EMBED_INDEX_LIMIT).await {
+                Ok(0) => {}
+                Ok(count) => debug!(count, "indexed new memories"),
+                Err(e) => warn!(err = %e, "embedding indexing failed"),
+            }
+        }
+
         // Process background goals
         if let Err(e) = self.process_background_goals().await {
             error!(err = %e, "background goal processing failed");
         }
 
-        // Memory consolidation: periodically summarize old memories
-        if let Err(e) = self.consolidate_memories().await {
-            error!(err = %e, "memory consolidation failed");
+        // Reach out if the user has gone quiet or a goal is stuck
+        if let Err(e) = self.check_in(chrono::Utc::now()).await {
+            error!(err = %e, "check-in failed");
         }
 
         // Record tick
@@ -37,8 +84,47 @@ impl Agent {
         Ok(())
     }
 
-    /// Run memory consolidation: summarize old archival memories to keep context manageable.
-    async fn consolidate_memories(&self) -> crate::error::Result<()> {
+    /// Drive memory consolidation on its own schedule until shutdown.
+    ///
+    /// Runs every `memory.consolidation_interval_secs`, independent of the
+    /// tick interval.  An interval of 0 disables scheduled runs; manual
+    /// triggers via [`Agent::consolidate_memories`] still work.
+    pub async fn run_memory_consolidation(&self, mut shutdown: broadcast::Receiver<()>) {
+        let interval = self.consolidation.interval();
+        if interval.is_zero() {
+            info!("scheduled memory consolidation disabled");
+            return;
+        }
+
+        info!(interval_secs = interval.as_secs(), "memory consolidation scheduler starting");
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.consolidate_memories(false).await {
+                        error!(err = %e, "memory consolidation failed");
+                    }
+                }
+                _ = shutdown.recv() => {
+                    debug!("memory consolidation scheduler shutting down");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Consolidate memories now if due (or unconditionally when `force` is
+    /// set), unless a run is already in progress.
+    pub async fn consolidate_memories(&self, force: bool) -> Result<ConsolidationOutcome> {
+        self.consolidation
+            .run(force, || self.consolidate_pending())
+            .await
+    }
+
+    /// Summarize old archival memories to keep context manageable.
+    async fn consolidate_pending(&self) -> Result<usize> {
         let age_days = self.config.memory.consolidation_age_days;
         let batch = self.config.memory.consolidation_batch_size;
 
@@ -48,7 +134,7 @@ impl Agent {
         ).await?;
 
         if pending == 0 {
-            return Ok(());
+            return Ok(0);
         }
 
         debug!(pending, age_days, "old memories pending consolidation");
@@ -64,7 +150,7 @@ impl Agent {
             info!(consolidated, "archival memories consolidated");
         }
 
-        Ok(())
+        Ok(consolidated)
     }
 
     /// Process background goals: find the next actionable task and execute it.
@@ -72,7 +158,8 @@ impl Agent {
     /// Called every tick. Only processes one task per tick to avoid monopolizing
     /// the agent's time. The agent works through goals incrementally.
     async fn process_background_goals(&self) -> Result<()> {
-        let goal_mgr = GoalManager::new(self.ctx.db.clone());
+        let goal_mgr = GoalManager::new(self.ctx.db.clone())
+            .with_priority_aging(self.config.goals.priority_aging_per_hour);
 
         let active_count = goal_mgr.active_goal_count().await?;
         if active_count == 0 {
@@ -81,7 +168,7 @@ impl Agent {
 
         debug!(active_goals = active_count, "checking for actionable goal tasks");
 
-        // Find the highest-priority actionable task
+        // Find the actionable task with the highest (aged) priority
         let actionable = goal_mgr.next_actionable_task().await?;
         let (goal, task) = match actionable {
             Some(pair) => pair,
@@ -93,6 +180,8 @@ impl Agent {
             goal_id = %goal.id,
             task = %task.title,
             task_id = %task.id,
+            priority = goal.priority,
+            effective_priority = goal_mgr.effective_priority(&goal),
             "processing background goal task"
         );
 
@@ -166,12 +255,7 @@ impl Agent {
         let _ = goal_mgr.next_actionable_task().await;
 
         // Check if this goal just completed so we can run self-reflection
-        let updated_goal = goal_mgr.get_goal(&goal.id).await?;
-        if updated_goal.status == GoalStatus::Completed
-            || updated_goal.status == GoalStatus::Failed
-        {
-            self.run_self_reflection(&goal_mgr, &updated_goal).await;
-        }
+        self.reflect_if_finished(&goal_mgr, &goal.id).await?;
 
         // Send proactive notification about progress
         self.send_goal_progress_notification(&goal, &task, success, &result_text)
@@ -208,7 +292,7 @@ impl Agent {
             reasoning,
         };
 
-        match super::actions::execute_tool_call(&self.tools, &self.ctx, &tc).await {
+        match self.execute_tool(&tc).await {
             Ok(output) => (output.success, output.output),
             Err(e) => (false, format!("Tool execution error: {e}")),
         }
@@ -239,31 +323,27 @@ impl Agent {
             message: &prompt,
             tools: Some(&self.tools),
             prompt_skills: &self.always_on_skills,
+            personality: None,
+            persona: None,
+            attachments: &[],
         };
 
-        match self.llm.generate(&gen_ctx).await {
+        match self.llm.generate_response(&gen_ctx).await {
             Ok(reply) => {
                 // Parse for tool calls and execute them
-                let parsed = super::tool_parse::parse_llm_response(&reply);
+                let parsed = super::tool_parse::parse_response(reply);
 
                 if parsed.tool_calls.is_empty() {
                     return (true, parsed.text);
                 }
 
                 // Execute auto-approved tool calls
-                let auto_approve: std::collections::HashSet<&str> = self
-                    .config
-                    .auto_approve_tools
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect();
-
                 let mut results = Vec::new();
                 let mut all_success = true;
 
                 for call in &parsed.tool_calls {
-                    if auto_approve.contains(call.tool.as_str()) {
-                        match super::actions::execute_tool_call(&self.tools, &self.ctx, call).await
+                    if self.auto_approves(call) {
+                        match self.execute_tool(call).await
                         {
                             Ok(output) => {
                                 if !output.success {
@@ -297,6 +377,17 @@ impl Agent {
         }
     }
 
+    /// Run self-reflection on a goal that has completed or failed.  Goals
+    /// that already have a reflection are left alone.
+    pub(super) async fn reflect_if_finished(&self, goal_mgr: &GoalManager, goal_id: &str) -> Result<()> {
+        let goal = goal_mgr.get_goal(goal_id).await?;
+        let finished = goal.status == GoalStatus::Completed || goal.status == GoalStatus::Failed;
+        if finished && goal.reflection.is_none() {
+            self.run_self_reflection(goal_mgr, &goal).await;
+        }
+        Ok(())
+    }
+
     /// After a goal completes or fails, ask the LLM to reflect on the result.
     async fn run_self_reflection(
         &self,
@@ -323,7 +414,8 @@ impl Agent {
 
         let prompt = format!(
             "Reflect on the completed goal below. Was the goal achieved? \
-             What went well? What could improve next time? Be concise (2-3 sentences).\n\n\
+             What worked, and what should be done differently on similar goals \
+             next time? Be concise (2-3 sentences).\n\n\
              Goal: {} (status: {})\nDescription: {}\n\nTasks:\n{}",
             goal.title,
             goal.status.as_str(),
@@ -335,6 +427,9 @@ impl Agent {
             message: &prompt,
             tools: None,
             prompt_skills: &self.always_on_skills,
+            personality: None,
+            persona: None,
+            attachments: &[],
         };
 
         match self.llm.generate(&gen_ctx).await {
@@ -365,7 +460,7 @@ impl Agent {
                     goal.status.as_str(),
                     reflection,
                 );
-                self.ctx.messaging.send_all(&msg).await;
+                self.ctx.messaging.send_all(&msg, Urgency::Normal).await;
             }
             Err(e) => {
                 warn!(err = %e, "failed to generate self-reflection");
@@ -392,7 +487,7 @@ impl Agent {
             goal.title, task.title, status, preview,
         );
 
-        self.ctx.messaging.send_all(&msg).await;
+        self.ctx.messaging.send_all(&msg, Urgency::Normal).await;
     }
 
     /// Drain and execute all approved tool calls from the approval queue.
@@ -403,6 +498,11 @@ impl Agent {
     /// user gets a complete natural-language response via their messaging
     /// platform.
     pub async fn execute_approved(&self) -> Result<()> {
+        // Approved actions stay queued while the kill switch is engaged.
+        if self.is_halted() {
+            return Ok(());
+        }
+
         let mut executed_any = false;
         let mut result_summaries: Vec<String> = Vec::new();
 
@@ -422,7 +522,7 @@ impl Agent {
             // Send typing indicator while executing
             self.ctx.messaging.typing_all().await;
 
-            match super::actions::execute_tool_call(&self.tools, &self.ctx, &call).await {
+            match self.execute_approved_tool(&call).await {
                 Ok(output) => {
                     self.approval_queue
                         .mark_executed(&action.id, true)
@@ -520,6 +620,9 @@ impl Agent {
                 message: &context,
                 tools: Some(&self.tools),
                 prompt_skills: &self.always_on_skills,
+                personality: None,
+                persona: None,
+                attachments: &[],
             };
 
             match self.llm.generate(&gen_ctx).await {
@@ -530,7 +633,7 @@ impl Agent {
                         .await?;
 
                     // Send the follow-up reply to the user via messaging
-                    self.ctx.messaging.send_all(&reply).await;
+                    self.ctx.messaging.send_all(&reply, Urgency::Urgent).await;
 
                     self.emit_event(serde_json::json!({
                         "type": "turn_complete",
@@ -549,7 +652,7 @@ impl Agent {
                     error!(err = %e, "failed to generate follow-up after approved tools");
                     // Still notify with raw results
                     let fallback = result_summaries.join("\n\n");
-                    self.ctx.messaging.send_all(&fallback).await;
+                    self.ctx.messaging.send_all(&fallback, Urgency::Urgent).await;
 
                     self.emit_event(serde_json::json!({
                         "type": "error",
@@ -1,5 +1,6 @@
 use tracing::warn;
 
+use crate::llm::LlmResponse;
 use crate::tools::ToolCall;
 
 /// The result of parsing an LLM response that may contain tool_call blocks.
@@ -12,6 +13,21 @@ pub struct ParsedResponse {
     pub tool_calls: Vec<ToolCall>,
 }
 
+/// Turn a backend response into text and tool calls.
+///
+/// Structured tool calls from native function-calling backends are used
+/// as-is; otherwise the text is scanned for `tool_call` blocks with
+/// [`parse_llm_response`].
+pub fn parse_response(response: LlmResponse) -> ParsedResponse {
+    if response.tool_calls.is_empty() {
+        return parse_llm_response(&response.text);
+    }
+    ParsedResponse {
+        text: response.text.trim().to_string(),
+        tool_calls: response.tool_calls,
+    }
+}
+
 /// Parse `tool_call` fenced blocks from LLM output.
 ///
 /// The LLM is instructed to wrap tool calls like this:
@@ -256,4 +272,33 @@ Some text after."#;
         let parsed = parse_llm_response(response);
         assert!(parsed.tool_calls.is_empty());
     }
+
+    #[test]
+    fn test_structured_calls_bypass_text_parsing() {
+        // Prose that happens to contain a fenced block must not produce an
+        // extra call when the backend already returned structured ones.
+        let response = LlmResponse {
+            text: "Running it now.\n\n```tool_call\n{\"tool\": \"delete_file\", \"params\": {}}\n```".into(),
+            tool_calls: vec![ToolCall {
+                tool: "exec".into(),
+                params: serde_json::json!({"command": "ls"}),
+                reasoning: String::new(),
+            }],
+        };
+        let parsed = parse_response(response);
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].tool, "exec");
+        assert!(parsed.text.starts_with("Running it now."));
+    }
+
+    #[test]
+    fn test_text_response_falls_back_to_parsing() {
+        let response = LlmResponse::from_text(
+            "Checking.\n\n```tool_call\n{\"tool\": \"exec\", \"params\": {\"command\": \"date\"}}\n```",
+        );
+        let parsed = parse_response(response);
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].params["command"], "date");
+        assert_eq!(parsed.text, "Checking.");
+    }
 }
@@ -1,3 +1,4 @@
+pub mod policy;
 pub mod types;
 
 use std::sync::Arc;
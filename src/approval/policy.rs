@@ -0,0 +1,295 @@
+//! Auto-approval policy: decides whether a proposed tool call runs
+//! immediately or goes to the approval queue.
+//!
+//! Tools listed in `auto_approve_tools` are always auto-approved.  Beyond
+//! that, `auto_approve_rules` can approve a tool only when its parameters
+//! satisfy a set of predicates — e.g. `write_file` under `reports/`, or
+//! `exec` when the command runs `git`.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use regex::Regex;
+
+use crate::config::AutoApproveRule;
+use crate::error::{Result, SafeAgentError};
+use crate::tools::ToolCall;
+
+/// Characters that let a shell command run something other than its first
+/// word (chaining, pipes, redirection, substitution, subshells).
+const SHELL_META: &[char] = &[';', '&', '|', '`', '$', '>', '<', '(', ')', '\n', '\r'];
+
+pub struct AutoApprovePolicy {
+    /// Tools approved regardless of parameters.
+    tools: HashSet<String>,
+    /// Conditional rules, keyed by tool name.  Any matching rule approves.
+    rules: HashMap<String, Vec<Rule>>,
+}
+
+/// A compiled [`AutoApproveRule`]; all set predicates must hold.
+struct Rule {
+    param: Option<String>,
+    path_prefix: Option<PathBuf>,
+    commands: Vec<String>,
+    pattern: Option<Regex>,
+}
+
+impl AutoApprovePolicy {
+    pub fn new(tools: &[String], rules: &[AutoApproveRule]) -> Result<Self> {
+        let mut compiled: HashMap<String, Vec<Rule>> = HashMap::new();
+        for rule in rules {
+            if rule.path_prefix.is_none() && rule.commands.is_empty() && rule.pattern.is_none() {
+                return Err(SafeAgentError::Config(format!(
+                    "auto_approve_rules entry for '{}' has no predicates; list the tool in auto_approve_tools instead",
+                    rule.tool
+                )));
+            }
+            let pattern = rule
+                .pattern
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .map_err(|e| {
+                    SafeAgentError::Config(format!(
+                        "invalid auto_approve_rules pattern for '{}': {e}",
+                        rule.tool
+                    ))
+                })?;
+            let path_prefix = rule.path_prefix.as_deref().map(normalize_relative).transpose()?;
+            if path_prefix.as_ref().is_some_and(|p| p.as_os_str().is_empty()) {
+                return Err(SafeAgentError::Config(format!(
+                    "auto_approve_rules path_prefix for '{}' must name a directory",
+                    rule.tool
+                )));
+            }
+            compiled.entry(rule.tool.clone()).or_default().push(Rule {
+                param: rule.param.clone(),
+                path_prefix,
+                commands: rule.commands.clone(),
+                pattern,
+            });
+        }
+
+        Ok(Self {
+            tools: tools.iter().cloned().collect(),
+            rules: compiled,
+        })
+    }
+
+    /// Whether `call` may run without human approval.  `process start` is
+    /// judged by the `exec` entries.
+    pub fn allows(&self, call: &ToolCall) -> bool {
+        let tool = crate::security::gate_name(&call.tool, &call.params);
+        if self.tools.contains(tool) {
+            return true;
+        }
+        self.rules
+            .get(tool)
+            .is_some_and(|rules| rules.iter().any(|r| r.matches(&call.params)))
+    }
+}
+
+impl Rule {
+    fn matches(&self, params: &serde_json::Value) -> bool {
+        if let Some(prefix) = &self.path_prefix {
+            let path = self.str_param(params, "path");
+            let inside = path
+                .and_then(|p| normalize_relative(p).ok())
+                .is_some_and(|p| p.starts_with(prefix));
+            if !inside {
+                return false;
+            }
+        }
+
+        if !self.commands.is_empty() {
+            let allowed = self
+                .str_param(params, "command")
+                .and_then(command_binary)
+                .is_some_and(|bin| self.commands.iter().any(|c| c == bin));
+            if !allowed {
+                return false;
+            }
+        }
+
+        if let Some(pattern) = &self.pattern {
+            let value = match &self.param {
+                Some(name) => match params.get(name) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                    None => return false,
+                },
+                None => params.to_string(),
+            };
+            if !pattern.is_match(&value) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn str_param<'a>(&self, params: &'a serde_json::Value, default: &str) -> Option<&'a str> {
+        params
+            .get(self.param.as_deref().unwrap_or(default))
+            .and_then(|v| v.as_str())
+    }
+}
+
+/// Normalize a sandbox-relative path, rejecting anything that could escape
+/// (absolute paths and `..` components).
+fn normalize_relative(path: &str) -> Result<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(c) => out.push(c),
+            Component::CurDir => {}
+            _ => {
+                return Err(SafeAgentError::SandboxViolation(format!(
+                    "path '{path}' is not a plain relative path"
+                )));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// The binary a simple shell command runs, or `None` if the command uses
+/// shell syntax that could run anything else.
+fn command_binary(command: &str) -> Option<&str> {
+    if command.contains(SHELL_META) {
+        return None;
+    }
+    let first = command.split_whitespace().next()?;
+    Path::new(first).file_name()?.to_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool: &str, params: serde_json::Value) -> ToolCall {
+        ToolCall {
+            tool: tool.into(),
+            params,
+            reasoning: String::new(),
+        }
+    }
+
+    fn policy(rules: Vec<AutoApproveRule>) -> AutoApprovePolicy {
+        AutoApprovePolicy::new(&["message".to_string()], &rules).unwrap()
+    }
+
+    #[test]
+    fn flat_allowlist_still_applies() {
+        let p = policy(vec![]);
+        assert!(p.allows(&call("message", serde_json::json!({}))));
+        assert!(!p.allows(&call("write_file", serde_json::json!({"path": "a.txt"}))));
+    }
+
+    #[test]
+    fn process_start_needs_exec_approval() {
+        let start = call("process", serde_json::json!({"action": "start", "command": "ls"}));
+        let list = call("process", serde_json::json!({"action": "list"}));
+
+        let process_only = AutoApprovePolicy::new(&["process".to_string()], &[]).unwrap();
+        assert!(!process_only.allows(&start));
+        assert!(process_only.allows(&list));
+
+        let exec = AutoApprovePolicy::new(&["exec".to_string()], &[]).unwrap();
+        assert!(exec.allows(&start));
+        assert!(!exec.allows(&list));
+    }
+
+    #[test]
+    fn write_file_auto_approved_only_under_reports() {
+        let p = policy(vec![AutoApproveRule {
+            tool: "write_file".into(),
+            path_prefix: Some("reports/".into()),
+            ..Default::default()
+        }]);
+        let write = |path: &str| call("write_file", serde_json::json!({"path": path, "content": "x"}));
+
+        assert!(p.allows(&write("reports/weekly.md")));
+        assert!(p.allows(&write("./reports/2024/q1.md")));
+        assert!(!p.allows(&write("notes/todo.md")));
+        assert!(!p.allows(&write("reports_old/x.md")));
+        assert!(!p.allows(&write("reports/../secrets.txt")));
+        assert!(!p.allows(&write("/reports/x.md")));
+        assert!(!p.allows(&call("write_file", serde_json::json!({"content": "x"}))));
+    }
+
+    #[test]
+    fn exec_auto_approved_only_for_git() {
+        let p = policy(vec![AutoApproveRule {
+            tool: "exec".into(),
+            commands: vec!["git".into()],
+            ..Default::default()
+        }]);
+        let exec = |cmd: &str| call("exec", serde_json::json!({"command": cmd}));
+
+        assert!(p.allows(&exec("git status")));
+        assert!(p.allows(&exec("/usr/bin/git log --oneline")));
+        assert!(!p.allows(&exec("ls -la")));
+        assert!(!p.allows(&exec("gitk")));
+        assert!(!p.allows(&exec("git status; rm -rf ~")));
+        assert!(!p.allows(&exec("git log | sh")));
+        assert!(!p.allows(&exec("git $(whoami)")));
+        assert!(!p.allows(&exec("FOO=1 git status")));
+    }
+
+    #[test]
+    fn pattern_matches_named_param() {
+        let p = policy(vec![AutoApproveRule {
+            tool: "web_fetch".into(),
+            param: Some("url".into()),
+            pattern: Some(r"^https://docs\.rs/".into()),
+            ..Default::default()
+        }]);
+        assert!(p.allows(&call("web_fetch", serde_json::json!({"url": "https://docs.rs/regex"}))));
+        assert!(!p.allows(&call("web_fetch", serde_json::json!({"url": "https://evil.example/docs.rs/"}))));
+        assert!(!p.allows(&call("web_fetch", serde_json::json!({}))));
+    }
+
+    #[test]
+    fn all_predicates_in_a_rule_must_hold() {
+        let p = policy(vec![AutoApproveRule {
+            tool: "exec".into(),
+            commands: vec!["git".into()],
+            pattern: Some(r"^git (status|log|diff)\b".into()),
+            param: Some("command".into()),
+            ..Default::default()
+        }]);
+        assert!(p.allows(&call("exec", serde_json::json!({"command": "git diff HEAD"}))));
+        assert!(!p.allows(&call("exec", serde_json::json!({"command": "git push --force"}))));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let bad_regex = AutoApproveRule {
+            tool: "exec".into(),
+            pattern: Some("(".into()),
+            ..Default::default()
+        };
+        assert!(AutoApprovePolicy::new(&[], &[bad_regex]).is_err());
+
+        let empty = AutoApproveRule {
+            tool: "exec".into(),
+            ..Default::default()
+        };
+        assert!(AutoApprovePolicy::new(&[], &[empty]).is_err());
+
+        let escaping = AutoApproveRule {
+            tool: "write_file".into(),
+            path_prefix: Some("../".into()),
+            ..Default::default()
+        };
+        assert!(AutoApprovePolicy::new(&[], &[escaping]).is_err());
+
+        let whole_sandbox = AutoApproveRule {
+            tool: "write_file".into(),
+            path_prefix: Some("./".into()),
+            ..Default::default()
+        };
+        assert!(AutoApprovePolicy::new(&[], &[whole_sandbox]).is_err());
+    }
+}
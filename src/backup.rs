@@ -0,0 +1,364 @@
+//! `--backup` / `--restore`: a single `.tar.gz` of the data directory and
+//! config.
+//!
+//! Archive layout:
+//!
+//! ```text
+//! config.toml        config with secret values blanked
+//! data/safeclaw.db   consistent database snapshot (WAL folded in)
+//! data/...           everything else under the data directory
+//! ```
+//!
+//! Secrets stay out of the archive: the PII encryption key, the federation
+//! signing key, OAuth token files, skill credentials, ACME certificates and
+//! WhatsApp session credentials are skipped, and string config values whose key names a
+//! token, password, secret or API key are blanked.  Without the original
+//! `encryption.key` the encrypted user fields of a restored database can't
+//! be read, so keep that file somewhere safe on its own.
+//!
+//! Embeddings live in the database, so the snapshot covers semantic search
+//! as well.
+
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::Connection;
+use tracing::{info, warn};
+
+use crate::error::{Result, SafeAgentError};
+
+/// Database file name inside the data directory.
+const DB_FILE: &str = "safeclaw.db";
+
+/// Archive entry holding the scrubbed config.
+const CONFIG_ENTRY: &str = "config.toml";
+
+/// Archive directory holding the data directory.
+const DATA_PREFIX: &str = "data";
+
+/// File name prefix of the database snapshot written while archiving.
+const SNAPSHOT_PREFIX: &str = ".backup-snapshot-";
+
+/// Paths under the data directory that are never archived: the live
+/// database files (the snapshot replaces them) and secrets.
+const EXCLUDED: &[&str] = &[
+    "safeclaw.db",
+    "safeclaw.db-wal",
+    "safeclaw.db-shm",
+    "safeclaw.db-journal",
+    "encryption.key",
+    "federation.key",
+    "oauth",
+    "skills/credentials.json",
+    "acme-cache",
+    "whatsapp/auth",
+];
+
+/// Config key suffixes whose string values are secrets.
+const SECRET_KEY_SUFFIXES: &[&str] = &["api_key", "token", "secret", "password"];
+
+/// Write a backup of `data_dir` and the config at `config_path` to `out`.
+/// Returns the number of archive entries written.
+pub fn create(data_dir: &Path, config_path: &Path, out: &Path) -> Result<usize> {
+    let file = File::create(out)?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    tar.follow_symlinks(false);
+    let mut entries = 0;
+
+    if config_path.exists() {
+        let contents = std::fs::read_to_string(config_path)?;
+        let scrubbed = scrub_config(&contents)?;
+        append_bytes(&mut tar, CONFIG_ENTRY, scrubbed.as_bytes())?;
+        entries += 1;
+    }
+
+    let db_path = data_dir.join(DB_FILE);
+    if db_path.exists() {
+        // Next to the live database rather than in a shared temp dir, and
+        // readable by the owner only.
+        let snapshot = data_dir.join(format!("{SNAPSHOT_PREFIX}{}.db", uuid::Uuid::new_v4()));
+        let result = create_private(&snapshot)
+            .and_then(|()| snapshot_db(&db_path, &snapshot))
+            .and_then(|()| {
+                tar.append_path_with_name(&snapshot, Path::new(DATA_PREFIX).join(DB_FILE))?;
+                Ok(())
+            });
+        let _ = std::fs::remove_file(&snapshot);
+        result?;
+        entries += 1;
+    }
+
+    if data_dir.is_dir() {
+        entries += append_dir(&mut tar, data_dir, Path::new(""))?;
+    }
+
+    tar.into_inner()?.finish()?;
+    info!(path = %out.display(), entries, "backup written");
+    Ok(entries)
+}
+
+/// Unpack a backup made by [`create`] into `data_dir`, which must not exist
+/// yet or be empty.  The config is written to `config_path` only if there
+/// is no config there already.  Returns the number of entries restored.
+pub fn restore(archive: &Path, data_dir: &Path, config_path: &Path) -> Result<usize> {
+    let occupied = std::fs::read_dir(data_dir).map(|mut d| d.next().is_some()).unwrap_or(false);
+    if occupied {
+        return Err(SafeAgentError::Config(format!(
+            "refusing to restore into non-empty data directory {}",
+            data_dir.display()
+        )));
+    }
+    std::fs::create_dir_all(data_dir)?;
+
+    let mut tar = tar::Archive::new(GzDecoder::new(File::open(archive)?));
+    let mut entries = 0;
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let kind = entry.header().entry_type();
+        if !kind.is_file() && !kind.is_dir() {
+            warn!(path = %path.display(), "skipping non-regular archive entry");
+            continue;
+        }
+
+        if path == Path::new(CONFIG_ENTRY) {
+            if config_path.exists() {
+                warn!(path = %config_path.display(), "config already exists, not overwriting it");
+                continue;
+            }
+            if let Some(parent) = config_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry.unpack(config_path)?;
+            entries += 1;
+            continue;
+        }
+
+        let Some(rel) = data_relative(&path) else {
+            warn!(path = %path.display(), "skipping unexpected archive entry");
+            continue;
+        };
+        let dest = data_dir.join(rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&dest)?;
+        entries += 1;
+    }
+
+    info!(data_dir = %data_dir.display(), entries, "backup restored");
+    Ok(entries)
+}
+
+/// Copy a consistent snapshot of the database at `db_path` to `dest`.
+/// The WAL is checkpointed first so the live file is current, and
+/// `VACUUM INTO` reads from a single transaction even if the agent is
+/// writing concurrently.
+/// Create an empty file only the owner can read.  `VACUUM INTO` accepts
+/// an existing empty file and keeps its permissions.
+fn create_private(path: &Path) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?;
+    Ok(())
+}
+
+fn snapshot_db(db_path: &Path, dest: &Path) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    conn.execute("VACUUM INTO ?1", [dest.to_string_lossy()])?;
+    Ok(())
+}
+
+/// Append every regular file under `root/rel`, skipping [`EXCLUDED`] paths
+/// and symlinks.  Returns the number of files appended.
+fn append_dir<W: std::io::Write>(tar: &mut tar::Builder<W>, root: &Path, rel: &Path) -> Result<usize> {
+    let mut children: Vec<_> = std::fs::read_dir(root.join(rel))?.collect::<std::io::Result<_>>()?;
+    children.sort_by_key(|e| e.file_name());
+
+    let mut count = 0;
+    for child in children {
+        let child_rel = rel.join(child.file_name());
+        if is_excluded(&child_rel) {
+            continue;
+        }
+        let kind = child.file_type()?;
+        if kind.is_dir() {
+            count += append_dir(tar, root, &child_rel)?;
+        } else if kind.is_file() {
+            tar.append_path_with_name(child.path(), Path::new(DATA_PREFIX).join(&child_rel))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn is_excluded(rel: &Path) -> bool {
+    // A snapshot left behind by an interrupted backup is never archived.
+    let stale_snapshot = rel.components().count() == 1
+        && rel.to_string_lossy().starts_with(SNAPSHOT_PREFIX);
+    stale_snapshot || EXCLUDED.iter().any(|e| rel == Path::new(e))
+}
+
+fn append_bytes<W: std::io::Write>(tar: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    tar.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// The path under `data/` of an archive entry, if it is one and contains
+/// only plain components.
+fn data_relative(path: &Path) -> Option<PathBuf> {
+    let rel = path.strip_prefix(DATA_PREFIX).ok()?;
+    let plain = rel.components().all(|c| matches!(c, Component::Normal(_)));
+    (plain && rel.components().next().is_some()).then(|| rel.to_path_buf())
+}
+
+/// Blank string values whose key looks like a secret.
+fn scrub_config(contents: &str) -> Result<String> {
+    let mut value: toml::Value = toml::from_str(contents)
+        .map_err(|e| SafeAgentError::Config(format!("config: {e}")))?;
+    scrub_value(&mut value);
+    toml::to_string(&value).map_err(|e| SafeAgentError::Config(format!("config: {e}")))
+}
+
+fn scrub_value(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, v) in table.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if v.is_str() && SECRET_KEY_SUFFIXES.iter().any(|s| key.ends_with(s)) {
+                    *v = toml::Value::String(String::new());
+                } else {
+                    scrub_value(v);
+                }
+            }
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(scrub_value),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_names(archive: &Path) -> Vec<String> {
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(archive).unwrap()));
+        tar.entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// A data directory with a database, a skill and some secrets, plus a
+    /// config holding an API key.
+    fn fixture(dir: &Path) -> (PathBuf, PathBuf) {
+        let data = dir.join("data");
+        std::fs::create_dir_all(data.join("skills/notes")).unwrap();
+        std::fs::create_dir_all(data.join("oauth/google")).unwrap();
+        std::fs::write(data.join("skills/notes/skill.toml"), "name = \"notes\"\n").unwrap();
+        std::fs::write(data.join("CLAUDE.md"), "rules\n").unwrap();
+        std::fs::write(data.join("encryption.key"), "00".repeat(32)).unwrap();
+        std::fs::write(data.join("oauth/google/token.json"), "{}").unwrap();
+        std::fs::write(data.join("skills/credentials.json"), "{\"notes\":{\"API_KEY\":\"k\"}}").unwrap();
+
+        let conn = crate::db::open(&data.join(DB_FILE)).unwrap();
+        conn.execute("INSERT INTO metadata (key, value) VALUES ('onboarded', 'yes')", []).unwrap();
+        drop(conn);
+
+        let config = dir.join("config.toml");
+        std::fs::write(
+            &config,
+            "agent_name = \"Ada\"\n\n[llm]\nopenrouter_api_key = \"sk-live\"\nmax_tokens = 4096\n\n[tunnel.ngrok]\nauthtoken = \"ngrok-secret\"\n",
+        )
+        .unwrap();
+        (data, config)
+    }
+
+    #[test]
+    fn backup_contains_db_snapshot_config_and_skills_but_no_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let (data, config) = fixture(dir.path());
+        let archive = dir.path().join("backup.tar.gz");
+
+        let written = create(&data, &config, &archive).unwrap();
+        let names = entry_names(&archive);
+        assert_eq!(names.len(), written);
+        for expected in ["config.toml", "data/safeclaw.db", "data/CLAUDE.md", "data/skills/notes/skill.toml"] {
+            assert!(names.iter().any(|n| n == expected), "missing {expected} in {names:?}");
+        }
+        assert!(!names.iter().any(|n| n.contains("encryption.key") || n.contains("oauth")));
+        assert!(!names.iter().any(|n| n.contains("credentials.json")), "{names:?}");
+        assert!(!names.iter().any(|n| n.ends_with("-wal") || n.ends_with("-shm")));
+        // The snapshot is private and cleaned up afterwards.
+        let leftovers: Vec<_> = std::fs::read_dir(&data)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|n| n.starts_with(SNAPSHOT_PREFIX))
+            .collect();
+        assert!(leftovers.is_empty(), "{leftovers:?}");
+        #[cfg(unix)]
+        {
+            let mut tar = tar::Archive::new(GzDecoder::new(File::open(&archive).unwrap()));
+            let db_entry = tar
+                .entries()
+                .unwrap()
+                .map(|e| e.unwrap())
+                .find(|e| e.path().unwrap() == Path::new("data/safeclaw.db"))
+                .unwrap();
+            assert_eq!(db_entry.header().mode().unwrap() & 0o777, 0o600);
+        }
+
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(&archive).unwrap()));
+        let mut entry = tar.entries().unwrap().next().unwrap().unwrap();
+        let mut scrubbed = String::new();
+        std::io::Read::read_to_string(&mut entry, &mut scrubbed).unwrap();
+        assert!(scrubbed.contains("Ada"));
+        assert!(scrubbed.contains("4096"));
+        assert!(!scrubbed.contains("sk-live"));
+        assert!(!scrubbed.contains("ngrok-secret"));
+    }
+
+    #[test]
+    fn restore_reproduces_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let (data, config) = fixture(dir.path());
+        let archive = dir.path().join("backup.tar.gz");
+        create(&data, &config, &archive).unwrap();
+
+        let fresh = dir.path().join("fresh");
+        let fresh_config = dir.path().join("fresh-config/config.toml");
+        restore(&archive, &fresh, &fresh_config).unwrap();
+
+        let conn = crate::db::open(&fresh.join(DB_FILE)).unwrap();
+        let value: String = conn
+            .query_row("SELECT value FROM metadata WHERE key = 'onboarded'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(value, "yes");
+        assert!(fresh.join("skills/notes/skill.toml").exists());
+        assert!(!fresh.join("encryption.key").exists());
+        assert!(std::fs::read_to_string(&fresh_config).unwrap().contains("Ada"));
+
+        // A second restore into the now-populated directory is refused.
+        assert!(restore(&archive, &fresh, &fresh_config).is_err());
+    }
+
+    #[test]
+    fn restore_ignores_entries_outside_the_data_dir() {
+        assert_eq!(data_relative(Path::new("data/skills/a.toml")), Some(PathBuf::from("skills/a.toml")));
+        assert_eq!(data_relative(Path::new("data/../etc/passwd")), None);
+        assert_eq!(data_relative(Path::new("other/file")), None);
+        assert_eq!(data_relative(Path::new("data")), None);
+    }
+}
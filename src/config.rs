@@ -3,8 +3,9 @@ use std::path::{Path, PathBuf};
 use tracing::info;
 
 use crate::error::{Result, SafeAgentError};
+use crate::security::secrets::SecretProvider;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Config {
     #[serde(default = "default_agent_name")]
     pub agent_name: String,
@@ -28,20 +29,49 @@ pub struct Config {
     #[serde(default = "default_tick_interval_secs")]
     pub tick_interval_secs: u64,
 
+    /// On shutdown, how long to wait for the current tick, message or tool
+    /// call to finish before abandoning it.
+    #[serde(default = "default_shutdown_grace_secs")]
+    pub shutdown_grace_secs: u64,
+
     #[serde(default = "default_conversation_window")]
     pub conversation_window: usize,
 
+    /// Estimated token budget for the conversation context sent to the
+    /// LLM, including the core personality.  Older conversation turns are
+    /// dropped to fit.  0 disables the limit.
+    #[serde(default = "default_context_max_tokens")]
+    pub context_max_tokens: usize,
+
     #[serde(default = "default_approval_expiry_secs")]
     pub approval_expiry_secs: u64,
 
     #[serde(default = "default_auto_approve_tools")]
     pub auto_approve_tools: Vec<String>,
 
+    /// Conditional auto-approval: a call to `tool` is auto-approved when
+    /// its parameters satisfy every predicate in a matching rule.  Tools in
+    /// `auto_approve_tools` are approved unconditionally.
+    #[serde(default)]
+    pub auto_approve_rules: Vec<AutoApproveRule>,
+
     /// Maximum number of tool-call round-trips per user message before the
     /// agent returns whatever it has.  Prevents infinite tool-call loops.
+    /// On running out, one last LLM call summarises the progress so far.
     #[serde(default = "default_max_tool_turns")]
     pub max_tool_turns: usize,
 
+    /// How many times one message may make the same tool call (same tool,
+    /// same parameters) before further repeats are refused and the model
+    /// is told it is looping.  0 turns the check off.
+    #[serde(default = "default_max_repeated_tool_calls")]
+    pub max_repeated_tool_calls: usize,
+
+    /// When a message runs out of tool turns, create a goal so the
+    /// unfinished work continues in the background.
+    #[serde(default)]
+    pub continue_unfinished_as_goal: bool,
+
     #[serde(default)]
     pub llm: LlmConfig,
 
@@ -72,6 +102,12 @@ pub struct Config {
     #[serde(default)]
     pub signal: SignalConfig,
 
+    #[serde(default)]
+    pub status_notifications: StatusNotificationsConfig,
+
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+
     #[serde(default)]
     pub sessions: SessionsConfig,
 
@@ -84,19 +120,42 @@ pub struct Config {
     #[serde(default)]
     pub security: SecurityConfig,
 
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+
     #[serde(default)]
     pub federation: FederationConfig,
 
+    #[serde(default)]
+    pub mcp: McpConfig,
+
+    #[serde(default)]
+    pub rhai: RhaiConfig,
+
+    #[serde(default)]
+    pub skill_registry: SkillRegistryConfig,
+
     #[serde(default)]
     pub plugins: PluginsConfig,
 
     #[serde(default)]
     pub memory: MemoryConfig,
+
+    #[serde(default)]
+    pub goals: GoalsConfig,
+
+    #[serde(default)]
+    pub check_in: CheckInConfig,
+
+    /// File this config was loaded from (or would be, if it didn't exist).
+    /// Used by [`Config::reload`].
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
 }
 
 // -- Federation --------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct FederationConfig {
     /// Enable multi-node federation.
     #[serde(default)]
@@ -111,6 +170,26 @@ pub struct FederationConfig {
     #[serde(default)]
     pub advertise_address: String,
 
+    /// Seconds between peer health checks (`GET <peer>/healthz`).
+    #[serde(default = "default_federation_health_interval")]
+    pub health_check_interval_secs: u64,
+
+    /// Consecutive failed health checks before a peer is marked offline.
+    #[serde(default = "default_federation_failure_threshold")]
+    pub health_failure_threshold: u32,
+
+    /// Base64 Ed25519 public keys of nodes allowed to register themselves
+    /// by heartbeat.  Any other node must be added by an admin first.
+    #[serde(default)]
+    pub trusted_peer_keys: Vec<String>,
+}
+
+fn default_federation_health_interval() -> u64 {
+    30
+}
+
+fn default_federation_failure_threshold() -> u32 {
+    3
 }
 
 impl Default for FederationConfig {
@@ -119,13 +198,112 @@ impl Default for FederationConfig {
             enabled: false,
             node_name: String::new(),
             advertise_address: String::new(),
+            health_check_interval_secs: default_federation_health_interval(),
+            health_failure_threshold: default_federation_failure_threshold(),
+            trusted_peer_keys: Vec::new(),
+        }
+    }
+}
+
+// -- MCP server --------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct McpConfig {
+    /// Serve the tool registry to MCP clients (JSON-RPC over HTTP).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Address the MCP endpoint listens on.  Binding anything but loopback
+    /// requires the `MCP_TOKEN` env var, which clients send as a bearer
+    /// token.
+    #[serde(default = "default_mcp_bind")]
+    pub bind: String,
+}
+
+fn default_mcp_bind() -> String {
+    "127.0.0.1:3032".to_string()
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_mcp_bind(),
+        }
+    }
+}
+
+// -- Rhai limits -------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RhaiConfig {
+    /// Operations a skill extension handler may run before it is aborted.
+    #[serde(default = "default_rhai_max_operations")]
+    pub max_operations: u64,
+
+    /// Maximum function call nesting depth.
+    #[serde(default = "default_rhai_max_call_depth")]
+    pub max_call_depth: usize,
+
+    /// Longest string a script may build, in bytes.
+    #[serde(default = "default_rhai_max_string_size")]
+    pub max_string_size: usize,
+
+    /// Most elements an array or object map may hold.
+    #[serde(default = "default_rhai_max_collection_size")]
+    pub max_collection_size: usize,
+
+    /// Wall-clock limit for one extension handler call, in seconds
+    /// (0 = none).
+    #[serde(default = "default_rhai_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_rhai_max_operations() -> u64 {
+    1_000_000
+}
+fn default_rhai_max_call_depth() -> usize {
+    64
+}
+fn default_rhai_max_string_size() -> usize {
+    1024 * 1024
+}
+fn default_rhai_max_collection_size() -> usize {
+    100_000
+}
+fn default_rhai_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for RhaiConfig {
+    fn default() -> Self {
+        Self {
+            max_operations: default_rhai_max_operations(),
+            max_call_depth: default_rhai_max_call_depth(),
+            max_string_size: default_rhai_max_string_size(),
+            max_collection_size: default_rhai_max_collection_size(),
+            timeout_secs: default_rhai_timeout_secs(),
         }
     }
 }
 
+// -- Skill registry ----------------------------------------------------------
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct SkillRegistryConfig {
+    /// URL of the registry index (JSON).  Empty disables registry imports.
+    #[serde(default)]
+    pub index_url: String,
+
+    /// Publishers whose signed skills may be installed: publisher name ->
+    /// base64 Ed25519 public key.
+    #[serde(default)]
+    pub trusted_publishers: std::collections::HashMap<String, String>,
+}
+
 // -- Security ----------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct SecurityConfig {
     /// Tools that are completely blocked (never executable).
     #[serde(default)]
@@ -135,6 +313,16 @@ pub struct SecurityConfig {
     #[serde(default = "default_2fa_tools")]
     pub require_2fa: Vec<String>,
 
+    /// Seconds a 2FA challenge stays open before it is auto-rejected.
+    #[serde(default = "default_twofa_ttl_secs")]
+    pub twofa_ttl_secs: u64,
+
+    /// Require a TOTP code (from the confirming user's authenticator app)
+    /// to confirm a 2FA challenge.  When false, users with TOTP enabled
+    /// still have to enter a code and others confirm with a click.
+    #[serde(default)]
+    pub twofa_require_totp: bool,
+
     /// Maximum tool calls per minute (0 = unlimited).
     #[serde(default = "default_rate_limit_per_minute")]
     pub rate_limit_per_minute: u32,
@@ -147,6 +335,16 @@ pub struct SecurityConfig {
     #[serde(default)]
     pub daily_cost_limit_usd: f64,
 
+    /// Maximum estimated LLM cost per user per day in USD (0.0 = unlimited).
+    /// Applies in addition to `daily_cost_limit_usd`.
+    #[serde(default)]
+    pub user_daily_cost_limit_usd: f64,
+
+    /// Price per 1000 tokens in USD used to estimate the cost of each LLM
+    /// turn for the limits above (0.0 = don't count cost).
+    #[serde(default)]
+    pub cost_per_1k_tokens_usd: f64,
+
     /// Enable PII/sensitive data detection in LLM responses.
     #[serde(default = "default_true")]
     pub pii_detection: bool,
@@ -156,11 +354,84 @@ pub struct SecurityConfig {
     /// e.g. { "exec" = ["echo", "ls", "cat"], "file" = ["read"] }
     #[serde(default)]
     pub tool_capabilities: std::collections::HashMap<String, Vec<String>>,
+
+    /// Path restrictions per tool, applied to `path`/`paths` parameters on
+    /// top of the sandbox.  Keys are tool names, or `*` for every tool.
+    /// e.g. { "read_file" = { allow = ["reports/"], deny = ["secrets/"] } }
+    #[serde(default)]
+    pub path_policy: std::collections::HashMap<String, PathPolicy>,
+
+    /// Binaries the `exec` tool may launch (empty = any not denied).
+    /// Every command in the script is checked, including ones behind
+    /// pipes, wrappers like `env`/`timeout` and nested `sh -c`.
+    #[serde(default)]
+    pub exec_allow: Vec<String>,
+
+    /// Binaries `exec` may not launch, or binary-plus-argument prefixes
+    /// such as `"bash -c"`.  Deny wins over allow.
+    #[serde(default)]
+    pub exec_deny: Vec<String>,
+
+    /// Install a seccomp-bpf syscall filter at startup (Linux only).
+    /// Skipped, like Landlock, when `NO_JAIL=1`.
+    #[serde(default = "default_true")]
+    pub seccomp: bool,
+
+    /// Syscalls the seccomp filter denies with `EPERM`.  Inherited by every
+    /// skill and tool subprocess.
+    #[serde(default = "default_seccomp_deny")]
+    pub seccomp_deny: Vec<String>,
+
+    /// Most bytes the sandbox (the data directory) may hold; writes through
+    /// the file tools that would exceed it are rejected.  The database
+    /// doesn't count.  0 = unlimited.
+    #[serde(default)]
+    pub sandbox_quota_bytes: u64,
+}
+
+/// Allowed and denied paths for one tool, as globs relative to the sandbox
+/// root.  `*` and `?` match within a path segment and `**` across
+/// segments; a pattern that matches a directory covers everything in it.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PathPolicy {
+    /// If non-empty, paths must match one of these.
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Paths matching any of these are refused, even if allowed.
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+// -- Auto-approval rules -------------------------------------------------
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct AutoApproveRule {
+    /// Tool the rule applies to.
+    pub tool: String,
+
+    /// Parameter the predicates inspect.  Defaults to `path` for
+    /// `path_prefix`, `command` for `commands`; `pattern` matches the whole
+    /// params JSON when unset.
+    #[serde(default)]
+    pub param: Option<String>,
+
+    /// Relative path that must contain the target (e.g. "reports/").
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+
+    /// Allowed binaries for command-style params (e.g. ["git"]).  Commands
+    /// using shell chaining, pipes, redirection or substitution never match.
+    #[serde(default)]
+    pub commands: Vec<String>,
+
+    /// Regex the parameter value must match.
+    #[serde(default)]
+    pub pattern: Option<String>,
 }
 
 // -- LLM -----------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct LlmConfig {
     /// Backend to use: "claude" (default), "cline", "codex", "gemini",
     /// "aider", "openrouter", "ollama", or "local".
@@ -199,6 +470,12 @@ pub struct LlmConfig {
     #[serde(default = "default_timeout_secs")]
     pub timeout_secs: u64,
 
+    /// Resource-limit profile per CLI backend ("claude", "codex", "gemini",
+    /// "aider", "cline").  Values: "permissive" (default), "default",
+    /// "skill", or "none".
+    #[serde(default)]
+    pub process_limits: std::collections::HashMap<String, String>,
+
     // -- Cline CLI settings (backend = "cline") --
 
     /// Path to the `cline` binary (default: "cline").
@@ -284,6 +561,17 @@ pub struct LlmConfig {
     #[serde(default)]
     pub openrouter_app_name: String,
 
+    /// Whether the OpenRouter model accepts images (e.g. "openai/gpt-4o").
+    /// When false, inbound images are noted in the prompt but not sent.
+    #[serde(default)]
+    pub openrouter_vision: bool,
+
+    /// Whether the OpenRouter model supports OpenAI-style function calling.
+    /// When true, tools are declared natively and come back as structured
+    /// `tool_calls`; when false, the model emits `tool_call` blocks in text.
+    #[serde(default)]
+    pub openrouter_native_tools: bool,
+
     // -- Ollama settings (backend = "ollama") --
 
     /// Ollama API base URL (default: "http://localhost:11434").
@@ -296,6 +584,10 @@ pub struct LlmConfig {
     #[serde(default)]
     pub ollama_model: String,
 
+    /// Whether the Ollama model accepts images (e.g. "llava", "llama3.2-vision").
+    #[serde(default)]
+    pub ollama_vision: bool,
+
     // -- Local model settings (backend = "local") --
 
     /// Path to a GGUF model file for local inference.
@@ -319,8 +611,18 @@ pub struct LlmConfig {
 
 // -- Tools ---------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct ToolsConfig {
+    /// Largest tool output (in bytes) passed back to the LLM.  Longer
+    /// output is truncated and the full text saved to the sandbox.
+    /// 0 disables the cap.
+    #[serde(default = "default_tool_max_output_bytes")]
+    pub max_output_bytes: usize,
+
+    /// Per-tool overrides for `max_output_bytes`, keyed by tool name.
+    #[serde(default)]
+    pub max_output_bytes_per_tool: std::collections::HashMap<String, usize>,
+
     #[serde(default)]
     pub exec: ExecToolConfig,
 
@@ -335,9 +637,18 @@ pub struct ToolsConfig {
 
     #[serde(default)]
     pub cron: CronToolConfig,
+
+    #[serde(default)]
+    pub git: GitToolConfig,
+
+    #[serde(default)]
+    pub http: HttpToolConfig,
+
+    #[serde(default)]
+    pub image: ImageToolConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct ExecToolConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -346,39 +657,140 @@ pub struct ExecToolConfig {
     pub timeout_secs: u64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct WebToolConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
 
     #[serde(default = "default_web_max_results")]
     pub max_results: usize,
+
+    /// Backend for `web_search`: `"duckduckgo"` or `"searxng"`.
+    #[serde(default = "default_web_search_provider")]
+    pub search_provider: String,
+
+    /// Base URL of a SearxNG instance, used when `search_provider` is
+    /// `"searxng"`.
+    #[serde(default)]
+    pub searxng_url: String,
+
+    /// Largest response body `web_fetch` will read; larger downloads are
+    /// aborted.
+    #[serde(default = "default_web_max_fetch_bytes")]
+    pub max_fetch_bytes: usize,
+
+    /// Content types `web_fetch` accepts (compared without parameters such
+    /// as `charset`).
+    #[serde(default = "default_web_fetch_content_types")]
+    pub fetch_content_types: Vec<String>,
+
+    /// Reduce HTML to plain readable text instead of converting it to
+    /// markdown.
+    #[serde(default)]
+    pub html_to_text: bool,
+
+    /// Check robots.txt before `web_fetch` requests.
+    #[serde(default = "default_true")]
+    pub respect_robots: bool,
+
+    /// Hosts (and their subdomains) exempt from robots.txt checks, e.g.
+    /// internal services.
+    #[serde(default)]
+    pub robots_exempt_hosts: Vec<String>,
+
+    /// Minimum delay between `web_fetch` requests to the same host.  A
+    /// longer robots.txt `Crawl-delay` takes precedence.
+    #[serde(default = "default_web_per_host_delay_ms")]
+    pub per_host_delay_ms: u64,
+
+    /// How long `web_search` results are reused for an identical query,
+    /// in seconds.  0 disables the cache.
+    #[serde(default = "default_web_search_cache_ttl_secs")]
+    pub search_cache_ttl_secs: u64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct BrowserToolConfig {
     #[serde(default)]
     pub enabled: bool,
 
     #[serde(default = "default_true")]
     pub headless: bool,
+
+    /// Per-operation timeout in seconds.  A timed-out browser is killed
+    /// and relaunched on the next call.
+    #[serde(default = "default_browser_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Maximum characters of page content returned by snapshot, scrape,
+    /// evaluate and screenshot_describe.
+    #[serde(default = "default_browser_max_content_chars")]
+    pub max_content_chars: usize,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct MessageToolConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct CronToolConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GitToolConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    #[serde(default = "default_exec_timeout")]
+    pub timeout_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HttpToolConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Hosts (and their subdomains) `http_request` may reach.  Empty means
+    /// the tool can't reach anything.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+
+    /// Allowed hosts for which the agent may also set credential headers
+    /// (Authorization, Cookie, X-Api-Key, ...).
+    #[serde(default)]
+    pub auth_hosts: Vec<String>,
+
+    #[serde(default = "default_exec_timeout")]
+    pub timeout_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ImageToolConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// OpenAI-compatible image generation endpoint.
+    #[serde(default = "default_image_api_url")]
+    pub api_url: String,
+
+    #[serde(default = "default_image_model")]
+    pub model: String,
+
+    /// Environment variable holding the API key.
+    #[serde(default = "default_image_api_key_env")]
+    pub api_key_env: String,
+
+    #[serde(default = "default_image_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
 // -- Dashboard -----------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DashboardConfig {
     /// Whether password-based login is enabled (default: true).
     /// Set to false to require SSO-only login.
@@ -395,6 +807,15 @@ pub struct DashboardConfig {
     /// Empty means any authenticated SSO user is allowed.
     #[serde(default)]
     pub sso_allowed_emails: Vec<String>,
+
+    /// OpenID Connect login.  Also limited by `sso_allowed_emails`.
+    #[serde(default)]
+    pub oidc: OidcConfig,
+
+    /// How many recent events are kept for page-reload hydration and for
+    /// replay to SSE clients reconnecting with `Last-Event-ID`.
+    #[serde(default = "default_event_buffer_size")]
+    pub event_buffer_size: usize,
 }
 
 impl Default for DashboardConfig {
@@ -403,24 +824,80 @@ impl Default for DashboardConfig {
             password_enabled: true,
             sso_providers: Vec::new(),
             sso_allowed_emails: Vec::new(),
+            oidc: OidcConfig::default(),
+            event_buffer_size: default_event_buffer_size(),
+        }
+    }
+}
+
+/// OpenID Connect dashboard login.  The client secret is read from the
+/// secrets provider as `OIDC_CLIENT_SECRET`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OidcConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// "google", "github" or "generic" (any issuer with discovery).
+    #[serde(default = "default_oidc_provider")]
+    pub provider: String,
+
+    /// Issuer URL.  Required for "generic"; ignored for "github".
+    #[serde(default)]
+    pub issuer: String,
+
+    #[serde(default)]
+    pub client_id: String,
+
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: String,
+
+    /// Create a local user on first login when no user has the verified
+    /// email.  When false, only existing users can sign in.
+    #[serde(default = "default_true")]
+    pub auto_provision: bool,
+
+    /// Role given to auto-provisioned users: "admin", "user" or "viewer".
+    #[serde(default = "default_oidc_role")]
+    pub default_role: String,
+
+    /// Button label on the login page (defaults to the provider name).
+    #[serde(default)]
+    pub display_name: String,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_oidc_provider(),
+            issuer: String::new(),
+            client_id: String::new(),
+            scopes: default_oidc_scopes(),
+            auto_provision: true,
+            default_role: default_oidc_role(),
+            display_name: String::new(),
         }
     }
 }
 
 // -- Telegram ------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct TelegramConfig {
     #[serde(default)]
     pub enabled: bool,
 
     #[serde(default)]
     pub allowed_chat_ids: Vec<i64>,
+
+    /// Send notifications to every allowed chat, not just the first.
+    #[serde(default)]
+    pub notify_all_chats: bool,
 }
 
 // -- WhatsApp ------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct WhatsAppConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -435,6 +912,16 @@ pub struct WhatsAppConfig {
 
     #[serde(default)]
     pub allowed_numbers: Vec<String>,
+
+    /// Approved message template for proactive notifications sent outside
+    /// the 24-hour session window.  The notification text fills its first
+    /// body parameter.  Empty = always send free-form text.
+    #[serde(default)]
+    pub notification_template: String,
+
+    /// Language code of `notification_template`.
+    #[serde(default = "default_whatsapp_template_language")]
+    pub template_language: String,
 }
 
 fn default_whatsapp_bridge_port() -> u16 {
@@ -445,6 +932,10 @@ fn default_whatsapp_webhook_port() -> u16 {
     3030
 }
 
+fn default_whatsapp_template_language() -> String {
+    "en_US".to_string()
+}
+
 impl Default for WhatsAppConfig {
     fn default() -> Self {
         Self {
@@ -452,13 +943,15 @@ impl Default for WhatsAppConfig {
             bridge_port: default_whatsapp_bridge_port(),
             webhook_port: default_whatsapp_webhook_port(),
             allowed_numbers: Vec::new(),
+            notification_template: String::new(),
+            template_language: default_whatsapp_template_language(),
         }
     }
 }
 
 // -- iMessage ------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct IMessageConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -488,7 +981,7 @@ impl Default for IMessageConfig {
 
 // -- Twilio --------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct TwilioConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -500,6 +993,12 @@ pub struct TwilioConfig {
     /// Allowed destination phone numbers.
     #[serde(default)]
     pub allowed_numbers: Vec<String>,
+
+    /// Public URL Twilio posts incoming SMS to (the `/webhooks/twilio`
+    /// route as seen from outside), used to check request signatures.
+    /// Empty = rebuild it from the request's Host and X-Forwarded-Proto.
+    #[serde(default)]
+    pub webhook_url: String,
 }
 
 impl Default for TwilioConfig {
@@ -508,13 +1007,14 @@ impl Default for TwilioConfig {
             enabled: false,
             from_number: String::new(),
             allowed_numbers: Vec::new(),
+            webhook_url: String::new(),
         }
     }
 }
 
 // -- Android SMS ---------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct AndroidSmsConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -544,7 +1044,7 @@ impl Default for AndroidSmsConfig {
 
 // -- Discord -------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DiscordConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -568,7 +1068,7 @@ impl Default for DiscordConfig {
 
 // -- Signal --------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct SignalConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -595,17 +1095,265 @@ impl Default for SignalConfig {
     }
 }
 
+// -- Status notifications ------------------------------------------------
+
+/// Notices sent to every messaging backend's primary channel when the
+/// process starts and stops, so users know when the agent is unreachable.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StatusNotificationsConfig {
+    /// Send `online_message` once the messaging backends are up.
+    #[serde(default)]
+    pub on_startup: bool,
+
+    /// Send `offline_message` after Ctrl+C, before shutting down.
+    #[serde(default)]
+    pub on_shutdown: bool,
+
+    /// `{agent_name}` is replaced with the configured agent name.
+    #[serde(default = "default_online_message")]
+    pub online_message: String,
+
+    #[serde(default = "default_offline_message")]
+    pub offline_message: String,
+}
+
+fn default_online_message() -> String {
+    "{agent_name} is back online.".to_string()
+}
+fn default_offline_message() -> String {
+    "{agent_name} is going offline.".to_string()
+}
+
+impl Default for StatusNotificationsConfig {
+    fn default() -> Self {
+        Self {
+            on_startup: false,
+            on_shutdown: false,
+            online_message: default_online_message(),
+            offline_message: default_offline_message(),
+        }
+    }
+}
+
+// -- Quiet hours ---------------------------------------------------------
+
+/// Hours during which non-urgent proactive messages (goal updates,
+/// reminders, check-ins) are held back and delivered when the window ends.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct QuietHoursConfig {
+    /// Local "HH:MM" in `timezone`.  Empty disables quiet hours.
+    #[serde(default)]
+    pub start: String,
+
+    #[serde(default)]
+    pub end: String,
+}
+
+impl QuietHoursConfig {
+    pub fn window(&self) -> std::result::Result<Option<QuietHours>, String> {
+        QuietHours::parse(&self.start, &self.end)
+    }
+}
+
 // -- Sessions ------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct SessionsConfig {
     #[serde(default)]
     pub enabled: bool,
+
+    /// Active sessions with no activity for this many hours are reaped at
+    /// startup and on each tick.  0 keeps sessions forever.
+    #[serde(default = "default_session_max_idle_hours")]
+    pub max_idle_hours: u64,
+}
+
+// -- Secrets -------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SecretsConfig {
+    /// Where tokens like `TELEGRAM_BOT_TOKEN` and `JWT_SECRET` come from:
+    /// "env" (default), "file", or "vault".
+    #[serde(default = "default_secrets_provider")]
+    pub provider: String,
+
+    /// `KEY=VALUE` file read when `provider = "file"`.
+    #[serde(default)]
+    pub file: String,
+
+    /// Vault server address when `provider = "vault"`, e.g.
+    /// "https://vault.internal:8200".  The token is read from `VAULT_TOKEN`.
+    #[serde(default)]
+    pub vault_addr: String,
+
+    /// KV v2 secrets engine mount.
+    #[serde(default = "default_vault_mount")]
+    pub vault_mount: String,
+
+    /// Secret path under the mount; its fields are the secret keys.
+    #[serde(default = "default_vault_path")]
+    pub vault_path: String,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            provider: default_secrets_provider(),
+            file: String::new(),
+            vault_addr: String::new(),
+            vault_mount: default_vault_mount(),
+            vault_path: default_vault_path(),
+        }
+    }
+}
+
+// -- Goals ---------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GoalsConfig {
+    /// Prompt used to decompose an objective into a goal and tasks.
+    /// `{objective}` and `{max_tasks}` are substituted.  Empty uses the
+    /// built-in prompt.
+    #[serde(default)]
+    pub decompose_prompt: String,
+
+    /// Most tasks kept from a decomposition.
+    #[serde(default = "default_goal_max_decomposed_tasks")]
+    pub max_decomposed_tasks: usize,
+
+    /// Scheduling priority a goal gains per hour without task progress, so
+    /// low-priority goals aren't starved.  0 disables aging.
+    #[serde(default = "default_goal_priority_aging_per_hour")]
+    pub priority_aging_per_hour: f64,
+}
+
+impl Default for GoalsConfig {
+    fn default() -> Self {
+        Self {
+            decompose_prompt: String::new(),
+            max_decomposed_tasks: default_goal_max_decomposed_tasks(),
+            priority_aging_per_hour: default_goal_priority_aging_per_hour(),
+        }
+    }
+}
+
+// -- Check-ins -----------------------------------------------------------
+
+/// Proactive check-ins: the agent messages the user when they've been quiet
+/// for a while or a goal has stopped making progress.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CheckInConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Hours without a user message before the agent checks in.  Sent once
+    /// per idle stretch.
+    #[serde(default = "default_check_in_idle_hours")]
+    pub idle_hours: u64,
+
+    #[serde(default = "default_check_in_message")]
+    pub message: String,
+
+    /// Hours an active goal may go without task progress before the agent
+    /// asks about it.  0 disables stalled-goal check-ins.
+    #[serde(default = "default_check_in_stalled_goal_hours")]
+    pub stalled_goal_hours: u64,
+
+    /// Minimum hours between any two check-ins.
+    #[serde(default = "default_check_in_min_interval_hours")]
+    pub min_interval_hours: u64,
+
+    /// No check-ins between these local times ("HH:MM", in `timezone`).
+    /// The window may wrap past midnight.  Empty disables quiet hours.
+    #[serde(default)]
+    pub quiet_hours_start: String,
+
+    #[serde(default)]
+    pub quiet_hours_end: String,
+}
+
+fn default_check_in_idle_hours() -> u64 {
+    24
+}
+fn default_check_in_message() -> String {
+    "Anything you'd like me to work on?".to_string()
+}
+fn default_check_in_stalled_goal_hours() -> u64 {
+    48
+}
+fn default_check_in_min_interval_hours() -> u64 {
+    12
+}
+
+impl CheckInConfig {
+    /// The parsed quiet-hours window, if one is configured.
+    pub fn quiet_hours(&self) -> std::result::Result<Option<QuietHours>, String> {
+        QuietHours::parse(&self.quiet_hours_start, &self.quiet_hours_end)
+    }
+}
+
+/// A daily window of local time, `start` inclusive to `end` exclusive,
+/// that wraps past midnight when `end` is earlier than `start`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuietHours {
+    pub start: chrono::NaiveTime,
+    pub end: chrono::NaiveTime,
+}
+
+impl QuietHours {
+    /// Parse "HH:MM" bounds.  Both empty means no window.
+    pub fn parse(start: &str, end: &str) -> std::result::Result<Option<Self>, String> {
+        let (start, end) = (start.trim(), end.trim());
+        if start.is_empty() && end.is_empty() {
+            return Ok(None);
+        }
+        let time = |s: &str| {
+            chrono::NaiveTime::parse_from_str(s, "%H:%M")
+                .map_err(|_| format!("\"{s}\" is not a time of the form HH:MM"))
+        };
+        Ok(Some(Self { start: time(start)?, end: time(end)? }))
+    }
+
+    pub fn contains(&self, t: chrono::NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= t && t < self.end
+        } else {
+            t >= self.start || t < self.end
+        }
+    }
+
+    /// The first time after `now` that the window ends.
+    pub fn end_after<Tz: chrono::TimeZone>(&self, now: &chrono::DateTime<Tz>) -> chrono::DateTime<chrono::Utc> {
+        let local = now.naive_local();
+        let mut end = local.date().and_time(self.end);
+        if end <= local {
+            end += chrono::Duration::days(1);
+        }
+        match now.timezone().from_local_datetime(&end).earliest() {
+            Some(t) => t.with_timezone(&chrono::Utc),
+            // The end time falls in a DST gap; the hour after it exists
+            None => (now.clone() + (end - local) + chrono::Duration::hours(1)).with_timezone(&chrono::Utc),
+        }
+    }
+}
+
+impl Default for CheckInConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_hours: default_check_in_idle_hours(),
+            message: default_check_in_message(),
+            stalled_goal_hours: default_check_in_stalled_goal_hours(),
+            min_interval_hours: default_check_in_min_interval_hours(),
+            quiet_hours_start: String::new(),
+            quiet_hours_end: String::new(),
+        }
+    }
 }
 
 // -- Plugins -------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct PluginsConfig {
     /// Global plugin directory (default: ~/.config/safeclaw/plugins).
     /// Empty string means use the default path.
@@ -634,7 +1382,7 @@ impl Default for PluginsConfig {
 
 // -- TLS / ACME ----------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct TlsConfig {
     /// Enable ACME (Let's Encrypt) automatic certificate management.
     /// When enabled, `acme_domains` and `acme_email` are required.
@@ -671,7 +1419,7 @@ pub struct TlsConfig {
 
 // -- Tunnel (multi-provider) ------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct TunnelConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -689,7 +1437,7 @@ pub struct TunnelConfig {
     pub tailscale: TailscaleConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct NgrokConfig {
     #[serde(default)]
     pub authtoken: String,
@@ -707,7 +1455,7 @@ pub struct NgrokConfig {
     pub poll_interval_secs: u64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct CloudflareConfig {
     #[serde(default)]
     pub tunnel_id: String,
@@ -725,7 +1473,7 @@ pub struct CloudflareConfig {
     pub url: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct TailscaleConfig {
     #[serde(default = "default_tailscale_mode")]
     pub mode: String,
@@ -742,7 +1490,7 @@ pub struct TailscaleConfig {
 
 // -- Memory --------------------------------------------------------------
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct MemoryConfig {
     /// Ollama model used for generating embeddings (default: "nomic-embed-text").
     /// Set to empty string to disable embeddings and fall back to FTS5.
@@ -754,6 +1502,47 @@ pub struct MemoryConfig {
     #[serde(default)]
     pub embedding_host: String,
 
+    /// Embedding backend: "ollama" (default) or "local" for an in-process
+    /// ONNX model (requires the `local` feature).
+    #[serde(default = "default_embedding_backend")]
+    pub embedding_backend: String,
+
+    /// Path to the local embedding model — a directory containing
+    /// `model.onnx` and `tokenizer.json`, or the `.onnx` file itself.
+    #[serde(default)]
+    pub embedding_model_path: String,
+
+    /// Maximum number of texts sent in a single embedding request.  Larger
+    /// inputs are split into sub-batches of this size.
+    #[serde(default = "default_embedding_batch_size")]
+    pub embedding_batch_size: usize,
+
+    /// Maximum number of embedding sub-batch requests in flight at once.
+    #[serde(default = "default_embedding_concurrency")]
+    pub embedding_concurrency: usize,
+
+    /// Dimension of the vectors the embedding model produces.  When set,
+    /// a model producing anything else is rejected before it writes, and
+    /// startup fails if the stored embeddings have another dimension.
+    /// 0 learns the dimension from the first stored embedding.
+    #[serde(default)]
+    pub embedding_dim: usize,
+
+    /// Rerank semantic search results with a `/rerank` API endpoint
+    /// (Cohere, Jina, vLLM, ...).  Empty disables reranking.  The API key,
+    /// if needed, comes from the `RERANK_API_KEY` env var.
+    #[serde(default)]
+    pub rerank_url: String,
+
+    /// Model name sent to the rerank endpoint.
+    #[serde(default)]
+    pub rerank_model: String,
+
+    /// When reranking, vector search fetches this many candidates per
+    /// requested result for the reranker to choose from.
+    #[serde(default = "default_rerank_candidates")]
+    pub rerank_candidates: usize,
+
     /// Automatically extract facts, preferences, and entities after each conversation.
     #[serde(default = "default_true")]
     pub auto_extract: bool,
@@ -762,9 +1551,14 @@ pub struct MemoryConfig {
     #[serde(default = "default_consolidation_age_days")]
     pub consolidation_age_days: u32,
 
-    /// Maximum number of old memories to consolidate per tick.
+    /// Maximum number of old memories to consolidate per run.
     #[serde(default = "default_consolidation_batch")]
     pub consolidation_batch_size: usize,
+
+    /// How often to run memory consolidation, independent of the tick
+    /// interval.  Set to 0 to only consolidate when triggered manually.
+    #[serde(default = "default_consolidation_interval_secs")]
+    pub consolidation_interval_secs: u64,
 }
 
 impl Default for MemoryConfig {
@@ -772,9 +1566,18 @@ impl Default for MemoryConfig {
         Self {
             embedding_model: default_embedding_model(),
             embedding_host: String::new(),
+            embedding_backend: default_embedding_backend(),
+            embedding_model_path: String::new(),
+            embedding_batch_size: default_embedding_batch_size(),
+            embedding_concurrency: default_embedding_concurrency(),
+            embedding_dim: 0,
+            rerank_url: String::new(),
+            rerank_model: String::new(),
+            rerank_candidates: default_rerank_candidates(),
             auto_extract: true,
             consolidation_age_days: default_consolidation_age_days(),
             consolidation_batch_size: default_consolidation_batch(),
+            consolidation_interval_secs: default_consolidation_interval_secs(),
         }
     }
 }
@@ -790,15 +1593,25 @@ fn default_timezone() -> String {
 fn default_locale() -> String {
     "en-US".to_string()
 }
+fn default_event_buffer_size() -> usize {
+    500
+}
+
 fn default_dashboard_bind() -> String {
     "127.0.0.1:3030".to_string()
 }
 fn default_tick_interval_secs() -> u64 {
     120
 }
+fn default_shutdown_grace_secs() -> u64 {
+    30
+}
 fn default_conversation_window() -> usize {
     5
 }
+fn default_context_max_tokens() -> usize {
+    32_000
+}
 fn default_approval_expiry_secs() -> u64 {
     3600
 }
@@ -810,6 +1623,10 @@ fn default_auto_approve_tools() -> Vec<String> {
         "goal".to_string(),
     ]
 }
+fn default_max_repeated_tool_calls() -> usize {
+    2
+}
+
 fn default_max_tool_turns() -> usize {
     5
 }
@@ -849,15 +1666,81 @@ fn default_top_p() -> f32 {
 fn default_max_tokens() -> usize {
     2048
 }
+fn default_oidc_provider() -> String {
+    "generic".to_string()
+}
+
+fn default_oidc_scopes() -> String {
+    "openid email profile".to_string()
+}
+
+fn default_oidc_role() -> String {
+    "viewer".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
 fn default_exec_timeout() -> u64 {
     30
 }
+fn default_image_api_url() -> String {
+    "https://api.openai.com/v1/images/generations".to_string()
+}
+fn default_image_model() -> String {
+    "dall-e-3".to_string()
+}
+fn default_image_api_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+fn default_image_timeout_secs() -> u64 {
+    120
+}
 fn default_web_max_results() -> usize {
     10
 }
+fn default_web_max_fetch_bytes() -> usize {
+    5 * 1024 * 1024
+}
+fn default_secrets_provider() -> String {
+    "env".to_string()
+}
+fn default_vault_mount() -> String {
+    "secret".to_string()
+}
+fn default_vault_path() -> String {
+    "safeclaw".to_string()
+}
+fn default_goal_priority_aging_per_hour() -> f64 {
+    0.1
+}
+fn default_goal_max_decomposed_tasks() -> usize {
+    10
+}
+fn default_session_max_idle_hours() -> u64 {
+    72
+}
+fn default_tool_max_output_bytes() -> usize {
+    64 * 1024
+}
+fn default_web_search_provider() -> String {
+    "duckduckgo".to_string()
+}
+fn default_web_per_host_delay_ms() -> u64 {
+    1000
+}
+fn default_web_search_cache_ttl_secs() -> u64 {
+    3600
+}
+fn default_browser_timeout_secs() -> u64 {
+    30
+}
+fn default_browser_max_content_chars() -> usize {
+    8000
+}
+fn default_web_fetch_content_types() -> Vec<String> {
+    vec!["text/html".into(), "text/plain".into(), "application/json".into()]
+}
 fn default_acme_port() -> u16 {
     443
 }
@@ -885,23 +1768,54 @@ fn default_tailscale_mode() -> String {
 fn default_embedding_model() -> String {
     "nomic-embed-text".to_string()
 }
+fn default_embedding_backend() -> String {
+    "ollama".to_string()
+}
+fn default_embedding_batch_size() -> usize {
+    64
+}
+fn default_embedding_concurrency() -> usize {
+    4
+}
+fn default_rerank_candidates() -> usize {
+    4
+}
 fn default_consolidation_age_days() -> u32 {
     30
 }
 fn default_consolidation_batch() -> usize {
     20
 }
+fn default_consolidation_interval_secs() -> u64 {
+    3600
+}
 fn default_2fa_tools() -> Vec<String> {
     vec![
         "exec".to_string(),
     ]
 }
+fn default_twofa_ttl_secs() -> u64 {
+    300
+}
 fn default_rate_limit_per_minute() -> u32 {
     30
 }
 fn default_rate_limit_per_hour() -> u32 {
     300
 }
+fn default_seccomp_deny() -> Vec<String> {
+    [
+        "ptrace", "process_vm_readv", "process_vm_writev",
+        "mount", "umount2", "pivot_root",
+        "keyctl", "add_key", "request_key",
+        "kexec_load", "init_module", "finit_module", "delete_module",
+        "reboot", "swapon", "swapoff", "acct",
+        "bpf", "perf_event_open", "userfaultfd", "open_by_handle_at",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
 
 // -- Default impls -------------------------------------------------------
 
@@ -915,6 +1829,7 @@ impl Default for LlmConfig {
             model: default_model(),
             max_turns: default_max_turns(),
             timeout_secs: default_timeout_secs(),
+            process_limits: std::collections::HashMap::new(),
             cline_bin: default_cline_bin(),
             cline_model: String::new(),
             codex_bin: default_codex_bin(),
@@ -930,8 +1845,11 @@ impl Default for LlmConfig {
             openrouter_max_tokens: 0,
             openrouter_site_url: String::new(),
             openrouter_app_name: String::new(),
+            openrouter_vision: false,
+            openrouter_native_tools: false,
             ollama_host: String::new(),
             ollama_model: String::new(),
+            ollama_vision: false,
             model_path: String::new(),
             temperature: default_temperature(),
             top_p: default_top_p(),
@@ -954,6 +1872,32 @@ impl Default for WebToolConfig {
         Self {
             enabled: true,
             max_results: default_web_max_results(),
+            search_provider: default_web_search_provider(),
+            searxng_url: String::new(),
+            max_fetch_bytes: default_web_max_fetch_bytes(),
+            fetch_content_types: default_web_fetch_content_types(),
+            html_to_text: false,
+            respect_robots: true,
+            robots_exempt_hosts: Vec::new(),
+            per_host_delay_ms: default_web_per_host_delay_ms(),
+            search_cache_ttl_secs: default_web_search_cache_ttl_secs(),
+        }
+    }
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            max_output_bytes: default_tool_max_output_bytes(),
+            max_output_bytes_per_tool: std::collections::HashMap::new(),
+            exec: ExecToolConfig::default(),
+            web: WebToolConfig::default(),
+            browser: BrowserToolConfig::default(),
+            message: MessageToolConfig::default(),
+            cron: CronToolConfig::default(),
+            git: GitToolConfig::default(),
+            http: HttpToolConfig::default(),
+            image: ImageToolConfig::default(),
         }
     }
 }
@@ -963,6 +1907,8 @@ impl Default for BrowserToolConfig {
         Self {
             enabled: false,
             headless: true,
+            timeout_secs: default_browser_timeout_secs(),
+            max_content_chars: default_browser_max_content_chars(),
         }
     }
 }
@@ -981,11 +1927,44 @@ impl Default for CronToolConfig {
     }
 }
 
+impl Default for GitToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: default_exec_timeout(),
+        }
+    }
+}
+
+impl Default for HttpToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_hosts: Vec::new(),
+            auth_hosts: Vec::new(),
+            timeout_secs: default_exec_timeout(),
+        }
+    }
+}
+
+impl Default for ImageToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_url: default_image_api_url(),
+            model: default_image_model(),
+            api_key_env: default_image_api_key_env(),
+            timeout_secs: default_image_timeout_secs(),
+        }
+    }
+}
+
 impl Default for TelegramConfig {
     fn default() -> Self {
         Self {
             enabled: false,
             allowed_chat_ids: Vec::new(),
+            notify_all_chats: false,
         }
     }
 }
@@ -994,6 +1973,7 @@ impl Default for SessionsConfig {
     fn default() -> Self {
         Self {
             enabled: false,
+            max_idle_hours: default_session_max_idle_hours(),
         }
     }
 }
@@ -1063,11 +2043,21 @@ impl Default for SecurityConfig {
         Self {
             blocked_tools: Vec::new(),
             require_2fa: default_2fa_tools(),
+            twofa_ttl_secs: default_twofa_ttl_secs(),
+            twofa_require_totp: false,
             rate_limit_per_minute: default_rate_limit_per_minute(),
             rate_limit_per_hour: default_rate_limit_per_hour(),
             daily_cost_limit_usd: 0.0,
+            user_daily_cost_limit_usd: 0.0,
+            cost_per_1k_tokens_usd: 0.0,
             pii_detection: true,
             tool_capabilities: std::collections::HashMap::new(),
+            path_policy: std::collections::HashMap::new(),
+            exec_allow: Vec::new(),
+            exec_deny: Vec::new(),
+            seccomp: true,
+            seccomp_deny: default_seccomp_deny(),
+            sandbox_quota_bytes: 0,
         }
     }
 }
@@ -1081,10 +2071,15 @@ impl Default for Config {
             locale: default_locale(),
             dashboard_bind: default_dashboard_bind(),
             tick_interval_secs: default_tick_interval_secs(),
+            shutdown_grace_secs: default_shutdown_grace_secs(),
             conversation_window: default_conversation_window(),
+            context_max_tokens: default_context_max_tokens(),
             approval_expiry_secs: default_approval_expiry_secs(),
             auto_approve_tools: default_auto_approve_tools(),
+            auto_approve_rules: Vec::new(),
             max_tool_turns: default_max_tool_turns(),
+            max_repeated_tool_calls: default_max_repeated_tool_calls(),
+            continue_unfinished_as_goal: false,
             llm: LlmConfig::default(),
             tools: ToolsConfig::default(),
             dashboard: DashboardConfig::default(),
@@ -1095,40 +2090,336 @@ impl Default for Config {
             android_sms: AndroidSmsConfig::default(),
             discord: DiscordConfig::default(),
             signal: SignalConfig::default(),
+            status_notifications: StatusNotificationsConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
             sessions: SessionsConfig::default(),
             tunnel: TunnelConfig::default(),
             tls: TlsConfig::default(),
             security: SecurityConfig::default(),
+            secrets: SecretsConfig::default(),
             federation: FederationConfig::default(),
+            mcp: McpConfig::default(),
+            rhai: RhaiConfig::default(),
+            skill_registry: SkillRegistryConfig::default(),
             plugins: PluginsConfig::default(),
             memory: MemoryConfig::default(),
+            goals: GoalsConfig::default(),
+            check_in: CheckInConfig::default(),
+            source_path: None,
         }
     }
 }
 
 // -- Config impl ---------------------------------------------------------
 
+/// Settings [`Config::reload`] can apply without a restart.
+pub const HOT_RELOADABLE: &[&str] = &[
+    "tick_interval_secs",
+    "security.rate_limit_per_minute",
+    "security.rate_limit_per_hour",
+    "auto_approve_tools",
+    "auto_approve_rules",
+    "security.blocked_tools",
+    "core_personality",
+    "quiet_hours",
+];
+
+/// A single problem found by [`Config::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Dotted path of the offending setting, e.g. `tls.acme_email`.
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Check that `bind` is `host:port` with a usable port.
+fn validate_bind_addr(bind: &str) -> std::result::Result<(), String> {
+    if bind.parse::<std::net::SocketAddr>().is_ok() {
+        return Ok(());
+    }
+    let Some((host, port)) = bind.rsplit_once(':') else {
+        return Err(format!("\"{bind}\" is not of the form host:port"));
+    };
+    if host.is_empty() {
+        return Err(format!("\"{bind}\" has no host"));
+    }
+    match port.parse::<u16>() {
+        Ok(0) | Err(_) => Err(format!("\"{port}\" is not a valid port")),
+        Ok(_) => Ok(()),
+    }
+}
+
+/// Result of [`Config::reload`].
+#[derive(Debug)]
+pub struct ConfigReload {
+    /// The freshly parsed config.
+    pub config: Config,
+    /// Hot-reloadable settings that changed.
+    pub applied: Vec<&'static str>,
+    /// Sections that changed but only take effect after a restart.
+    pub restart_required: Vec<&'static str>,
+}
+
 impl Config {
-    /// Load config from the given path, or the default XDG config location.
+    /// Load config from the given path, or the default XDG config location,
+    /// and [`validate`](Self::validate) it.  Every problem found is listed
+    /// in the error, one per line.
     pub fn load(path: Option<&Path>) -> Result<Self> {
+        let config = Self::load_unvalidated(path)?;
+        let issues = config.validate();
+        if issues.is_empty() {
+            return Ok(config);
+        }
+        let list: Vec<String> = issues.iter().map(|i| format!("  {i}")).collect();
+        Err(SafeAgentError::Config(format!(
+            "{} problem(s) in config:\n{}",
+            issues.len(),
+            list.join("\n")
+        )))
+    }
+
+    /// Load config without cross-field validation.  Used by `--check`,
+    /// which reports the problems itself.
+    pub fn load_unvalidated(path: Option<&Path>) -> Result<Self> {
         let config_path = match path {
             Some(p) => p.to_path_buf(),
             None => Self::default_config_path(),
         };
 
-        let config = if config_path.exists() {
+        let mut config = if config_path.exists() {
             info!("loading config from {}", config_path.display());
             let contents = std::fs::read_to_string(&config_path).map_err(SafeAgentError::Io)?;
-            toml::from_str(&contents)
-                .map_err(|e| SafeAgentError::Config(format!("parse error: {e}")))?
+            toml::from_str(&contents).map_err(|e| {
+                SafeAgentError::Config(format!("{}: {e}", config_path.display()))
+            })?
         } else {
             info!("no config file found, using defaults");
             Config::default()
         };
+        config.source_path = Some(config_path);
 
         Ok(config)
     }
 
+    /// Check cross-field invariants that serde can't express.  Returns
+    /// every problem found; an empty list means the config is usable.
+    ///
+    /// Settings with environment overrides (`LLM_BACKEND`, `MODEL_PATH`,
+    /// `ACME_*`) are checked with the overrides applied.
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |field: &str, message: String| {
+            issues.push(ConfigIssue {
+                field: field.to_string(),
+                message,
+            });
+        };
+
+        if let Err(e) = validate_bind_addr(&self.dashboard_bind) {
+            issue("dashboard_bind", e);
+        }
+        if self.tick_interval_secs == 0 {
+            issue("tick_interval_secs", "must be at least 1".into());
+        }
+        if self.max_tool_turns == 0 {
+            issue("max_tool_turns", "must be at least 1".into());
+        }
+        if self.tools.browser.timeout_secs == 0 {
+            issue("tools.browser.timeout_secs", "must be at least 1".into());
+        }
+
+        // LLM backends
+        let known = crate::llm::BACKEND_KEYS;
+        let backends: Vec<(String, String)> = if self.llm.failover_chain.is_empty() {
+            let backend = std::env::var("LLM_BACKEND").unwrap_or_else(|_| self.llm.backend.clone());
+            vec![("llm.backend".into(), backend)]
+        } else {
+            self.llm
+                .failover_chain
+                .iter()
+                .enumerate()
+                .map(|(i, key)| (format!("llm.failover_chain[{i}]"), key.clone()))
+                .collect()
+        };
+        for (field, key) in &backends {
+            if !known.contains(&key.as_str()) {
+                issue(
+                    field,
+                    format!("unknown backend \"{key}\" (expected one of: {})", known.join(", ")),
+                );
+            }
+        }
+        let uses_local = backends.iter().any(|(_, key)| key == "local");
+        if uses_local && self.llm.model_path.is_empty() && std::env::var("MODEL_PATH").is_err() {
+            issue(
+                "llm.model_path",
+                "the local backend requires a .gguf model path (or MODEL_PATH)".into(),
+            );
+        }
+
+        // TLS / tunnel
+        let tls = crate::acme::resolve_tls_config(self);
+        if tls.acme_enabled {
+            if tls.acme_domains.is_empty() {
+                issue("tls.acme_domains", "ACME is enabled but no domains are set".into());
+            }
+            if tls.acme_email.is_empty() {
+                issue("tls.acme_email", "ACME is enabled but no contact email is set".into());
+            }
+            if self.tunnel.enabled {
+                issue(
+                    "tls.acme_enabled",
+                    "conflicts with tunnel.enabled; the tunnel already terminates TLS, enable one or the other".into(),
+                );
+            }
+        }
+        if self.tunnel.enabled && !["ngrok", "cloudflare", "tailscale"].contains(&self.tunnel.provider.as_str()) {
+            issue(
+                "tunnel.provider",
+                format!(
+                    "unknown provider \"{}\" (expected ngrok, cloudflare or tailscale)",
+                    self.tunnel.provider
+                ),
+            );
+        }
+
+        if let Err(e) = self.check_in.quiet_hours() {
+            issue("check_in.quiet_hours_start", e);
+        }
+        if let Err(e) = self.quiet_hours.window() {
+            issue("quiet_hours.start", e);
+        }
+
+        // Dashboard login
+        let oidc = &self.dashboard.oidc;
+        if oidc.enabled {
+            if oidc.client_id.is_empty() {
+                issue("dashboard.oidc.client_id", "required when OIDC login is enabled".into());
+            }
+            match oidc.provider.as_str() {
+                "google" | "github" => {}
+                "generic" if oidc.issuer.is_empty() => {
+                    issue("dashboard.oidc.issuer", "required when dashboard.oidc.provider = \"generic\"".into());
+                }
+                "generic" => {}
+                other => issue(
+                    "dashboard.oidc.provider",
+                    format!("unknown provider \"{other}\" (expected google, github or generic)"),
+                ),
+            }
+            if !["admin", "user", "viewer"].contains(&oidc.default_role.as_str()) {
+                issue(
+                    "dashboard.oidc.default_role",
+                    format!("unknown role \"{}\" (expected admin, user or viewer)", oidc.default_role),
+                );
+            }
+        }
+
+        // Secrets
+        match self.secrets.provider.as_str() {
+            "env" => {}
+            "file" if self.secrets.file.is_empty() => {
+                issue("secrets.file", "required when secrets.provider = \"file\"".into());
+            }
+            "file" => {}
+            "vault" if self.secrets.vault_addr.is_empty() => {
+                issue("secrets.vault_addr", "required when secrets.provider = \"vault\"".into());
+            }
+            "vault" => {}
+            other => issue(
+                "secrets.provider",
+                format!("unknown provider \"{other}\" (expected env, file or vault)"),
+            ),
+        }
+
+        issues
+    }
+
+    /// Re-read the file this config came from and compare it against `self`.
+    ///
+    /// Only the settings in [`HOT_RELOADABLE`] can be applied to a running
+    /// agent; every other section that changed is listed in
+    /// [`ConfigReload::restart_required`].
+    pub fn reload(&self) -> Result<ConfigReload> {
+        let config = Self::load(self.source_path.as_deref())?;
+
+        let mut applied = Vec::new();
+        if config.tick_interval_secs != self.tick_interval_secs {
+            applied.push("tick_interval_secs");
+        }
+        if config.security.rate_limit_per_minute != self.security.rate_limit_per_minute {
+            applied.push("security.rate_limit_per_minute");
+        }
+        if config.security.rate_limit_per_hour != self.security.rate_limit_per_hour {
+            applied.push("security.rate_limit_per_hour");
+        }
+        if config.auto_approve_tools != self.auto_approve_tools {
+            applied.push("auto_approve_tools");
+        }
+        if config.auto_approve_rules != self.auto_approve_rules {
+            applied.push("auto_approve_rules");
+        }
+        if config.security.blocked_tools != self.security.blocked_tools {
+            applied.push("security.blocked_tools");
+        }
+        if config.core_personality != self.core_personality {
+            applied.push("core_personality");
+        }
+        if config.quiet_hours != self.quiet_hours {
+            applied.push("quiet_hours");
+        }
+
+        // Compare everything else with the hot-reloadable values masked out.
+        let old = self.without_hot_settings();
+        let new = config.without_hot_settings();
+        let mut restart_required = Vec::new();
+        macro_rules! compare {
+            ($($field:ident),* $(,)?) => {
+                $(if old.$field != new.$field {
+                    restart_required.push(stringify!($field));
+                })*
+            };
+        }
+        compare!(
+            agent_name, timezone, locale, dashboard_bind, conversation_window,
+            context_max_tokens, approval_expiry_secs, shutdown_grace_secs, max_tool_turns, max_repeated_tool_calls,
+            continue_unfinished_as_goal, llm, tools, dashboard, telegram,
+            whatsapp, imessage, twilio, android_sms, discord, signal, status_notifications, sessions,
+            tunnel, tls, security, secrets, federation, mcp, rhai, skill_registry, plugins, memory,
+            goals, check_in,
+        );
+
+        Ok(ConfigReload {
+            config,
+            applied,
+            restart_required,
+        })
+    }
+
+    fn without_hot_settings(&self) -> Config {
+        Config {
+            tick_interval_secs: 0,
+            auto_approve_tools: Vec::new(),
+            auto_approve_rules: Vec::new(),
+            core_personality: String::new(),
+            quiet_hours: QuietHoursConfig::default(),
+            security: SecurityConfig {
+                rate_limit_per_minute: 0,
+                rate_limit_per_hour: 0,
+                blocked_tools: Vec::new(),
+                ..self.security.clone()
+            },
+            source_path: None,
+            ..self.clone()
+        }
+    }
+
     /// Returns the default config file path: `$XDG_CONFIG_HOME/safeclaw/config.toml`
     pub fn default_config_path() -> PathBuf {
         dirs::config_dir()
@@ -1144,18 +2435,32 @@ impl Config {
             .join("safeclaw")
     }
 
-    /// Get the Telegram bot token from the environment.
-    pub fn telegram_bot_token() -> Result<String> {
-        std::env::var("TELEGRAM_BOT_TOKEN")
-            .map_err(|_| SafeAgentError::Config("TELEGRAM_BOT_TOKEN environment variable not set".into()))
+    /// The secret provider selected by `[secrets]`.
+    pub fn secret_provider(&self) -> Result<Box<dyn SecretProvider>> {
+        crate::security::secrets::provider_from_config(&self.secrets)
     }
 
-    /// Read Twilio credentials from environment variables.
-    pub fn twilio_credentials() -> Result<(String, String)> {
-        let sid = std::env::var("TWILIO_ACCOUNT_SID")
-            .map_err(|_| SafeAgentError::Config("TWILIO_ACCOUNT_SID not set".into()))?;
-        let token = std::env::var("TWILIO_AUTH_TOKEN")
-            .map_err(|_| SafeAgentError::Config("TWILIO_AUTH_TOKEN not set".into()))?;
+    /// Resolve a required secret through the configured provider.
+    pub async fn secret(&self, key: &str) -> Result<String> {
+        let provider = self.secret_provider()?;
+        crate::security::secrets::require(provider.as_ref(), key).await
+    }
+
+    /// Get the Telegram bot token.
+    pub async fn telegram_bot_token(&self) -> Result<String> {
+        self.secret("TELEGRAM_BOT_TOKEN").await
+    }
+
+    /// Get the Discord bot token.
+    pub async fn discord_bot_token(&self) -> Result<String> {
+        self.secret("DISCORD_BOT_TOKEN").await
+    }
+
+    /// Get the Twilio account SID and auth token.
+    pub async fn twilio_credentials(&self) -> Result<(String, String)> {
+        let provider = self.secret_provider()?;
+        let sid = crate::security::secrets::require(provider.as_ref(), "TWILIO_ACCOUNT_SID").await?;
+        let token = crate::security::secrets::require(provider.as_ref(), "TWILIO_AUTH_TOKEN").await?;
         Ok((sid, token))
     }
 
@@ -1223,6 +2528,9 @@ mod tests {
         assert!(d.password_enabled);
         assert!(d.sso_providers.is_empty());
         assert!(d.sso_allowed_emails.is_empty());
+        assert!(!d.oidc.enabled);
+        assert_eq!(d.oidc.scopes, "openid email profile");
+        assert_eq!(d.oidc.default_role, "viewer");
     }
 
     #[test]
@@ -1329,6 +2637,28 @@ mod tests {
         assert_eq!(c.dashboard.sso_allowed_emails, vec!["admin@example.com"]);
     }
 
+    #[test]
+    fn parse_and_validate_dashboard_oidc() {
+        let toml_str = r#"
+        [dashboard.oidc]
+        enabled = true
+        issuer = "https://id.example.com"
+        client_id = "safeclaw"
+        default_role = "user"
+        "#;
+        let c: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(c.dashboard.oidc.provider, "generic");
+        assert!(c.dashboard.oidc.auto_provision);
+        assert!(c.validate().iter().all(|i| !i.field.starts_with("dashboard.")));
+
+        let mut c = c;
+        c.dashboard.oidc.issuer.clear();
+        c.dashboard.oidc.default_role = "owner".into();
+        let fields: Vec<String> = c.validate().into_iter().map(|i| i.field).collect();
+        assert!(fields.contains(&"dashboard.oidc.issuer".to_string()));
+        assert!(fields.contains(&"dashboard.oidc.default_role".to_string()));
+    }
+
     #[test]
     fn load_nonexistent_returns_defaults() {
         let c = Config::load(Some(Path::new("/tmp/nonexistent-safeclaw-test.toml"))).unwrap();
@@ -1357,10 +2687,10 @@ mod tests {
         assert!(path.to_string_lossy().contains("safeclaw"));
     }
 
-    #[test]
-    fn telegram_bot_token_without_env_var_errors() {
+    #[tokio::test]
+    async fn telegram_bot_token_without_env_var_errors() {
         unsafe { std::env::remove_var("TELEGRAM_BOT_TOKEN"); }
-        assert!(Config::telegram_bot_token().is_err());
+        assert!(Config::default().telegram_bot_token().await.is_err());
     }
 
     #[test]
@@ -1407,6 +2737,14 @@ mod tests {
         assert!(c.allowed_numbers.is_empty());
     }
 
+    #[test]
+    fn default_federation_config() {
+        let f = FederationConfig::default();
+        assert!(!f.enabled);
+        assert_eq!(f.health_check_interval_secs, 30);
+        assert_eq!(f.health_failure_threshold, 3);
+    }
+
     #[test]
     fn default_android_sms_config() {
         let c = AndroidSmsConfig::default();
@@ -1414,4 +2752,139 @@ mod tests {
         assert_eq!(c.bridge_url, "http://127.0.0.1:3041");
         assert!(c.allowed_ids.is_empty());
     }
+
+    #[test]
+    fn reload_applies_hot_settings_and_flags_the_rest() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "tick_interval_secs = 120\n[security]\nrate_limit_per_minute = 30\n",
+        )
+        .unwrap();
+        let config = Config::load(Some(&path)).unwrap();
+        assert_eq!(config.source_path.as_deref(), Some(path.as_path()));
+
+        std::fs::write(
+            &path,
+            "tick_interval_secs = 15\n\
+             dashboard_bind = \"0.0.0.0:9000\"\n\
+             [security]\nrate_limit_per_minute = 5\nrate_limit_per_hour = 50\n\
+             [llm]\nbackend = \"ollama\"\n",
+        )
+        .unwrap();
+        let reload = config.reload().unwrap();
+        assert_eq!(reload.config.tick_interval_secs, 15);
+        assert_eq!(reload.config.security.rate_limit_per_minute, 5);
+        assert_eq!(
+            reload.applied,
+            vec![
+                "tick_interval_secs",
+                "security.rate_limit_per_minute",
+                "security.rate_limit_per_hour",
+            ]
+        );
+        assert_eq!(reload.restart_required, vec!["dashboard_bind", "llm"]);
+    }
+
+    #[test]
+    fn reload_of_unchanged_file_reports_nothing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[security]\nblocked_tools = [\"exec\"]\n").unwrap();
+        let config = Config::load(Some(&path)).unwrap();
+
+        let reload = config.reload().unwrap();
+        assert!(reload.applied.is_empty());
+        assert!(reload.restart_required.is_empty());
+
+        std::fs::write(&path, "[security]\nblocked_tools = [\"exec\"]\npii_detection = false\n").unwrap();
+        assert_eq!(config.reload().unwrap().restart_required, vec!["security"]);
+
+        std::fs::write(&path, "shutdown_grace_secs = 5\n[security]\nblocked_tools = [\"exec\"]\n").unwrap();
+        assert_eq!(config.reload().unwrap().restart_required, vec!["shutdown_grace_secs"]);
+    }
+
+    fn issues(toml_src: &str) -> Vec<String> {
+        let config: Config = toml::from_str(toml_src).unwrap();
+        config.validate().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(Config::default().validate().is_empty());
+    }
+
+    #[test]
+    fn validate_rejects_bad_bind_and_intervals() {
+        assert_eq!(
+            issues("dashboard_bind = \"localhost\"\ntick_interval_secs = 0\n"),
+            vec![
+                "dashboard_bind: \"localhost\" is not of the form host:port",
+                "tick_interval_secs: must be at least 1",
+            ]
+        );
+        assert_eq!(
+            issues("dashboard_bind = \"0.0.0.0:99999\"\n"),
+            vec!["dashboard_bind: \"99999\" is not a valid port"]
+        );
+        assert!(issues("dashboard_bind = \"localhost:8080\"\n").is_empty());
+        assert_eq!(
+            issues("[tools.browser]\ntimeout_secs = 0\n"),
+            vec!["tools.browser.timeout_secs: must be at least 1"]
+        );
+    }
+
+    #[test]
+    fn validate_checks_quiet_hours() {
+        assert_eq!(
+            issues("[check_in]\nquiet_hours_start = \"10pm\"\nquiet_hours_end = \"07:00\"\n"),
+            vec!["check_in.quiet_hours_start: \"10pm\" is not a time of the form HH:MM"]
+        );
+        let quiet = QuietHours::parse("22:00", "07:00").unwrap().unwrap();
+        let t = |s| chrono::NaiveTime::parse_from_str(s, "%H:%M").unwrap();
+        assert!(quiet.contains(t("23:30")) && quiet.contains(t("06:59")));
+        assert!(!quiet.contains(t("07:00")) && !quiet.contains(t("12:00")));
+    }
+
+    #[test]
+    fn validate_checks_failover_chain_and_local_model() {
+        assert_eq!(
+            issues("[llm]\nfailover_chain = [\"claude\", \"gpt5\", \"local\"]\n"),
+            vec![
+                "llm.failover_chain[1]: unknown backend \"gpt5\" (expected one of: claude, cline, codex, gemini, aider, openrouter, ollama, local)",
+                "llm.model_path: the local backend requires a .gguf model path (or MODEL_PATH)",
+            ]
+        );
+        assert!(issues("[llm]\nfailover_chain = [\"local\"]\nmodel_path = \"/models/a.gguf\"\n").is_empty());
+    }
+
+    #[test]
+    fn validate_checks_acme_and_tunnel() {
+        assert_eq!(
+            issues("[tls]\nacme_enabled = true\n[tunnel]\nenabled = true\nprovider = \"frp\"\n"),
+            vec![
+                "tls.acme_domains: ACME is enabled but no domains are set",
+                "tls.acme_email: ACME is enabled but no contact email is set",
+                "tls.acme_enabled: conflicts with tunnel.enabled; the tunnel already terminates TLS, enable one or the other",
+                "tunnel.provider: unknown provider \"frp\" (expected ngrok, cloudflare or tailscale)",
+            ]
+        );
+    }
+
+    #[test]
+    fn load_lists_every_problem() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "tick_interval_secs = 0\n[tls]\nacme_enabled = true\nacme_domains = [\"a.example\"]\n").unwrap();
+        let err = Config::load(Some(&path)).unwrap_err().to_string();
+        assert!(err.contains("2 problem(s)"), "{err}");
+        assert!(err.contains("tick_interval_secs: must be at least 1"));
+        assert!(err.contains("tls.acme_email:"));
+        assert!(Config::load_unvalidated(Some(&path)).is_ok());
+
+        std::fs::write(&path, "tick_interval_secs = -5\n").unwrap();
+        let err = Config::load(Some(&path)).unwrap_err().to_string();
+        assert!(err.contains("tick_interval_secs"), "{err}");
+    }
 }
@@ -204,11 +204,11 @@ impl FieldEncryptor {
 // Hex helpers (no extra dependency)
 // ---------------------------------------------------------------------------
 
-fn hex_encode(bytes: &[u8]) -> String {
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
     bytes.iter().map(|b| format!("{b:02x}")).collect()
 }
 
-fn hex_decode(hex: &str, out: &mut [u8]) -> Result<()> {
+pub(crate) fn hex_decode(hex: &str, out: &mut [u8]) -> Result<()> {
     if hex.len() != out.len() * 2 {
         return Err(SafeAgentError::Config("hex decode: length mismatch".into()));
     }
@@ -1,8 +1,10 @@
 use axum::body::Body;
 use axum::extract::{Path, Query, State};
-use axum::http::{Request, StatusCode};
-use axum::middleware::Next;
+use axum::http::{Method, Request, StatusCode};
+use axum::middleware::{self, Next};
 use axum::response::{IntoResponse, Json, Redirect, Response};
+use axum::routing::MethodRouter;
+use hmac::Mac;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
@@ -10,9 +12,19 @@ use tracing::{error, info, warn};
 use super::authn;
 use super::oauth;
 use super::routes::DashState;
+use crate::users::{UserContext, UserManager, UserRole};
+
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
 
 const COOKIE_NAME: &str = "sa_token";
 
+/// Cookie carrying the CSRF token.  Readable by the frontend, which echoes
+/// it back in [`CSRF_HEADER`].
+const CSRF_COOKIE: &str = "sa_csrf";
+
+/// Header that must carry the CSRF token on state-changing requests.
+const CSRF_HEADER: &str = "x-csrf-token";
+
 /// JWT expiry: 7 days (in seconds).
 const TOKEN_EXPIRY_SECS: u64 = 7 * 24 * 60 * 60;
 
@@ -39,7 +51,16 @@ struct Claims {
 /// Extract and validate the JWT from the request's cookie header.
 /// Returns the decoded claims if valid, or None.
 fn extract_claims(req: &Request<Body>, secret: &[u8]) -> Option<Claims> {
-    let cookie_header = req.headers().get(axum::http::header::COOKIE)?;
+    claims_from_headers(req.headers(), secret)
+}
+
+fn claims_from_headers(headers: &axum::http::HeaderMap, secret: &[u8]) -> Option<Claims> {
+    session_from_headers(headers, secret).map(|(_, claims)| claims)
+}
+
+/// The first valid session JWT in the cookie header, with its claims.
+fn session_from_headers<'h>(headers: &'h axum::http::HeaderMap, secret: &[u8]) -> Option<(&'h str, Claims)> {
+    let cookie_header = headers.get(axum::http::header::COOKIE)?;
     let cookies = cookie_header.to_str().ok()?;
 
     for pair in cookies.split(';') {
@@ -51,7 +72,7 @@ fn extract_claims(req: &Request<Body>, secret: &[u8]) -> Option<Claims> {
             validation.validate_exp = true;
 
             if let Ok(data) = decode::<Claims>(token, &key, &validation) {
-                return Some(data.claims);
+                return Some((token, data.claims));
             }
         }
     }
@@ -59,9 +80,105 @@ fn extract_claims(req: &Request<Body>, secret: &[u8]) -> Option<Claims> {
     None
 }
 
-/// Backward-compatible: returns true if there's any valid JWT.
-fn validate_token(req: &Request<Body>, secret: &[u8]) -> bool {
-    extract_claims(req, secret).is_some()
+/// CSRF token for a session: an HMAC of the session JWT, so it can't be
+/// forged without the server secret and dies with the session.
+fn csrf_mac(secret: &[u8], session_token: &str) -> HmacSha256 {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(b"csrf:");
+    mac.update(session_token.as_bytes());
+    mac
+}
+
+fn csrf_token(secret: &[u8], session_token: &str) -> String {
+    crate::crypto::hex_encode(&csrf_mac(secret, session_token).finalize().into_bytes())
+}
+
+/// Attributes shared by every session and CSRF cookie.  Clearing a cookie
+/// only works with the same path and attributes it was set with.
+const COOKIE_ATTRS: &str = "Path=/; SameSite=Lax";
+
+/// Set-Cookie headers for a new session: the HttpOnly JWT plus the CSRF
+/// token the frontend sends back in [`CSRF_HEADER`].
+pub(super) fn session_cookies(secret: &[u8], token: &str) -> axum::http::HeaderMap {
+    let session = format!(
+        "{COOKIE_NAME}={token}; {COOKIE_ATTRS}; HttpOnly; Max-Age={TOKEN_EXPIRY_SECS}"
+    );
+    let mut headers = axum::http::HeaderMap::new();
+    headers.append(axum::http::header::SET_COOKIE, session.parse().unwrap());
+    headers.append(axum::http::header::SET_COOKIE, csrf_cookie(secret, token).parse().unwrap());
+    headers
+}
+
+fn csrf_cookie(secret: &[u8], token: &str) -> String {
+    format!(
+        "{CSRF_COOKIE}={}; {COOKIE_ATTRS}; Max-Age={TOKEN_EXPIRY_SECS}",
+        csrf_token(secret, token)
+    )
+}
+
+/// Double-submit CSRF check.  Requests carrying a valid session cookie must
+/// repeat the session's CSRF token in [`CSRF_HEADER`]; a cross-site form or
+/// fetch gets the cookie sent along but can't read the token.  Requests
+/// without a session (skill tokens, webhooks) have nothing to protect and
+/// pass.
+fn csrf_ok(headers: &axum::http::HeaderMap, secret: &[u8]) -> bool {
+    let Some((token, _)) = session_from_headers(headers, secret) else {
+        return true;
+    };
+    let Some(sent) = headers.get(CSRF_HEADER).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let mut sent_bytes = [0u8; 32];
+    if crate::crypto::hex_decode(sent, &mut sent_bytes).is_err() {
+        return false;
+    }
+    csrf_mac(secret, token).verify_slice(&sent_bytes).is_ok()
+}
+
+/// Resolve the signed-in user behind the request's JWT.
+///
+/// Sessions tied to a user are re-read from the users table, so role
+/// changes and disabled or deleted accounts take effect immediately rather
+/// than when the token expires.  Legacy sessions act as admin.  None means
+/// there is no usable session.
+pub(crate) async fn session_user(
+    headers: &axum::http::HeaderMap,
+    secret: &[u8],
+    users: &UserManager,
+) -> Option<UserContext> {
+    let claims = claims_from_headers(headers, secret)?;
+    let Some(user_id) = claims.user_id else {
+        return Some(UserContext {
+            user_id: String::new(),
+            username: claims.sub.clone(),
+            display_name: claims.sub,
+            role: claims.role.as_deref().map_or(UserRole::Admin, UserRole::from_str),
+            source: "dashboard".to_string(),
+        });
+    };
+    let user = users.get_by_id(&user_id).await.ok()?;
+    if !user.enabled {
+        return None;
+    }
+    Some(UserContext::from_user(&user, "dashboard"))
+}
+
+/// Routes API keys may reach: the OpenAI-compatible endpoints.
+pub(crate) const API_KEY_PREFIX: &str = "/v1/";
+
+/// Resolve the user behind an `Authorization: Bearer <api key>` header.
+/// Disabled accounts are refused.
+pub(crate) async fn api_key_user(headers: &axum::http::HeaderMap, users: &UserManager) -> Option<UserContext> {
+    let key = headers
+        .get(axum::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    let user = users.get_by_api_key(key.trim()).await?;
+    if !user.enabled {
+        return None;
+    }
+    Some(UserContext::from_user(&user, "api"))
 }
 
 /// Mint a new JWT signed with the server's secret.
@@ -70,7 +187,7 @@ fn mint_token(secret: &[u8], subject: &str, method: &str) -> Result<String, json
 }
 
 /// Mint a JWT with user identity embedded.
-fn mint_token_with_user(
+pub(super) fn mint_token_with_user(
     secret: &[u8],
     subject: &str,
     method: &str,
@@ -102,13 +219,38 @@ fn mint_token_with_user(
 ///
 /// Always passes through: static assets (`/`, `/style.css`, `/app.js`)
 /// and auth endpoints (`/api/auth/*`).
+///
+/// Authenticated sessions, and API clients presenting a user's key as a
+/// bearer token, get their [`UserContext`] attached to the request for
+/// [`RequireRole`] guards and handlers.  API keys skip the login's second
+/// factor, so they only open the OpenAI-compatible routes under
+/// [`API_KEY_PREFIX`], not the dashboard API.
+///
+/// State-changing requests (anything but GET, HEAD and OPTIONS) made with a
+/// session cookie must also pass the CSRF check, including those to the
+/// auth endpoints.  Login and logout are exempt so a stale cookie can't lock
+/// anyone out.
 pub async fn require_auth(
     State(state): State<DashState>,
-    req: Request<Body>,
+    mut req: Request<Body>,
     next: Next,
 ) -> Response {
     let path = req.uri().path();
 
+    let mutating = !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    if mutating
+        && path != "/api/auth/login"
+        && path != "/api/auth/logout"
+        && !csrf_ok(req.headers(), &state.jwt_secret)
+    {
+        warn!(path, "request refused: missing or invalid CSRF token");
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({ "error": "invalid CSRF token" })),
+        )
+            .into_response();
+    }
+
     if path == "/"
         || path == "/style.css"
         || path == "/app.js"
@@ -120,7 +262,21 @@ pub async fn require_auth(
         return next.run(req).await;
     }
 
-    if validate_token(&req, &state.jwt_secret) {
+    if let Some(user) = session_user(req.headers(), &state.jwt_secret, &state.agent.user_manager).await {
+        req.extensions_mut().insert(user);
+        return next.run(req).await;
+    }
+
+    if path.starts_with(API_KEY_PREFIX) {
+        if let Some(user) = api_key_user(req.headers(), &state.agent.user_manager).await {
+            req.extensions_mut().insert(user);
+            return next.run(req).await;
+        }
+    }
+
+    // Skills calling back into their own extension routes authenticate
+    // with the capability token issued at launch.
+    if super::skill_ext::has_valid_skill_token(&state, &req) {
         return next.run(req).await;
     }
 
@@ -131,6 +287,40 @@ pub async fn require_auth(
         .into_response()
 }
 
+/// Route-level role guard, e.g. `post(handler).require_role(UserRole::Admin)`.
+///
+/// Requests whose [`UserContext`] (attached by [`require_auth`]) has a
+/// lower role get 403, as do requests authenticated some other way, such as
+/// skill capability tokens.
+pub trait RequireRole {
+    fn require_role(self, role: UserRole) -> Self;
+}
+
+impl<S: Clone + Send + Sync + 'static> RequireRole for MethodRouter<S> {
+    fn require_role(self, role: UserRole) -> Self {
+        self.route_layer(middleware::from_fn_with_state(role, check_role))
+    }
+}
+
+async fn check_role(State(required): State<UserRole>, req: Request<Body>, next: Next) -> Response {
+    match req.extensions().get::<UserContext>() {
+        Some(user) if user.role.at_least(required) => next.run(req).await,
+        user => {
+            warn!(
+                path = %req.uri().path(),
+                user = user.map(|u| u.username.as_str()).unwrap_or("-"),
+                required = %required,
+                "request refused: insufficient role"
+            );
+            (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({ "error": format!("{required} role required") })),
+            )
+                .into_response()
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Endpoints
 // ---------------------------------------------------------------------------
@@ -218,11 +408,7 @@ pub async fn login(
                     }
                 };
 
-                let cookie = format!(
-                    "{COOKIE_NAME}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={TOKEN_EXPIRY_SECS}"
-                );
-                let mut headers = axum::http::HeaderMap::new();
-                headers.insert(axum::http::header::SET_COOKIE, cookie.parse().unwrap());
+                let headers = session_cookies(&state.jwt_secret, &token);
 
                 info!(username = %u.username, role = %u.role, "user login successful");
                 return (headers, Json(serde_json::json!({
@@ -266,32 +452,29 @@ pub async fn login(
         }
     };
 
-    let cookie = format!(
-        "{COOKIE_NAME}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={TOKEN_EXPIRY_SECS}"
-    );
-
-    let mut headers = axum::http::HeaderMap::new();
-    headers.insert(axum::http::header::SET_COOKIE, cookie.parse().unwrap());
-
+    let headers = session_cookies(&state.jwt_secret, &token);
     (headers, Json(serde_json::json!({ "ok": true }))).into_response()
 }
 
-/// POST /api/auth/logout — clear the JWT cookie.
+/// POST /api/auth/logout — clear the JWT and CSRF cookies.
 pub async fn logout() -> Response {
-    let cookie = format!("{COOKIE_NAME}=; Path=/; HttpOnly; SameSite=Strict; Max-Age=0");
+    let cookie = format!("{COOKIE_NAME}=; {COOKIE_ATTRS}; HttpOnly; Max-Age=0");
+    let csrf = format!("{CSRF_COOKIE}=; {COOKIE_ATTRS}; Max-Age=0");
     let mut headers = axum::http::HeaderMap::new();
-    headers.insert(axum::http::header::SET_COOKIE, cookie.parse().unwrap());
+    headers.append(axum::http::header::SET_COOKIE, cookie.parse().unwrap());
+    headers.append(axum::http::header::SET_COOKIE, csrf.parse().unwrap());
     (headers, Json(serde_json::json!({ "ok": true }))).into_response()
 }
 
 /// GET /api/auth/check — report whether the current request carries a valid JWT.
-/// Also returns user identity if available (multi-user mode).
+/// Also returns user identity if available (multi-user mode), and re-issues
+/// the CSRF cookie for sessions that predate it.
 pub async fn check(
     State(state): State<DashState>,
     req: Request<Body>,
-) -> Json<serde_json::Value> {
-    match extract_claims(&req, &state.jwt_secret) {
-        Some(claims) => {
+) -> Response {
+    match session_from_headers(req.headers(), &state.jwt_secret) {
+        Some((token, claims)) => {
             let mut resp = serde_json::json!({
                 "required": true,
                 "authenticated": true,
@@ -304,10 +487,11 @@ pub async fn check(
             if let Some(ref role) = claims.role {
                 resp["role"] = serde_json::json!(role);
             }
-            Json(resp)
+            let csrf = csrf_cookie(&state.jwt_secret, token);
+            ([(axum::http::header::SET_COOKIE, csrf)], Json(resp)).into_response()
         }
         None => {
-            Json(serde_json::json!({ "required": true, "authenticated": false }))
+            Json(serde_json::json!({ "required": true, "authenticated": false })).into_response()
         }
     }
 }
@@ -330,7 +514,7 @@ pub async fn login_info(
     let password_enabled = state.config.dashboard.password_enabled
         && !state.dashboard_password.is_empty();
 
-    let sso_providers: Vec<SsoProviderInfo> = state
+    let mut sso_providers: Vec<SsoProviderInfo> = state
         .config
         .dashboard
         .sso_providers
@@ -348,6 +532,17 @@ pub async fn login_info(
         })
         .collect();
 
+    let oidc = &state.config.dashboard.oidc;
+    if oidc.enabled && !oidc.client_id.is_empty() {
+        let (name, icon) = super::oidc::login_button(oidc);
+        sso_providers.push(SsoProviderInfo {
+            id: "oidc".to_string(),
+            name,
+            icon,
+            login_url: "/api/auth/oidc/start".to_string(),
+        });
+    }
+
     // Check if there are any registered users (multi-user mode)
     let user_count = state.agent.user_manager.count().await;
 
@@ -474,15 +669,7 @@ pub async fn sso_callback(
     Path(provider_id): Path<String>,
     Query(params): Query<SsoCallbackParams>,
 ) -> Response {
-    let error_page = |msg: &str| -> Response {
-        axum::response::Html(format!(
-            r#"<!DOCTYPE html><html><head><title>SSO Error</title>
-            <style>body{{font-family:system-ui;background:#1a1a1a;color:#e0e0e0;display:flex;justify-content:center;align-items:center;height:100vh;margin:0}}
-            .card{{background:#2a2a2a;border-radius:12px;padding:2rem 3rem;text-align:center;box-shadow:0 4px 20px rgba(0,0,0,.5);max-width:400px}}
-            h2{{color:#ef4444}}a{{color:#ff9800;text-decoration:none}}</style></head>
-            <body><div class="card"><h2>SSO Login Failed</h2><p>{msg}</p><p><a href="/">Back to Dashboard</a></p></div></body></html>"#
-        )).into_response()
-    };
+    let error_page = login_error_page;
 
     // Check provider is allowed
     if !state.config.dashboard.sso_providers.iter().any(|p| p == &provider_id) {
@@ -605,12 +792,7 @@ pub async fn sso_callback(
     info!(provider = provider.id, email = %email, "SSO login successful");
 
     // Set cookie and redirect to dashboard
-    let cookie = format!(
-        "{COOKIE_NAME}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={TOKEN_EXPIRY_SECS}"
-    );
-
-    let mut headers = axum::http::HeaderMap::new();
-    headers.insert(axum::http::header::SET_COOKIE, cookie.parse().unwrap());
+    let mut headers = session_cookies(&state.jwt_secret, &token);
     headers.insert(
         axum::http::header::LOCATION,
         "/".parse().unwrap(),
@@ -619,6 +801,22 @@ pub async fn sso_callback(
     (StatusCode::FOUND, headers).into_response()
 }
 
+/// HTML page shown when an SSO or OIDC login fails.
+pub(super) fn login_error_page(msg: &str) -> Response {
+    let msg = msg
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+    axum::response::Html(format!(
+        r#"<!DOCTYPE html><html><head><title>SSO Error</title>
+        <style>body{{font-family:system-ui;background:#1a1a1a;color:#e0e0e0;display:flex;justify-content:center;align-items:center;height:100vh;margin:0}}
+        .card{{background:#2a2a2a;border-radius:12px;padding:2rem 3rem;text-align:center;box-shadow:0 4px 20px rgba(0,0,0,.5);max-width:400px}}
+        h2{{color:#ef4444}}a{{color:#ff9800;text-decoration:none}}</style></head>
+        <body><div class="card"><h2>SSO Login Failed</h2><p>{msg}</p><p><a href="/">Back to Dashboard</a></p></div></body></html>"#
+    )).into_response()
+}
+
 /// Fetch the user's email from the SSO provider's userinfo endpoint.
 async fn fetch_sso_email(provider: &oauth::OAuthProvider, access_token: &str) -> Option<String> {
     if provider.userinfo_url.is_empty() {
@@ -784,11 +982,7 @@ pub async fn verify_2fa(
         }
     };
 
-    let cookie = format!(
-        "{COOKIE_NAME}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={TOKEN_EXPIRY_SECS}"
-    );
-    let mut headers = axum::http::HeaderMap::new();
-    headers.insert(axum::http::header::SET_COOKIE, cookie.parse().unwrap());
+    let headers = session_cookies(&state.jwt_secret, &token);
 
     info!(username = %user.username, "2FA verification successful");
     (headers, Json(serde_json::json!({
@@ -861,7 +1055,7 @@ pub async fn setup_totp(
         }
         Err(e) => {
             error!(user_id, err = %e, "failed to setup TOTP");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "ok": false, "error": e.to_string() }))).into_response()
+            e.into_response()
         }
     }
 }
@@ -926,7 +1120,38 @@ pub async fn disable_totp(
 
     match state.agent.user_manager.disable_totp(&user_id).await {
         Ok(()) => Json(serde_json::json!({ "ok": true })).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "ok": false, "error": e.to_string() }))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// POST /api/auth/api-key — issue (or rotate) the caller's API key.  The
+/// key is only ever shown in this response.
+pub async fn issue_api_key(
+    State(state): State<DashState>,
+    req: Request<Body>,
+) -> Response {
+    let Some(user_id) = session_user_id(&req, &state.jwt_secret) else {
+        return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({ "error": "not authenticated" }))).into_response();
+    };
+
+    match state.agent.user_manager.issue_api_key(&user_id).await {
+        Ok(key) => Json(serde_json::json!({ "ok": true, "api_key": key })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// DELETE /api/auth/api-key — revoke the caller's API key.
+pub async fn revoke_api_key(
+    State(state): State<DashState>,
+    req: Request<Body>,
+) -> Response {
+    let Some(user_id) = session_user_id(&req, &state.jwt_secret) else {
+        return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({ "error": "not authenticated" }))).into_response();
+    };
+
+    match state.agent.user_manager.revoke_api_key(&user_id).await {
+        Ok(()) => Json(serde_json::json!({ "ok": true })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -959,7 +1184,7 @@ pub async fn passkey_register_start(
         })).into_response(),
         Err(e) => {
             error!(user_id, err = %e, "passkey registration start failed");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "ok": false, "error": e.to_string() }))).into_response()
+            e.into_response()
         }
     }
 }
@@ -1026,7 +1251,7 @@ pub async fn passkey_auth_start(
         })).into_response(),
         Err(e) => {
             error!(user_id = %user_id, err = %e, "passkey auth start failed");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "ok": false, "error": e.to_string() }))).into_response()
+            e.into_response()
         }
     }
 }
@@ -1078,11 +1303,7 @@ pub async fn passkey_auth_finish(
         }
     };
 
-    let cookie = format!(
-        "{COOKIE_NAME}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={TOKEN_EXPIRY_SECS}"
-    );
-    let mut headers = axum::http::HeaderMap::new();
-    headers.insert(axum::http::header::SET_COOKIE, cookie.parse().unwrap());
+    let headers = session_cookies(&state.jwt_secret, &token);
 
     info!(username = %user.username, "passkey authentication successful");
     (headers, Json(serde_json::json!({
@@ -1149,38 +1370,38 @@ mod tests {
             .header(axum::http::header::COOKIE, format!("{COOKIE_NAME}={token}"))
             .body(Body::empty())
             .unwrap();
-        assert!(validate_token(&req, secret));
+        assert!(extract_claims(&req, secret).is_some());
     }
 
     #[test]
-    fn validate_token_no_cookie() {
+    fn extract_claims_no_cookie() {
         let secret = b"secret";
         let req = Request::builder().body(Body::empty()).unwrap();
-        assert!(!validate_token(&req, secret));
+        assert!(!extract_claims(&req, secret).is_some());
     }
 
     #[test]
-    fn validate_token_wrong_secret() {
+    fn extract_claims_wrong_secret() {
         let secret = b"correct-secret";
         let token = mint_token(secret, "sub", "method").unwrap();
         let req = Request::builder()
             .header(axum::http::header::COOKIE, format!("{COOKIE_NAME}={token}"))
             .body(Body::empty())
             .unwrap();
-        assert!(!validate_token(&req, b"wrong-secret"));
+        assert!(!extract_claims(&req, b"wrong-secret").is_some());
     }
 
     #[test]
-    fn validate_token_malformed() {
+    fn extract_claims_malformed() {
         let req = Request::builder()
             .header(axum::http::header::COOKIE, format!("{COOKIE_NAME}=not-a-jwt"))
             .body(Body::empty())
             .unwrap();
-        assert!(!validate_token(&req, b"secret"));
+        assert!(!extract_claims(&req, b"secret").is_some());
     }
 
     #[test]
-    fn validate_token_among_other_cookies() {
+    fn extract_claims_among_other_cookies() {
         let secret = b"sec";
         let token = mint_token(secret, "test", "pw").unwrap();
         let cookie = format!("other=value; {COOKIE_NAME}={token}; foo=bar");
@@ -1188,7 +1409,7 @@ mod tests {
             .header(axum::http::header::COOKIE, cookie)
             .body(Body::empty())
             .unwrap();
-        assert!(validate_token(&req, secret));
+        assert!(extract_claims(&req, secret).is_some());
     }
 
     #[test]
@@ -1266,4 +1487,153 @@ mod tests {
         assert_eq!(url, "http://localhost:9999/api/auth/sso/github/callback");
         unsafe { std::env::remove_var("DASHBOARD_BIND"); }
     }
+
+    /// Attaches the session user the way `require_auth` does, for a router
+    /// without a full `DashState`.
+    async fn attach_session(
+        State(users): State<std::sync::Arc<UserManager>>,
+        mut req: Request<Body>,
+        next: Next,
+    ) -> Response {
+        if let Some(user) = session_user(req.headers(), b"guard-secret", &users).await {
+            req.extensions_mut().insert(user);
+        }
+        next.run(req).await
+    }
+
+    #[tokio::test]
+    async fn role_guard_blocks_viewer_and_allows_admin() {
+        use axum::routing::{get, post};
+
+        let dir = tempfile::tempdir().unwrap();
+        let enc = crate::crypto::FieldEncryptor::ensure_key(dir.path()).unwrap();
+        let users = std::sync::Arc::new(UserManager::new(crate::db::test_db(), enc));
+        let viewer = users.create("vera", "Vera", UserRole::Viewer, "pw").await.unwrap();
+        let admin = users.create("ada", "Ada", UserRole::Admin, "pw").await.unwrap();
+        let token_for = |u: &crate::users::User| {
+            mint_token_with_user(b"guard-secret", &u.username, "password", Some(&u.id), Some(u.role.as_str())).unwrap()
+        };
+        let (viewer_token, admin_token) = (token_for(&viewer), token_for(&admin));
+
+        let app = axum::Router::new()
+            .route("/api/status", get(|| async { "ok" }))
+            .route("/api/kill", post(|| async { "halted" }).require_role(UserRole::Admin))
+            .layer(middleware::from_fn_with_state(users.clone(), attach_session));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let status = |method: reqwest::Method, path: &str, token: &str| {
+            client
+                .request(method, format!("{base}{path}"))
+                .header("Cookie", format!("{COOKIE_NAME}={token}"))
+                .send()
+        };
+
+        // Viewers can read but not reach admin routes.
+        let resp = status(reqwest::Method::GET, "/api/status", &viewer_token).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = status(reqwest::Method::POST, "/api/kill", &viewer_token).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = status(reqwest::Method::POST, "/api/kill", "not-a-token").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = status(reqwest::Method::POST, "/api/kill", &admin_token).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.text().await.unwrap(), "halted");
+
+        // Role changes apply to existing sessions.
+        users.update(&admin.id, None, Some(UserRole::Viewer), None, None).await.unwrap();
+        let resp = status(reqwest::Method::POST, "/api/kill", &admin_token).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Applies the CSRF check the way `require_auth` does.
+    async fn csrf_guard(req: Request<Body>, next: Next) -> Response {
+        let mutating = !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+        if mutating && !csrf_ok(req.headers(), b"csrf-secret") {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+        next.run(req).await
+    }
+
+    #[tokio::test]
+    async fn logout_clears_cookies_with_the_attributes_login_set() {
+        // Everything but the value and Max-Age must match, or browsers keep the cookie.
+        let attrs = |cookie: &str| {
+            cookie
+                .split("; ")
+                .skip(1)
+                .filter(|attr| !attr.starts_with("Max-Age="))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let set = session_cookies(b"secret", "token");
+        let cleared = logout().await;
+        let cleared: Vec<_> = cleared.headers().get_all(axum::http::header::SET_COOKIE).iter().collect();
+        let set: Vec<_> = set.get_all(axum::http::header::SET_COOKIE).iter().collect();
+        assert_eq!(set.len(), cleared.len());
+        for (set, cleared) in set.iter().zip(&cleared) {
+            let (set, cleared) = (set.to_str().unwrap(), cleared.to_str().unwrap());
+            assert_eq!(set.split('=').next(), cleared.split('=').next());
+            assert_eq!(attrs(set), attrs(cleared), "{set} vs {cleared}");
+            assert!(cleared.ends_with("Max-Age=0"));
+        }
+    }
+
+    #[tokio::test]
+    async fn mutating_requests_need_the_session_csrf_token() {
+        use axum::routing::{get, post};
+
+        let secret = b"csrf-secret";
+        let token = mint_token(secret, "dashboard", "password").unwrap();
+        let other = mint_token_with_user(secret, "eve", "password", Some("u9"), Some("admin")).unwrap();
+
+        // The login response hands out both cookies.
+        let cookies = session_cookies(secret, &token);
+        let issued: Vec<_> = cookies.get_all(axum::http::header::SET_COOKIE).iter().collect();
+        assert_eq!(issued.len(), 2);
+        let csrf = csrf_token(secret, &token);
+        assert!(issued[1].to_str().unwrap().starts_with(&format!("{CSRF_COOKIE}={csrf};")));
+
+        let app = axum::Router::new()
+            .route("/api/status", get(|| async { "ok" }))
+            .route("/api/agent/pause", post(|| async { "paused" }))
+            .layer(middleware::from_fn(csrf_guard));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let send = |method: reqwest::Method, path: &str, csrf: Option<&str>| {
+            let mut req = client
+                .request(method, format!("{base}{path}"))
+                .header("Cookie", format!("{COOKIE_NAME}={token}"));
+            if let Some(csrf) = csrf {
+                req = req.header(CSRF_HEADER, csrf);
+            }
+            req.send()
+        };
+
+        let resp = send(reqwest::Method::GET, "/api/status", None).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = send(reqwest::Method::POST, "/api/agent/pause", None).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = send(reqwest::Method::POST, "/api/agent/pause", Some("deadbeef")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        // A token minted for another session doesn't carry over.
+        let foreign = csrf_token(secret, &other);
+        let resp = send(reqwest::Method::POST, "/api/agent/pause", Some(&foreign)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = send(reqwest::Method::POST, "/api/agent/pause", Some(&csrf)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.text().await.unwrap(), "paused");
+
+        // Without a session cookie there is nothing to forge.
+        let resp = client.post(format!("{base}/api/agent/pause")).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
 }
@@ -57,6 +57,15 @@ pub fn totp_uri(secret_base32: &str, username: &str, issuer: &str) -> String {
 /// Verify a TOTP code against a base32-encoded secret.
 /// Allows ±1 time step of tolerance (current, previous, next).
 pub fn verify_totp(secret_base32: &str, code: &str) -> bool {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    verify_totp_at(secret_base32, code, now)
+}
+
+/// [`verify_totp`] at a given Unix time.
+pub fn verify_totp_at(secret_base32: &str, code: &str, now: u64) -> bool {
     let Ok(secret) = data_encoding::BASE32_NOPAD.decode(secret_base32.as_bytes()) else {
         return false;
     };
@@ -66,11 +75,6 @@ pub fn verify_totp(secret_base32: &str, code: &str) -> bool {
         return false;
     }
 
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
     let current_step = now / TOTP_STEP;
 
     // Check current step and ±1 for clock drift tolerance
@@ -368,7 +372,7 @@ impl PasskeyManager {
             rusqlite::params![passkey_id, user_id],
         )?;
         if deleted == 0 {
-            return Err(SafeAgentError::Config("passkey not found".into()));
+            return Err(SafeAgentError::NotFound("passkey".into()));
         }
         info!(user_id, passkey_id, "passkey deleted");
         Ok(())
@@ -463,6 +467,14 @@ impl UserManager {
         .unwrap_or(0) == 1
     }
 
+    /// The user's TOTP secret (decrypted), if TOTP is enabled for them.
+    pub async fn enabled_totp_secret(&self, user_id: &str) -> Option<String> {
+        if !self.is_totp_enabled(user_id).await {
+            return None;
+        }
+        self.get_totp_secret(user_id).await
+    }
+
     /// Get the TOTP secret for a user (decrypted).
     async fn get_totp_secret(&self, user_id: &str) -> Option<String> {
         let db = self.db.lock().await;
@@ -552,6 +564,15 @@ mod tests {
         assert!(!verify_totp(&secret, "000000"));
     }
 
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        // RFC 6238 appendix B SHA1 seed, truncated to six digits.
+        let secret = data_encoding::BASE32_NOPAD.encode(b"12345678901234567890");
+        assert!(verify_totp_at(&secret, "287082", 59));
+        assert!(verify_totp_at(&secret, "081804", 1_111_111_109));
+        assert!(!verify_totp_at(&secret, "287082", 1_111_111_109));
+    }
+
     #[test]
     fn test_totp_uri() {
         let uri = totp_uri("JBSWY3DPEHPK3PXP", "alice", "safeclaw");
@@ -0,0 +1,40 @@
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
+import { csrfToken, installCsrfFetch } from '../csrf';
+
+describe('csrf', () => {
+    let inner: ReturnType<typeof vi.fn>;
+
+    beforeEach(() => {
+        inner = vi.fn(() => Promise.resolve(new Response()));
+        vi.stubGlobal('fetch', inner);
+        document.cookie = 'sa_csrf=abc123; path=/';
+        installCsrfFetch();
+    });
+
+    afterEach(() => {
+        vi.unstubAllGlobals();
+        document.cookie = 'sa_csrf=; path=/; max-age=0';
+    });
+
+    const sentHeaders = () => new Headers((inner.mock.calls[0][1] as RequestInit | undefined)?.headers);
+
+    it('reads the token from the cookie', () => {
+        expect(csrfToken()).toBe('abc123');
+    });
+
+    it('adds the header to mutating requests', async () => {
+        await fetch('/api/agent/pause', { method: 'POST', headers: { 'Content-Type': 'application/json' } });
+        expect(sentHeaders().get('X-CSRF-Token')).toBe('abc123');
+        expect(sentHeaders().get('Content-Type')).toBe('application/json');
+    });
+
+    it('leaves GET requests alone', async () => {
+        await fetch('/api/status');
+        expect(sentHeaders().has('X-CSRF-Token')).toBe(false);
+    });
+
+    it('never sends the token to other origins', async () => {
+        await fetch('https://example.com/hook', { method: 'POST' });
+        expect(sentHeaders().has('X-CSRF-Token')).toBe(false);
+    });
+});
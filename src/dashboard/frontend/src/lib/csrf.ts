@@ -0,0 +1,33 @@
+export const CSRF_COOKIE = 'sa_csrf';
+export const CSRF_HEADER = 'X-CSRF-Token';
+
+const SAFE_METHODS = new Set(['GET', 'HEAD', 'OPTIONS']);
+
+/** The CSRF token the server issued alongside the session cookie. */
+export function csrfToken(): string | null {
+    for (const pair of document.cookie.split(';')) {
+        const [name, ...rest] = pair.trim().split('=');
+        if (name === CSRF_COOKIE) return decodeURIComponent(rest.join('='));
+    }
+    return null;
+}
+
+/**
+ * Wrap `window.fetch` so every same-origin, state-changing request carries
+ * the CSRF token header.  Components call `fetch` directly, so this is
+ * installed once at startup instead of in `api()`.
+ */
+export function installCsrfFetch(): void {
+    const original = window.fetch.bind(window);
+    window.fetch = (input: RequestInfo | URL, init?: RequestInit) => {
+        const method = (init?.method ?? (input instanceof Request ? input.method : 'GET')).toUpperCase();
+        const url = new URL(input instanceof Request ? input.url : String(input), window.location.href);
+        const token = csrfToken();
+        if (SAFE_METHODS.has(method) || url.origin !== window.location.origin || !token) {
+            return original(input, init);
+        }
+        const headers = new Headers(init?.headers ?? (input instanceof Request ? input.headers : undefined));
+        headers.set(CSRF_HEADER, token);
+        return original(input, { ...init, headers });
+    };
+}
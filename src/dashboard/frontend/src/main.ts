@@ -1,5 +1,7 @@
 import { mount } from 'svelte';
 import App from './App.svelte';
+import { installCsrfFetch } from './lib/csrf';
 import './app.css';
 
+installCsrfFetch();
 mount(App, { target: document.getElementById('app')! });
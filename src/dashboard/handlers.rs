@@ -1,4 +1,4 @@
-use axum::extract::{Path, Query, State};
+use axum::extract::{Extension, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use serde::{Deserialize, Serialize};
@@ -11,6 +11,7 @@ use crate::memory::knowledge::KnowledgeGraph;
 pub struct StatusResponse {
     pub running: bool,
     pub paused: bool,
+    pub safe_mode: bool,
     pub agent_name: String,
     pub dashboard_bind: String,
     pub tick_interval_secs: u64,
@@ -30,6 +31,27 @@ pub struct ActionResponse {
 pub struct PaginationQuery {
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    /// Keyset cursor for endpoints that support it (activity, audit).
+    pub before_id: Option<i64>,
+}
+
+/// Response header carrying the `before_id` cursor for the next page.
+/// Only set when the page was full, i.e. more rows may follow.
+const NEXT_CURSOR_HEADER: &str = "x-next-before-id";
+
+/// JSON response with the next-page cursor header for keyset-paginated
+/// lists.  The body stays a plain array so offset-based clients keep
+/// working.
+fn paged_response<T: Serialize>(
+    entries: &[T],
+    limit: usize,
+    last_id: Option<i64>,
+) -> axum::response::Response {
+    let mut resp = Json(serde_json::to_value(entries).unwrap()).into_response();
+    if let Some(id) = last_id.filter(|_| entries.len() == limit) {
+        resp.headers_mut().insert(NEXT_CURSOR_HEADER, id.into());
+    }
+    resp
 }
 
 #[derive(Deserialize)]
@@ -43,9 +65,10 @@ pub async fn get_status(State(state): State<DashState>) -> Json<StatusResponse>
     Json(StatusResponse {
         running: true,
         paused: state.agent.is_paused(),
+        safe_mode: state.agent.is_safe_mode(),
         agent_name: state.agent.config.agent_name.clone(),
         dashboard_bind: state.agent.config.dashboard_bind.clone(),
-        tick_interval_secs: state.agent.config.tick_interval_secs,
+        tick_interval_secs: state.agent.tick_interval_secs(),
         tools_count: state.agent.tools.len(),
     })
 }
@@ -61,7 +84,7 @@ pub async fn get_stats(
         .map(|stats| Json(serde_json::to_value(stats).unwrap()))
         .map_err(|e| {
             error!("stats: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
@@ -75,6 +98,32 @@ pub async fn pause_agent(State(state): State<DashState>) -> Json<ActionResponse>
     })
 }
 
+/// POST /api/kill — emergency stop (admin only): halt tool execution, stop
+/// all skills and reject pending approvals until the agent is resumed.
+pub async fn kill_switch(State(state): State<DashState>) -> impl IntoResponse {
+    match state.agent.kill_switch("dashboard").await {
+        Ok(rejected) => (
+            StatusCode::OK,
+            Json(ActionResponse {
+                ok: true,
+                message: Some("kill switch engaged".into()),
+                count: Some(rejected),
+            }),
+        ),
+        Err(e) => {
+            error!("kill switch: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ActionResponse {
+                    ok: false,
+                    message: Some(e.to_string()),
+                    count: None,
+                }),
+            )
+        }
+    }
+}
+
 pub async fn resume_agent(State(state): State<DashState>) -> Json<ActionResponse> {
     state.agent.resume();
     state.agent.notify_update();
@@ -85,6 +134,31 @@ pub async fn resume_agent(State(state): State<DashState>) -> Json<ActionResponse
     })
 }
 
+#[derive(Deserialize)]
+pub struct SafeModeRequest {
+    pub enabled: bool,
+}
+
+/// GET /api/safe-mode — whether side-effecting tools are held for approval.
+pub async fn get_safe_mode(State(state): State<DashState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "enabled": state.agent.is_safe_mode() }))
+}
+
+/// POST /api/safe-mode — turn safe mode on or off at runtime.
+pub async fn set_safe_mode(
+    State(state): State<DashState>,
+    Json(body): Json<SafeModeRequest>,
+) -> Json<ActionResponse> {
+    state.agent.set_safe_mode(body.enabled);
+    state.agent.notify_update();
+    let message = if body.enabled { "safe mode enabled" } else { "safe mode disabled" };
+    Json(ActionResponse {
+        ok: true,
+        message: Some(message.into()),
+        count: None,
+    })
+}
+
 pub async fn force_tick(
     State(state): State<DashState>,
 ) -> Result<Json<ActionResponse>, StatusCode> {
@@ -102,7 +176,7 @@ pub async fn force_tick(
         })
         .map_err(|e| {
             error!("force tick: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
@@ -119,7 +193,7 @@ pub async fn get_pending(
         .map(|actions| Json(serde_json::to_value(actions).unwrap()))
         .map_err(|e| {
             error!("list pending: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
@@ -187,7 +261,7 @@ pub async fn approve_all(
         })
         .map_err(|e| {
             error!("approve_all: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
@@ -209,27 +283,29 @@ pub async fn reject_all(
         })
         .map_err(|e| {
             error!("reject_all: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
 // -- Activity ------------------------------------------------------------
 
+/// GET /api/activity — newest first.  Page with `before_id` (preferred;
+/// the next value is in the `x-next-before-id` header) or `offset`.
 pub async fn get_activity(
     State(state): State<DashState>,
     Query(params): Query<PaginationQuery>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<axum::response::Response, StatusCode> {
     let limit = params.limit.unwrap_or(50);
     let offset = params.offset.unwrap_or(0);
     state
         .agent
         .memory
-        .recent_activity(limit, offset)
+        .recent_activity(limit, offset, params.before_id)
         .await
-        .map(|entries| Json(serde_json::to_value(entries).unwrap()))
+        .map(|entries| paged_response(&entries, limit, entries.last().map(|e| e.id)))
         .map_err(|e| {
             error!("activity: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
@@ -247,7 +323,7 @@ pub async fn get_core_memory(
         .map(|personality| Json(serde_json::json!({ "personality": personality })))
         .map_err(|e| {
             error!("core memory: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
@@ -263,10 +339,120 @@ pub async fn get_conversation_memory(
         .map(|messages| Json(serde_json::to_value(messages).unwrap()))
         .map_err(|e| {
             error!("conversation memory: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
+/// Re-read the config file and apply the settings that can change without
+/// a restart.
+pub async fn reload_config(State(state): State<DashState>) -> Json<serde_json::Value> {
+    match state.agent.reload_config().await {
+        Ok(reload) => {
+            state.agent.notify_update();
+            Json(serde_json::json!({
+                "ok": true,
+                "applied": reload.applied,
+                "restart_required": reload.restart_required,
+                "reloadable": crate::config::HOT_RELOADABLE,
+            }))
+        }
+        Err(e) => {
+            error!("config reload: {e}");
+            Json(serde_json::json!({ "ok": false, "error": e.to_string() }))
+        }
+    }
+}
+
+/// Status for a failed preset operation: bad settings are the caller's
+/// fault.
+fn preset_error(e: crate::error::SafeAgentError) -> StatusCode {
+    error!("config preset: {e}");
+    match e {
+        crate::error::SafeAgentError::Config(_) => StatusCode::BAD_REQUEST,
+        e => e.status_code(),
+    }
+}
+
+pub async fn list_config_presets(
+    State(state): State<DashState>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let presets = state.agent.presets.list().await.map_err(preset_error)?;
+    Ok(Json(serde_json::json!({ "presets": presets })))
+}
+
+/// Create or replace a preset.
+pub async fn save_config_preset(
+    State(state): State<DashState>,
+    Path(name): Path<String>,
+    Json(settings): Json<crate::agent::presets::PresetSettings>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    state.agent.presets.save(&name, &settings).await.map_err(preset_error)?;
+    Ok(Json(ActionResponse {
+        ok: true,
+        message: Some(format!("preset '{name}' saved")),
+        count: None,
+    }))
+}
+
+pub async fn delete_config_preset(
+    State(state): State<DashState>,
+    Path(name): Path<String>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    if !state.agent.presets.delete(&name).await.map_err(preset_error)? {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(ActionResponse {
+        ok: true,
+        message: Some(format!("preset '{name}' deleted")),
+        count: None,
+    }))
+}
+
+/// Apply a preset's settings to the running agent.
+pub async fn apply_config_preset(
+    State(state): State<DashState>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let settings = state.agent.apply_preset(&name).await.map_err(preset_error)?;
+    state.agent.notify_update();
+    Ok(Json(serde_json::json!({ "ok": true, "preset": name, "settings": settings })))
+}
+
+pub async fn get_consolidation_status(State(state): State<DashState>) -> Json<serde_json::Value> {
+    let scheduler = &state.agent.consolidation;
+    Json(serde_json::json!({
+        "interval_secs": scheduler.interval().as_secs(),
+        "last_run": scheduler.last_run(),
+        "running": scheduler.is_running(),
+    }))
+}
+
+pub async fn consolidate_memory(
+    State(state): State<DashState>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    use crate::memory::consolidation::ConsolidationOutcome;
+
+    match state.agent.consolidate_memories(true).await {
+        Ok(ConsolidationOutcome::Ran { consolidated }) => {
+            state.agent.notify_update();
+            Ok(Json(ActionResponse {
+                ok: true,
+                message: Some("memory consolidation completed".into()),
+                count: Some(consolidated as u64),
+            }))
+        }
+        Ok(_) => Ok(Json(ActionResponse {
+            ok: false,
+            message: Some("memory consolidation already running".into()),
+            count: None,
+        })),
+        Err(e) => {
+            error!("memory consolidation: {e}");
+            Err(e.status_code())
+        }
+    }
+}
+
 pub async fn search_archival_memory(
     State(state): State<DashState>,
     Query(params): Query<SearchQuery>,
@@ -282,7 +468,7 @@ pub async fn search_archival_memory(
             .map(|entries| Json(serde_json::to_value(entries).unwrap()))
             .map_err(|e| {
                 error!("archival list: {e}");
-                StatusCode::INTERNAL_SERVER_ERROR
+                e.status_code()
             });
     }
     state
@@ -294,7 +480,7 @@ pub async fn search_archival_memory(
         .map(|entries| Json(serde_json::to_value(entries).unwrap()))
         .map_err(|e| {
             error!("archival search: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })
 }
 
@@ -312,12 +498,186 @@ fn default_history_limit() -> usize {
     50
 }
 
+#[derive(Deserialize)]
+pub struct EpisodeQuery {
+    /// Inclusive lower bound (RFC 3339 or `YYYY-MM-DD HH:MM:SS`, UTC).
+    pub from: Option<String>,
+    /// Exclusive upper bound, same formats as `from`.
+    pub to: Option<String>,
+    pub tag: Option<String>,
+    #[serde(default = "default_history_limit")]
+    pub limit: usize,
+}
+
+/// Parse a dashboard timestamp as RFC 3339 or SQLite's datetime format.
+fn parse_query_time(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").map(|ndt| ndt.and_utc()))
+        .ok()
+}
+
+/// Episodes in a time window, optionally filtered by tag, oldest first.
+pub async fn query_episodes(
+    State(state): State<DashState>,
+    Query(params): Query<EpisodeQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let bound = |s: &Option<String>| match s.as_deref().filter(|s| !s.is_empty()) {
+        None => Ok(None),
+        Some(s) => parse_query_time(s).map(Some).ok_or(StatusCode::BAD_REQUEST),
+    };
+    let from = bound(&params.from)?;
+    let to = bound(&params.to)?;
+    let tag = params.tag.as_deref().filter(|t| !t.is_empty());
+
+    state
+        .agent
+        .memory
+        .episodic
+        .query(from, to, tag, params.limit.min(500))
+        .await
+        .map(|episodes| Json(serde_json::to_value(episodes).unwrap()))
+        .map_err(|e| {
+            error!("episode query: {e}");
+            e.status_code()
+        })
+}
+
+#[derive(Deserialize)]
+pub struct ConversationExportQuery {
+    /// Branch to export; defaults to the chat's active one.
+    pub branch: Option<String>,
+    /// Chat to export, e.g. `telegram:12345`; the default conversation
+    /// when absent.
+    pub channel: Option<String>,
+    /// Inclusive lower bound, same formats as [`EpisodeQuery::from`].
+    pub from: Option<String>,
+    /// Exclusive upper bound.
+    pub to: Option<String>,
+}
+
+/// Download one chat's history on a branch as a Markdown transcript.
+pub async fn export_conversation(
+    State(state): State<DashState>,
+    Query(params): Query<ConversationExportQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let bound = |s: &Option<String>| match s.as_deref().filter(|s| !s.is_empty()) {
+        None => Ok(None),
+        Some(s) => parse_query_time(s).map(Some).ok_or(StatusCode::BAD_REQUEST),
+    };
+    let from = bound(&params.from)?;
+    let to = bound(&params.to)?;
+    let branch = params.branch.as_deref().filter(|b| !b.is_empty());
+    let channel = params.channel.as_deref().filter(|c| !c.is_empty());
+
+    let md = state
+        .agent
+        .memory
+        .conversation
+        .export_markdown(branch, channel, from, to)
+        .await
+        .map_err(|e| {
+            error!("conversation export: {e}");
+            e.status_code()
+        })?;
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "text/markdown; charset=utf-8".parse().unwrap(),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        format!(
+            "attachment; filename=\"safeclaw-conversation-{}.md\"",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        )
+        .parse()
+        .unwrap(),
+    );
+    Ok((headers, md))
+}
+
+/// Status for a failed branch operation: bad names are the caller's fault.
+fn branch_error(e: crate::error::SafeAgentError) -> StatusCode {
+    error!("conversation branch: {e}");
+    match e {
+        crate::error::SafeAgentError::Config(_) => StatusCode::BAD_REQUEST,
+        e => e.status_code(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BranchChatQuery {
+    /// Chat whose branches to act on, e.g. `telegram:12345`; the default
+    /// conversation when absent.
+    pub channel: Option<String>,
+}
+
+/// Conversation branches as seen from one chat.
+pub async fn list_conversation_branches(
+    State(state): State<DashState>,
+    Query(params): Query<BranchChatQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let channel = params.channel.as_deref().filter(|c| !c.is_empty());
+    let branches = state
+        .agent
+        .memory
+        .conversation
+        .list_branches(channel)
+        .await
+        .map_err(branch_error)?;
+    Ok(Json(serde_json::json!({ "branches": branches })))
+}
+
+#[derive(Deserialize)]
+pub struct BranchRequest {
+    pub name: String,
+    /// See [`BranchChatQuery::channel`].
+    pub channel: Option<String>,
+}
+
+/// Fork a chat's active branch and switch the chat to the fork.
+pub async fn fork_conversation(
+    State(state): State<DashState>,
+    Json(body): Json<BranchRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let channel = body.channel.as_deref().filter(|c| !c.is_empty());
+    let branch = state
+        .agent
+        .memory
+        .conversation
+        .fork_conversation(&body.name, channel)
+        .await
+        .map_err(branch_error)?;
+    state.agent.notify_update();
+    Ok(Json(serde_json::json!({ "ok": true, "branch": branch })))
+}
+
+/// Switch a chat to an existing branch.
+pub async fn switch_conversation_branch(
+    State(state): State<DashState>,
+    Json(body): Json<BranchRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let channel = body.channel.as_deref().filter(|c| !c.is_empty());
+    let branch = state
+        .agent
+        .memory
+        .conversation
+        .switch_branch(&body.name, channel)
+        .await
+        .map_err(branch_error)?;
+    state.agent.notify_update();
+    Ok(Json(serde_json::json!({ "ok": true, "branch": branch })))
+}
+
 /// Search and paginate ALL conversation history (not just the window).
 pub async fn conversation_history(
     State(state): State<DashState>,
     Query(params): Query<ConversationHistoryQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let db = state.db.lock().await;
+    let conn = state.read_db.get();
+    let db = conn.lock().await;
 
     let result = if params.q.is_empty() {
         let mut stmt = db
@@ -398,10 +758,12 @@ pub async fn conversation_history(
 
 // -- Persona ---------------------------------------------------------------
 
-/// Get the agent's core personality.
+/// Get the agent's core personality and the name of the active persona.
 pub async fn get_persona(State(state): State<DashState>) -> Json<serde_json::Value> {
-    let personality = state.agent.memory.core.get().await.unwrap_or_default();
-    Json(serde_json::json!({ "personality": personality }))
+    let core = &state.agent.memory.core;
+    let personality = core.get().await.unwrap_or_default();
+    let active = core.active_persona().await.ok().flatten().map(|p| p.name);
+    Json(serde_json::json!({ "personality": personality, "active_persona": active }))
 }
 
 #[derive(Deserialize)]
@@ -424,6 +786,78 @@ pub async fn update_persona(
     }
 }
 
+fn persona_error(e: crate::error::SafeAgentError) -> StatusCode {
+    error!("persona: {e}");
+    match e {
+        crate::error::SafeAgentError::Config(_) => StatusCode::BAD_REQUEST,
+        e => e.status_code(),
+    }
+}
+
+/// All stored personas and which one is active.
+pub async fn list_personas(State(state): State<DashState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    let core = &state.agent.memory.core;
+    let personas = core.list_personas().await.map_err(persona_error)?;
+    let active = core.active_persona().await.map_err(persona_error)?.map(|p| p.name);
+    Ok(Json(serde_json::json!({ "personas": personas, "active": active })))
+}
+
+#[derive(Deserialize)]
+pub struct PersonaPrompt {
+    pub prompt: String,
+}
+
+/// Create or replace a persona.  Edits to the active one apply immediately.
+pub async fn save_persona(
+    State(state): State<DashState>,
+    Path(name): Path<String>,
+    Json(body): Json<PersonaPrompt>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    state.agent.memory.core.save_persona(&name, &body.prompt).await.map_err(persona_error)?;
+    state.agent.reload_persona().await.map_err(persona_error)?;
+    Ok(Json(ActionResponse {
+        ok: true,
+        message: Some(format!("persona '{name}' saved")),
+        count: None,
+    }))
+}
+
+/// Delete a persona; deleting the active one deactivates it.
+pub async fn delete_persona(
+    State(state): State<DashState>,
+    Path(name): Path<String>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    if !state.agent.memory.core.delete_persona(&name).await.map_err(persona_error)? {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    state.agent.reload_persona().await.map_err(persona_error)?;
+    Ok(Json(ActionResponse {
+        ok: true,
+        message: Some(format!("persona '{name}' deleted")),
+        count: None,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ActivePersona {
+    /// `null` goes back to the plain core personality.
+    pub name: Option<String>,
+}
+
+/// Switch the active persona.
+pub async fn set_active_persona(
+    State(state): State<DashState>,
+    Json(body): Json<ActivePersona>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let persona = state
+        .agent
+        .set_active_persona(body.name.as_deref())
+        .await
+        .map_err(persona_error)?;
+    state.agent.notify_update();
+    Ok(Json(serde_json::json!({ "ok": true, "active": persona })))
+}
+
 // -- Knowledge Graph -----------------------------------------------------
 
 pub async fn get_knowledge_nodes(
@@ -469,7 +903,7 @@ pub async fn get_knowledge_node(
     State(state): State<DashState>,
     Path(id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let kg = KnowledgeGraph::new(state.db.clone());
+    let kg = KnowledgeGraph::new(state.read_db.get());
     let node = kg.get_node(id).await.map_err(|e| {
         error!("knowledge node {id}: {e}");
         StatusCode::NOT_FOUND
@@ -481,10 +915,10 @@ pub async fn get_knowledge_neighbors(
     State(state): State<DashState>,
     Path(id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let kg = KnowledgeGraph::new(state.db.clone());
+    let kg = KnowledgeGraph::new(state.read_db.get());
     let neighbors = kg.neighbors(id, None).await.map_err(|e| {
         error!("knowledge neighbors: {e}");
-        StatusCode::INTERNAL_SERVER_ERROR
+        e.status_code()
     })?;
     let result: Vec<serde_json::Value> = neighbors
         .iter()
@@ -517,10 +951,10 @@ pub async fn search_knowledge(
     if query.is_empty() {
         return Err(StatusCode::BAD_REQUEST);
     }
-    let kg = KnowledgeGraph::new(state.db.clone());
+    let kg = KnowledgeGraph::new(state.read_db.get());
     let nodes = kg.search(&query, 50).await.map_err(|e| {
         error!("knowledge search: {e}");
-        StatusCode::INTERNAL_SERVER_ERROR
+        e.status_code()
     })?;
     Ok(Json(serde_json::to_value(nodes).unwrap()))
 }
@@ -528,10 +962,10 @@ pub async fn search_knowledge(
 pub async fn get_knowledge_stats(
     State(state): State<DashState>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let kg = KnowledgeGraph::new(state.db.clone());
+    let kg = KnowledgeGraph::new(state.read_db.get());
     let (nodes, edges) = kg.stats().await.map_err(|e| {
         error!("knowledge stats: {e}");
-        StatusCode::INTERNAL_SERVER_ERROR
+        e.status_code()
     })?;
     Ok(Json(serde_json::json!({ "nodes": nodes, "edges": edges })))
 }
@@ -556,6 +990,22 @@ pub async fn list_tools(
     Json(serde_json::to_value(tools).unwrap())
 }
 
+/// GET /api/tools/schema — every tool with its parameter JSON Schema, so
+/// external orchestrators can build valid calls.
+pub async fn tool_schemas(
+    State(state): State<DashState>,
+) -> Json<serde_json::Value> {
+    Json(serde_json::Value::Array(state.agent.tools.schemas()))
+}
+
+/// GET /api/capabilities — tools, skills, backends and pause/safe-mode
+/// state in one manifest.
+pub async fn get_capabilities(
+    State(state): State<DashState>,
+) -> Json<crate::agent::capabilities::CapabilityManifest> {
+    Json(state.agent.capabilities().await)
+}
+
 // -- Skills & Credentials ------------------------------------------------
 
 pub async fn list_skills(
@@ -600,7 +1050,7 @@ pub async fn set_skill_credential(
     sm.set_credential(&skill_name, &body.key, &body.value)
         .map_err(|e| {
             error!("set credential: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -617,7 +1067,7 @@ pub async fn delete_skill_credential(
     sm.delete_credential(&skill_name, &key)
         .map_err(|e| {
             error!("delete credential: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -731,12 +1181,18 @@ pub async fn update_skill_manifest(
     State(state): State<DashState>,
     Path(skill_name): Path<String>,
     Json(body): Json<UpdateManifestBody>,
-) -> Result<Json<ActionResponse>, StatusCode> {
+) -> Result<Json<ActionResponse>, (StatusCode, Json<ActionResponse>)> {
     let sm = state.agent.skill_manager.lock().await;
     sm.update_manifest(&skill_name, &body.toml)
         .map_err(|e| {
             error!("update manifest: {e}");
-            StatusCode::BAD_REQUEST
+            // A manifest failing validation is the caller's fault.
+            let status = match e {
+                crate::error::SafeAgentError::Config(_) => StatusCode::BAD_REQUEST,
+                _ => e.status_code(),
+            };
+            // The message lists each offending manifest key.
+            (status, Json(ActionResponse { ok: false, message: Some(e.to_string()), count: None }))
         })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -745,6 +1201,37 @@ pub async fn update_skill_manifest(
     }))
 }
 
+/// Earlier versions of a skill's manifest, newest first.
+pub async fn get_skill_manifest_history(
+    State(state): State<DashState>,
+    Path(skill_name): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let sm = state.agent.skill_manager.lock().await;
+    sm.manifest_history(&skill_name)
+        .map(|versions| Json(serde_json::json!({ "versions": versions })))
+        .map_err(|e| {
+            error!("manifest history: {e}");
+            e.status_code()
+        })
+}
+
+/// Roll a skill's manifest back to the version before its last edit.
+pub async fn revert_skill_manifest(
+    State(state): State<DashState>,
+    Path(skill_name): Path<String>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let sm = state.agent.skill_manager.lock().await;
+    sm.revert_manifest(&skill_name).map_err(|e| {
+        error!("revert manifest: {e}");
+        e.status_code()
+    })?;
+    Ok(Json(ActionResponse {
+        ok: true,
+        message: Some(format!("manifest for '{}' reverted", skill_name)),
+        count: None,
+    }))
+}
+
 /// Toggle skill enabled/disabled.
 #[derive(Deserialize)]
 pub struct SetEnabledBody {
@@ -760,7 +1247,7 @@ pub async fn set_skill_enabled(
         let sm = state.agent.skill_manager.lock().await;
         sm.set_enabled(&skill_name, body.enabled).map_err(|e| {
             error!("set enabled: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
     }
 
@@ -799,7 +1286,7 @@ pub async fn set_skill_env_var(
     sm.set_env_var(&skill_name, &body.key, &body.value)
         .map_err(|e| {
             error!("set env var: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -817,7 +1304,7 @@ pub async fn delete_skill_env_var(
     sm.delete_env_var(&skill_name, &key)
         .map_err(|e| {
             error!("delete env var: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -830,12 +1317,17 @@ pub async fn delete_skill_env_var(
 
 #[derive(Deserialize)]
 pub struct ImportSkillBody {
-    /// Import source type: "git", "path", or "url".
+    /// Import source type: "git", "path", "url", or "registry".
     pub source: String,
-    /// The git URL, local path, or archive URL.
+    /// The git URL, local path, archive URL, or registry skill name
+    /// (`name` or `name@version`).
     pub location: String,
     /// Optional skill name override (directory name).
     pub name: Option<String>,
+    /// Install registry skills that are unsigned or from an untrusted
+    /// publisher.
+    #[serde(default)]
+    pub force: bool,
 }
 
 pub async fn import_skill(
@@ -845,7 +1337,7 @@ pub async fn import_skill(
     let sm = state.agent.skill_manager.lock().await;
     let name_ref = body.name.as_deref();
 
-    match sm.import_skill(&body.source, &body.location, name_ref).await {
+    match sm.import_skill(&body.source, &body.location, name_ref, body.force).await {
         Ok((name, _dir)) => {
             // Trigger reconcile to auto-start if enabled
             drop(sm);
@@ -878,7 +1370,7 @@ pub async fn delete_skill(
         .await
         .map_err(|e| {
             error!("delete skill: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -926,7 +1418,7 @@ pub async fn send_chat_message(
         .await
         .map_err(|e| {
             error!("chat: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
 
     let timestamp = chrono::Utc::now().to_rfc3339();
@@ -945,6 +1437,18 @@ pub async fn get_tool_events(
     Json(serde_json::to_value(events).unwrap())
 }
 
+// -- Prompt debugging ----------------------------------------------------
+
+/// The most recent system prompts and contexts sent to the LLM, newest
+/// last, with secrets redacted.  Admin only.
+pub async fn get_last_prompts(
+    State(state): State<DashState>,
+    Query(params): Query<PaginationQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let prompts = state.agent.recent_prompts(params.limit.unwrap_or(1)).await;
+    Ok(Json(serde_json::to_value(prompts).unwrap()))
+}
+
 // -- Tunnel --------------------------------------------------------------
 
 #[derive(Serialize)]
@@ -993,7 +1497,7 @@ pub async fn restore_trash(
 ) -> Result<Json<ActionResponse>, StatusCode> {
     let entry = state.trash.restore(&id).map_err(|e| {
         error!("restore trash: {e}");
-        StatusCode::INTERNAL_SERVER_ERROR
+        e.status_code()
     })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -1008,7 +1512,7 @@ pub async fn permanent_delete_trash(
 ) -> Result<Json<ActionResponse>, StatusCode> {
     let entry = state.trash.permanent_delete(&id).map_err(|e| {
         error!("permanent delete trash: {e}");
-        StatusCode::INTERNAL_SERVER_ERROR
+        e.status_code()
     })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -1022,7 +1526,7 @@ pub async fn empty_trash(
 ) -> Result<Json<ActionResponse>, StatusCode> {
     let count = state.trash.empty().map_err(|e| {
         error!("empty trash: {e}");
-        StatusCode::INTERNAL_SERVER_ERROR
+        e.status_code()
     })?;
     Ok(Json(ActionResponse {
         ok: true,
@@ -1039,7 +1543,7 @@ pub async fn list_goals(
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     use crate::goals::GoalManager;
 
-    let mgr = GoalManager::new(state.db.clone());
+    let mgr = GoalManager::new(state.read_db.get());
     let status = params.status.as_deref();
     let limit = params.limit.unwrap_or(50);
     let offset = params.offset.unwrap_or(0);
@@ -1049,7 +1553,7 @@ pub async fn list_goals(
         .await
         .map_err(|e| {
             error!("list goals: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
 
     Ok(Json(serde_json::to_value(goals).unwrap()))
@@ -1068,14 +1572,14 @@ pub async fn get_goal(
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     use crate::goals::GoalManager;
 
-    let mgr = GoalManager::new(state.db.clone());
+    let mgr = GoalManager::new(state.read_db.get());
     let goal = mgr.get_goal(&goal_id).await.map_err(|e| {
         error!("get goal: {e}");
         StatusCode::NOT_FOUND
     })?;
     let tasks = mgr.get_tasks(&goal_id).await.map_err(|e| {
         error!("get goal tasks: {e}");
-        StatusCode::INTERNAL_SERVER_ERROR
+        e.status_code()
     })?;
 
     Ok(Json(serde_json::json!({
@@ -1097,7 +1601,7 @@ pub async fn update_goal_status(
         .await
         .map_err(|e| {
             error!("update goal status: {e}");
-            StatusCode::INTERNAL_SERVER_ERROR
+            e.status_code()
         })?;
 
     Ok(Json(ActionResponse {
@@ -1112,28 +1616,146 @@ pub struct UpdateGoalStatusBody {
     pub status: String,
 }
 
+#[derive(Deserialize)]
+pub struct CreateGoalBody {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub priority: i32,
+    /// Parent goal ID.
+    #[serde(default)]
+    pub parent: Option<String>,
+}
+
+/// POST /api/goals — create a goal.
+///
+/// Goals lead to tool execution, so the route needs a role that may
+/// trigger tools; viewers and skill tokens are refused.
+pub async fn create_goal(
+    State(state): State<DashState>,
+    Json(body): Json<CreateGoalBody>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    use crate::goals::GoalManager;
+
+    let title = body.title.trim();
+    if title.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mgr = GoalManager::new(state.db.clone());
+    if let Some(ref parent) = body.parent {
+        mgr.get_goal(parent).await.map_err(|_| StatusCode::BAD_REQUEST)?;
+    }
+    let id = mgr
+        .create_goal(title, &body.description, body.priority, body.parent.as_deref())
+        .await
+        .map_err(|e| {
+            error!("create goal: {e}");
+            e.status_code()
+        })?;
+
+    state.agent.notify_update();
+    Ok(Json(serde_json::json!({ "ok": true, "id": id })))
+}
+
+#[derive(Deserialize)]
+pub struct AddGoalTaskBody {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// IDs of tasks in the same goal that must finish first.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+/// POST /api/goals/{id}/tasks — add a task to a goal.
+pub async fn add_goal_task(
+    State(state): State<DashState>,
+    Path(goal_id): Path<String>,
+    Json(body): Json<AddGoalTaskBody>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    use crate::goals::GoalManager;
+
+    let title = body.title.trim();
+    if title.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mgr = GoalManager::new(state.db.clone());
+    mgr.get_goal(&goal_id).await.map_err(|_| StatusCode::NOT_FOUND)?;
+    mgr.check_task_dependencies(&goal_id, &body.depends_on)
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let id = mgr
+        .add_task(&goal_id, title, &body.description, None, &body.depends_on, body.sort_order)
+        .await
+        .map_err(|e| {
+            error!("add goal task: {e}");
+            e.status_code()
+        })?;
+
+    state.agent.notify_update();
+    Ok(Json(serde_json::json!({ "ok": true, "id": id })))
+}
+
+/// DELETE /api/goals/{id} — delete a goal and its tasks (admin only).
+pub async fn delete_goal(
+    State(state): State<DashState>,
+    Path(goal_id): Path<String>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    use crate::goals::GoalManager;
+
+    let mgr = GoalManager::new(state.db.clone());
+    let deleted = mgr.delete_goal(&goal_id).await.map_err(|e| {
+        error!("delete goal: {e}");
+        e.status_code()
+    })?;
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    state.agent.notify_update();
+    Ok(Json(ActionResponse {
+        ok: true,
+        message: Some(format!("Goal {goal_id} deleted")),
+        count: None,
+    }))
+}
+
 // -- Security: Audit Trail ---------------------------------------------------
 
 #[derive(Deserialize)]
 pub struct AuditQuery {
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    /// Keyset cursor; preferred over `offset`.
+    pub before_id: Option<i64>,
     pub event_type: Option<String>,
     pub tool: Option<String>,
 }
 
+/// GET /api/security/audit — newest first, paged like [`get_activity`].
 pub async fn get_audit_log(
     State(state): State<DashState>,
     Query(query): Query<AuditQuery>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<axum::response::Response, StatusCode> {
     let limit = query.limit.unwrap_or(50);
     let offset = query.offset.unwrap_or(0);
     let entries = state
         .agent
         .audit
-        .recent(limit, offset, query.event_type.as_deref(), query.tool.as_deref())
+        .recent(
+            limit,
+            offset,
+            query.before_id,
+            query.event_type.as_deref(),
+            query.tool.as_deref(),
+        )
         .await;
-    Ok(Json(serde_json::to_value(entries).unwrap()))
+    Ok(paged_response(&entries, limit, entries.last().map(|e| e.id)))
 }
 
 pub async fn get_audit_summary(
@@ -1143,6 +1765,18 @@ pub async fn get_audit_summary(
     Ok(Json(serde_json::to_value(summary).unwrap()))
 }
 
+pub async fn verify_audit_chain(
+    State(state): State<DashState>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.agent.audit.verify_chain().await {
+        Ok(status) => Ok(Json(serde_json::to_value(status).unwrap())),
+        Err(e) => {
+            error!("audit chain verification failed: {e}");
+            Err(e.status_code())
+        }
+    }
+}
+
 pub async fn explain_action(
     State(state): State<DashState>,
     Path(audit_id): Path<i64>,
@@ -1151,6 +1785,81 @@ pub async fn explain_action(
     Ok(Json(serde_json::to_value(chain).unwrap()))
 }
 
+/// POST /api/security/audit/{id}/replay — re-run the tool call an audit
+/// entry recorded and return the fresh output (admin only).
+pub async fn replay_audit_entry(
+    State(state): State<DashState>,
+    Path(audit_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    use crate::error::SafeAgentError;
+
+    let entry = state.agent.audit.get(audit_id).await.ok_or(StatusCode::NOT_FOUND)?;
+    let call = entry.tool_call().ok_or(StatusCode::BAD_REQUEST)?;
+    match state.agent.replay_tool_call(audit_id, &call, "dashboard").await {
+        Ok((replay_id, output)) => Ok(Json(serde_json::json!({
+            "ok": true,
+            "tool": call.tool,
+            "replay_of": audit_id,
+            "audit_id": replay_id,
+            "success": output.success,
+            "output": output.output,
+        }))),
+        Err(SafeAgentError::RateLimited(_)) => Err(StatusCode::TOO_MANY_REQUESTS),
+        Err(e) => Ok(Json(serde_json::json!({ "ok": false, "error": e.to_string() }))),
+    }
+}
+
+pub async fn get_action_trace(
+    State(state): State<DashState>,
+    Path(audit_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.agent.traces.for_audit(audit_id).await {
+        Ok(Some(trace)) => Ok(Json(serde_json::to_value(trace.redacted()).unwrap())),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("reasoning trace lookup failed: {e}");
+            Err(e.status_code())
+        }
+    }
+}
+
+// -- Reasoning traces --------------------------------------------------------
+
+#[derive(Deserialize)]
+pub struct TraceQuery {
+    pub limit: Option<usize>,
+}
+
+pub async fn list_traces(
+    State(state): State<DashState>,
+    Query(query): Query<TraceQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.agent.traces.recent(query.limit.unwrap_or(20)).await {
+        Ok(traces) => {
+            let traces: Vec<_> = traces.into_iter().map(|t| t.redacted()).collect();
+            Ok(Json(serde_json::to_value(traces).unwrap()))
+        }
+        Err(e) => {
+            error!("reasoning trace listing failed: {e}");
+            Err(e.status_code())
+        }
+    }
+}
+
+pub async fn get_trace(
+    State(state): State<DashState>,
+    Path(turn_id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.agent.traces.get(&turn_id).await {
+        Ok(Some(trace)) => Ok(Json(serde_json::to_value(trace.redacted()).unwrap())),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("reasoning trace lookup failed: {e}");
+            Err(e.status_code())
+        }
+    }
+}
+
 // -- Security: Cost Tracking -------------------------------------------------
 
 pub async fn get_cost_summary(
@@ -1193,20 +1902,40 @@ pub async fn get_2fa_challenges(
     Json(serde_json::to_value(challenges).unwrap())
 }
 
+#[derive(Deserialize, Default)]
+pub struct Confirm2faBody {
+    /// TOTP code; required when the confirming user has TOTP enabled.
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
 pub async fn confirm_2fa(
     State(state): State<DashState>,
     Path(challenge_id): Path<String>,
+    user: Option<Extension<crate::users::UserContext>>,
+    body: axum::body::Bytes,
 ) -> Json<ActionResponse> {
-    let ok = state.agent.twofa.confirm(&challenge_id);
+    let body: Confirm2faBody = serde_json::from_slice(&body).unwrap_or_default();
+    let secret = match user {
+        Some(Extension(ref u)) => state.agent.user_manager.enabled_totp_secret(&u.user_id).await,
+        None => None,
+    };
+    let totp = secret.as_deref().map(|secret| crate::security::twofa::TotpCode {
+        secret,
+        code: body.code.as_deref().unwrap_or(""),
+    });
+
+    let result = state.agent.twofa.confirm(&challenge_id, totp);
+    let ok = result.is_ok();
     if ok {
         state.agent.audit.log_2fa("", "confirmed", "dashboard").await;
     }
     Json(ActionResponse {
         ok,
-        message: Some(if ok {
-            "2FA challenge confirmed".into()
-        } else {
-            "Challenge not found or already resolved".into()
+        message: Some(match result {
+            Ok(()) => "2FA challenge confirmed".into(),
+            Err(crate::error::SafeAgentError::NotFound(_)) => "Challenge not found or already resolved".into(),
+            Err(e) => e.to_string(),
         }),
         count: None,
     })
@@ -1328,6 +2057,12 @@ pub async fn metrics(
         state.agent.tools.len(),
     ));
 
+    // Tool execution latency and outcomes
+    out.push_str(&state.agent.tools.metrics().render_prometheus());
+
+    // LLM latency, failovers and empty responses
+    out.push_str(&state.agent.llm.metrics().render_prometheus());
+
     // Stats from DB
     if let Ok(stats) = state.agent.memory.get_stats().await {
         out.push_str(&format!(
@@ -1350,6 +2085,11 @@ pub async fn metrics(
         ));
     }
 
+    // Goals by status and executed goal tasks
+    if let Ok(goals) = crate::goals::GoalManager::new(state.read_db.get()).metrics().await {
+        out.push_str(&goals.render_prometheus());
+    }
+
     // Audit summary
     let audit = state.agent.audit.summary().await;
     out.push_str(&format!(
@@ -1789,6 +2529,14 @@ pub async fn federation_peers(
     Json(serde_json::json!({ "peers": peers }))
 }
 
+/// List federation peers with their health-check state.
+pub async fn federation_peer_health(
+    State(state): State<DashState>,
+) -> Json<serde_json::Value> {
+    let peers = state.agent.federation.peer_statuses().await;
+    Json(serde_json::json!({ "peers": peers }))
+}
+
 /// Add a peer by address.
 #[derive(Deserialize)]
 pub struct AddPeerBody {
@@ -1799,14 +2547,15 @@ pub async fn federation_add_peer(
     State(state): State<DashState>,
     Json(body): Json<AddPeerBody>,
 ) -> Result<Json<ActionResponse>, StatusCode> {
-    // Fetch the peer's info
     let client = reqwest::Client::builder()
         .user_agent("safeclaw-federation")
         .timeout(std::time::Duration::from_secs(10))
         .build()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let url = format!("{}/healthz", body.address);
+    // Fetch the peer's identity (node ID + public key)
+    let address = body.address.trim_end_matches('/');
+    let url = format!("{address}/api/federation/identity");
     let resp = client.get(&url).send().await.map_err(|e| {
         error!("failed to reach peer at {}: {e}", body.address);
         StatusCode::BAD_GATEWAY
@@ -1820,29 +2569,30 @@ pub async fn federation_add_peer(
         }));
     }
 
-    let peer_info: serde_json::Value = resp.json().await.map_err(|_| StatusCode::BAD_GATEWAY)?;
-    let version = peer_info
-        .get("version")
-        .and_then(|v| v.as_str())
-        .unwrap_or("unknown");
-
-    // Register the peer with a fabricated NodeInfo
-    let node_info = crate::federation::NodeInfo {
-        node_id: uuid::Uuid::new_v4().to_string(),
-        name: body.address.clone(),
-        address: body.address.clone(),
-        version: version.to_string(),
-        started_at: chrono::Utc::now().to_rfc3339(),
-        last_heartbeat: chrono::Utc::now().to_rfc3339(),
-        status: crate::federation::NodeStatus::Online,
-    };
+    let mut node_info: crate::federation::NodeInfo =
+        resp.json().await.map_err(|_| StatusCode::BAD_GATEWAY)?;
+    if node_info.public_key.is_empty() {
+        return Ok(Json(ActionResponse {
+            ok: false,
+            message: Some(format!("Peer at {} did not advertise a public key", body.address)),
+            count: None,
+        }));
+    }
+    node_info.address = address.to_string();
+    node_info.last_heartbeat = chrono::Utc::now().to_rfc3339();
+    let version = node_info.version.clone();
 
     state.agent.federation.register_peer(node_info).await;
 
-    // Register ourselves with the peer
-    let register_url = format!("{}/api/federation/heartbeat", body.address);
+    // Register ourselves with the peer (signed, carrying our public key)
+    let register_url = format!("{address}/api/federation/heartbeat");
     let local = state.agent.federation.local_info();
-    let _ = client.post(&register_url).json(&local).send().await;
+    match state.agent.federation.sign(&local) {
+        Ok(signed) => {
+            let _ = client.post(&register_url).json(&signed).send().await;
+        }
+        Err(e) => error!("failed to sign federation heartbeat: {e}"),
+    }
 
     Ok(Json(ActionResponse {
         ok: true,
@@ -1864,7 +2614,16 @@ pub async fn federation_remove_peer(
     })
 }
 
-/// Receive sync deltas from a peer (no auth required).
+/// This node's identity, including the public key peers use to verify its
+/// messages (no auth required).
+pub async fn federation_identity(
+    State(state): State<DashState>,
+) -> Json<crate::federation::NodeInfo> {
+    Json(state.agent.federation.local_info())
+}
+
+/// Receive sync deltas from a peer (no auth required — the payload must be
+/// signed by a registered peer).
 #[derive(Deserialize)]
 pub struct SyncBody {
     pub origin: String,
@@ -1873,45 +2632,58 @@ pub struct SyncBody {
 
 pub async fn federation_receive_sync(
     State(state): State<DashState>,
-    Json(body): Json<SyncBody>,
-) -> Json<ActionResponse> {
+    Json(msg): Json<crate::federation::SignedMessage>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let body: SyncBody = state.agent.federation.verify(&msg).await.map_err(|e| {
+        error!(node_id = %msg.node_id, "rejected federation sync: {e}");
+        StatusCode::UNAUTHORIZED
+    })?;
     let count = body.deltas.len();
     state.agent.federation.apply_deltas(&state.db, body.deltas).await;
-    Json(ActionResponse {
+    Ok(Json(ActionResponse {
         ok: true,
         message: Some(format!("Applied {count} deltas from {}", body.origin)),
         count: Some(count as u64),
-    })
+    }))
 }
 
-/// Receive heartbeat from a peer (no auth required).
+/// Receive a signed heartbeat from a peer (no auth required).  Only peers
+/// an admin has added, or whose key is in `trusted_peer_keys`, are accepted.
 pub async fn federation_receive_heartbeat(
     State(state): State<DashState>,
-    Json(info): Json<crate::federation::NodeInfo>,
-) -> Json<ActionResponse> {
-    state.agent.federation.register_peer(info).await;
-    Json(ActionResponse {
+    Json(msg): Json<crate::federation::SignedMessage>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    state.agent.federation.accept_heartbeat(&msg).await.map_err(|e| {
+        error!(node_id = %msg.node_id, "rejected federation heartbeat: {e}");
+        StatusCode::UNAUTHORIZED
+    })?;
+    Ok(Json(ActionResponse {
         ok: true,
         message: Some("Heartbeat accepted".into()),
         count: None,
-    })
+    }))
 }
 
-/// Receive a task claim notification from a peer (no auth required).
+/// Receive a signed task claim notification from a peer (no auth required).
 pub async fn federation_receive_claim(
-    State(_state): State<DashState>,
-    Json(claim): Json<crate::federation::TaskClaim>,
-) -> Json<ActionResponse> {
+    State(state): State<DashState>,
+    Json(msg): Json<crate::federation::SignedMessage>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let claim: crate::federation::TaskClaim =
+        state.agent.federation.verify(&msg).await.map_err(|e| {
+            error!(node_id = %msg.node_id, "rejected federation claim: {e}");
+            StatusCode::UNAUTHORIZED
+        })?;
     info!(
         task_id = %claim.task_id,
         claimed_by = %claim.claimed_by,
         "received task claim from peer"
     );
-    Json(ActionResponse {
+    Ok(Json(ActionResponse {
         ok: true,
         message: Some(format!("Claim for {} acknowledged", claim.task_id)),
         count: None,
-    })
+    }))
 }
 
 // -- LLM Plugin Backend Management -------------------------------------------
@@ -2120,6 +2892,9 @@ pub async fn onboarding_test_llm(
         message: "Say hello in one sentence.",
         tools: None,
         prompt_skills: &[],
+        personality: None,
+        persona: None,
+        attachments: &[],
     };
     match state.agent.llm.generate(&gen_ctx).await {
         Ok(response) => Json(serde_json::json!({
@@ -1,10 +1,14 @@
-use axum::extract::State;
-use axum::http::StatusCode;
-use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{OriginalUri, Query, State};
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::{Form, Json};
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use super::routes::DashState;
+use crate::agent::attachments::{Origin, MAX_INBOUND_IMAGE_BYTES};
+use crate::users::{UserContext, UserManager};
 
 // ---------------------------------------------------------------------------
 // POST /api/messaging/incoming
@@ -22,6 +26,21 @@ pub struct IncomingMessage {
     /// Whether the agent was @mentioned or directly replied to.
     #[serde(default)]
     pub is_mentioned: bool,
+    /// Images sent with the message.
+    #[serde(default)]
+    pub attachments: Vec<IncomingAttachment>,
+}
+
+/// Largest `/api/messaging/incoming` body: one base64 image at the
+/// inbound limit plus room for the rest of the message.
+pub const MAX_INCOMING_BYTES: usize = MAX_INBOUND_IMAGE_BYTES / 3 * 4 + 64 * 1024;
+
+/// An inbound image, inlined by the bridge that received it.
+#[derive(Deserialize)]
+pub struct IncomingAttachment {
+    /// Base64-encoded file contents.
+    pub data: String,
+    pub mime_type: String,
 }
 
 #[derive(Serialize)]
@@ -42,6 +61,10 @@ pub async fn incoming(
         "incoming message via webhook"
     );
 
+    if let Some(backend) = state.messaging.get(&body.platform) {
+        backend.note_incoming(&body.channel);
+    }
+
     // Group message gating: only respond if mentioned or replied to
     if body.is_group && !body.is_mentioned {
         return (
@@ -51,51 +74,7 @@ pub async fn incoming(
     }
 
     // Look up user by platform identity for multi-user routing
-    let user_ctx = match body.platform.as_str() {
-        "whatsapp" => {
-            state.agent.user_manager.get_by_whatsapp_id(&body.sender).await
-                .map(|u| crate::users::UserContext::from_user(&u, "whatsapp"))
-        }
-        "telegram" => {
-            // sender might be a chat_id string — try parsing as i64
-            body.sender.parse::<i64>().ok()
-                .and_then(|_id| {
-                    // Use a blocking approach since we're already in an async context
-                    // but get_by_telegram_id is also async
-                    None::<crate::users::User> // Will be resolved below
-                })
-                .map(|u| crate::users::UserContext::from_user(&u, "telegram"))
-        }
-        "imessage" => {
-            state.agent.user_manager.get_by_imessage_id(&body.sender).await
-                .map(|u| crate::users::UserContext::from_user(&u, "imessage"))
-        }
-        "android_sms" => {
-            state.agent.user_manager.get_by_android_sms_id(&body.sender).await
-                .map(|u| crate::users::UserContext::from_user(&u, "android_sms"))
-        }
-        "discord" => {
-            state.agent.user_manager.get_by_discord_id(&body.sender).await
-                .map(|u| crate::users::UserContext::from_user(&u, "discord"))
-        }
-        "signal" => {
-            state.agent.user_manager.get_by_signal_id(&body.sender).await
-                .map(|u| crate::users::UserContext::from_user(&u, "signal"))
-        }
-        _ => None,
-    };
-
-    // For telegram, we need a separate async lookup
-    let user_ctx = if user_ctx.is_none() && body.platform == "telegram" {
-        if let Ok(tg_id) = body.sender.parse::<i64>() {
-            state.agent.user_manager.get_by_telegram_id(tg_id).await
-                .map(|u| crate::users::UserContext::from_user(&u, "telegram"))
-        } else {
-            None
-        }
-    } else {
-        user_ctx
-    };
+    let user_ctx = user_context_for(&state.agent.user_manager, &body.platform, &body.sender).await;
 
     // Strip @mention prefix so the agent sees clean text
     let clean_text = if body.is_mentioned {
@@ -104,8 +83,15 @@ pub async fn incoming(
         body.text.clone()
     };
 
+    let attachments = save_attachments(&state, &body.platform, &body.attachments);
+    let origin = Origin { platform: &body.platform, channel: &body.channel };
+
     // Send the message to the agent for processing
-    match state.agent.handle_message_as(&clean_text, user_ctx.as_ref()).await {
+    match state
+        .agent
+        .handle_message_with_attachments(&clean_text, &attachments, Some(origin), user_ctx.as_ref())
+        .await
+    {
         Ok(reply) => {
             // Also send the reply back through the platform's backend
             if let Some(backend) = state.messaging.get(&body.platform) {
@@ -138,6 +124,60 @@ pub async fn incoming(
     }
 }
 
+/// Decode inbound images into the sandbox.  Ones that aren't images or
+/// can't be decoded are logged and dropped.
+fn save_attachments(
+    state: &DashState,
+    platform: &str,
+    attachments: &[IncomingAttachment],
+) -> Vec<crate::llm::Attachment> {
+    attachments
+        .iter()
+        .filter(|a| a.mime_type.starts_with("image/"))
+        .filter(|a| {
+            // Skip decoding anything that would be over the limit anyway
+            let fits = a.data.len() / 4 * 3 <= MAX_INBOUND_IMAGE_BYTES + 2;
+            if !fits {
+                warn!(platform, "attachment too large, dropped");
+            }
+            fits
+        })
+        .filter_map(|a| {
+            let data = data_encoding::BASE64
+                .decode(a.data.as_bytes())
+                .map_err(|e| warn!(platform, err = %e, "undecodable attachment"))
+                .ok()?;
+            state
+                .agent
+                .save_inbound_image(platform, &data, &a.mime_type)
+                .map_err(|e| warn!(platform, err = %e, "failed to save attachment"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Find the user linked to `sender` on `platform`, for multi-user routing.
+pub(crate) async fn user_context_for(
+    users: &UserManager,
+    platform: &str,
+    sender: &str,
+) -> Option<UserContext> {
+    let user = match platform {
+        "whatsapp" => users.get_by_whatsapp_id(sender).await,
+        "telegram" => match sender.parse::<i64>() {
+            Ok(id) => users.get_by_telegram_id(id).await,
+            Err(_) => None,
+        },
+        "imessage" => users.get_by_imessage_id(sender).await,
+        "sms" => users.get_by_twilio_number(sender).await,
+        "android_sms" => users.get_by_android_sms_id(sender).await,
+        "discord" => users.get_by_discord_id(sender).await,
+        "signal" => users.get_by_signal_id(sender).await,
+        _ => None,
+    }?;
+    Some(UserContext::from_user(&user, platform))
+}
+
 // ---------------------------------------------------------------------------
 // Mention stripping
 // ---------------------------------------------------------------------------
@@ -292,8 +332,8 @@ pub async fn list_platforms(
 
     if state.config.twilio.enabled {
         platforms.push(PlatformInfo {
-            name: "twilio".to_string(),
-            connected: state.messaging.get("twilio").is_some(),
+            name: "sms".to_string(),
+            connected: state.messaging.get("sms").is_some(),
         });
     }
 
@@ -357,7 +397,7 @@ pub struct MessagingConfigResponse {
 pub async fn messaging_config(
     State(state): State<DashState>,
 ) -> Json<MessagingConfigResponse> {
-    let has_token = std::env::var("TELEGRAM_BOT_TOKEN").is_ok();
+    let has_token = state.config.telegram_bot_token().await.is_ok();
     let tg_connected = state.messaging.get("telegram").is_some();
     let tg_primary = state.messaging.primary_channel("telegram").map(|s| s.to_string());
 
@@ -433,21 +473,17 @@ pub async fn twilio_incoming(
 ) -> (StatusCode, String) {
     info!(from = %form.from, to = %form.to, "incoming Twilio SMS");
 
-    let user_ctx = state
-        .agent
-        .user_manager
-        .get_by_twilio_number(&form.from)
-        .await
-        .map(|u| crate::users::UserContext::from_user(&u, "twilio"));
+    let user_ctx = user_context_for(&state.agent.user_manager, "sms", &form.from).await;
 
+    let origin = Origin { platform: "sms", channel: &form.from };
     match state
         .agent
-        .handle_message_as(&form.body, user_ctx.as_ref())
+        .handle_message_with_attachments(&form.body, &[], Some(origin), user_ctx.as_ref())
         .await
     {
         Ok(reply) => {
             // Send reply back via Twilio backend
-            if let Some(backend) = state.messaging.get("twilio") {
+            if let Some(backend) = state.messaging.get("sms") {
                 if let Err(e) = backend.send_message(&form.from, &reply).await {
                     error!(err = %e, "failed to relay Twilio reply");
                 }
@@ -468,3 +504,345 @@ pub async fn twilio_incoming(
         }
     }
 }
+
+// ---------------------------------------------------------------------------
+// Provider webhooks: POST /webhooks/twilio, GET|POST /webhooks/whatsapp
+// ---------------------------------------------------------------------------
+//
+// These sit outside the dashboard auth layer, so every request must carry a
+// valid provider signature instead; anything unverified gets a 403.
+
+type HmacSha1 = Hmac<sha1::Sha1>;
+type HmacSha256 = Hmac<sha2::Sha256>;
+
+/// A text message extracted from a provider webhook.
+#[derive(Debug, PartialEq)]
+struct InboundMessage {
+    /// E.164 number of the sender; replies go back here.
+    sender: String,
+    text: String,
+}
+
+/// Twilio signs the full request URL followed by each POST parameter as
+/// name + value, sorted by name, with HMAC-SHA1 keyed by the auth token.
+fn verify_twilio_signature(auth_token: &str, url: &str, params: &[(String, String)], signature: &str) -> bool {
+    let Ok(expected) = data_encoding::BASE64.decode(signature.as_bytes()) else {
+        return false;
+    };
+    let mut sorted: Vec<&(String, String)> = params.iter().collect();
+    sorted.sort();
+    let mut mac = HmacSha1::new_from_slice(auth_token.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(url.as_bytes());
+    for (name, value) in sorted {
+        mac.update(name.as_bytes());
+        mac.update(value.as_bytes());
+    }
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Meta signs the raw body with HMAC-SHA256 keyed by the app secret and
+/// sends it as `X-Hub-Signature-256: sha256=<hex>`.
+fn verify_whatsapp_signature(app_secret: &str, body: &[u8], header: &str) -> bool {
+    let Some(hex) = header.strip_prefix("sha256=") else {
+        return false;
+    };
+    let Ok(expected) = data_encoding::HEXLOWER_PERMISSIVE.decode(hex.as_bytes()) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(app_secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// The URL Twilio signed: `twilio.webhook_url` when set, otherwise the
+/// request URL rebuilt from the Host and X-Forwarded-Proto headers.
+fn twilio_request_url(configured: &str, headers: &HeaderMap, uri: &Uri) -> String {
+    if !configured.is_empty() {
+        return configured.to_string();
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let proto = header("x-forwarded-proto").unwrap_or("http");
+    let host = header("host").unwrap_or_default();
+    format!("{proto}://{host}{uri}")
+}
+
+fn twilio_inbound(params: &[(String, String)]) -> Option<InboundMessage> {
+    let field = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+    Some(InboundMessage {
+        sender: field("From")?,
+        text: field("Body")?,
+    })
+}
+
+/// Text messages in a WhatsApp Cloud API notification.  Status updates and
+/// non-text messages are skipped.
+fn whatsapp_inbound(payload: &serde_json::Value) -> Vec<InboundMessage> {
+    fn items<'a>(v: &'a serde_json::Value, key: &str) -> impl Iterator<Item = &'a serde_json::Value> {
+        v.get(key).and_then(|a| a.as_array()).into_iter().flatten()
+    }
+    items(payload, "entry")
+        .flat_map(|entry| items(entry, "changes"))
+        .filter_map(|change| change.get("value"))
+        .flat_map(|value| items(value, "messages"))
+        .filter(|msg| msg.get("type").and_then(|t| t.as_str()) == Some("text"))
+        .filter_map(|msg| {
+            let from = msg.get("from")?.as_str()?;
+            Some(InboundMessage {
+                sender: format!("+{}", from.trim_start_matches('+')),
+                text: msg.pointer("/text/body")?.as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Only listed senders are admitted; an empty allow-list denies everyone.
+fn sender_allowed(allowed: &[String], sender: &str) -> bool {
+    allowed.iter().any(|n| n == sender)
+}
+
+/// Hand an inbound message to the agent in the background and relay the
+/// reply through the platform's backend.  Providers time out webhook calls
+/// well before a multi-turn reply is ready, so this doesn't block the
+/// response.
+fn dispatch_inbound(state: DashState, platform: &'static str, msg: InboundMessage) {
+    tokio::spawn(async move {
+        let backend = state.messaging.get(platform).cloned();
+        if let Some(ref backend) = backend {
+            backend.note_incoming(&msg.sender);
+        }
+        let user_ctx = user_context_for(&state.agent.user_manager, platform, &msg.sender).await;
+        let origin = Origin { platform, channel: &msg.sender };
+        let reply = match state
+            .agent
+            .handle_message_with_attachments(&msg.text, &[], Some(origin), user_ctx.as_ref())
+            .await
+        {
+            Ok(reply) => reply,
+            Err(e) => {
+                error!(platform, "agent handle_message failed: {e}");
+                format!("⚠️ Error: {e}")
+            }
+        };
+        match backend {
+            Some(backend) => {
+                if let Err(e) = backend.send_message(&msg.sender, &reply).await {
+                    error!(platform, err = %e, "failed to relay reply");
+                }
+            }
+            None => warn!(platform, "no backend registered to relay reply"),
+        }
+    });
+}
+
+pub async fn twilio_webhook(
+    State(state): State<DashState>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    Form(params): Form<Vec<(String, String)>>,
+) -> (StatusCode, String) {
+    const EMPTY_TWIML: &str = "<Response></Response>";
+
+    if !state.config.twilio.enabled {
+        return (StatusCode::NOT_FOUND, String::new());
+    }
+    let Some(signature) = headers.get("x-twilio-signature").and_then(|v| v.to_str().ok()) else {
+        warn!("Twilio webhook without signature");
+        return (StatusCode::FORBIDDEN, String::new());
+    };
+    let auth_token = match state.config.twilio_credentials().await {
+        Ok((_, token)) => token,
+        Err(e) => {
+            error!("cannot verify Twilio webhook: {e}");
+            return (StatusCode::FORBIDDEN, String::new());
+        }
+    };
+    let url = twilio_request_url(&state.config.twilio.webhook_url, &headers, &uri);
+    if !verify_twilio_signature(&auth_token, &url, &params, signature) {
+        warn!(url, "Twilio webhook signature mismatch");
+        return (StatusCode::FORBIDDEN, String::new());
+    }
+
+    let Some(msg) = twilio_inbound(&params) else {
+        return (StatusCode::BAD_REQUEST, String::new());
+    };
+    if !sender_allowed(&state.config.twilio.allowed_numbers, &msg.sender) {
+        warn!(sender = %msg.sender, "SMS from number not in allowed_numbers");
+        return (StatusCode::OK, EMPTY_TWIML.to_string());
+    }
+
+    info!(from = %msg.sender, "incoming SMS via Twilio webhook");
+    dispatch_inbound(state, "sms", msg);
+    (StatusCode::OK, EMPTY_TWIML.to_string())
+}
+
+#[derive(Deserialize)]
+pub struct WhatsAppVerifyQuery {
+    #[serde(rename = "hub.mode")]
+    pub mode: Option<String>,
+    #[serde(rename = "hub.verify_token")]
+    pub verify_token: Option<String>,
+    #[serde(rename = "hub.challenge")]
+    pub challenge: Option<String>,
+}
+
+/// Subscription handshake: echo the challenge if the verify token matches.
+pub async fn whatsapp_webhook_verify(
+    State(state): State<DashState>,
+    Query(query): Query<WhatsAppVerifyQuery>,
+) -> (StatusCode, String) {
+    let expected = state.config.secret("WHATSAPP_VERIFY_TOKEN").await.ok();
+    let subscribe = query.mode.as_deref() == Some("subscribe");
+    match (expected, query.verify_token, query.challenge) {
+        (Some(expected), Some(token), Some(challenge)) if subscribe && token == expected => {
+            (StatusCode::OK, challenge)
+        }
+        _ => (StatusCode::FORBIDDEN, String::new()),
+    }
+}
+
+pub async fn whatsapp_webhook(
+    State(state): State<DashState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if !state.config.whatsapp.enabled {
+        return StatusCode::NOT_FOUND;
+    }
+    let Some(signature) = headers.get("x-hub-signature-256").and_then(|v| v.to_str().ok()) else {
+        warn!("WhatsApp webhook without signature");
+        return StatusCode::FORBIDDEN;
+    };
+    let app_secret = match state.config.secret("WHATSAPP_APP_SECRET").await {
+        Ok(secret) => secret,
+        Err(e) => {
+            error!("cannot verify WhatsApp webhook: {e}");
+            return StatusCode::FORBIDDEN;
+        }
+    };
+    if !verify_whatsapp_signature(&app_secret, &body, signature) {
+        warn!("WhatsApp webhook signature mismatch");
+        return StatusCode::FORBIDDEN;
+    }
+
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return StatusCode::BAD_REQUEST;
+    };
+    for msg in whatsapp_inbound(&payload) {
+        if !sender_allowed(&state.config.whatsapp.allowed_numbers, &msg.sender) {
+            warn!(sender = %msg.sender, "WhatsApp message from number not in allowed_numbers");
+            continue;
+        }
+        info!(from = %msg.sender, "incoming WhatsApp message via Cloud API webhook");
+        dispatch_inbound(state.clone(), "whatsapp", msg);
+    }
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn twilio_signature_matches_documented_example() {
+        let url = "https://mycompany.com/myapp.php?foo=1&bar=2";
+        let params = pairs(&[
+            ("Digits", "1234"),
+            ("To", "+18005551212"),
+            ("From", "+14158675309"),
+            ("Caller", "+14158675309"),
+            ("CallSid", "CA1234567890ABCDE"),
+        ]);
+        assert!(verify_twilio_signature("12345", url, &params, "RSOYDt4T1cUTdK1PDd93/VVr8B8="));
+
+        assert!(!verify_twilio_signature("wrong", url, &params, "RSOYDt4T1cUTdK1PDd93/VVr8B8="));
+        assert!(!verify_twilio_signature("12345", "https://evil.example/myapp.php", &params, "RSOYDt4T1cUTdK1PDd93/VVr8B8="));
+        let mut tampered = params.clone();
+        tampered[0].1 = "9999".into();
+        assert!(!verify_twilio_signature("12345", url, &tampered, "RSOYDt4T1cUTdK1PDd93/VVr8B8="));
+        assert!(!verify_twilio_signature("12345", url, &params, "not base64!"));
+    }
+
+    #[test]
+    fn whatsapp_signature_verification() {
+        let body = br#"{"object":"whatsapp_business_account"}"#;
+        let header = "sha256=0bf7374433906636bed1653bc7c64affb42fa5da9a39e251ab95c2e3a9c06923";
+        assert!(verify_whatsapp_signature("app-secret", body, header));
+
+        assert!(!verify_whatsapp_signature("other-secret", body, header));
+        assert!(!verify_whatsapp_signature("app-secret", b"{}", header));
+        assert!(!verify_whatsapp_signature("app-secret", body, &header[7..]));
+        assert!(!verify_whatsapp_signature("app-secret", body, "sha256=zz"));
+    }
+
+    #[test]
+    fn twilio_url_prefers_configured_value() {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", "agent.example.com".parse().unwrap());
+        headers.insert("x-forwarded-proto", "https".parse().unwrap());
+        let uri: Uri = "/webhooks/twilio?x=1".parse().unwrap();
+        assert_eq!(
+            twilio_request_url("", &headers, &uri),
+            "https://agent.example.com/webhooks/twilio?x=1"
+        );
+        assert_eq!(
+            twilio_request_url("https://public.example/webhooks/twilio", &headers, &uri),
+            "https://public.example/webhooks/twilio"
+        );
+    }
+
+    #[tokio::test]
+    async fn inbound_payloads_map_to_linked_users() {
+        let users = UserManager::new(
+            crate::db::test_db(),
+            crate::crypto::FieldEncryptor::ensure_key(tempfile::TempDir::new().unwrap().path()).unwrap(),
+        );
+        let alice = users
+            .create("alice", "Alice", crate::users::UserRole::User, "pw")
+            .await
+            .unwrap();
+        users.link_twilio(&alice.id, "+15551234567").await.unwrap();
+        users.link_whatsapp(&alice.id, "+15557654321").await.unwrap();
+
+        let sms = twilio_inbound(&pairs(&[
+            ("From", "+15551234567"),
+            ("To", "+15559876543"),
+            ("Body", "what's on today?"),
+        ]))
+        .unwrap();
+        assert_eq!(sms.text, "what's on today?");
+        let ctx = user_context_for(&users, "sms", &sms.sender).await.unwrap();
+        assert_eq!((ctx.user_id.as_str(), ctx.source.as_str()), (alice.id.as_str(), "sms"));
+        assert!(twilio_inbound(&pairs(&[("From", "+1")])).is_none());
+
+        let payload = serde_json::json!({
+            "object": "whatsapp_business_account",
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [
+                            { "from": "15557654321", "type": "text", "text": { "body": "hi" } },
+                            { "from": "15557654321", "type": "image", "image": { "id": "1" } },
+                        ],
+                    },
+                }, {
+                    "value": { "statuses": [{ "status": "delivered" }] },
+                }],
+            }],
+        });
+        let messages = whatsapp_inbound(&payload);
+        assert_eq!(
+            messages,
+            vec![InboundMessage { sender: "+15557654321".into(), text: "hi".into() }]
+        );
+        let ctx = user_context_for(&users, "whatsapp", &messages[0].sender).await.unwrap();
+        assert_eq!(ctx.user_id, alice.id);
+
+        assert!(user_context_for(&users, "sms", "+19999999999").await.is_none());
+        assert!(!sender_allowed(&[], "+1"));
+        assert!(!sender_allowed(&["+2".to_string()], "+1"));
+    }
+}
@@ -4,6 +4,8 @@ pub mod binaries;
 pub mod handlers;
 pub mod messaging_webhook;
 pub mod oauth;
+pub mod oidc;
+pub mod openai;
 pub mod routes;
 pub mod skill_ext;
 pub mod sse;
@@ -31,7 +33,7 @@ pub async fn serve(
     trash: Arc<TrashManager>,
     installer: BinaryInstaller,
 ) -> Result<()> {
-    let app = routes::build(agent, config.clone(), db, messaging, trash, installer)?;
+    let app = routes::build(agent, config.clone(), db, messaging, trash, installer).await?;
 
     // If ACME TLS is configured, serve over HTTPS using rustls-acme.
     // Otherwise fall back to plain HTTP on the dashboard_bind address.
@@ -0,0 +1,749 @@
+//! OpenID Connect login for the dashboard.
+//!
+//! `/api/auth/oidc/start` redirects to the provider with a fresh `state`
+//! and `nonce`, both kept in a short-lived signed cookie.  The callback
+//! checks the returned state against that cookie, exchanges the code,
+//! verifies the ID token (signature, issuer, audience, expiry and nonce)
+//! and maps the identity to a local user by verified email — creating one
+//! with `dashboard.oidc.default_role` when `auto_provision` is on — before
+//! issuing the normal session cookie.
+//!
+//! GitHub doesn't issue ID tokens, so for it the identity comes from the
+//! REST API and only the state is checked.
+
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json, Redirect, Response};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use super::auth;
+use super::routes::DashState;
+use crate::config::OidcConfig;
+use crate::users::{User, UserManager, UserRole};
+
+const FLOW_COOKIE: &str = "sa_oidc";
+
+/// Subject of flow tokens, so a session JWT can't stand in for one.
+const FLOW_SUBJECT: &str = "oidc_flow";
+
+/// How long a login attempt may take (seconds).
+const FLOW_EXPIRY_SECS: u64 = 10 * 60;
+
+/// Allowed clock skew when checking ID token expiry (seconds).
+const CLOCK_SKEW_SECS: u64 = 60;
+
+/// Asymmetric algorithms accepted for ID token signatures.
+const SIGNING_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+const GOOGLE_ISSUER: &str = "https://accounts.google.com";
+
+// ---------------------------------------------------------------------------
+// Provider metadata
+// ---------------------------------------------------------------------------
+
+/// Endpoints of the configured provider, from its discovery document.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    #[serde(default)]
+    userinfo_endpoint: Option<String>,
+    /// None for GitHub, which doesn't issue ID tokens.
+    #[serde(default)]
+    jwks_uri: Option<String>,
+}
+
+fn issuer_url(config: &OidcConfig) -> &str {
+    match config.provider.as_str() {
+        "google" => GOOGLE_ISSUER,
+        _ => config.issuer.trim_end_matches('/'),
+    }
+}
+
+async fn provider_metadata(config: &OidcConfig) -> Result<ProviderMetadata, String> {
+    if config.provider == "github" {
+        return Ok(ProviderMetadata {
+            issuer: "https://github.com".into(),
+            authorization_endpoint: "https://github.com/login/oauth/authorize".into(),
+            token_endpoint: "https://github.com/login/oauth/access_token".into(),
+            userinfo_endpoint: Some("https://api.github.com/user".into()),
+            jwks_uri: None,
+        });
+    }
+
+    let issuer = issuer_url(config);
+    let url = format!("{issuer}/.well-known/openid-configuration");
+    let meta: ProviderMetadata = fetch_json(reqwest::Client::new().get(&url)).await?;
+    if meta.issuer.trim_end_matches('/') != issuer {
+        return Err(format!("discovery document at {url} is for issuer {}", meta.issuer));
+    }
+    Ok(meta)
+}
+
+fn scopes(config: &OidcConfig) -> &str {
+    // GitHub has no OIDC scopes; the default would be rejected.
+    if config.provider == "github" && config.scopes == "openid email profile" {
+        "read:user user:email"
+    } else {
+        &config.scopes
+    }
+}
+
+fn callback_url() -> String {
+    if let Ok(tunnel) = std::env::var("TUNNEL_URL") {
+        if !tunnel.is_empty() {
+            return format!("{tunnel}/api/auth/oidc/callback");
+        }
+    }
+    let bind = std::env::var("DASHBOARD_BIND").unwrap_or_else(|_| "http://localhost:3031".into());
+    format!("{bind}/api/auth/oidc/callback")
+}
+
+/// Label and icon for the login page button.
+pub(super) fn login_button(config: &OidcConfig) -> (String, String) {
+    let (name, icon) = match config.provider.as_str() {
+        "google" => ("Google", "fa-brands fa-google"),
+        "github" => ("GitHub", "fa-brands fa-github"),
+        _ => ("Single sign-on", "fa-solid fa-right-to-bracket"),
+    };
+    let name = if config.display_name.is_empty() { name } else { &config.display_name };
+    (name.to_string(), icon.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Login flow state
+// ---------------------------------------------------------------------------
+
+/// The `state` and `nonce` of one login attempt.  Kept in the browser as a
+/// signed cookie, so the server holds nothing between start and callback.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LoginFlow {
+    state: String,
+    nonce: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FlowClaims {
+    sub: String,
+    iat: u64,
+    exp: u64,
+    #[serde(flatten)]
+    flow: LoginFlow,
+}
+
+impl LoginFlow {
+    fn new() -> Self {
+        Self { state: random_token(), nonce: random_token() }
+    }
+
+    /// Sign the flow for the cookie.
+    fn seal(&self, secret: &[u8], now: u64) -> Result<String, jsonwebtoken::errors::Error> {
+        let claims = FlowClaims {
+            sub: FLOW_SUBJECT.to_string(),
+            iat: now,
+            exp: now + FLOW_EXPIRY_SECS,
+            flow: self.clone(),
+        };
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret))
+    }
+
+    /// Recover the flow from its cookie.  None if the token is forged or
+    /// expired, or `returned_state` isn't the state it was started with.
+    fn open(secret: &[u8], token: &str, returned_state: &str) -> Option<Self> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_required_spec_claims(&["sub", "exp", "iat"]);
+        let claims = decode::<FlowClaims>(token, &DecodingKey::from_secret(secret), &validation)
+            .ok()?
+            .claims;
+        if claims.sub != FLOW_SUBJECT || returned_state.is_empty() || claims.flow.state != returned_state {
+            return None;
+        }
+        Some(claims.flow)
+    }
+}
+
+fn random_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    let cookies = headers.get(axum::http::header::COOKIE)?.to_str().ok()?;
+    cookies
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+// ---------------------------------------------------------------------------
+// ID token verification
+// ---------------------------------------------------------------------------
+
+/// Verify an ID token's signature against the provider's keys, then its
+/// claims.  Returns the claims.
+fn verify_id_token(
+    token: &str,
+    jwks: &JwkSet,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+    now: u64,
+) -> Result<serde_json::Value, String> {
+    let header = jsonwebtoken::decode_header(token).map_err(|e| format!("malformed ID token: {e}"))?;
+    if !SIGNING_ALGORITHMS.contains(&header.alg) {
+        return Err(format!("ID token signed with unsupported algorithm {:?}", header.alg));
+    }
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None => jwks.keys.first(),
+    }
+    .ok_or("no provider key matches the ID token")?;
+    let key = DecodingKey::from_jwk(jwk).map_err(|e| format!("unusable provider key: {e}"))?;
+
+    // Claims are checked below, where the nonce can be compared too.
+    let mut validation = Validation::new(header.alg);
+    validation.validate_exp = false;
+    validation.validate_aud = false;
+    validation.required_spec_claims.clear();
+    let claims = decode::<serde_json::Value>(token, &key, &validation)
+        .map_err(|e| format!("ID token signature is invalid: {e}"))?
+        .claims;
+
+    check_id_claims(&claims, issuer, client_id, nonce, now)?;
+    Ok(claims)
+}
+
+/// Check that the ID token was issued by `issuer` for `client_id` during
+/// this login attempt, and hasn't expired.
+fn check_id_claims(
+    claims: &serde_json::Value,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+    now: u64,
+) -> Result<(), String> {
+    let iss = claims.get("iss").and_then(|v| v.as_str()).unwrap_or_default();
+    if iss.trim_end_matches('/') != issuer.trim_end_matches('/') {
+        return Err(format!("ID token issuer {iss:?} is not {issuer:?}"));
+    }
+
+    let audience_ok = match claims.get("aud") {
+        Some(serde_json::Value::String(aud)) => aud == client_id,
+        Some(serde_json::Value::Array(auds)) => auds.iter().any(|a| a.as_str() == Some(client_id)),
+        _ => false,
+    };
+    if !audience_ok {
+        return Err("ID token was issued for a different client".into());
+    }
+
+    match claims.get("exp").and_then(|v| v.as_u64()) {
+        Some(exp) if exp + CLOCK_SKEW_SECS >= now => {}
+        Some(_) => return Err("ID token has expired".into()),
+        None => return Err("ID token has no expiry".into()),
+    }
+
+    if claims.get("nonce").and_then(|v| v.as_str()) != Some(nonce) {
+        return Err("ID token nonce does not match this login attempt".into());
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Identity → local user
+// ---------------------------------------------------------------------------
+
+/// Who the provider says signed in.
+#[derive(Debug, Clone, PartialEq)]
+struct OidcIdentity {
+    subject: String,
+    email: String,
+    email_verified: bool,
+    name: String,
+    preferred_username: String,
+}
+
+impl OidcIdentity {
+    /// Read the standard claims.  None without a subject.
+    fn from_claims(claims: &serde_json::Value) -> Option<Self> {
+        let text = |key: &str| claims.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let subject = text("sub");
+        if subject.is_empty() {
+            return None;
+        }
+        // Some providers send the flag as a string.
+        let email_verified = match claims.get("email_verified") {
+            Some(serde_json::Value::Bool(b)) => *b,
+            Some(serde_json::Value::String(s)) => s == "true",
+            _ => false,
+        };
+        Some(Self {
+            subject,
+            email: text("email"),
+            email_verified,
+            name: text("name"),
+            preferred_username: text("preferred_username"),
+        })
+    }
+}
+
+/// Map a verified identity to a local user, creating one when
+/// `auto_provision` is on.  The error is shown to the person signing in.
+async fn resolve_user(
+    users: &UserManager,
+    identity: &OidcIdentity,
+    config: &OidcConfig,
+    allowed_emails: &[String],
+) -> Result<User, String> {
+    let email = &identity.email;
+    if email.is_empty() || !identity.email_verified {
+        return Err("The provider did not return a verified email address.".into());
+    }
+    if !allowed_emails.is_empty() && !allowed_emails.iter().any(|e| e.eq_ignore_ascii_case(email)) {
+        return Err(format!("Your email ({email}) is not authorized to access this dashboard."));
+    }
+
+    if let Some(user) = users.get_by_email(email).await {
+        if !user.enabled {
+            return Err("Your account is disabled. Contact an administrator.".into());
+        }
+        users.touch(&user.id).await;
+        return Ok(user);
+    }
+
+    if !config.auto_provision {
+        return Err(format!("No account uses {email}. Ask an administrator to create one."));
+    }
+
+    let username = unused_username(users, identity).await;
+    let display_name = if identity.name.is_empty() { &username } else { &identity.name };
+    // Provisioned users sign in through the provider; the random password
+    // only keeps password login from working for them.
+    let user = users
+        .create(&username, display_name, UserRole::from_str(&config.default_role), &random_token())
+        .await
+        .map_err(|e| format!("Could not create your account: {e}"))?;
+    let user = users
+        .update(&user.id, None, None, Some(email), None)
+        .await
+        .map_err(|e| format!("Could not create your account: {e}"))?;
+    info!(username = %user.username, role = %user.role, subject = %identity.subject, "provisioned user from OIDC login");
+    Ok(user)
+}
+
+/// A free username based on the preferred username or email.
+async fn unused_username(users: &UserManager, identity: &OidcIdentity) -> String {
+    let source = if identity.preferred_username.is_empty() {
+        identity.email.split('@').next().unwrap_or_default()
+    } else {
+        &identity.preferred_username
+    };
+    let mut base: String = source
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if base.is_empty() {
+        base = "user".into();
+    }
+
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while users.get_by_username(&candidate).await.is_some() {
+        candidate = format!("{base}-{n}");
+        n += 1;
+    }
+    candidate
+}
+
+// ---------------------------------------------------------------------------
+// Provider requests
+// ---------------------------------------------------------------------------
+
+async fn fetch_json<T: serde::de::DeserializeOwned>(req: reqwest::RequestBuilder) -> Result<T, String> {
+    let resp = req
+        .header("Accept", "application/json")
+        .header("User-Agent", "safeclaw/1.0")
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("provider returned {status}: {body}"));
+    }
+    resp.json().await.map_err(|e| format!("invalid provider response: {e}"))
+}
+
+async fn exchange_code(
+    meta: &ProviderMetadata,
+    config: &OidcConfig,
+    client_secret: &str,
+    code: &str,
+) -> Result<serde_json::Value, String> {
+    let redirect_uri = callback_url();
+    let form = [
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("client_id", config.client_id.as_str()),
+        ("client_secret", client_secret),
+    ];
+    fetch_json(reqwest::Client::new().post(&meta.token_endpoint).form(&form)).await
+}
+
+/// Identity from an OIDC provider: the verified ID token, topped up from
+/// the userinfo endpoint when the token carries no email.
+async fn id_token_identity(
+    meta: &ProviderMetadata,
+    jwks_uri: &str,
+    config: &OidcConfig,
+    tokens: &serde_json::Value,
+    nonce: &str,
+) -> Result<OidcIdentity, String> {
+    let id_token = tokens
+        .get("id_token")
+        .and_then(|v| v.as_str())
+        .ok_or("The provider did not return an ID token.")?;
+    let jwks: JwkSet = fetch_json(reqwest::Client::new().get(jwks_uri)).await?;
+    let claims = verify_id_token(id_token, &jwks, &meta.issuer, &config.client_id, nonce, unix_now())?;
+    let mut identity = OidcIdentity::from_claims(&claims).ok_or("The ID token has no subject.")?;
+
+    if identity.email.is_empty() {
+        let access_token = tokens.get("access_token").and_then(|v| v.as_str());
+        if let (Some(url), Some(access_token)) = (&meta.userinfo_endpoint, access_token) {
+            let info: serde_json::Value =
+                fetch_json(reqwest::Client::new().get(url).bearer_auth(access_token)).await?;
+            // Userinfo must describe the same subject as the ID token.
+            if let Some(extra) = OidcIdentity::from_claims(&info).filter(|i| i.subject == identity.subject) {
+                identity.email = extra.email;
+                identity.email_verified = extra.email_verified;
+            }
+        }
+    }
+    Ok(identity)
+}
+
+/// Identity from GitHub's REST API, using the primary verified email.
+async fn github_identity(tokens: &serde_json::Value) -> Result<OidcIdentity, String> {
+    let access_token = tokens
+        .get("access_token")
+        .and_then(|v| v.as_str())
+        .ok_or("GitHub did not return an access token.")?;
+    let client = reqwest::Client::new();
+    let user: serde_json::Value =
+        fetch_json(client.get("https://api.github.com/user").bearer_auth(access_token)).await?;
+    let emails: Vec<serde_json::Value> =
+        fetch_json(client.get("https://api.github.com/user/emails").bearer_auth(access_token)).await?;
+
+    let email = emails
+        .iter()
+        .find(|e| e["primary"].as_bool() == Some(true) && e["verified"].as_bool() == Some(true))
+        .and_then(|e| e["email"].as_str())
+        .unwrap_or_default();
+    let subject = user["id"].as_u64().map(|id| id.to_string()).ok_or("GitHub returned no user id.")?;
+    Ok(OidcIdentity {
+        subject,
+        email: email.to_string(),
+        email_verified: !email.is_empty(),
+        name: user["name"].as_str().unwrap_or_default().to_string(),
+        preferred_username: user["login"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/auth/oidc/start
+// ---------------------------------------------------------------------------
+
+/// GET /api/auth/oidc/start — redirect to the provider's login page.
+pub async fn oidc_start(State(state): State<DashState>) -> Response {
+    let config = &state.config.dashboard.oidc;
+    if !config.enabled {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "OIDC login is not enabled" })))
+            .into_response();
+    }
+
+    let meta = match provider_metadata(config).await {
+        Ok(m) => m,
+        Err(e) => {
+            error!(err = %e, "OIDC discovery failed");
+            return (StatusCode::BAD_GATEWAY, Json(serde_json::json!({ "error": "OIDC provider unavailable" })))
+                .into_response();
+        }
+    };
+
+    let flow = LoginFlow::new();
+    let flow_token = match flow.seal(&state.jwt_secret, unix_now()) {
+        Ok(t) => t,
+        Err(e) => {
+            error!("failed to sign OIDC flow: {e}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": "internal error" })))
+                .into_response();
+        }
+    };
+
+    let mut url = match reqwest::Url::parse(&meta.authorization_endpoint) {
+        Ok(u) => u,
+        Err(e) => {
+            error!(err = %e, "OIDC authorization endpoint is not a URL");
+            return (StatusCode::BAD_GATEWAY, Json(serde_json::json!({ "error": "OIDC provider unavailable" })))
+                .into_response();
+        }
+    };
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &config.client_id)
+        .append_pair("redirect_uri", &callback_url())
+        .append_pair("scope", scopes(config))
+        .append_pair("state", &flow.state)
+        .append_pair("nonce", &flow.nonce);
+
+    let cookie = format!(
+        "{FLOW_COOKIE}={flow_token}; Path=/api/auth/oidc; HttpOnly; SameSite=Lax; Max-Age={FLOW_EXPIRY_SECS}"
+    );
+    info!(provider = %config.provider, "starting OIDC login flow");
+    ([(axum::http::header::SET_COOKIE, cookie)], Redirect::to(url.as_str())).into_response()
+}
+
+// ---------------------------------------------------------------------------
+// GET /api/auth/oidc/callback
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+pub struct OidcCallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+/// GET /api/auth/oidc/callback — verify the login and issue the session JWT.
+pub async fn oidc_callback(
+    State(state): State<DashState>,
+    Query(params): Query<OidcCallbackParams>,
+    headers: HeaderMap,
+) -> Response {
+    let clear_flow = format!("{FLOW_COOKIE}=; Path=/api/auth/oidc; HttpOnly; SameSite=Lax; Max-Age=0");
+    let config = &state.config.dashboard.oidc;
+
+    let user = match complete_login(&state, params, &headers).await {
+        Ok(u) => u,
+        Err(msg) => {
+            warn!(provider = %config.provider, err = %msg, "OIDC login failed");
+            let mut resp = auth::login_error_page(&msg);
+            resp.headers_mut()
+                .insert(axum::http::header::SET_COOKIE, clear_flow.parse().unwrap());
+            return resp;
+        }
+    };
+
+    let method = format!("oidc:{}", config.provider);
+    let token = match auth::mint_token_with_user(
+        &state.jwt_secret,
+        &user.username,
+        &method,
+        Some(&user.id),
+        Some(user.role.as_str()),
+    ) {
+        Ok(t) => t,
+        Err(e) => {
+            error!("failed to mint JWT for OIDC: {e}");
+            return auth::login_error_page("Internal error generating session.");
+        }
+    };
+
+    info!(provider = %config.provider, username = %user.username, "OIDC login successful");
+
+    let mut headers = auth::session_cookies(&state.jwt_secret, &token);
+    headers.append(axum::http::header::SET_COOKIE, clear_flow.parse().unwrap());
+    headers.insert(axum::http::header::LOCATION, "/".parse().unwrap());
+    (StatusCode::FOUND, headers).into_response()
+}
+
+async fn complete_login(
+    state: &DashState,
+    params: OidcCallbackParams,
+    headers: &HeaderMap,
+) -> Result<User, String> {
+    let config = &state.config.dashboard.oidc;
+    if !config.enabled {
+        return Err("OIDC login is not enabled.".into());
+    }
+    if let Some(err) = params.error {
+        return Err(format!("The provider returned an error: {err}"));
+    }
+
+    let flow_token = cookie_value(headers, FLOW_COOKIE).ok_or("Your login attempt expired. Please try again.")?;
+    let flow = LoginFlow::open(&state.jwt_secret, flow_token, params.state.as_deref().unwrap_or_default())
+        .ok_or("Login state did not match. Please try again.")?;
+    let code = params.code.ok_or("No authorization code received.")?;
+
+    let client_secret = state.config.secret("OIDC_CLIENT_SECRET").await.map_err(|e| {
+        error!(err = %e, "OIDC client secret unavailable");
+        "OIDC login is not configured.".to_string()
+    })?;
+    let meta = provider_metadata(config).await?;
+    let tokens = exchange_code(&meta, config, &client_secret, &code).await?;
+
+    let identity = match &meta.jwks_uri {
+        Some(jwks_uri) => id_token_identity(&meta, jwks_uri, config, &tokens, &flow.nonce).await?,
+        None => github_identity(&tokens).await?,
+    };
+
+    resolve_user(
+        &state.agent.user_manager,
+        &identity,
+        config,
+        &state.config.dashboard.sso_allowed_emails,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn test_users() -> UserManager {
+        let dir = std::env::temp_dir().join(format!("sa-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let enc = crate::crypto::FieldEncryptor::ensure_key(&dir).unwrap();
+        UserManager::new(crate::db::test_db(), enc)
+    }
+
+    fn identity(email: &str, verified: bool) -> OidcIdentity {
+        OidcIdentity::from_claims(&serde_json::json!({
+            "sub": "abc123",
+            "email": email,
+            "email_verified": verified,
+            "name": "Alice Example",
+            "preferred_username": "Alice",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn flow_cookie_requires_matching_state() {
+        let flow = LoginFlow::new();
+        assert_ne!(flow.state, flow.nonce);
+        let token = flow.seal(SECRET, unix_now()).unwrap();
+
+        assert_eq!(LoginFlow::open(SECRET, &token, &flow.state), Some(flow.clone()));
+        assert_eq!(LoginFlow::open(SECRET, &token, "other-state"), None);
+        assert_eq!(LoginFlow::open(SECRET, &token, ""), None);
+        assert_eq!(LoginFlow::open(b"wrong-secret", &token, &flow.state), None);
+
+        // Expired attempts are rejected.
+        let old = flow.seal(SECRET, unix_now() - 2 * FLOW_EXPIRY_SECS).unwrap();
+        assert_eq!(LoginFlow::open(SECRET, &old, &flow.state), None);
+
+        // A session JWT signed with the same secret is not a flow token.
+        let session = auth::mint_token_with_user(SECRET, "alice", "password", None, None).unwrap();
+        assert_eq!(LoginFlow::open(SECRET, &session, &flow.state), None);
+    }
+
+    #[test]
+    fn id_claims_must_match_nonce_issuer_and_audience() {
+        let now = unix_now();
+        let claims = serde_json::json!({
+            "iss": "https://id.example.com/",
+            "aud": ["other", "safeclaw"],
+            "exp": now + 300,
+            "nonce": "n-1",
+            "sub": "abc123",
+        });
+        assert!(check_id_claims(&claims, "https://id.example.com", "safeclaw", "n-1", now).is_ok());
+
+        let err = check_id_claims(&claims, "https://id.example.com", "safeclaw", "n-2", now).unwrap_err();
+        assert!(err.contains("nonce"), "{err}");
+        let mut no_nonce = claims.clone();
+        no_nonce.as_object_mut().unwrap().remove("nonce");
+        assert!(check_id_claims(&no_nonce, "https://id.example.com", "safeclaw", "n-1", now).is_err());
+
+        assert!(check_id_claims(&claims, "https://evil.example.com", "safeclaw", "n-1", now).is_err());
+        assert!(check_id_claims(&claims, "https://id.example.com", "another-app", "n-1", now).is_err());
+        let err = check_id_claims(&claims, "https://id.example.com", "safeclaw", "n-1", now + 1000).unwrap_err();
+        assert!(err.contains("expired"), "{err}");
+    }
+
+    #[test]
+    fn unsigned_or_symmetric_id_tokens_are_rejected() {
+        let token = encode(
+            &Header::default(),
+            &serde_json::json!({ "iss": "https://id.example.com", "aud": "safeclaw", "exp": unix_now() + 60, "nonce": "n" }),
+            &EncodingKey::from_secret(b"client-secret"),
+        )
+        .unwrap();
+        let jwks = JwkSet { keys: Vec::new() };
+        let err = verify_id_token(&token, &jwks, "https://id.example.com", "safeclaw", "n", unix_now()).unwrap_err();
+        assert!(err.contains("unsupported algorithm"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verified_identity_maps_to_existing_or_new_user() {
+        let users = test_users();
+        let config = OidcConfig { enabled: true, default_role: "user".into(), ..Default::default() };
+
+        // First login provisions a user with the default role.
+        let user = resolve_user(&users, &identity("alice@example.com", true), &config, &[]).await.unwrap();
+        assert_eq!(user.username, "alice");
+        assert_eq!(user.display_name, "Alice Example");
+        assert_eq!(user.email, "alice@example.com");
+        assert_eq!(user.role, UserRole::User);
+        assert!(users.authenticate("alice", "").await.is_none());
+
+        // Later logins find the same user.
+        let again = resolve_user(&users, &identity("alice@example.com", true), &config, &[]).await.unwrap();
+        assert_eq!(again.id, user.id);
+
+        // A different person with the same preferred username gets a fresh name.
+        let other = resolve_user(&users, &identity("alice@other.test", true), &config, &[]).await.unwrap();
+        assert_eq!(other.username, "alice-2");
+        assert_eq!(users.count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn unverified_unlisted_or_unknown_identities_are_refused() {
+        let users = test_users();
+        let config = OidcConfig { enabled: true, auto_provision: false, ..Default::default() };
+
+        let err = resolve_user(&users, &identity("bob@example.com", false), &config, &[]).await.unwrap_err();
+        assert!(err.contains("verified"), "{err}");
+
+        let allowed = vec!["carol@example.com".to_string()];
+        let err = resolve_user(&users, &identity("bob@example.com", true), &config, &allowed).await.unwrap_err();
+        assert!(err.contains("not authorized"), "{err}");
+
+        // Without auto-provisioning only existing users may sign in.
+        assert!(resolve_user(&users, &identity("bob@example.com", true), &config, &[]).await.is_err());
+        let bob = users.create("bob", "Bob", UserRole::Admin, "pw").await.unwrap();
+        users.update(&bob.id, None, None, Some("bob@example.com"), None).await.unwrap();
+        let user = resolve_user(&users, &identity("bob@example.com", true), &config, &[]).await.unwrap();
+        assert_eq!(user.id, bob.id);
+        assert_eq!(user.role, UserRole::Admin);
+
+        users.update(&bob.id, None, None, None, Some(false)).await.unwrap();
+        let err = resolve_user(&users, &identity("bob@example.com", true), &config, &[]).await.unwrap_err();
+        assert!(err.contains("disabled"), "{err}");
+    }
+}
@@ -0,0 +1,258 @@
+//! OpenAI-compatible chat endpoint, so existing OpenAI client tooling can
+//! talk to the agent.
+//!
+//! `POST /v1/chat/completions` takes the standard request body, runs the
+//! last user message through [`Agent::handle_message_as`] and answers with
+//! a `chat.completion` object.  Earlier messages in the array are ignored:
+//! the agent keeps its own conversation memory, one per API key holder, so
+//! clients don't see each other's context.  Clients authenticate with a
+//! per-user API key as `Authorization: Bearer <key>`; see
+//! [`super::auth::api_key_user`].  The key opens these routes only.
+
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::Extension;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::error;
+
+use crate::agent::attachments::Origin;
+use crate::agent::Agent;
+use crate::llm::context::estimate_tokens;
+use crate::users::UserContext;
+
+#[derive(Deserialize)]
+struct ChatCompletionRequest {
+    #[serde(default)]
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    role: String,
+    /// A string, or an array of content parts of which only `text` parts
+    /// are used.
+    #[serde(default)]
+    content: Value,
+}
+
+impl ChatMessage {
+    fn text(&self) -> String {
+        match &self.content {
+            Value::String(s) => s.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter(|p| p["type"] == "text")
+                .filter_map(|p| p["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// POST /v1/chat/completions
+pub async fn chat_completions(
+    State(agent): State<Arc<Agent>>,
+    user: Option<Extension<UserContext>>,
+    body: Bytes,
+) -> Response {
+    let request: ChatCompletionRequest = match serde_json::from_slice(&body) {
+        Ok(r) => r,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "invalid_request_error", &e.to_string()),
+    };
+    if request.stream {
+        return error_response(StatusCode::BAD_REQUEST, "invalid_request_error", "streaming is not supported");
+    }
+    let prompt = request
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .map(|m| m.text())
+        .unwrap_or_default();
+    if prompt.trim().is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "invalid_request_error", "no user message to answer");
+    }
+
+    let user = user.map(|Extension(u)| u);
+    // Each key holder gets a conversation of their own
+    let origin = user.as_ref().map(|u| Origin { platform: "api", channel: &u.user_id });
+    let reply = match agent.handle_message_with_attachments(prompt.trim(), &[], origin, user.as_ref()).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            error!("chat completion: {e}");
+            return error_response(e.status_code(), e.code(), &e.to_string());
+        }
+    };
+
+    let model = if request.model.is_empty() { agent.config.agent_name.clone() } else { request.model };
+    Json(completion(&model, &prompt, &reply)).into_response()
+}
+
+/// A `chat.completion` object for one reply.  Token counts are estimates.
+fn completion(model: &str, prompt: &str, reply: &str) -> Value {
+    let prompt_tokens = estimate_tokens(prompt);
+    let completion_tokens = estimate_tokens(reply);
+    json!({
+        "id": format!("chatcmpl-{}", uuid::Uuid::new_v4().simple()),
+        "object": "chat.completion",
+        "created": chrono::Utc::now().timestamp(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": reply },
+            "finish_reason": "stop",
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens,
+        },
+    })
+}
+
+/// Errors in OpenAI's `{"error": {...}}` shape, which its clients parse.
+fn error_response(status: StatusCode, kind: &str, message: &str) -> Response {
+    let body = json!({ "error": { "message": message, "type": kind, "code": null } });
+    (status, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::auth::{require_auth, RequireRole};
+    use crate::dashboard::routes::DashState;
+    use crate::error::Result;
+    use crate::users::UserRole;
+    use axum::middleware;
+
+    struct FixedLlm;
+
+    #[async_trait::async_trait]
+    impl crate::llm::LlmBackend for FixedLlm {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn generate(&self, _ctx: &crate::llm::GenerateContext<'_>) -> Result<String> {
+            Ok("Pong.".to_string())
+        }
+    }
+
+    /// The dashboard state `require_auth` needs, around `agent`.
+    fn dash_state(
+        dir: &std::path::Path,
+        agent: Arc<Agent>,
+        db: Arc<tokio::sync::Mutex<rusqlite::Connection>>,
+    ) -> DashState {
+        DashState {
+            config: agent.config.clone(),
+            read_db: crate::db::ReadPool::for_writer(&db, None, 1).unwrap(),
+            db,
+            dashboard_password: String::new(),
+            jwt_secret: b"openai-test-secret".to_vec(),
+            extension_manager: Arc::new(tokio::sync::Mutex::new(crate::skills::extensions::ExtensionManager::new(
+                dir.join("skills"),
+                dir.join("safeclaw.db"),
+                agent.skill_tokens.clone(),
+            ))),
+            messaging: Arc::new(crate::messaging::MessagingManager::new()),
+            trash: Arc::new(crate::trash::TrashManager::new(dir).unwrap()),
+            passkey_manager: None,
+            installer: crate::installer::BinaryInstaller::new(dir.join("bin"), dir),
+            agent,
+        }
+    }
+
+    #[tokio::test]
+    async fn chat_completions_answers_in_openai_shape_for_api_key_holders() {
+        use axum::routing::{get, post};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = crate::config::Config::default();
+        config.memory.auto_extract = false;
+        let mut agent = crate::agent::test_agent_with_tools(
+            dir.path(),
+            config,
+            crate::tools::ToolRegistry::new(),
+            crate::messaging::MessagingManager::new(),
+        )
+        .await;
+        let db = agent.ctx.db.clone();
+        agent.llm = crate::llm::LlmEngine::with_backend("fixed", Arc::new(FixedLlm));
+        let olga = agent.user_manager.create("olga", "Olga", UserRole::User, "pw").await.unwrap();
+        let key = agent.user_manager.issue_api_key(&olga.id).await.unwrap();
+        let piet = agent.user_manager.create("piet", "Piet", UserRole::User, "pw").await.unwrap();
+        let other_key = agent.user_manager.issue_api_key(&piet.id).await.unwrap();
+        let agent = Arc::new(agent);
+
+        let state = dash_state(dir.path(), agent.clone(), db);
+        let app = axum::Router::new()
+            .route("/v1/chat/completions", post(chat_completions).require_role(UserRole::User))
+            .route("/api/stats", get(|| async { "stats" }))
+            .layer(middleware::from_fn_with_state(state.clone(), require_auth))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let url = format!("{base}/v1/chat/completions");
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let body = json!({
+            "model": "safeclaw",
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "Ping?" },
+            ],
+        });
+        let client = reqwest::Client::new();
+        let resp = client.post(&url).bearer_auth(&key).json(&body).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let reply: Value = resp.json().await.unwrap();
+        assert_eq!(reply["object"], "chat.completion");
+        assert_eq!(reply["model"], "safeclaw");
+        assert!(reply["id"].as_str().unwrap().starts_with("chatcmpl-"));
+        assert_eq!(reply["choices"][0]["message"]["role"], "assistant");
+        assert_eq!(reply["choices"][0]["message"]["content"], "Pong.");
+        assert_eq!(reply["choices"][0]["finish_reason"], "stop");
+        let usage = &reply["usage"];
+        assert_eq!(
+            usage["total_tokens"].as_u64().unwrap(),
+            usage["prompt_tokens"].as_u64().unwrap() + usage["completion_tokens"].as_u64().unwrap()
+        );
+
+        let resp = client.post(&url).json(&body).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = client.post(&url).bearer_auth("sa-forged").json(&body).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // The key opens the OpenAI routes only, not the dashboard API
+        let resp = client.get(format!("{base}/api/stats")).bearer_auth(&key).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let no_user = json!({ "messages": [{ "role": "system", "content": "hi" }] });
+        let resp = client.post(&url).bearer_auth(&key).json(&no_user).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let reply: Value = resp.json().await.unwrap();
+        assert_eq!(reply["error"]["type"], "invalid_request_error");
+
+        // Each key holder has a conversation of their own
+        let private = json!({ "messages": [{ "role": "user", "content": "my secret plan" }] });
+        let resp = client.post(&url).bearer_auth(&other_key).json(&private).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let conversation = &agent.memory.conversation;
+        let olgas = conversation.recent_in(Some(&format!("api:{}", olga.id))).await.unwrap();
+        assert!(olgas.iter().any(|m| m.content == "Ping?"));
+        assert!(!olgas.iter().any(|m| m.content.contains("secret plan")));
+        let piets = conversation.recent_in(Some(&format!("api:{}", piet.id))).await.unwrap();
+        assert!(piets.iter().any(|m| m.content == "my secret plan"));
+        assert!(conversation.recent_in(None).await.unwrap().is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use axum::extract::DefaultBodyLimit;
 use axum::middleware;
 use axum::routing::{any, delete, get, post, put};
 use axum::Router;
@@ -8,26 +9,36 @@ use tokio::sync::Mutex;
 
 use crate::agent::Agent;
 use crate::config::Config;
+use crate::db::ReadPool;
 use crate::dashboard::authn::PasskeyManager;
 use crate::error::{Result, SafeAgentError};
 use crate::installer::BinaryInstaller;
 use crate::messaging::MessagingManager;
 use crate::skills::ExtensionManager;
 use crate::trash::TrashManager;
+use crate::users::UserRole;
 
-use super::auth;
+use super::auth::{self, RequireRole};
 use super::handlers;
 use super::messaging_webhook;
 use super::oauth;
+use super::oidc;
+use super::openai;
 use super::skill_ext;
 use super::sse;
 
+/// Read-only database connections opened for the dashboard.
+const READ_POOL_SIZE: usize = 4;
+
 /// State shared across all routes.
 #[derive(Clone)]
 pub struct DashState {
     pub agent: Arc<Agent>,
     pub config: Config,
     pub db: Arc<Mutex<Connection>>,
+    /// Read-only connections for handlers that only query, so they don't
+    /// wait on the agent loop's writes.
+    pub read_db: ReadPool,
     /// The password users must provide to access the dashboard.
     pub dashboard_password: String,
     /// Secret bytes used to sign/verify HS256 JWT cookies.
@@ -44,7 +55,13 @@ pub struct DashState {
     pub installer: BinaryInstaller,
 }
 
-pub fn build(
+impl axum::extract::FromRef<DashState> for Arc<Agent> {
+    fn from_ref(state: &DashState) -> Self {
+        state.agent.clone()
+    }
+}
+
+pub async fn build(
     agent: Arc<Agent>,
     config: Config,
     db: Arc<Mutex<Connection>>,
@@ -53,11 +70,13 @@ pub fn build(
     installer: BinaryInstaller,
 ) -> Result<Router> {
     let password_required = config.dashboard.password_enabled
-        && config.dashboard.sso_providers.is_empty();
+        && config.dashboard.sso_providers.is_empty()
+        && !config.dashboard.oidc.enabled;
 
-    let dashboard_password = std::env::var("DASHBOARD_PASSWORD")
+    let dashboard_password = config
+        .secret("DASHBOARD_PASSWORD")
+        .await
         .ok()
-        .filter(|s| !s.is_empty())
         .or_else(|| {
             if password_required {
                 None
@@ -68,18 +87,11 @@ pub fn build(
         })
         .ok_or_else(|| {
             SafeAgentError::Config(
-                "DASHBOARD_PASSWORD environment variable is required but not set".to_string(),
+                "DASHBOARD_PASSWORD is required but not set".to_string(),
             )
         })?;
 
-    let jwt_secret_str = std::env::var("JWT_SECRET")
-        .ok()
-        .filter(|s| !s.is_empty())
-        .ok_or_else(|| {
-            SafeAgentError::Config(
-                "JWT_SECRET environment variable is required but not set".to_string(),
-            )
-        })?;
+    let jwt_secret_str = config.secret("JWT_SECRET").await?;
 
     let jwt_secret = jwt_secret_str.into_bytes();
 
@@ -88,7 +100,8 @@ pub fn build(
     // Initialize skill extension manager
     let skills_dir = Config::data_dir().join("skills");
     let db_path = Config::data_dir().join("safeclaw.db");
-    let mut ext_mgr = ExtensionManager::new(skills_dir, db_path);
+    let mut ext_mgr = ExtensionManager::new(skills_dir, db_path, agent.skill_tokens.clone());
+    ext_mgr.set_limits(config.rhai.clone());
     ext_mgr.discover();
 
     // Attempt to build a PasskeyManager for WebAuthn support.
@@ -122,10 +135,16 @@ pub fn build(
         }
     };
 
+    let read_db = {
+        let path = db.lock().await.path().map(str::to_string);
+        ReadPool::for_writer(&db, path.as_deref(), READ_POOL_SIZE)?
+    };
+
     let state = DashState {
         agent,
         config,
         db,
+        read_db,
         dashboard_password,
         jwt_secret,
         extension_manager: Arc::new(Mutex::new(ext_mgr)),
@@ -151,12 +170,16 @@ pub fn build(
         .route("/api/auth/logout", post(auth::logout))
         .route("/api/auth/sso/{provider}/start", get(auth::sso_start))
         .route("/api/auth/sso/{provider}/callback", get(auth::sso_callback))
+        .route("/api/auth/oidc/start", get(oidc::oidc_start))
+        .route("/api/auth/oidc/callback", get(oidc::oidc_callback))
         // 2FA / Passkey authentication endpoints
         .route("/api/auth/2fa/verify", post(auth::verify_2fa))
         .route("/api/auth/2fa/setup", post(auth::setup_totp))
         .route("/api/auth/2fa/enable", post(auth::enable_totp))
         .route("/api/auth/2fa/disable", post(auth::disable_totp))
         .route("/api/auth/2fa/status", get(auth::totp_status))
+        .route("/api/auth/api-key", post(auth::issue_api_key))
+        .route("/api/auth/api-key", delete(auth::revoke_api_key))
         .route("/api/auth/passkey/register/start", post(auth::passkey_register_start))
         .route("/api/auth/passkey/register/finish", post(auth::passkey_register_finish))
         .route("/api/auth/passkey/authenticate/start", post(auth::passkey_auth_start))
@@ -166,22 +189,42 @@ pub fn build(
         // API — Status & Control
         .route("/api/status", get(handlers::get_status))
         .route("/api/stats", get(handlers::get_stats))
-        .route("/api/agent/pause", post(handlers::pause_agent))
-        .route("/api/agent/resume", post(handlers::resume_agent))
-        .route("/api/agent/tick", post(handlers::force_tick))
+        .route("/api/agent/pause", post(handlers::pause_agent).require_role(UserRole::Admin))
+        .route("/api/agent/resume", post(handlers::resume_agent).require_role(UserRole::Admin))
+        .route("/api/kill", post(handlers::kill_switch).require_role(UserRole::Admin))
+        .route("/api/safe-mode", get(handlers::get_safe_mode))
+        .route("/api/safe-mode", post(handlers::set_safe_mode).require_role(UserRole::Admin))
+        .route("/api/agent/tick", post(handlers::force_tick).require_role(UserRole::User))
         // API — Approval Queue
         .route("/api/pending", get(handlers::get_pending))
-        .route("/api/pending/{id}/approve", post(handlers::approve_action))
-        .route("/api/pending/{id}/reject", post(handlers::reject_action))
-        .route("/api/pending/approve-all", post(handlers::approve_all))
-        .route("/api/pending/reject-all", post(handlers::reject_all))
+        .route("/api/pending/{id}/approve", post(handlers::approve_action).require_role(UserRole::User))
+        .route("/api/pending/{id}/reject", post(handlers::reject_action).require_role(UserRole::User))
+        .route("/api/pending/approve-all", post(handlers::approve_all).require_role(UserRole::User))
+        .route("/api/pending/reject-all", post(handlers::reject_all).require_role(UserRole::User))
         // API — Activity
         .route("/api/activity", get(handlers::get_activity))
+        .route("/api/config/reload", post(handlers::reload_config).require_role(UserRole::Admin))
+        .route("/api/config/presets", get(handlers::list_config_presets).require_role(UserRole::Admin))
+        .route("/api/config/presets/{name}", put(handlers::save_config_preset).require_role(UserRole::Admin))
+        .route("/api/config/presets/{name}", delete(handlers::delete_config_preset).require_role(UserRole::Admin))
+        .route("/api/config/presets/{name}/apply", post(handlers::apply_config_preset).require_role(UserRole::Admin))
+        // API — Personas
+        .route("/api/persona/personas", get(handlers::list_personas))
+        .route("/api/persona/personas/{name}", put(handlers::save_persona).require_role(UserRole::Admin))
+        .route("/api/persona/personas/{name}", delete(handlers::delete_persona).require_role(UserRole::Admin))
+        .route("/api/persona/active", put(handlers::set_active_persona).require_role(UserRole::User))
         // API — Memory
         .route("/api/memory/core", get(handlers::get_core_memory))
         .route("/api/memory/conversation", get(handlers::get_conversation_memory))
         .route("/api/memory/archival", get(handlers::search_archival_memory))
         .route("/api/memory/conversation/history", get(handlers::conversation_history))
+        .route("/api/conversation/export", get(handlers::export_conversation))
+        .route("/api/conversation/branches", get(handlers::list_conversation_branches))
+        .route("/api/conversation/branches", post(handlers::fork_conversation).require_role(UserRole::User))
+        .route("/api/conversation/branches/active", put(handlers::switch_conversation_branch).require_role(UserRole::User))
+        .route("/api/memory/episodes", get(handlers::query_episodes))
+        .route("/api/memory/consolidate", get(handlers::get_consolidation_status))
+        .route("/api/memory/consolidate", post(handlers::consolidate_memory).require_role(UserRole::User))
         // API — Knowledge Graph
         .route("/api/knowledge/nodes", get(handlers::get_knowledge_nodes))
         .route("/api/knowledge/nodes/{id}", get(handlers::get_knowledge_node))
@@ -190,38 +233,48 @@ pub fn build(
         .route("/api/knowledge/stats", get(handlers::get_knowledge_stats))
         // API — Tools
         .route("/api/tools", get(handlers::list_tools))
+        .route("/api/tools/schema", get(handlers::tool_schemas))
+        .route("/api/capabilities", get(handlers::get_capabilities))
         // API — Chat
-        .route("/api/chat", post(handlers::send_chat_message))
+        .route("/api/chat", post(handlers::send_chat_message).require_role(UserRole::User))
+        // OpenAI-compatible chat, for clients holding an API key
+        .route("/v1/chat/completions", post(openai::chat_completions).require_role(UserRole::User))
         // API — Skills & Credentials
         .route("/api/skills", get(handlers::list_skills))
-        .route("/api/skills/import", post(handlers::import_skill))
-        .route("/api/skills/{name}", delete(handlers::delete_skill))
-        .route("/api/skills/{name}/credentials", get(handlers::get_skill_credentials))
-        .route("/api/skills/{name}/credentials", put(handlers::set_skill_credential))
-        .route("/api/skills/{name}/credentials/{key}", delete(handlers::delete_skill_credential))
-        .route("/api/skills/{name}/stop", post(handlers::stop_skill))
-        .route("/api/skills/{name}/start", post(handlers::start_skill))
-        .route("/api/skills/{name}/restart", post(handlers::restart_skill))
+        .route("/api/skills/import", post(handlers::import_skill).require_role(UserRole::Admin))
+        .route("/api/skills/{name}", delete(handlers::delete_skill).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/credentials", get(handlers::get_skill_credentials).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/credentials", put(handlers::set_skill_credential).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/credentials/{key}", delete(handlers::delete_skill_credential).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/stop", post(handlers::stop_skill).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/start", post(handlers::start_skill).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/restart", post(handlers::restart_skill).require_role(UserRole::Admin))
         .route("/api/skills/{name}/detail", get(handlers::get_skill_detail))
         .route("/api/skills/{name}/log", get(handlers::get_skill_log))
-        .route("/api/skills/{name}/manifest", put(handlers::update_skill_manifest))
-        .route("/api/skills/{name}/enabled", put(handlers::set_skill_enabled))
-        .route("/api/skills/{name}/env", put(handlers::set_skill_env_var))
-        .route("/api/skills/{name}/env/{key}", delete(handlers::delete_skill_env_var))
+        .route("/api/skills/{name}/logs/stream", get(sse::skill_log))
+        .route("/api/skills/{name}/manifest", put(handlers::update_skill_manifest).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/manifest/history", get(handlers::get_skill_manifest_history).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/manifest/revert", post(handlers::revert_skill_manifest).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/enabled", put(handlers::set_skill_enabled).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/env", put(handlers::set_skill_env_var).require_role(UserRole::Admin))
+        .route("/api/skills/{name}/env/{key}", delete(handlers::delete_skill_env_var).require_role(UserRole::Admin))
         // OAuth — generic multi-provider (start/callback exempt from auth in auth.rs)
         .route("/oauth/{provider}/start", get(oauth::oauth_start))
         .route("/oauth/{provider}/callback", get(oauth::oauth_callback))
         .route("/api/oauth/status", get(oauth::all_oauth_status))
         .route("/api/oauth/providers", get(oauth::list_providers))
-        .route("/api/oauth/{provider}/refresh", post(oauth::oauth_refresh))
-        .route("/api/oauth/{provider}/disconnect/{account}", post(oauth::oauth_disconnect))
+        .route("/api/oauth/{provider}/refresh", post(oauth::oauth_refresh).require_role(UserRole::Admin))
+        .route("/api/oauth/{provider}/disconnect/{account}", post(oauth::oauth_disconnect).require_role(UserRole::Admin))
         // API — Skill Extensions (Rhai routes + static files)
         .route("/api/skills/extensions", get(skill_ext::list_extensions))
         .route("/api/skills/{name}/ext/{*path}", any(skill_ext::skill_ext_handler))
         .route("/skills/{name}/ui/{*path}", get(skill_ext::skill_static_file))
         .route("/skills/{name}/page", get(skill_ext::skill_page))
         // API — Messaging (webhook + WhatsApp QR + config)
-        .route("/api/messaging/incoming", post(messaging_webhook::incoming))
+        .route(
+            "/api/messaging/incoming",
+            post(messaging_webhook::incoming).layer(DefaultBodyLimit::max(messaging_webhook::MAX_INCOMING_BYTES)),
+        )
         .route("/api/messaging/config", get(messaging_webhook::messaging_config))
         .route("/api/messaging/whatsapp/status", get(messaging_webhook::whatsapp_status))
         .route("/api/messaging/whatsapp/qr", get(messaging_webhook::whatsapp_qr))
@@ -229,18 +282,27 @@ pub fn build(
         .route("/api/messaging/twilio/incoming", post(messaging_webhook::twilio_incoming))
         // API — Goals
         .route("/api/goals", get(handlers::list_goals))
+        .route("/api/goals", post(handlers::create_goal).require_role(UserRole::User))
         .route("/api/goals/{id}", get(handlers::get_goal))
-        .route("/api/goals/{id}/status", put(handlers::update_goal_status))
+        .route("/api/goals/{id}", delete(handlers::delete_goal).require_role(UserRole::Admin))
+        .route("/api/goals/{id}/tasks", post(handlers::add_goal_task).require_role(UserRole::User))
+        .route("/api/goals/{id}/status", put(handlers::update_goal_status).require_role(UserRole::User))
         // API — Trash
         .route("/api/trash", get(handlers::list_trash))
         .route("/api/trash/stats", get(handlers::trash_stats))
-        .route("/api/trash/empty", post(handlers::empty_trash))
-        .route("/api/trash/{id}/restore", post(handlers::restore_trash))
-        .route("/api/trash/{id}", delete(handlers::permanent_delete_trash))
+        .route("/api/trash/empty", post(handlers::empty_trash).require_role(UserRole::Admin))
+        .route("/api/trash/{id}/restore", post(handlers::restore_trash).require_role(UserRole::User))
+        .route("/api/trash/{id}", delete(handlers::permanent_delete_trash).require_role(UserRole::Admin))
         // API — Security: Audit Trail
         .route("/api/security/audit", get(handlers::get_audit_log))
         .route("/api/security/audit/summary", get(handlers::get_audit_summary))
+        .route("/api/security/audit/verify", get(handlers::verify_audit_chain))
         .route("/api/security/audit/{id}/explain", get(handlers::explain_action))
+        .route("/api/security/audit/{id}/trace", get(handlers::get_action_trace).require_role(UserRole::Admin))
+        .route("/api/security/audit/{id}/replay", post(handlers::replay_audit_entry).require_role(UserRole::Admin))
+        // API — Reasoning traces
+        .route("/api/traces", get(handlers::list_traces).require_role(UserRole::Admin))
+        .route("/api/traces/{turn_id}", get(handlers::get_trace).require_role(UserRole::Admin))
         // API — Security: Cost Tracking
         .route("/api/security/cost", get(handlers::get_cost_summary))
         .route("/api/security/cost/recent", get(handlers::get_cost_recent))
@@ -248,34 +310,36 @@ pub fn build(
         .route("/api/security/rate-limit", get(handlers::get_rate_limit_status))
         // API — Security: 2FA
         .route("/api/security/2fa", get(handlers::get_2fa_challenges))
-        .route("/api/security/2fa/{id}/confirm", post(handlers::confirm_2fa))
-        .route("/api/security/2fa/{id}/reject", post(handlers::reject_2fa))
+        .route("/api/security/2fa/{id}/confirm", post(handlers::confirm_2fa).require_role(UserRole::User))
+        .route("/api/security/2fa/{id}/reject", post(handlers::reject_2fa).require_role(UserRole::User))
         // API — Security: Overview
         .route("/api/security/overview", get(handlers::get_security_overview))
         // API — Tool Events (streaming progress)
         .route("/api/tool-events", get(handlers::get_tool_events))
+        // API — Debug
+        .route("/api/debug/last-prompt", get(handlers::get_last_prompts).require_role(UserRole::Admin))
         // API — Tunnel
         .route("/api/tunnel/status", get(handlers::tunnel_status))
         // API — Binaries (install/uninstall tool binaries)
         .route("/api/binaries", get(super::binaries::list_binaries))
         .route("/api/binaries/{name}", get(super::binaries::get_binary))
-        .route("/api/binaries/{name}", post(super::binaries::install_binary))
-        .route("/api/binaries/{name}", delete(super::binaries::uninstall_binary))
+        .route("/api/binaries/{name}", post(super::binaries::install_binary).require_role(UserRole::Admin))
+        .route("/api/binaries/{name}", delete(super::binaries::uninstall_binary).require_role(UserRole::Admin))
         // API — Backup & Restore
-        .route("/api/backup", get(handlers::create_backup))
-        .route("/api/restore", post(handlers::restore_backup))
+        .route("/api/backup", get(handlers::create_backup).require_role(UserRole::Admin))
+        .route("/api/restore", post(handlers::restore_backup).require_role(UserRole::Admin))
         // API — Updates
         .route("/api/update/check", get(handlers::check_update))
-        .route("/api/update/apply", post(handlers::trigger_update))
+        .route("/api/update/apply", post(handlers::trigger_update).require_role(UserRole::Admin))
         // API — Users (multi-user management)
-        .route("/api/users", get(handlers::list_users))
-        .route("/api/users", post(handlers::create_user))
-        .route("/api/users/{id}", get(handlers::get_user))
-        .route("/api/users/{id}", put(handlers::update_user))
-        .route("/api/users/{id}", delete(handlers::delete_user))
+        .route("/api/users", get(handlers::list_users).require_role(UserRole::Admin))
+        .route("/api/users", post(handlers::create_user).require_role(UserRole::Admin))
+        .route("/api/users/{id}", get(handlers::get_user).require_role(UserRole::Admin))
+        .route("/api/users/{id}", put(handlers::update_user).require_role(UserRole::Admin))
+        .route("/api/users/{id}", delete(handlers::delete_user).require_role(UserRole::Admin))
         // API — Timezone & Locale
         .route("/api/timezone", get(handlers::get_timezone))
-        .route("/api/timezone", post(handlers::set_timezone))
+        .route("/api/timezone", post(handlers::set_timezone).require_role(UserRole::Admin))
         .route("/api/timezones", get(handlers::list_timezones))
         .route("/api/timezone/convert", get(handlers::convert_time))
         // API — LLM Backends (plugin architecture)
@@ -284,17 +348,19 @@ pub fn build(
         .route("/api/llm/advisor/system", get(handlers::llm_system_specs))
         .route("/api/llm/advisor/recommend", get(handlers::llm_recommend))
         .route("/api/llm/ollama/status", get(handlers::ollama_status))
-        .route("/api/llm/ollama/pull", post(handlers::ollama_pull))
-        .route("/api/llm/ollama/models/{tag}", delete(handlers::ollama_delete))
-        .route("/api/llm/ollama/configure", post(handlers::ollama_configure))
+        .route("/api/llm/ollama/pull", post(handlers::ollama_pull).require_role(UserRole::Admin))
+        .route("/api/llm/ollama/models/{tag}", delete(handlers::ollama_delete).require_role(UserRole::Admin))
+        .route("/api/llm/ollama/configure", post(handlers::ollama_configure).require_role(UserRole::Admin))
         // API — Federation
         .route("/api/federation/status", get(handlers::federation_status))
         .route("/api/federation/peers", get(handlers::federation_peers))
-        .route("/api/federation/peers", post(handlers::federation_add_peer))
-        .route("/api/federation/peers/{id}", delete(handlers::federation_remove_peer))
+        .route("/api/federation/peers", post(handlers::federation_add_peer).require_role(UserRole::Admin))
+        .route("/api/federation/peers/health", get(handlers::federation_peer_health))
+        .route("/api/federation/peers/{id}", delete(handlers::federation_remove_peer).require_role(UserRole::Admin))
         // SSE
         .route("/api/events", get(sse::events))
-        // Auth middleware — applied to all routes above
+        // Auth middleware — applied to all routes above.  It attaches the
+        // signed-in user, which `require_role` guards check.
         .layer(middleware::from_fn_with_state(state.clone(), auth::require_auth))
         // Unauthenticated endpoints (health check, metrics, federation sync, onboarding) — below auth layer
         .route("/healthz", get(handlers::healthz))
@@ -306,6 +372,11 @@ pub fn build(
         .route("/api/persona", get(handlers::get_persona))
         .route("/api/persona", put(handlers::update_persona))
         .route("/metrics", get(handlers::metrics))
+        // Messaging provider webhooks — verified by provider signature instead
+        .route("/webhooks/twilio", post(messaging_webhook::twilio_webhook))
+        .route("/webhooks/whatsapp", get(messaging_webhook::whatsapp_webhook_verify))
+        .route("/webhooks/whatsapp", post(messaging_webhook::whatsapp_webhook))
+        .route("/api/federation/identity", get(handlers::federation_identity))
         .route("/api/federation/sync", post(handlers::federation_receive_sync))
         .route("/api/federation/heartbeat", post(handlers::federation_receive_heartbeat))
         .route("/api/federation/claim", post(handlers::federation_receive_claim))
@@ -2,11 +2,12 @@ use std::collections::HashMap;
 
 use axum::body::Body;
 use axum::extract::{Path, Query, State};
-use axum::http::{header, HeaderMap, StatusCode};
+use axum::http::{header, HeaderMap, Request, StatusCode};
 use axum::response::{Json, Response};
 
 use super::routes::DashState;
 use crate::skills::extensions::SkillExtensionInfo;
+use crate::skills::token::SKILL_TOKEN_HEADER;
 
 // ---------------------------------------------------------------------------
 // GET /api/skills/extensions — list all skill extensions
@@ -33,10 +34,30 @@ pub async fn skill_ext_handler(
 ) -> Response<Body> {
     let ext_mgr = state.extension_manager.lock().await;
 
+    // Every request runs under the skill's capability token.  A stopped
+    // skill has no token at all, which is unavailability, not a denial.
+    let presented = headers.get(SKILL_TOKEN_HEADER).and_then(|v| v.to_str().ok());
+    let Some(token) = ext_mgr.authorize(&skill_name, presented) else {
+        let (status, error) = if ext_mgr.is_running(&skill_name) {
+            (StatusCode::FORBIDDEN, "skill token missing, invalid, or revoked".to_string())
+        } else {
+            (StatusCode::SERVICE_UNAVAILABLE, format!("skill '{skill_name}' is not running"))
+        };
+        let body = serde_json::json!({"error": error}).to_string();
+        return Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+    };
+
     let path = format!("/{}", ext_path.trim_start_matches('/'));
 
     let mut header_map = HashMap::new();
     for (key, value) in headers.iter() {
+        if key == SKILL_TOKEN_HEADER {
+            continue;
+        }
         if let Ok(v) = value.to_str() {
             header_map.insert(key.to_string(), v.to_string());
         }
@@ -44,7 +65,7 @@ pub async fn skill_ext_handler(
 
     match ext_mgr
         .handle_request(
-            &skill_name,
+            &token,
             method.as_str(),
             &path,
             &query_params,
@@ -82,6 +103,25 @@ pub async fn skill_ext_handler(
     }
 }
 
+/// Whether `req` is a skill calling its own extension routes with a valid
+/// token.  Such requests skip dashboard authentication.
+pub fn has_valid_skill_token<B>(state: &DashState, req: &Request<B>) -> bool {
+    let Some(skill_name) = req
+        .uri()
+        .path()
+        .strip_prefix("/api/skills/")
+        .and_then(|rest| rest.split_once("/ext/"))
+        .map(|(name, _)| name)
+    else {
+        return false;
+    };
+
+    req.headers()
+        .get(SKILL_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|secret| state.agent.skill_tokens.validate(skill_name, secret).is_some())
+}
+
 // ---------------------------------------------------------------------------
 // GET /skills/{name}/ui/{*path} — serve static files from skill directories
 // ---------------------------------------------------------------------------
@@ -1,19 +1,110 @@
+use std::collections::{HashSet, VecDeque};
 use std::convert::Infallible;
+use std::future::Future;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use axum::extract::State;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::sse::{Event, Sse};
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::broadcast;
 use tokio_stream::wrappers::BroadcastStream;
-use tokio_stream::StreamExt;
+use tokio_stream::{Stream, StreamExt};
+use tracing::error;
 
 use super::routes::DashState;
+use crate::agent::events::SseMessage;
 
+/// How often the log stream checks the file for new output.
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EventsQuery {
+    /// Comma-separated event types to forward, e.g.
+    /// `approval_needed,2fa_challenge`.  Everything when absent.
+    pub types: Option<String>,
+}
+
+/// Which agent events a subscriber wants.
+#[derive(Debug, Default)]
+struct EventFilter {
+    /// `None` forwards everything.
+    types: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    fn parse(types: Option<&str>) -> Self {
+        let types: HashSet<String> = types
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self { types: (!types.is_empty()).then_some(types) }
+    }
+
+    /// Whether `data` should be forwarded.  Structured events are matched
+    /// on their JSON `type`; bare messages like `update` on their text.
+    fn matches(&self, data: &str) -> bool {
+        let Some(types) = &self.types else {
+            return true;
+        };
+        match serde_json::from_str::<serde_json::Value>(data) {
+            Ok(serde_json::Value::Object(obj)) => {
+                obj.get("type").and_then(|t| t.as_str()).is_some_and(|t| types.contains(t))
+            }
+            _ => types.contains(data),
+        }
+    }
+}
+
+/// `replay`, then live messages from `rx`, both passed through `filter`.
+/// Live events the replay already covered are dropped; lagged messages
+/// are skipped.
+fn filtered_events(
+    replay: Vec<SseMessage>,
+    rx: broadcast::Receiver<SseMessage>,
+    filter: EventFilter,
+) -> impl Stream<Item = SseMessage> {
+    let replayed_up_to = replay.iter().filter_map(|m| m.id).max().unwrap_or(0);
+    let live = BroadcastStream::new(rx).filter_map(move |msg| match msg {
+        Ok(msg) if msg.id.is_none_or(|id| id > replayed_up_to) => Some(msg),
+        _ => None,
+    });
+    tokio_stream::iter(replay)
+        .chain(live)
+        .filter(move |msg| filter.matches(&msg.data))
+}
+
+/// GET /api/events[?types=a,b]
+///
+/// The agent's event stream, optionally narrowed to the listed event types.
+/// Events carry their sequence id as the SSE `id`; a client reconnecting
+/// with `Last-Event-ID` first gets the buffered events it missed.
 pub async fn events(
     State(state): State<DashState>,
+    Query(params): Query<EventsQuery>,
+    headers: HeaderMap,
 ) -> Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>> {
-    let rx = state.agent.subscribe_sse();
-    let stream = BroadcastStream::new(rx).filter_map(|msg| match msg {
-        Ok(data) => Some(Ok(Event::default().data(data))),
-        Err(_) => None,
+    let last_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    let (replay, rx) = match last_id {
+        Some(id) => state.agent.subscribe_sse_after(id),
+        None => (Vec::new(), state.agent.subscribe_sse()),
+    };
+    let filter = EventFilter::parse(params.types.as_deref());
+    let stream = filtered_events(replay, rx, filter).map(|msg| {
+        let event = Event::default().data(msg.data);
+        Ok(match msg.id {
+            Some(id) => event.id(id.to_string()),
+            None => event,
+        })
     });
     Sse::new(stream).keep_alive(
         axum::response::sse::KeepAlive::new()
@@ -21,3 +112,269 @@ pub async fn events(
             .text("ping"),
     )
 }
+
+/// GET /api/skills/{name}/logs/stream
+///
+/// Streams lines appended to the skill's log as `data` events.  When the
+/// skill stops or its log is rotated or removed, an `end` event carrying
+/// the reason is sent and the stream closes; clients should close their
+/// `EventSource` on it rather than let it reconnect.
+pub async fn skill_log(
+    State(state): State<DashState>,
+    Path(skill_name): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    let path = state.agent.skill_manager.lock().await.log_path(&skill_name).map_err(|e| {
+        error!("skill log stream: {e}");
+        StatusCode::NOT_FOUND
+    })?;
+    let tail = LogTail::open(path).await.map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let agent = state.agent.clone();
+    let running = move || {
+        let agent = agent.clone();
+        let name = skill_name.clone();
+        async move { agent.skill_manager.lock().await.is_running(&name) }
+    };
+    let stream = log_lines(tail, LOG_POLL_INTERVAL, running).map(|line| {
+        Ok(match line {
+            LogLine::Line(text) => Event::default().data(text),
+            LogLine::End(reason) => Event::default().event("end").data(reason),
+        })
+    });
+    Ok(Sse::new(stream).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("ping"),
+    ))
+}
+
+#[derive(Debug, PartialEq)]
+enum LogLine {
+    Line(String),
+    /// Last item of the stream, with why it ended.
+    End(&'static str),
+}
+
+/// Follows a log file from its current end, like `tail -f`.  File access
+/// goes through `tokio::fs` so polling never blocks a runtime worker.
+struct LogTail {
+    path: PathBuf,
+    file: tokio::fs::File,
+    pos: u64,
+    /// Bytes after the last newline, held until the line is complete.
+    partial: Vec<u8>,
+}
+
+impl LogTail {
+    async fn open(path: PathBuf) -> std::io::Result<Self> {
+        let mut file = tokio::fs::File::open(&path).await?;
+        let pos = file.seek(SeekFrom::End(0)).await?;
+        Ok(Self { path, file, pos, partial: Vec::new() })
+    }
+
+    /// Complete lines written since the last call, or `None` once the file
+    /// has been removed, replaced or truncated.
+    async fn poll(&mut self) -> Option<Vec<String>> {
+        let on_disk = tokio::fs::metadata(&self.path).await.ok()?;
+        if !self.same_file(&on_disk).await || on_disk.len() < self.pos {
+            return None;
+        }
+        let mut buf = Vec::new();
+        self.file.seek(SeekFrom::Start(self.pos)).await.ok()?;
+        self.pos += self.file.read_to_end(&mut buf).await.ok()? as u64;
+        self.partial.extend_from_slice(&buf);
+
+        let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Some(Vec::new());
+        };
+        let rest = self.partial.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        Some(
+            String::from_utf8_lossy(&complete)
+                .lines()
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    #[cfg(unix)]
+    async fn same_file(&self, on_disk: &std::fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+        self.file
+            .metadata()
+            .await
+            .is_ok_and(|open| open.ino() == on_disk.ino() && open.dev() == on_disk.dev())
+    }
+
+    #[cfg(not(unix))]
+    async fn same_file(&self, _on_disk: &std::fs::Metadata) -> bool {
+        true
+    }
+}
+
+struct TailState<F> {
+    tail: LogTail,
+    running: F,
+    pending: VecDeque<LogLine>,
+    finished: bool,
+}
+
+/// Lines appended to `tail`, checked every `interval`, until `running`
+/// reports the skill stopped or the file goes away.  Output written before
+/// the skill stopped is still delivered.
+fn log_lines<F, Fut>(tail: LogTail, interval: Duration, running: F) -> impl Stream<Item = LogLine>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let state = TailState { tail, running, pending: VecDeque::new(), finished: false };
+    futures::stream::unfold(state, move |mut st| async move {
+        loop {
+            if let Some(line) = st.pending.pop_front() {
+                return Some((line, st));
+            }
+            if st.finished {
+                return None;
+            }
+            tokio::time::sleep(interval).await;
+            let alive = (st.running)().await;
+            match st.tail.poll().await {
+                Some(lines) => st.pending.extend(lines.into_iter().map(LogLine::Line)),
+                None => {
+                    st.pending.push_back(LogLine::End("log rotated or removed"));
+                    st.finished = true;
+                    continue;
+                }
+            }
+            if !alive {
+                st.pending.push_back(LogLine::End("skill stopped"));
+                st.finished = true;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::events::EventLog;
+    use std::io::Write;
+
+    const TICK: Duration = Duration::from_millis(10);
+
+    fn append(path: &std::path::Path, text: &str) {
+        let mut f = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn tailer_emits_only_appended_complete_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skill.log");
+        std::fs::write(&path, "old line\n").unwrap();
+
+        let stream = log_lines(LogTail::open(path.clone()).await.unwrap(), TICK, || async { true });
+        tokio::pin!(stream);
+        append(&path, "first\nsecond\nhalf");
+        assert_eq!(stream.next().await, Some(LogLine::Line("first".into())));
+        assert_eq!(stream.next().await, Some(LogLine::Line("second".into())));
+        append(&path, " done\n");
+        assert_eq!(stream.next().await, Some(LogLine::Line("half done".into())));
+    }
+
+    #[tokio::test]
+    async fn tailer_ends_when_log_is_removed_or_rotated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skill.log");
+        std::fs::write(&path, "").unwrap();
+        let stream = log_lines(LogTail::open(path.clone()).await.unwrap(), TICK, || async { true });
+        tokio::pin!(stream);
+        append(&path, "bye\n");
+        assert_eq!(stream.next().await, Some(LogLine::Line("bye".into())));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(stream.next().await, Some(LogLine::End("log rotated or removed")));
+        assert_eq!(stream.next().await, None);
+
+        std::fs::write(&path, "").unwrap();
+        let stream = log_lines(LogTail::open(path.clone()).await.unwrap(), TICK, || async { true });
+        tokio::pin!(stream);
+        std::fs::rename(&path, dir.path().join("skill.log.1")).unwrap();
+        std::fs::write(&path, "fresh file\n").unwrap();
+        assert_eq!(stream.next().await, Some(LogLine::End("log rotated or removed")));
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn tailer_flushes_last_output_when_skill_stops() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("skill.log");
+        std::fs::write(&path, "").unwrap();
+        let stream = log_lines(LogTail::open(path.clone()).await.unwrap(), TICK, || async { false });
+        tokio::pin!(stream);
+        append(&path, "last words\n");
+        assert_eq!(stream.next().await, Some(LogLine::Line("last words".into())));
+        assert_eq!(stream.next().await, Some(LogLine::End("skill stopped")));
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn filtered_subscriber_gets_only_matching_types() {
+        let (tx, _) = broadcast::channel(16);
+        let filtered = filtered_events(
+            Vec::new(),
+            tx.subscribe(),
+            EventFilter::parse(Some("approval_needed, 2fa_challenge")),
+        );
+        let everything = filtered_events(Vec::new(), tx.subscribe(), EventFilter::parse(None));
+        tokio::pin!(filtered);
+        tokio::pin!(everything);
+
+        let sent = [
+            r#"{"type":"thinking"}"#,
+            r#"{"type":"approval_needed","id":"a1"}"#,
+            "update",
+            r#"{"type":"tool_start","tool":"exec"}"#,
+            r#"{"type":"2fa_challenge"}"#,
+        ];
+        for msg in sent {
+            tx.send(SseMessage { id: None, data: msg.to_string() }).unwrap();
+        }
+        drop(tx);
+
+        let got: Vec<String> = filtered.map(|m| m.data).collect().await;
+        assert_eq!(got, [sent[1], sent[4]]);
+        let got: Vec<String> = everything.map(|m| m.data).collect().await;
+        assert_eq!(got, sent);
+    }
+
+    #[tokio::test]
+    async fn reconnect_replays_missed_events_then_goes_live() {
+        let (tx, _) = broadcast::channel(16);
+        let mut log = EventLog::new(10);
+        for t in ["a", "b", "c"] {
+            log.push(serde_json::json!({ "type": t }));
+        }
+
+        // Client saw id 1; id 3 is emitted again live after subscribing,
+        // as can happen between subscribing and reading the buffer.
+        let rx = tx.subscribe();
+        let replay = log.after(1);
+        let stream = filtered_events(replay, rx, EventFilter::parse(None));
+        tokio::pin!(stream);
+        tx.send(log.after(2).remove(0)).unwrap();
+        tx.send(log.push(serde_json::json!({ "type": "d" }))).unwrap();
+        drop(tx);
+
+        let ids: Vec<_> = stream.map(|m| m.id.unwrap()).collect().await;
+        assert_eq!(ids, [2, 3, 4]);
+    }
+
+    #[test]
+    fn filter_matches_bare_messages_and_ignores_empty_lists() {
+        assert!(EventFilter::parse(Some(" , ")).types.is_none());
+        let filter = EventFilter::parse(Some("update"));
+        assert!(filter.matches("update"));
+        assert!(!filter.matches(r#"{"type":"thinking"}"#));
+        assert!(!filter.matches(r#"{"no_type":true}"#));
+    }
+}
@@ -1,4 +1,4 @@
-var Cl=Object.defineProperty;var Qo=r=>{throw TypeError(r)};var Al=(r,i,o)=>i in r?Cl(r,i,{enumerable:!0,configurable:!0,writable:!0,value:o}):r[i]=o;var Gr=(r,i,o)=>Al(r,typeof i!="symbol"?i+"":i,o),xo=(r,i,o)=>i.has(r)||Qo("Cannot "+o);var de=(r,i,o)=>(xo(r,i,"read from private field"),o?o.call(r):i.get(r)),ra=(r,i,o)=>i.has(r)?Qo("Cannot add the same private member more than once"):i instanceof WeakSet?i.add(r):i.set(r,o),ea=(r,i,o,d)=>(xo(r,i,"write to private field"),d?d.call(r,o):i.set(r,o),o),Qa=(r,i,o)=>(xo(r,i,"access private method"),o);var lo=Array.isArray,Pl=Array.prototype.indexOf,Ys=Array.prototype.includes,co=Array.from,Js=Object.defineProperty,ri=Object.getOwnPropertyDescriptor,jl=Object.getOwnPropertyDescriptors,$n=Object.prototype,Ol=Array.prototype,Io=Object.getPrototypeOf,Xo=Object.isExtensible;const Ll=()=>{};function Rl(r){for(var i=0;i<r.length;i++)r[i]()}function Tn(){var r,i,o=new Promise((d,c)=>{r=d,i=c});return{promise:o,resolve:r,reject:i}}function vo(r,i){if(Array.isArray(r))return r;if(!(Symbol.iterator in r))return Array.from(r);const o=[];for(const d of r)if(o.push(d),o.length===i)break;return o}const Ka=2,Li=4,uo=8,En=1<<24,vs=16,Ir=32,ei=64,Sn=128,kr=512,Ba=1024,Wa=2048,Nr=4096,xr=8192,gs=16384,zi=32768,vi=65536,to=1<<17,Cn=1<<18,bi=1<<19,Ml=1<<20,ns=1<<25,Qs=65536,wo=1<<21,fo=1<<22,hs=1<<23,Vs=Symbol("$state"),Nl=Symbol(""),An=Symbol("proxy path"),Ms=new class extends Error{constructor(){super(...arguments);Gr(this,"name","StaleReactionError");Gr(this,"message","The reaction that called `getAbortSignal()` was re-run or destroyed")}},Il=1,Dl=11;function Fl(r){{const i=new Error(`lifecycle_outside_component
+(()=>{const r=new Set(["GET","HEAD","OPTIONS"]),i=()=>{for(const e of document.cookie.split(";")){const[t,...n]=e.trim().split("=");if(t==="sa_csrf")return decodeURIComponent(n.join("="))}return null},o=window.fetch.bind(window);window.fetch=(e,t)=>{const n=((t==null?void 0:t.method)??(e instanceof Request?e.method:"GET")).toUpperCase(),s=new URL(e instanceof Request?e.url:String(e),window.location.href),c=i();if(r.has(n)||s.origin!==window.location.origin||!c)return o(e,t);const a=new Headers((t==null?void 0:t.headers)??(e instanceof Request?e.headers:void 0));return a.set("X-CSRF-Token",c),o(e,{...t,headers:a})}})();var Cl=Object.defineProperty;var Qo=r=>{throw TypeError(r)};var Al=(r,i,o)=>i in r?Cl(r,i,{enumerable:!0,configurable:!0,writable:!0,value:o}):r[i]=o;var Gr=(r,i,o)=>Al(r,typeof i!="symbol"?i+"":i,o),xo=(r,i,o)=>i.has(r)||Qo("Cannot "+o);var de=(r,i,o)=>(xo(r,i,"read from private field"),o?o.call(r):i.get(r)),ra=(r,i,o)=>i.has(r)?Qo("Cannot add the same private member more than once"):i instanceof WeakSet?i.add(r):i.set(r,o),ea=(r,i,o,d)=>(xo(r,i,"write to private field"),d?d.call(r,o):i.set(r,o),o),Qa=(r,i,o)=>(xo(r,i,"access private method"),o);var lo=Array.isArray,Pl=Array.prototype.indexOf,Ys=Array.prototype.includes,co=Array.from,Js=Object.defineProperty,ri=Object.getOwnPropertyDescriptor,jl=Object.getOwnPropertyDescriptors,$n=Object.prototype,Ol=Array.prototype,Io=Object.getPrototypeOf,Xo=Object.isExtensible;const Ll=()=>{};function Rl(r){for(var i=0;i<r.length;i++)r[i]()}function Tn(){var r,i,o=new Promise((d,c)=>{r=d,i=c});return{promise:o,resolve:r,reject:i}}function vo(r,i){if(Array.isArray(r))return r;if(!(Symbol.iterator in r))return Array.from(r);const o=[];for(const d of r)if(o.push(d),o.length===i)break;return o}const Ka=2,Li=4,uo=8,En=1<<24,vs=16,Ir=32,ei=64,Sn=128,kr=512,Ba=1024,Wa=2048,Nr=4096,xr=8192,gs=16384,zi=32768,vi=65536,to=1<<17,Cn=1<<18,bi=1<<19,Ml=1<<20,ns=1<<25,Qs=65536,wo=1<<21,fo=1<<22,hs=1<<23,Vs=Symbol("$state"),Nl=Symbol(""),An=Symbol("proxy path"),Ms=new class extends Error{constructor(){super(...arguments);Gr(this,"name","StaleReactionError");Gr(this,"message","The reaction that called `getAbortSignal()` was re-run or destroyed")}},Il=1,Dl=11;function Fl(r){{const i=new Error(`lifecycle_outside_component
 \`${r}(...)\` can only be used during component initialisation
 https://svelte.dev/e/lifecycle_outside_component`);throw i.name="Svelte error",i}}function Ul(){{const r=new Error("async_derived_orphan\nCannot create a `$derived(...)` with an `await` expression outside of an effect tree\nhttps://svelte.dev/e/async_derived_orphan");throw r.name="Svelte error",r}}function Zo(){{const r=new Error("bind_invalid_checkbox_value\nUsing `bind:value` together with a checkbox input is not allowed. Use `bind:checked` instead\nhttps://svelte.dev/e/bind_invalid_checkbox_value");throw r.name="Svelte error",r}}function zl(r,i){{const o=new Error(`component_api_changed
 Calling \`${r}\` on a component instance (of ${i}) is no longer valid in Svelte 5
@@ -73,7 +73,7 @@ ${c}
                        placeholder:text-text-subtle disabled:opacity-50 disabled:cursor-not-allowed resize-none"></textarea> <button class="px-4 py-2 rounded-md bg-primary-600 text-white text-sm font-medium
                        hover:bg-primary-500 transition-colors
                        disabled:opacity-50 disabled:cursor-not-allowed shrink-0"><!></button></div></div></div>`),ja[u],[[86,0,[[88,4],[161,4,[[162,8,[[163,12],[173,12]]]]]]]]);function ja(r,i){wa(new.target),ga(i,!0,ja);let o=S(P(Jt([])),"messages"),d=S(P(""),"input"),c=S(P(!1),"sending"),f=S(P(""),"error"),b=S(P(void 0),"messagesEl");async function h(){try{p(o,(await M(Nt("GET","/api/memory/conversation")))(),!0),(await M(Xi()))(),w()}catch(K){console.error(...Xt("error","loadChat:",K))}}function w(){e(b)&&(e(b).scrollTop=e(b).scrollHeight)}async function L(){const K=e(d).trim();if(!(!K||e(c))){p(f,""),p(c,!0),p(d,""),p(o,[...e(o),{id:Date.now(),role:"user",content:K,created_at:new Date().toISOString()}],!0),(await M(Xi()))(),w();try{const W={message:K};va.userId&&(W.user_id=va.userId);const Ue=(await M(Nt("POST","/api/chat",W)))();(await M(h()))()}catch(W){p(f,(W==null?void 0:W.message)||l("chat.send_failed"),!0),(await M(h()))()}finally{p(c,!1),(await M(Xi()))(),w()}}}function j(K){C(K.key,"Enter")&&!K.shiftKey&&(K.preventDefault(),L())}Es(()=>{h()}),Ga(()=>{kt.refreshCounter,e(c)||h()});var A={...ka()},k=Ru(),q=a(k),V=a(q);{var X=K=>{var W=wu(),Ue=a(W),Ne=s(a(Ue),2),Pe=a(Ne);x(R=>n(Pe,R),[()=>l("chat.no_messages")]),v(K,W)},N=K=>{var W=Pu(),Ue=Ae(W);g(()=>Dt(Ue,17,()=>e(o),R=>R.id,(R,ie)=>{var qe=Oa(),Se=Ae(qe);{var me=Z=>{var H=ku(),Le=a(H),O=a(Le),B=a(O,!0);var ue=s(O,2),D=a(ue,!0);x(T=>{n(B,e(ie).content),n(D,T)},[()=>hn(e(ie).created_at)]),v(Z,H)},$=Z=>{var H=$u(),Le=a(H),O=a(Le),B=a(O,!0);var ue=s(O,2),D=a(ue,!0);x(T=>{n(B,e(ie).content),n(D,T)},[()=>hn(e(ie).created_at)]),v(Z,H)},z=Z=>{var H=Tu(),Le=a(H),O=a(Le,!0);x(()=>n(O,e(ie).content)),v(Z,H)};g(()=>y(Se,Z=>{C(e(ie).role,"user")?Z(me):C(e(ie).role,"assistant")?Z($,1):Z(z,!1)}),"if",ja,101,16)}v(R,qe)}),"each",ja,100,12);var Ne=s(Ue,2);{var Pe=R=>{var ie=Au(),qe=a(ie),Se=a(qe),me=a(Se);{var $=H=>{var Le=Eu(),O=s(Ae(Le),2),B=a(O);x(()=>n(B,Sa.activeTool)),v(H,Le)},z=H=>{var Le=Su();v(H,Le)},Z=H=>{var Le=Cu(),O=s(Ae(Le),1,!0);x(B=>n(O,B),[()=>l("chat.processing")]),v(H,Le)};g(()=>y(me,H=>{Sa.activeTool?H($):Sa.isThinking?H(z,1):H(Z,!1)}),"if",ja,137,28)}v(R,ie)};g(()=>y(Ne,R=>{e(c)&&R(Pe)}),"if",ja,133,12)}v(K,W)};g(()=>y(V,K=>{C(e(o).length,0)&&!e(c)?K(X):K(N,!1)}),"if",ja,92,8)}ml(q,K=>p(b,K),()=>e(b));var ne=s(q,2);{var je=K=>{var W=ju(),Ue=s(a(W),1,!0);x(()=>n(Ue,e(f))),v(K,W)};g(()=>y(ne,K=>{e(f)&&K(je)}),"if",ja,154,4)}var we=s(ne,2),Te=a(we),Ke=a(Te),Ee=s(Ke,2),te=a(Ee);{var _e=K=>{var W=Ou();v(K,W)},Ve=K=>{var W=Lu();v(K,W)};g(()=>y(te,K=>{e(c)?K(_e):K(Ve,!1)}),"if",ja,180,16)}return x(K=>{Ke.disabled=e(c),Ee.disabled=K},[()=>e(c)||!e(d).trim()]),Y("keydown",Ke,j),ua(Ke,function(){return e(d)},function(W){p(d,W)}),Y("click",Ee,L),v(r,k),ha(A)}La(["keydown","click"]);Vt[u]="src/components/GoalsTab.svelte";var Mu=_(m('<p class="text-text-subtle text-sm italic text-center py-4"> </p>'),Vt[u],[[164,12]]),Nu=_(m('<p class="text-text-subtle text-sm italic text-center py-4"> </p>'),Vt[u],[[166,12]]),Iu=_(m('<span class="text-[11px] px-1.5 py-0.5 rounded-full bg-warning-500/15 text-warning-500 font-medium shrink-0"> </span>'),Vt[u],[[181,32]]),Du=_(m('<div class="flex items-center gap-1.5"><div class="w-20 h-1.5 bg-border rounded-full overflow-hidden"><div class="h-full bg-success-500 rounded-full transition-all"></div></div> <span class="text-[11px] text-text-muted font-mono"> </span></div>'),Vt[u],[[188,32,[[189,36,[[190,40]]],[195,36]]]]),Fu=_(m('<div class="text-sm text-text-muted px-4 pb-3 -mt-1"> </div>'),Vt[u],[[207,24]]),Uu=_(m('<div class="text-sm text-text-muted px-4 pt-3 pb-2"> </div>'),Vt[u],[[214,32]]),zu=_(m('<button class="px-3 py-1.5 text-xs border border-border rounded-md bg-surface text-warning-500 hover:bg-warning-500/10 hover:border-warning-500 transition-colors"><i class="fa-solid fa-pause mr-1"></i> </button> <button class="px-3 py-1.5 text-xs border border-border rounded-md bg-surface text-error-400 hover:bg-error-500/10 hover:border-error-500 transition-colors"><i class="fa-solid fa-ban mr-1"></i> </button>',1),Vt[u],[[220,36,[[224,40]]],[226,36,[[230,40]]]]),Bu=_(m('<button class="px-3 py-1.5 text-xs border border-border rounded-md bg-surface text-success-500 hover:bg-success-500/10 hover:border-success-500 transition-colors"><i class="fa-solid fa-play mr-1"></i> </button> <button class="px-3 py-1.5 text-xs border border-border rounded-md bg-surface text-error-400 hover:bg-error-500/10 hover:border-error-500 transition-colors"><i class="fa-solid fa-ban mr-1"></i> </button>',1),Vt[u],[[233,36,[[237,40]]],[239,36,[[243,40]]]]),qu=_(m('<div class="p-4 text-sm text-text-subtle italic text-center"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </div>'),Vt[u],[[249,32,[[250,36]]]]),Gu=_(m('<div class="mx-4 mt-3 p-3 bg-primary-950/40 border border-primary-800/30 rounded-md"><div class="text-[11px] font-semibold uppercase tracking-wider text-primary-400 mb-1"><i class="fa-solid fa-brain mr-1"></i> </div> <div class="text-xs text-text-muted leading-relaxed"> </div></div>'),Vt[u],[[255,36,[[256,40,[[257,44]]],[259,40]]]]),Vu=_(m('<p class="text-text-subtle text-sm italic text-center py-2"> </p>'),Vt[u],[[273,40]]),Hu=_(m('<div class="text-xs text-text-muted mb-1"> </div>'),Vt[u],[[293,60]]),Ku=_(m('<div class="text-[10px] text-text-subtle"> </div>'),Vt[u],[[296,60]]),Wu=_(m('<div class="mt-1.5 p-2 bg-background border border-border rounded text-[11px] font-mono text-text-muted max-h-[120px] overflow-auto whitespace-pre-wrap break-all leading-relaxed"> </div>'),Vt[u],[[301,60]]),Yu=_(m('<div class="mt-1 text-[10px] text-accent-300 font-mono"> </div>'),Vt[u],[[306,60]]),Ju=_(m('<div class="flex items-start gap-3 p-3 bg-surface border border-border rounded-md"><div class="shrink-0 mt-0.5"><span><i></i></span></div> <div class="flex-1 min-w-0"><div class="flex items-center gap-2 mb-0.5"><span class="text-sm font-medium"> </span> <span> </span></div> <!> <!> <!> <!></div></div>'),Vt[u],[[279,48,[[280,52,[[281,56,[[282,60]]]]],[285,52,[[286,56,[[287,60],[288,60]]]]]]]]),Qu=_(m('<div class="space-y-2"></div>'),Vt[u],[[277,40]]),Xu=_(m('<span class="text-text-subtle"> </span> <span class="text-text-muted font-mono"> </span>',1),Vt[u],[[325,44],[326,44]]),Zu=_(m('<!> <div class="p-4"><div class="text-xs font-semibold uppercase tracking-wider text-text-muted mb-3"><i class="fa-solid fa-list-check mr-1"></i> </div> <!></div> <div class="px-4 pb-4"><div class="grid grid-cols-2 gap-x-4 gap-y-1 text-[11px]"><span class="text-text-subtle"> </span> <span class="text-text-muted font-mono"> </span> <span class="text-text-subtle"> </span> <span class="text-text-muted font-mono"> </span> <!> <span class="text-text-subtle"> </span> <span class="text-text-muted font-mono truncate"> </span></div></div>',1),Vt[u],[[266,32,[[267,36,[[268,40]]]]],[318,32,[[319,36,[[320,40],[321,40],[322,40],[323,40],[328,40],[329,40]]]]]]),ef=_(m('<div class="border-t border-border"><!> <div class="flex items-center gap-2 px-4 py-2 border-b border-border"><!></div> <!></div>'),Vt[u],[[212,24,[[218,28]]]]),tf=_(m('<div class="border border-border rounded-md mb-3 bg-surface-muted overflow-hidden"><button class="w-full p-4 flex justify-between items-center hover:bg-surface-elevated/40 transition-colors cursor-pointer text-left"><div class="flex items-center gap-2 flex-1 min-w-0"><i></i> <span class="text-[15px] font-semibold truncate"> </span> <!></div> <div class="flex items-center gap-2 shrink-0 ml-3"><!> <span><i></i> </span></div></button> <!> <!></div>'),Vt[u],[[171,16,[[173,20,[[177,24,[[178,28],[179,28]]],[186,24,[[200,28,[[201,32]]]]]]]]]]),af=_(m('<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden"><div class="flex justify-between items-center border-b border-border"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted"><i class="fa-solid fa-bullseye mr-1.5"></i> </h2> <div class="flex items-center gap-2 pr-3"><select class="text-xs px-2 py-1 border border-border rounded-md bg-surface text-text outline-none focus:border-primary-500"><option> </option><option> </option><option> </option><option> </option><option> </option><option> </option></select> <span class="text-xs text-text-muted"> </span> <button class="px-2.5 py-1 text-xs border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-arrows-rotate mr-1"></i> </button></div></div> <div class="p-3"><!></div></section>'),Vt[u],[[132,0,[[133,4,[[134,8,[[135,12]]],[137,8,[[138,12,[[143,16],[144,16],[145,16],[146,16],[147,16],[148,16]]],[150,12],[153,12,[[157,16]]]]]]],[162,4]]]]);function Vt(r,i){wa(new.target),ga(i,!0,Vt);let o=S(P(Jt([])),"goals"),d=S(P(!1),"error"),c=S(P(""),"statusFilter"),f=S(P(null),"expandedGoal"),b=S(P(null),"goalDetail"),h=S(P(!1),"detailLoading");async function w(){p(d,!1);try{const B=e(c)?`?status=${e(c)}`:"";p(o,(await M(Nt("GET",`/api/goals${B}`)))(),!0)}catch(B){p(d,!0),console.error(...Xt("error","loadGoals:",B))}}async function L(B){p(h,!0);try{p(b,(await M(Nt("GET",`/api/goals/${encodeURIComponent(B)}`)))(),!0)}catch(ue){console.error(...Xt("error","loadGoalDetail:",ue))}finally{p(h,!1)}}async function j(B,ue){try{(await M(Nt("PUT",`/api/goals/${encodeURIComponent(B)}/status`,{status:ue})))(),w(),C(e(f),B)&&L(B)}catch(D){alert(l("goals.status_update_failed")+D.message)}}function A(B){C(e(f),B)?(p(f,null),p(b,null)):(p(f,B,!0),L(B))}function k(B){switch(B){case"active":return"bg-primary-500/15 text-primary-400";case"paused":return"bg-warning-500/15 text-warning-500";case"completed":return"bg-success-500/15 text-success-500";case"failed":return"bg-error-500/12 text-error-400";case"cancelled":return"bg-text-subtle/10 text-text-subtle";case"in_progress":return"bg-primary-500/15 text-primary-400";case"pending":return"bg-text-subtle/10 text-text-muted";case"skipped":return"bg-text-subtle/10 text-text-subtle";default:return"bg-text-subtle/10 text-text-subtle"}}function q(B){switch(B){case"active":return"fa-circle-play";case"paused":return"fa-circle-pause";case"completed":return"fa-circle-check";case"failed":return"fa-circle-xmark";case"cancelled":return"fa-ban";case"in_progress":return"fa-spinner fa-spin";case"pending":return"fa-clock";case"skipped":return"fa-forward";default:return"fa-question"}}Ga(()=>{C(kt.currentTab,"goals")&&(kt.refreshCounter,w())});var V={...ka()},X=af(),N=a(X),ne=a(N),je=s(a(ne)),we=s(ne,2),Te=a(we),Ke=a(Te),Ee=a(Ke);Ke.value=Ke.__value="";var te=s(Ke),_e=a(te);te.value=te.__value="active";var Ve=s(te),K=a(Ve);Ve.value=Ve.__value="paused";var W=s(Ve),Ue=a(W);W.value=W.__value="completed";var Ne=s(W),Pe=a(Ne);Ne.value=Ne.__value="failed";var R=s(Ne),ie=a(R);R.value=R.__value="cancelled";var qe=s(Te,2),Se=a(qe),me=s(qe,2),$=s(a(me)),z=s(N,2),Z=a(z);{var H=B=>{var ue=Mu(),D=a(ue);x(T=>n(D,T),[()=>l("goals.error_loading")]),v(B,ue)},Le=B=>{var ue=Nu(),D=a(ue);x(T=>n(D,T),[()=>l("goals.no_goals")]),v(B,ue)},O=B=>{var ue=Oa(),D=Ae(ue);g(()=>Dt(D,17,()=>e(o),T=>T.id,(T,E)=>{var U=tf(),J=a(U),xe=a(J),fe=a(xe),F=s(fe,2),he=a(F,!0);var Xe=s(F,2);{var yt=ve=>{var Q=Iu(),$e=a(Q);x(()=>n($e,`P${e(E).priority??""}`)),v(ve,Q)};g(()=>y(Xe,ve=>{e(E).priority>0&&ve(yt)}),"if",Vt,180,28)}var ht=s(xe,2),ct=a(ht);{var oe=ve=>{var Q=Du(),$e=a(Q),Ie=a($e);var Re=s($e,2),Ct=a(Re);x(()=>{Ds(Ie,`width: ${e(E).total_tasks>0?e(E).completed_tasks/e(E).total_tasks*100:0}%`),n(Ct,`${e(E).completed_tasks??""}/${e(E).total_tasks??""}`)}),v(ve,Q)};g(()=>y(ct,ve=>{e(E).total_tasks>0&&ve(oe)}),"if",Vt,187,28)}var le=s(ct,2),I=a(le),ae=s(I,1,!0);var G=s(J,2);{var ee=ve=>{var Q=Fu(),$e=a(Q,!0);x(()=>n($e,e(E).description)),v(ve,Q)};g(()=>y(G,ve=>{e(E).description&&C(e(f),e(E).id,!1)&&ve(ee)}),"if",Vt,206,20)}var re=s(G,2);{var ke=ve=>{var Q=ef(),$e=a(Q);{var Ie=ze=>{var be=Uu(),ce=a(be,!0);x(()=>n(ce,e(E).description)),v(ze,be)};g(()=>y($e,ze=>{e(E).description&&ze(Ie)}),"if",Vt,213,28)}var Re=s($e,2),Ct=a(Re);{var st=ze=>{var be=zu(),ce=Ae(be),pe=s(a(ce),1,!0);var Oe=s(ce,2),Me=s(a(Oe),1,!0);x((Ze,Be)=>{n(pe,Ze),n(Me,Be)},[()=>l("goals.pause"),()=>l("common.cancel")]),Y("click",ce,function(){return j(e(E).id,"paused")}),Y("click",Oe,function(){return j(e(E).id,"cancelled")}),v(ze,be)},De=ze=>{var be=Bu(),ce=Ae(be),pe=s(a(ce),1,!0);var Oe=s(ce,2),Me=s(a(Oe),1,!0);x((Ze,Be)=>{n(pe,Ze),n(Me,Be)},[()=>l("goals.resume"),()=>l("common.cancel")]),Y("click",ce,function(){return j(e(E).id,"active")}),Y("click",Oe,function(){return j(e(E).id,"cancelled")}),v(ze,be)};g(()=>y(Ct,ze=>{C(e(E).status,"active")?ze(st):C(e(E).status,"paused")&&ze(De,1)}),"if",Vt,219,32)}var lt=s(Re,2);{var Je=ze=>{var be=qu(),ce=s(a(be),1,!0);x(pe=>n(ce,pe),[()=>l("common.loading")]),v(ze,be)},_t=ze=>{var be=Zu(),ce=Ae(be);{var pe=Ge=>{var bt=Gu(),pt=a(bt),vt=s(a(pt));var Pt=s(pt,2),Et=a(Pt,!0);x(jt=>{n(vt,` ${jt??""}`),n(Et,e(b).goal.reflection)},[()=>l("goals.self_reflection")]),v(Ge,bt)};g(()=>y(ce,Ge=>{e(b).goal.reflection&&Ge(pe)}),"if",Vt,254,32)}var Oe=s(ce,2),Me=a(Oe),Ze=s(a(Me));var Be=s(Me,2);{var We=Ge=>{var bt=Vu(),pt=a(bt,!0);x(vt=>n(pt,vt),[()=>l("goals.no_tasks")]),v(Ge,bt)},it=Ge=>{var bt=Qu();g(()=>Dt(bt,23,()=>e(b).tasks,pt=>pt.id,(pt,vt)=>{var Pt=Ju(),Et=a(Pt),jt=a(Et),Ft=a(jt);var Ht=s(Et,2),Ut=a(Ht),Kt=a(Ut),na=a(Kt,!0);var ta=s(Kt,2),Ea=a(ta,!0);var pa=s(Ut,2);{var zt=Lt=>{var Mt=Hu(),fa=a(Mt,!0);x(()=>n(fa,e(vt).description)),v(Lt,Mt)};g(()=>y(pa,Lt=>{e(vt).description&&Lt(zt)}),"if",Vt,292,56)}var da=s(pa,2);{var ia=Lt=>{var Mt=Ku(),fa=a(Mt);x((Aa,Ra)=>n(fa,`${Aa??""} ${Ra??""}`),[()=>l("goals.depends_on"),()=>e(vt).depends_on.join(", ")]),v(Lt,Mt)};g(()=>y(da,Lt=>{e(vt).depends_on.length>0&&Lt(ia)}),"if",Vt,295,56)}var aa=s(da,2);{var $a=Lt=>{var Mt=Wu(),fa=a(Mt,!0);x(()=>n(fa,e(vt).result)),v(Lt,Mt)};g(()=>y(aa,Lt=>{e(vt).result&&Lt($a)}),"if",Vt,300,56)}var gt=s(aa,2);{var Ot=Lt=>{var Mt=Yu(),fa=a(Mt);x((Aa,Ra)=>n(fa,`${Aa??""} ${Ra??""}`),[()=>l("goals.tool"),()=>JSON.stringify(e(vt).tool_call)]),v(Lt,Mt)};g(()=>y(gt,Lt=>{e(vt).tool_call&&Lt(Ot)}),"if",Vt,305,56)}x((Lt,Mt,fa)=>{Qe(jt,1,`inline-flex items-center justify-center w-6 h-6 rounded-full text-[11px] font-semibold ${Lt??""}`),Qe(Ft,1,`fa-solid ${Mt??""}`),n(na,e(vt).title),Qe(ta,1,`text-[10px] px-1.5 py-0.5 rounded-full ${fa??""}`),n(Ea,e(vt).status)},[()=>k(e(vt).status),()=>q(e(vt).status),()=>k(e(vt).status)]),v(pt,Pt)}),"each",Vt,278,44),v(Ge,bt)};g(()=>y(Be,Ge=>{C(e(b).tasks.length,0)?Ge(We):Ge(it,!1)}),"if",Vt,272,36)}var ot=s(Oe,2),rt=a(ot),Fe=a(rt),ut=a(Fe,!0);var mt=s(Fe,2),Tt=a(mt,!0);var xt=s(mt,2),se=a(xt,!0);var ye=s(xt,2),et=a(ye,!0);var dt=s(ye,2);{var tt=Ge=>{var bt=Xu(),pt=Ae(bt),vt=a(pt,!0);var Pt=s(pt,2),Et=a(Pt,!0);x((jt,Ft)=>{n(vt,jt),n(Et,Ft)},[()=>l("goals.completed_at"),()=>Zr(e(b).goal.completed_at)]),v(Ge,bt)};g(()=>y(dt,Ge=>{e(b).goal.completed_at&&Ge(tt)}),"if",Vt,324,40)}var Ye=s(dt,2),at=a(Ye,!0);var ft=s(Ye,2),wt=a(ft,!0);x((Ge,bt,pt,vt,Pt,Et)=>{n(Ze,` ${Ge??""} (${e(b).tasks.length??""})`),n(ut,bt),n(Tt,pt),n(se,vt),n(et,Pt),n(at,Et),St(ft,"title",e(b).goal.id),n(wt,e(b).goal.id)},[()=>l("goals.tasks"),()=>l("goals.created"),()=>Zr(e(b).goal.created_at),()=>l("goals.updated"),()=>Zr(e(b).goal.updated_at),()=>l("goals.id")]),v(ze,be)};g(()=>y(lt,ze=>{e(h)?ze(Je):e(b)&&ze(_t,1)}),"if",Vt,248,28)}v(ve,Q)};g(()=>y(re,ve=>{C(e(f),e(E).id)&&ve(ke)}),"if",Vt,211,20)}x((ve,Q)=>{Qe(fe,1,`fa-solid fa-chevron-right text-[10px] text-text-subtle transition-transform ${C(e(f),e(E).id)?"rotate-90":""}`),n(he,e(E).title),Qe(le,1,`text-[11px] px-2 py-0.5 rounded-full font-medium ${ve??""}`),Qe(I,1,`fa-solid ${Q??""} mr-1`),n(ae,e(E).status)},[()=>k(e(E).status),()=>q(e(E).status)]),Y("click",J,function(){return A(e(E).id)}),v(T,U)}),"each",Vt,170,12),v(B,ue)};g(()=>y(Z,B=>{e(d)?B(H):C(e(o).length,0)?B(Le,1):B(O,!1)}),"if",Vt,163,8)}return x((B,ue,D,T,E,U,J,xe,fe)=>{n(je,` ${B??""}`),n(Ee,ue),n(_e,D),n(K,T),n(Ue,E),n(Pe,U),n(ie,J),n(Se,xe),n($,` ${fe??""}`)},[()=>l("goals.title"),()=>l("goals.all_statuses"),()=>l("goals.active"),()=>l("goals.paused"),()=>l("goals.completed"),()=>l("goals.failed"),()=>l("goals.cancelled"),()=>l("goals.count",{count:e(o).length}),()=>l("common.refresh")]),Y("change",Te,function(){return w()}),Gi(Te,function(){return e(c)},function(ue){p(c,ue)}),Y("click",me,w),v(r,X),ha(V)}La(["change","click"]);xi[u]="src/components/CredentialRow.svelte";var rf=_(m('<button class="px-2.5 py-1 text-xs border border-border rounded-md bg-surface text-error-500 hover:bg-error-500/10 hover:border-error-500 transition-colors"><i class="fa-solid fa-trash-can"></i></button>'),xi[u],[[91,12,[[96,16]]]]),sf=_(m('<div class="flex items-center gap-2 mb-2 text-sm"><div></div> <div class="font-mono text-xs min-w-[200px] shrink-0 text-accent-300"> </div> <input type="password" class="flex-1 px-2 py-1 border border-border rounded-md bg-background text-text text-xs font-mono outline-none focus:border-primary-500 focus:ring-1 focus:ring-primary-900 placeholder:text-text-subtle"/> <div class="flex gap-1 shrink-0"><button class="px-2.5 py-1 text-xs border border-border rounded-md bg-surface text-success-500 hover:bg-success-500/10 hover:border-success-500 transition-colors"><i class="fa-solid fa-floppy-disk"></i></button> <!></div></div>'),xi[u],[[67,0,[[68,4],[69,4],[75,4],[82,4,[[83,8,[[88,12]]]]]]]]);function xi(r,i){wa(new.target),ga(i,!0,xi);let o=S(P(""),"inputValue");function d(){return i.credential.configured?"bg-success-500":i.credential.required?"bg-error-500":"bg-warning-500"}function c(){return i.credential.configured?l("cred.configured"):i.credential.required?l("cred.required"):l("cred.optional")}function f(){return i.credential.configured?l("cred.configured_placeholder"):i.credential.description||l("cred.enter_value")}async function b(){const we=e(o).trim();if(we)try{(await M(Nt("PUT",`/api/skills/${encodeURIComponent(i.skillName)}/credentials`,{key:i.credential.name,value:we})))(),p(o,""),i.onchange()}catch(Te){console.error(...Xt("error","saveCredential:",Te)),alert(l("cred.save_failed")+Te.message)}}async function h(){if(confirm(l("cred.remove_confirm",{name:i.credential.name,skill:i.skillName})))try{(await M(Nt("DELETE",`/api/skills/${encodeURIComponent(i.skillName)}/credentials/${encodeURIComponent(i.credential.name)}`)))(),i.onchange()}catch(we){console.error(...Xt("error","deleteCredential:",we)),alert(l("cred.remove_failed")+we.message)}}function w(we){C(we.key,"Enter")&&b()}var L={...ka()},j=sf(),A=a(j),k=s(A,2),q=a(k),V=s(k,2),X=s(V,2),N=a(X),ne=s(N,2);{var je=we=>{var Te=rf();x(Ke=>St(Te,"title",Ke),[()=>l("common.delete")]),Y("click",Te,h),v(we,Te)};g(()=>y(ne,we=>{i.credential.configured&&we(je)}),"if",xi,90,8)}return x((we,Te,Ke,Ee)=>{Qe(A,1,`w-2 h-2 rounded-full shrink-0 ${we??""}`),St(A,"title",Te),St(k,"title",i.credential.description),n(q,i.credential.label||i.credential.name),St(V,"placeholder",Ke),St(N,"title",Ee)},[d,c,f,()=>l("common.save")]),Y("keyup",V,w),ua(V,function(){return e(o)},function(Te){p(o,Te)}),Y("click",N,b),v(r,j),ha(L)}La(["keyup","click"]);$t[u]="src/components/SkillCard.svelte";var of=_(m('<span class="text-[11px] px-2 py-0.5 rounded-full font-medium bg-primary-950 text-primary-400"><i class="fa-solid fa-server mr-1"></i>daemon</span>'),$t[u],[[202,16,[[203,20]]]]),nf=_(m('<span class="text-[11px] px-2 py-0.5 rounded-full font-medium bg-warning-500/15 text-warning-500"><i class="fa-solid fa-bolt mr-1"></i>oneshot</span>'),$t[u],[[206,16,[[207,20]]]]),lf=_(m('<span class="text-[11px] px-2 py-0.5 rounded-full font-medium bg-text-subtle/10 text-text-subtle"><i class="fa-solid fa-ban mr-1"></i>disabled</span>'),$t[u],[[212,16,[[213,20]]]]),df=_(m('<span class="text-[11px] px-2 py-0.5 rounded-full font-medium bg-success-500/15 text-success-500"><i class="fa-solid fa-circle-check mr-1"></i>running</span>'),$t[u],[[216,16,[[217,20]]]]),cf=_(m('<span class="text-[11px] px-2 py-0.5 rounded-full font-medium bg-error-500/12 text-error-400"><i class="fa-solid fa-circle-stop mr-1"></i>stopped</span>'),$t[u],[[220,16,[[221,20]]]]),vf=_(m('<span class="text-[11px] px-2 py-0.5 rounded-full font-medium bg-violet-900/30 text-violet-400 border border-violet-800/40"><i class="fa-solid fa-plug mr-1"></i>ext</span>'),$t[u],[[226,16,[[227,20]]]]),uf=_(m('<span class="text-[11px] text-text-subtle font-mono"> </span>'),$t[u],[[232,16]]),ff=_(m('<div class="text-sm text-text-muted px-4 pb-3 -mt-1"> </div>'),$t[u],[[238,8]]),pf=_(m('<div class="text-sm text-text-muted px-4 pt-3 pb-2"> </div>'),$t[u],[[245,16]]),_f=_(m('<i class="fa-solid fa-power-off mr-1"></i> ',1),$t[u],[[255,24]]),mf=_(m('<i class="fa-solid fa-play mr-1"></i> ',1),$t[u],[[257,24]]),xf=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),$t[u],[[279,24]]),bf=_(m('<i class="fa-solid fa-trash-can mr-1"></i> ',1),$t[u],[[281,24]]),gf=_(m('<div class="p-4 text-sm text-text-subtle italic text-center"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </div>'),$t[u],[[287,16,[[288,20]]]]),hf=_(m('<div class="p-4 text-sm text-error-400 italic text-center"> </div>'),$t[u],[[291,16]]),yf=_(m('<button><i class="fa-solid fa-plug mr-1"></i> </button>'),$t[u],[[314,24,[[318,28]]]]),wf=_(m('<div class="flex items-center gap-2 mb-1.5 text-xs"><span class="font-mono text-accent-300 min-w-[180px]"> </span> <span class="flex-1 font-mono text-text-muted truncate"> </span> <button class="px-2 py-0.5 text-xs border border-border rounded bg-surface text-error-500 hover:bg-error-500/10 hover:border-error-500 transition-colors shrink-0"><i class="fa-solid fa-trash-can"></i></button></div>'),$t[u],[[345,36,[[346,40],[347,40],[348,40,[[353,44]]]]]]),kf=_(m('<div class="border-t border-border pt-3 mb-3"><div class="text-xs font-semibold uppercase tracking-wider text-primary-400 mb-2"><i class="fa-solid fa-gear mr-1"></i> </div> <!> <div class="flex items-center gap-2 mt-2 text-xs"><input type="text" placeholder="KEY" class="w-[180px] px-2 py-1 border border-border rounded bg-background text-text text-xs font-mono outline-none focus:border-primary-500 placeholder:text-text-subtle"/> <input type="text" placeholder="value" class="flex-1 px-2 py-1 border border-border rounded bg-background text-text text-xs font-mono outline-none focus:border-primary-500 placeholder:text-text-subtle"/> <button class="px-2.5 py-1 text-xs border border-border rounded bg-surface text-success-500 hover:bg-success-500/10 hover:border-success-500 transition-colors shrink-0"><i class="fa-solid fa-plus mr-1"></i> </button></div></div>'),$t[u],[[340,28,[[341,32,[[342,36]]],[359,32,[[360,36],[366,36],[372,36,[[376,40]]]]]]]]),$f=_(m('<div class="border-t border-border pt-3"><div class="text-xs font-semibold uppercase tracking-wider text-accent-500 mb-2"><i class="fa-solid fa-key mr-1"></i> </div> <!></div>'),$t[u],[[384,28,[[385,32,[[386,36]]]]]]),Tf=_(m('<div class="grid grid-cols-2 gap-x-4 gap-y-1 mb-4 text-xs"><div class="text-text-muted"> </div> <div class="font-mono text-accent-300"> </div> <div class="text-text-muted">Directory</div> <div class="font-mono text-accent-300 truncate"> </div> <div class="text-text-muted">Type</div> <div> </div> <div class="text-text-muted">Enabled</div> <div> </div></div> <!> <!>',1),$t[u],[[327,24,[[328,28],[329,28],[330,28],[331,28],[332,28],[333,28],[334,28],[335,28]]]]),Ef=_(m('<div class="p-4 text-sm text-text-subtle italic text-center"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </div>'),$t[u],[[408,32,[[409,36]]]]),Sf=_(m('<pre class="bg-background border border-border rounded-md p-3 text-[11px] font-mono text-text-muted max-h-[400px] overflow-auto whitespace-pre-wrap break-all leading-relaxed"> </pre>'),$t[u],[[412,32]]),Cf=_(m('<div class="relative"><div class="flex justify-between items-center mb-2"><span class="text-xs text-text-muted"> </span> <button class="px-2.5 py-1 text-xs border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-arrows-rotate mr-1"></i> </button></div> <!></div>'),$t[u],[[395,24,[[396,28,[[397,32],[400,32,[[404,36]]]]]]]]),Af=_(m('<div><div class="text-xs font-semibold uppercase tracking-wider text-violet-400 mb-2"><i class="fa-solid fa-window-maximize mr-1"></i> </div> <div class="bg-background border border-border rounded-md overflow-hidden"><iframe class="w-full border-0" style="min-height: 300px;" sandbox="allow-scripts allow-same-origin allow-forms allow-popups"></iframe></div></div>'),$t[u],[[420,32,[[421,36,[[422,40]]],[424,36,[[425,40]]]]]]),Pf=_(m('<div class="flex items-center gap-2"><a target="_blank" class="inline-flex items-center gap-1.5 px-3 py-1.5 text-xs font-medium rounded-md border border-violet-800/50 bg-violet-900/20 text-violet-400 hover:bg-violet-900/40 transition-colors"><i class="fa-solid fa-arrow-up-right-from-square"></i> </a></div>'),$t[u],[[438,32,[[439,36,[[444,40]]]]]]),jf=_(m('<div class="flex items-center gap-2 text-xs font-mono"><span class="px-1.5 py-0.5 rounded bg-violet-900/30 text-violet-300 border border-violet-800/40 min-w-[48px] text-center"> </span> <span class="text-text-muted"> </span></div>'),$t[u],[[457,44,[[458,48],[461,48]]]]),Of=_(m('<div><div class="text-xs font-semibold uppercase tracking-wider text-violet-400 mb-2"><i class="fa-solid fa-route mr-1"></i> </div> <div class="space-y-1"></div></div>'),$t[u],[[451,32,[[452,36,[[453,40]]],[455,36]]]]),Lf=_(m('<p class="text-sm text-text-subtle italic text-center py-4"> </p>'),$t[u],[[469,32]]),Rf=_(m('<div class="space-y-4"><!> <!> <!> <!></div>'),$t[u],[[417,24]]),Mf=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),$t[u],[[487,40]]),Nf=_(m('<i class="fa-solid fa-floppy-disk mr-1"></i> ',1),$t[u],[[489,40]]),If=_(m('<div class="text-xs text-error-400 mb-2 p-2 bg-error-500/10 border border-error-500/30 rounded"><i class="fa-solid fa-triangle-exclamation mr-1"></i> </div>'),$t[u],[[494,32,[[495,36]]]]),Df=_(m('<div class="text-xs text-success-500 mb-2 p-2 bg-success-500/10 border border-success-500/30 rounded"><i class="fa-solid fa-check mr-1"></i> </div>'),$t[u],[[499,32,[[500,36]]]]),Ff=_(m('<div><div class="flex justify-between items-center mb-2"><span class="text-xs text-text-muted"> </span> <button class="px-3 py-1.5 text-xs border border-border rounded-md bg-surface text-success-500 hover:bg-success-500/10 hover:border-success-500 transition-colors disabled:opacity-50"><!></button></div> <!> <!> <textarea spellcheck="false" class="w-full h-[320px] bg-background border border-border rounded-md p-3 text-[12px] font-mono text-text outline-none focus:border-primary-500 focus:ring-1 focus:ring-primary-900 resize-y leading-relaxed"></textarea></div>'),$t[u],[[476,24,[[477,28,[[478,32],[481,32]]],[503,28]]]]),Uf=_(m('<div class="flex border-b border-border"><button><i class="fa-solid fa-sliders mr-1"></i> </button> <button><i class="fa-solid fa-terminal mr-1"></i> </button> <button><i class="fa-solid fa-file-code mr-1"></i> </button> <!></div> <div class="p-4"><!></div>',1),$t[u],[[294,16,[[295,20,[[299,24]]],[301,20,[[305,24]]],[307,20,[[311,24]]]]],[324,16]]),zf=_(m('<div class="border-t border-border"><!> <div class="flex items-center gap-2 px-4 py-2 border-b border-border"><button><!></button> <button class="px-3 py-1.5 text-xs border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-rotate-right mr-1"></i> </button> <button class="px-3 py-1.5 text-xs border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-arrows-rotate mr-1"></i>Refresh</button> <div class="flex-1"></div> <button class="px-3 py-1.5 text-xs border border-error-500/40 rounded-md bg-surface text-error-400 hover:bg-error-500/10 hover:border-error-500 transition-colors disabled:opacity-50"><!></button></div> <!></div>'),$t[u],[[243,8,[[249,12,[[250,16],[260,16,[[264,20]]],[266,16,[[270,20]]],[272,16],[273,16]]]]]]),Bf=_(m('<div class="border border-border rounded-md mb-3 bg-surface-muted overflow-hidden"><button class="w-full p-4 flex justify-between items-center hover:bg-surface-elevated/40 transition-colors cursor-pointer text-left"><div class="flex items-center gap-2"><i></i> <span class="text-[15px] font-semibold"> </span></div> <div class="flex items-center gap-2"><!> <!> <!> <!></div></button> <!> <!></div>'),$t[u],[[190,0,[[192,4,[[196,8,[[197,12],[198,12]]],[200,8]]]]]]);function $t(r,i){wa(new.target),ga(i,!0,$t);let o=$c(i,"extension",3,null),d=S(P(!1),"expanded"),c=S(P(null),"detail"),f=S(P(!1),"detailLoading"),b=S(P(""),"detailError"),h=S(P("params"),"activeTab"),w=S(P(""),"logContent"),L=S(P(!1),"logLoading"),j=S(P(""),"manifestEdit"),A=S(P(!1),"manifestSaving"),k=S(P(""),"manifestError"),q=S(P(""),"manifestSuccess"),V=S(P(""),"newEnvKey"),X=S(P(""),"newEnvValue"),N=S(P(!1),"deleting");async function ne(){p(d,!e(d)),e(d)&&!e(c)&&(await M(je()))()}async function je(){p(f,!0),p(b,"");try{p(c,(await M(Nt("GET",`/api/skills/${encodeURIComponent(i.skill.name)}/detail`)))(),!0),p(j,e(c).manifest_raw,!0)}catch(F){p(b,F.message,!0)}finally{p(f,!1)}}async function we(){p(L,!0);try{const F=(await M(Nt("GET",`/api/skills/${encodeURIComponent(i.skill.name)}/log?lines=300`)))();p(w,F.log,!0)}catch(F){p(w,`Error loading log: ${F.message}`)}finally{p(L,!1)}}async function Te(){try{(await M(Nt("POST",`/api/skills/${encodeURIComponent(i.skill.name)}/restart`)))(),setTimeout(i.onrefresh,1e3)}catch(F){console.error(...Xt("error","restartSkill:",F)),alert(l("skills.restart_failed")+F.message)}}async function Ke(){const F=!i.skill.enabled;try{(await M(Nt("PUT",`/api/skills/${encodeURIComponent(i.skill.name)}/enabled`,{enabled:F})))(),setTimeout(i.onrefresh,500)}catch(he){console.error(...Xt("error","toggleEnabled:",he)),alert(l("skills.toggle_failed")+he.message)}}async function Ee(){p(A,!0),p(k,""),p(q,"");try{(await M(Nt("PUT",`/api/skills/${encodeURIComponent(i.skill.name)}/manifest`,{toml:e(j)})))(),p(q,l("skills.saved_restart"),!0),setTimeout(()=>{p(q,"")},5e3),(await M(je()))()}catch(F){p(k,F.message,!0)}finally{p(A,!1)}}async function te(){const F=e(V).trim(),he=e(X).trim();if(F)try{(await M(Nt("PUT",`/api/skills/${encodeURIComponent(i.skill.name)}/env`,{key:F,value:he})))(),p(V,""),p(X,""),(await M(je()))()}catch(Xe){alert(l("skills.env_failed")+Xe.message)}}async function _e(F){if(confirm(l("skills.remove_env_confirm",{key:F,skill:i.skill.name})))try{(await M(Nt("DELETE",`/api/skills/${encodeURIComponent(i.skill.name)}/env/${encodeURIComponent(F)}`)))(),(await M(je()))()}catch(he){alert(l("skills.delete_env_failed")+he.message)}}async function Ve(){if(confirm(l("skills.delete_confirm",{name:i.skill.name}))){p(N,!0);try{(await M(Nt("DELETE",`/api/skills/${encodeURIComponent(i.skill.name)}`)))(),i.onrefresh()}catch(F){console.error(...Xt("error","deleteSkill:",F)),alert(l("skills.delete_failed")+F.message)}finally{p(N,!1)}}}function K(F){p(h,F,!0),C(F,"logs")&&we()}let W=S(ma(()=>C(o(),null,!1)&&(on(o().ui.panel,null,!1)||on(o().ui.page,null,!1)||o().route_count>0)),"hasExtension");var Ue={...ka()},Ne=Bf(),Pe=a(Ne),R=a(Pe),ie=a(R),qe=s(ie,2),Se=a(qe),me=s(R,2),$=a(me);{var z=F=>{var he=of();v(F,he)},Z=F=>{var he=nf();v(F,he)};g(()=>y($,F=>{C(i.skill.skill_type,"daemon")?F(z):F(Z,!1)}),"if",$t,201,12)}var H=s($,2);{var Le=F=>{var he=lf();v(F,he)},O=F=>{var he=df();v(F,he)},B=F=>{var he=cf();v(F,he)};g(()=>y(H,F=>{i.skill.enabled?i.skill.running?F(O,1):F(B,!1):F(Le)}),"if",$t,211,12)}var ue=s(H,2);{var D=F=>{var he=vf();v(F,he)};g(()=>y(ue,F=>{e(W)&&F(D)}),"if",$t,225,12)}var T=s(ue,2);{var E=F=>{var he=uf(),Xe=a(he);x(()=>n(Xe,`PID ${i.skill.pid??""}`)),v(F,he)};g(()=>y(T,F=>{i.skill.pid&&F(E)}),"if",$t,231,12)}var U=s(Pe,2);{var J=F=>{var he=ff(),Xe=a(he);x(()=>n(Xe,i.skill.description)),v(F,he)};g(()=>y(U,F=>{i.skill.description&&!e(d)&&F(J)}),"if",$t,237,4)}var xe=s(U,2);{var fe=F=>{var he=zf(),Xe=a(he);{var yt=st=>{var De=pf(),lt=a(De);x(()=>n(lt,i.skill.description)),v(st,De)};g(()=>y(Xe,st=>{i.skill.description&&st(yt)}),"if",$t,244,12)}var ht=s(Xe,2),ct=a(ht),oe=a(ct);{var le=st=>{var De=_f(),lt=s(Ae(De),1,!0);x(Je=>n(lt,Je),[()=>l("skills.disable")]),v(st,De)},I=st=>{var De=mf(),lt=s(Ae(De),1,!0);x(Je=>n(lt,Je),[()=>l("skills.enable")]),v(st,De)};g(()=>y(oe,st=>{i.skill.enabled?st(le):st(I,!1)}),"if",$t,254,20)}var ae=s(ct,2),G=s(a(ae),1,!0),ee=s(ae,2),re=s(ee,4),ke=a(re);{var ve=st=>{var De=xf(),lt=s(Ae(De),1,!0);x(Je=>n(lt,Je),[()=>l("skills.deleting")]),v(st,De)},Q=st=>{var De=bf(),lt=s(Ae(De),1,!0);x(Je=>n(lt,Je),[()=>l("skills.delete")]),v(st,De)};g(()=>y(ke,st=>{e(N)?st(ve):st(Q,!1)}),"if",$t,278,20)}var $e=s(ht,2);{var Ie=st=>{var De=gf(),lt=s(a(De),1,!0);x(Je=>n(lt,Je),[()=>l("skills.loading_detail")]),v(st,De)},Re=st=>{var De=hf(),lt=a(De);x(()=>n(lt,e(b))),v(st,De)},Ct=st=>{var De=Uf(),lt=Ae(De),Je=a(lt),_t=s(a(Je),1,!0),ze=s(Je,2),be=s(a(ze),1,!0),ce=s(ze,2),pe=s(a(ce),1,!0),Oe=s(ce,2);{var Me=Fe=>{var ut=yf(),mt=s(a(ut),1,!0);x(Tt=>{Qe(ut,1,`px-4 py-2 text-xs font-semibold uppercase tracking-wider transition-colors ${C(e(h),"plugin")?"text-violet-400 border-b-2 border-violet-400":"text-text-muted hover:text-text"}`),n(mt,Tt)},[()=>l("skills.extension")]),Y("click",ut,function(){return K("plugin")}),v(Fe,ut)};g(()=>y(Oe,Fe=>{e(W)&&Fe(Me)}),"if",$t,313,20)}var Ze=s(lt,2),Be=a(Ze);{var We=Fe=>{var ut=Tf(),mt=Ae(ut),Tt=a(mt),xt=a(Tt),se=s(Tt,2),ye=a(se),et=s(se,4),dt=a(et),tt=s(et,4),Ye=a(tt),at=s(tt,4),ft=a(at),wt=s(mt,2);{var Ge=Pt=>{var Et=kf(),jt=a(Et),Ft=s(a(jt)),Ht=s(jt,2);g(()=>Dt(Ht,17,()=>Object.entries(e(c).env),([pa,zt])=>pa,(pa,zt)=>{var da=ma(()=>vo(e(zt),2));let ia=()=>e(da)[0];ia();let aa=()=>e(da)[1];aa();var $a=wf(),gt=a($a),Ot=a(gt,!0);var Lt=s(gt,2),Mt=a(Lt,!0);var fa=s(Lt,2);x(Aa=>{n(Ot,ia()),St(Lt,"title",aa()),n(Mt,aa()),St(fa,"title",Aa)},[()=>l("common.delete")]),Y("click",fa,function(){return _e(ia())}),v(pa,$a)}),"each",$t,344,32);var Ut=s(Ht,2),Kt=a(Ut),na=s(Kt,2),ta=s(na,2),Ea=s(a(ta),1,!0);x((pa,zt)=>{n(Ft,` ${pa??""}`),n(Ea,zt)},[()=>l("skills.environment_vars"),()=>l("skills.add")]),ua(Kt,function(){return e(V)},function(zt){p(V,zt)}),ua(na,function(){return e(X)},function(zt){p(X,zt)}),Y("click",ta,te),v(Pt,Et)},bt=ma(()=>Object.keys(e(c).env).length>0||!0);g(()=>y(wt,Pt=>{e(bt)&&Pt(Ge)}),"if",$t,339,24)}var pt=s(wt,2);{var vt=Pt=>{var Et=$f(),jt=a(Et),Ft=s(a(jt)),Ht=s(jt,2);g(()=>Dt(Ht,17,()=>i.skill.credentials,Ut=>Ut.name,(Ut,Kt)=>{g(()=>xi(Ut,{get credential(){return e(Kt)},get skillName(){return i.skill.name},get onchange(){return i.onrefresh}}),"component",$t,389,36,{componentTag:"CredentialRow"})}),"each",$t,388,32),x(Ut=>n(Ft,` ${Ut??""}`),[()=>l("skills.credentials")]),v(Pt,Et)};g(()=>y(pt,Pt=>{var Et;(Et=i.skill.credentials)!=null&&Et.length&&Pt(vt)}),"if",$t,383,24)}x(Pt=>{n(xt,Pt),n(ye,e(c).entrypoint),St(et,"title",e(c).dir),n(dt,e(c).dir),n(Ye,e(c).skill_type),n(ft,e(c).enabled?"Yes":"No")},[()=>l("skills.entrypoint")]),v(Fe,ut)},it=Fe=>{var ut=Cf(),mt=a(ut),Tt=a(mt),xt=a(Tt),se=s(Tt,2),ye=s(a(se),1,!0),et=s(mt,2);{var dt=Ye=>{var at=Ef(),ft=s(a(at),1,!0);x(wt=>n(ft,wt),[()=>l("common.loading")]),v(Ye,at)},tt=Ye=>{var at=Sf(),ft=a(at);x(wt=>n(ft,wt),[()=>e(w)||l("skills.no_log")]),v(Ye,at)};g(()=>y(et,Ye=>{e(L)?Ye(dt):Ye(tt,!1)}),"if",$t,407,28)}x((Ye,at)=>{n(xt,Ye),n(ye,at)},[()=>l("skills.last_lines"),()=>l("common.refresh")]),Y("click",se,we),v(Fe,ut)},ot=Fe=>{var ut=Rf(),mt=a(ut);{var Tt=Ye=>{var at=Af(),ft=a(at),wt=s(a(ft)),Ge=s(ft,2),bt=a(Ge);x((pt,vt)=>{n(wt,` ${pt??""}`),St(bt,"src",`/skills/${vt??""}/ui/${o().ui.panel??""}`),St(bt,"title",`${i.skill.name??""} extension panel`)},[()=>l("skills.skill_panel"),()=>encodeURIComponent(i.skill.name)]),v(Ye,at)};g(()=>y(mt,Ye=>{o().ui.panel&&Ye(Tt)}),"if",$t,419,28)}var xt=s(mt,2);{var se=Ye=>{var at=Pf(),ft=a(at),wt=s(a(ft));x((Ge,bt)=>{St(ft,"href",`/skills/${Ge??""}/page`),n(wt,` ${bt??""}`)},[()=>encodeURIComponent(i.skill.name),()=>l("skills.open_full_page")]),v(Ye,at)};g(()=>y(xt,Ye=>{o().ui.page&&Ye(se)}),"if",$t,437,28)}var ye=s(xt,2);{var et=Ye=>{var at=Of(),ft=a(at),wt=s(a(ft)),Ge=s(ft,2);g(()=>Dt(Ge,21,()=>o().routes,la,(bt,pt)=>{var vt=jf(),Pt=a(vt),Et=a(Pt,!0);var jt=s(Pt,2),Ft=a(jt);x((Ht,Ut)=>{n(Et,Ht),n(Ft,`/api/skills/${i.skill.name??""}/ext${Ut??""}`)},[()=>e(pt).split(" ")[0],()=>e(pt).split(" ").slice(1).join(" ")]),v(bt,vt)}),"each",$t,456,40),x(bt=>n(wt,` ${bt??""} (${o().route_count??""})`),[()=>l("skills.registered_routes")]),v(Ye,at)};g(()=>y(ye,Ye=>{o().route_count>0&&Ye(et)}),"if",$t,450,28)}var dt=s(ye,2);{var tt=Ye=>{var at=Lf(),ft=a(at);x(wt=>n(ft,wt),[()=>l("skills.no_extension_ui")]),v(Ye,at)};g(()=>y(dt,Ye=>{!o().ui.panel&&C(o().route_count,0)&&Ye(tt)}),"if",$t,468,28)}v(Fe,ut)},rt=Fe=>{var ut=Ff(),mt=a(ut),Tt=a(mt),xt=a(Tt),se=s(Tt,2),ye=a(se);{var et=Ge=>{var bt=Mf(),pt=s(Ae(bt),1,!0);x(vt=>n(pt,vt),[()=>l("common.saving")]),v(Ge,bt)},dt=Ge=>{var bt=Nf(),pt=s(Ae(bt),1,!0);x(vt=>n(pt,vt),[()=>l("common.save")]),v(Ge,bt)};g(()=>y(ye,Ge=>{e(A)?Ge(et):Ge(dt,!1)}),"if",$t,486,36)}var tt=s(mt,2);{var Ye=Ge=>{var bt=If(),pt=s(a(bt),1,!0);x(()=>n(pt,e(k))),v(Ge,bt)};g(()=>y(tt,Ge=>{e(k)&&Ge(Ye)}),"if",$t,493,28)}var at=s(tt,2);{var ft=Ge=>{var bt=Df(),pt=s(a(bt),1,!0);x(()=>n(pt,e(q))),v(Ge,bt)};g(()=>y(at,Ge=>{e(q)&&Ge(ft)}),"if",$t,498,28)}var wt=s(at,2);x(Ge=>{n(xt,Ge),se.disabled=e(A)},[()=>l("skills.edit_manifest")]),Y("click",se,Ee),ua(wt,function(){return e(j)},function(bt){p(j,bt)}),v(Fe,ut)};g(()=>y(Be,Fe=>{C(e(h),"params")?Fe(We):C(e(h),"logs")?Fe(it,1):C(e(h),"plugin")&&o()?Fe(ot,2):C(e(h),"manifest")&&Fe(rt,3)}),"if",$t,325,20)}x((Fe,ut,mt)=>{Qe(Je,1,`px-4 py-2 text-xs font-semibold uppercase tracking-wider transition-colors ${C(e(h),"params")?"text-primary-400 border-b-2 border-primary-400":"text-text-muted hover:text-text"}`),n(_t,Fe),Qe(ze,1,`px-4 py-2 text-xs font-semibold uppercase tracking-wider transition-colors ${C(e(h),"logs")?"text-primary-400 border-b-2 border-primary-400":"text-text-muted hover:text-text"}`),n(be,ut),Qe(ce,1,`px-4 py-2 text-xs font-semibold uppercase tracking-wider transition-colors ${C(e(h),"manifest")?"text-primary-400 border-b-2 border-primary-400":"text-text-muted hover:text-text"}`),n(pe,mt)},[()=>l("skills.parameters"),()=>l("skills.logs"),()=>l("skills.manifest")]),Y("click",Je,function(){return K("params")}),Y("click",ze,function(){return K("logs")}),Y("click",ce,function(){return K("manifest")}),v(st,De)};g(()=>y($e,st=>{e(f)?st(Ie):e(b)?st(Re,1):e(c)&&st(Ct,2)}),"if",$t,286,12)}x(st=>{Qe(ct,1,`px-3 py-1.5 text-xs border border-border rounded-md bg-surface transition-colors ${i.skill.enabled?"hover:bg-error-500/10 hover:border-error-500 text-error-400":"hover:bg-success-500/10 hover:border-success-500 text-success-500"}`),n(G,st),re.disabled=e(N)},[()=>l("skills.restart")]),Y("click",ct,Ke),Y("click",ae,Te),Y("click",ee,je),Y("click",re,Ve),v(F,he)};g(()=>y(xe,F=>{e(d)&&F(fe)}),"if",$t,242,4)}return x(()=>{Qe(ie,1,`fa-solid fa-chevron-right text-[10px] text-text-subtle transition-transform ${e(d)?"rotate-90":""}`),n(Se,i.skill.name)}),Y("click",Pe,ne),v(r,Ne),ha(Ue)}La(["click"]);za[u]="src/components/SkillMarketplace.svelte";var qf=_(m("<button> </button>"),za[u],[[153,16]]),Gf=_(m('<p class="text-text-subtle text-sm italic text-center py-4 col-span-full"> </p>'),za[u],[[170,12]]),Vf=_(m('<span class="text-[10px] px-1.5 py-0.5 rounded bg-surface-elevated border border-border/50 text-text-subtle"> </span>'),za[u],[[185,36]]),Hf=_(m('<span class="text-xs text-success-500 font-medium"><i class="fa-solid fa-check mr-1"></i> </span>'),za[u],[[192,28,[[193,32]]]]),Kf=_(m('<span class="text-xs text-error-500"> </span>'),za[u],[[196,28]]),Wf=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),za[u],[[204,36]]),Yf=_(m('<i class="fa-solid fa-download mr-1"></i> ',1),za[u],[[206,36]]),Jf=_(m('<button class="px-3 py-1 text-xs font-medium rounded border border-primary-600 text-primary-400 hover:bg-primary-900/30 transition-colors disabled:opacity-50"><!></button>'),za[u],[[198,28]]),Qf=_(m('<div class="p-3 rounded-lg border border-border bg-surface-muted hover:border-primary-500/30 transition-colors"><div class="flex items-start justify-between gap-2"><div class="min-w-0 flex-1"><div class="flex items-center gap-2"><i></i> <span class="text-sm font-semibold text-text"> </span></div> <p class="text-xs text-text-muted mt-1"> </p> <div class="flex items-center gap-2 mt-2"><span class="text-[10px] text-text-subtle"> </span> <div class="flex gap-1"></div></div></div> <div class="flex flex-col items-end gap-1 shrink-0"><!> <a target="_blank" rel="noopener noreferrer" class="text-[10px] text-text-subtle hover:text-primary-400 transition-colors"><i class="fa-brands fa-github mr-0.5"></i> </a></div></div></div>'),za[u],[[173,12,[[174,16,[[175,20,[[176,24,[[177,28],[178,28]]],[180,24],[181,24,[[182,28],[183,28]]]]],[190,20,[[210,24,[[216,28]]]]]]]]]]),Xf=_(m('<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden mt-4"><div class="flex justify-between items-center border-b border-border"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted"><i class="fa-solid fa-store mr-1.5"></i> </h2> <a href="https://github.com/topics/safe-agent-skill" target="_blank" rel="noopener noreferrer" class="text-xs text-primary-400 hover:text-primary-300 pr-3 transition-colors"><i class="fa-brands fa-github mr-1"></i> </a></div> <div class="px-4 py-2 border-b border-border flex flex-col sm:flex-row gap-2"><input type="text" class="flex-1 px-2.5 py-1.5 border border-border rounded-md bg-background text-text text-sm outline-none focus:border-primary-500 focus:ring-1 focus:ring-primary-900"/> <div class="flex gap-1 flex-wrap"></div></div> <div class="p-3 grid grid-cols-1 md:grid-cols-2 gap-3 max-h-[500px] overflow-y-auto custom-scroll"><!> <!></div></section>'),za[u],[[129,0,[[130,4,[[131,8,[[132,12]]],[134,8,[[140,12]]]]],[144,4,[[145,8],[151,8]]],[168,4]]]]);function za(r,i){wa(new.target),ga(i,!0,za);const o=[{name:"google-calendar",description:"Monitor and manage Google Calendar events with automatic reminders",author:"safe-agent",repo:"https://github.com/PegasusHeavyIndustries/safe-agent-skill-google-calendar",category:"productivity",tags:["calendar","google","reminders"],stars:0},{name:"web-monitor",description:"Monitor websites for changes and send notifications",author:"safe-agent",repo:"https://github.com/PegasusHeavyIndustries/safe-agent-skill-web-monitor",category:"data",tags:["monitoring","web","alerts"],stars:0},{name:"email-digest",description:"Summarize and categorize incoming emails with AI-powered triage",author:"safe-agent",repo:"https://github.com/PegasusHeavyIndustries/safe-agent-skill-email-digest",category:"communication",tags:["email","digest","summary"],stars:0},{name:"github-issues",description:"Track GitHub issues and PRs, auto-triage with labels",author:"safe-agent",repo:"https://github.com/PegasusHeavyIndustries/safe-agent-skill-github-issues",category:"development",tags:["github","issues","development"],stars:0},{name:"rss-reader",description:"Follow RSS/Atom feeds and get AI-summarized updates",author:"safe-agent",repo:"https://github.com/PegasusHeavyIndustries/safe-agent-skill-rss-reader",category:"data",tags:["rss","feeds","news"],stars:0},{name:"daily-briefing",description:"Generate a personalized morning briefing from your calendars, email, and news",author:"safe-agent",repo:"https://github.com/PegasusHeavyIndustries/safe-agent-skill-daily-briefing",category:"productivity",tags:["briefing","summary","daily"],stars:0}];let d=S(P(""),"searchQuery"),c=S(P("all"),"categoryFilter"),f=S(P(null),"installing"),b=S(P(Jt({})),"installStatus");const h=["all","productivity","development","communication","data"],w=S(ma(()=>o.filter(K=>{if(C(e(c),"all",!1)&&C(K.category,e(c),!1))return!1;if(e(d)){const W=e(d).toLowerCase();return K.name.toLowerCase().includes(W)||K.description.toLowerCase().includes(W)||K.tags.some(Ue=>Ue.includes(W))}return!0})),"filtered");function L(K){return{all:l("marketplace.category_all"),productivity:l("marketplace.category_productivity"),development:l("marketplace.category_development"),communication:l("marketplace.category_communication"),data:l("marketplace.category_data")}[K]??K}function j(K){return{productivity:"fa-briefcase",development:"fa-code",communication:"fa-envelope",data:"fa-chart-bar"}[K]??"fa-puzzle-piece"}async function A(K){p(f,K.name,!0);try{const W=(await M(Nt("POST","/api/skills/import",{source:"git",location:K.repo})))();p(b,{...e(b),[K.name]:W.ok?"ok":"error"},!0)}catch{p(b,{...e(b),[K.name]:"error"},!0)}p(f,null)}var k={...ka()},q=Xf(),V=a(q),X=a(V),N=s(a(X)),ne=s(X,2),je=s(a(ne)),we=s(V,2),Te=a(we),Ke=s(Te,2);g(()=>Dt(Ke,21,()=>h,la,(K,W)=>{var Ue=qf();let Ne;var Pe=a(Ue,!0);x(R=>{Ne=Qe(Ue,1,"px-2 py-1 text-xs rounded border transition-colors",null,Ne,{"bg-primary-600":C(e(c),e(W)),"text-white":C(e(c),e(W)),"border-primary-600":C(e(c),e(W)),"border-border":C(e(c),e(W),!1),"text-text-muted":C(e(c),e(W),!1)}),n(Pe,R)},[()=>L(e(W))]),Y("click",Ue,function(){return p(c,e(W),!0)}),v(K,Ue)}),"each",za,152,12);var Ee=s(we,2),te=a(Ee);{var _e=K=>{var W=Gf(),Ue=a(W);x(Ne=>n(Ue,Ne),[()=>l("marketplace.no_results")]),v(K,W)};g(()=>y(te,K=>{C(e(w).length,0)&&K(_e)}),"if",za,169,8)}var Ve=s(te,2);return g(()=>Dt(Ve,17,()=>e(w),K=>K.name,(K,W)=>{var Ue=Qf(),Ne=a(Ue),Pe=a(Ne),R=a(Pe),ie=a(R),qe=s(ie,2),Se=a(qe,!0);var me=s(R,2),$=a(me,!0);var z=s(me,2),Z=a(z),H=a(Z);var Le=s(Z,2);g(()=>Dt(Le,21,()=>e(W).tags.slice(0,3),la,(J,xe)=>{var fe=Vf(),F=a(fe,!0);x(()=>n(F,e(xe))),v(J,fe)}),"each",za,184,32);var O=s(Pe,2),B=a(O);{var ue=J=>{var xe=Hf(),fe=s(a(xe),1,!0);x(F=>n(fe,F),[()=>l("marketplace.installed")]),v(J,xe)},D=J=>{var xe=Kf(),fe=a(xe,!0);x(F=>n(fe,F),[()=>l("marketplace.failed")]),v(J,xe)},T=J=>{var xe=Jf(),fe=a(xe);{var F=Xe=>{var yt=Wf(),ht=s(Ae(yt),1,!0);x(ct=>n(ht,ct),[()=>l("marketplace.installing")]),v(Xe,yt)},he=Xe=>{var yt=Yf(),ht=s(Ae(yt),1,!0);x(ct=>n(ht,ct),[()=>l("marketplace.install")]),v(Xe,yt)};g(()=>y(fe,Xe=>{C(e(f),e(W).name)?Xe(F):Xe(he,!1)}),"if",za,203,32)}x(()=>xe.disabled=C(e(f),null,!1)),Y("click",xe,function(){return A(e(W))}),v(J,xe)};g(()=>y(B,J=>{C(e(b)[e(W).name],"ok")?J(ue):C(e(b)[e(W).name],"error")?J(D,1):J(T,!1)}),"if",za,191,24)}var E=s(B,2),U=s(a(E));x((J,xe,fe)=>{Qe(ie,1,`fa-solid ${J??""} text-primary-400 text-sm`),n(Se,e(W).name),n($,e(W).description),n(H,`${xe??""} ${e(W).author??""}`),St(E,"href",e(W).repo),n(U,` ${fe??""}`)},[()=>j(e(W).category),()=>l("marketplace.by"),()=>l("marketplace.repo")]),v(K,Ue)}),"each",za,172,8),x((K,W,Ue)=>{n(N,` ${K??""}`),n(je,` ${W??""}`),St(Te,"placeholder",Ue)},[()=>l("marketplace.title"),()=>l("marketplace.browse_github"),()=>l("marketplace.search")]),ua(Te,function(){return e(d)},function(W){p(d,W)}),v(r,q),ha(k)}La(["click"]);Fa[u]="src/components/SkillsTab.svelte";var Zf=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Fa[u],[[169,28]]),ep=_(m('<i class="fa-solid fa-download mr-1"></i> ',1),Fa[u],[[171,28]]),tp=_(m('<div class="mt-2 text-xs text-error-400 p-2 bg-error-500/10 border border-error-500/30 rounded"><i class="fa-solid fa-triangle-exclamation mr-1"></i> </div>'),Fa[u],[[178,16,[[179,20]]]]),ap=_(m('<div class="mt-2 text-xs text-success-500 p-2 bg-success-500/10 border border-success-500/30 rounded"><i class="fa-solid fa-check mr-1"></i> </div>'),Fa[u],[[183,16,[[184,20]]]]),rp=_(m('<div class="border-b border-border bg-surface-muted p-4"><div class="text-xs font-semibold uppercase tracking-wider text-primary-400 mb-3"><i class="fa-solid fa-file-import mr-1"></i> </div> <div class="flex items-center gap-1 mb-3"><button><i class="fa-brands fa-git-alt mr-1"></i> </button> <button><i class="fa-solid fa-globe mr-1"></i> </button> <button><i class="fa-solid fa-folder mr-1"></i> </button></div> <div class="flex flex-col gap-2"><input type="text" class="w-full px-3 py-2 text-sm border border-border rounded-md bg-background text-text font-mono outline-none focus:border-primary-500 focus:ring-1 focus:ring-primary-900 placeholder:text-text-subtle"/> <div class="flex items-center gap-2"><input type="text" class="flex-1 px-3 py-2 text-sm border border-border rounded-md bg-background text-text outline-none focus:border-primary-500 focus:ring-1 focus:ring-primary-900 placeholder:text-text-subtle"/> <button class="px-4 py-2 text-xs font-semibold border border-primary-500/50 rounded-md bg-primary-500/15 text-primary-400 hover:bg-primary-500/25 transition-colors disabled:opacity-50 disabled:cursor-not-allowed whitespace-nowrap"><!></button></div></div> <!> <!> <p class="mt-2 text-[11px] text-text-subtle"> </p></div>'),Fa[u],[[121,8,[[122,12,[[123,16]]],[127,12,[[128,16,[[132,20]]],[134,16,[[138,20]]],[140,16,[[144,20]]]]],[149,12,[[150,16],[156,16,[[157,20],[163,20]]]]],[188,12]]]]),sp=_(m('<p class="text-text-subtle text-sm italic text-center py-4"> </p>'),Fa[u],[[196,12]]),ip=_(m('<p class="text-text-subtle text-sm italic text-center py-4"> </p>'),Fa[u],[[198,12]]),op=_(m('<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden"><div class="flex justify-between items-center border-b border-border"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted"><i class="fa-solid fa-puzzle-piece mr-1.5"></i> </h2> <div class="flex items-center gap-2 pr-3"><span class="text-xs text-text-muted"> </span> <button class="px-2.5 py-1 text-xs border border-border rounded-md bg-surface hover:bg-primary-500/10 hover:border-primary-500 text-primary-400 transition-colors"><i class="fa-solid fa-file-import mr-1"></i> Import</button> <button class="px-2.5 py-1 text-xs border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-arrows-rotate mr-1"></i> </button></div></div> <!> <div class="p-3"><!></div></section> <!>',1),Fa[u],[[95,0,[[96,4,[[97,8,[[98,12]]],[100,8,[[101,12],[104,12,[[108,16]]],[110,12,[[114,16]]]]]]],[194,4]]]]);function Fa(r,i){wa(new.target),ga(i,!0,Fa);let o=S(P(Jt([])),"skills"),d=S(P(!1),"error"),c=S(P(Jt([])),"extensions"),f=S(P(!1),"showImport"),b=S(P("git"),"importSource"),h=S(P(""),"importLocation"),w=S(P(""),"importName"),L=S(P(!1),"importing"),j=S(P(""),"importError"),A=S(P(""),"importSuccess");async function k(){p(d,!1);try{p(o,(await M(Nt("GET","/api/skills")))(),!0)}catch($){p(d,!0),console.error(...Xt("error","loadSkills:",$))}}async function q(){try{p(c,(await M(Nt("GET","/api/skills/extensions")))(),!0)}catch($){console.error(...Xt("error","loadExtensions:",$))}}function V($){return e(c).find(z=>C(z.skill_name,$))??null}async function X(){const $=e(h).trim();if($){p(L,!0),p(j,""),p(A,"");try{const z={source:e(b),location:$},Z=e(w).trim();Z&&(z.name=Z);const H=(await M(Nt("POST","/api/skills/import",z)))();H.ok?(p(A,H.message??"Skill imported successfully",!0),p(h,""),p(w,""),setTimeout(()=>{p(A,""),p(f,!1)},3e3),k(),q()):p(j,H.message??"Import failed",!0)}catch(z){p(j,z.message,!0)}finally{p(L,!1)}}}Ga(()=>{C(kt.currentTab,"skills")&&(kt.refreshCounter,k(),q())});var N={...ka()},ne=op(),je=Ae(ne),we=a(je),Te=a(we),Ke=s(a(Te)),Ee=s(Te,2),te=a(Ee),_e=a(te),Ve=s(te,2),K=s(Ve,2),W=s(a(K)),Ue=s(we,2);{var Ne=$=>{var z=rp(),Z=a(z),H=s(a(Z)),Le=s(Z,2),O=a(Le),B=s(a(O)),ue=s(O,2),D=s(a(ue)),T=s(ue,2),E=s(a(T)),U=s(Le,2),J=a(U),xe=s(J,2),fe=a(xe),F=s(fe,2),he=a(F);{var Xe=G=>{var ee=Zf(),re=s(Ae(ee));x(ke=>n(re,` ${ke??""}`),[()=>l("skills.importing")]),v(G,ee)},yt=G=>{var ee=ep(),re=s(Ae(ee));x(ke=>n(re,` ${ke??""}`),[()=>l("skills.import_skill")]),v(G,ee)};g(()=>y(he,G=>{e(L)?G(Xe):G(yt,!1)}),"if",Fa,168,24)}var ht=s(U,2);{var ct=G=>{var ee=tp(),re=s(a(ee),1,!0);x(()=>n(re,e(j))),v(G,ee)};g(()=>y(ht,G=>{e(j)&&G(ct)}),"if",Fa,177,12)}var oe=s(ht,2);{var le=G=>{var ee=ap(),re=s(a(ee),1,!0);x(()=>n(re,e(A))),v(G,ee)};g(()=>y(oe,G=>{e(A)&&G(le)}),"if",Fa,182,12)}var I=s(oe,2),ae=a(I);x((G,ee,re,ke,ve,Q,$e,Ie)=>{n(H,` ${G??""}`),Qe(O,1,`px-3 py-1.5 text-xs rounded-md transition-colors ${C(e(b),"git")?"bg-primary-500/20 text-primary-400 border border-primary-500/50":"border border-border text-text-muted hover:bg-surface-elevated"}`),n(B,` ${ee??""}`),Qe(ue,1,`px-3 py-1.5 text-xs rounded-md transition-colors ${C(e(b),"url")?"bg-primary-500/20 text-primary-400 border border-primary-500/50":"border border-border text-text-muted hover:bg-surface-elevated"}`),n(D,` ${re??""}`),Qe(T,1,`px-3 py-1.5 text-xs rounded-md transition-colors ${C(e(b),"path")?"bg-primary-500/20 text-primary-400 border border-primary-500/50":"border border-border text-text-muted hover:bg-surface-elevated"}`),n(E,` ${ke??""}`),St(J,"placeholder",ve),St(fe,"placeholder",Q),F.disabled=$e,n(ae,Ie)},[()=>l("skills.import_skill"),()=>l("skills.git_repo"),()=>l("skills.archive_url"),()=>l("skills.local_path"),()=>C(e(b),"git")?l("skills.git_placeholder"):C(e(b),"url")?l("skills.url_placeholder"):l("skills.path_placeholder"),()=>l("skills.name_placeholder"),()=>e(L)||!e(h).trim(),()=>l("skills.import_desc")]),Y("click",O,function(){return p(b,"git")}),Y("click",ue,function(){return p(b,"url")}),Y("click",T,function(){return p(b,"path")}),ua(J,function(){return e(h)},function(ee){p(h,ee)}),ua(fe,function(){return e(w)},function(ee){p(w,ee)}),Y("click",F,X),v($,z)};g(()=>y(Ue,$=>{e(f)&&$(Ne)}),"if",Fa,120,4)}var Pe=s(Ue,2),R=a(Pe);{var ie=$=>{var z=sp(),Z=a(z);x(H=>n(Z,H),[()=>l("skills.error_loading")]),v($,z)},qe=$=>{var z=ip(),Z=a(z);x(H=>n(Z,H),[()=>l("skills.no_skills_import")]),v($,z)},Se=$=>{var z=Oa(),Z=Ae(z);g(()=>Dt(Z,17,()=>e(o),H=>H.name,(H,Le)=>{{let O=ma(()=>V(e(Le).name));g(()=>$t(H,{get skill(){return e(Le)},onrefresh:k,get extension(){return e(O)}}),"component",Fa,203,16,{componentTag:"SkillCard"})}}),"each",Fa,202,12),v($,z)};g(()=>y(R,$=>{e(d)?$(ie):C(e(o).length,0)?$(qe,1):$(Se,!1)}),"if",Fa,195,8)}var me=s(je,2);return g(()=>za(me,{}),"component",Fa,209,0,{componentTag:"SkillMarketplace"}),x(($,z,Z)=>{n(Ke,` ${$??""}`),n(_e,z),n(W,` ${Z??""}`)},[()=>l("skills.title"),()=>l("skills.count",{count:e(o).length}),()=>l("common.refresh")]),Y("click",Ve,function(){p(f,!e(f)),p(j,""),p(A,"")}),Y("click",K,k),v(r,ne),ha(N)}La(["click"]);ar[u]="src/components/KnowledgeGraphViz.svelte";var np=_(m('<div class="absolute inset-0 flex items-center justify-center bg-surface/80 z-10 rounded-lg"><p class="text-text-muted text-sm"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </p></div>'),ar[u],[[295,8,[[296,12,[[297,16]]]]]]),lp=_(m('<div class="flex items-center gap-1 text-[10px] text-text-muted"><span class="inline-block w-2.5 h-2.5 rounded-full"></span> </div>'),ar[u],[[330,12,[[331,16]]]]),dp=_(m('<div class="text-xs text-text-muted"><span class="text-accent-500"> </span> </div>'),ar[u],[[349,28,[[350,32]]]]),cp=_(m('<div class="mt-2 space-y-1"></div>'),ar[u],[[345,16]]),vp=_(m('<div class="mt-3 p-3 rounded-lg bg-surface-elevated border border-border"><div class="flex items-center justify-between mb-1"><span class="text-sm font-semibold text-text"> </span> <span class="text-[10px] px-1.5 py-0.5 rounded-full border border-border text-text-muted"> </span></div> <!></div>'),ar[u],[[339,8,[[340,12,[[341,16],[342,16]]]]]]),up=_(m('<div class="relative"><!> <div class="flex items-center justify-between mb-2"><p class="text-xs text-text-subtle"> </p> <div class="flex gap-1"><button class="px-2 py-1 text-xs border border-border rounded bg-surface hover:bg-surface-elevated"><i class="fa-solid fa-plus"></i></button> <button class="px-2 py-1 text-xs border border-border rounded bg-surface hover:bg-surface-elevated"><i class="fa-solid fa-minus"></i></button> <button class="px-2 py-1 text-xs border border-border rounded bg-surface hover:bg-surface-elevated"><i class="fa-solid fa-arrows-to-dot"></i></button></div></div> <canvas class="kg-canvas bg-surface-muted border border-border"></canvas> <div class="flex flex-wrap gap-3 mt-2"></div> <!></div>'),ar[u],[[293,0,[[302,4,[[303,8],[304,8,[[305,12,[[306,16]]],[308,12,[[309,16]]],[311,12,[[312,16]]]]]]],[317,4],[328,4]]]]);function ar(r,i){wa(new.target),ga(i,!0,ar);let o,d=S(P(Jt([])),"nodes"),c=S(P(Jt([])),"edges"),f=S(P(null),"selectedNode"),b=S(P(!0),"loading"),h=S(P(1),"zoom"),w=S(P(0),"panX"),L=S(P(0),"panY"),j=S(P(!1),"dragging"),A=null,k={x:0,y:0},q;const V={person:"#ff9800",concept:"#2196f3",event:"#9c27b0",place:"#4caf50",bookmark:"#e91e63",tag:"#00bcd4",fact:"#ff5722",entity:"#607d8b"};function X(O){return V[O.toLowerCase()]??"#a89c90"}async function N(){p(b,!0);try{const O=(await M(Nt("GET","/api/knowledge/nodes?limit=200")))(),B=(o==null?void 0:o.width)??800,ue=(o==null?void 0:o.height)??500;p(d,O.map((J,xe)=>{var fe;return{id:J.id,label:J.label,type:J.node_type??"node",x:B/2+(Math.random()-.5)*B*.6,y:ue/2+(Math.random()-.5)*ue*.6,vx:0,vy:0,radius:Math.min(8+(((fe=J.content)==null?void 0:fe.length)??0)/50,20)}}),!0);const D=new Set,T=[],E=e(d).slice(0,80).map(J=>J.id),U=10;for(let J=0;J<E.length;J+=U){const xe=E.slice(J,J+U),fe=(await M(Promise.all(xe.map(F=>Nt("GET",`/api/knowledge/nodes/${F}/neighbors`).then(he=>({id:F,neighbors:he})).catch(()=>({id:F,neighbors:[]}))))))();for(const{id:F,neighbors:he}of fe)for(const Xe of he){const yt=e(d).find(ct=>C(ct.label,Xe.node.label));if(!yt)continue;const ht=[Math.min(F,yt.id),Math.max(F,yt.id)].join("-");D.has(ht)||(D.add(ht),T.push({source:F,target:yt.id,relation:Xe.edge.relation}))}}p(c,T,!0)}catch(O){console.error(...Xt("error","loadGraph:",O))}p(b,!1)}function ne(){const T=new Map(e(d).map(E=>[E.id,E]));for(const E of e(d)){for(const xe of e(d)){if(C(E.id,xe.id))continue;const fe=E.x-xe.x,F=E.y-xe.y,he=Math.max(Math.sqrt(fe*fe+F*F),1),Xe=2e3/(he*he);E.vx+=fe/he*Xe*.3,E.vy+=F/he*Xe*.3}const U=((o==null?void 0:o.width)??800)/2,J=((o==null?void 0:o.height)??500)/2;E.vx+=(U-E.x)*.001,E.vy+=(J-E.y)*.001}for(const E of e(c)){const U=T.get(E.source),J=T.get(E.target);if(!U||!J)continue;const xe=J.x-U.x,fe=J.y-U.y,he=Math.sqrt(xe*xe+fe*fe)*.005;U.vx+=xe*he,U.vy+=fe*he,J.vx-=xe*he,J.vy-=fe*he}for(const E of e(d))A&&C(E.id,A.id)||(E.vx*=.85,E.vy*=.85,E.x+=E.vx,E.y+=E.vy)}function je(){var E;if(!o)return;const O=o.getContext("2d");if(!O)return;const B=o.width,ue=o.height,D=C(document.documentElement.getAttribute("data-theme"),"light",!1);O.clearRect(0,0,B,ue),O.save(),O.translate(e(w),e(L)),O.scale(e(h),e(h)),O.lineWidth=1;const T=new Map(e(d).map(U=>[U.id,U]));for(const U of e(c)){const J=T.get(U.source),xe=T.get(U.target);!J||!xe||(O.beginPath(),O.moveTo(J.x,J.y),O.lineTo(xe.x,xe.y),O.strokeStyle=D?"rgba(168,156,144,0.2)":"rgba(107,97,88,0.2)",O.stroke())}for(const U of e(d)){O.beginPath(),O.arc(U.x,U.y,U.radius,0,Math.PI*2),O.fillStyle=X(U.type),C((E=e(f))==null?void 0:E.id,U.id)&&(O.shadowColor=X(U.type),O.shadowBlur=15),O.fill(),O.shadowBlur=0,O.fillStyle=D?"#f5f0eb":"#1a1410",O.font='10px "Fira Sans", sans-serif',O.textAlign="center";const J=U.label.length>18?U.label.slice(0,16)+"…":U.label;O.fillText(J,U.x,U.y+U.radius+12)}O.restore(),ne(),q=requestAnimationFrame(je)}function we(O,B){const ue=(O-e(w))/e(h),D=(B-e(L))/e(h);for(const T of e(d)){const E=T.x-ue,U=T.y-D;if(E*E+U*U<(T.radius+4)*(T.radius+4))return T}return null}function Te(O){const B=o.getBoundingClientRect(),ue=O.clientX-B.left,D=O.clientY-B.top,T=we(ue,D);T?(A=T,p(f,T,!0)):p(j,!0),k={x:O.clientX,y:O.clientY}}function Ke(O){if(A){const B=o.getBoundingClientRect();A.x=(O.clientX-B.left-e(w))/e(h),A.y=(O.clientY-B.top-e(L))/e(h),A.vx=0,A.vy=0}else e(j)&&(p(w,e(w)+(O.clientX-k.x)),p(L,e(L)+(O.clientY-k.y)));k={x:O.clientX,y:O.clientY}}function Ee(){A=null,p(j,!1)}function te(O){O.preventDefault();const B=O.deltaY>0?.9:1.1;p(h,Math.max(.2,Math.min(5,e(h)*B)),!0)}function _e(){p(h,1),p(w,0),p(L,0),p(f,null)}Es(()=>{const O=()=>{if(o){const B=o.parentElement.getBoundingClientRect();o.width=B.width,o.height=500}};return O(),window.addEventListener("resize",O),N().then(()=>{q=requestAnimationFrame(je)}),()=>{cancelAnimationFrame(q),window.removeEventListener("resize",O)}});const Ve=S(ma(()=>e(f)?e(c).filter(O=>C(O.source,e(f).id)||C(O.target,e(f).id)):[]),"selectedEdges");var K={...ka()},W=up(),Ue=a(W);{var Ne=O=>{var B=np(),ue=a(B),D=s(a(ue));x(T=>n(D,` ${T??""}`),[()=>l("knowledge.loading_graph")]),v(O,B)};g(()=>y(Ue,O=>{e(b)&&O(Ne)}),"if",ar,294,4)}var Pe=s(Ue,2),R=a(Pe),ie=a(R),qe=s(R,2),Se=a(qe),me=s(Se,2),$=s(me,2),z=s(Pe,2);ml(z,O=>o=O,()=>o);var Z=s(z,2);g(()=>Dt(Z,21,()=>Object.entries(V),la,(O,B)=>{var ue=ma(()=>vo(e(B),2));let D=()=>e(ue)[0];D();let T=()=>e(ue)[1];T();var E=lp(),U=a(E),J=s(U);x(()=>{Ds(U,`background:${T()??""}`),n(J,` ${D()??""}`)}),v(O,E)}),"each",ar,329,8);var H=s(Z,2);{var Le=O=>{var B=vp(),ue=a(B),D=a(ue),T=a(D),E=s(D,2),U=a(E),J=s(ue,2);{var xe=fe=>{var F=cp();g(()=>Dt(F,21,()=>e(Ve),la,(he,Xe)=>{const yt=S(ma(()=>e(d).find(le=>C(le.id,C(e(Xe).source,e(f).id)?e(Xe).target:e(Xe).source))),"other");e(yt);var ht=Oa(),ct=Ae(ht);{var oe=le=>{var I=dp(),ae=a(I),G=a(ae,!0);var ee=s(ae);x(()=>{n(G,e(Xe).relation),n(ee,` → ${e(yt).label??""}`)}),v(le,I)};g(()=>y(ct,le=>{e(yt)&&le(oe)}),"if",ar,348,24)}v(he,ht)}),"each",ar,346,20),v(fe,F)};g(()=>y(J,fe=>{e(Ve).length>0&&fe(xe)}),"if",ar,344,12)}x(()=>{n(T,e(f).label),n(U,e(f).type)}),v(O,B)};g(()=>y(H,O=>{e(f)&&O(Le)}),"if",ar,338,4)}return x((O,B,ue,D)=>{n(ie,O),St(Se,"title",B),St(me,"title",ue),St($,"title",D)},[()=>l("knowledge.graph_hint"),()=>l("knowledge.zoom_in"),()=>l("knowledge.zoom_out"),()=>l("knowledge.reset_view")]),Y("click",Se,function(){return p(h,Math.min(5,e(h)*1.3),!0)}),Y("click",me,function(){return p(h,Math.max(.2,e(h)*.7),!0)}),Y("click",$,_e),Y("mousedown",z,Te),Y("mousemove",z,Ke),Y("mouseup",z,Ee),jo("mouseleave",z,Ee),jo("wheel",z,te),v(r,W),ha(K)}La(["click","mousedown","mousemove","mouseup"]);rr[u]="src/components/KnowledgeTab.svelte";var fp=_(m('<div class="p-3"><!></div>'),rr[u],[[117,8]]),pp=_(m('<div class="p-3 border border-border rounded-md mb-2 bg-surface-muted"><div class="text-xs uppercase tracking-wider text-accent-500 font-semibold mb-1"><i class="fa-solid fa-arrow-right-arrow-left mr-1"></i> </div> <div class="text-sm"> <span class="text-text-muted text-[11px]"> </span></div></div>'),rr[u],[[139,20,[[140,24,[[141,28]]],[143,24,[[145,28]]]]]]),_p=_(m('<button class="px-2.5 py-1 text-xs border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors mb-3"><i class="fa-solid fa-arrow-left mr-1"></i> </button> <!>',1),rr[u],[[132,16,[[136,20]]]]),mp=_(m('<p class="text-text-subtle text-sm italic text-center py-4"> </p>'),rr[u],[[150,16]]),xp=_(m('<button class="w-full text-left p-3 border border-border rounded-md mb-2 bg-surface-muted cursor-pointer hover:border-primary-500/50 transition-colors"><div class="text-xs uppercase tracking-wider text-primary-500 font-semibold mb-1"><i class="fa-solid fa-tag mr-1"></i> </div> <div class="text-sm font-semibold mb-1"> </div> <div class="text-xs text-text-muted"> </div> <div class="text-[11px] text-text-subtle mt-1"> </div></button>'),rr[u],[[153,20,[[157,24,[[158,28]]],[160,24],[161,24],[162,24]]]]),bp=_(m('<div class="px-4 py-2 border-b border-border"><input type="text" class="w-full px-2.5 py-1.5 border border-border rounded-md bg-background text-text text-sm outline-none focus:border-primary-500 focus:ring-1 focus:ring-primary-900 font-sans"/></div> <div class="p-3 max-h-[600px] overflow-y-auto custom-scroll"><!></div>',1),rr[u],[[121,8,[[122,12]]],[130,8]]),gp=_(m('<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden"><div class="flex justify-between items-center border-b border-border"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted"><i class="fa-solid fa-diagram-project mr-1.5"></i> </h2> <div class="flex items-center gap-2 pr-3"><span class="text-xs text-text-muted"><i class="fa-solid fa-circle-nodes mr-1"></i> </span> <div class="flex border border-border rounded overflow-hidden ml-2"><button><i class="fa-solid fa-list"></i></button> <button><i class="fa-solid fa-project-diagram"></i></button></div></div></div> <!></section>'),rr[u],[[79,0,[[80,4,[[81,8,[[82,12]]],[84,8,[[85,12,[[86,16]]],[89,12,[[90,16,[[99,20]]],[101,16,[[110,20]]]]]]]]]]]]);function rr(r,i){wa(new.target),ga(i,!0,rr);let o=S(P(Jt({nodes:0,edges:0})),"stats"),d=S(P(Jt([])),"nodes"),c=S(P(Jt([])),"neighbors"),f=S(P(!1),"viewingNeighbors"),b=S(P(""),"searchQuery"),h=S(P("list"),"viewMode");async function w(){try{const[Ne,Pe]=(await M(Promise.all([Nt("GET","/api/knowledge/stats"),Nt("GET","/api/knowledge/nodes?limit=100")])))();p(o,Ne,!0),p(d,Pe,!0),p(f,!1)}catch(Ne){console.error(...Xt("error","loadKnowledge:",Ne))}}async function L(){if(!e(b)){w();return}try{p(d,(await M(Nt("GET",`/api/knowledge/search?q=${encodeURIComponent(e(b))}`)))(),!0),p(f,!1)}catch(Ne){console.error(...Xt("error","searchKnowledge:",Ne))}}async function j(Ne){try{const Pe=(await M(Nt("GET",`/api/knowledge/nodes/${Ne}/neighbors`)))();if(!Pe.length){alert(l("knowledge.no_neighbors"));return}p(c,Pe,!0),p(f,!0)}catch(Pe){console.error(...Xt("error","loadNodeNeighbors:",Pe))}}function A(){p(f,!1)}function k(Ne){C(Ne.key,"Enter")&&L()}Ga(()=>{C(kt.currentTab,"knowledge")&&(kt.refreshCounter,w())});var q={...ka()},V=gp(),X=a(V),N=a(X),ne=s(a(N)),je=s(N,2),we=a(je),Te=s(a(we),1,!0),Ke=s(we,2),Ee=a(Ke);let te;var _e=s(Ee,2);let Ve;var K=s(X,2);{var W=Ne=>{var Pe=fp(),R=a(Pe);g(()=>ar(R,{}),"component",rr,118,12,{componentTag:"KnowledgeGraphViz"}),v(Ne,Pe)},Ue=Ne=>{var Pe=bp(),R=Ae(Pe),ie=a(R),qe=s(R,2),Se=a(qe);{var me=Z=>{var H=_p(),Le=Ae(H),O=s(a(Le)),B=s(Le,2);g(()=>Dt(B,17,()=>e(c),la,(ue,D)=>{var T=pp(),E=a(T),U=s(a(E),1,!0);var J=s(E,2),xe=a(J),fe=s(xe),F=a(fe);x(()=>{n(U,e(D).edge.relation),n(xe,`${e(D).node.label??""} `),n(F,`(${e(D).node.node_type??""})`)}),v(ue,T)}),"each",rr,138,16),x(ue=>n(O,` ${ue??""}`),[()=>l("knowledge.back")]),Y("click",Le,A),v(Z,H)},$=Z=>{var H=mp(),Le=a(H);x(O=>n(Le,O),[()=>l("knowledge.no_nodes")]),v(Z,H)},z=Z=>{var H=Oa(),Le=Ae(H);g(()=>Dt(Le,17,()=>e(d),O=>O.id,(O,B)=>{var ue=xp(),D=a(ue),T=s(a(D),1,!0);var E=s(D,2),U=a(E,!0);var J=s(E,2),xe=a(J,!0);var fe=s(J,2),F=a(fe);x((he,Xe,yt,ht)=>{n(T,e(B).node_type??"node"),n(U,e(B).label),n(xe,he),n(F,`${Xe??""} ${yt??""} · ${ht??""}`)},[()=>e(B).content.slice(0,200),()=>l("knowledge.confidence"),()=>(e(B).confidence??1).toFixed(2),()=>Zr(e(B).updated_at)]),Y("click",ue,function(){return j(e(B).id)}),v(O,ue)}),"each",rr,152,16),v(Z,H)};g(()=>y(Se,Z=>{e(f)?Z(me):C(e(d).length,0)?Z($,1):Z(z,!1)}),"if",rr,131,12)}x(Z=>St(ie,"placeholder",Z),[()=>l("knowledge.search_placeholder")]),Y("keyup",ie,k),ua(ie,function(){return e(b)},function(H){p(b,H)}),v(Ne,Pe)};g(()=>y(K,Ne=>{C(e(h),"graph")?Ne(W):Ne(Ue,!1)}),"if",rr,116,4)}return x((Ne,Pe,R,ie)=>{n(ne,` ${Ne??""}`),n(Te,Pe),te=Qe(Ee,1,"px-2 py-1 text-xs transition-colors",null,te,{"bg-primary-600":C(e(h),"list"),"text-white":C(e(h),"list"),"text-text-muted":C(e(h),"list",!1),"hover:text-text":C(e(h),"list",!1)}),St(Ee,"title",R),Ve=Qe(_e,1,"px-2 py-1 text-xs transition-colors",null,Ve,{"bg-primary-600":C(e(h),"graph"),"text-white":C(e(h),"graph"),"text-text-muted":C(e(h),"graph",!1),"hover:text-text":C(e(h),"graph",!1)}),St(_e,"title",ie)},[()=>l("knowledge.title"),()=>l("knowledge.summary",{nodes:e(o).nodes,edges:e(o).edges}),()=>l("knowledge.list_view"),()=>l("knowledge.graph_view")]),Y("click",Ee,function(){return p(h,"list")}),Y("click",_e,function(){return p(h,"graph")}),v(r,V),ha(q)}La(["click","keyup"]);Ts[u]="src/components/ToolsTab.svelte";var hp=_(m('<p class="text-text-subtle text-sm italic text-center py-4"> </p>'),Ts[u],[[31,12]]),yp=_(m('<div class="p-3 border border-border rounded-md mb-2 bg-surface-muted"><div class="text-xs uppercase tracking-wider text-primary-500 font-semibold mb-1"><i class="fa-solid fa-wrench mr-1"></i> </div> <div class="text-sm text-text-muted"> </div></div>'),Ts[u],[[34,16,[[35,20,[[36,24]]],[38,20]]]]),wp=_(m('<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted border-b border-border"><i class="fa-solid fa-screwdriver-wrench mr-1.5"></i> </h2> <div class="p-3 max-h-[600px] overflow-y-auto custom-scroll"><!></div></section>'),Ts[u],[[25,0,[[26,4,[[27,8]]],[29,4]]]]);function Ts(r,i){wa(new.target),ga(i,!0,Ts);let o=S(P(Jt([])),"tools");async function d(){try{p(o,(await M(Nt("GET","/api/tools")))(),!0)}catch(k){console.error(...Xt("error","loadTools:",k))}}Ga(()=>{C(kt.currentTab,"tools")&&(kt.refreshCounter,d())});var c={...ka()},f=wp(),b=a(f),h=s(a(b)),w=s(b,2),L=a(w);{var j=k=>{var q=hp(),V=a(q);x(X=>n(V,X),[()=>l("tools.no_tools")]),v(k,q)},A=k=>{var q=Oa(),V=Ae(q);g(()=>Dt(V,17,()=>e(o),X=>X.name,(X,N,ne,je)=>{var we=yp(),Te=a(we),Ke=s(a(Te),1,!0);var Ee=s(Te,2),te=a(Ee,!0);x(()=>{n(Ke,e(N).name),n(te,e(N).description)}),v(X,we)}),"each",Ts,33,12),v(k,q)};g(()=>y(L,k=>{C(e(o).length,0)?k(j):k(A,!1)}),"if",Ts,30,8)}return x(k=>n(h,` ${k??""}`),[()=>l("tools.title")]),v(r,f),ha(c)}Pa[u]="src/components/TrashTab.svelte";var kp=_(m('<button class="text-xs px-3 py-1.5 rounded bg-red-500/10 hover:bg-red-500/20 border border-red-500/30 text-red-400 hover:text-red-300 transition-colors"><i class="fa-solid fa-trash mr-1"></i> </button>'),Pa[u],[[164,24,[[168,28]]]]),$p=_(m('<div class="flex items-center gap-1.5"><span class="text-xs text-red-400"> </span> <button class="text-xs px-2 py-1 rounded bg-red-600 hover:bg-red-700 text-white transition-colors">Yes</button> <button class="text-xs px-2 py-1 rounded bg-surface-muted hover:bg-surface border border-border text-text-muted transition-colors">No</button></div>'),Pa[u],[[171,24,[[172,28],[173,28],[177,28]]]]),Tp=_(m('<div class="bg-red-500/10 border border-red-500/30 text-red-400 text-sm rounded-lg px-4 py-2"><i class="fa-solid fa-circle-exclamation mr-1.5"></i> </div>'),Pa[u],[[190,8,[[191,12]]]]),Ep=_(m('<div class="bg-emerald-500/10 border border-emerald-500/30 text-emerald-400 text-sm rounded-lg px-4 py-2"><i class="fa-solid fa-check-circle mr-1.5"></i> </div>'),Pa[u],[[195,8,[[196,12]]]]),Sp=_(m('<div class="relative"><i class="fa-solid fa-search absolute left-3 top-1/2 -translate-y-1/2 text-text-subtle text-xs"></i> <input type="text" class="w-full pl-9 pr-3 py-2 text-sm bg-surface border border-border rounded-lg text-text placeholder:text-text-subtle focus:outline-none focus:ring-1 focus:ring-primary-500 focus:border-primary-500"/></div>'),Pa[u],[[202,8,[[203,12],[204,12]]]]),Cp=_(m('<div class="p-8 text-center text-text-subtle text-sm"><i class="fa-solid fa-spinner fa-spin mr-1.5"></i> </div>'),Pa[u],[[216,12,[[217,16]]]]),Ap=_(m('<i class="fa-solid fa-search mr-1.5"></i> ',1),Pa[u],[[222,20]]),Pp=_(m('<i class="fa-solid fa-trash-can mr-1.5 opacity-40"></i> <p class="mt-1"> </p> <p class="text-xs mt-1 text-text-subtle opacity-70"> </p>',1),Pa[u],[[224,20],[225,20],[226,20]]),jp=_(m('<div class="p-8 text-center text-text-subtle text-sm"><!></div>'),Pa[u],[[220,12]]),Op=_(m('<div class="p-3 hover:bg-surface-muted/50 transition-colors group"><div class="flex items-start justify-between gap-3"><div class="flex-1 min-w-0"><div class="flex items-center gap-2 mb-1"><i></i> <span class="text-sm font-medium text-text truncate"> </span> <span class="text-xs text-text-subtle bg-surface-muted px-1.5 py-0.5 rounded"> </span></div> <div class="text-xs text-text-subtle truncate pl-6"> </div> <div class="flex items-center gap-3 mt-1 pl-6"><span class="text-xs text-text-subtle"><i class="fa-regular fa-clock mr-0.5"></i> </span> <span class="text-xs text-text-subtle"><i class="fa-solid fa-tag mr-0.5"></i> </span></div></div> <div class="flex items-center gap-1.5 shrink-0 opacity-0 group-hover:opacity-100 transition-opacity"><button class="text-xs px-2.5 py-1.5 rounded bg-emerald-500/10 hover:bg-emerald-500/20 border border-emerald-500/30 text-emerald-400 hover:text-emerald-300 transition-colors"><i class="fa-solid fa-rotate-left mr-1"></i> </button> <button class="text-xs px-2.5 py-1.5 rounded bg-red-500/10 hover:bg-red-500/20 border border-red-500/30 text-red-400 hover:text-red-300 transition-colors"><i class="fa-solid fa-xmark"></i></button></div></div></div>'),Pa[u],[[234,20,[[235,24,[[236,28,[[237,32,[[238,36],[239,36],[240,36]]],[244,32],[247,32,[[248,36,[[249,40]]],[251,36,[[252,40]]]]]]],[256,28,[[257,32,[[262,36]]],[264,32,[[269,36]]]]]]]]]]),Lp=_(m('<div class="max-h-[600px] overflow-y-auto custom-scroll divide-y divide-border"></div>'),Pa[u],[[232,12]]),Rp=_(m('<section class="space-y-4"><div class="bg-surface border border-border rounded-lg shadow-sm p-4"><div class="flex items-center justify-between"><div class="flex items-center gap-3"><h2 class="text-xs font-semibold uppercase tracking-wider text-text-muted"><i class="fa-solid fa-trash-can mr-1.5"></i> </h2> <span class="text-xs text-text-subtle bg-surface-muted px-2 py-0.5 rounded-full"> </span></div> <div class="flex items-center gap-2"><button class="text-xs px-3 py-1.5 rounded bg-surface-muted hover:bg-surface border border-border text-text-muted hover:text-text transition-colors"><i></i> </button> <!></div></div></div> <!> <!> <!> <div class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden"><!></div></section>'),Pa[u],[[142,0,[[144,4,[[145,8,[[146,12,[[147,16,[[148,20]]],[150,16]]],[154,12,[[155,16,[[160,20]]]]]]]]],[214,4]]]]);function Pa(r,i){wa(new.target),ga(i,!0,Pa);let o=S(P(Jt([])),"items"),d=S(P(Jt({count:0,total_bytes:0})),"stats"),c=S(P(!1),"loading"),f=S(P(""),"error"),b=S(P(""),"successMsg"),h=S(P(!1),"confirmEmpty"),w=S(P(""),"searchQuery");async function L(){p(c,!0),p(f,"");try{const D=(await M(Nt("GET","/api/trash")))();p(o,D.items,!0),p(d,D.stats,!0)}catch(D){p(f,(D==null?void 0:D.message)||l("trash.load_failed"),!0),console.error(...Xt("error","loadTrash:",D))}finally{p(c,!1)}}async function j(D){try{const T=(await M(Nt("POST",`/api/trash/${D}/restore`)))();p(b,T.message||l("trash.restored"),!0),(await M(L()))(),setTimeout(()=>p(b,""),3e3)}catch(T){p(f,(T==null?void 0:T.message)||l("trash.restore_failed"),!0)}}async function A(D,T){if(confirm(l("trash.permanent_confirm",{name:T})))try{const E=(await M(Nt("DELETE",`/api/trash/${D}`)))();p(b,E.message||l("trash.deleted"),!0),(await M(L()))(),setTimeout(()=>p(b,""),3e3)}catch(E){p(f,(E==null?void 0:E.message)||l("trash.delete_failed"),!0)}}async function k(){try{const D=(await M(Nt("POST","/api/trash/empty")))();p(b,D.message||l("trash.emptied"),!0),p(h,!1),(await M(L()))(),setTimeout(()=>p(b,""),3e3)}catch(D){p(f,(D==null?void 0:D.message)||l("trash.empty_failed"),!0)}}function q(D){if(C(D,0))return"0 B";const T=["B","KB","MB","GB"],E=Math.min(Math.floor(Math.log(D)/Math.log(1024)),T.length-1);return`${(D/Math.pow(1024,E)).toFixed(C(E,0)?0:1)} ${T[E]}`}function V(D){try{const T=new Date(D),U=new Date().getTime()-T.getTime(),J=Math.floor(U/6e4),xe=Math.floor(U/36e5),fe=Math.floor(U/864e5);return J<1?"just now":J<60?`${J}m ago`:xe<24?`${xe}h ago`:fe<7?`${fe}d ago`:T.toLocaleDateString()}catch{return D}}function X(D){return D.startsWith("tool:")?D.replace("tool:",""):D.startsWith("shell:")?D.replace("shell:","")+" (shell)":D.startsWith("rhai:")?D.replace("rhai:","")+" (skill)":D}let N=S(ma(()=>e(w).trim()?e(o).filter(D=>D.name.toLowerCase().includes(e(w).toLowerCase())||D.original_path.toLowerCase().includes(e(w).toLowerCase())||D.source.toLowerCase().includes(e(w).toLowerCase())):e(o)),"filtered");Ga(()=>{C(kt.currentTab,"trash")&&(kt.refreshCounter,L())});var ne={...ka()},je=Rp(),we=a(je),Te=a(we),Ke=a(Te),Ee=a(Ke),te=s(a(Ee)),_e=s(Ee,2),Ve=a(_e),K=s(Ke,2),W=a(K),Ue=a(W);let Ne;var Pe=s(Ue),R=s(W,2);{var ie=D=>{var T=Oa(),E=Ae(T);{var U=xe=>{var fe=kp(),F=s(a(fe));x(he=>n(F,` ${he??""}`),[()=>l("trash.empty_trash")]),Y("click",fe,function(){return p(h,!0)}),v(xe,fe)},J=xe=>{var fe=$p(),F=a(fe),he=a(F),Xe=s(F,2),yt=s(Xe,2);x(ht=>n(he,ht),[()=>l("trash.delete_all")]),Y("click",Xe,k),Y("click",yt,function(){return p(h,!1)}),v(xe,fe)};g(()=>y(E,xe=>{e(h)?xe(J,!1):xe(U)}),"if",Pa,163,20)}v(D,T)};g(()=>y(R,D=>{e(d).count>0&&D(ie)}),"if",Pa,162,16)}var qe=s(we,2);{var Se=D=>{var T=Tp(),E=s(a(T));x(()=>n(E,` ${e(f)??""}`)),v(D,T)};g(()=>y(qe,D=>{e(f)&&D(Se)}),"if",Pa,189,4)}var me=s(qe,2);{var $=D=>{var T=Ep(),E=s(a(T));x(()=>n(E,` ${e(b)??""}`)),v(D,T)};g(()=>y(me,D=>{e(b)&&D($)}),"if",Pa,194,4)}var z=s(me,2);{var Z=D=>{var T=Sp(),E=s(a(T),2);x(U=>St(E,"placeholder",U),[()=>l("trash.search_placeholder")]),ua(E,function(){return e(w)},function(J){p(w,J)}),v(D,T)};g(()=>y(z,D=>{e(o).length>0&&D(Z)}),"if",Pa,201,4)}var H=s(z,2),Le=a(H);{var O=D=>{var T=Cp(),E=s(a(T));x(U=>n(E,` ${U??""}`),[()=>l("common.loading")]),v(D,T)},B=D=>{var T=jp(),E=a(T);{var U=fe=>{var F=Ap(),he=s(Ae(F));x(Xe=>n(he,` ${Xe??""}`),[()=>l("trash.no_match",{query:e(w)})]),v(fe,F)},J=ma(()=>e(w).trim()),xe=fe=>{var F=Pp(),he=s(Ae(F),2),Xe=a(he),yt=s(he,2),ht=a(yt);x((ct,oe)=>{n(Xe,ct),n(ht,oe)},[()=>l("trash.empty"),()=>l("trash.empty_hint")]),v(fe,F)};g(()=>y(E,fe=>{e(J)?fe(U):fe(xe,!1)}),"if",Pa,221,16)}v(D,T)},ue=D=>{var T=Lp();g(()=>Dt(T,21,()=>e(N),E=>E.id,(E,U)=>{var J=Op(),xe=a(J),fe=a(xe),F=a(fe),he=a(F),Xe=s(he,2),yt=a(Xe,!0);var ht=s(Xe,2),ct=a(ht,!0);var oe=s(F,2),le=a(oe,!0);var I=s(oe,2),ae=a(I),G=s(a(ae));var ee=s(ae,2),re=s(a(ee));var ke=s(fe,2),ve=a(ke),Q=s(a(ve));var $e=s(ve,2);x((Ie,Re,Ct,st,De,lt)=>{Qe(he,1,`fa-solid ${e(U).is_dir?"fa-folder text-amber-400":"fa-file text-blue-400"} text-sm`),n(yt,e(U).name),n(ct,Ie),St(oe,"title",e(U).original_path),n(le,e(U).original_path),St(ae,"title",e(U).trashed_at),n(G,` ${Re??""}`),n(re,` ${Ct??""}`),St(ve,"title",st),n(Q,` ${De??""}`),St($e,"title",lt)},[()=>q(e(U).size_bytes),()=>V(e(U).trashed_at),()=>X(e(U).source),()=>l("trash.restore_title"),()=>l("trash.restore"),()=>l("trash.delete_permanent_title")]),Y("click",ve,function(){return j(e(U).id)}),Y("click",$e,function(){return A(e(U).id,e(U).name)}),v(E,J)}),"each",Pa,233,16),v(D,T)};g(()=>y(Le,D=>{e(c)&&C(e(o).length,0)?D(O):C(e(N).length,0)?D(B,1):D(ue,!1)}),"if",Pa,215,8)}return x((D,T,E,U)=>{n(te,` ${D??""}`),n(Ve,`${T??""} · ${E??""}`),W.disabled=e(c),Ne=Qe(Ue,1,"fa-solid fa-arrows-rotate mr-1",null,Ne,{"fa-spin":e(c)}),n(Pe,` ${U??""}`)},[()=>l("trash.title"),()=>l("trash.items_count",{count:e(d).count}),()=>q(e(d).total_bytes),()=>l("common.refresh")]),Y("click",W,L),v(r,je),ha(ne)}La(["click"]);Rt[u]="src/components/SecurityTab.svelte";var Mp=_(m("<button><i></i> </button>"),Rt[u],[[206,12,[[214,16]]]]),Np=_(m('<div class="grid grid-cols-2 md:grid-cols-4 gap-3"><div class="card p-3 text-center"><div class="text-2xl font-bold text-heading"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-3 text-center"><div class="text-2xl font-bold text-blue-400"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-3 text-center"><div> </div> <div class="text-xs text-muted"> <!></div></div> <div class="card p-3 text-center"><div> </div> <div class="text-xs text-muted"> </div></div></div> <div class="grid grid-cols-2 md:grid-cols-4 gap-3 mt-3"><div class="card p-3 text-center"><div class="text-lg font-bold text-green-400"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-3 text-center"><div class="text-lg font-bold text-red-400"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-3 text-center"><div class="text-lg font-bold text-orange-400"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-3 text-center"><div class="text-lg font-bold text-red-500"> </div> <div class="text-xs text-muted"> </div></div></div> <div class="grid grid-cols-1 md:grid-cols-2 gap-3 mt-3"><div class="card p-4"><h3 class="text-sm font-semibold text-heading mb-2"><i class="fa-solid fa-dollar-sign mr-1"></i>Cost Summary</h3> <div class="space-y-1 text-sm"><div class="flex justify-between"><span class="text-muted"> </span><span> </span></div> <div class="flex justify-between"><span class="text-muted"> </span><span> </span></div> <div class="flex justify-between"><span class="text-muted"> </span><span> </span></div> <div class="flex justify-between"><span class="text-muted"> </span><span> </span></div> <div class="flex justify-between"><span class="text-muted"> </span><span> </span></div> <div class="flex justify-between"><span class="text-muted">Requests Today</span><span> </span></div></div></div> <div class="card p-4"><h3 class="text-sm font-semibold text-heading mb-2"><i class="fa-solid fa-gauge-high mr-1"></i>Rate Limits</h3> <div class="space-y-1 text-sm"><div class="flex justify-between"><span class="text-muted">Per Minute</span><span> </span></div> <div class="flex justify-between"><span class="text-muted">Per Hour</span><span> </span></div> <div class="flex justify-between"><span class="text-muted">Status</span> <span> </span></div> <div class="flex justify-between"><span class="text-muted">2FA Challenges</span><span> </span></div> <div class="flex justify-between"><span class="text-muted">Permission Denials</span><span> </span></div></div></div></div>',1),Rt[u],[[222,12,[[223,16,[[224,20],[225,20]]],[227,16,[[228,20],[229,20]]],[231,16,[[232,20],[235,20]]],[242,16,[[243,20],[246,20]]]]],[250,12,[[251,16,[[252,20],[253,20]]],[255,16,[[256,20],[257,20]]],[259,16,[[260,20],[261,20]]],[263,16,[[264,20],[265,20]]]]],[269,12,[[270,16,[[271,20,[[271,72]]],[272,20,[[273,24,[[273,58],[273,117]]],[274,24,[[274,58],[274,116]]],[275,24,[[275,58],[275,116]]],[276,24,[[276,58],[276,112]]],[277,24,[[277,58],[277,118]]],[278,24,[[278,58],[278,104]]]]]]],[281,16,[[282,20,[[282,72]]],[283,20,[[284,24,[[284,58],[284,100]]],[285,24,[[285,58],[285,98]]],[286,24,[[287,28],[288,28]]],[292,24,[[292,58],[292,104]]],[293,24,[[293,58],[293,108]]]]]]]]]]),Ip=_(m('<p class="text-muted text-sm"> </p>'),Rt[u],[[298,12]]),Dp=_(m('<p class="text-muted text-sm"> </p>'),Rt[u],[[326,12]]),Fp=_(m('<span class="text-xs bg-card-hover px-1.5 py-0.5 rounded"> </span>'),Rt[u],[[336,36]]),Up=_(m('<span class="text-xs text-muted"> </span>'),Rt[u],[[339,36]]),zp=_(m("<span> </span>"),Rt[u],[[342,36]]),Bp=_(m('<div class="text-xs text-muted mt-0.5 truncate"> </div>'),Rt[u],[[349,32]]),qp=_(m('<div class="text-xs text-muted mt-0.5 truncate"> </div>'),Rt[u],[[352,32]]),Gp=_(m('<div class="card p-2 flex items-start gap-2 text-sm"><i></i> <div class="flex-1 min-w-0"><div class="flex items-center gap-2"><span> </span> <!> <!> <!> <span class="text-xs text-muted ml-auto"> </span></div> <!> <!> <div class="text-xs text-muted mt-0.5 flex justify-between"><span> </span> <button class="text-blue-400 hover:text-blue-300" title="Explain this action"><i class="fa-solid fa-magnifying-glass"></i> </button></div></div></div>'),Rt[u],[[330,20,[[331,24],[332,24,[[333,28,[[334,32],[346,32]]],[354,28,[[355,32],[356,32,[[357,36]]]]]]]]]]),Vp=_(m('<div class="space-y-1"></div>'),Rt[u],[[328,12]]),Hp=_(m('<p class="text-muted text-sm"> </p>'),Rt[u],[[377,24]]),Kp=_(m('<span class="text-muted"> </span>'),Rt[u],[[384,55]]),Wp=_(m('<div class="text-sm mt-1"><span class="text-muted">Reasoning:</span> </div>'),Rt[u],[[387,40,[[387,66]]]]),Yp=_(m('<div class="text-xs text-muted mt-1"> </div>'),Rt[u],[[390,40]]),Jp=_(m('<div class="text-xs text-muted mt-1"> </div>'),Rt[u],[[393,40]]),Qp=_(m('<div><div> <!></div> <!> <!> <!> <div class="text-xs text-muted"> </div></div>'),Rt[u],[[381,32,[[382,36],[395,36]]]]),Xp=_(m('<div class="space-y-2"></div>'),Rt[u],[[379,24]]),Zp=_(m('<div class="fixed inset-0 bg-black/60 flex items-center justify-center z-50" role="dialog"><div class="card p-4 max-w-lg w-full mx-4 max-h-[80vh] overflow-y-auto" role="document"><div class="flex justify-between items-center mb-3"><h3 class="text-lg font-bold text-heading"><i class="fa-solid fa-lightbulb mr-2"></i> </h3> <button class="text-muted hover:text-heading"><i class="fa-solid fa-xmark"></i></button></div> <!></div></div>'),Rt[u],[[368,12,[[369,16,[[370,20,[[371,24,[[371,67]]],[372,24,[[373,28]]]]]]]]]]),e_=_(m('<div class="flex gap-2 mb-3"><select class="bg-bg border border-border rounded px-2 py-1 text-sm"><option> </option><option>Tool Calls</option><option>Approvals</option><option>Rate Limits</option><option>PII Detected</option><option>2FA</option><option>Permission Denied</option></select> <input type="text" class="bg-bg border border-border rounded px-2 py-1 text-sm flex-1"/> <button class="btn btn-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div> <!> <!>',1),Rt[u],[[303,8,[[304,12,[[305,16],[306,16],[307,16],[308,16],[309,16],[310,16],[311,16]]],[313,12],[320,12,[[321,16]]]]]]),t_=_(m('<div class="mt-2 w-full bg-bg rounded-full h-2"><div></div></div> <div class="text-xs text-muted mt-1"> </div>',1),Rt[u],[[414,24,[[415,28]]],[422,24]]),a_=_(m('<div class="grid grid-cols-2 md:grid-cols-3 gap-3 mb-4"><div class="card p-4 text-center"><div> </div> <div class="text-xs text-muted"> </div> <!></div> <div class="card p-4 text-center"><div class="text-3xl font-bold text-heading"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-4 text-center"><div class="text-3xl font-bold text-heading"> </div> <div class="text-xs text-muted"> </div></div></div> <div class="grid grid-cols-3 gap-3"><div class="card p-3 text-center"><div class="text-xl font-bold"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-3 text-center"><div class="text-xl font-bold"> </div> <div class="text-xs text-muted"> </div></div> <div class="card p-3 text-center"><div class="text-xl font-bold"> </div> <div class="text-xs text-muted">Requests Today</div></div></div>',1),Rt[u],[[407,12,[[408,16,[[409,20],[412,20]]],[425,16,[[426,20],[427,20]]],[429,16,[[430,20],[431,20]]]]],[434,12,[[435,16,[[436,20],[437,20]]],[439,16,[[440,20],[441,20]]],[443,16,[[444,20],[445,20]]]]]]),r_=_(m('<p class="text-muted text-sm"> </p>'),Rt[u],[[449,12]]),s_=_(m('<div class="w-full bg-bg rounded-full h-3"><div></div></div>'),Rt[u],[[464,28,[[465,32]]]]),i_=_(m('<div class="w-full bg-bg rounded-full h-3"><div></div></div>'),Rt[u],[[481,28,[[482,32]]]]),o_=_(m('<div class="card p-6 max-w-md"><h3 class="text-lg font-bold text-heading mb-4"><i class="fa-solid fa-gauge-high mr-2"></i>Rate Limit Status</h3> <div class="space-y-4"><div><div class="flex justify-between text-sm mb-1"><span class="text-muted">Per Minute</span> <span> </span></div> <!></div> <div><div class="flex justify-between text-sm mb-1"><span class="text-muted">Per Hour</span> <span> </span></div> <!></div> <div class="text-center p-3 rounded"><span> </span></div></div></div>'),Rt[u],[[455,12,[[456,16,[[456,64]]],[457,16,[[458,20,[[459,24,[[460,28],[461,28]]]]],[475,20,[[476,24,[[477,28],[478,28]]]]],[492,20,[[493,24]]]]]]]]),n_=_(m('<p class="text-muted text-sm"> </p>'),Rt[u],[[500,12]]),l_=_(m(`<div class="card p-6 text-center"><i class="fa-solid fa-shield-check text-3xl text-green-400 mb-2"></i> <p class="text-muted"> </p> <p class="text-xs text-muted mt-1">When the agent attempts a dangerous operation (like executing shell commands),
-                        a 2FA challenge will appear here for your confirmation.</p></div>`),Rt[u],[[514,16,[[515,20],[516,20],[517,20]]]]),d_=_(m('<div class="card p-4"><div class="flex items-start gap-3"><i class="fa-solid fa-lock text-purple-400 text-lg mt-1"></i> <div class="flex-1"><div class="flex items-center gap-2"><span class="font-semibold text-heading"> </span> <span class="text-xs bg-card-hover px-1.5 py-0.5 rounded"> </span> <span class="text-xs text-muted"> </span></div> <p class="text-sm text-muted mt-1"> </p> <div class="flex gap-2 mt-2"><button class="btn btn-sm bg-green-600 hover:bg-green-500 text-white"><i class="fa-solid fa-check mr-1"></i> </button> <button class="btn btn-sm bg-red-600 hover:bg-red-500 text-white"><i class="fa-solid fa-xmark mr-1"></i> </button></div></div></div></div>'),Rt[u],[[524,20,[[525,24,[[526,28],[527,28,[[528,32,[[529,36],[530,36],[531,36]]],[533,32],[534,32,[[535,36,[[536,40]]],[538,36,[[539,40]]]]]]]]]]]]),c_=_(m('<div class="space-y-3"><div class="flex items-center justify-between mb-2"><h3 class="text-lg font-bold text-heading"><i class="fa-solid fa-lock mr-2"></i> </h3> <button class="btn btn-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div> <!></div>'),Rt[u],[[505,8,[[506,12,[[507,16,[[507,59]]],[508,16,[[509,20]]]]]]]]),v_=_(m('<div class="space-y-4"><div class="flex items-center gap-3 mb-4"><h2 class="text-xl font-bold text-heading"><i class="fa-solid fa-shield-halved mr-2"></i> </h2></div> <div class="flex gap-1 border-b border-border pb-1 mb-4"></div> <!></div>'),Rt[u],[[192,0,[[193,4,[[194,8,[[194,51]]]]],[198,4]]]]);function Rt(r,i){wa(new.target),ga(i,!0,Rt);let o=S(P(null),"auditSummary"),d=S(P(null),"costSummary"),c=S(P(null),"rateStatus"),f=S(P(Jt([])),"challenges"),b=S(P(Jt([])),"auditEntries"),h=S(P(Jt([])),"explanationChain"),w=S(P(!1),"showExplanation"),L=S(P("overview"),"activeSection"),j=yi(Jt({event_type:"",tool:""}),"auditFilter");async function A(){try{const $=(await M(fetch("/api/security/overview")))(),z=(await M($.json()))();p(o,z.audit,!0),p(d,z.cost,!0),p(c,z.rate_limit,!0),p(f,[],!0)}catch($){console.error(...Xt("error","Failed to load security overview",$))}}async function k(){try{const $=new URLSearchParams({limit:"50"});j.event_type&&$.set("event_type",j.event_type),j.tool&&$.set("tool",j.tool);const z=(await M(fetch(`/api/security/audit?${$}`)))();p(b,(await M(z.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load audit log",$))}}async function q(){try{const $=(await M(fetch("/api/security/cost")))();p(d,(await M($.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load cost summary",$))}}async function V(){try{const $=(await M(fetch("/api/security/rate-limit")))();p(c,(await M($.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load rate limit status",$))}}async function X(){try{const $=(await M(fetch("/api/security/2fa")))();p(f,(await M($.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load 2FA challenges",$))}}async function N($){(await M(fetch(`/api/security/2fa/${$}/confirm`,{method:"POST"})))(),(await M(X()))()}async function ne($){(await M(fetch(`/api/security/2fa/${$}/reject`,{method:"POST"})))(),(await M(X()))()}async function je($){try{const z=(await M(fetch(`/api/security/audit/${$}/explain`)))();p(h,(await M(z.json()))(),!0),p(w,!0)}catch(z){console.error(...Xt("error","Failed to load explanation",z))}}function we($){p(L,$,!0),C($,"overview")?A():C($,"audit")?k():C($,"cost")?q():C($,"rate")?V():C($,"2fa")&&X()}function Te($){return`$${$.toFixed(4)}`}function Ke($){return $>=1e6?`${($/1e6).toFixed(1)}M`:$>=1e3?`${($/1e3).toFixed(1)}K`:$.toString()}function Ee($){switch($){case"tool_call":return"text-blue-400";case"approval":return"text-yellow-400";case"rate_limit":return"text-orange-400";case"pii_detected":return"text-red-400";case"2fa":return"text-purple-400";case"permission_denied":return"text-red-500";default:return"text-muted"}}function te($){switch($){case"tool_call":return"fa-wrench";case"approval":return"fa-check-double";case"rate_limit":return"fa-gauge-high";case"pii_detected":return"fa-user-shield";case"2fa":return"fa-lock";case"permission_denied":return"fa-ban";default:return"fa-circle-info"}}Ga(()=>{kt.refreshCounter,A()});var _e={...ka()},Ve=v_(),K=a(Ve),W=a(K),Ue=s(a(W),1,!0),Ne=s(K,2);g(()=>Dt(Ne,21,()=>[{id:"overview",label:l("security.overview"),icon:"fa-chart-pie"},{id:"audit",label:l("security.audit_trail"),icon:"fa-scroll"},{id:"cost",label:l("security.cost_tracking"),icon:"fa-dollar-sign"},{id:"rate",label:l("security.rate_limits"),icon:"fa-gauge-high"},{id:"2fa",label:l("security.twofa"),icon:"fa-lock"}],la,($,z)=>{var Z=Mp();let H;var Le=a(Z),O=s(Le,1,!0);x(()=>{H=Qe(Z,1,"px-3 py-1.5 text-sm rounded-t transition-colors",null,H,{"bg-card":C(e(L),e(z).id),"text-heading":C(e(L),e(z).id),"text-muted":C(e(L),e(z).id,!1),"hover:text-heading":C(e(L),e(z).id,!1)}),Qe(Le,1,`fa-solid ${e(z).icon??""} mr-1`),n(O,e(z).label)}),Y("click",Z,function(){return we(e(z).id)}),v($,Z)}),"each",Rt,199,8);var Pe=s(Ne,2);{var R=$=>{var z=Oa(),Z=Ae(z);{var H=O=>{var B=Np(),ue=Ae(B),D=a(ue),T=a(D),E=a(T),U=s(T,2),J=a(U),xe=s(D,2),fe=a(xe),F=a(fe),he=s(fe,2),Xe=a(he),yt=s(xe,2),ht=a(yt);let ct;var oe=a(ht),le=s(ht,2),I=a(le),ae=s(I);{var G=Za=>{var qt=Jr();x(Wt=>n(qt,`/ ${Wt??""}`),[()=>Te(e(d).daily_limit_usd)]),v(Za,qt)};g(()=>y(ae,Za=>{e(d).daily_limit_usd>0&&Za(G)}),"if",Rt,237,24)}var ee=s(yt,2),re=a(ee);let ke;var ve=a(re),Q=s(re,2),$e=a(Q),Ie=s(ue,2),Re=a(Ie),Ct=a(Re),st=a(Ct),De=s(Ct,2),lt=a(De),Je=s(Re,2),_t=a(Je),ze=a(_t),be=s(_t,2),ce=a(be),pe=s(Je,2),Oe=a(pe),Me=a(Oe),Ze=s(Oe,2),Be=a(Ze),We=s(pe,2),it=a(We),ot=a(it),rt=s(it,2),Fe=a(rt),ut=s(Ie,2),mt=a(ut),Tt=s(a(mt),2),xt=a(Tt),se=a(xt),ye=a(se),et=s(se),dt=a(et),tt=s(xt,2),Ye=a(tt),at=a(Ye),ft=s(Ye),wt=a(ft),Ge=s(tt,2),bt=a(Ge),pt=a(bt),vt=s(bt),Pt=a(vt),Et=s(Ge,2),jt=a(Et),Ft=a(jt),Ht=s(jt),Ut=a(Ht),Kt=s(Et,2),na=a(Kt),ta=a(na),Ea=s(na),pa=a(Ea),zt=s(Kt,2),da=s(a(zt)),ia=a(da),aa=s(mt,2),$a=s(a(aa),2),gt=a($a),Ot=s(a(gt)),Lt=a(Ot),Mt=s(gt,2),fa=s(a(Mt)),Aa=a(fa),Ra=s(Mt,2),Zt=s(a(Ra),2);let Ya;var vr=a(Zt),sr=s(Ra,2),ir=s(a(sr)),br=a(ir),Er=s(sr,2),or=s(a(Er)),Dr=a(or);x((Za,qt,Wt,xa,ba,rs,Sr,Cr,Fr,Ur,Ss,er,fs,zr,Cs,He,nt,It,Gt)=>{n(E,e(o).total_events),n(J,Za),n(F,e(o).tool_calls),n(Xe,qt),ct=Qe(ht,1,"text-2xl font-bold",null,ct,{"text-red-400":e(d).limit_exceeded,"text-green-400":!e(d).limit_exceeded}),n(oe,Wt),n(I,`${xa??""} `),ke=Qe(re,1,"text-2xl font-bold",null,ke,{"text-orange-400":e(c).is_limited,"text-green-400":!e(c).is_limited}),n(ve,`${e(c).calls_last_minute??""}/${(e(c).limit_per_minute||"∞")??""}`),n($e,ba),n(st,e(o).approvals),n(lt,rs),n(ze,e(o).rejections),n(ce,Sr),n(Me,e(o).rate_limits),n(Be,Cr),n(ot,e(o).pii_detections),n(Fe,Fr),n(ye,Ur),n(dt,Ss),n(at,er),n(wt,fs),n(pt,zr),n(Pt,Cs),n(Ft,He),n(Ut,nt),n(ta,It),n(pa,Gt),n(ia,e(d).today_requests),n(Lt,`${e(c).calls_last_minute??""} / ${(e(c).limit_per_minute||"∞")??""}`),n(Aa,`${e(c).calls_last_hour??""} / ${(e(c).limit_per_hour||"∞")??""}`),Ya=Qe(Zt,1,"",null,Ya,{"text-red-400":e(c).is_limited,"text-green-400":!e(c).is_limited}),n(vr,e(c).is_limited?"RATE LIMITED":"OK"),n(br,e(o).twofa_challenges),n(Dr,e(o).permission_denials)},[()=>l("security.total_events"),()=>l("security.tool_calls"),()=>Te(e(d).today_usd),()=>l("security.todays_cost"),()=>l("security.calls_per_min"),()=>l("security.approvals"),()=>l("security.rejections"),()=>l("security.rate_limits"),()=>l("security.pii_detections"),()=>l("security.todays_cost"),()=>Te(e(d).today_usd),()=>l("security.month_cost"),()=>Te(e(d).month_usd),()=>l("security.total_cost"),()=>Te(e(d).total_usd),()=>l("security.tokens"),()=>Ke(e(d).today_tokens),()=>l("security.total_tokens"),()=>Ke(e(d).total_tokens)]),v(O,B)},Le=O=>{var B=Ip(),ue=a(B);x(D=>n(ue,D),[()=>l("common.loading")]),v(O,B)};g(()=>y(Z,O=>{e(o)&&e(d)&&e(c)?O(H):O(Le,!1)}),"if",Rt,221,8)}v($,z)},ie=$=>{var z=e_(),Z=Ae(z),H=a(Z),Le=a(H),O=a(Le);Le.value=Le.__value="";var B=s(Le);B.value=B.__value="tool_call";var ue=s(B);ue.value=ue.__value="approval";var D=s(ue);D.value=D.__value="rate_limit";var T=s(D);T.value=T.__value="pii_detected";var E=s(T);E.value=E.__value="2fa";var U=s(E);U.value=U.__value="permission_denied",xn("bind:value={auditFilter.event_type}",[],()=>j,()=>"event_type",304,81);var J=s(H,2);xn("bind:value={auditFilter.tool}",[],()=>j,()=>"tool",317,16);var xe=s(J,2),fe=s(a(xe),1,!0),F=s(Z,2);{var he=ct=>{var oe=Dp(),le=a(oe);x(I=>n(le,I),[()=>l("security.no_events")]),v(ct,oe)},Xe=ct=>{var oe=Vp();g(()=>Dt(oe,21,()=>e(b),la,(le,I)=>{var ae=Gp(),G=a(ae),ee=s(G,2),re=a(ee),ke=a(re),ve=a(ke,!0);var Q=s(ke,2);{var $e=Be=>{var We=Fp(),it=a(We,!0);x(()=>n(it,e(I).tool)),v(Be,We)};g(()=>y(Q,Be=>{e(I).tool&&Be($e)}),"if",Rt,335,32)}var Ie=s(Q,2);{var Re=Be=>{var We=Up(),it=a(We,!0);x(()=>n(it,e(I).action)),v(Be,We)};g(()=>y(Ie,Be=>{e(I).action&&Be(Re)}),"if",Rt,338,32)}var Ct=s(Ie,2);{var st=Be=>{var We=zp();let it;var ot=a(We,!0);x(()=>{it=Qe(We,1,"text-xs",null,it,{"text-green-400":e(I).success,"text-red-400":!e(I).success}),n(ot,e(I).success?"✓":"✗")}),v(Be,We)};g(()=>y(Ct,Be=>{C(e(I).success,null,!1)&&Be(st)}),"if",Rt,341,32)}var De=s(Ct,2),lt=a(De,!0);var Je=s(re,2);{var _t=Be=>{var We=Bp(),it=a(We);x(()=>n(it,`Reasoning: ${e(I).reasoning??""}`)),v(Be,We)};g(()=>y(Je,Be=>{e(I).reasoning&&Be(_t)}),"if",Rt,348,28)}var ze=s(Je,2);{var be=Be=>{var We=qp(),it=a(We);x(()=>n(it,`Result: ${e(I).result??""}`)),v(Be,We)};g(()=>y(ze,Be=>{e(I).result&&Be(be)}),"if",Rt,351,28)}var ce=s(ze,2),pe=a(ce),Oe=a(pe,!0);var Me=s(pe,2),Ze=s(a(Me));x((Be,We,it,ot,rt)=>{Qe(G,1,`fa-solid ${Be??""} ${We??""} mt-0.5`),Qe(ke,1,`font-semibold ${it??""}`),n(ve,e(I).event_type),n(lt,e(I).source),n(Oe,ot),n(Ze,` ${rt??""}`)},[()=>te(e(I).event_type),()=>Ee(e(I).event_type),()=>Ee(e(I).event_type),()=>Zr(e(I).created_at),()=>l("security.why")]),Y("click",Me,function(){return je(e(I).id)}),v(le,ae)}),"each",Rt,329,16),v(ct,oe)};g(()=>y(F,ct=>{C(e(b).length,0)?ct(he):ct(Xe,!1)}),"if",Rt,325,8)}var yt=s(F,2);{var ht=ct=>{var oe=Zp(),le=a(oe),I=a(le),ae=a(I),G=s(a(ae),1,!0),ee=s(ae,2),re=s(I,2);{var ke=Q=>{var $e=Hp(),Ie=a($e);x(Re=>n(Ie,Re),[()=>l("security.no_explanation")]),v(Q,$e)},ve=Q=>{var $e=Xp();g(()=>Dt($e,21,()=>e(h),la,(Ie,Re,Ct)=>{var st=Qp();let De;var lt=a(st),Je=a(lt),_t=s(Je);{var ze=it=>{var ot=Kp(),rt=a(ot);x(()=>n(rt,`(${e(Re).tool??""})`)),v(it,ot)};g(()=>y(_t,it=>{e(Re).tool&&it(ze)}),"if",Rt,384,40)}var be=s(lt,2);{var ce=it=>{var ot=Wp(),rt=s(a(ot));x(()=>n(rt,` ${e(Re).reasoning??""}`)),v(it,ot)};g(()=>y(be,it=>{e(Re).reasoning&&it(ce)}),"if",Rt,386,36)}var pe=s(be,2);{var Oe=it=>{var ot=Yp(),rt=a(ot);x(()=>n(rt,`Context: ${e(Re).user_context??""}`)),v(it,ot)};g(()=>y(pe,it=>{e(Re).user_context&&it(Oe)}),"if",Rt,389,36)}var Me=s(pe,2);{var Ze=it=>{var ot=Jp(),rt=a(ot);x(()=>n(rt,`Result: ${e(Re).result??""}`)),v(it,ot)};g(()=>y(Me,it=>{e(Re).result&&it(Ze)}),"if",Rt,392,36)}var Be=s(Me,2),We=a(Be,!0);x((it,ot,rt)=>{De=Qe(st,1,"border-l-2 pl-3",null,De,it),Qe(lt,1,`text-sm font-semibold ${ot??""}`),n(Je,`Step ${Ct+1}: ${e(Re).event_type??""} `),n(We,rt)},[()=>({"border-blue-400":C(e(Re).event_type,"tool_call"),"border-yellow-400":C(e(Re).event_type,"approval"),"border-red-400":C(e(Re).event_type,"pii_detected")||C(e(Re).event_type,"permission_denied"),"border-border":!["tool_call","approval","pii_detected","permission_denied"].includes(e(Re).event_type)}),()=>Ee(e(Re).event_type),()=>Zr(e(Re).created_at)]),v(Ie,st)}),"each",Rt,380,28),v(Q,$e)};g(()=>y(re,Q=>{C(e(h).length,0)?Q(ke):Q(ve,!1)}),"if",Rt,376,20)}x((Q,$e)=>{n(G,Q),St(ee,"title",$e)},[()=>l("security.action_explanation"),()=>l("common.close")]),Y("click",oe,function(){return p(w,!1)}),Y("click",le,function($e){return $e.stopPropagation()}),Y("click",ee,function(){return p(w,!1)}),v(ct,oe)};g(()=>y(yt,ct=>{e(w)&&ct(ht)}),"if",Rt,367,8)}x((ct,oe,le)=>{n(O,ct),St(J,"placeholder",oe),n(fe,le)},[()=>l("security.all_types"),()=>l("security.filter"),()=>l("common.refresh")]),Y("change",H,function(){return k()}),Gi(H,function(){return j.event_type},function(oe){j.event_type=oe}),Y("keyup",J,function(oe){C(oe.key,"Enter")&&k()}),ua(J,function(){return j.tool},function(oe){j.tool=oe}),Y("click",xe,function(){return k()}),v($,z)},qe=$=>{var z=Oa(),Z=Ae(z);{var H=O=>{var B=a_(),ue=Ae(B),D=a(ue),T=a(D);let E;var U=a(T),J=s(T,2),xe=a(J),fe=s(J,2);{var F=ze=>{var be=t_(),ce=Ae(be),pe=a(ce);let Oe;var Me=s(ce,2),Ze=a(Me);x((Be,We)=>{Oe=Qe(pe,1,"h-2 rounded-full transition-all",null,Oe,{"bg-green-400":!e(d).limit_exceeded,"bg-red-400":e(d).limit_exceeded}),Ds(pe,`width: ${Be??""}%`),n(Ze,`Limit: ${We??""}`)},[()=>Math.min(100,e(d).today_usd/e(d).daily_limit_usd*100),()=>Te(e(d).daily_limit_usd)]),v(ze,be)};g(()=>y(fe,ze=>{e(d).daily_limit_usd>0&&ze(F)}),"if",Rt,413,20)}var he=s(D,2),Xe=a(he),yt=a(Xe),ht=s(Xe,2),ct=a(ht),oe=s(he,2),le=a(oe),I=a(le),ae=s(le,2),G=a(ae),ee=s(ue,2),re=a(ee),ke=a(re),ve=a(ke),Q=s(ke,2),$e=a(Q),Ie=s(re,2),Re=a(Ie),Ct=a(Re),st=s(Re,2),De=a(st),lt=s(Ie,2),Je=a(lt),_t=a(Je);x((ze,be,ce,pe,Oe,Me,Ze,Be,We,it)=>{E=Qe(T,1,"text-3xl font-bold",null,E,{"text-red-400":e(d).limit_exceeded,"text-green-400":!e(d).limit_exceeded}),n(U,ze),n(xe,be),n(yt,ce),n(ct,pe),n(I,Oe),n(G,Me),n(ve,Ze),n($e,Be),n(Ct,We),n(De,it),n(_t,e(d).today_requests)},[()=>Te(e(d).today_usd),()=>l("security.todays_cost"),()=>Te(e(d).month_usd),()=>l("security.month_cost"),()=>Te(e(d).total_usd),()=>l("security.total_cost"),()=>Ke(e(d).today_tokens),()=>l("security.tokens"),()=>Ke(e(d).total_tokens),()=>l("security.total_tokens")]),v(O,B)},Le=O=>{var B=r_(),ue=a(B);x(D=>n(ue,D),[()=>l("common.loading")]),v(O,B)};g(()=>y(Z,O=>{e(d)?O(H):O(Le,!1)}),"if",Rt,406,8)}v($,z)},Se=$=>{var z=Oa(),Z=Ae(z);{var H=O=>{var B=o_(),ue=s(a(B),2),D=a(ue),T=a(D),E=s(a(T),2),U=a(E),J=s(T,2);{var xe=ae=>{var G=s_(),ee=a(G);let re;x(ke=>{re=Qe(ee,1,"h-3 rounded-full transition-all",null,re,{"bg-green-400":e(c).calls_last_minute/e(c).limit_per_minute<.8,"bg-yellow-400":e(c).calls_last_minute/e(c).limit_per_minute>=.8&&e(c).calls_last_minute/e(c).limit_per_minute<1,"bg-red-400":e(c).calls_last_minute/e(c).limit_per_minute>=1}),Ds(ee,`width: ${ke??""}%`)},[()=>Math.min(100,e(c).calls_last_minute/e(c).limit_per_minute*100)]),v(ae,G)};g(()=>y(J,ae=>{e(c).limit_per_minute>0&&ae(xe)}),"if",Rt,463,24)}var fe=s(D,2),F=a(fe),he=s(a(F),2),Xe=a(he),yt=s(F,2);{var ht=ae=>{var G=i_(),ee=a(G);let re;x(ke=>{re=Qe(ee,1,"h-3 rounded-full transition-all",null,re,{"bg-green-400":e(c).calls_last_hour/e(c).limit_per_hour<.8,"bg-yellow-400":e(c).calls_last_hour/e(c).limit_per_hour>=.8&&e(c).calls_last_hour/e(c).limit_per_hour<1,"bg-red-400":e(c).calls_last_hour/e(c).limit_per_hour>=1}),Ds(ee,`width: ${ke??""}%`)},[()=>Math.min(100,e(c).calls_last_hour/e(c).limit_per_hour*100)]),v(ae,G)};g(()=>y(yt,ae=>{e(c).limit_per_hour>0&&ae(ht)}),"if",Rt,480,24)}var ct=s(fe,2),oe=a(ct);let le;var I=a(oe);x(()=>{n(U,`${e(c).calls_last_minute??""} / ${(e(c).limit_per_minute||"∞")??""}`),n(Xe,`${e(c).calls_last_hour??""} / ${(e(c).limit_per_hour||"∞")??""}`),Ds(ct,`background-color: ${e(c).is_limited?"rgba(248,113,113,0.1)":"rgba(74,222,128,0.1)"}`),le=Qe(oe,1,"text-lg font-bold",null,le,{"text-green-400":!e(c).is_limited,"text-red-400":e(c).is_limited}),n(I,e(c).is_limited?"RATE LIMITED":"Within Limits")}),v(O,B)},Le=O=>{var B=n_(),ue=a(B);x(D=>n(ue,D),[()=>l("common.loading")]),v(O,B)};g(()=>y(Z,O=>{e(c)?O(H):O(Le,!1)}),"if",Rt,454,8)}v($,z)},me=$=>{var z=c_(),Z=a(z),H=a(Z),Le=s(a(H),1,!0),O=s(H,2),B=s(a(O),1,!0),ue=s(Z,2);{var D=E=>{var U=l_(),J=s(a(U),2),xe=a(J);x(fe=>n(xe,fe),[()=>l("security.no_challenges")]),v(E,U)},T=E=>{var U=Oa(),J=Ae(U);g(()=>Dt(J,17,()=>e(f),la,(xe,fe)=>{var F=d_(),he=a(F),Xe=s(a(he),2),yt=a(Xe),ht=a(yt),ct=a(ht,!0);var oe=s(ht,2),le=a(oe,!0);var I=s(oe,2),ae=a(I);var G=s(yt,2),ee=a(G,!0);var re=s(G,2),ke=a(re),ve=s(a(ke),1,!0);var Q=s(ke,2),$e=s(a(Q),1,!0);x((Ie,Re)=>{n(ct,e(fe).tool),n(le,e(fe).source),n(ae,`${e(fe).age_secs??""}s ago`),n(ee,e(fe).description),n(ve,Ie),n($e,Re)},[()=>l("common.confirm"),()=>l("security.reject")]),Y("click",ke,function(){return N(e(fe).id)}),Y("click",Q,function(){return ne(e(fe).id)}),v(xe,F)}),"each",Rt,523,16),v(E,U)};g(()=>y(ue,E=>{C(e(f).length,0)?E(D):E(T,!1)}),"if",Rt,513,12)}x((E,U)=>{n(Le,E),n(B,U)},[()=>l("security.pending_challenges"),()=>l("common.refresh")]),Y("click",O,function(){return X()}),v($,z)};g(()=>y(Pe,$=>{C(e(L),"overview")?$(R):C(e(L),"audit")?$(ie,1):C(e(L),"cost")?$(qe,2):C(e(L),"rate")?$(Se,3):C(e(L),"2fa")&&$(me,4)}),"if",Rt,220,4)}return x($=>n(Ue,$),[()=>l("security.title")]),v(r,Ve),ha(_e)}La(["click","change","keyup"]);Ce[u]="src/components/OperationsTab.svelte";var u_=_(m("<button><i></i> </button>"),Ce[u],[[271,12,[[275,16]]]]),f_=_(m('<p class="text-muted"> </p>'),Ce[u],[[287,16]]),p_=_(m('<div class="grid grid-cols-1 sm:grid-cols-3 gap-3 mt-3"><div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div></div></div>'),Ce[u],[[298,24,[[299,28,[[300,32],[301,32]]],[305,28,[[306,32],[307,32]]],[311,28,[[312,32],[313,32]]]]]]),__=_(m('<div class="space-y-3"><div class="flex items-center gap-3"><span> </span> <span class="text-muted text-sm"> </span></div> <!> <button class="btn-secondary text-sm mt-2"><i class="fa-solid fa-rotate mr-1"></i> </button></div>'),Ce[u],[[289,16,[[290,20,[[291,24],[294,24]]],[318,20,[[319,24]]]]]]),m_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-heart-pulse mr-1"></i> </h3> <!></div> <div class="card"><h3 class="text-lg font-semibold mb-2"><i class="fa-solid fa-chart-bar mr-1"></i> </h3> <p class="text-sm text-muted mb-2"> </p> <p class="text-sm text-muted"> </p> <div class="mt-2"><button class="btn-secondary text-sm"><i class="fa-solid fa-arrow-up-right-from-square mr-1"></i> </button></div></div>',1),Ce[u],[[282,8,[[283,12,[[284,16]]]]],[326,8,[[327,12,[[328,16]]],[330,12],[333,12],[336,12,[[337,16,[[338,20]]]]]]]]),x_=_(m('<span class="text-sm text-muted">&rarr;</span> <span class="font-mono text-green-400"> </span> <span class="bg-green-500/20 text-green-400 text-xs px-2 py-0.5 rounded"> </span>',1),Ce[u],[[356,28],[357,28],[358,28]]),b_=_(m('<span class="bg-surface text-muted text-xs px-2 py-0.5 rounded"> </span>'),Ce[u],[[360,28]]),g_=_(m('<div class="bg-surface rounded p-3 text-sm"><div class="text-xs text-muted mb-1"> </div> <pre class="whitespace-pre-wrap text-fg"> </pre></div>'),Ce[u],[[365,24,[[366,28],[367,28]]]]),h_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[375,36]]),y_=_(m('<i class="fa-solid fa-rocket mr-1"></i> ',1),Ce[u],[[377,36]]),w_=_(m('<a target="_blank" class="btn-secondary text-sm"><i class="fa-solid fa-arrow-up-right-from-square mr-1"></i> </a>'),Ce[u],[[381,32,[[382,36]]]]),k_=_(m('<div class="flex gap-2"><button class="btn-primary text-sm"><!></button> <!></div>'),Ce[u],[[372,24,[[373,28]]]]),$_=_(m('<div class="flex items-center gap-3 flex-wrap"><span class="text-sm text-muted"> </span> <span class="font-mono text-fg"> </span> <!></div> <!> <!>',1),Ce[u],[[352,20,[[353,24],[354,24]]]]),T_=_(m('<div class="bg-surface rounded p-2 text-sm text-muted"> </div>'),Ce[u],[[390,20]]),E_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[395,24]]),S_=_(m('<i class="fa-solid fa-magnifying-glass mr-1"></i> ',1),Ce[u],[[397,24]]),C_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-download mr-1"></i> </h3> <div class="space-y-3"><!> <!> <button class="btn-secondary text-sm"><!></button></div></div>'),Ce[u],[[346,8,[[347,12,[[348,16]]],[350,12,[[393,16]]]]]]),A_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[418,28]]),P_=_(m('<i class="fa-solid fa-download mr-1"></i> ',1),Ce[u],[[420,28]]),j_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[434,28]]),O_=_(m('<i class="fa-solid fa-upload mr-1"></i> ',1),Ce[u],[[436,28]]),L_=_(m('<div class="bg-surface rounded p-2 text-sm text-muted mt-2"> </div>'),Ce[u],[[441,24]]),R_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-box-archive mr-1"></i> </h3> <div class="space-y-4"><div><h4 class="text-sm font-medium mb-2"> </h4> <p class="text-xs text-muted mb-2">Download a JSON export of all agent data (memory, activity, goals, cron jobs, stats).</p> <button class="btn-primary text-sm"><!></button></div> <hr class="border-border"/> <div><h4 class="text-sm font-medium mb-2"> </h4> <p class="text-xs text-muted mb-2">Upload a previously exported backup file. Existing data will be merged (INSERT OR REPLACE).</p> <label><!> <input type="file" accept=".json" class="hidden"/></label> <!></div></div></div>'),Ce[u],[[406,8,[[407,12,[[408,16]]],[410,12,[[411,16,[[412,20],[413,20],[416,20]]],[425,16],[427,16,[[428,20],[429,20],[432,20,[[438,24]]]]]]]]]]),M_=_(m('<p class="text-muted"> </p>'),Ce[u],[[456,16]]),N_=_(m('<span class="text-sm text-muted">Node:</span> <span class="font-mono text-sm text-fg"> </span> <span class="text-xs text-muted font-mono"> </span>',1),Ce[u],[[464,28],[465,28],[466,28]]),I_=_(m('<p class="text-sm text-muted"> </p>'),Ce[u],[[471,24]]),D_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i>'),Ce[u],[[488,36]]),F_=_(m('<i class="fa-solid fa-plus mr-1"></i>'),Ce[u],[[490,36]]),U_=_(m('<div class="text-sm text-muted"> </div>'),Ce[u],[[496,28]]),z_=_(m('<div class="bg-surface rounded p-3 flex items-center justify-between"><div><span class="font-mono text-sm text-fg"> </span> <span class="text-xs text-muted ml-2"> </span> <span> </span> <span class="text-xs text-muted ml-2"> </span></div> <button class="text-red-400 hover:text-red-300 text-sm"><i class="fa-solid fa-xmark"></i></button></div>'),Ce[u],[[504,36,[[505,40,[[506,44],[507,44],[508,44],[511,44]]],[513,40,[[514,44]]]]]]),B_=_(m('<div class="space-y-2"></div>'),Ce[u],[[502,28]]),q_=_(m('<p class="text-sm text-muted"> </p>'),Ce[u],[[520,28]]),G_=_(m('<div class="flex gap-2 items-end"><div class="flex-1"><label class="text-xs text-muted block mb-1"> </label> <input type="text" class="w-full bg-surface border border-border rounded px-3 py-1.5 text-sm font-mono"/></div> <button class="btn-primary text-sm"><!> </button></div> <!> <h4 class="text-sm font-medium"> </h4> <!>',1),Ce[u],[[476,24,[[477,28,[[478,32],[479,32]]],[486,28]]],[500,24]]),V_=_(m('<div class="space-y-4"><div class="flex items-center gap-3"><span> </span> <!></div> <!> <button class="btn-secondary text-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div>'),Ce[u],[[458,16,[[459,20,[[460,24]]],[524,20,[[525,24]]]]]]),H_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-network-wired mr-1"></i> </h3> <!></div>'),Ce[u],[[450,8,[[451,12,[[452,16]]]]]]),K_=_(m('<p class="text-muted"> </p>'),Ce[u],[[540,16]]),W_=_(m('<div><i></i> <span class="font-mono text-sm"> </span></div>'),Ce[u],[[552,28,[[553,32],[554,32]]]]),Y_=_(m('<div class="space-y-3"><div class="flex items-center gap-3"><span class="text-sm text-muted"> </span> <span class="bg-accent/20 text-accent px-2 py-1 rounded font-mono text-sm"> </span> <span class="text-sm text-muted"> </span></div> <h4 class="text-sm font-medium mt-3"> </h4> <div class="grid grid-cols-2 sm:grid-cols-3 gap-2"></div> <p class="text-xs text-muted mt-2"> </p> <button class="btn-secondary text-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div>'),Ce[u],[[542,16,[[543,20,[[544,24],[545,24],[546,24]]],[549,20],[550,20],[559,20],[563,20,[[564,24]]]]]]),J_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-brain mr-1"></i> </h3> <!></div>'),Ce[u],[[534,8,[[535,12,[[536,16]]]]]]),Q_=_(m('<p class="text-muted"> </p>'),Ce[u],[[579,16]]),X_=_(m('<div class="font-mono text-fg"> </div> <div class="text-xs text-muted truncate"> </div>',1),Ce[u],[[595,28],[596,28]]),Z_=_(m('<div class="font-mono text-muted"> </div>'),Ce[u],[[598,28]]),em=_(m('<div class="text-xs text-green-400"> </div>'),Ce[u],[[605,28]]),tm=_(m('<div class="grid grid-cols-2 sm:grid-cols-4 gap-3"><div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div> <div class="text-xs text-muted"> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div> <div class="text-xs text-muted truncate"> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <!></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div> <!></div></div>'),Ce[u],[[581,16,[[582,20,[[583,24],[584,24],[585,24]]],[587,20,[[588,24],[589,24],[590,24]]],[592,20,[[593,24]]],[601,20,[[602,24],[603,24]]]]]]),am=_(m('<button class="btn-primary text-sm"><i class="fa-solid fa-wand-magic-sparkles mr-1"></i> </button>'),Ce[u],[[610,16,[[611,20]]]]),rm=_(m('<p class="text-muted"> </p>'),Ce[u],[[622,16]]),sm=_(m('<span class="text-xs text-muted"> </span>'),Ce[u],[[628,24]]),im=_(m('<span class="bg-surface px-2 py-1 rounded text-xs font-mono"> </span>'),Ce[u],[[634,28]]),om=_(m('<div class="flex flex-wrap gap-2"></div>'),Ce[u],[[632,20]]),nm=_(m('<p class="text-xs text-muted mt-2"> </p>'),Ce[u],[[639,20]]),lm=_(m('<div class="flex items-center gap-3 mb-3"><span></span> <span class="text-sm"> </span> <!></div> <!> <!>',1),Ce[u],[[624,16,[[625,20],[626,20]]]]),dm=_(m('<p class="text-muted text-sm"> </p>'),Ce[u],[[642,16]]),cm=_(m('<div class="bg-green-500/10 border border-green-500/30 rounded p-3 text-sm text-green-400"> </div>'),Ce[u],[[647,12]]),vm=_(m('<p class="text-muted"> </p>'),Ce[u],[[679,20]]),um=_(m('<button class="bg-accent/20 text-accent px-2 py-1 rounded text-xs hover:bg-accent/30 transition-colors"> </button>'),Ce[u],[[726,48]]),fm=_(m('<span class="text-xs text-muted"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </span>'),Ce[u],[[734,52,[[735,56]]]]),pm=_(m('<button class="bg-surface text-fg px-2 py-1 rounded text-xs hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-download mr-1"></i> </button>'),Ce[u],[[738,52,[[742,56]]]]),_m=_(m('<span class="text-xs text-muted">—</span>'),Ce[u],[[746,48]]),mm=_(m('<tr class="border-b border-border/30 hover:bg-surface/50"><td class="py-2 pr-3"><div class="font-mono text-fg"> </div> <div class="text-xs text-muted"> </div></td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2 pr-3"><div class="flex items-center gap-1"><div class="w-16 h-2 bg-surface rounded-full overflow-hidden"><div></div></div> <span class="text-xs font-mono"> </span></div></td><td class="py-2 pr-3"><span> </span></td><td class="py-2 pr-3 text-xs"> </td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2"><!></td></tr>'),Ce[u],[[698,36,[[699,40,[[700,44],[701,44]]],[703,40],[704,40,[[705,44,[[706,48,[[707,52]]],[712,48]]]]],[715,40,[[716,44]]],[720,40],[721,40],[722,40],[723,40],[724,40]]]]),xm=_(m('<div class="overflow-x-auto"><table class="w-full text-sm"><thead><tr class="text-left text-xs text-muted border-b border-border"><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2"> </th></tr></thead><tbody></tbody></table></div>'),Ce[u],[[681,20,[[682,24,[[683,28,[[684,32,[[685,36],[686,36],[687,36],[688,36],[689,36],[690,36],[691,36],[692,36],[693,36]]]]],[696,28]]]]]]),bm=_(m('<p class="text-muted text-sm"> </p>'),Ce[u],[[755,20]]),gm=_(m('<div class="card"><div class="flex items-center justify-between mb-3 flex-wrap gap-2"><h3 class="text-lg font-semibold"><i class="fa-solid fa-ranking-star mr-1"></i> </h3> <div class="flex items-center gap-2"><select class="bg-surface border border-border rounded px-2 py-1 text-sm"><option> </option><option> </option><option> </option><option> </option><option> </option><option> </option></select> <button class="btn-secondary text-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div></div> <!></div>'),Ce[u],[[654,12,[[655,16,[[656,20,[[657,24]]],[659,20,[[660,24,[[665,28],[666,28],[667,28],[668,28],[669,28],[670,28]]],[672,24,[[673,28]]]]]]]]]]),hm=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-microchip mr-1"></i> </h3> <!></div> <div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-server mr-1"></i> </h3> <!></div> <!> <!>',1),Ce[u],[[574,8,[[575,12,[[576,16]]]]],[617,8,[[618,12,[[619,16]]]]]]),ym=_(m('<div class="space-y-4"><div class="flex gap-2 mb-4 flex-wrap"></div> <!> <!> <!> <!> <!> <!></div>'),Ce[u],[[268,0,[[269,4]]]]);function Ce(r,i){wa(new.target),ga(i,!0,Ce);let o=S(P("health"),"activeSection"),d=S(P(null),"health"),c=S(P(!0),"healthLoading"),f=S(P(null),"updateInfo"),b=S(P(!1),"updateLoading"),h=S(P(!1),"updateApplying"),w=S(P(""),"updateMessage"),L=S(P(!1),"backupLoading"),j=S(P(!1),"restoreLoading"),A=S(P(""),"restoreMessage"),k=S(P(null),"fedStatus"),q=S(P(!0),"fedLoading"),V=S(P(""),"addPeerAddress"),X=S(P(!1),"addPeerLoading"),N=S(P(""),"addPeerMessage"),ne=S(P(null),"backends"),je=S(P(!0),"backendsLoading"),we=S(P(null),"systemSpecs"),Te=S(P(!1),"specsLoading"),Ke=S(P(Jt([])),"recommendations"),Ee=S(P(!1),"recsLoading"),te=S(P(""),"useCaseFilter"),_e=S(P(null),"ollamaStatus"),Ve=S(P(!1),"ollamaLoading"),K=S(P(""),"pullingModel"),W=S(P(""),"pullStatus"),Ue=S(P(""),"configureMsg");async function Ne(){p(c,!0);try{const I=(await M(fetch("/healthz")))();p(d,(await M(I.json()))(),!0)}catch{p(d,{status:"error",checks:{}},!0)}p(c,!1)}async function Pe(){p(b,!0),p(w,"");try{const I=(await M(fetch("/api/update/check")))();p(f,(await M(I.json()))(),!0)}catch{p(w,"Failed to check for updates")}p(b,!1)}async function R(){p(h,!0),p(w,"");try{const I=(await M(fetch("/api/update/apply",{method:"POST"})))(),ae=(await M(I.json()))();p(w,ae.message||(ae.ok?"Update applied":"Update failed"),!0)}catch{p(w,"Failed to apply update")}p(h,!1)}async function ie(){p(L,!0);try{const I=(await M(fetch("/api/backup")))(),ae=(await M(I.blob()))(),G=URL.createObjectURL(ae),ee=document.createElement("a");ee.href=G,ee.download=`safe-agent-backup-${new Date().toISOString().slice(0,10)}.json`,ee.click(),URL.revokeObjectURL(G)}catch(I){console.error(...Xt("error","Backup download failed",I))}p(L,!1)}async function qe(I){var G;const ae=I.target;if((G=ae.files)!=null&&G.length){p(j,!0),p(A,"");try{const ee=(await M(ae.files[0].text()))(),re=JSON.parse(ee),ke=(await M(fetch("/api/restore",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify(re)})))(),ve=(await M(ke.json()))();p(A,ve.message||(ve.ok?"Restore complete":"Restore failed"),!0)}catch{p(A,"Failed to restore backup")}p(j,!1),ae.value=""}}async function Se(){p(q,!0);try{const I=(await M(fetch("/api/federation/status")))();p(k,(await M(I.json()))(),!0)}catch{p(k,{enabled:!1,peers:[]},!0)}p(q,!1)}async function me(){if(e(V).trim()){p(X,!0),p(N,"");try{const I=(await M(fetch("/api/federation/peers",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({address:e(V)})})))(),ae=(await M(I.json()))();p(N,ae.message||(ae.ok?"Peer added":"Failed"),!0),ae.ok&&(p(V,""),Se())}catch{p(N,"Failed to add peer")}p(X,!1)}}async function $(I){try{(await M(fetch(`/api/federation/peers/${I}`,{method:"DELETE"})))(),Se()}catch(ae){console.error(...Xt("error","Failed to remove peer",ae))}}async function z(){p(je,!0);try{const I=(await M(fetch("/api/llm/backends")))();p(ne,(await M(I.json()))(),!0)}catch{p(ne,{active:"unknown",available:[]},!0)}p(je,!1)}async function Z(){p(Te,!0);try{const I=(await M(fetch("/api/llm/advisor/system")))();p(we,(await M(I.json()))(),!0)}catch{p(we,null)}p(Te,!1)}async function H(){p(Ee,!0);try{const I=new URLSearchParams;e(te)&&I.set("use_case",e(te)),I.set("limit","20");const ae=(await M(fetch(`/api/llm/advisor/recommend?${I}`)))(),G=(await M(ae.json()))();p(Ke,G.models||[],!0)}catch{p(Ke,[],!0)}p(Ee,!1)}async function Le(){p(Ve,!0);try{const I=(await M(fetch("/api/llm/ollama/status")))();p(_e,(await M(I.json()))(),!0)}catch{p(_e,{available:!1,installed_models:[]},!0)}p(Ve,!1)}async function O(I){p(K,I,!0),p(W,l("ops.advisor_pulling"),!0);try{const ae=(await M(fetch("/api/llm/ollama/pull",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({tag:I})})))(),G=(await M(ae.json()))();G.ok?(p(W,l("ops.advisor_pull_complete"),!0),Le(),H()):p(W,G.error||"Pull failed",!0)}catch{p(W,"Pull failed")}setTimeout(()=>{p(K,""),p(W,"")},3e3)}async function B(I){p(Ue,"");try{const ae=(await M(fetch("/api/llm/ollama/configure",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({model:I})})))(),G=(await M(ae.json()))();p(Ue,G.ok?l("ops.advisor_configured",{model:I}):G.error||"Failed",!0),z()}catch{p(Ue,"Configuration failed")}setTimeout(()=>{p(Ue,"")},5e3)}Es(()=>{Ne(),Se(),z()});const ue=[{id:"health",label:l("ops.health"),icon:"fa-heart-pulse"},{id:"update",label:l("ops.updates"),icon:"fa-download"},{id:"backup",label:l("ops.backup"),icon:"fa-box-archive"},{id:"federation",label:l("ops.federation"),icon:"fa-network-wired"},{id:"backends",label:l("ops.llm_backends"),icon:"fa-brain"},{id:"advisor",label:l("ops.model_advisor"),icon:"fa-microchip"}];var D={...ka()},T=ym(),E=a(T);g(()=>Dt(E,21,()=>ue,la,(I,ae)=>{var G=u_(),ee=a(G),re=s(ee);x(()=>{Qe(G,1,`px-3 py-1.5 rounded text-sm transition-colors ${C(e(o),e(ae).id)?"bg-accent text-white":"bg-surface text-muted hover:text-fg"}`),Qe(ee,1,`fa-solid ${e(ae).icon??""} mr-1`),n(re,` ${e(ae).label??""}`)}),Y("click",G,function(){return p(o,e(ae).id,!0)}),v(I,G)}),"each",Ce,270,8);var U=s(E,2);{var J=I=>{var ae=m_(),G=Ae(ae),ee=a(G),re=s(a(ee)),ke=s(ee,2);{var ve=be=>{var ce=f_(),pe=a(ce);x(Oe=>n(pe,Oe),[()=>l("ops.checking_health")]),v(be,ce)},Q=be=>{var ce=__(),pe=a(ce),Oe=a(pe),Me=a(Oe),Ze=s(Oe,2),Be=a(Ze),We=s(pe,2);{var it=Fe=>{var ut=p_(),mt=a(ut),Tt=a(mt),xt=a(Tt),se=s(Tt,2),ye=a(se),et=s(mt,2),dt=a(et),tt=a(dt),Ye=s(dt,2),at=a(Ye),ft=s(et,2),wt=a(ft),Ge=a(wt),bt=s(wt,2),pt=a(bt);x((vt,Pt,Et)=>{n(xt,vt),Qe(se,1,`font-mono ${C(e(d).checks.database,"ok")?"text-green-400":"text-red-400"}`),n(ye,e(d).checks.database),n(tt,Pt),Qe(Ye,1,`font-mono ${C(e(d).checks.agent,"running")?"text-green-400":"text-yellow-400"}`),n(at,e(d).checks.agent),n(Ge,Et),n(pt,e(d).checks.tools)},[()=>l("ops.database"),()=>l("ops.agent"),()=>l("ops.tools")]),v(Fe,ut)};g(()=>y(We,Fe=>{e(d).checks&&Fe(it)}),"if",Ce,297,20)}var ot=s(We,2),rt=s(a(ot));x((Fe,ut)=>{Qe(Oe,1,`px-3 py-1 rounded text-sm font-mono font-bold ${C(e(d).status,"healthy")?"bg-green-500/20 text-green-400":"bg-red-500/20 text-red-400"}`),n(Me,Fe),n(Be,`v${e(d).version??""}`),n(rt,` ${ut??""}`)},[()=>{var Fe;return(Fe=e(d).status)==null?void 0:Fe.toUpperCase()},()=>l("common.refresh")]),Y("click",ot,Ne),v(be,ce)};g(()=>y(ke,be=>{e(c)?be(ve):e(d)&&be(Q,1)}),"if",Ce,286,12)}var $e=s(G,2),Ie=a($e),Re=s(a(Ie)),Ct=s(Ie,2),st=a(Ct),De=s(Ct,2),lt=a(De),Je=s(De,2),_t=a(Je),ze=s(a(_t));x((be,ce,pe,Oe,Me)=>{n(re,` ${be??""}`),n(Re,` ${ce??""}`),n(st,pe),n(lt,Oe),n(ze,` ${Me??""}`)},[()=>l("ops.health_check"),()=>l("ops.prometheus"),()=>l("ops.prometheus_desc"),()=>l("ops.prometheus_hint"),()=>l("ops.view_raw_metrics")]),Y("click",_t,function(){return window.open("/metrics","_blank")}),v(I,ae)};g(()=>y(U,I=>{C(e(o),"health")&&I(J)}),"if",Ce,281,4)}var xe=s(U,2);{var fe=I=>{var ae=C_(),G=a(ae),ee=s(a(G)),re=s(G,2),ke=a(re);{var ve=De=>{var lt=$_(),Je=Ae(lt),_t=a(Je),ze=a(_t),be=s(_t,2),ce=a(be),pe=s(be,2);{var Oe=ot=>{var rt=x_(),Fe=s(Ae(rt),2),ut=a(Fe),mt=s(Fe,2),Tt=a(mt);x(xt=>{n(ut,`v${e(f).latest_version??""}`),n(Tt,xt)},[()=>l("ops.update_available")]),v(ot,rt)},Me=ot=>{var rt=b_(),Fe=a(rt);x(ut=>n(Fe,ut),[()=>l("ops.up_to_date")]),v(ot,rt)};g(()=>y(pe,ot=>{e(f).update_available?ot(Oe):ot(Me,!1)}),"if",Ce,355,24)}var Ze=s(Je,2);{var Be=ot=>{var rt=g_(),Fe=a(rt),ut=a(Fe),mt=s(Fe,2),Tt=a(mt);x(xt=>{n(ut,xt),n(Tt,e(f).release_notes)},[()=>l("ops.release_notes")]),v(ot,rt)};g(()=>y(Ze,ot=>{e(f).update_available&&e(f).release_notes&&ot(Be)}),"if",Ce,364,20)}var We=s(Ze,2);{var it=ot=>{var rt=k_(),Fe=a(rt),ut=a(Fe);{var mt=ye=>{var et=h_(),dt=s(Ae(et));x(tt=>n(dt,` ${tt??""}`),[()=>l("ops.applying")]),v(ye,et)},Tt=ye=>{var et=y_(),dt=s(Ae(et));x(tt=>n(dt,` ${tt??""}`),[()=>l("ops.apply_update")]),v(ye,et)};g(()=>y(ut,ye=>{e(h)?ye(mt):ye(Tt,!1)}),"if",Ce,374,32)}var xt=s(Fe,2);{var se=ye=>{var et=w_(),dt=s(a(et));x(tt=>{St(et,"href",e(f).release_url),n(dt,` ${tt??""}`)},[()=>l("ops.view_release")]),v(ye,et)};g(()=>y(xt,ye=>{e(f).release_url&&ye(se)}),"if",Ce,380,28)}x(()=>Fe.disabled=e(h)),Y("click",Fe,R),v(ot,rt)};g(()=>y(We,ot=>{e(f).update_available&&ot(it)}),"if",Ce,371,20)}x(ot=>{n(ze,ot),n(ce,`v${e(f).current_version??""}`)},[()=>l("ops.current_version")]),v(De,lt)};g(()=>y(ke,De=>{e(f)&&De(ve)}),"if",Ce,351,16)}var Q=s(ke,2);{var $e=De=>{var lt=T_(),Je=a(lt);x(()=>n(Je,e(w))),v(De,lt)};g(()=>y(Q,De=>{e(w)&&De($e)}),"if",Ce,389,16)}var Ie=s(Q,2),Re=a(Ie);{var Ct=De=>{var lt=E_(),Je=s(Ae(lt));x(_t=>n(Je,` ${_t??""}`),[()=>l("ops.checking")]),v(De,lt)},st=De=>{var lt=S_(),Je=s(Ae(lt));x(_t=>n(Je,` ${_t??""}`),[()=>l("ops.check_updates")]),v(De,lt)};g(()=>y(Re,De=>{e(b)?De(Ct):De(st,!1)}),"if",Ce,394,20)}x(De=>{n(ee,` ${De??""}`),Ie.disabled=e(b)},[()=>l("ops.auto_update")]),Y("click",Ie,Pe),v(I,ae)};g(()=>y(xe,I=>{C(e(o),"update")&&I(fe)}),"if",Ce,345,4)}var F=s(xe,2);{var he=I=>{var ae=R_(),G=a(ae),ee=s(a(G)),re=s(G,2),ke=a(re),ve=a(ke),Q=a(ve),$e=s(ve,4),Ie=a($e);{var Re=Me=>{var Ze=A_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.exporting")]),v(Me,Ze)},Ct=Me=>{var Ze=P_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.download_backup")]),v(Me,Ze)};g(()=>y(Ie,Me=>{e(L)?Me(Re):Me(Ct,!1)}),"if",Ce,417,24)}var st=s(ke,4),De=a(st),lt=a(De),Je=s(De,4),_t=a(Je);{var ze=Me=>{var Ze=j_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.restoring")]),v(Me,Ze)},be=Me=>{var Ze=O_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.upload_backup")]),v(Me,Ze)};g(()=>y(_t,Me=>{e(j)?Me(ze):Me(be,!1)}),"if",Ce,433,24)}var ce=s(_t,2),pe=s(Je,2);{var Oe=Me=>{var Ze=L_(),Be=a(Ze);x(()=>n(Be,e(A))),v(Me,Ze)};g(()=>y(pe,Me=>{e(A)&&Me(Oe)}),"if",Ce,440,20)}x((Me,Ze,Be)=>{n(ee,` ${Me??""}`),n(Q,Ze),$e.disabled=e(L),n(lt,Be),Qe(Je,1,`btn-secondary text-sm inline-flex items-center cursor-pointer ${e(j)?"opacity-50":""}`),ce.disabled=e(j)},[()=>l("ops.backup_restore"),()=>l("ops.export_backup"),()=>l("ops.restore_backup")]),Y("click",$e,ie),Y("change",ce,qe),v(I,ae)};g(()=>y(F,I=>{C(e(o),"backup")&&I(he)}),"if",Ce,405,4)}var Xe=s(F,2);{var yt=I=>{var ae=H_(),G=a(ae),ee=s(a(G)),re=s(G,2);{var ke=Q=>{var $e=M_(),Ie=a($e);x(Re=>n(Ie,Re),[()=>l("ops.federation_loading")]),v(Q,$e)},ve=Q=>{var $e=V_(),Ie=a($e),Re=a(Ie),Ct=a(Re),st=s(Re,2);{var De=ce=>{var pe=N_(),Oe=s(Ae(pe),2),Me=a(Oe),Ze=s(Oe,2),Be=a(Ze);x(We=>{n(Me,e(k).node.name),n(Be,`(${We??""}...)`)},[()=>e(k).node.node_id.slice(0,8)]),v(ce,pe)};g(()=>y(st,ce=>{e(k).node&&ce(De)}),"if",Ce,463,24)}var lt=s(Ie,2);{var Je=ce=>{var pe=I_(),Oe=a(pe);x(Me=>n(Oe,Me),[()=>l("ops.federation_disabled")]),v(ce,pe)},_t=ce=>{var pe=G_(),Oe=Ae(pe),Me=a(Oe),Ze=a(Me),Be=a(Ze),We=s(Ze,2),it=s(Me,2),ot=a(it);{var rt=tt=>{var Ye=D_();v(tt,Ye)},Fe=tt=>{var Ye=F_();v(tt,Ye)};g(()=>y(ot,tt=>{e(X)?tt(rt):tt(Fe,!1)}),"if",Ce,487,32)}var ut=s(ot),mt=s(Oe,2);{var Tt=tt=>{var Ye=U_(),at=a(Ye);x(()=>n(at,e(N))),v(tt,Ye)};g(()=>y(mt,tt=>{e(N)&&tt(Tt)}),"if",Ce,495,24)}var xt=s(mt,2),se=a(xt),ye=s(xt,2);{var et=tt=>{var Ye=B_();g(()=>Dt(Ye,21,()=>e(k).peers,la,(at,ft)=>{var wt=z_(),Ge=a(wt),bt=a(Ge),pt=a(bt,!0);var vt=s(bt,2),Pt=a(vt,!0);var Et=s(vt,2),jt=a(Et,!0);var Ft=s(Et,2),Ht=a(Ft);var Ut=s(Ge,2);x(Kt=>{n(pt,e(ft).name),n(Pt,e(ft).address),Qe(Et,1,`ml-2 px-1.5 py-0.5 rounded text-xs ${C(e(ft).status,"online")?"bg-green-500/20 text-green-400":"bg-red-500/20 text-red-400"}`),n(jt,e(ft).status),n(Ht,`v${e(ft).version??""}`),St(Ut,"title",Kt)},[()=>l("ops.remove_peer_title")]),Y("click",Ut,function(){return $(e(ft).node_id)}),v(at,wt)}),"each",Ce,503,32),v(tt,Ye)},dt=tt=>{var Ye=q_(),at=a(Ye);x(ft=>n(at,ft),[()=>l("ops.no_peers")]),v(tt,Ye)};g(()=>y(ye,tt=>{var Ye;(Ye=e(k).peers)!=null&&Ye.length?tt(et):tt(dt,!1)}),"if",Ce,501,24)}x((tt,Ye,at,ft)=>{n(Be,tt),St(We,"placeholder",Ye),it.disabled=e(X),n(ut,` ${at??""}`),n(se,ft)},[()=>l("ops.add_peer"),()=>l("ops.peer_placeholder"),()=>l("ops.add"),()=>l("ops.peers_count",{count:e(k).peer_count||0})]),ua(We,function(){return e(V)},function(Ye){p(V,Ye)}),Y("click",it,me),v(ce,pe)};g(()=>y(lt,ce=>{e(k).enabled?ce(_t,!1):ce(Je)}),"if",Ce,470,20)}var ze=s(lt,2),be=s(a(ze));x(ce=>{Qe(Re,1,`px-2 py-1 rounded text-xs font-semibold ${e(k).enabled?"bg-green-500/20 text-green-400":"bg-surface text-muted"}`),n(Ct,e(k).enabled?"ENABLED":"DISABLED"),n(be,` ${ce??""}`)},[()=>l("common.refresh")]),Y("click",ze,Se),v(Q,$e)};g(()=>y(re,Q=>{e(q)?Q(ke):e(k)&&Q(ve,1)}),"if",Ce,455,12)}x(Q=>n(ee,` ${Q??""}`),[()=>l("ops.federation_title")]),v(I,ae)};g(()=>y(Xe,I=>{C(e(o),"federation")&&I(yt)}),"if",Ce,449,4)}var ht=s(Xe,2);{var ct=I=>{var ae=J_(),G=a(ae),ee=s(a(G)),re=s(G,2);{var ke=Q=>{var $e=K_(),Ie=a($e);x(Re=>n(Ie,Re),[()=>l("ops.loading_backends")]),v(Q,$e)},ve=Q=>{var $e=Y_(),Ie=a($e),Re=a(Ie),Ct=a(Re),st=s(Re,2),De=a(st),lt=s(st,2),Je=a(lt),_t=s(Ie,2),ze=a(_t),be=s(_t,2);g(()=>Dt(be,21,()=>e(ne).available,la,(Ze,Be)=>{var We=W_(),it=a(We),ot=s(it,2),rt=a(ot,!0);x(()=>{Qe(We,1,`bg-surface rounded p-3 flex items-center gap-2 ${C(e(Be),e(ne).active)?"ring-1 ring-accent":""}`),Qe(it,1,`fa-solid ${C(e(Be),e(ne).active)?"fa-circle-check text-accent":"fa-circle text-muted"} text-xs`),n(rt,e(Be))}),v(Ze,We)}),"each",Ce,551,24);var ce=s(be,2),pe=a(ce),Oe=s(ce,2),Me=s(a(Oe));x((Ze,Be,We,it)=>{n(Ct,Ze),n(De,e(ne).active),n(Je,`(${e(ne).active_info??""})`),n(ze,Be),n(pe,We),n(Me,` ${it??""}`)},[()=>l("ops.active_backend"),()=>l("ops.available_backends"),()=>l("ops.switch_backend_hint"),()=>l("common.refresh")]),Y("click",Oe,z),v(Q,$e)};g(()=>y(re,Q=>{e(je)?Q(ke):e(ne)&&Q(ve,1)}),"if",Ce,539,12)}x(Q=>n(ee,` ${Q??""}`),[()=>l("ops.llm_plugins")]),v(I,ae)};g(()=>y(ht,I=>{C(e(o),"backends")&&I(ct)}),"if",Ce,533,4)}var oe=s(ht,2);{var le=I=>{var ae=hm(),G=Ae(ae),ee=a(G),re=s(a(ee)),ke=s(ee,2);{var ve=pe=>{var Oe=Q_(),Me=a(Oe);x(Ze=>n(Me,Ze),[()=>l("common.loading")]),v(pe,Oe)},Q=pe=>{var Oe=tm(),Me=a(Oe),Ze=a(Me),Be=a(Ze),We=s(Ze,2),it=a(We),ot=s(We,2),rt=a(ot),Fe=s(Me,2),ut=a(Fe),mt=a(ut),Tt=s(ut,2),xt=a(Tt),se=s(Tt,2),ye=a(se),et=s(Fe,2),dt=a(et),tt=a(dt),Ye=s(dt,2);{var at=jt=>{var Ft=X_(),Ht=Ae(Ft),Ut=a(Ht),Kt=s(Ht,2),na=a(Kt);x(ta=>{n(Ut,`${ta??""} GB VRAM`),St(Kt,"title",e(we).gpu_name),n(na,`${(e(we).gpu_name||"GPU")??""}${e(we).gpu_count>1?` (×${e(we).gpu_count})`:""}`)},[()=>{var ta;return((ta=e(we).gpu_vram_gb)==null?void 0:ta.toFixed(1))??"?"}]),v(jt,Ft)},ft=jt=>{var Ft=Z_(),Ht=a(Ft);x(Ut=>n(Ht,Ut),[()=>l("ops.advisor_no_gpu")]),v(jt,Ft)};g(()=>y(Ye,jt=>{e(we).has_gpu?jt(at):jt(ft,!1)}),"if",Ce,594,24)}var wt=s(et,2),Ge=a(wt),bt=a(Ge),pt=s(Ge,2),vt=a(pt),Pt=s(pt,2);{var Et=jt=>{var Ft=em(),Ht=a(Ft);x(Ut=>n(Ht,Ut),[()=>l("ops.advisor_unified")]),v(jt,Ft)};g(()=>y(Pt,jt=>{e(we).unified_memory&&jt(Et)}),"if",Ce,604,24)}x((jt,Ft,Ht,Ut,Kt,na,ta,Ea)=>{n(Be,jt),n(it,`${Ft??""} GB`),n(rt,`${Ht??""} GB ${Ut??""}`),n(mt,Kt),n(xt,`${e(we).cpu_cores??""} ${na??""}`),St(se,"title",e(we).cpu_name),n(ye,e(we).cpu_name),n(tt,ta),n(bt,Ea),n(vt,e(we).backend)},[()=>l("ops.advisor_ram"),()=>{var jt;return(jt=e(we).total_ram_gb)==null?void 0:jt.toFixed(1)},()=>{var jt;return(jt=e(we).available_ram_gb)==null?void 0:jt.toFixed(1)},()=>l("ops.advisor_available"),()=>l("ops.advisor_cpu"),()=>l("ops.advisor_cores"),()=>l("ops.advisor_gpu"),()=>l("ops.advisor_backend_accel")]),v(pe,Oe)},$e=pe=>{var Oe=am(),Me=s(a(Oe));x(Ze=>n(Me,` ${Ze??""}`),[()=>l("ops.advisor_detect")]),Y("click",Oe,function(){Z(),H(),Le()}),v(pe,Oe)};g(()=>y(ke,pe=>{e(Te)?pe(ve):e(we)?pe(Q,1):pe($e,!1)}),"if",Ce,578,12)}var Ie=s(G,2),Re=a(Ie),Ct=s(a(Re)),st=s(Re,2);{var De=pe=>{var Oe=rm(),Me=a(Oe);x(Ze=>n(Me,Ze),[()=>l("common.loading")]),v(pe,Oe)},lt=pe=>{var Oe=lm(),Me=Ae(Oe),Ze=a(Me),Be=s(Ze,2),We=a(Be),it=s(Be,2);{var ot=Tt=>{var xt=sm(),se=a(xt);x(ye=>n(se,`(${e(_e).installed_models.length??""} ${ye??""})`),[()=>l("ops.advisor_models_installed")]),v(Tt,xt)};g(()=>y(it,Tt=>{var xt;e(_e).available&&((xt=e(_e).installed_models)!=null&&xt.length)&&Tt(ot)}),"if",Ce,627,20)}var rt=s(Me,2);{var Fe=Tt=>{var xt=om();g(()=>Dt(xt,21,()=>e(_e).installed_models,la,(se,ye)=>{var et=im(),dt=a(et,!0);x(()=>n(dt,e(ye))),v(se,et)}),"each",Ce,633,24),v(Tt,xt)};g(()=>y(rt,Tt=>{var xt;e(_e).available&&((xt=e(_e).installed_models)!=null&&xt.length)&&Tt(Fe)}),"if",Ce,631,16)}var ut=s(rt,2);{var mt=Tt=>{var xt=nm(),se=a(xt);x(ye=>n(se,ye),[()=>l("ops.ollama_install_hint")]),v(Tt,xt)};g(()=>y(ut,Tt=>{e(_e).available||Tt(mt)}),"if",Ce,638,16)}x(Tt=>{Qe(Ze,1,`w-3 h-3 rounded-full ${e(_e).available?"bg-green-500":"bg-red-500"}`),n(We,Tt)},[()=>e(_e).available?l("ops.ollama_running"):l("ops.ollama_not_running")]),v(pe,Oe)},Je=pe=>{var Oe=dm(),Me=a(Oe);x(Ze=>n(Me,Ze),[()=>l("ops.advisor_detect_first")]),v(pe,Oe)};g(()=>y(st,pe=>{e(Ve)?pe(De):e(_e)?pe(lt,1):pe(Je,!1)}),"if",Ce,621,12)}var _t=s(Ie,2);{var ze=pe=>{var Oe=cm(),Me=a(Oe);x(()=>n(Me,e(Ue))),v(pe,Oe)};g(()=>y(_t,pe=>{e(Ue)&&pe(ze)}),"if",Ce,646,8)}var be=s(_t,2);{var ce=pe=>{var Oe=gm(),Me=a(Oe),Ze=a(Me),Be=s(a(Ze)),We=s(Ze,2),it=a(We),ot=a(it),rt=a(ot);ot.value=ot.__value="";var Fe=s(ot),ut=a(Fe);Fe.value=Fe.__value="general";var mt=s(Fe),Tt=a(mt);mt.value=mt.__value="coding";var xt=s(mt),se=a(xt);xt.value=xt.__value="reasoning";var ye=s(xt),et=a(ye);ye.value=ye.__value="chat";var dt=s(ye),tt=a(dt);dt.value=dt.__value="multimodal";var Ye=s(it,2),at=s(a(Ye)),ft=s(Me,2);{var wt=pt=>{var vt=vm(),Pt=a(vt);x(Et=>n(Pt,Et),[()=>l("ops.advisor_analyzing")]),v(pt,vt)},Ge=pt=>{var vt=xm(),Pt=a(vt),Et=a(Pt),jt=a(Et),Ft=a(jt),Ht=a(Ft),Ut=s(Ft),Kt=a(Ut),na=s(Ut),ta=a(na),Ea=s(na),pa=a(Ea),zt=s(Ea),da=a(zt),ia=s(zt),aa=a(ia),$a=s(ia),gt=a($a),Ot=s($a),Lt=a(Ot),Mt=s(Ot),fa=a(Mt),Aa=s(Et);g(()=>Dt(Aa,21,()=>e(Ke),la,(Ra,Zt)=>{var Ya=mm(),vr=a(Ya),sr=a(vr),ir=a(sr,!0);var br=s(sr,2),Er=a(br);var or=s(vr),Dr=a(or);var Za=s(or),qt=a(Za),Wt=a(qt),xa=a(Wt);var ba=s(Wt,2),rs=a(ba,!0);var Sr=s(Za),Cr=a(Sr),Fr=a(Cr,!0);var Ur=s(Sr),Ss=a(Ur,!0);var er=s(Ur),fs=a(er,!0);var zr=s(er),Cs=a(zr,!0);var He=s(zr),nt=a(He);var It=s(He),Gt=a(It);{var Ma=Bt=>{var Ta=um(),tr=a(Ta,!0);x(Br=>n(tr,Br),[()=>l("ops.advisor_use")]),Y("click",Ta,function(){return B(e(Zt).ollama_tag||e(Zt).name)}),v(Bt,Ta)},ur=Bt=>{var Ta=Oa(),tr=Ae(Ta);{var Br=ss=>{var qr=fm(),ki=s(a(qr),1,!0);x(()=>n(ki,e(W))),v(ss,qr)},As=ss=>{var qr=pm(),ki=s(a(qr),1,!0);x($i=>n(ki,$i),[()=>l("ops.advisor_install")]),Y("click",qr,function(){return O(e(Zt).ollama_tag)}),v(ss,qr)};g(()=>y(tr,ss=>{C(e(K),e(Zt).ollama_tag)?ss(Br):ss(As,!1)}),"if",Ce,733,48)}v(Bt,Ta)},Na=Bt=>{var Ta=_m();v(Bt,Ta)};g(()=>y(Gt,Bt=>{var Ta;e(Zt).installed?Bt(Ma):e(Zt).ollama_tag&&((Ta=e(_e))!=null&&Ta.available)?Bt(ur,1):Bt(Na,!1)}),"if",Ce,725,44)}x((Bt,Ta,tr,Br,As)=>{n(ir,e(Zt).name),n(Er,`${e(Zt).provider??""} · ${e(Zt).use_case??""}`),n(Dr,`${Bt??""}B`),Qe(xa,1,`h-full rounded-full ${e(Zt).score>=70?"bg-green-500":e(Zt).score>=40?"bg-yellow-500":"bg-red-500"}`),Ds(xa,`width: ${Ta??""}%`),n(rs,tr),Qe(Cr,1,`px-1.5 py-0.5 rounded text-xs ${C(e(Zt).fit_level,"Perfect")?"bg-green-500/20 text-green-400":C(e(Zt).fit_level,"Good")?"bg-blue-500/20 text-blue-400":"bg-yellow-500/20 text-yellow-400"}`),n(Fr,e(Zt).fit_level),n(Ss,e(Zt).run_mode),n(fs,e(Zt).best_quant),n(Cs,Br),n(nt,`${As??""} GB`)},[()=>{var Bt;return(Bt=e(Zt).params_b)==null?void 0:Bt.toFixed(1)},()=>Math.min(e(Zt).score,100),()=>{var Bt;return(Bt=e(Zt).score)==null?void 0:Bt.toFixed(0)},()=>{var Bt;return(Bt=e(Zt).estimated_tps)==null?void 0:Bt.toFixed(1)},()=>{var Bt;return(Bt=e(Zt).memory_required_gb)==null?void 0:Bt.toFixed(1)}]),v(Ra,Ya)}),"each",Ce,697,32),x((Ra,Zt,Ya,vr,sr,ir,br,Er,or)=>{n(Ht,Ra),n(Kt,Zt),n(ta,Ya),n(pa,vr),n(da,sr),n(aa,ir),n(gt,br),n(Lt,Er),n(fa,or)},[()=>l("ops.advisor_col_name"),()=>l("ops.advisor_col_params"),()=>l("ops.advisor_col_score"),()=>l("ops.advisor_col_fit"),()=>l("ops.advisor_col_mode"),()=>l("ops.advisor_col_quant"),()=>l("ops.advisor_col_tps"),()=>l("ops.advisor_col_mem"),()=>l("ops.advisor_col_action")]),v(pt,vt)},bt=pt=>{var vt=bm(),Pt=a(vt);x(Et=>n(Pt,Et),[()=>l("ops.advisor_no_models")]),v(pt,vt)};g(()=>y(ft,pt=>{e(Ee)?pt(wt):e(Ke).length?pt(Ge,1):pt(bt,!1)}),"if",Ce,678,16)}x((pt,vt,Pt,Et,jt,Ft,Ht,Ut)=>{n(Be,` ${pt??""}`),n(rt,vt),n(ut,Pt),n(Tt,Et),n(se,jt),n(et,Ft),n(tt,Ht),n(at,` ${Ut??""}`)},[()=>l("ops.recommended_models"),()=>l("ops.advisor_all_use_cases"),()=>l("ops.advisor_uc_general"),()=>l("ops.advisor_uc_coding"),()=>l("ops.advisor_uc_reasoning"),()=>l("ops.advisor_uc_chat"),()=>l("ops.advisor_uc_multimodal"),()=>l("common.refresh")]),Y("change",it,function(){return H()}),Gi(it,function(){return e(te)},function(vt){p(te,vt)}),Y("click",Ye,H),v(pe,Oe)};g(()=>y(be,pe=>{e(we)&&pe(ce)}),"if",Ce,653,8)}x((pe,Oe)=>{n(re,` ${pe??""}`),n(Ct,` ${Oe??""}`)},[()=>l("ops.system_specs"),()=>l("ops.ollama_status")]),v(I,ae)};g(()=>y(oe,I=>{C(e(o),"advisor")&&I(le)}),"if",Ce,572,4)}return v(r,T),ha(D)}La(["click","change"]);ya[u]="src/components/UsersPanel.svelte";var wm=_(m('<button class="btn-primary text-sm"><i class="fa-solid fa-plus mr-1"></i> </button>'),ya[u],[[143,12,[[144,16]]]]),km=_(m('<div class="bg-surface rounded p-2 text-sm text-muted mb-3"> </div>'),ya[u],[[150,8]]),$m=_(m('<div class="bg-surface rounded p-4 mb-4 space-y-3"><h4 class="text-sm font-medium"> </h4> <div class="grid grid-cols-1 sm:grid-cols-2 gap-3"><div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="jdoe" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="Jane Doe" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <select class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"><option>Admin</option><option>User</option><option>Viewer</option></select></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="password" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="email" placeholder="jane@example.com" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="123456789" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="+15551234567" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div></div> <div class="flex gap-2"><button class="btn-primary text-sm"><i class="fa-solid fa-check mr-1"></i> </button> <button class="btn-secondary text-sm"> </button></div></div>'),ya[u],[[155,8,[[156,12],[157,12,[[158,16,[[159,20],[160,20]]],[162,16,[[163,20],[164,20]]],[166,16,[[167,20],[168,20,[[169,24],[170,24],[171,24]]]]],[174,16,[[175,20],[176,20]]],[178,16,[[179,20],[180,20]]],[182,16,[[183,20],[184,20]]],[186,16,[[187,20],[188,20]]]]],[191,12,[[192,16,[[193,20]]],[195,16]]]]]]),Tm=_(m('<p class="text-muted text-sm"> </p>'),ya[u],[[202,8]]),Em=_(m('<p class="text-muted text-sm"> </p>'),ya[u],[[204,8]]),Sm=_(m('<span class="px-1.5 py-0.5 rounded text-xs bg-red-500/20 text-red-400"> </span>'),ya[u],[[219,36]]),Cm=_(m('<span><i class="fa-solid fa-envelope mr-0.5"></i> </span>'),ya[u],[[224,36,[[224,42]]]]),Am=_(m('<span><i class="fa-brands fa-telegram mr-0.5"></i> </span>'),ya[u],[[227,36,[[227,42]]]]),Pm=_(m('<span><i class="fa-brands fa-whatsapp mr-0.5"></i> </span>'),ya[u],[[230,36,[[230,42]]]]),jm=_(m('<div class="flex items-center gap-2 flex-shrink-0"><select class="bg-bg border border-border rounded px-2 py-1 text-xs"><option>Admin</option><option>User</option><option>Viewer</option></select> <button> </button> <button class="text-xs px-2 py-1 rounded bg-red-500/20 text-red-400 hover:bg-red-500/30"><i class="fa-solid fa-trash"></i></button></div>'),ya[u],[[238,24,[[239,28,[[244,32],[245,32],[246,32]]],[248,28],[255,28,[[260,32]]]]]]),Om=_(m('<div class="bg-surface rounded p-3 flex items-center justify-between gap-3 flex-wrap"><div class="flex items-center gap-3 min-w-0"><div class="w-8 h-8 rounded-full bg-accent/20 flex items-center justify-center text-accent font-bold text-sm flex-shrink-0"> </div> <div class="min-w-0"><div class="flex items-center gap-2"><span class="font-medium text-fg truncate"> </span> <span class="text-xs text-muted font-mono"> </span> <span> </span> <!></div> <div class="flex gap-3 text-xs text-muted mt-0.5"><!> <!> <!> <span> </span></div></div></div> <!></div>'),ya[u],[[208,16,[[209,20,[[210,24],[213,24,[[214,28,[[215,32],[216,32],[217,32]]],[222,28,[[232,32]]]]]]]]]]),Lm=_(m('<div class="space-y-2"></div>'),ya[u],[[206,8]]),Rm=_(m('<div class="card"><div class="flex items-center justify-between mb-4"><h3 class="text-lg font-semibold"><i class="fa-solid fa-users mr-1"></i> </h3> <!></div> <!> <!> <!></div>'),ya[u],[[137,0,[[138,4,[[139,8,[[140,12]]]]]]]]);function ya(r,i){wa(new.target),ga(i,!0,ya);let o=S(P(Jt([])),"users"),d=S(P(!0),"loading"),c=S(P(!1),"showCreateForm"),f=S(P(""),"message"),b=S(P(""),"newUsername"),h=S(P(""),"newDisplayName"),w=S(P("user"),"newRole"),L=S(P(""),"newPassword"),j=S(P(""),"newEmail"),A=S(P(""),"newTelegramId"),k=S(P(""),"newWhatsappId");async function q(){p(d,!0);try{const $=(await M(fetch("/api/users")))(),z=(await M($.json()))();p(o,z.users||[],!0)}catch{p(o,[],!0)}p(d,!1)}async function V(){p(f,"");const $={username:e(b),display_name:e(h)||e(b),role:e(w),password:e(L)};e(j)&&($.email=e(j)),e(A)&&($.telegram_id=parseInt(e(A))),e(k)&&($.whatsapp_id=e(k));try{(await M(fetch("/api/users",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify($)})))().ok?(p(f,l("users.created",{username:e(b)}),!0),p(b,""),p(h,""),p(w,"user"),p(L,""),p(j,""),p(A,""),p(k,""),p(c,!1),q()):p(f,l("users.failed_create"),!0)}catch{p(f,l("users.error_create"),!0)}}async function X($){try{(await M(fetch(`/api/users/${$.id}`,{method:"PUT",headers:{"Content-Type":"application/json"},body:JSON.stringify({enabled:!$.enabled})})))(),q()}catch{p(f,l("users.failed_update"),!0)}}async function N($,z){try{(await M(fetch(`/api/users/${$.id}`,{method:"PUT",headers:{"Content-Type":"application/json"},body:JSON.stringify({role:z})})))(),q()}catch{p(f,l("users.failed_role"),!0)}}async function ne($){if(confirm(l("users.delete_confirm",{username:$.username})))try{(await M(fetch(`/api/users/${$.id}`,{method:"DELETE"})))(),q()}catch{p(f,l("users.failed_delete"),!0)}}function je($){switch($){case"admin":return"bg-red-500/20 text-red-400";case"viewer":return"bg-blue-500/20 text-blue-400";default:return"bg-green-500/20 text-green-400"}}function we($){if(!$)return l("users.never");const z=new Date($+"Z"),Z=Date.now()-z.getTime();return Z<6e4?l("users.just_now"):Z<36e5?`${Math.floor(Z/6e4)}m ago`:Z<864e5?`${Math.floor(Z/36e5)}h ago`:`${Math.floor(Z/864e5)}d ago`}Es(q);const Te=S(ma(()=>C(va.role,"admin")||!va.userId),"isAdmin");var Ke={...ka()},Ee=Rm(),te=a(Ee),_e=a(te),Ve=s(a(_e)),K=s(_e,2);{var W=$=>{var z=wm(),Z=s(a(z));x(H=>n(Z,` ${H??""}`),[()=>l("users.add_user")]),Y("click",z,function(){return p(c,!e(c))}),v($,z)};g(()=>y(K,$=>{e(Te)&&$(W)}),"if",ya,142,8)}var Ue=s(te,2);{var Ne=$=>{var z=km(),Z=a(z);x(()=>n(Z,e(f))),v($,z)};g(()=>y(Ue,$=>{e(f)&&$(Ne)}),"if",ya,149,4)}var Pe=s(Ue,2);{var R=$=>{var z=$m(),Z=a(z),H=a(Z),Le=s(Z,2),O=a(Le),B=a(O),ue=a(B),D=s(B,2),T=s(O,2),E=a(T),U=a(E),J=s(E,2),xe=s(T,2),fe=a(xe),F=a(fe),he=s(fe,2),Xe=a(he);Xe.value=Xe.__value="admin";var yt=s(Xe);yt.value=yt.__value="user";var ht=s(yt);ht.value=ht.__value="viewer";var ct=s(xe,2),oe=a(ct),le=a(oe),I=s(oe,2),ae=s(ct,2),G=a(ae),ee=a(G),re=s(G,2),ke=s(ae,2),ve=a(ke),Q=a(ve),$e=s(ve,2),Ie=s(ke,2),Re=a(Ie),Ct=a(Re),st=s(Re,2),De=s(Le,2),lt=a(De),Je=s(a(lt)),_t=s(lt,2),ze=a(_t);x((be,ce,pe,Oe,Me,Ze,Be,We,it,ot,rt)=>{n(H,be),n(ue,`${ce??""} *`),n(U,pe),n(F,Oe),n(le,Me),n(ee,Ze),n(Q,Be),n(Ct,We),lt.disabled=it,n(Je,` ${ot??""}`),n(ze,rt)},[()=>l("users.create_user"),()=>l("users.username"),()=>l("users.display_name"),()=>l("users.role"),()=>l("users.password"),()=>l("users.email"),()=>l("users.telegram_id"),()=>l("users.whatsapp_id"),()=>!e(b).trim(),()=>l("users.create"),()=>l("common.cancel")]),ua(D,function(){return e(b)},function(ce){p(b,ce)}),ua(J,function(){return e(h)},function(ce){p(h,ce)}),Gi(he,function(){return e(w)},function(ce){p(w,ce)}),ua(I,function(){return e(L)},function(ce){p(L,ce)}),ua(re,function(){return e(j)},function(ce){p(j,ce)}),ua($e,function(){return e(A)},function(ce){p(A,ce)}),ua(st,function(){return e(k)},function(ce){p(k,ce)}),Y("click",lt,V),Y("click",_t,function(){return p(c,!1)}),v($,z)};g(()=>y(Pe,$=>{e(c)&&e(Te)&&$(R)}),"if",ya,154,4)}var ie=s(Pe,2);{var qe=$=>{var z=Tm(),Z=a(z);x(H=>n(Z,H),[()=>l("users.loading")]),v($,z)},Se=$=>{var z=Em(),Z=a(z);x(H=>n(Z,H),[()=>l("users.no_users_desc")]),v($,z)},me=$=>{var z=Lm();g(()=>Dt(z,21,()=>e(o),la,(Z,H)=>{var Le=Om(),O=a(Le),B=a(O),ue=a(B,!0);var D=s(B,2),T=a(D),E=a(T),U=a(E,!0);var J=s(E,2),xe=a(J);var fe=s(J,2),F=a(fe,!0);var he=s(fe,2);{var Xe=ve=>{var Q=Sm(),$e=a(Q,!0);x(Ie=>n($e,Ie),[()=>l("users.disabled")]),v(ve,Q)};g(()=>y(he,ve=>{e(H).enabled||ve(Xe)}),"if",ya,218,32)}var yt=s(T,2),ht=a(yt);{var ct=ve=>{var Q=Cm(),$e=s(a(Q));x(()=>n($e,` ${e(H).email??""}`)),v(ve,Q)};g(()=>y(ht,ve=>{e(H).email&&ve(ct)}),"if",ya,223,32)}var oe=s(ht,2);{var le=ve=>{var Q=Am(),$e=s(a(Q));x(()=>n($e,` ${e(H).telegram_id??""}`)),v(ve,Q)};g(()=>y(oe,ve=>{e(H).telegram_id&&ve(le)}),"if",ya,226,32)}var I=s(oe,2);{var ae=ve=>{var Q=Pm(),$e=s(a(Q));x(()=>n($e,` ${e(H).whatsapp_id??""}`)),v(ve,Q)};g(()=>y(I,ve=>{e(H).whatsapp_id&&ve(ae)}),"if",ya,229,32)}var G=s(I,2),ee=a(G);var re=s(O,2);{var ke=ve=>{var Q=jm(),$e=a(Q),Ie=a($e);Ie.value=Ie.__value="admin";var Re=s(Ie);Re.value=Re.__value="user";var Ct=s(Re);Ct.value=Ct.__value="viewer";var st;Wo($e);var De=s($e,2),lt=a(De,!0);var Je=s(De,2);x((_t,ze,be)=>{st!==(st=e(H).role)&&($e.value=($e.__value=e(H).role)??"",mo($e,e(H).role)),Qe(De,1,`text-xs px-2 py-1 rounded ${e(H).enabled?"bg-yellow-500/20 text-yellow-400 hover:bg-yellow-500/30":"bg-green-500/20 text-green-400 hover:bg-green-500/30"}`),St(De,"title",_t),n(lt,ze),St(Je,"title",be)},[()=>e(H).enabled?l("users.disable_user"):l("users.enable_user"),()=>e(H).enabled?l("users.disable"):l("users.enable"),()=>l("users.delete_user")]),Y("change",$e,function(ze){return N(e(H),ze.target.value)}),Y("click",De,function(){return X(e(H))}),Y("click",Je,function(){return ne(e(H))}),v(ve,Q)};g(()=>y(re,ve=>{e(Te)&&ve(ke)}),"if",ya,237,20)}x((ve,Q,$e,Ie)=>{n(ue,ve),n(U,e(H).display_name||e(H).username),n(xe,`@${e(H).username??""}`),Qe(fe,1,`px-1.5 py-0.5 rounded text-xs font-semibold ${Q??""}`),n(F,e(H).role),n(ee,`${$e??""} ${Ie??""}`)},[()=>{var ve,Q;return((Q=(ve=e(H).display_name)==null?void 0:ve.charAt(0))==null?void 0:Q.toUpperCase())||e(H).username.charAt(0).toUpperCase()},()=>je(e(H).role),()=>l("users.last_seen"),()=>we(e(H).last_seen_at)]),v(Z,Le)}),"each",ya,207,12),v($,z)};g(()=>y(ie,$=>{e(d)?$(qe):C(e(o).length,0)?$(Se,1):$(me,!1)}),"if",ya,201,4)}return x($=>n(Ve,` ${$??""}`),[()=>l("users.title")]),v(r,Ee),ha(Ke)}La(["click","change"]);sa[u]="src/components/TwoFactorPanel.svelte";var Mm=_(m('<div class="card"><p class="text-text-muted text-sm"><i class="fa-solid fa-circle-info mr-1"></i> </p></div>'),sa[u],[[243,4,[[244,8,[[245,12]]]]]]),Nm=_(m('<div class="card"><p class="text-text-muted text-sm"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </p></div>'),sa[u],[[250,4,[[251,8,[[251,43]]]]]]),Im=_(m("<div><i></i> </div>"),sa[u],[[256,12,[[257,16]]]]),Dm=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),sa[u],[[273,20]]),Fm=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),sa[u],[[275,20]]),Um=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i>'),sa[u],[[301,32]]),zm=_(m('<p class="text-sm text-text-subtle mb-3"> </p> <div class="flex gap-2 items-end"><div><label class="text-xs text-text-muted block mb-1"> </label> <input type="text" maxlength="6" placeholder="000000" class="w-32 bg-bg border border-border rounded px-3 py-1.5 text-sm font-mono tracking-widest text-center"/></div> <button class="px-3 py-1.5 text-sm rounded bg-red-600/80 text-white hover:bg-red-500 transition-colors disabled:opacity-50"><!> </button></div>',1),sa[u],[[281,20],[284,20,[[285,24,[[286,28],[287,28]]],[295,24]]]]),Bm=_(m('<p class="text-sm text-text-subtle mb-3"> </p> <button class="px-4 py-2 text-sm rounded bg-primary-600 text-white hover:bg-primary-500 transition-colors"><i class="fa-solid fa-shield-halved mr-1"></i> </button>',1),sa[u],[[308,20],[311,20,[[315,24]]]]),qm=_(m('<div class="px-2 py-1 rounded bg-bg border border-border"><span class="text-text-muted mr-1"></span> </div>'),sa[u],[[350,40,[[351,44]]]]),Gm=_(m('<div class="grid grid-cols-2 gap-1 font-mono text-xs"></div>'),sa[u],[[348,32]]),Vm=_(m('<div class="space-y-4"><div class="p-4 rounded-lg bg-surface border border-border"><p class="text-sm text-text mb-2 font-medium"> </p> <div class="bg-white p-4 rounded-lg inline-block"><img alt="TOTP QR Code" class="w-48 h-48"/></div> <p class="text-xs text-text-muted mt-3"> </p> <code class="block mt-1 px-3 py-2 rounded bg-bg border border-border text-sm font-mono break-all select-all"> </code></div> <div class="p-4 rounded-lg bg-surface border border-border"><p class="text-sm text-text mb-2 font-medium"> </p> <p class="text-xs text-text-subtle mb-2"> </p> <button class="text-xs text-primary-400 hover:text-primary-300 mb-2"> </button> <!></div> <div class="p-4 rounded-lg bg-surface border border-border"><p class="text-sm text-text mb-2 font-medium"> </p> <p class="text-xs text-text-subtle mb-2"> </p> <div class="flex gap-2"><input type="text" maxlength="6" placeholder="000000" class="w-32 bg-bg border border-border rounded px-3 py-1.5 text-sm font-mono tracking-widest text-center"/> <button class="px-4 py-1.5 text-sm rounded bg-green-600 text-white hover:bg-green-500 transition-colors disabled:opacity-50"><i class="fa-solid fa-check mr-1"></i> </button> <button class="px-3 py-1.5 text-sm rounded border border-border text-text-muted hover:bg-surface-elevated transition-colors"> </button></div></div></div>'),sa[u],[[319,20,[[320,24,[[321,28],[322,28,[[324,32]]],[330,28],[331,28]]],[336,24,[[337,28],[338,28],[341,28]]],[358,24,[[359,28],[360,28],[361,28,[[362,32],[370,32,[[375,36]]],[377,32]]]]]]]]),Hm=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),sa[u],[[402,24]]),Km=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),sa[u],[[406,24]]),Wm=_(m('<div class="flex items-center justify-between p-2.5 rounded-md bg-surface border border-border/50"><div class="flex items-center gap-2.5"><i class="fa-solid fa-key text-text-muted"></i> <div><span class="text-sm text-text"> </span> <span class="text-xs text-text-subtle ml-2"> </span></div></div> <button class="text-xs px-2 py-1 rounded bg-red-500/20 text-red-400 hover:bg-red-500/30 transition-colors"><i class="fa-solid fa-trash"></i></button></div>'),sa[u],[[414,32,[[415,36,[[416,40],[417,40,[[418,44],[419,44]]]]],[422,36,[[427,40]]]]]]),Ym=_(m('<div class="space-y-2 mb-4"></div>'),sa[u],[[412,24]]),Jm=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),sa[u],[[451,32]]),Qm=_(m('<i class="fa-solid fa-plus mr-1"></i> ',1),sa[u],[[453,32]]),Xm=_(m('<p class="text-xs text-text-subtle mt-3"><i class="fa-solid fa-circle-info mr-1"></i> </p>'),sa[u],[[459,24,[[460,28]]]]),Zm=_(m('<div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-solid fa-fingerprint text-amber-400"></i> <div><span class="text-sm font-medium text-text"> </span> <p class="text-xs text-text-subtle mt-0.5"> </p></div></div> <!></div> <div class="p-4"><!> <div class="flex items-end gap-2"><div class="flex-1"><label class="text-xs text-text-muted block mb-1"> </label> <input type="text" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <button class="px-4 py-1.5 text-sm rounded bg-primary-600 text-white hover:bg-primary-500 transition-colors disabled:opacity-50 whitespace-nowrap"><!></button></div> <!></div></div>'),sa[u],[[392,12,[[393,16,[[394,20,[[395,24],[396,24,[[397,24],[398,24]]]]]]],[409,16,[[435,20,[[436,24,[[437,28],[438,28]]],[445,24]]]]]]]]),e2=_(m('<div class="space-y-4"><!> <div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-solid fa-clock text-primary-400"></i> <div><span class="text-sm font-medium text-text"> </span> <p class="text-xs text-text-subtle mt-0.5"> </p></div></div> <!></div> <div class="p-4"><!></div></div> <!> <div class="p-3 rounded-lg bg-surface-elevated border border-border/50"><p class="text-xs text-text-subtle"><i class="fa-solid fa-circle-info mr-1"></i> <!></p></div></div>'),sa[u],[[254,4,[[263,8,[[264,12,[[265,16,[[266,20],[267,20,[[268,24],[269,24]]]]]]],[278,12]]],[469,8,[[470,12,[[471,16]]]]]]]]);function sa(r,i){wa(new.target),ga(i,!0,sa);let o=S(P(null),"status"),d=S(P(Jt([])),"passkeys"),c=S(P(!0),"loading"),f=S(P(""),"message"),b=S(P("success"),"messageType"),h=S(P("idle"),"setupStep"),w=S(P(""),"totpSecret"),L=S(P(""),"otpauthUri"),j=S(P(Jt([])),"recoveryCodes"),A=S(P(""),"verifyCode"),k=S(P(!1),"showRecoveryCodes"),q=S(P(""),"passkeyName"),V=S(P(!1),"registeringPasskey"),X=S(P(""),"disableCode"),N=S(P(!1),"disabling");async function ne(){p(c,!0);try{const[Se,me]=(await M(Promise.all([fetch("/api/auth/2fa/status"),fetch("/api/auth/passkeys")])))();p(o,(await M(Se.json()))(),!0);const $=(await M(me.json()))();p(d,$.passkeys||[],!0)}catch{je(l("twofa.load_failed"),"error")}p(c,!1)}function je(Se,me="success"){p(f,Se,!0),p(b,me,!0),Se&&setTimeout(()=>{p(f,"")},5e3)}async function we(){try{const Se=(await M(fetch("/api/auth/2fa/setup",{method:"POST"})))(),me=(await M(Se.json()))();me.ok?(p(w,me.secret,!0),p(L,me.otpauth_uri,!0),p(j,me.recovery_codes,!0),p(h,"showing_secret")):je(me.error||"Failed to set up TOTP","error")}catch{je("Failed to set up TOTP","error")}}async function Te(){if(e(A).trim())try{const Se=(await M(fetch("/api/auth/2fa/enable",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({code:e(A).trim()})})))(),me=(await M(Se.json()))();me.ok?(je("TOTP 2FA enabled successfully"),p(h,"idle"),p(A,""),p(w,""),p(L,""),(await M(ne()))()):je(me.error||"Invalid code","error")}catch{je("Failed to enable TOTP","error")}}async function Ke(){if(e(X).trim()){p(N,!0);try{const Se=(await M(fetch("/api/auth/2fa/disable",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({code:e(X).trim()})})))(),me=(await M(Se.json()))();me.ok?(je("TOTP 2FA disabled"),p(X,""),(await M(ne()))()):je(me.error||"Invalid code","error")}catch{je("Failed to disable TOTP","error")}p(N,!1)}}async function Ee(){p(V,!0);try{const Se=(await M(fetch("/api/auth/passkey/register/start",{method:"POST"})))(),me=(await M(Se.json()))();if(!me.ok){je(me.error||"Failed to start passkey registration","error"),p(V,!1);return}const $=me.options;if($.publicKey.challenge=_e($.publicKey.challenge),$.publicKey.user.id=_e($.publicKey.user.id),$.publicKey.excludeCredentials)for(const B of $.publicKey.excludeCredentials)B.id=_e(B.id);const z=(await M(navigator.credentials.create($)))();if(!z){je("Passkey creation was cancelled","error"),p(V,!1);return}const Z=z.response,H={credential:{id:z.id,rawId:Ve(z.rawId),response:{attestationObject:Ve(Z.attestationObject),clientDataJSON:Ve(Z.clientDataJSON)},type:z.type},name:e(q)||"Passkey"},Le=(await M(fetch("/api/auth/passkey/register/finish",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify(H)})))(),O=(await M(Le.json()))();O.ok?(je("Passkey registered successfully"),p(q,""),(await M(ne()))()):je(O.error||"Failed to register passkey","error")}catch(Se){C(Se.name,"NotAllowedError")?je("Passkey creation was cancelled or timed out","error"):je(`Passkey registration failed: ${Se.message}`,"error")}p(V,!1)}async function te(Se){if(confirm(`Delete passkey "${Se.name}"?`))try{const me=(await M(fetch(`/api/auth/passkeys/${Se.id}`,{method:"DELETE"})))(),$=(await M(me.json()))();$.ok?(je("Passkey deleted"),(await M(ne()))()):je($.error||"Failed to delete passkey","error")}catch{je("Failed to delete passkey","error")}}function _e(Se){const me=Se.replace(/-/g,"+").replace(/_/g,"/"),$=me+"=".repeat((4-me.length%4)%4),z=atob($),Z=new ArrayBuffer(z.length),H=new Uint8Array(Z);for(let Le=0;Le<z.length;Le++)H[Le]=z.charCodeAt(Le);return Z}function Ve(Se){const me=new Uint8Array(Se);let $="";for(const z of me)$+=String.fromCharCode(z);return btoa($).replace(/\+/g,"-").replace(/\//g,"_").replace(/=/g,"")}function K(Se){const me=new Date(Se+"Z"),$=Date.now()-me.getTime();return $<6e4?"Just now":$<36e5?`${Math.floor($/6e4)}m ago`:$<864e5?`${Math.floor($/36e5)}h ago`:`${Math.floor($/864e5)}d ago`}const W=S(ma(()=>!!va.userId),"hasUserId");Es(()=>{e(W)&&ne()});var Ue={...ka()},Ne=Oa(),Pe=Ae(Ne);{var R=Se=>{var me=Mm(),$=a(me),z=s(a($));x(Z=>n(z,` ${Z??""}`),[()=>l("twofa.multi_user_only")]),v(Se,me)},ie=Se=>{var me=Nm(),$=a(me),z=s(a($));x(Z=>n(z,` ${Z??""}`),[()=>l("twofa.loading_status")]),v(Se,me)},qe=Se=>{var me=e2(),$=a(me);{var z=G=>{var ee=Im(),re=a(ee),ke=s(re);x(()=>{Qe(ee,1,`px-3 py-2 rounded-md text-sm ${C(e(b),"error")?"bg-red-900/20 border border-red-800/40 text-red-400":"bg-green-900/20 border border-green-800/40 text-green-400"}`),Qe(re,1,`fa-solid ${C(e(b),"error")?"fa-circle-exclamation":"fa-check-circle"} mr-1`),n(ke,` ${e(f)??""}`)}),v(G,ee)};g(()=>y($,G=>{e(f)&&G(z)}),"if",sa,255,8)}var Z=s($,2),H=a(Z),Le=a(H),O=s(a(Le),2),B=a(O),ue=a(B),D=s(B,2),T=a(D),E=s(Le,2);{var U=G=>{var ee=Dm(),re=a(ee);x(ke=>n(re,ke),[()=>l("twofa.enabled_badge")]),v(G,ee)},J=G=>{var ee=Fm(),re=a(ee);x(ke=>n(re,ke),[()=>l("twofa.disabled_badge")]),v(G,ee)};g(()=>y(E,G=>{e(o).totp_enabled?G(U):G(J,!1)}),"if",sa,272,16)}var xe=s(H,2),fe=a(xe);{var F=G=>{var ee=zm(),re=Ae(ee),ke=a(re),ve=s(re,2),Q=a(ve),$e=a(Q),Ie=a($e),Re=s($e,2),Ct=s(Q,2),st=a(Ct);{var De=Je=>{var _t=Um();v(Je,_t)};g(()=>y(st,Je=>{e(N)&&Je(De)}),"if",sa,300,28)}var lt=s(st);x((Je,_t,ze,be)=>{n(ke,Je),n(Ie,_t),Ct.disabled=ze,n(lt,` ${be??""}`)},[()=>l("twofa.totp_enabled"),()=>l("twofa.totp_code"),()=>e(N)||C(e(X).trim().length,6,!1),()=>l("twofa.disable_2fa")]),ua(Re,function(){return e(X)},function(_t){p(X,_t)}),Y("click",Ct,Ke),v(G,ee)},he=G=>{var ee=Bm(),re=Ae(ee),ke=a(re),ve=s(re,2),Q=s(a(ve));x(($e,Ie)=>{n(ke,$e),n(Q,` ${Ie??""}`)},[()=>l("twofa.setup_desc"),()=>l("twofa.setup_authenticator")]),Y("click",ve,we),v(G,ee)},Xe=G=>{var ee=Vm(),re=a(ee),ke=a(re),ve=a(ke),Q=s(ke,2),$e=a(Q),Ie=s(Q,2),Re=a(Ie),Ct=s(Ie,2),st=a(Ct),De=s(re,2),lt=a(De),Je=a(lt),_t=s(lt,2),ze=a(_t),be=s(_t,2),ce=a(be),pe=s(be,2);{var Oe=xt=>{var se=Gm();g(()=>Dt(se,21,()=>e(j),la,(ye,et,dt)=>{var tt=qm(),Ye=a(tt);Ye.textContent=`${dt+1}.`;var at=s(Ye);x(()=>n(at,` ${e(et)??""}`)),v(ye,tt)}),"each",sa,349,36),v(xt,se)};g(()=>y(pe,xt=>{e(k)&&xt(Oe)}),"if",sa,347,28)}var Me=s(De,2),Ze=a(Me),Be=a(Ze),We=s(Ze,2),it=a(We),ot=s(We,2),rt=a(ot),Fe=s(rt,2),ut=s(a(Fe)),mt=s(Fe,2),Tt=a(mt);x((xt,se,ye,et,dt,tt,Ye,at,ft,wt,Ge)=>{n(ve,xt),St($e,"src",`https://api.qrserver.com/v1/create-qr-code/?size=200x200&data=${se??""}`),n(Re,ye),n(st,e(w)),n(Je,et),n(ze,dt),n(ce,tt),n(Be,Ye),n(it,at),Fe.disabled=ft,n(ut,` ${wt??""}`),n(Tt,Ge)},[()=>l("twofa.scan_step"),()=>encodeURIComponent(e(L)),()=>l("twofa.manual_key"),()=>l("twofa.save_recovery"),()=>l("twofa.recovery_hint"),()=>e(k)?l("twofa.hide_codes"):l("twofa.show_codes"),()=>l("twofa.verify_step"),()=>l("twofa.verify_hint"),()=>C(e(A).trim().length,6,!1),()=>l("twofa.verify_enable"),()=>l("common.cancel")]),Y("click",be,function(){return p(k,!e(k))}),Y("keydown",rt,function(se){C(se.key,"Enter")&&Te()}),ua(rt,function(){return e(A)},function(se){p(A,se)}),Y("click",Fe,Te),Y("click",mt,function(){p(h,"idle"),p(w,""),p(L,"")}),v(G,ee)};g(()=>y(fe,G=>{e(o).totp_enabled?G(F):C(e(h),"idle")?G(he,1):C(e(h),"showing_secret")&&G(Xe,2)}),"if",sa,279,16)}var yt=s(Z,2);{var ht=G=>{var ee=Zm(),re=a(ee),ke=a(re),ve=s(a(ke),2),Q=a(ve),$e=a(Q),Ie=s(Q,2),Re=a(Ie),Ct=s(ke,2);{var st=rt=>{var Fe=Hm(),ut=a(Fe);x(mt=>n(ut,mt),[()=>l("twofa.registered",{count:e(o).passkey_count})]),v(rt,Fe)},De=rt=>{var Fe=Km(),ut=a(Fe);x(mt=>n(ut,mt),[()=>l("twofa.none_badge")]),v(rt,Fe)};g(()=>y(Ct,rt=>{e(o).passkey_count>0?rt(st):rt(De,!1)}),"if",sa,401,20)}var lt=s(re,2),Je=a(lt);{var _t=rt=>{var Fe=Ym();g(()=>Dt(Fe,21,()=>e(d),la,(ut,mt)=>{var Tt=Wm(),xt=a(Tt),se=s(a(xt),2),ye=a(se),et=a(ye,!0);var dt=s(ye,2),tt=a(dt);var Ye=s(xt,2);x((at,ft,wt)=>{n(et,e(mt).name),n(tt,`${at??""} ${ft??""}`),St(Ye,"title",wt)},[()=>l("twofa.added_label"),()=>K(e(mt).created_at),()=>l("twofa.delete_passkey")]),Y("click",Ye,function(){return te(e(mt))}),v(ut,Tt)}),"each",sa,413,28),v(rt,Fe)};g(()=>y(Je,rt=>{e(d).length>0&&rt(_t)}),"if",sa,411,20)}var ze=s(Je,2),be=a(ze),ce=a(be),pe=a(ce),Oe=s(ce,2),Me=s(be,2),Ze=a(Me);{var Be=rt=>{var Fe=Jm(),ut=s(Ae(Fe));x(mt=>n(ut,` ${mt??""}`),[()=>l("twofa.registering")]),v(rt,Fe)},We=rt=>{var Fe=Qm(),ut=s(Ae(Fe));x(mt=>n(ut,` ${mt??""}`),[()=>l("twofa.add_passkey")]),v(rt,Fe)};g(()=>y(Ze,rt=>{e(V)?rt(Be):rt(We,!1)}),"if",sa,450,28)}var it=s(ze,2);{var ot=rt=>{var Fe=Xm(),ut=s(a(Fe));x(mt=>n(ut,` ${mt??""}`),[()=>l("twofa.passkey_info")]),v(rt,Fe)};g(()=>y(it,rt=>{C(e(d).length,0)&&rt(ot)}),"if",sa,458,20)}x((rt,Fe,ut,mt)=>{n($e,rt),n(Re,Fe),n(pe,ut),St(Oe,"placeholder",mt),Me.disabled=e(V)},[()=>l("twofa.passkeys"),()=>l("twofa.passkeys_desc"),()=>l("twofa.passkey_name"),()=>l("twofa.passkey_placeholder")]),ua(Oe,function(){return e(q)},function(Fe){p(q,Fe)}),Y("click",Me,Ee),v(G,ee)};g(()=>y(yt,G=>{e(o).passkeys_available&&G(ht)}),"if",sa,391,8)}var ct=s(yt,2),oe=a(ct),le=s(a(oe)),I=s(le);{var ae=G=>{var ee=Jr();x(re=>n(ee,re),[()=>l("twofa.info_both")]),v(G,ee)};g(()=>y(I,G=>{e(o).totp_enabled&&e(o).passkey_count>0&&G(ae)}),"if",sa,473,16)}x((G,ee,re)=>{n(ue,G),n(T,ee),n(le,` ${re??""} `)},[()=>l("twofa.authenticator_app"),()=>l("twofa.authenticator_hint"),()=>l("twofa.info_box")]),v(Se,me)};g(()=>y(Pe,Se=>{e(W)?e(c)?Se(ie,1):e(o)&&Se(qe,2):Se(R)}),"if",sa,242,0)}return v(r,Ne),ha(Ue)}La(["click","keydown"]);Mr[u]="src/components/PersonaEditor.svelte";var t2=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Mr[u],[[72,20]]),a2=_(m('<i class="fa-solid fa-floppy-disk mr-1"></i> ',1),Mr[u],[[74,20]]),r2=_(m("<span> </span>"),Mr[u],[[78,16]]),s2=_(m('<span class="text-xs text-warning-500"><i class="fa-solid fa-circle-exclamation mr-0.5"></i> Unsaved changes</span>'),Mr[u],[[81,16,[[82,20]]]]),i2=_(m(`<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden mb-4"><div class="border-b border-border"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted"><i class="fa-solid fa-masks-theater mr-1.5"></i> </h2></div> <div class="p-4 space-y-3"><p class="text-xs text-text-subtle"> </p> <div><label class="text-xs font-medium text-text-muted uppercase tracking-wide mb-1 block"> </label> <textarea rows="6" class="w-full px-3 py-2 rounded-md border border-border bg-surface-elevated text-text text-sm
+                        a 2FA challenge will appear here for your confirmation.</p></div>`),Rt[u],[[514,16,[[515,20],[516,20],[517,20]]]]),d_=_(m('<div class="card p-4"><div class="flex items-start gap-3"><i class="fa-solid fa-lock text-purple-400 text-lg mt-1"></i> <div class="flex-1"><div class="flex items-center gap-2"><span class="font-semibold text-heading"> </span> <span class="text-xs bg-card-hover px-1.5 py-0.5 rounded"> </span> <span class="text-xs text-muted"> </span></div> <p class="text-sm text-muted mt-1"> </p> <div class="flex gap-2 mt-2"><button class="btn btn-sm bg-green-600 hover:bg-green-500 text-white"><i class="fa-solid fa-check mr-1"></i> </button> <button class="btn btn-sm bg-red-600 hover:bg-red-500 text-white"><i class="fa-solid fa-xmark mr-1"></i> </button></div></div></div></div>'),Rt[u],[[524,20,[[525,24,[[526,28],[527,28,[[528,32,[[529,36],[530,36],[531,36]]],[533,32],[534,32,[[535,36,[[536,40]]],[538,36,[[539,40]]]]]]]]]]]]),c_=_(m('<div class="space-y-3"><div class="flex items-center justify-between mb-2"><h3 class="text-lg font-bold text-heading"><i class="fa-solid fa-lock mr-2"></i> </h3> <button class="btn btn-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div> <!></div>'),Rt[u],[[505,8,[[506,12,[[507,16,[[507,59]]],[508,16,[[509,20]]]]]]]]),v_=_(m('<div class="space-y-4"><div class="flex items-center gap-3 mb-4"><h2 class="text-xl font-bold text-heading"><i class="fa-solid fa-shield-halved mr-2"></i> </h2></div> <div class="flex gap-1 border-b border-border pb-1 mb-4"></div> <!></div>'),Rt[u],[[192,0,[[193,4,[[194,8,[[194,51]]]]],[198,4]]]]);function Rt(r,i){wa(new.target),ga(i,!0,Rt);let o=S(P(null),"auditSummary"),d=S(P(null),"costSummary"),c=S(P(null),"rateStatus"),f=S(P(Jt([])),"challenges"),b=S(P(Jt([])),"auditEntries"),h=S(P(Jt([])),"explanationChain"),w=S(P(!1),"showExplanation"),L=S(P("overview"),"activeSection"),j=yi(Jt({event_type:"",tool:""}),"auditFilter");async function A(){try{const $=(await M(fetch("/api/security/overview")))(),z=(await M($.json()))();p(o,z.audit,!0),p(d,z.cost,!0),p(c,z.rate_limit,!0),p(f,[],!0)}catch($){console.error(...Xt("error","Failed to load security overview",$))}}async function k(){try{const $=new URLSearchParams({limit:"50"});j.event_type&&$.set("event_type",j.event_type),j.tool&&$.set("tool",j.tool);const z=(await M(fetch(`/api/security/audit?${$}`)))();p(b,(await M(z.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load audit log",$))}}async function q(){try{const $=(await M(fetch("/api/security/cost")))();p(d,(await M($.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load cost summary",$))}}async function V(){try{const $=(await M(fetch("/api/security/rate-limit")))();p(c,(await M($.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load rate limit status",$))}}async function X(){try{const $=(await M(fetch("/api/security/2fa")))();p(f,(await M($.json()))(),!0)}catch($){console.error(...Xt("error","Failed to load 2FA challenges",$))}}async function N($){const st=(await M(fetch("/api/auth/2fa/status").then(r=>r.ok?r.json():null).catch(()=>null)))();let code=null;if(st?.totp_enabled&&(code=prompt(l("twofa.totp_code")),!code))return;const res=(await M(fetch(`/api/security/2fa/${$}/confirm`,{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({code})})))(),data=(await M(res.json()))();!data.ok&&data.message&&alert(data.message),(await M(X()))()}async function ne($){(await M(fetch(`/api/security/2fa/${$}/reject`,{method:"POST"})))(),(await M(X()))()}async function je($){try{const z=(await M(fetch(`/api/security/audit/${$}/explain`)))();p(h,(await M(z.json()))(),!0),p(w,!0)}catch(z){console.error(...Xt("error","Failed to load explanation",z))}}function we($){p(L,$,!0),C($,"overview")?A():C($,"audit")?k():C($,"cost")?q():C($,"rate")?V():C($,"2fa")&&X()}function Te($){return`$${$.toFixed(4)}`}function Ke($){return $>=1e6?`${($/1e6).toFixed(1)}M`:$>=1e3?`${($/1e3).toFixed(1)}K`:$.toString()}function Ee($){switch($){case"tool_call":return"text-blue-400";case"approval":return"text-yellow-400";case"rate_limit":return"text-orange-400";case"pii_detected":return"text-red-400";case"2fa":return"text-purple-400";case"permission_denied":return"text-red-500";default:return"text-muted"}}function te($){switch($){case"tool_call":return"fa-wrench";case"approval":return"fa-check-double";case"rate_limit":return"fa-gauge-high";case"pii_detected":return"fa-user-shield";case"2fa":return"fa-lock";case"permission_denied":return"fa-ban";default:return"fa-circle-info"}}Ga(()=>{kt.refreshCounter,A()});var _e={...ka()},Ve=v_(),K=a(Ve),W=a(K),Ue=s(a(W),1,!0),Ne=s(K,2);g(()=>Dt(Ne,21,()=>[{id:"overview",label:l("security.overview"),icon:"fa-chart-pie"},{id:"audit",label:l("security.audit_trail"),icon:"fa-scroll"},{id:"cost",label:l("security.cost_tracking"),icon:"fa-dollar-sign"},{id:"rate",label:l("security.rate_limits"),icon:"fa-gauge-high"},{id:"2fa",label:l("security.twofa"),icon:"fa-lock"}],la,($,z)=>{var Z=Mp();let H;var Le=a(Z),O=s(Le,1,!0);x(()=>{H=Qe(Z,1,"px-3 py-1.5 text-sm rounded-t transition-colors",null,H,{"bg-card":C(e(L),e(z).id),"text-heading":C(e(L),e(z).id),"text-muted":C(e(L),e(z).id,!1),"hover:text-heading":C(e(L),e(z).id,!1)}),Qe(Le,1,`fa-solid ${e(z).icon??""} mr-1`),n(O,e(z).label)}),Y("click",Z,function(){return we(e(z).id)}),v($,Z)}),"each",Rt,199,8);var Pe=s(Ne,2);{var R=$=>{var z=Oa(),Z=Ae(z);{var H=O=>{var B=Np(),ue=Ae(B),D=a(ue),T=a(D),E=a(T),U=s(T,2),J=a(U),xe=s(D,2),fe=a(xe),F=a(fe),he=s(fe,2),Xe=a(he),yt=s(xe,2),ht=a(yt);let ct;var oe=a(ht),le=s(ht,2),I=a(le),ae=s(I);{var G=Za=>{var qt=Jr();x(Wt=>n(qt,`/ ${Wt??""}`),[()=>Te(e(d).daily_limit_usd)]),v(Za,qt)};g(()=>y(ae,Za=>{e(d).daily_limit_usd>0&&Za(G)}),"if",Rt,237,24)}var ee=s(yt,2),re=a(ee);let ke;var ve=a(re),Q=s(re,2),$e=a(Q),Ie=s(ue,2),Re=a(Ie),Ct=a(Re),st=a(Ct),De=s(Ct,2),lt=a(De),Je=s(Re,2),_t=a(Je),ze=a(_t),be=s(_t,2),ce=a(be),pe=s(Je,2),Oe=a(pe),Me=a(Oe),Ze=s(Oe,2),Be=a(Ze),We=s(pe,2),it=a(We),ot=a(it),rt=s(it,2),Fe=a(rt),ut=s(Ie,2),mt=a(ut),Tt=s(a(mt),2),xt=a(Tt),se=a(xt),ye=a(se),et=s(se),dt=a(et),tt=s(xt,2),Ye=a(tt),at=a(Ye),ft=s(Ye),wt=a(ft),Ge=s(tt,2),bt=a(Ge),pt=a(bt),vt=s(bt),Pt=a(vt),Et=s(Ge,2),jt=a(Et),Ft=a(jt),Ht=s(jt),Ut=a(Ht),Kt=s(Et,2),na=a(Kt),ta=a(na),Ea=s(na),pa=a(Ea),zt=s(Kt,2),da=s(a(zt)),ia=a(da),aa=s(mt,2),$a=s(a(aa),2),gt=a($a),Ot=s(a(gt)),Lt=a(Ot),Mt=s(gt,2),fa=s(a(Mt)),Aa=a(fa),Ra=s(Mt,2),Zt=s(a(Ra),2);let Ya;var vr=a(Zt),sr=s(Ra,2),ir=s(a(sr)),br=a(ir),Er=s(sr,2),or=s(a(Er)),Dr=a(or);x((Za,qt,Wt,xa,ba,rs,Sr,Cr,Fr,Ur,Ss,er,fs,zr,Cs,He,nt,It,Gt)=>{n(E,e(o).total_events),n(J,Za),n(F,e(o).tool_calls),n(Xe,qt),ct=Qe(ht,1,"text-2xl font-bold",null,ct,{"text-red-400":e(d).limit_exceeded,"text-green-400":!e(d).limit_exceeded}),n(oe,Wt),n(I,`${xa??""} `),ke=Qe(re,1,"text-2xl font-bold",null,ke,{"text-orange-400":e(c).is_limited,"text-green-400":!e(c).is_limited}),n(ve,`${e(c).calls_last_minute??""}/${(e(c).limit_per_minute||"∞")??""}`),n($e,ba),n(st,e(o).approvals),n(lt,rs),n(ze,e(o).rejections),n(ce,Sr),n(Me,e(o).rate_limits),n(Be,Cr),n(ot,e(o).pii_detections),n(Fe,Fr),n(ye,Ur),n(dt,Ss),n(at,er),n(wt,fs),n(pt,zr),n(Pt,Cs),n(Ft,He),n(Ut,nt),n(ta,It),n(pa,Gt),n(ia,e(d).today_requests),n(Lt,`${e(c).calls_last_minute??""} / ${(e(c).limit_per_minute||"∞")??""}`),n(Aa,`${e(c).calls_last_hour??""} / ${(e(c).limit_per_hour||"∞")??""}`),Ya=Qe(Zt,1,"",null,Ya,{"text-red-400":e(c).is_limited,"text-green-400":!e(c).is_limited}),n(vr,e(c).is_limited?"RATE LIMITED":"OK"),n(br,e(o).twofa_challenges),n(Dr,e(o).permission_denials)},[()=>l("security.total_events"),()=>l("security.tool_calls"),()=>Te(e(d).today_usd),()=>l("security.todays_cost"),()=>l("security.calls_per_min"),()=>l("security.approvals"),()=>l("security.rejections"),()=>l("security.rate_limits"),()=>l("security.pii_detections"),()=>l("security.todays_cost"),()=>Te(e(d).today_usd),()=>l("security.month_cost"),()=>Te(e(d).month_usd),()=>l("security.total_cost"),()=>Te(e(d).total_usd),()=>l("security.tokens"),()=>Ke(e(d).today_tokens),()=>l("security.total_tokens"),()=>Ke(e(d).total_tokens)]),v(O,B)},Le=O=>{var B=Ip(),ue=a(B);x(D=>n(ue,D),[()=>l("common.loading")]),v(O,B)};g(()=>y(Z,O=>{e(o)&&e(d)&&e(c)?O(H):O(Le,!1)}),"if",Rt,221,8)}v($,z)},ie=$=>{var z=e_(),Z=Ae(z),H=a(Z),Le=a(H),O=a(Le);Le.value=Le.__value="";var B=s(Le);B.value=B.__value="tool_call";var ue=s(B);ue.value=ue.__value="approval";var D=s(ue);D.value=D.__value="rate_limit";var T=s(D);T.value=T.__value="pii_detected";var E=s(T);E.value=E.__value="2fa";var U=s(E);U.value=U.__value="permission_denied",xn("bind:value={auditFilter.event_type}",[],()=>j,()=>"event_type",304,81);var J=s(H,2);xn("bind:value={auditFilter.tool}",[],()=>j,()=>"tool",317,16);var xe=s(J,2),fe=s(a(xe),1,!0),F=s(Z,2);{var he=ct=>{var oe=Dp(),le=a(oe);x(I=>n(le,I),[()=>l("security.no_events")]),v(ct,oe)},Xe=ct=>{var oe=Vp();g(()=>Dt(oe,21,()=>e(b),la,(le,I)=>{var ae=Gp(),G=a(ae),ee=s(G,2),re=a(ee),ke=a(re),ve=a(ke,!0);var Q=s(ke,2);{var $e=Be=>{var We=Fp(),it=a(We,!0);x(()=>n(it,e(I).tool)),v(Be,We)};g(()=>y(Q,Be=>{e(I).tool&&Be($e)}),"if",Rt,335,32)}var Ie=s(Q,2);{var Re=Be=>{var We=Up(),it=a(We,!0);x(()=>n(it,e(I).action)),v(Be,We)};g(()=>y(Ie,Be=>{e(I).action&&Be(Re)}),"if",Rt,338,32)}var Ct=s(Ie,2);{var st=Be=>{var We=zp();let it;var ot=a(We,!0);x(()=>{it=Qe(We,1,"text-xs",null,it,{"text-green-400":e(I).success,"text-red-400":!e(I).success}),n(ot,e(I).success?"✓":"✗")}),v(Be,We)};g(()=>y(Ct,Be=>{C(e(I).success,null,!1)&&Be(st)}),"if",Rt,341,32)}var De=s(Ct,2),lt=a(De,!0);var Je=s(re,2);{var _t=Be=>{var We=Bp(),it=a(We);x(()=>n(it,`Reasoning: ${e(I).reasoning??""}`)),v(Be,We)};g(()=>y(Je,Be=>{e(I).reasoning&&Be(_t)}),"if",Rt,348,28)}var ze=s(Je,2);{var be=Be=>{var We=qp(),it=a(We);x(()=>n(it,`Result: ${e(I).result??""}`)),v(Be,We)};g(()=>y(ze,Be=>{e(I).result&&Be(be)}),"if",Rt,351,28)}var ce=s(ze,2),pe=a(ce),Oe=a(pe,!0);var Me=s(pe,2),Ze=s(a(Me));x((Be,We,it,ot,rt)=>{Qe(G,1,`fa-solid ${Be??""} ${We??""} mt-0.5`),Qe(ke,1,`font-semibold ${it??""}`),n(ve,e(I).event_type),n(lt,e(I).source),n(Oe,ot),n(Ze,` ${rt??""}`)},[()=>te(e(I).event_type),()=>Ee(e(I).event_type),()=>Ee(e(I).event_type),()=>Zr(e(I).created_at),()=>l("security.why")]),Y("click",Me,function(){return je(e(I).id)}),v(le,ae)}),"each",Rt,329,16),v(ct,oe)};g(()=>y(F,ct=>{C(e(b).length,0)?ct(he):ct(Xe,!1)}),"if",Rt,325,8)}var yt=s(F,2);{var ht=ct=>{var oe=Zp(),le=a(oe),I=a(le),ae=a(I),G=s(a(ae),1,!0),ee=s(ae,2),re=s(I,2);{var ke=Q=>{var $e=Hp(),Ie=a($e);x(Re=>n(Ie,Re),[()=>l("security.no_explanation")]),v(Q,$e)},ve=Q=>{var $e=Xp();g(()=>Dt($e,21,()=>e(h),la,(Ie,Re,Ct)=>{var st=Qp();let De;var lt=a(st),Je=a(lt),_t=s(Je);{var ze=it=>{var ot=Kp(),rt=a(ot);x(()=>n(rt,`(${e(Re).tool??""})`)),v(it,ot)};g(()=>y(_t,it=>{e(Re).tool&&it(ze)}),"if",Rt,384,40)}var be=s(lt,2);{var ce=it=>{var ot=Wp(),rt=s(a(ot));x(()=>n(rt,` ${e(Re).reasoning??""}`)),v(it,ot)};g(()=>y(be,it=>{e(Re).reasoning&&it(ce)}),"if",Rt,386,36)}var pe=s(be,2);{var Oe=it=>{var ot=Yp(),rt=a(ot);x(()=>n(rt,`Context: ${e(Re).user_context??""}`)),v(it,ot)};g(()=>y(pe,it=>{e(Re).user_context&&it(Oe)}),"if",Rt,389,36)}var Me=s(pe,2);{var Ze=it=>{var ot=Jp(),rt=a(ot);x(()=>n(rt,`Result: ${e(Re).result??""}`)),v(it,ot)};g(()=>y(Me,it=>{e(Re).result&&it(Ze)}),"if",Rt,392,36)}var Be=s(Me,2),We=a(Be,!0);x((it,ot,rt)=>{De=Qe(st,1,"border-l-2 pl-3",null,De,it),Qe(lt,1,`text-sm font-semibold ${ot??""}`),n(Je,`Step ${Ct+1}: ${e(Re).event_type??""} `),n(We,rt)},[()=>({"border-blue-400":C(e(Re).event_type,"tool_call"),"border-yellow-400":C(e(Re).event_type,"approval"),"border-red-400":C(e(Re).event_type,"pii_detected")||C(e(Re).event_type,"permission_denied"),"border-border":!["tool_call","approval","pii_detected","permission_denied"].includes(e(Re).event_type)}),()=>Ee(e(Re).event_type),()=>Zr(e(Re).created_at)]),v(Ie,st)}),"each",Rt,380,28),v(Q,$e)};g(()=>y(re,Q=>{C(e(h).length,0)?Q(ke):Q(ve,!1)}),"if",Rt,376,20)}x((Q,$e)=>{n(G,Q),St(ee,"title",$e)},[()=>l("security.action_explanation"),()=>l("common.close")]),Y("click",oe,function(){return p(w,!1)}),Y("click",le,function($e){return $e.stopPropagation()}),Y("click",ee,function(){return p(w,!1)}),v(ct,oe)};g(()=>y(yt,ct=>{e(w)&&ct(ht)}),"if",Rt,367,8)}x((ct,oe,le)=>{n(O,ct),St(J,"placeholder",oe),n(fe,le)},[()=>l("security.all_types"),()=>l("security.filter"),()=>l("common.refresh")]),Y("change",H,function(){return k()}),Gi(H,function(){return j.event_type},function(oe){j.event_type=oe}),Y("keyup",J,function(oe){C(oe.key,"Enter")&&k()}),ua(J,function(){return j.tool},function(oe){j.tool=oe}),Y("click",xe,function(){return k()}),v($,z)},qe=$=>{var z=Oa(),Z=Ae(z);{var H=O=>{var B=a_(),ue=Ae(B),D=a(ue),T=a(D);let E;var U=a(T),J=s(T,2),xe=a(J),fe=s(J,2);{var F=ze=>{var be=t_(),ce=Ae(be),pe=a(ce);let Oe;var Me=s(ce,2),Ze=a(Me);x((Be,We)=>{Oe=Qe(pe,1,"h-2 rounded-full transition-all",null,Oe,{"bg-green-400":!e(d).limit_exceeded,"bg-red-400":e(d).limit_exceeded}),Ds(pe,`width: ${Be??""}%`),n(Ze,`Limit: ${We??""}`)},[()=>Math.min(100,e(d).today_usd/e(d).daily_limit_usd*100),()=>Te(e(d).daily_limit_usd)]),v(ze,be)};g(()=>y(fe,ze=>{e(d).daily_limit_usd>0&&ze(F)}),"if",Rt,413,20)}var he=s(D,2),Xe=a(he),yt=a(Xe),ht=s(Xe,2),ct=a(ht),oe=s(he,2),le=a(oe),I=a(le),ae=s(le,2),G=a(ae),ee=s(ue,2),re=a(ee),ke=a(re),ve=a(ke),Q=s(ke,2),$e=a(Q),Ie=s(re,2),Re=a(Ie),Ct=a(Re),st=s(Re,2),De=a(st),lt=s(Ie,2),Je=a(lt),_t=a(Je);x((ze,be,ce,pe,Oe,Me,Ze,Be,We,it)=>{E=Qe(T,1,"text-3xl font-bold",null,E,{"text-red-400":e(d).limit_exceeded,"text-green-400":!e(d).limit_exceeded}),n(U,ze),n(xe,be),n(yt,ce),n(ct,pe),n(I,Oe),n(G,Me),n(ve,Ze),n($e,Be),n(Ct,We),n(De,it),n(_t,e(d).today_requests)},[()=>Te(e(d).today_usd),()=>l("security.todays_cost"),()=>Te(e(d).month_usd),()=>l("security.month_cost"),()=>Te(e(d).total_usd),()=>l("security.total_cost"),()=>Ke(e(d).today_tokens),()=>l("security.tokens"),()=>Ke(e(d).total_tokens),()=>l("security.total_tokens")]),v(O,B)},Le=O=>{var B=r_(),ue=a(B);x(D=>n(ue,D),[()=>l("common.loading")]),v(O,B)};g(()=>y(Z,O=>{e(d)?O(H):O(Le,!1)}),"if",Rt,406,8)}v($,z)},Se=$=>{var z=Oa(),Z=Ae(z);{var H=O=>{var B=o_(),ue=s(a(B),2),D=a(ue),T=a(D),E=s(a(T),2),U=a(E),J=s(T,2);{var xe=ae=>{var G=s_(),ee=a(G);let re;x(ke=>{re=Qe(ee,1,"h-3 rounded-full transition-all",null,re,{"bg-green-400":e(c).calls_last_minute/e(c).limit_per_minute<.8,"bg-yellow-400":e(c).calls_last_minute/e(c).limit_per_minute>=.8&&e(c).calls_last_minute/e(c).limit_per_minute<1,"bg-red-400":e(c).calls_last_minute/e(c).limit_per_minute>=1}),Ds(ee,`width: ${ke??""}%`)},[()=>Math.min(100,e(c).calls_last_minute/e(c).limit_per_minute*100)]),v(ae,G)};g(()=>y(J,ae=>{e(c).limit_per_minute>0&&ae(xe)}),"if",Rt,463,24)}var fe=s(D,2),F=a(fe),he=s(a(F),2),Xe=a(he),yt=s(F,2);{var ht=ae=>{var G=i_(),ee=a(G);let re;x(ke=>{re=Qe(ee,1,"h-3 rounded-full transition-all",null,re,{"bg-green-400":e(c).calls_last_hour/e(c).limit_per_hour<.8,"bg-yellow-400":e(c).calls_last_hour/e(c).limit_per_hour>=.8&&e(c).calls_last_hour/e(c).limit_per_hour<1,"bg-red-400":e(c).calls_last_hour/e(c).limit_per_hour>=1}),Ds(ee,`width: ${ke??""}%`)},[()=>Math.min(100,e(c).calls_last_hour/e(c).limit_per_hour*100)]),v(ae,G)};g(()=>y(yt,ae=>{e(c).limit_per_hour>0&&ae(ht)}),"if",Rt,480,24)}var ct=s(fe,2),oe=a(ct);let le;var I=a(oe);x(()=>{n(U,`${e(c).calls_last_minute??""} / ${(e(c).limit_per_minute||"∞")??""}`),n(Xe,`${e(c).calls_last_hour??""} / ${(e(c).limit_per_hour||"∞")??""}`),Ds(ct,`background-color: ${e(c).is_limited?"rgba(248,113,113,0.1)":"rgba(74,222,128,0.1)"}`),le=Qe(oe,1,"text-lg font-bold",null,le,{"text-green-400":!e(c).is_limited,"text-red-400":e(c).is_limited}),n(I,e(c).is_limited?"RATE LIMITED":"Within Limits")}),v(O,B)},Le=O=>{var B=n_(),ue=a(B);x(D=>n(ue,D),[()=>l("common.loading")]),v(O,B)};g(()=>y(Z,O=>{e(c)?O(H):O(Le,!1)}),"if",Rt,454,8)}v($,z)},me=$=>{var z=c_(),Z=a(z),H=a(Z),Le=s(a(H),1,!0),O=s(H,2),B=s(a(O),1,!0),ue=s(Z,2);{var D=E=>{var U=l_(),J=s(a(U),2),xe=a(J);x(fe=>n(xe,fe),[()=>l("security.no_challenges")]),v(E,U)},T=E=>{var U=Oa(),J=Ae(U);g(()=>Dt(J,17,()=>e(f),la,(xe,fe)=>{var F=d_(),he=a(F),Xe=s(a(he),2),yt=a(Xe),ht=a(yt),ct=a(ht,!0);var oe=s(ht,2),le=a(oe,!0);var I=s(oe,2),ae=a(I);var G=s(yt,2),ee=a(G,!0);var re=s(G,2),ke=a(re),ve=s(a(ke),1,!0);var Q=s(ke,2),$e=s(a(Q),1,!0);x((Ie,Re)=>{n(ct,e(fe).tool),n(le,e(fe).source),n(ae,`${e(fe).age_secs??""}s ago`),n(ee,e(fe).description),n(ve,Ie),n($e,Re)},[()=>l("common.confirm"),()=>l("security.reject")]),Y("click",ke,function(){return N(e(fe).id)}),Y("click",Q,function(){return ne(e(fe).id)}),v(xe,F)}),"each",Rt,523,16),v(E,U)};g(()=>y(ue,E=>{C(e(f).length,0)?E(D):E(T,!1)}),"if",Rt,513,12)}x((E,U)=>{n(Le,E),n(B,U)},[()=>l("security.pending_challenges"),()=>l("common.refresh")]),Y("click",O,function(){return X()}),v($,z)};g(()=>y(Pe,$=>{C(e(L),"overview")?$(R):C(e(L),"audit")?$(ie,1):C(e(L),"cost")?$(qe,2):C(e(L),"rate")?$(Se,3):C(e(L),"2fa")&&$(me,4)}),"if",Rt,220,4)}return x($=>n(Ue,$),[()=>l("security.title")]),v(r,Ve),ha(_e)}La(["click","change","keyup"]);Ce[u]="src/components/OperationsTab.svelte";var u_=_(m("<button><i></i> </button>"),Ce[u],[[271,12,[[275,16]]]]),f_=_(m('<p class="text-muted"> </p>'),Ce[u],[[287,16]]),p_=_(m('<div class="grid grid-cols-1 sm:grid-cols-3 gap-3 mt-3"><div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div></div></div>'),Ce[u],[[298,24,[[299,28,[[300,32],[301,32]]],[305,28,[[306,32],[307,32]]],[311,28,[[312,32],[313,32]]]]]]),__=_(m('<div class="space-y-3"><div class="flex items-center gap-3"><span> </span> <span class="text-muted text-sm"> </span></div> <!> <button class="btn-secondary text-sm mt-2"><i class="fa-solid fa-rotate mr-1"></i> </button></div>'),Ce[u],[[289,16,[[290,20,[[291,24],[294,24]]],[318,20,[[319,24]]]]]]),m_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-heart-pulse mr-1"></i> </h3> <!></div> <div class="card"><h3 class="text-lg font-semibold mb-2"><i class="fa-solid fa-chart-bar mr-1"></i> </h3> <p class="text-sm text-muted mb-2"> </p> <p class="text-sm text-muted"> </p> <div class="mt-2"><button class="btn-secondary text-sm"><i class="fa-solid fa-arrow-up-right-from-square mr-1"></i> </button></div></div>',1),Ce[u],[[282,8,[[283,12,[[284,16]]]]],[326,8,[[327,12,[[328,16]]],[330,12],[333,12],[336,12,[[337,16,[[338,20]]]]]]]]),x_=_(m('<span class="text-sm text-muted">&rarr;</span> <span class="font-mono text-green-400"> </span> <span class="bg-green-500/20 text-green-400 text-xs px-2 py-0.5 rounded"> </span>',1),Ce[u],[[356,28],[357,28],[358,28]]),b_=_(m('<span class="bg-surface text-muted text-xs px-2 py-0.5 rounded"> </span>'),Ce[u],[[360,28]]),g_=_(m('<div class="bg-surface rounded p-3 text-sm"><div class="text-xs text-muted mb-1"> </div> <pre class="whitespace-pre-wrap text-fg"> </pre></div>'),Ce[u],[[365,24,[[366,28],[367,28]]]]),h_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[375,36]]),y_=_(m('<i class="fa-solid fa-rocket mr-1"></i> ',1),Ce[u],[[377,36]]),w_=_(m('<a target="_blank" class="btn-secondary text-sm"><i class="fa-solid fa-arrow-up-right-from-square mr-1"></i> </a>'),Ce[u],[[381,32,[[382,36]]]]),k_=_(m('<div class="flex gap-2"><button class="btn-primary text-sm"><!></button> <!></div>'),Ce[u],[[372,24,[[373,28]]]]),$_=_(m('<div class="flex items-center gap-3 flex-wrap"><span class="text-sm text-muted"> </span> <span class="font-mono text-fg"> </span> <!></div> <!> <!>',1),Ce[u],[[352,20,[[353,24],[354,24]]]]),T_=_(m('<div class="bg-surface rounded p-2 text-sm text-muted"> </div>'),Ce[u],[[390,20]]),E_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[395,24]]),S_=_(m('<i class="fa-solid fa-magnifying-glass mr-1"></i> ',1),Ce[u],[[397,24]]),C_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-download mr-1"></i> </h3> <div class="space-y-3"><!> <!> <button class="btn-secondary text-sm"><!></button></div></div>'),Ce[u],[[346,8,[[347,12,[[348,16]]],[350,12,[[393,16]]]]]]),A_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[418,28]]),P_=_(m('<i class="fa-solid fa-download mr-1"></i> ',1),Ce[u],[[420,28]]),j_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Ce[u],[[434,28]]),O_=_(m('<i class="fa-solid fa-upload mr-1"></i> ',1),Ce[u],[[436,28]]),L_=_(m('<div class="bg-surface rounded p-2 text-sm text-muted mt-2"> </div>'),Ce[u],[[441,24]]),R_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-box-archive mr-1"></i> </h3> <div class="space-y-4"><div><h4 class="text-sm font-medium mb-2"> </h4> <p class="text-xs text-muted mb-2">Download a JSON export of all agent data (memory, activity, goals, cron jobs, stats).</p> <button class="btn-primary text-sm"><!></button></div> <hr class="border-border"/> <div><h4 class="text-sm font-medium mb-2"> </h4> <p class="text-xs text-muted mb-2">Upload a previously exported backup file. Existing data will be merged (INSERT OR REPLACE).</p> <label><!> <input type="file" accept=".json" class="hidden"/></label> <!></div></div></div>'),Ce[u],[[406,8,[[407,12,[[408,16]]],[410,12,[[411,16,[[412,20],[413,20],[416,20]]],[425,16],[427,16,[[428,20],[429,20],[432,20,[[438,24]]]]]]]]]]),M_=_(m('<p class="text-muted"> </p>'),Ce[u],[[456,16]]),N_=_(m('<span class="text-sm text-muted">Node:</span> <span class="font-mono text-sm text-fg"> </span> <span class="text-xs text-muted font-mono"> </span>',1),Ce[u],[[464,28],[465,28],[466,28]]),I_=_(m('<p class="text-sm text-muted"> </p>'),Ce[u],[[471,24]]),D_=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i>'),Ce[u],[[488,36]]),F_=_(m('<i class="fa-solid fa-plus mr-1"></i>'),Ce[u],[[490,36]]),U_=_(m('<div class="text-sm text-muted"> </div>'),Ce[u],[[496,28]]),z_=_(m('<div class="bg-surface rounded p-3 flex items-center justify-between"><div><span class="font-mono text-sm text-fg"> </span> <span class="text-xs text-muted ml-2"> </span> <span> </span> <span class="text-xs text-muted ml-2"> </span></div> <button class="text-red-400 hover:text-red-300 text-sm"><i class="fa-solid fa-xmark"></i></button></div>'),Ce[u],[[504,36,[[505,40,[[506,44],[507,44],[508,44],[511,44]]],[513,40,[[514,44]]]]]]),B_=_(m('<div class="space-y-2"></div>'),Ce[u],[[502,28]]),q_=_(m('<p class="text-sm text-muted"> </p>'),Ce[u],[[520,28]]),G_=_(m('<div class="flex gap-2 items-end"><div class="flex-1"><label class="text-xs text-muted block mb-1"> </label> <input type="text" class="w-full bg-surface border border-border rounded px-3 py-1.5 text-sm font-mono"/></div> <button class="btn-primary text-sm"><!> </button></div> <!> <h4 class="text-sm font-medium"> </h4> <!>',1),Ce[u],[[476,24,[[477,28,[[478,32],[479,32]]],[486,28]]],[500,24]]),V_=_(m('<div class="space-y-4"><div class="flex items-center gap-3"><span> </span> <!></div> <!> <button class="btn-secondary text-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div>'),Ce[u],[[458,16,[[459,20,[[460,24]]],[524,20,[[525,24]]]]]]),H_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-network-wired mr-1"></i> </h3> <!></div>'),Ce[u],[[450,8,[[451,12,[[452,16]]]]]]),K_=_(m('<p class="text-muted"> </p>'),Ce[u],[[540,16]]),W_=_(m('<div><i></i> <span class="font-mono text-sm"> </span></div>'),Ce[u],[[552,28,[[553,32],[554,32]]]]),Y_=_(m('<div class="space-y-3"><div class="flex items-center gap-3"><span class="text-sm text-muted"> </span> <span class="bg-accent/20 text-accent px-2 py-1 rounded font-mono text-sm"> </span> <span class="text-sm text-muted"> </span></div> <h4 class="text-sm font-medium mt-3"> </h4> <div class="grid grid-cols-2 sm:grid-cols-3 gap-2"></div> <p class="text-xs text-muted mt-2"> </p> <button class="btn-secondary text-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div>'),Ce[u],[[542,16,[[543,20,[[544,24],[545,24],[546,24]]],[549,20],[550,20],[559,20],[563,20,[[564,24]]]]]]),J_=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-brain mr-1"></i> </h3> <!></div>'),Ce[u],[[534,8,[[535,12,[[536,16]]]]]]),Q_=_(m('<p class="text-muted"> </p>'),Ce[u],[[579,16]]),X_=_(m('<div class="font-mono text-fg"> </div> <div class="text-xs text-muted truncate"> </div>',1),Ce[u],[[595,28],[596,28]]),Z_=_(m('<div class="font-mono text-muted"> </div>'),Ce[u],[[598,28]]),em=_(m('<div class="text-xs text-green-400"> </div>'),Ce[u],[[605,28]]),tm=_(m('<div class="grid grid-cols-2 sm:grid-cols-4 gap-3"><div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div> <div class="text-xs text-muted"> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div> <div class="text-xs text-muted truncate"> </div></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <!></div> <div class="bg-surface rounded p-3"><div class="text-xs text-muted mb-1"> </div> <div class="font-mono text-fg"> </div> <!></div></div>'),Ce[u],[[581,16,[[582,20,[[583,24],[584,24],[585,24]]],[587,20,[[588,24],[589,24],[590,24]]],[592,20,[[593,24]]],[601,20,[[602,24],[603,24]]]]]]),am=_(m('<button class="btn-primary text-sm"><i class="fa-solid fa-wand-magic-sparkles mr-1"></i> </button>'),Ce[u],[[610,16,[[611,20]]]]),rm=_(m('<p class="text-muted"> </p>'),Ce[u],[[622,16]]),sm=_(m('<span class="text-xs text-muted"> </span>'),Ce[u],[[628,24]]),im=_(m('<span class="bg-surface px-2 py-1 rounded text-xs font-mono"> </span>'),Ce[u],[[634,28]]),om=_(m('<div class="flex flex-wrap gap-2"></div>'),Ce[u],[[632,20]]),nm=_(m('<p class="text-xs text-muted mt-2"> </p>'),Ce[u],[[639,20]]),lm=_(m('<div class="flex items-center gap-3 mb-3"><span></span> <span class="text-sm"> </span> <!></div> <!> <!>',1),Ce[u],[[624,16,[[625,20],[626,20]]]]),dm=_(m('<p class="text-muted text-sm"> </p>'),Ce[u],[[642,16]]),cm=_(m('<div class="bg-green-500/10 border border-green-500/30 rounded p-3 text-sm text-green-400"> </div>'),Ce[u],[[647,12]]),vm=_(m('<p class="text-muted"> </p>'),Ce[u],[[679,20]]),um=_(m('<button class="bg-accent/20 text-accent px-2 py-1 rounded text-xs hover:bg-accent/30 transition-colors"> </button>'),Ce[u],[[726,48]]),fm=_(m('<span class="text-xs text-muted"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </span>'),Ce[u],[[734,52,[[735,56]]]]),pm=_(m('<button class="bg-surface text-fg px-2 py-1 rounded text-xs hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-download mr-1"></i> </button>'),Ce[u],[[738,52,[[742,56]]]]),_m=_(m('<span class="text-xs text-muted">—</span>'),Ce[u],[[746,48]]),mm=_(m('<tr class="border-b border-border/30 hover:bg-surface/50"><td class="py-2 pr-3"><div class="font-mono text-fg"> </div> <div class="text-xs text-muted"> </div></td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2 pr-3"><div class="flex items-center gap-1"><div class="w-16 h-2 bg-surface rounded-full overflow-hidden"><div></div></div> <span class="text-xs font-mono"> </span></div></td><td class="py-2 pr-3"><span> </span></td><td class="py-2 pr-3 text-xs"> </td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2 pr-3 font-mono text-xs"> </td><td class="py-2"><!></td></tr>'),Ce[u],[[698,36,[[699,40,[[700,44],[701,44]]],[703,40],[704,40,[[705,44,[[706,48,[[707,52]]],[712,48]]]]],[715,40,[[716,44]]],[720,40],[721,40],[722,40],[723,40],[724,40]]]]),xm=_(m('<div class="overflow-x-auto"><table class="w-full text-sm"><thead><tr class="text-left text-xs text-muted border-b border-border"><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2 pr-3"> </th><th class="pb-2"> </th></tr></thead><tbody></tbody></table></div>'),Ce[u],[[681,20,[[682,24,[[683,28,[[684,32,[[685,36],[686,36],[687,36],[688,36],[689,36],[690,36],[691,36],[692,36],[693,36]]]]],[696,28]]]]]]),bm=_(m('<p class="text-muted text-sm"> </p>'),Ce[u],[[755,20]]),gm=_(m('<div class="card"><div class="flex items-center justify-between mb-3 flex-wrap gap-2"><h3 class="text-lg font-semibold"><i class="fa-solid fa-ranking-star mr-1"></i> </h3> <div class="flex items-center gap-2"><select class="bg-surface border border-border rounded px-2 py-1 text-sm"><option> </option><option> </option><option> </option><option> </option><option> </option><option> </option></select> <button class="btn-secondary text-sm"><i class="fa-solid fa-rotate mr-1"></i> </button></div></div> <!></div>'),Ce[u],[[654,12,[[655,16,[[656,20,[[657,24]]],[659,20,[[660,24,[[665,28],[666,28],[667,28],[668,28],[669,28],[670,28]]],[672,24,[[673,28]]]]]]]]]]),hm=_(m('<div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-microchip mr-1"></i> </h3> <!></div> <div class="card"><h3 class="text-lg font-semibold mb-3"><i class="fa-solid fa-server mr-1"></i> </h3> <!></div> <!> <!>',1),Ce[u],[[574,8,[[575,12,[[576,16]]]]],[617,8,[[618,12,[[619,16]]]]]]),ym=_(m('<div class="space-y-4"><div class="flex gap-2 mb-4 flex-wrap"></div> <!> <!> <!> <!> <!> <!></div>'),Ce[u],[[268,0,[[269,4]]]]);function Ce(r,i){wa(new.target),ga(i,!0,Ce);let o=S(P("health"),"activeSection"),d=S(P(null),"health"),c=S(P(!0),"healthLoading"),f=S(P(null),"updateInfo"),b=S(P(!1),"updateLoading"),h=S(P(!1),"updateApplying"),w=S(P(""),"updateMessage"),L=S(P(!1),"backupLoading"),j=S(P(!1),"restoreLoading"),A=S(P(""),"restoreMessage"),k=S(P(null),"fedStatus"),q=S(P(!0),"fedLoading"),V=S(P(""),"addPeerAddress"),X=S(P(!1),"addPeerLoading"),N=S(P(""),"addPeerMessage"),ne=S(P(null),"backends"),je=S(P(!0),"backendsLoading"),we=S(P(null),"systemSpecs"),Te=S(P(!1),"specsLoading"),Ke=S(P(Jt([])),"recommendations"),Ee=S(P(!1),"recsLoading"),te=S(P(""),"useCaseFilter"),_e=S(P(null),"ollamaStatus"),Ve=S(P(!1),"ollamaLoading"),K=S(P(""),"pullingModel"),W=S(P(""),"pullStatus"),Ue=S(P(""),"configureMsg");async function Ne(){p(c,!0);try{const I=(await M(fetch("/healthz")))();p(d,(await M(I.json()))(),!0)}catch{p(d,{status:"error",checks:{}},!0)}p(c,!1)}async function Pe(){p(b,!0),p(w,"");try{const I=(await M(fetch("/api/update/check")))();p(f,(await M(I.json()))(),!0)}catch{p(w,"Failed to check for updates")}p(b,!1)}async function R(){p(h,!0),p(w,"");try{const I=(await M(fetch("/api/update/apply",{method:"POST"})))(),ae=(await M(I.json()))();p(w,ae.message||(ae.ok?"Update applied":"Update failed"),!0)}catch{p(w,"Failed to apply update")}p(h,!1)}async function ie(){p(L,!0);try{const I=(await M(fetch("/api/backup")))(),ae=(await M(I.blob()))(),G=URL.createObjectURL(ae),ee=document.createElement("a");ee.href=G,ee.download=`safe-agent-backup-${new Date().toISOString().slice(0,10)}.json`,ee.click(),URL.revokeObjectURL(G)}catch(I){console.error(...Xt("error","Backup download failed",I))}p(L,!1)}async function qe(I){var G;const ae=I.target;if((G=ae.files)!=null&&G.length){p(j,!0),p(A,"");try{const ee=(await M(ae.files[0].text()))(),re=JSON.parse(ee),ke=(await M(fetch("/api/restore",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify(re)})))(),ve=(await M(ke.json()))();p(A,ve.message||(ve.ok?"Restore complete":"Restore failed"),!0)}catch{p(A,"Failed to restore backup")}p(j,!1),ae.value=""}}async function Se(){p(q,!0);try{const I=(await M(fetch("/api/federation/status")))();p(k,(await M(I.json()))(),!0)}catch{p(k,{enabled:!1,peers:[]},!0)}p(q,!1)}async function me(){if(e(V).trim()){p(X,!0),p(N,"");try{const I=(await M(fetch("/api/federation/peers",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({address:e(V)})})))(),ae=(await M(I.json()))();p(N,ae.message||(ae.ok?"Peer added":"Failed"),!0),ae.ok&&(p(V,""),Se())}catch{p(N,"Failed to add peer")}p(X,!1)}}async function $(I){try{(await M(fetch(`/api/federation/peers/${I}`,{method:"DELETE"})))(),Se()}catch(ae){console.error(...Xt("error","Failed to remove peer",ae))}}async function z(){p(je,!0);try{const I=(await M(fetch("/api/llm/backends")))();p(ne,(await M(I.json()))(),!0)}catch{p(ne,{active:"unknown",available:[]},!0)}p(je,!1)}async function Z(){p(Te,!0);try{const I=(await M(fetch("/api/llm/advisor/system")))();p(we,(await M(I.json()))(),!0)}catch{p(we,null)}p(Te,!1)}async function H(){p(Ee,!0);try{const I=new URLSearchParams;e(te)&&I.set("use_case",e(te)),I.set("limit","20");const ae=(await M(fetch(`/api/llm/advisor/recommend?${I}`)))(),G=(await M(ae.json()))();p(Ke,G.models||[],!0)}catch{p(Ke,[],!0)}p(Ee,!1)}async function Le(){p(Ve,!0);try{const I=(await M(fetch("/api/llm/ollama/status")))();p(_e,(await M(I.json()))(),!0)}catch{p(_e,{available:!1,installed_models:[]},!0)}p(Ve,!1)}async function O(I){p(K,I,!0),p(W,l("ops.advisor_pulling"),!0);try{const ae=(await M(fetch("/api/llm/ollama/pull",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({tag:I})})))(),G=(await M(ae.json()))();G.ok?(p(W,l("ops.advisor_pull_complete"),!0),Le(),H()):p(W,G.error||"Pull failed",!0)}catch{p(W,"Pull failed")}setTimeout(()=>{p(K,""),p(W,"")},3e3)}async function B(I){p(Ue,"");try{const ae=(await M(fetch("/api/llm/ollama/configure",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({model:I})})))(),G=(await M(ae.json()))();p(Ue,G.ok?l("ops.advisor_configured",{model:I}):G.error||"Failed",!0),z()}catch{p(Ue,"Configuration failed")}setTimeout(()=>{p(Ue,"")},5e3)}Es(()=>{Ne(),Se(),z()});const ue=[{id:"health",label:l("ops.health"),icon:"fa-heart-pulse"},{id:"update",label:l("ops.updates"),icon:"fa-download"},{id:"backup",label:l("ops.backup"),icon:"fa-box-archive"},{id:"federation",label:l("ops.federation"),icon:"fa-network-wired"},{id:"backends",label:l("ops.llm_backends"),icon:"fa-brain"},{id:"advisor",label:l("ops.model_advisor"),icon:"fa-microchip"}];var D={...ka()},T=ym(),E=a(T);g(()=>Dt(E,21,()=>ue,la,(I,ae)=>{var G=u_(),ee=a(G),re=s(ee);x(()=>{Qe(G,1,`px-3 py-1.5 rounded text-sm transition-colors ${C(e(o),e(ae).id)?"bg-accent text-white":"bg-surface text-muted hover:text-fg"}`),Qe(ee,1,`fa-solid ${e(ae).icon??""} mr-1`),n(re,` ${e(ae).label??""}`)}),Y("click",G,function(){return p(o,e(ae).id,!0)}),v(I,G)}),"each",Ce,270,8);var U=s(E,2);{var J=I=>{var ae=m_(),G=Ae(ae),ee=a(G),re=s(a(ee)),ke=s(ee,2);{var ve=be=>{var ce=f_(),pe=a(ce);x(Oe=>n(pe,Oe),[()=>l("ops.checking_health")]),v(be,ce)},Q=be=>{var ce=__(),pe=a(ce),Oe=a(pe),Me=a(Oe),Ze=s(Oe,2),Be=a(Ze),We=s(pe,2);{var it=Fe=>{var ut=p_(),mt=a(ut),Tt=a(mt),xt=a(Tt),se=s(Tt,2),ye=a(se),et=s(mt,2),dt=a(et),tt=a(dt),Ye=s(dt,2),at=a(Ye),ft=s(et,2),wt=a(ft),Ge=a(wt),bt=s(wt,2),pt=a(bt);x((vt,Pt,Et)=>{n(xt,vt),Qe(se,1,`font-mono ${C(e(d).checks.database,"ok")?"text-green-400":"text-red-400"}`),n(ye,e(d).checks.database),n(tt,Pt),Qe(Ye,1,`font-mono ${C(e(d).checks.agent,"running")?"text-green-400":"text-yellow-400"}`),n(at,e(d).checks.agent),n(Ge,Et),n(pt,e(d).checks.tools)},[()=>l("ops.database"),()=>l("ops.agent"),()=>l("ops.tools")]),v(Fe,ut)};g(()=>y(We,Fe=>{e(d).checks&&Fe(it)}),"if",Ce,297,20)}var ot=s(We,2),rt=s(a(ot));x((Fe,ut)=>{Qe(Oe,1,`px-3 py-1 rounded text-sm font-mono font-bold ${C(e(d).status,"healthy")?"bg-green-500/20 text-green-400":"bg-red-500/20 text-red-400"}`),n(Me,Fe),n(Be,`v${e(d).version??""}`),n(rt,` ${ut??""}`)},[()=>{var Fe;return(Fe=e(d).status)==null?void 0:Fe.toUpperCase()},()=>l("common.refresh")]),Y("click",ot,Ne),v(be,ce)};g(()=>y(ke,be=>{e(c)?be(ve):e(d)&&be(Q,1)}),"if",Ce,286,12)}var $e=s(G,2),Ie=a($e),Re=s(a(Ie)),Ct=s(Ie,2),st=a(Ct),De=s(Ct,2),lt=a(De),Je=s(De,2),_t=a(Je),ze=s(a(_t));x((be,ce,pe,Oe,Me)=>{n(re,` ${be??""}`),n(Re,` ${ce??""}`),n(st,pe),n(lt,Oe),n(ze,` ${Me??""}`)},[()=>l("ops.health_check"),()=>l("ops.prometheus"),()=>l("ops.prometheus_desc"),()=>l("ops.prometheus_hint"),()=>l("ops.view_raw_metrics")]),Y("click",_t,function(){return window.open("/metrics","_blank")}),v(I,ae)};g(()=>y(U,I=>{C(e(o),"health")&&I(J)}),"if",Ce,281,4)}var xe=s(U,2);{var fe=I=>{var ae=C_(),G=a(ae),ee=s(a(G)),re=s(G,2),ke=a(re);{var ve=De=>{var lt=$_(),Je=Ae(lt),_t=a(Je),ze=a(_t),be=s(_t,2),ce=a(be),pe=s(be,2);{var Oe=ot=>{var rt=x_(),Fe=s(Ae(rt),2),ut=a(Fe),mt=s(Fe,2),Tt=a(mt);x(xt=>{n(ut,`v${e(f).latest_version??""}`),n(Tt,xt)},[()=>l("ops.update_available")]),v(ot,rt)},Me=ot=>{var rt=b_(),Fe=a(rt);x(ut=>n(Fe,ut),[()=>l("ops.up_to_date")]),v(ot,rt)};g(()=>y(pe,ot=>{e(f).update_available?ot(Oe):ot(Me,!1)}),"if",Ce,355,24)}var Ze=s(Je,2);{var Be=ot=>{var rt=g_(),Fe=a(rt),ut=a(Fe),mt=s(Fe,2),Tt=a(mt);x(xt=>{n(ut,xt),n(Tt,e(f).release_notes)},[()=>l("ops.release_notes")]),v(ot,rt)};g(()=>y(Ze,ot=>{e(f).update_available&&e(f).release_notes&&ot(Be)}),"if",Ce,364,20)}var We=s(Ze,2);{var it=ot=>{var rt=k_(),Fe=a(rt),ut=a(Fe);{var mt=ye=>{var et=h_(),dt=s(Ae(et));x(tt=>n(dt,` ${tt??""}`),[()=>l("ops.applying")]),v(ye,et)},Tt=ye=>{var et=y_(),dt=s(Ae(et));x(tt=>n(dt,` ${tt??""}`),[()=>l("ops.apply_update")]),v(ye,et)};g(()=>y(ut,ye=>{e(h)?ye(mt):ye(Tt,!1)}),"if",Ce,374,32)}var xt=s(Fe,2);{var se=ye=>{var et=w_(),dt=s(a(et));x(tt=>{St(et,"href",e(f).release_url),n(dt,` ${tt??""}`)},[()=>l("ops.view_release")]),v(ye,et)};g(()=>y(xt,ye=>{e(f).release_url&&ye(se)}),"if",Ce,380,28)}x(()=>Fe.disabled=e(h)),Y("click",Fe,R),v(ot,rt)};g(()=>y(We,ot=>{e(f).update_available&&ot(it)}),"if",Ce,371,20)}x(ot=>{n(ze,ot),n(ce,`v${e(f).current_version??""}`)},[()=>l("ops.current_version")]),v(De,lt)};g(()=>y(ke,De=>{e(f)&&De(ve)}),"if",Ce,351,16)}var Q=s(ke,2);{var $e=De=>{var lt=T_(),Je=a(lt);x(()=>n(Je,e(w))),v(De,lt)};g(()=>y(Q,De=>{e(w)&&De($e)}),"if",Ce,389,16)}var Ie=s(Q,2),Re=a(Ie);{var Ct=De=>{var lt=E_(),Je=s(Ae(lt));x(_t=>n(Je,` ${_t??""}`),[()=>l("ops.checking")]),v(De,lt)},st=De=>{var lt=S_(),Je=s(Ae(lt));x(_t=>n(Je,` ${_t??""}`),[()=>l("ops.check_updates")]),v(De,lt)};g(()=>y(Re,De=>{e(b)?De(Ct):De(st,!1)}),"if",Ce,394,20)}x(De=>{n(ee,` ${De??""}`),Ie.disabled=e(b)},[()=>l("ops.auto_update")]),Y("click",Ie,Pe),v(I,ae)};g(()=>y(xe,I=>{C(e(o),"update")&&I(fe)}),"if",Ce,345,4)}var F=s(xe,2);{var he=I=>{var ae=R_(),G=a(ae),ee=s(a(G)),re=s(G,2),ke=a(re),ve=a(ke),Q=a(ve),$e=s(ve,4),Ie=a($e);{var Re=Me=>{var Ze=A_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.exporting")]),v(Me,Ze)},Ct=Me=>{var Ze=P_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.download_backup")]),v(Me,Ze)};g(()=>y(Ie,Me=>{e(L)?Me(Re):Me(Ct,!1)}),"if",Ce,417,24)}var st=s(ke,4),De=a(st),lt=a(De),Je=s(De,4),_t=a(Je);{var ze=Me=>{var Ze=j_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.restoring")]),v(Me,Ze)},be=Me=>{var Ze=O_(),Be=s(Ae(Ze));x(We=>n(Be,` ${We??""}`),[()=>l("ops.upload_backup")]),v(Me,Ze)};g(()=>y(_t,Me=>{e(j)?Me(ze):Me(be,!1)}),"if",Ce,433,24)}var ce=s(_t,2),pe=s(Je,2);{var Oe=Me=>{var Ze=L_(),Be=a(Ze);x(()=>n(Be,e(A))),v(Me,Ze)};g(()=>y(pe,Me=>{e(A)&&Me(Oe)}),"if",Ce,440,20)}x((Me,Ze,Be)=>{n(ee,` ${Me??""}`),n(Q,Ze),$e.disabled=e(L),n(lt,Be),Qe(Je,1,`btn-secondary text-sm inline-flex items-center cursor-pointer ${e(j)?"opacity-50":""}`),ce.disabled=e(j)},[()=>l("ops.backup_restore"),()=>l("ops.export_backup"),()=>l("ops.restore_backup")]),Y("click",$e,ie),Y("change",ce,qe),v(I,ae)};g(()=>y(F,I=>{C(e(o),"backup")&&I(he)}),"if",Ce,405,4)}var Xe=s(F,2);{var yt=I=>{var ae=H_(),G=a(ae),ee=s(a(G)),re=s(G,2);{var ke=Q=>{var $e=M_(),Ie=a($e);x(Re=>n(Ie,Re),[()=>l("ops.federation_loading")]),v(Q,$e)},ve=Q=>{var $e=V_(),Ie=a($e),Re=a(Ie),Ct=a(Re),st=s(Re,2);{var De=ce=>{var pe=N_(),Oe=s(Ae(pe),2),Me=a(Oe),Ze=s(Oe,2),Be=a(Ze);x(We=>{n(Me,e(k).node.name),n(Be,`(${We??""}...)`)},[()=>e(k).node.node_id.slice(0,8)]),v(ce,pe)};g(()=>y(st,ce=>{e(k).node&&ce(De)}),"if",Ce,463,24)}var lt=s(Ie,2);{var Je=ce=>{var pe=I_(),Oe=a(pe);x(Me=>n(Oe,Me),[()=>l("ops.federation_disabled")]),v(ce,pe)},_t=ce=>{var pe=G_(),Oe=Ae(pe),Me=a(Oe),Ze=a(Me),Be=a(Ze),We=s(Ze,2),it=s(Me,2),ot=a(it);{var rt=tt=>{var Ye=D_();v(tt,Ye)},Fe=tt=>{var Ye=F_();v(tt,Ye)};g(()=>y(ot,tt=>{e(X)?tt(rt):tt(Fe,!1)}),"if",Ce,487,32)}var ut=s(ot),mt=s(Oe,2);{var Tt=tt=>{var Ye=U_(),at=a(Ye);x(()=>n(at,e(N))),v(tt,Ye)};g(()=>y(mt,tt=>{e(N)&&tt(Tt)}),"if",Ce,495,24)}var xt=s(mt,2),se=a(xt),ye=s(xt,2);{var et=tt=>{var Ye=B_();g(()=>Dt(Ye,21,()=>e(k).peers,la,(at,ft)=>{var wt=z_(),Ge=a(wt),bt=a(Ge),pt=a(bt,!0);var vt=s(bt,2),Pt=a(vt,!0);var Et=s(vt,2),jt=a(Et,!0);var Ft=s(Et,2),Ht=a(Ft);var Ut=s(Ge,2);x(Kt=>{n(pt,e(ft).name),n(Pt,e(ft).address),Qe(Et,1,`ml-2 px-1.5 py-0.5 rounded text-xs ${C(e(ft).status,"online")?"bg-green-500/20 text-green-400":"bg-red-500/20 text-red-400"}`),n(jt,e(ft).status),n(Ht,`v${e(ft).version??""}`),St(Ut,"title",Kt)},[()=>l("ops.remove_peer_title")]),Y("click",Ut,function(){return $(e(ft).node_id)}),v(at,wt)}),"each",Ce,503,32),v(tt,Ye)},dt=tt=>{var Ye=q_(),at=a(Ye);x(ft=>n(at,ft),[()=>l("ops.no_peers")]),v(tt,Ye)};g(()=>y(ye,tt=>{var Ye;(Ye=e(k).peers)!=null&&Ye.length?tt(et):tt(dt,!1)}),"if",Ce,501,24)}x((tt,Ye,at,ft)=>{n(Be,tt),St(We,"placeholder",Ye),it.disabled=e(X),n(ut,` ${at??""}`),n(se,ft)},[()=>l("ops.add_peer"),()=>l("ops.peer_placeholder"),()=>l("ops.add"),()=>l("ops.peers_count",{count:e(k).peer_count||0})]),ua(We,function(){return e(V)},function(Ye){p(V,Ye)}),Y("click",it,me),v(ce,pe)};g(()=>y(lt,ce=>{e(k).enabled?ce(_t,!1):ce(Je)}),"if",Ce,470,20)}var ze=s(lt,2),be=s(a(ze));x(ce=>{Qe(Re,1,`px-2 py-1 rounded text-xs font-semibold ${e(k).enabled?"bg-green-500/20 text-green-400":"bg-surface text-muted"}`),n(Ct,e(k).enabled?"ENABLED":"DISABLED"),n(be,` ${ce??""}`)},[()=>l("common.refresh")]),Y("click",ze,Se),v(Q,$e)};g(()=>y(re,Q=>{e(q)?Q(ke):e(k)&&Q(ve,1)}),"if",Ce,455,12)}x(Q=>n(ee,` ${Q??""}`),[()=>l("ops.federation_title")]),v(I,ae)};g(()=>y(Xe,I=>{C(e(o),"federation")&&I(yt)}),"if",Ce,449,4)}var ht=s(Xe,2);{var ct=I=>{var ae=J_(),G=a(ae),ee=s(a(G)),re=s(G,2);{var ke=Q=>{var $e=K_(),Ie=a($e);x(Re=>n(Ie,Re),[()=>l("ops.loading_backends")]),v(Q,$e)},ve=Q=>{var $e=Y_(),Ie=a($e),Re=a(Ie),Ct=a(Re),st=s(Re,2),De=a(st),lt=s(st,2),Je=a(lt),_t=s(Ie,2),ze=a(_t),be=s(_t,2);g(()=>Dt(be,21,()=>e(ne).available,la,(Ze,Be)=>{var We=W_(),it=a(We),ot=s(it,2),rt=a(ot,!0);x(()=>{Qe(We,1,`bg-surface rounded p-3 flex items-center gap-2 ${C(e(Be),e(ne).active)?"ring-1 ring-accent":""}`),Qe(it,1,`fa-solid ${C(e(Be),e(ne).active)?"fa-circle-check text-accent":"fa-circle text-muted"} text-xs`),n(rt,e(Be))}),v(Ze,We)}),"each",Ce,551,24);var ce=s(be,2),pe=a(ce),Oe=s(ce,2),Me=s(a(Oe));x((Ze,Be,We,it)=>{n(Ct,Ze),n(De,e(ne).active),n(Je,`(${e(ne).active_info??""})`),n(ze,Be),n(pe,We),n(Me,` ${it??""}`)},[()=>l("ops.active_backend"),()=>l("ops.available_backends"),()=>l("ops.switch_backend_hint"),()=>l("common.refresh")]),Y("click",Oe,z),v(Q,$e)};g(()=>y(re,Q=>{e(je)?Q(ke):e(ne)&&Q(ve,1)}),"if",Ce,539,12)}x(Q=>n(ee,` ${Q??""}`),[()=>l("ops.llm_plugins")]),v(I,ae)};g(()=>y(ht,I=>{C(e(o),"backends")&&I(ct)}),"if",Ce,533,4)}var oe=s(ht,2);{var le=I=>{var ae=hm(),G=Ae(ae),ee=a(G),re=s(a(ee)),ke=s(ee,2);{var ve=pe=>{var Oe=Q_(),Me=a(Oe);x(Ze=>n(Me,Ze),[()=>l("common.loading")]),v(pe,Oe)},Q=pe=>{var Oe=tm(),Me=a(Oe),Ze=a(Me),Be=a(Ze),We=s(Ze,2),it=a(We),ot=s(We,2),rt=a(ot),Fe=s(Me,2),ut=a(Fe),mt=a(ut),Tt=s(ut,2),xt=a(Tt),se=s(Tt,2),ye=a(se),et=s(Fe,2),dt=a(et),tt=a(dt),Ye=s(dt,2);{var at=jt=>{var Ft=X_(),Ht=Ae(Ft),Ut=a(Ht),Kt=s(Ht,2),na=a(Kt);x(ta=>{n(Ut,`${ta??""} GB VRAM`),St(Kt,"title",e(we).gpu_name),n(na,`${(e(we).gpu_name||"GPU")??""}${e(we).gpu_count>1?` (×${e(we).gpu_count})`:""}`)},[()=>{var ta;return((ta=e(we).gpu_vram_gb)==null?void 0:ta.toFixed(1))??"?"}]),v(jt,Ft)},ft=jt=>{var Ft=Z_(),Ht=a(Ft);x(Ut=>n(Ht,Ut),[()=>l("ops.advisor_no_gpu")]),v(jt,Ft)};g(()=>y(Ye,jt=>{e(we).has_gpu?jt(at):jt(ft,!1)}),"if",Ce,594,24)}var wt=s(et,2),Ge=a(wt),bt=a(Ge),pt=s(Ge,2),vt=a(pt),Pt=s(pt,2);{var Et=jt=>{var Ft=em(),Ht=a(Ft);x(Ut=>n(Ht,Ut),[()=>l("ops.advisor_unified")]),v(jt,Ft)};g(()=>y(Pt,jt=>{e(we).unified_memory&&jt(Et)}),"if",Ce,604,24)}x((jt,Ft,Ht,Ut,Kt,na,ta,Ea)=>{n(Be,jt),n(it,`${Ft??""} GB`),n(rt,`${Ht??""} GB ${Ut??""}`),n(mt,Kt),n(xt,`${e(we).cpu_cores??""} ${na??""}`),St(se,"title",e(we).cpu_name),n(ye,e(we).cpu_name),n(tt,ta),n(bt,Ea),n(vt,e(we).backend)},[()=>l("ops.advisor_ram"),()=>{var jt;return(jt=e(we).total_ram_gb)==null?void 0:jt.toFixed(1)},()=>{var jt;return(jt=e(we).available_ram_gb)==null?void 0:jt.toFixed(1)},()=>l("ops.advisor_available"),()=>l("ops.advisor_cpu"),()=>l("ops.advisor_cores"),()=>l("ops.advisor_gpu"),()=>l("ops.advisor_backend_accel")]),v(pe,Oe)},$e=pe=>{var Oe=am(),Me=s(a(Oe));x(Ze=>n(Me,` ${Ze??""}`),[()=>l("ops.advisor_detect")]),Y("click",Oe,function(){Z(),H(),Le()}),v(pe,Oe)};g(()=>y(ke,pe=>{e(Te)?pe(ve):e(we)?pe(Q,1):pe($e,!1)}),"if",Ce,578,12)}var Ie=s(G,2),Re=a(Ie),Ct=s(a(Re)),st=s(Re,2);{var De=pe=>{var Oe=rm(),Me=a(Oe);x(Ze=>n(Me,Ze),[()=>l("common.loading")]),v(pe,Oe)},lt=pe=>{var Oe=lm(),Me=Ae(Oe),Ze=a(Me),Be=s(Ze,2),We=a(Be),it=s(Be,2);{var ot=Tt=>{var xt=sm(),se=a(xt);x(ye=>n(se,`(${e(_e).installed_models.length??""} ${ye??""})`),[()=>l("ops.advisor_models_installed")]),v(Tt,xt)};g(()=>y(it,Tt=>{var xt;e(_e).available&&((xt=e(_e).installed_models)!=null&&xt.length)&&Tt(ot)}),"if",Ce,627,20)}var rt=s(Me,2);{var Fe=Tt=>{var xt=om();g(()=>Dt(xt,21,()=>e(_e).installed_models,la,(se,ye)=>{var et=im(),dt=a(et,!0);x(()=>n(dt,e(ye))),v(se,et)}),"each",Ce,633,24),v(Tt,xt)};g(()=>y(rt,Tt=>{var xt;e(_e).available&&((xt=e(_e).installed_models)!=null&&xt.length)&&Tt(Fe)}),"if",Ce,631,16)}var ut=s(rt,2);{var mt=Tt=>{var xt=nm(),se=a(xt);x(ye=>n(se,ye),[()=>l("ops.ollama_install_hint")]),v(Tt,xt)};g(()=>y(ut,Tt=>{e(_e).available||Tt(mt)}),"if",Ce,638,16)}x(Tt=>{Qe(Ze,1,`w-3 h-3 rounded-full ${e(_e).available?"bg-green-500":"bg-red-500"}`),n(We,Tt)},[()=>e(_e).available?l("ops.ollama_running"):l("ops.ollama_not_running")]),v(pe,Oe)},Je=pe=>{var Oe=dm(),Me=a(Oe);x(Ze=>n(Me,Ze),[()=>l("ops.advisor_detect_first")]),v(pe,Oe)};g(()=>y(st,pe=>{e(Ve)?pe(De):e(_e)?pe(lt,1):pe(Je,!1)}),"if",Ce,621,12)}var _t=s(Ie,2);{var ze=pe=>{var Oe=cm(),Me=a(Oe);x(()=>n(Me,e(Ue))),v(pe,Oe)};g(()=>y(_t,pe=>{e(Ue)&&pe(ze)}),"if",Ce,646,8)}var be=s(_t,2);{var ce=pe=>{var Oe=gm(),Me=a(Oe),Ze=a(Me),Be=s(a(Ze)),We=s(Ze,2),it=a(We),ot=a(it),rt=a(ot);ot.value=ot.__value="";var Fe=s(ot),ut=a(Fe);Fe.value=Fe.__value="general";var mt=s(Fe),Tt=a(mt);mt.value=mt.__value="coding";var xt=s(mt),se=a(xt);xt.value=xt.__value="reasoning";var ye=s(xt),et=a(ye);ye.value=ye.__value="chat";var dt=s(ye),tt=a(dt);dt.value=dt.__value="multimodal";var Ye=s(it,2),at=s(a(Ye)),ft=s(Me,2);{var wt=pt=>{var vt=vm(),Pt=a(vt);x(Et=>n(Pt,Et),[()=>l("ops.advisor_analyzing")]),v(pt,vt)},Ge=pt=>{var vt=xm(),Pt=a(vt),Et=a(Pt),jt=a(Et),Ft=a(jt),Ht=a(Ft),Ut=s(Ft),Kt=a(Ut),na=s(Ut),ta=a(na),Ea=s(na),pa=a(Ea),zt=s(Ea),da=a(zt),ia=s(zt),aa=a(ia),$a=s(ia),gt=a($a),Ot=s($a),Lt=a(Ot),Mt=s(Ot),fa=a(Mt),Aa=s(Et);g(()=>Dt(Aa,21,()=>e(Ke),la,(Ra,Zt)=>{var Ya=mm(),vr=a(Ya),sr=a(vr),ir=a(sr,!0);var br=s(sr,2),Er=a(br);var or=s(vr),Dr=a(or);var Za=s(or),qt=a(Za),Wt=a(qt),xa=a(Wt);var ba=s(Wt,2),rs=a(ba,!0);var Sr=s(Za),Cr=a(Sr),Fr=a(Cr,!0);var Ur=s(Sr),Ss=a(Ur,!0);var er=s(Ur),fs=a(er,!0);var zr=s(er),Cs=a(zr,!0);var He=s(zr),nt=a(He);var It=s(He),Gt=a(It);{var Ma=Bt=>{var Ta=um(),tr=a(Ta,!0);x(Br=>n(tr,Br),[()=>l("ops.advisor_use")]),Y("click",Ta,function(){return B(e(Zt).ollama_tag||e(Zt).name)}),v(Bt,Ta)},ur=Bt=>{var Ta=Oa(),tr=Ae(Ta);{var Br=ss=>{var qr=fm(),ki=s(a(qr),1,!0);x(()=>n(ki,e(W))),v(ss,qr)},As=ss=>{var qr=pm(),ki=s(a(qr),1,!0);x($i=>n(ki,$i),[()=>l("ops.advisor_install")]),Y("click",qr,function(){return O(e(Zt).ollama_tag)}),v(ss,qr)};g(()=>y(tr,ss=>{C(e(K),e(Zt).ollama_tag)?ss(Br):ss(As,!1)}),"if",Ce,733,48)}v(Bt,Ta)},Na=Bt=>{var Ta=_m();v(Bt,Ta)};g(()=>y(Gt,Bt=>{var Ta;e(Zt).installed?Bt(Ma):e(Zt).ollama_tag&&((Ta=e(_e))!=null&&Ta.available)?Bt(ur,1):Bt(Na,!1)}),"if",Ce,725,44)}x((Bt,Ta,tr,Br,As)=>{n(ir,e(Zt).name),n(Er,`${e(Zt).provider??""} · ${e(Zt).use_case??""}`),n(Dr,`${Bt??""}B`),Qe(xa,1,`h-full rounded-full ${e(Zt).score>=70?"bg-green-500":e(Zt).score>=40?"bg-yellow-500":"bg-red-500"}`),Ds(xa,`width: ${Ta??""}%`),n(rs,tr),Qe(Cr,1,`px-1.5 py-0.5 rounded text-xs ${C(e(Zt).fit_level,"Perfect")?"bg-green-500/20 text-green-400":C(e(Zt).fit_level,"Good")?"bg-blue-500/20 text-blue-400":"bg-yellow-500/20 text-yellow-400"}`),n(Fr,e(Zt).fit_level),n(Ss,e(Zt).run_mode),n(fs,e(Zt).best_quant),n(Cs,Br),n(nt,`${As??""} GB`)},[()=>{var Bt;return(Bt=e(Zt).params_b)==null?void 0:Bt.toFixed(1)},()=>Math.min(e(Zt).score,100),()=>{var Bt;return(Bt=e(Zt).score)==null?void 0:Bt.toFixed(0)},()=>{var Bt;return(Bt=e(Zt).estimated_tps)==null?void 0:Bt.toFixed(1)},()=>{var Bt;return(Bt=e(Zt).memory_required_gb)==null?void 0:Bt.toFixed(1)}]),v(Ra,Ya)}),"each",Ce,697,32),x((Ra,Zt,Ya,vr,sr,ir,br,Er,or)=>{n(Ht,Ra),n(Kt,Zt),n(ta,Ya),n(pa,vr),n(da,sr),n(aa,ir),n(gt,br),n(Lt,Er),n(fa,or)},[()=>l("ops.advisor_col_name"),()=>l("ops.advisor_col_params"),()=>l("ops.advisor_col_score"),()=>l("ops.advisor_col_fit"),()=>l("ops.advisor_col_mode"),()=>l("ops.advisor_col_quant"),()=>l("ops.advisor_col_tps"),()=>l("ops.advisor_col_mem"),()=>l("ops.advisor_col_action")]),v(pt,vt)},bt=pt=>{var vt=bm(),Pt=a(vt);x(Et=>n(Pt,Et),[()=>l("ops.advisor_no_models")]),v(pt,vt)};g(()=>y(ft,pt=>{e(Ee)?pt(wt):e(Ke).length?pt(Ge,1):pt(bt,!1)}),"if",Ce,678,16)}x((pt,vt,Pt,Et,jt,Ft,Ht,Ut)=>{n(Be,` ${pt??""}`),n(rt,vt),n(ut,Pt),n(Tt,Et),n(se,jt),n(et,Ft),n(tt,Ht),n(at,` ${Ut??""}`)},[()=>l("ops.recommended_models"),()=>l("ops.advisor_all_use_cases"),()=>l("ops.advisor_uc_general"),()=>l("ops.advisor_uc_coding"),()=>l("ops.advisor_uc_reasoning"),()=>l("ops.advisor_uc_chat"),()=>l("ops.advisor_uc_multimodal"),()=>l("common.refresh")]),Y("change",it,function(){return H()}),Gi(it,function(){return e(te)},function(vt){p(te,vt)}),Y("click",Ye,H),v(pe,Oe)};g(()=>y(be,pe=>{e(we)&&pe(ce)}),"if",Ce,653,8)}x((pe,Oe)=>{n(re,` ${pe??""}`),n(Ct,` ${Oe??""}`)},[()=>l("ops.system_specs"),()=>l("ops.ollama_status")]),v(I,ae)};g(()=>y(oe,I=>{C(e(o),"advisor")&&I(le)}),"if",Ce,572,4)}return v(r,T),ha(D)}La(["click","change"]);ya[u]="src/components/UsersPanel.svelte";var wm=_(m('<button class="btn-primary text-sm"><i class="fa-solid fa-plus mr-1"></i> </button>'),ya[u],[[143,12,[[144,16]]]]),km=_(m('<div class="bg-surface rounded p-2 text-sm text-muted mb-3"> </div>'),ya[u],[[150,8]]),$m=_(m('<div class="bg-surface rounded p-4 mb-4 space-y-3"><h4 class="text-sm font-medium"> </h4> <div class="grid grid-cols-1 sm:grid-cols-2 gap-3"><div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="jdoe" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="Jane Doe" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <select class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"><option>Admin</option><option>User</option><option>Viewer</option></select></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="password" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="email" placeholder="jane@example.com" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="123456789" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <div><label class="text-xs text-muted block mb-1"> </label> <input type="text" placeholder="+15551234567" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div></div> <div class="flex gap-2"><button class="btn-primary text-sm"><i class="fa-solid fa-check mr-1"></i> </button> <button class="btn-secondary text-sm"> </button></div></div>'),ya[u],[[155,8,[[156,12],[157,12,[[158,16,[[159,20],[160,20]]],[162,16,[[163,20],[164,20]]],[166,16,[[167,20],[168,20,[[169,24],[170,24],[171,24]]]]],[174,16,[[175,20],[176,20]]],[178,16,[[179,20],[180,20]]],[182,16,[[183,20],[184,20]]],[186,16,[[187,20],[188,20]]]]],[191,12,[[192,16,[[193,20]]],[195,16]]]]]]),Tm=_(m('<p class="text-muted text-sm"> </p>'),ya[u],[[202,8]]),Em=_(m('<p class="text-muted text-sm"> </p>'),ya[u],[[204,8]]),Sm=_(m('<span class="px-1.5 py-0.5 rounded text-xs bg-red-500/20 text-red-400"> </span>'),ya[u],[[219,36]]),Cm=_(m('<span><i class="fa-solid fa-envelope mr-0.5"></i> </span>'),ya[u],[[224,36,[[224,42]]]]),Am=_(m('<span><i class="fa-brands fa-telegram mr-0.5"></i> </span>'),ya[u],[[227,36,[[227,42]]]]),Pm=_(m('<span><i class="fa-brands fa-whatsapp mr-0.5"></i> </span>'),ya[u],[[230,36,[[230,42]]]]),jm=_(m('<div class="flex items-center gap-2 flex-shrink-0"><select class="bg-bg border border-border rounded px-2 py-1 text-xs"><option>Admin</option><option>User</option><option>Viewer</option></select> <button> </button> <button class="text-xs px-2 py-1 rounded bg-red-500/20 text-red-400 hover:bg-red-500/30"><i class="fa-solid fa-trash"></i></button></div>'),ya[u],[[238,24,[[239,28,[[244,32],[245,32],[246,32]]],[248,28],[255,28,[[260,32]]]]]]),Om=_(m('<div class="bg-surface rounded p-3 flex items-center justify-between gap-3 flex-wrap"><div class="flex items-center gap-3 min-w-0"><div class="w-8 h-8 rounded-full bg-accent/20 flex items-center justify-center text-accent font-bold text-sm flex-shrink-0"> </div> <div class="min-w-0"><div class="flex items-center gap-2"><span class="font-medium text-fg truncate"> </span> <span class="text-xs text-muted font-mono"> </span> <span> </span> <!></div> <div class="flex gap-3 text-xs text-muted mt-0.5"><!> <!> <!> <span> </span></div></div></div> <!></div>'),ya[u],[[208,16,[[209,20,[[210,24],[213,24,[[214,28,[[215,32],[216,32],[217,32]]],[222,28,[[232,32]]]]]]]]]]),Lm=_(m('<div class="space-y-2"></div>'),ya[u],[[206,8]]),Rm=_(m('<div class="card"><div class="flex items-center justify-between mb-4"><h3 class="text-lg font-semibold"><i class="fa-solid fa-users mr-1"></i> </h3> <!></div> <!> <!> <!></div>'),ya[u],[[137,0,[[138,4,[[139,8,[[140,12]]]]]]]]);function ya(r,i){wa(new.target),ga(i,!0,ya);let o=S(P(Jt([])),"users"),d=S(P(!0),"loading"),c=S(P(!1),"showCreateForm"),f=S(P(""),"message"),b=S(P(""),"newUsername"),h=S(P(""),"newDisplayName"),w=S(P("user"),"newRole"),L=S(P(""),"newPassword"),j=S(P(""),"newEmail"),A=S(P(""),"newTelegramId"),k=S(P(""),"newWhatsappId");async function q(){p(d,!0);try{const $=(await M(fetch("/api/users")))(),z=(await M($.json()))();p(o,z.users||[],!0)}catch{p(o,[],!0)}p(d,!1)}async function V(){p(f,"");const $={username:e(b),display_name:e(h)||e(b),role:e(w),password:e(L)};e(j)&&($.email=e(j)),e(A)&&($.telegram_id=parseInt(e(A))),e(k)&&($.whatsapp_id=e(k));try{(await M(fetch("/api/users",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify($)})))().ok?(p(f,l("users.created",{username:e(b)}),!0),p(b,""),p(h,""),p(w,"user"),p(L,""),p(j,""),p(A,""),p(k,""),p(c,!1),q()):p(f,l("users.failed_create"),!0)}catch{p(f,l("users.error_create"),!0)}}async function X($){try{(await M(fetch(`/api/users/${$.id}`,{method:"PUT",headers:{"Content-Type":"application/json"},body:JSON.stringify({enabled:!$.enabled})})))(),q()}catch{p(f,l("users.failed_update"),!0)}}async function N($,z){try{(await M(fetch(`/api/users/${$.id}`,{method:"PUT",headers:{"Content-Type":"application/json"},body:JSON.stringify({role:z})})))(),q()}catch{p(f,l("users.failed_role"),!0)}}async function ne($){if(confirm(l("users.delete_confirm",{username:$.username})))try{(await M(fetch(`/api/users/${$.id}`,{method:"DELETE"})))(),q()}catch{p(f,l("users.failed_delete"),!0)}}function je($){switch($){case"admin":return"bg-red-500/20 text-red-400";case"viewer":return"bg-blue-500/20 text-blue-400";default:return"bg-green-500/20 text-green-400"}}function we($){if(!$)return l("users.never");const z=new Date($+"Z"),Z=Date.now()-z.getTime();return Z<6e4?l("users.just_now"):Z<36e5?`${Math.floor(Z/6e4)}m ago`:Z<864e5?`${Math.floor(Z/36e5)}h ago`:`${Math.floor(Z/864e5)}d ago`}Es(q);const Te=S(ma(()=>C(va.role,"admin")||!va.userId),"isAdmin");var Ke={...ka()},Ee=Rm(),te=a(Ee),_e=a(te),Ve=s(a(_e)),K=s(_e,2);{var W=$=>{var z=wm(),Z=s(a(z));x(H=>n(Z,` ${H??""}`),[()=>l("users.add_user")]),Y("click",z,function(){return p(c,!e(c))}),v($,z)};g(()=>y(K,$=>{e(Te)&&$(W)}),"if",ya,142,8)}var Ue=s(te,2);{var Ne=$=>{var z=km(),Z=a(z);x(()=>n(Z,e(f))),v($,z)};g(()=>y(Ue,$=>{e(f)&&$(Ne)}),"if",ya,149,4)}var Pe=s(Ue,2);{var R=$=>{var z=$m(),Z=a(z),H=a(Z),Le=s(Z,2),O=a(Le),B=a(O),ue=a(B),D=s(B,2),T=s(O,2),E=a(T),U=a(E),J=s(E,2),xe=s(T,2),fe=a(xe),F=a(fe),he=s(fe,2),Xe=a(he);Xe.value=Xe.__value="admin";var yt=s(Xe);yt.value=yt.__value="user";var ht=s(yt);ht.value=ht.__value="viewer";var ct=s(xe,2),oe=a(ct),le=a(oe),I=s(oe,2),ae=s(ct,2),G=a(ae),ee=a(G),re=s(G,2),ke=s(ae,2),ve=a(ke),Q=a(ve),$e=s(ve,2),Ie=s(ke,2),Re=a(Ie),Ct=a(Re),st=s(Re,2),De=s(Le,2),lt=a(De),Je=s(a(lt)),_t=s(lt,2),ze=a(_t);x((be,ce,pe,Oe,Me,Ze,Be,We,it,ot,rt)=>{n(H,be),n(ue,`${ce??""} *`),n(U,pe),n(F,Oe),n(le,Me),n(ee,Ze),n(Q,Be),n(Ct,We),lt.disabled=it,n(Je,` ${ot??""}`),n(ze,rt)},[()=>l("users.create_user"),()=>l("users.username"),()=>l("users.display_name"),()=>l("users.role"),()=>l("users.password"),()=>l("users.email"),()=>l("users.telegram_id"),()=>l("users.whatsapp_id"),()=>!e(b).trim(),()=>l("users.create"),()=>l("common.cancel")]),ua(D,function(){return e(b)},function(ce){p(b,ce)}),ua(J,function(){return e(h)},function(ce){p(h,ce)}),Gi(he,function(){return e(w)},function(ce){p(w,ce)}),ua(I,function(){return e(L)},function(ce){p(L,ce)}),ua(re,function(){return e(j)},function(ce){p(j,ce)}),ua($e,function(){return e(A)},function(ce){p(A,ce)}),ua(st,function(){return e(k)},function(ce){p(k,ce)}),Y("click",lt,V),Y("click",_t,function(){return p(c,!1)}),v($,z)};g(()=>y(Pe,$=>{e(c)&&e(Te)&&$(R)}),"if",ya,154,4)}var ie=s(Pe,2);{var qe=$=>{var z=Tm(),Z=a(z);x(H=>n(Z,H),[()=>l("users.loading")]),v($,z)},Se=$=>{var z=Em(),Z=a(z);x(H=>n(Z,H),[()=>l("users.no_users_desc")]),v($,z)},me=$=>{var z=Lm();g(()=>Dt(z,21,()=>e(o),la,(Z,H)=>{var Le=Om(),O=a(Le),B=a(O),ue=a(B,!0);var D=s(B,2),T=a(D),E=a(T),U=a(E,!0);var J=s(E,2),xe=a(J);var fe=s(J,2),F=a(fe,!0);var he=s(fe,2);{var Xe=ve=>{var Q=Sm(),$e=a(Q,!0);x(Ie=>n($e,Ie),[()=>l("users.disabled")]),v(ve,Q)};g(()=>y(he,ve=>{e(H).enabled||ve(Xe)}),"if",ya,218,32)}var yt=s(T,2),ht=a(yt);{var ct=ve=>{var Q=Cm(),$e=s(a(Q));x(()=>n($e,` ${e(H).email??""}`)),v(ve,Q)};g(()=>y(ht,ve=>{e(H).email&&ve(ct)}),"if",ya,223,32)}var oe=s(ht,2);{var le=ve=>{var Q=Am(),$e=s(a(Q));x(()=>n($e,` ${e(H).telegram_id??""}`)),v(ve,Q)};g(()=>y(oe,ve=>{e(H).telegram_id&&ve(le)}),"if",ya,226,32)}var I=s(oe,2);{var ae=ve=>{var Q=Pm(),$e=s(a(Q));x(()=>n($e,` ${e(H).whatsapp_id??""}`)),v(ve,Q)};g(()=>y(I,ve=>{e(H).whatsapp_id&&ve(ae)}),"if",ya,229,32)}var G=s(I,2),ee=a(G);var re=s(O,2);{var ke=ve=>{var Q=jm(),$e=a(Q),Ie=a($e);Ie.value=Ie.__value="admin";var Re=s(Ie);Re.value=Re.__value="user";var Ct=s(Re);Ct.value=Ct.__value="viewer";var st;Wo($e);var De=s($e,2),lt=a(De,!0);var Je=s(De,2);x((_t,ze,be)=>{st!==(st=e(H).role)&&($e.value=($e.__value=e(H).role)??"",mo($e,e(H).role)),Qe(De,1,`text-xs px-2 py-1 rounded ${e(H).enabled?"bg-yellow-500/20 text-yellow-400 hover:bg-yellow-500/30":"bg-green-500/20 text-green-400 hover:bg-green-500/30"}`),St(De,"title",_t),n(lt,ze),St(Je,"title",be)},[()=>e(H).enabled?l("users.disable_user"):l("users.enable_user"),()=>e(H).enabled?l("users.disable"):l("users.enable"),()=>l("users.delete_user")]),Y("change",$e,function(ze){return N(e(H),ze.target.value)}),Y("click",De,function(){return X(e(H))}),Y("click",Je,function(){return ne(e(H))}),v(ve,Q)};g(()=>y(re,ve=>{e(Te)&&ve(ke)}),"if",ya,237,20)}x((ve,Q,$e,Ie)=>{n(ue,ve),n(U,e(H).display_name||e(H).username),n(xe,`@${e(H).username??""}`),Qe(fe,1,`px-1.5 py-0.5 rounded text-xs font-semibold ${Q??""}`),n(F,e(H).role),n(ee,`${$e??""} ${Ie??""}`)},[()=>{var ve,Q;return((Q=(ve=e(H).display_name)==null?void 0:ve.charAt(0))==null?void 0:Q.toUpperCase())||e(H).username.charAt(0).toUpperCase()},()=>je(e(H).role),()=>l("users.last_seen"),()=>we(e(H).last_seen_at)]),v(Z,Le)}),"each",ya,207,12),v($,z)};g(()=>y(ie,$=>{e(d)?$(qe):C(e(o).length,0)?$(Se,1):$(me,!1)}),"if",ya,201,4)}return x($=>n(Ve,` ${$??""}`),[()=>l("users.title")]),v(r,Ee),ha(Ke)}La(["click","change"]);sa[u]="src/components/TwoFactorPanel.svelte";var Mm=_(m('<div class="card"><p class="text-text-muted text-sm"><i class="fa-solid fa-circle-info mr-1"></i> </p></div>'),sa[u],[[243,4,[[244,8,[[245,12]]]]]]),Nm=_(m('<div class="card"><p class="text-text-muted text-sm"><i class="fa-solid fa-spinner fa-spin mr-1"></i> </p></div>'),sa[u],[[250,4,[[251,8,[[251,43]]]]]]),Im=_(m("<div><i></i> </div>"),sa[u],[[256,12,[[257,16]]]]),Dm=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),sa[u],[[273,20]]),Fm=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),sa[u],[[275,20]]),Um=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i>'),sa[u],[[301,32]]),zm=_(m('<p class="text-sm text-text-subtle mb-3"> </p> <div class="flex gap-2 items-end"><div><label class="text-xs text-text-muted block mb-1"> </label> <input type="text" maxlength="6" placeholder="000000" class="w-32 bg-bg border border-border rounded px-3 py-1.5 text-sm font-mono tracking-widest text-center"/></div> <button class="px-3 py-1.5 text-sm rounded bg-red-600/80 text-white hover:bg-red-500 transition-colors disabled:opacity-50"><!> </button></div>',1),sa[u],[[281,20],[284,20,[[285,24,[[286,28],[287,28]]],[295,24]]]]),Bm=_(m('<p class="text-sm text-text-subtle mb-3"> </p> <button class="px-4 py-2 text-sm rounded bg-primary-600 text-white hover:bg-primary-500 transition-colors"><i class="fa-solid fa-shield-halved mr-1"></i> </button>',1),sa[u],[[308,20],[311,20,[[315,24]]]]),qm=_(m('<div class="px-2 py-1 rounded bg-bg border border-border"><span class="text-text-muted mr-1"></span> </div>'),sa[u],[[350,40,[[351,44]]]]),Gm=_(m('<div class="grid grid-cols-2 gap-1 font-mono text-xs"></div>'),sa[u],[[348,32]]),Vm=_(m('<div class="space-y-4"><div class="p-4 rounded-lg bg-surface border border-border"><p class="text-sm text-text mb-2 font-medium"> </p> <div class="bg-white p-4 rounded-lg inline-block"><img alt="TOTP QR Code" class="w-48 h-48"/></div> <p class="text-xs text-text-muted mt-3"> </p> <code class="block mt-1 px-3 py-2 rounded bg-bg border border-border text-sm font-mono break-all select-all"> </code></div> <div class="p-4 rounded-lg bg-surface border border-border"><p class="text-sm text-text mb-2 font-medium"> </p> <p class="text-xs text-text-subtle mb-2"> </p> <button class="text-xs text-primary-400 hover:text-primary-300 mb-2"> </button> <!></div> <div class="p-4 rounded-lg bg-surface border border-border"><p class="text-sm text-text mb-2 font-medium"> </p> <p class="text-xs text-text-subtle mb-2"> </p> <div class="flex gap-2"><input type="text" maxlength="6" placeholder="000000" class="w-32 bg-bg border border-border rounded px-3 py-1.5 text-sm font-mono tracking-widest text-center"/> <button class="px-4 py-1.5 text-sm rounded bg-green-600 text-white hover:bg-green-500 transition-colors disabled:opacity-50"><i class="fa-solid fa-check mr-1"></i> </button> <button class="px-3 py-1.5 text-sm rounded border border-border text-text-muted hover:bg-surface-elevated transition-colors"> </button></div></div></div>'),sa[u],[[319,20,[[320,24,[[321,28],[322,28,[[324,32]]],[330,28],[331,28]]],[336,24,[[337,28],[338,28],[341,28]]],[358,24,[[359,28],[360,28],[361,28,[[362,32],[370,32,[[375,36]]],[377,32]]]]]]]]),Hm=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),sa[u],[[402,24]]),Km=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),sa[u],[[406,24]]),Wm=_(m('<div class="flex items-center justify-between p-2.5 rounded-md bg-surface border border-border/50"><div class="flex items-center gap-2.5"><i class="fa-solid fa-key text-text-muted"></i> <div><span class="text-sm text-text"> </span> <span class="text-xs text-text-subtle ml-2"> </span></div></div> <button class="text-xs px-2 py-1 rounded bg-red-500/20 text-red-400 hover:bg-red-500/30 transition-colors"><i class="fa-solid fa-trash"></i></button></div>'),sa[u],[[414,32,[[415,36,[[416,40],[417,40,[[418,44],[419,44]]]]],[422,36,[[427,40]]]]]]),Ym=_(m('<div class="space-y-2 mb-4"></div>'),sa[u],[[412,24]]),Jm=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),sa[u],[[451,32]]),Qm=_(m('<i class="fa-solid fa-plus mr-1"></i> ',1),sa[u],[[453,32]]),Xm=_(m('<p class="text-xs text-text-subtle mt-3"><i class="fa-solid fa-circle-info mr-1"></i> </p>'),sa[u],[[459,24,[[460,28]]]]),Zm=_(m('<div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-solid fa-fingerprint text-amber-400"></i> <div><span class="text-sm font-medium text-text"> </span> <p class="text-xs text-text-subtle mt-0.5"> </p></div></div> <!></div> <div class="p-4"><!> <div class="flex items-end gap-2"><div class="flex-1"><label class="text-xs text-text-muted block mb-1"> </label> <input type="text" class="w-full bg-bg border border-border rounded px-3 py-1.5 text-sm"/></div> <button class="px-4 py-1.5 text-sm rounded bg-primary-600 text-white hover:bg-primary-500 transition-colors disabled:opacity-50 whitespace-nowrap"><!></button></div> <!></div></div>'),sa[u],[[392,12,[[393,16,[[394,20,[[395,24],[396,24,[[397,24],[398,24]]]]]]],[409,16,[[435,20,[[436,24,[[437,28],[438,28]]],[445,24]]]]]]]]),e2=_(m('<div class="space-y-4"><!> <div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-solid fa-clock text-primary-400"></i> <div><span class="text-sm font-medium text-text"> </span> <p class="text-xs text-text-subtle mt-0.5"> </p></div></div> <!></div> <div class="p-4"><!></div></div> <!> <div class="p-3 rounded-lg bg-surface-elevated border border-border/50"><p class="text-xs text-text-subtle"><i class="fa-solid fa-circle-info mr-1"></i> <!></p></div></div>'),sa[u],[[254,4,[[263,8,[[264,12,[[265,16,[[266,20],[267,20,[[268,24],[269,24]]]]]]],[278,12]]],[469,8,[[470,12,[[471,16]]]]]]]]);function sa(r,i){wa(new.target),ga(i,!0,sa);let o=S(P(null),"status"),d=S(P(Jt([])),"passkeys"),c=S(P(!0),"loading"),f=S(P(""),"message"),b=S(P("success"),"messageType"),h=S(P("idle"),"setupStep"),w=S(P(""),"totpSecret"),L=S(P(""),"otpauthUri"),j=S(P(Jt([])),"recoveryCodes"),A=S(P(""),"verifyCode"),k=S(P(!1),"showRecoveryCodes"),q=S(P(""),"passkeyName"),V=S(P(!1),"registeringPasskey"),X=S(P(""),"disableCode"),N=S(P(!1),"disabling");async function ne(){p(c,!0);try{const[Se,me]=(await M(Promise.all([fetch("/api/auth/2fa/status"),fetch("/api/auth/passkeys")])))();p(o,(await M(Se.json()))(),!0);const $=(await M(me.json()))();p(d,$.passkeys||[],!0)}catch{je(l("twofa.load_failed"),"error")}p(c,!1)}function je(Se,me="success"){p(f,Se,!0),p(b,me,!0),Se&&setTimeout(()=>{p(f,"")},5e3)}async function we(){try{const Se=(await M(fetch("/api/auth/2fa/setup",{method:"POST"})))(),me=(await M(Se.json()))();me.ok?(p(w,me.secret,!0),p(L,me.otpauth_uri,!0),p(j,me.recovery_codes,!0),p(h,"showing_secret")):je(me.error||"Failed to set up TOTP","error")}catch{je("Failed to set up TOTP","error")}}async function Te(){if(e(A).trim())try{const Se=(await M(fetch("/api/auth/2fa/enable",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({code:e(A).trim()})})))(),me=(await M(Se.json()))();me.ok?(je("TOTP 2FA enabled successfully"),p(h,"idle"),p(A,""),p(w,""),p(L,""),(await M(ne()))()):je(me.error||"Invalid code","error")}catch{je("Failed to enable TOTP","error")}}async function Ke(){if(e(X).trim()){p(N,!0);try{const Se=(await M(fetch("/api/auth/2fa/disable",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify({code:e(X).trim()})})))(),me=(await M(Se.json()))();me.ok?(je("TOTP 2FA disabled"),p(X,""),(await M(ne()))()):je(me.error||"Invalid code","error")}catch{je("Failed to disable TOTP","error")}p(N,!1)}}async function Ee(){p(V,!0);try{const Se=(await M(fetch("/api/auth/passkey/register/start",{method:"POST"})))(),me=(await M(Se.json()))();if(!me.ok){je(me.error||"Failed to start passkey registration","error"),p(V,!1);return}const $=me.options;if($.publicKey.challenge=_e($.publicKey.challenge),$.publicKey.user.id=_e($.publicKey.user.id),$.publicKey.excludeCredentials)for(const B of $.publicKey.excludeCredentials)B.id=_e(B.id);const z=(await M(navigator.credentials.create($)))();if(!z){je("Passkey creation was cancelled","error"),p(V,!1);return}const Z=z.response,H={credential:{id:z.id,rawId:Ve(z.rawId),response:{attestationObject:Ve(Z.attestationObject),clientDataJSON:Ve(Z.clientDataJSON)},type:z.type},name:e(q)||"Passkey"},Le=(await M(fetch("/api/auth/passkey/register/finish",{method:"POST",headers:{"Content-Type":"application/json"},body:JSON.stringify(H)})))(),O=(await M(Le.json()))();O.ok?(je("Passkey registered successfully"),p(q,""),(await M(ne()))()):je(O.error||"Failed to register passkey","error")}catch(Se){C(Se.name,"NotAllowedError")?je("Passkey creation was cancelled or timed out","error"):je(`Passkey registration failed: ${Se.message}`,"error")}p(V,!1)}async function te(Se){if(confirm(`Delete passkey "${Se.name}"?`))try{const me=(await M(fetch(`/api/auth/passkeys/${Se.id}`,{method:"DELETE"})))(),$=(await M(me.json()))();$.ok?(je("Passkey deleted"),(await M(ne()))()):je($.error||"Failed to delete passkey","error")}catch{je("Failed to delete passkey","error")}}function _e(Se){const me=Se.replace(/-/g,"+").replace(/_/g,"/"),$=me+"=".repeat((4-me.length%4)%4),z=atob($),Z=new ArrayBuffer(z.length),H=new Uint8Array(Z);for(let Le=0;Le<z.length;Le++)H[Le]=z.charCodeAt(Le);return Z}function Ve(Se){const me=new Uint8Array(Se);let $="";for(const z of me)$+=String.fromCharCode(z);return btoa($).replace(/\+/g,"-").replace(/\//g,"_").replace(/=/g,"")}function K(Se){const me=new Date(Se+"Z"),$=Date.now()-me.getTime();return $<6e4?"Just now":$<36e5?`${Math.floor($/6e4)}m ago`:$<864e5?`${Math.floor($/36e5)}h ago`:`${Math.floor($/864e5)}d ago`}const W=S(ma(()=>!!va.userId),"hasUserId");Es(()=>{e(W)&&ne()});var Ue={...ka()},Ne=Oa(),Pe=Ae(Ne);{var R=Se=>{var me=Mm(),$=a(me),z=s(a($));x(Z=>n(z,` ${Z??""}`),[()=>l("twofa.multi_user_only")]),v(Se,me)},ie=Se=>{var me=Nm(),$=a(me),z=s(a($));x(Z=>n(z,` ${Z??""}`),[()=>l("twofa.loading_status")]),v(Se,me)},qe=Se=>{var me=e2(),$=a(me);{var z=G=>{var ee=Im(),re=a(ee),ke=s(re);x(()=>{Qe(ee,1,`px-3 py-2 rounded-md text-sm ${C(e(b),"error")?"bg-red-900/20 border border-red-800/40 text-red-400":"bg-green-900/20 border border-green-800/40 text-green-400"}`),Qe(re,1,`fa-solid ${C(e(b),"error")?"fa-circle-exclamation":"fa-check-circle"} mr-1`),n(ke,` ${e(f)??""}`)}),v(G,ee)};g(()=>y($,G=>{e(f)&&G(z)}),"if",sa,255,8)}var Z=s($,2),H=a(Z),Le=a(H),O=s(a(Le),2),B=a(O),ue=a(B),D=s(B,2),T=a(D),E=s(Le,2);{var U=G=>{var ee=Dm(),re=a(ee);x(ke=>n(re,ke),[()=>l("twofa.enabled_badge")]),v(G,ee)},J=G=>{var ee=Fm(),re=a(ee);x(ke=>n(re,ke),[()=>l("twofa.disabled_badge")]),v(G,ee)};g(()=>y(E,G=>{e(o).totp_enabled?G(U):G(J,!1)}),"if",sa,272,16)}var xe=s(H,2),fe=a(xe);{var F=G=>{var ee=zm(),re=Ae(ee),ke=a(re),ve=s(re,2),Q=a(ve),$e=a(Q),Ie=a($e),Re=s($e,2),Ct=s(Q,2),st=a(Ct);{var De=Je=>{var _t=Um();v(Je,_t)};g(()=>y(st,Je=>{e(N)&&Je(De)}),"if",sa,300,28)}var lt=s(st);x((Je,_t,ze,be)=>{n(ke,Je),n(Ie,_t),Ct.disabled=ze,n(lt,` ${be??""}`)},[()=>l("twofa.totp_enabled"),()=>l("twofa.totp_code"),()=>e(N)||C(e(X).trim().length,6,!1),()=>l("twofa.disable_2fa")]),ua(Re,function(){return e(X)},function(_t){p(X,_t)}),Y("click",Ct,Ke),v(G,ee)},he=G=>{var ee=Bm(),re=Ae(ee),ke=a(re),ve=s(re,2),Q=s(a(ve));x(($e,Ie)=>{n(ke,$e),n(Q,` ${Ie??""}`)},[()=>l("twofa.setup_desc"),()=>l("twofa.setup_authenticator")]),Y("click",ve,we),v(G,ee)},Xe=G=>{var ee=Vm(),re=a(ee),ke=a(re),ve=a(ke),Q=s(ke,2),$e=a(Q),Ie=s(Q,2),Re=a(Ie),Ct=s(Ie,2),st=a(Ct),De=s(re,2),lt=a(De),Je=a(lt),_t=s(lt,2),ze=a(_t),be=s(_t,2),ce=a(be),pe=s(be,2);{var Oe=xt=>{var se=Gm();g(()=>Dt(se,21,()=>e(j),la,(ye,et,dt)=>{var tt=qm(),Ye=a(tt);Ye.textContent=`${dt+1}.`;var at=s(Ye);x(()=>n(at,` ${e(et)??""}`)),v(ye,tt)}),"each",sa,349,36),v(xt,se)};g(()=>y(pe,xt=>{e(k)&&xt(Oe)}),"if",sa,347,28)}var Me=s(De,2),Ze=a(Me),Be=a(Ze),We=s(Ze,2),it=a(We),ot=s(We,2),rt=a(ot),Fe=s(rt,2),ut=s(a(Fe)),mt=s(Fe,2),Tt=a(mt);x((xt,se,ye,et,dt,tt,Ye,at,ft,wt,Ge)=>{n(ve,xt),St($e,"src",`https://api.qrserver.com/v1/create-qr-code/?size=200x200&data=${se??""}`),n(Re,ye),n(st,e(w)),n(Je,et),n(ze,dt),n(ce,tt),n(Be,Ye),n(it,at),Fe.disabled=ft,n(ut,` ${wt??""}`),n(Tt,Ge)},[()=>l("twofa.scan_step"),()=>encodeURIComponent(e(L)),()=>l("twofa.manual_key"),()=>l("twofa.save_recovery"),()=>l("twofa.recovery_hint"),()=>e(k)?l("twofa.hide_codes"):l("twofa.show_codes"),()=>l("twofa.verify_step"),()=>l("twofa.verify_hint"),()=>C(e(A).trim().length,6,!1),()=>l("twofa.verify_enable"),()=>l("common.cancel")]),Y("click",be,function(){return p(k,!e(k))}),Y("keydown",rt,function(se){C(se.key,"Enter")&&Te()}),ua(rt,function(){return e(A)},function(se){p(A,se)}),Y("click",Fe,Te),Y("click",mt,function(){p(h,"idle"),p(w,""),p(L,"")}),v(G,ee)};g(()=>y(fe,G=>{e(o).totp_enabled?G(F):C(e(h),"idle")?G(he,1):C(e(h),"showing_secret")&&G(Xe,2)}),"if",sa,279,16)}var yt=s(Z,2);{var ht=G=>{var ee=Zm(),re=a(ee),ke=a(re),ve=s(a(ke),2),Q=a(ve),$e=a(Q),Ie=s(Q,2),Re=a(Ie),Ct=s(ke,2);{var st=rt=>{var Fe=Hm(),ut=a(Fe);x(mt=>n(ut,mt),[()=>l("twofa.registered",{count:e(o).passkey_count})]),v(rt,Fe)},De=rt=>{var Fe=Km(),ut=a(Fe);x(mt=>n(ut,mt),[()=>l("twofa.none_badge")]),v(rt,Fe)};g(()=>y(Ct,rt=>{e(o).passkey_count>0?rt(st):rt(De,!1)}),"if",sa,401,20)}var lt=s(re,2),Je=a(lt);{var _t=rt=>{var Fe=Ym();g(()=>Dt(Fe,21,()=>e(d),la,(ut,mt)=>{var Tt=Wm(),xt=a(Tt),se=s(a(xt),2),ye=a(se),et=a(ye,!0);var dt=s(ye,2),tt=a(dt);var Ye=s(xt,2);x((at,ft,wt)=>{n(et,e(mt).name),n(tt,`${at??""} ${ft??""}`),St(Ye,"title",wt)},[()=>l("twofa.added_label"),()=>K(e(mt).created_at),()=>l("twofa.delete_passkey")]),Y("click",Ye,function(){return te(e(mt))}),v(ut,Tt)}),"each",sa,413,28),v(rt,Fe)};g(()=>y(Je,rt=>{e(d).length>0&&rt(_t)}),"if",sa,411,20)}var ze=s(Je,2),be=a(ze),ce=a(be),pe=a(ce),Oe=s(ce,2),Me=s(be,2),Ze=a(Me);{var Be=rt=>{var Fe=Jm(),ut=s(Ae(Fe));x(mt=>n(ut,` ${mt??""}`),[()=>l("twofa.registering")]),v(rt,Fe)},We=rt=>{var Fe=Qm(),ut=s(Ae(Fe));x(mt=>n(ut,` ${mt??""}`),[()=>l("twofa.add_passkey")]),v(rt,Fe)};g(()=>y(Ze,rt=>{e(V)?rt(Be):rt(We,!1)}),"if",sa,450,28)}var it=s(ze,2);{var ot=rt=>{var Fe=Xm(),ut=s(a(Fe));x(mt=>n(ut,` ${mt??""}`),[()=>l("twofa.passkey_info")]),v(rt,Fe)};g(()=>y(it,rt=>{C(e(d).length,0)&&rt(ot)}),"if",sa,458,20)}x((rt,Fe,ut,mt)=>{n($e,rt),n(Re,Fe),n(pe,ut),St(Oe,"placeholder",mt),Me.disabled=e(V)},[()=>l("twofa.passkeys"),()=>l("twofa.passkeys_desc"),()=>l("twofa.passkey_name"),()=>l("twofa.passkey_placeholder")]),ua(Oe,function(){return e(q)},function(Fe){p(q,Fe)}),Y("click",Me,Ee),v(G,ee)};g(()=>y(yt,G=>{e(o).passkeys_available&&G(ht)}),"if",sa,391,8)}var ct=s(yt,2),oe=a(ct),le=s(a(oe)),I=s(le);{var ae=G=>{var ee=Jr();x(re=>n(ee,re),[()=>l("twofa.info_both")]),v(G,ee)};g(()=>y(I,G=>{e(o).totp_enabled&&e(o).passkey_count>0&&G(ae)}),"if",sa,473,16)}x((G,ee,re)=>{n(ue,G),n(T,ee),n(le,` ${re??""} `)},[()=>l("twofa.authenticator_app"),()=>l("twofa.authenticator_hint"),()=>l("twofa.info_box")]),v(Se,me)};g(()=>y(Pe,Se=>{e(W)?e(c)?Se(ie,1):e(o)&&Se(qe,2):Se(R)}),"if",sa,242,0)}return v(r,Ne),ha(Ue)}La(["click","keydown"]);Mr[u]="src/components/PersonaEditor.svelte";var t2=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i> ',1),Mr[u],[[72,20]]),a2=_(m('<i class="fa-solid fa-floppy-disk mr-1"></i> ',1),Mr[u],[[74,20]]),r2=_(m("<span> </span>"),Mr[u],[[78,16]]),s2=_(m('<span class="text-xs text-warning-500"><i class="fa-solid fa-circle-exclamation mr-0.5"></i> Unsaved changes</span>'),Mr[u],[[81,16,[[82,20]]]]),i2=_(m(`<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden mb-4"><div class="border-b border-border"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted"><i class="fa-solid fa-masks-theater mr-1.5"></i> </h2></div> <div class="p-4 space-y-3"><p class="text-xs text-text-subtle"> </p> <div><label class="text-xs font-medium text-text-muted uppercase tracking-wide mb-1 block"> </label> <textarea rows="6" class="w-full px-3 py-2 rounded-md border border-border bg-surface-elevated text-text text-sm
                        placeholder-text-muted/50 focus:outline-none focus:ring-2 focus:ring-primary-500/50 resize-y font-sans" placeholder="You are a helpful, proactive AI assistant..."></textarea></div> <div class="flex items-center gap-3"><button class="px-4 py-2 rounded-md bg-primary-600 text-white font-medium text-sm
                        hover:bg-primary-500 transition-colors disabled:opacity-50"><!></button> <!> <!></div></div></section>`),Mr[u],[[44,0,[[45,4,[[46,8,[[47,12]]]]],[50,4,[[51,8],[52,8,[[53,12],[56,12]]],[64,8,[[65,12]]]]]]]]);function Mr(r,i){wa(new.target),ga(i,!0,Mr);let o=S(P(""),"personality"),d=S(P(""),"original"),c=S(P(!1),"saving"),f=S(P(""),"message");async function b(){try{const Pe=(await M(Nt("GET","/api/persona")))();p(o,Pe.personality??"",!0),p(d,e(o),!0)}catch(Pe){console.error(...Xt("error","loadPersona:",Pe))}}async function h(){p(c,!0),p(f,"");try{(await M(Nt("PUT","/api/persona",{personality:e(o)})))().ok?(p(f,l("persona.saved"),!0),p(d,e(o),!0),setTimeout(()=>p(f,""),3e3)):p(f,l("persona.failed"),!0)}catch{p(f,l("persona.failed"),!0)}p(c,!1)}const w=S(ma(()=>C(e(o),e(d),!1)),"dirty");Es(b);var L={...ka()},j=i2(),A=a(j),k=a(A),q=s(a(k)),V=s(A,2),X=a(V),N=a(X),ne=s(X,2),je=a(ne),we=a(je),Te=s(je,2),Ke=s(ne,2),Ee=a(Ke),te=a(Ee);{var _e=Pe=>{var R=t2(),ie=s(Ae(R));x(qe=>n(ie,` ${qe??""}`),[()=>l("persona.saving")]),v(Pe,R)},Ve=Pe=>{var R=a2(),ie=s(Ae(R));x(qe=>n(ie,` ${qe??""}`),[()=>l("persona.save")]),v(Pe,R)};g(()=>y(te,Pe=>{e(c)?Pe(_e):Pe(Ve,!1)}),"if",Mr,71,16)}var K=s(Ee,2);{var W=Pe=>{var R=r2(),ie=a(R);x(qe=>{Qe(R,1,`text-xs ${qe??""}`),n(ie,e(f))},[()=>C(e(f),l("persona.saved"))?"text-success-500":"text-error-500"]),v(Pe,R)};g(()=>y(K,Pe=>{e(f)&&Pe(W)}),"if",Mr,77,12)}var Ue=s(K,2);{var Ne=Pe=>{var R=s2();v(Pe,R)};g(()=>y(Ue,Pe=>{e(w)&&Pe(Ne)}),"if",Mr,80,12)}return x((Pe,R,ie)=>{n(q,` ${Pe??""}`),n(N,R),n(we,ie),Ee.disabled=e(c)||!e(w)},[()=>l("persona.title"),()=>l("persona.personality_hint"),()=>l("persona.personality")]),ua(Te,function(){return e(o)},function(R){p(o,R)}),Y("click",Ee,h),v(r,j),ha(L)}La(["click"]);Xa[u]="src/components/ConversationHistory.svelte";var o2=_(m("<button> </button>"),Xa[u],[[97,16]]),n2=_(m('<p class="text-text-subtle text-sm italic text-center py-6"> </p>'),Xa[u],[[115,12]]),l2=_(m('<span class="text-[10px] text-text-subtle"> </span>'),Xa[u],[[123,24]]),d2=_(m('<div class="px-4 py-3 hover:bg-surface-muted/30 transition-colors"><div class="flex items-center gap-2 mb-1"><i></i> <span class="text-xs font-medium text-text-muted uppercase tracking-wide"> </span> <!> <span class="text-[10px] text-text-subtle ml-auto"> </span></div> <div class="text-sm text-text whitespace-pre-wrap break-words leading-relaxed pl-6"> </div></div>'),Xa[u],[[118,12,[[119,16,[[120,20],[121,20],[125,20]]],[127,16]]]]),c2=_(m('<i class="fa-solid fa-spinner fa-spin mr-1"></i>'),Xa[u],[[142,20]]),v2=_(m('<div class="px-4 py-3 border-t border-border text-center"><button class="px-4 py-1.5 text-sm border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors disabled:opacity-50"><!> </button></div>'),Xa[u],[[135,8,[[136,12]]]]),u2=_(m('<section class="bg-surface border border-border rounded-lg shadow-sm overflow-hidden"><div class="flex justify-between items-center border-b border-border"><h2 class="text-xs font-semibold px-4 py-3 uppercase tracking-wider text-text-muted"><i class="fa-solid fa-clock-rotate-left mr-1.5"></i> </h2> <span class="text-xs text-text-muted pr-3"> </span></div> <div class="px-4 py-2 border-b border-border flex flex-col sm:flex-row gap-2"><input type="text" class="flex-1 px-2.5 py-1.5 border border-border rounded-md bg-background text-text text-sm outline-none focus:border-primary-500 focus:ring-1 focus:ring-primary-900"/> <div class="flex gap-1"></div></div> <div class="max-h-[600px] overflow-y-auto custom-scroll divide-y divide-border-muted"><!> <!></div> <!></section>'),Xa[u],[[77,0,[[78,4,[[79,8,[[80,12]]],[82,8]]],[87,4,[[88,8],[95,8]]],[113,4]]]]);function Xa(r,i){wa(new.target),ga(i,!0,Xa);let o=S(P(Jt([])),"messages"),d=S(P(0),"total"),c=S(P(""),"query"),f=S(P("all"),"roleFilter"),b=S(P(!1),"loading"),h=S(P(0),"offset");const w=50;async function L(R=!1){p(b,!0);try{const ie=new URLSearchParams({limit:String(w),offset:String(e(h))});e(c)&&ie.set("q",e(c));const qe=(await M(Nt("GET",`/api/memory/conversation/history?${ie}`)))();R?p(o,[...e(o),...qe.messages],!0):p(o,qe.messages,!0),p(d,qe.total,!0)}catch(ie){console.error(...Xt("error","loadHistory:",ie))}p(b,!1)}function j(){p(h,0),L()}function A(){p(h,e(h)+w),L(!0)}function k(R){C(R.key,"Enter")&&j()}const q=S(ma(()=>C(e(f),"all")?e(o):e(o).filter(R=>C(R.role,e(f)))),"filtered");function V(R){switch(R){case"user":return"fa-user text-info-500";case"assistant":return"fa-robot text-primary-500";case"system":return"fa-gear text-text-subtle";default:return"fa-circle text-text-subtle"}}L();var X={...ka()},N=u2(),ne=a(N),je=a(ne),we=s(a(je)),Te=s(je,2),Ke=a(Te),Ee=s(ne,2),te=a(Ee),_e=s(te,2);g(()=>Dt(_e,21,()=>[["all",l("history.all_roles")],["user",l("history.user_only")],["assistant",l("history.assistant_only")],["system",l("history.system_only")]],la,(R,ie)=>{var qe=ma(()=>vo(e(ie),2));let Se=()=>e(qe)[0];Se();let me=()=>e(qe)[1];me();var $=o2();let z;var Z=a($,!0);x(()=>{z=Qe($,1,"px-2 py-1 text-xs rounded border transition-colors",null,z,{"bg-primary-600":C(e(f),Se()),"text-white":C(e(f),Se()),"border-primary-600":C(e(f),Se()),"border-border":C(e(f),Se(),!1),"text-text-muted":C(e(f),Se(),!1),"hover:text-text":C(e(f),Se(),!1)}),n(Z,me())}),Y("click",$,function(){return p(f,Se(),!0)}),v(R,$)}),"each",Xa,96,12);var Ve=s(Ee,2),K=a(Ve);{var W=R=>{var ie=n2(),qe=a(ie);x(Se=>n(qe,Se),[()=>l("history.no_results")]),v(R,ie)};g(()=>y(K,R=>{C(e(q).length,0)&&!e(b)&&R(W)}),"if",Xa,114,8)}var Ue=s(K,2);g(()=>Dt(Ue,17,()=>e(q),R=>R.id,(R,ie)=>{var qe=d2(),Se=a(qe),me=a(Se),$=s(me,2),z=a($,!0);var Z=s($,2);{var H=D=>{var T=l2(),E=a(T);x(()=>n(E,`(${e(ie).user_id??""})`)),v(D,T)};g(()=>y(Z,D=>{e(ie).user_id&&D(H)}),"if",Xa,122,20)}var Le=s(Z,2),O=a(Le,!0);var B=s(Se,2),ue=a(B,!0);x((D,T,E)=>{Qe(me,1,`fa-solid ${D??""} text-xs w-4 text-center`),n(z,e(ie).role),n(O,T),n(ue,E)},[()=>V(e(ie).role),()=>Zr(e(ie).created_at),()=>e(ie).content.length>500?e(ie).content.slice(0,500)+"…":e(ie).content]),v(R,qe)}),"each",Xa,117,8);var Ne=s(Ve,2);{var Pe=R=>{var ie=v2(),qe=a(ie),Se=a(qe);{var me=z=>{var Z=c2();v(z,Z)};g(()=>y(Se,z=>{e(b)&&z(me)}),"if",Xa,141,16)}var $=s(Se);x(z=>{qe.disabled=e(b),n($,` ${z??""}`)},[()=>l("history.load_more")]),Y("click",qe,A),v(R,ie)};g(()=>y(Ne,R=>{e(o).length<e(d)&&R(Pe)}),"if",Xa,134,4)}return x((R,ie,qe)=>{n(we,` ${R??""}`),n(Ke,ie),St(te,"placeholder",qe)},[()=>l("history.title"),()=>l("history.total",{total:e(d)}),()=>l("history.search")]),Y("keyup",te,k),ua(te,function(){return e(c)},function(ie){p(c,ie)}),v(r,N),ha(X)}La(["keyup","click"]);ge[u]="src/components/SettingsTab.svelte";var f2=_(m('<p class="text-text-subtle text-sm italic text-center py-2"> </p>'),ge[u],[[269,12]]),p2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),ge[u],[[282,24]]),_2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-red-900/40 text-red-400 border-red-800/50"> </span>'),ge[u],[[284,24]]),m2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),ge[u],[[286,24]]),x2=_(m('<div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-text font-mono text-xs"> </span></div>'),ge[u],[[301,24,[[302,28],[303,28]]]]),b2=_(m('<div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-amber-400 text-xs"><i class="fa-solid fa-triangle-exclamation mr-1"></i> </span></div>'),ge[u],[[308,24,[[309,28],[310,28,[[311,32]]]]]]),g2=_(m('<div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-text font-mono text-xs"> </span></div>'),ge[u],[[316,24,[[317,28],[318,28]]]]),h2=_(m('<div class="mt-2 p-2 rounded bg-zinc-800/40 border border-border/50"><p class="text-xs text-text-subtle"><i class="fa-solid fa-circle-info mr-1"></i> </p></div>'),ge[u],[[322,24,[[323,28,[[324,32]]]]]]),y2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),ge[u],[[343,24]]),w2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),ge[u],[[345,24]]),k2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-amber-900/40 text-amber-400 border-amber-800/50"> </span>'),ge[u],[[347,24]]),$2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-red-900/40 text-red-400 border-red-800/50"> </span>'),ge[u],[[349,24]]),T2=_(m('<div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-text font-mono text-xs"> </span></div>'),ge[u],[[367,28,[[368,32],[369,32]]]]),E2=_(m('<div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-amber-400 text-xs"><i class="fa-solid fa-triangle-exclamation mr-1"></i> </span></div>'),ge[u],[[374,28,[[375,32],[376,32,[[377,36]]]]]]),S2=_(m('<div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-green-400 font-mono text-xs"> </span></div>'),ge[u],[[382,28,[[383,32],[384,32]]]]),C2=_(m('<img alt="WhatsApp QR Code" class="mx-auto w-52 h-52 rounded-lg shadow-lg"/>'),ge[u],[[395,36]]),A2=_(m('<div class="w-52 h-52 mx-auto bg-zinc-800 rounded-lg flex items-center justify-center"><span class="text-text-subtle text-sm"> </span></div>'),ge[u],[[397,36,[[398,40]]]]),P2=_(m('<div class="mt-3 p-4 rounded-lg bg-surface border border-amber-800/50 text-center"><p class="text-sm text-amber-400 mb-3"><i class="fa-solid fa-qrcode mr-1.5"></i> </p> <!> <button class="mt-3 px-4 py-1.5 text-xs border border-border rounded-md bg-surface hover:bg-surface-elevated transition-colors"><i class="fa-solid fa-arrows-rotate mr-1"></i> </button></div>'),ge[u],[[390,28,[[391,32,[[392,36]]],[401,32,[[405,36]]]]]]),j2=_(m('<div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-text font-mono text-xs"> </span></div> <div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-text font-mono text-xs"> </span></div> <!> <!> <!>',1),ge[u],[[358,24,[[359,28],[360,28]]],[362,24,[[363,28],[364,28]]]]),O2=_(m('<div class="mt-2 p-2 rounded bg-zinc-800/40 border border-border/50"><p class="text-xs text-text-subtle"><i class="fa-solid fa-circle-info mr-1"></i> </p></div>'),ge[u],[[410,24,[[411,28,[[412,32]]]]]]),L2=_(m('<div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-brands fa-telegram text-blue-400 text-lg"></i> <div><span class="text-sm font-medium text-text"> </span> <p class="text-xs text-text-subtle mt-0.5"> </p></div></div> <!></div> <div class="p-3 space-y-2 text-sm"><div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-text"> </span></div> <div class="flex justify-between"><span class="text-text-muted"> </span> <span> </span></div> <!> <!> <!></div></div> <div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-brands fa-whatsapp text-green-400 text-lg"></i> <div><span class="text-sm font-medium text-text"> </span> <p class="text-xs text-text-subtle mt-0.5"> </p></div></div> <!></div> <div class="p-3 space-y-2 text-sm"><div class="flex justify-between"><span class="text-text-muted"> </span> <span class="text-text"> </span></div> <!></div></div> <div class="flex items-center gap-3 p-2.5 rounded-lg bg-surface-elevated border border-border/50"><i class="fa-solid fa-circle-info text-text-subtle"></i> <span class="text-xs text-text-subtle"> <!> </span></div>',1),ge[u],[[272,12,[[273,16,[[274,20,[[275,24],[276,24,[[277,28],[278,28]]]]]]],[289,16,[[290,20,[[291,24],[292,24]]],[294,20,[[295,24],[296,24]]]]]]],[333,12,[[334,16,[[335,20,[[336,24],[337,24,[[338,28],[339,28]]]]]]],[352,16,[[353,20,[[354,24],[355,24]]]]]]],[421,12,[[422,16],[423,16]]]]),R2=_(m('<p class="text-text-subtle text-sm italic text-center py-2"> </p>'),ge[u],[[444,12]]),M2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),ge[u],[[455,28]]),N2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),ge[u],[[457,28]]),I2=_(m("<p> </p>"),ge[u],[[462,28]]),D2=_(m("<p> </p>"),ge[u],[[464,28]]),F2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-green-900/40 text-green-400 border-green-800/50"> </span>'),ge[u],[[477,28]]),U2=_(m('<span class="text-xs px-2 py-0.5 rounded-full border bg-zinc-800/60 text-text-subtle border-border"> </span>'),ge[u],[[481,28]]),z2=_(m('<div class="flex items-center gap-2 p-2 rounded-md bg-surface border border-border/50"><i></i> <span class="text-sm text-text"> </span> <span class="text-xs px-1.5 py-0.5 rounded bg-primary-900/30 text-primary-400 border border-primary-800/30 ml-auto">SSO</span></div>'),ge[u],[[488,36,[[489,40],[490,40],[491,40]]]]),B2=_(m('<div class="space-y-1.5"></div>'),ge[u],[[486,28]]),q2=_(m('<p class="text-sm text-text-subtle"> </p>'),ge[u],[[496,28]]),G2=_(m('<div class="p-3 rounded-lg bg-red-900/20 border border-red-800/40"><p class="text-sm text-red-400"><i class="fa-solid fa-triangle-exclamation mr-1"></i> </p></div>'),ge[u],[[505,16,[[506,20,[[507,24]]]]]]),V2=_(m(`<div class="grid grid-cols-1 md:grid-cols-2 gap-4"><div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-solid fa-key text-amber-400"></i> <span class="text-sm font-medium text-text"> </span></div> <!></div> <div class="p-3 text-sm text-text-subtle"><!></div></div> <div class="rounded-lg border border-border overflow-hidden"><div class="flex items-center justify-between p-3 bg-surface-elevated"><div class="flex items-center gap-2.5"><i class="fa-solid fa-right-to-bracket text-primary-400"></i> <span class="text-sm font-medium text-text"> </span></div> <!></div> <div class="p-3"><!></div></div></div> <!> <div class="p-3 rounded bg-surface-elevated border border-border/50"><p class="text-xs text-text-subtle"><i class="fa-solid fa-circle-info mr-1"></i> Configure SSO in your <code class="px-1 py-0.5 bg-zinc-900 rounded text-text-muted">config.toml</code>:</p> <code class="block mt-2 whitespace-pre-wrap text-xs text-text-subtle leading-relaxed">[dashboard]
 password_enabled = true
@@ -0,0 +1,364 @@
+//! Versioned schema migrations.
+//!
+//! [`super::migrate`] creates the base schema; every change after that is a
+//! numbered step in [`MIGRATIONS`].  Applied versions are recorded in the
+//! `schema_version` table and each pending step runs in its own transaction,
+//! so a failed step leaves the database at the previous version.  Steps are
+//! written to be idempotent because databases created before versioning
+//! already carry some of their changes.
+
+use rusqlite::Connection;
+use tracing::info;
+
+use crate::error::Result;
+
+/// One schema change.
+struct Migration {
+    version: i64,
+    name: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// All migrations, in the order they are applied.  Append new steps with
+/// the next version number; never renumber or edit an existing one.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "audit_log_entry_hash", apply: audit_log_entry_hash },
+    Migration { version: 2, name: "reasoning_traces", apply: reasoning_traces },
+    Migration { version: 3, name: "processed_updates", apply: processed_updates },
+    Migration { version: 4, name: "outbound_messages", apply: outbound_messages },
+    Migration { version: 5, name: "goals_last_progress_at", apply: goals_last_progress_at },
+    Migration { version: 6, name: "scratchpad", apply: scratchpad },
+    Migration { version: 7, name: "episodes_tag", apply: episodes_tag },
+    Migration { version: 8, name: "user_profiles_reinforced_at", apply: user_profiles_reinforced_at },
+    Migration { version: 9, name: "llm_usage_user_id", apply: llm_usage_user_id },
+    Migration { version: 10, name: "users_api_key", apply: users_api_key },
+    Migration { version: 11, name: "config_presets", apply: config_presets },
+    Migration { version: 12, name: "personas", apply: personas },
+    Migration { version: 13, name: "conversation_branches", apply: conversation_branches },
+    Migration { version: 14, name: "conversation_channel", apply: conversation_channel },
+    Migration { version: 15, name: "search_cache", apply: search_cache },
+    Migration { version: 16, name: "conversation_active_branches", apply: conversation_active_branches },
+    Migration { version: 17, name: "conversation_branches_drop_active", apply: conversation_branches_drop_active },
+];
+
+/// The version a fully migrated database is at.
+#[cfg(test)]
+pub(crate) fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// The highest applied version, 0 for a database that predates versioning.
+pub(crate) fn current_version(conn: &Connection) -> Result<i64> {
+    Ok(conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))?)
+}
+
+/// Apply every migration newer than the recorded version.  Returns how many
+/// were applied.
+pub(crate) fn run(conn: &Connection) -> Result<usize> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version    INTEGER PRIMARY KEY,
+            name       TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        ",
+    )?;
+
+    let current = current_version(conn)?;
+    let mut applied = 0;
+    for m in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.unchecked_transaction()?;
+        (m.apply)(&tx)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name) VALUES (?1, ?2)",
+            rusqlite::params![m.version, m.name],
+        )?;
+        tx.commit()?;
+        info!(version = m.version, name = m.name, "applied schema migration");
+        applied += 1;
+    }
+    Ok(applied)
+}
+
+/// Add `column` to `table` unless it is already there.
+fn add_column(conn: &Connection, table: &str, column: &str, col_type: &str) -> Result<()> {
+    if conn.prepare(&format!("SELECT {column} FROM {table} LIMIT 0")).is_err() {
+        conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {col_type}"))?;
+    }
+    Ok(())
+}
+
+fn drop_column(conn: &Connection, table: &str, column: &str) -> Result<()> {
+    if conn.prepare(&format!("SELECT {column} FROM {table} LIMIT 0")).is_ok() {
+        conn.execute_batch(&format!("ALTER TABLE {table} DROP COLUMN {column}"))?;
+    }
+    Ok(())
+}
+
+// -- Steps --------------------------------------------------------------
+
+/// Hash chain over audit entries (tamper evidence); NULL for rows written
+/// before it was introduced.
+fn audit_log_entry_hash(conn: &Connection) -> Result<()> {
+    add_column(conn, "audit_log", "entry_hash", "TEXT DEFAULT NULL")
+}
+
+/// Per-message timeline of LLM turns and tool calls, linked from the audit
+/// log by `turn_id`.
+fn reasoning_traces(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS reasoning_traces (
+            turn_id     TEXT PRIMARY KEY,
+            user_id     TEXT,
+            message     TEXT NOT NULL,
+            trace_json  TEXT NOT NULL,
+            created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_reasoning_traces_created ON reasoning_traces(created_at);
+        ",
+    )?;
+    add_column(conn, "audit_log", "turn_id", "TEXT DEFAULT NULL")
+}
+
+/// Processed messaging updates (dedup across reconnects/restarts).
+fn processed_updates(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS processed_updates (
+            platform     TEXT NOT NULL,
+            update_id    INTEGER NOT NULL,
+            processed_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (platform, update_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_processed_updates_at ON processed_updates(processed_at);
+        ",
+    )?;
+    Ok(())
+}
+
+/// Outbound message queue (retried until delivered).
+fn outbound_messages(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS outbound_messages (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            platform        TEXT NOT NULL,
+            channel         TEXT NOT NULL,
+            text            TEXT NOT NULL,
+            attempts        INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at INTEGER NOT NULL,
+            last_error      TEXT,
+            created_at      INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_outbound_messages_due ON outbound_messages(next_attempt_at);
+        ",
+    )?;
+    Ok(())
+}
+
+/// Last time a goal made progress, for priority aging.
+fn goals_last_progress_at(conn: &Connection) -> Result<()> {
+    add_column(conn, "goals", "last_progress_at", "TEXT DEFAULT NULL")
+}
+
+/// Tool scratchpad (shared key-value store, optional expiry).
+fn scratchpad(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS scratchpad (
+            scope      TEXT NOT NULL,
+            key        TEXT NOT NULL,
+            value      TEXT NOT NULL,
+            expires_at INTEGER,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (scope, key)
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+/// Optional tag on episodes, for filtered recall.
+fn episodes_tag(conn: &Connection) -> Result<()> {
+    add_column(conn, "episodes", "tag", "TEXT DEFAULT NULL")?;
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_episodes_tag ON episodes(tag) WHERE tag IS NOT NULL;")?;
+    Ok(())
+}
+
+/// When a profile fact was last confirmed, for confidence decay.
+fn user_profiles_reinforced_at(conn: &Connection) -> Result<()> {
+    add_column(conn, "user_profiles", "reinforced_at", "TEXT DEFAULT NULL")
+}
+
+/// Which user an LLM request was made for, for per-user cost budgets.
+fn llm_usage_user_id(conn: &Connection) -> Result<()> {
+    add_column(conn, "llm_usage", "user_id", "TEXT DEFAULT NULL")?;
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_llm_usage_user ON llm_usage(user_id, created_at) WHERE user_id IS NOT NULL;",
+    )?;
+    Ok(())
+}
+
+/// Blind index of each user's API key, for bearer authentication.
+fn users_api_key(conn: &Connection) -> Result<()> {
+    add_column(conn, "users", "api_key_blind", "TEXT DEFAULT NULL")?;
+    conn.execute_batch(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_api_key ON users(api_key_blind) WHERE api_key_blind IS NOT NULL;",
+    )?;
+    Ok(())
+}
+
+/// Named presets of hot-reloadable settings.
+fn config_presets(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS config_presets (
+            name          TEXT PRIMARY KEY,
+            settings_json TEXT NOT NULL,
+            updated_at    TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+/// Named system-prompt personas, and which one is active.
+fn personas(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS personas (
+            name       TEXT PRIMARY KEY,
+            prompt     TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        ",
+    )?;
+    add_column(conn, "core_memory", "active_persona", "TEXT DEFAULT NULL")
+}
+
+fn conversation_branches(conn: &Connection) -> Result<()> {
+    add_column(conn, "conversation_history", "branch", "TEXT NOT NULL DEFAULT 'main'")?;
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS conversation_branches (
+            name        TEXT PRIMARY KEY,
+            parent      TEXT,
+            active      INTEGER NOT NULL DEFAULT 0,
+            created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        INSERT OR IGNORE INTO conversation_branches (name, active) VALUES ('main', 1);
+        CREATE INDEX IF NOT EXISTS idx_conversation_branch ON conversation_history(branch);
+        ",
+    )?;
+    Ok(())
+}
+
+fn conversation_channel(conn: &Connection) -> Result<()> {
+    add_column(conn, "conversation_history", "channel", "TEXT DEFAULT NULL")?;
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_conversation_channel ON conversation_history(channel);")?;
+    Ok(())
+}
+
+fn search_cache(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS search_cache (
+            provider    TEXT NOT NULL,
+            query       TEXT NOT NULL,
+            max_results INTEGER NOT NULL,
+            results     TEXT NOT NULL,
+            fetched_at  INTEGER NOT NULL,
+            PRIMARY KEY (provider, query, max_results)
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+/// Active branch per chat, replacing the single global
+/// `conversation_branches.active` flag.  The old flag carries over to the
+/// default conversation.
+fn conversation_active_branches(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS conversation_active_branches (
+            chat    TEXT PRIMARY KEY,
+            branch  TEXT NOT NULL
+        );
+        INSERT OR IGNORE INTO conversation_active_branches (chat, branch)
+            SELECT '', name FROM conversation_branches WHERE active = 1 AND name != 'main';
+        ",
+    )?;
+    Ok(())
+}
+
+/// The global flag `conversation_active_branches` replaced.
+fn conversation_branches_drop_active(conn: &Connection) -> Result<()> {
+    drop_column(conn, "conversation_branches", "active")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open;
+
+    fn has_column(conn: &Connection, table: &str, column: &str) -> bool {
+        conn.prepare(&format!("SELECT {column} FROM {table} LIMIT 0")).is_ok()
+    }
+
+    #[test]
+    fn opening_an_old_database_applies_pending_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("old.db");
+
+        // Roll a fresh database back to what it looked like before
+        // versioning and the later steps existed.
+        let conn = open(&path).unwrap();
+        conn.execute_batch(
+            "
+            DROP TABLE schema_version;
+            DROP TABLE scratchpad;
+            DROP INDEX idx_episodes_tag;
+            ALTER TABLE episodes DROP COLUMN tag;
+            ALTER TABLE user_profiles DROP COLUMN reinforced_at;
+            INSERT INTO user_profiles (user_id, key, value) VALUES ('u1', 'lang', 'en');
+            ",
+        )
+        .unwrap();
+        drop(conn);
+
+        let conn = open(&path).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(has_column(&conn, "episodes", "tag"));
+        assert!(has_column(&conn, "user_profiles", "reinforced_at"));
+        assert!(has_column(&conn, "scratchpad", "expires_at"));
+        let value: String = conn
+            .query_row("SELECT value FROM user_profiles WHERE key = 'lang'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(value, "en");
+    }
+
+    #[test]
+    fn reopening_a_migrated_database_is_a_no_op() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("current.db");
+        drop(open(&path).unwrap());
+
+        let conn = open(&path).unwrap();
+        assert_eq!(run(&conn).unwrap(), 0);
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(!has_column(&conn, "conversation_branches", "active"));
+        let rows: i64 = conn.query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn versions_are_strictly_increasing() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+    }
+}
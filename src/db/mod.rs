@@ -1,22 +1,74 @@
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
+use tokio::sync::Mutex;
 use tracing::info;
 
 use crate::error::Result;
 
+mod migrations;
+
+/// How long a connection waits on a locked database before failing with
+/// `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Open the writer connection: WAL journal, so readers on other
+/// connections never wait for it, plus a busy timeout and migrations.
 pub fn open(path: &Path) -> Result<Connection> {
     info!("opening database at {}", path.display());
     let conn = Connection::open(path)?;
 
     conn.execute_batch("PRAGMA journal_mode = WAL;")?;
     conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
 
     migrate(&conn)?;
     Ok(conn)
 }
 
-/// Run database migrations. Exposed for tests that use in-memory DBs.
+/// A few read-only connections to the same database file, handed out round
+/// robin.  With WAL they read the last committed state while the writer
+/// (the shared `Arc<Mutex<Connection>>`) is mid-transaction, so dashboard
+/// reads don't queue behind the agent loop.
+#[derive(Clone)]
+pub struct ReadPool {
+    conns: Arc<Vec<Arc<Mutex<Connection>>>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl ReadPool {
+    /// Open `size` read-only connections to the writer's database file.  An
+    /// in-memory writer can't be shared, so its handle is reused instead.
+    pub fn for_writer(writer: &Arc<Mutex<Connection>>, path: Option<&str>, size: usize) -> Result<Self> {
+        let conns = match path.filter(|p| !p.is_empty()) {
+            Some(path) => (0..size.max(1))
+                .map(|_| {
+                    let conn = Connection::open_with_flags(
+                        path,
+                        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                    )?;
+                    conn.busy_timeout(BUSY_TIMEOUT)?;
+                    Ok(Arc::new(Mutex::new(conn)))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![writer.clone()],
+        };
+        Ok(Self { conns: Arc::new(conns), next: Arc::new(AtomicUsize::new(0)) })
+    }
+
+    /// The next read connection, usable wherever a manager takes the shared
+    /// connection handle.  Writes through it fail.
+    pub fn get(&self) -> Arc<Mutex<Connection>> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.conns.len();
+        self.conns[i].clone()
+    }
+}
+
+/// Create the base schema, then apply pending versioned migrations (see
+/// [`migrations`]). Exposed for tests that use in-memory DBs.
 pub(crate) fn migrate(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "
@@ -454,7 +506,8 @@ pub(crate) fn migrate(conn: &Connection) -> Result<()> {
         info!("oauth_tokens migration complete");
     }
 
-    info!("database migrations complete");
+    let applied = migrations::run(conn)?;
+    info!(applied, version = migrations::current_version(conn)?, "database migrations complete");
     Ok(())
 }
 
@@ -475,13 +528,11 @@ fn add_column_if_missing(conn: &Connection, table: &str, column: &str, col_type:
 
 /// Creates an in-memory database with migrations applied. Use in tests.
 #[cfg(test)]
-pub(crate) fn test_db() -> std::sync::Arc<tokio::sync::Mutex<Connection>> {
-    use std::sync::Arc;
-
+pub(crate) fn test_db() -> Arc<Mutex<Connection>> {
     let conn = Connection::open_in_memory().unwrap();
     conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
     migrate(&conn).unwrap();
-    Arc::new(tokio::sync::Mutex::new(conn))
+    Arc::new(Mutex::new(conn))
 }
 
 #[cfg(test)]
@@ -528,6 +579,11 @@ mod tests {
             "episodes",
             "user_profiles",
             "memory_embeddings",
+            "reasoning_traces",
+            "processed_updates",
+            "outbound_messages",
+            "scratchpad",
+            "schema_version",
         ];
 
         for table in tables {
@@ -549,4 +605,69 @@ mod tests {
         migrate(&conn).unwrap();
         migrate(&conn).unwrap();
     }
+
+    #[test]
+    fn test_open_enables_wal_and_busy_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = open(&dir.path().join("wal.db")).unwrap();
+        let mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        let timeout: i64 = conn.query_row("PRAGMA busy_timeout", [], |row| row.get(0)).unwrap();
+        assert_eq!(timeout, BUSY_TIMEOUT.as_millis() as i64);
+    }
+
+    #[tokio::test]
+    async fn test_pool_reads_while_write_is_in_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = open(&dir.path().join("pool.db")).unwrap();
+        let path = writer.path().map(str::to_string);
+        let writer = Arc::new(Mutex::new(writer));
+        let pool = ReadPool::for_writer(&writer, path.as_deref(), 2).unwrap();
+
+        writer
+            .lock()
+            .await
+            .execute("INSERT INTO metadata (key, value) VALUES ('a', '1')", [])
+            .unwrap();
+
+        // Hold the writer lock with an open write transaction.
+        let guard = writer.lock().await;
+        guard.execute_batch("BEGIN IMMEDIATE").unwrap();
+        guard
+            .execute("INSERT INTO metadata (key, value) VALUES ('b', '2')", [])
+            .unwrap();
+
+        let read = tokio::time::timeout(Duration::from_secs(1), async {
+            let conn = pool.get();
+            let conn = conn.lock().await;
+            conn.query_row("SELECT COUNT(*) FROM metadata WHERE key IN ('a', 'b')", [], |row| {
+                row.get::<_, i64>(0)
+            })
+        })
+        .await
+        .expect("read blocked behind the writer")
+        .unwrap();
+        // Readers see the last committed state.
+        assert_eq!(read, 1);
+
+        guard.execute_batch("COMMIT").unwrap();
+        drop(guard);
+        let conn = pool.get();
+        let count: i64 = conn
+            .lock()
+            .await
+            .query_row("SELECT COUNT(*) FROM metadata WHERE key IN ('a', 'b')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+
+        // Pool connections are read-only.
+        assert!(pool.get().lock().await.execute("DELETE FROM metadata", []).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pool_shares_in_memory_writer() {
+        let writer = test_db();
+        let pool = ReadPool::for_writer(&writer, Some(""), 4).unwrap();
+        assert!(Arc::ptr_eq(&pool.get(), &writer));
+    }
 }
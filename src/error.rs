@@ -1,3 +1,6 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -40,6 +43,65 @@ pub enum SafeAgentError {
 
     #[error("plugin error: {0}")]
     Plugin(String),
+
+    /// A named thing (skill, goal, user, pending action, ...) doesn't exist.
+    /// The message names it, e.g. `skill 'notes'`.
+    #[error("{0} not found")]
+    NotFound(String),
+
+    /// A storage or usage limit would be exceeded.
+    #[error("quota exceeded: {0}")]
+    QuotaExceeded(String),
+}
+
+impl SafeAgentError {
+    /// Stable machine-readable code for API responses.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config",
+            Self::Database(_) => "database",
+            Self::Http(_) => "http",
+            Self::Json(_) => "json",
+            Self::Llm(_) => "llm",
+            Self::Io(_) => "io",
+            Self::SandboxViolation(_) => "sandbox_violation",
+            Self::RateLimited(_) => "rate_limited",
+            Self::Approval(_) => "approval",
+            Self::ToolNotFound(_) | Self::NotFound(_) => "not_found",
+            Self::Messaging(_) => "messaging",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::Plugin(_) => "plugin",
+            Self::QuotaExceeded(_) => "quota_exceeded",
+        }
+    }
+
+    /// The HTTP status a dashboard handler should answer with.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::ToolNotFound(_) | Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::PermissionDenied(_) | Self::SandboxViolation(_) => StatusCode::FORBIDDEN,
+            Self::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::QuotaExceeded(_) => StatusCode::INSUFFICIENT_STORAGE,
+            // The action exists but isn't in a state that allows this.
+            Self::Approval(_) => StatusCode::CONFLICT,
+            // An upstream service failed, not us.
+            Self::Llm(_) | Self::Http(_) => StatusCode::BAD_GATEWAY,
+            Self::Config(_)
+            | Self::Database(_)
+            | Self::Json(_)
+            | Self::Io(_)
+            | Self::Messaging(_)
+            | Self::Plugin(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// `{"ok": false, "error": ..., "code": ...}` with [`SafeAgentError::status_code`].
+impl IntoResponse for SafeAgentError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "ok": false, "error": self.to_string(), "code": self.code() });
+        (self.status_code(), Json(body)).into_response()
+    }
 }
 
 pub type Result<T> = std::result::Result<T, SafeAgentError>;
@@ -60,6 +122,8 @@ mod tests {
             (SafeAgentError::Messaging("offline".into()), "messaging error: offline"),
             (SafeAgentError::PermissionDenied("blocked".into()), "permission denied: blocked"),
             (SafeAgentError::Plugin("bad manifest".into()), "plugin error: bad manifest"),
+            (SafeAgentError::NotFound("skill 'notes'".into()), "skill 'notes' not found"),
+            (SafeAgentError::QuotaExceeded("sandbox full".into()), "quota exceeded: sandbox full"),
         ];
         for (err, expected) in cases {
             assert_eq!(err.to_string(), expected);
@@ -80,6 +144,36 @@ mod tests {
         assert!(err.to_string().starts_with("JSON error:"));
     }
 
+    #[test]
+    fn errors_map_to_http_status() {
+        let cases = [
+            (SafeAgentError::NotFound("goal 'g1'".into()), StatusCode::NOT_FOUND, "not_found"),
+            (SafeAgentError::ToolNotFound("foo".into()), StatusCode::NOT_FOUND, "not_found"),
+            (SafeAgentError::PermissionDenied("viewer".into()), StatusCode::FORBIDDEN, "permission_denied"),
+            (SafeAgentError::SandboxViolation("../etc".into()), StatusCode::FORBIDDEN, "sandbox_violation"),
+            (SafeAgentError::RateLimited("60/min".into()), StatusCode::TOO_MANY_REQUESTS, "rate_limited"),
+            (SafeAgentError::QuotaExceeded("sandbox".into()), StatusCode::INSUFFICIENT_STORAGE, "quota_exceeded"),
+            (SafeAgentError::Approval("already approved".into()), StatusCode::CONFLICT, "approval"),
+            (SafeAgentError::Llm("timeout".into()), StatusCode::BAD_GATEWAY, "llm"),
+            (SafeAgentError::Config("bad".into()), StatusCode::INTERNAL_SERVER_ERROR, "config"),
+        ];
+        for (err, status, code) in cases {
+            assert_eq!(err.status_code(), status, "{err}");
+            assert_eq!(err.code(), code, "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn error_response_carries_status_and_code() {
+        let resp = SafeAgentError::NotFound("skill 'notes'".into()).into_response();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "not_found");
+        assert_eq!(json["error"], "skill 'notes' not found");
+        assert_eq!(json["ok"], false);
+    }
+
     #[test]
     fn error_is_debug() {
         let err = SafeAgentError::Config("test".into());
@@ -11,13 +11,34 @@
 //!   - A background task periodically syncs with peers.
 //!   - Goal tasks are claimed with a `claimed_by` column; only the
 //!     claiming node executes them.
+//!   - A health-check task pings each peer's `/healthz`.  Peers that fail
+//!     repeatedly are marked offline and come back online on recovery.
+//!   - Every message between nodes is wrapped in a [`SignedMessage`] signed
+//!     with the sender's Ed25519 key, a timestamp and a one-time nonce.
+//!     Unsigned, badly signed, stale or replayed payloads are rejected.
+//!   - A node is only trusted once an admin adds it from the dashboard or
+//!     its public key is listed in `federation.trusted_peer_keys`; a
+//!     heartbeat alone never makes an unknown key trusted.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use data_encoding::BASE64;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
-use tracing::{debug, info};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{debug, info, warn};
+
+use crate::error::{Result, SafeAgentError};
+
+/// Signed messages older (or further in the future) than this are rejected
+/// to limit replay.
+const MAX_MESSAGE_SKEW_SECS: i64 = 300;
 
 /// Unique identity of this agent node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,16 +50,37 @@ pub struct NodeInfo {
     pub started_at: String,
     pub last_heartbeat: String,
     pub status: NodeStatus,
+    /// Base64-encoded Ed25519 public key used to verify this node's messages.
+    #[serde(default)]
+    pub public_key: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NodeStatus {
     Online,
+    /// Failed at least one health check but not yet past the threshold.
+    Degraded,
     Offline,
     Syncing,
 }
 
+/// Health-check bookkeeping for a single peer.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PeerHealth {
+    pub consecutive_failures: u32,
+    pub last_checked: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// A peer together with its health-check state (for the dashboard).
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerStatus {
+    #[serde(flatten)]
+    pub info: NodeInfo,
+    pub health: PeerHealth,
+}
+
 /// A memory mutation to replicate to peers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryDelta {
@@ -59,13 +101,160 @@ pub struct TaskClaim {
     pub claimed_at: String,
 }
 
+/// This node's Ed25519 signing identity.
+///
+/// Persisted to `<data_dir>/federation.key` (32-byte seed as hex, mode
+/// 0600) so peers keep trusting the node across restarts.
+#[derive(Clone)]
+pub struct NodeKey {
+    signing: SigningKey,
+}
+
+impl NodeKey {
+    /// Generate a fresh, unpersisted key.
+    pub fn generate() -> Self {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        Self {
+            signing: SigningKey::from_bytes(&seed),
+        }
+    }
+
+    /// Load the node key from `<data_dir>/federation.key`, generating it on
+    /// first launch.
+    pub fn ensure(data_dir: &Path) -> Result<Self> {
+        let key_path = data_dir.join("federation.key");
+
+        if key_path.exists() {
+            let hex = std::fs::read_to_string(&key_path)
+                .map_err(|e| SafeAgentError::Config(format!("failed to read federation key: {e}")))?;
+            let mut seed = [0u8; 32];
+            crate::crypto::hex_decode(hex.trim(), &mut seed)?;
+            return Ok(Self {
+                signing: SigningKey::from_bytes(&seed),
+            });
+        }
+
+        let key = Self::generate();
+        std::fs::write(&key_path, format!("{}\n", crate::crypto::hex_encode(key.signing.as_bytes())))
+            .map_err(|e| SafeAgentError::Config(format!("failed to write federation key: {e}")))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let perms = std::fs::Permissions::from_mode(0o600);
+            let _ = std::fs::set_permissions(&key_path, perms);
+        }
+
+        info!(path = %key_path.display(), "generated new federation signing key");
+        Ok(key)
+    }
+
+    /// Base64-encoded public key advertised to peers.
+    pub fn public_key(&self) -> String {
+        BASE64.encode(self.signing.verifying_key().as_bytes())
+    }
+}
+
+/// A federation payload signed by its originating node.
+///
+/// `payload` is the JSON-encoded message exactly as signed; the signature
+/// covers `node_id`, `timestamp`, `nonce` and `payload`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedMessage {
+    pub node_id: String,
+    pub timestamp: String,
+    /// Random per-message value; a receiver accepts each nonce only once
+    /// within the timestamp window.
+    #[serde(default)]
+    pub nonce: String,
+    pub payload: String,
+    pub signature: String,
+}
+
+impl SignedMessage {
+    fn signing_input(node_id: &str, timestamp: &str, nonce: &str, payload: &str) -> Vec<u8> {
+        format!("{node_id}\n{timestamp}\n{nonce}\n{payload}").into_bytes()
+    }
+
+    /// Sign `payload` as `node_id`.
+    pub fn sign<T: Serialize>(key: &NodeKey, node_id: &str, payload: &T) -> Result<Self> {
+        let payload = serde_json::to_string(payload)?;
+        let timestamp = chrono::Utc::now().to_rfc3339();
+        let mut nonce = [0u8; 16];
+        OsRng.fill_bytes(&mut nonce);
+        let nonce = crate::crypto::hex_encode(&nonce);
+        let signature = key
+            .signing
+            .sign(&Self::signing_input(node_id, &timestamp, &nonce, &payload));
+        Ok(Self {
+            node_id: node_id.to_string(),
+            timestamp,
+            nonce,
+            payload,
+            signature: BASE64.encode(&signature.to_bytes()),
+        })
+    }
+
+    /// Check the signature against a base64-encoded public key and that
+    /// the timestamp is recent.  Replay of the nonce is checked by
+    /// [`FederationManager`], which remembers the nonces it has seen.
+    pub fn verify(&self, public_key: &str) -> Result<()> {
+        let denied = |msg: &str| SafeAgentError::PermissionDenied(format!("federation message rejected: {msg}"));
+
+        if self.nonce.is_empty() {
+            return Err(denied("missing nonce"));
+        }
+
+        let key_bytes: [u8; 32] = BASE64
+            .decode(public_key.as_bytes())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| denied("malformed public key"))?;
+        let verifying = VerifyingKey::from_bytes(&key_bytes).map_err(|_| denied("malformed public key"))?;
+
+        let sig_bytes: [u8; 64] = BASE64
+            .decode(self.signature.as_bytes())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| denied("malformed signature"))?;
+        let signature = Signature::from_bytes(&sig_bytes);
+
+        verifying
+            .verify(
+                &Self::signing_input(&self.node_id, &self.timestamp, &self.nonce, &self.payload),
+                &signature,
+            )
+            .map_err(|_| denied("invalid signature"))?;
+
+        let sent = chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+            .map_err(|_| denied("invalid timestamp"))?;
+        let skew = (chrono::Utc::now() - sent.with_timezone(&chrono::Utc)).num_seconds();
+        if skew.abs() > MAX_MESSAGE_SKEW_SECS {
+            return Err(denied("timestamp outside allowed window"));
+        }
+
+        Ok(())
+    }
+}
+
 /// Federation manager handles peer communication and state sync.
 pub struct FederationManager {
     node_id: String,
     node_name: String,
     address: String,
+    key: NodeKey,
+    /// Public keys allowed to register by heartbeat without an admin
+    /// adding the peer first (`federation.trusted_peer_keys`).
+    trusted_keys: HashSet<String>,
+    /// Nonces of accepted messages -> unix time they fall out of the
+    /// timestamp window and can be forgotten.
+    seen_nonces: Mutex<HashMap<String, i64>>,
     peers: Mutex<HashMap<String, NodeInfo>>,
+    health: Mutex<HashMap<String, PeerHealth>>,
     enabled: bool,
+    health_check_interval: Duration,
+    failure_threshold: u32,
 }
 
 impl FederationManager {
@@ -82,11 +271,42 @@ impl FederationManager {
             node_id,
             node_name: node_name.to_string(),
             address: address.to_string(),
+            key: NodeKey::generate(),
+            trusted_keys: HashSet::new(),
+            seen_nonces: Mutex::new(HashMap::new()),
             peers: Mutex::new(HashMap::new()),
+            health: Mutex::new(HashMap::new()),
             enabled,
+            health_check_interval: Duration::from_secs(30),
+            failure_threshold: 3,
         }
     }
 
+    /// Use a persisted signing key instead of the ephemeral default.
+    pub fn with_key(mut self, key: NodeKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Trust these base64 public keys: nodes presenting them may register
+    /// themselves by heartbeat.
+    pub fn with_trusted_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.trusted_keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Configure how often peers are probed and how many consecutive
+    /// failures mark a peer offline.
+    pub fn with_health_checks(mut self, interval_secs: u64, failure_threshold: u32) -> Self {
+        self.health_check_interval = Duration::from_secs(interval_secs.max(1));
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
@@ -101,7 +321,97 @@ impl FederationManager {
             started_at: chrono::Utc::now().to_rfc3339(),
             last_heartbeat: chrono::Utc::now().to_rfc3339(),
             status: NodeStatus::Online,
+            public_key: self.key.public_key(),
+        }
+    }
+
+    /// Sign an outbound payload as this node.
+    pub fn sign<T: Serialize>(&self, payload: &T) -> Result<SignedMessage> {
+        SignedMessage::sign(&self.key, &self.node_id, payload)
+    }
+
+    /// Verify a message from a registered peer and decode its payload.
+    pub async fn verify<T: DeserializeOwned>(&self, msg: &SignedMessage) -> Result<T> {
+        let public_key = {
+            let peers = self.peers.lock().await;
+            peers
+                .get(&msg.node_id)
+                .map(|p| p.public_key.clone())
+                .filter(|k| !k.is_empty())
+                .ok_or_else(|| {
+                    SafeAgentError::PermissionDenied(format!(
+                        "federation message rejected: unknown peer {}",
+                        msg.node_id
+                    ))
+                })?
+        };
+        msg.verify(&public_key)?;
+        self.check_replay(msg).await?;
+        Ok(serde_json::from_str(&msg.payload)?)
+    }
+
+    /// Reject a nonce already seen within the timestamp window, otherwise
+    /// remember it.  Call only after the signature has been verified.
+    async fn check_replay(&self, msg: &SignedMessage) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let mut seen = self.seen_nonces.lock().await;
+        seen.retain(|_, expires| *expires > now);
+
+        let key = format!("{}:{}", msg.node_id, msg.nonce);
+        if seen.contains_key(&key) {
+            warn!(node_id = %msg.node_id, "replayed federation message — rejecting");
+            return Err(SafeAgentError::PermissionDenied(
+                "federation message rejected: replayed nonce".into(),
+            ));
+        }
+        // A message can be up to MAX_MESSAGE_SKEW_SECS in the future, so keep
+        // the nonce for both halves of the window.
+        seen.insert(key, now + 2 * MAX_MESSAGE_SKEW_SECS);
+        Ok(())
+    }
+
+    /// Accept a signed heartbeat, registering the sender.
+    ///
+    /// A known node must keep presenting the key it registered with.  An
+    /// unknown node is only registered if the key it presents is listed in
+    /// `trusted_keys`; otherwise an admin has to add it as a peer first.
+    pub async fn accept_heartbeat(&self, msg: &SignedMessage) -> Result<NodeInfo> {
+        let info: NodeInfo = serde_json::from_str(&msg.payload)?;
+        if info.node_id != msg.node_id {
+            return Err(SafeAgentError::PermissionDenied(
+                "federation message rejected: node_id mismatch".into(),
+            ));
         }
+
+        let registered_key = {
+            let peers = self.peers.lock().await;
+            peers
+                .get(&info.node_id)
+                .map(|p| p.public_key.clone())
+                .filter(|k| !k.is_empty())
+        };
+        match registered_key {
+            Some(key) if key != info.public_key => {
+                warn!(node_id = %info.node_id, "peer presented a different public key — rejecting");
+                return Err(SafeAgentError::PermissionDenied(
+                    "federation message rejected: public key changed".into(),
+                ));
+            }
+            Some(_) => {}
+            None if self.trusted_keys.contains(&info.public_key) => {}
+            None => {
+                warn!(node_id = %info.node_id, "heartbeat from untrusted node — rejecting");
+                return Err(SafeAgentError::PermissionDenied(format!(
+                    "federation message rejected: node {} is not a trusted peer",
+                    info.node_id
+                )));
+            }
+        }
+
+        msg.verify(&info.public_key)?;
+        self.check_replay(msg).await?;
+        self.register_peer(info.clone()).await;
+        Ok(info)
     }
 
     /// Register a peer node.
@@ -113,6 +423,7 @@ impl FederationManager {
             address = %info.address,
             "peer registered"
         );
+        self.health.lock().await.remove(&info.node_id);
         peers.insert(info.node_id.clone(), info);
     }
 
@@ -122,6 +433,7 @@ impl FederationManager {
         if peers.remove(node_id).is_some() {
             info!(node_id, "peer removed");
         }
+        self.health.lock().await.remove(node_id);
     }
 
     /// List all known peers.
@@ -130,6 +442,116 @@ impl FederationManager {
         peers.values().cloned().collect()
     }
 
+    /// List all known peers with their health-check state.
+    pub async fn peer_statuses(&self) -> Vec<PeerStatus> {
+        let peers = self.peers.lock().await;
+        let health = self.health.lock().await;
+        let mut statuses: Vec<PeerStatus> = peers
+            .values()
+            .map(|info| PeerStatus {
+                info: info.clone(),
+                health: health.get(&info.node_id).cloned().unwrap_or_default(),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.info.name.cmp(&b.info.name));
+        statuses
+    }
+
+    /// Record the outcome of a health check for a peer.
+    ///
+    /// A success resets the failure count and marks the peer online.  Each
+    /// failure marks it degraded until `failure_threshold` consecutive
+    /// failures, after which it is marked offline.  Returns the peer's new
+    /// status, or `None` if the peer is unknown.
+    pub async fn record_health(&self, node_id: &str, result: std::result::Result<(), String>) -> Option<NodeStatus> {
+        let mut peers = self.peers.lock().await;
+        let peer = peers.get_mut(node_id)?;
+        let mut health = self.health.lock().await;
+        let entry = health.entry(node_id.to_string()).or_default();
+        let now = chrono::Utc::now().to_rfc3339();
+        entry.last_checked = Some(now.clone());
+
+        let previous = peer.status;
+        match result {
+            Ok(()) => {
+                entry.consecutive_failures = 0;
+                entry.last_error = None;
+                peer.last_heartbeat = now;
+                peer.status = NodeStatus::Online;
+                if previous != NodeStatus::Online {
+                    info!(node_id, name = %peer.name, "peer recovered — reactivated");
+                }
+            }
+            Err(err) => {
+                entry.consecutive_failures += 1;
+                entry.last_error = Some(err);
+                if entry.consecutive_failures >= self.failure_threshold {
+                    peer.status = NodeStatus::Offline;
+                    if previous != NodeStatus::Offline {
+                        warn!(
+                            node_id,
+                            name = %peer.name,
+                            failures = entry.consecutive_failures,
+                            "peer unreachable — marked offline"
+                        );
+                    }
+                } else {
+                    peer.status = NodeStatus::Degraded;
+                    debug!(
+                        node_id,
+                        failures = entry.consecutive_failures,
+                        "peer health check failed"
+                    );
+                }
+            }
+        }
+        Some(peer.status)
+    }
+
+    /// Probe every known peer's `/healthz` once.
+    pub async fn check_peers(&self, client: &reqwest::Client) {
+        let targets: Vec<(String, String)> = {
+            let peers = self.peers.lock().await;
+            peers
+                .values()
+                .map(|p| (p.node_id.clone(), p.address.clone()))
+                .collect()
+        };
+
+        for (node_id, address) in targets {
+            let url = format!("{}/healthz", address.trim_end_matches('/'));
+            let result = match client.get(&url).send().await {
+                Ok(resp) if resp.status().is_success() => Ok(()),
+                Ok(resp) => Err(format!("HTTP {}", resp.status())),
+                Err(e) => Err(e.to_string()),
+            };
+            self.record_health(&node_id, result).await;
+        }
+    }
+
+    /// Run periodic peer health checks until shutdown.
+    pub async fn run_health_checks(&self, mut shutdown: broadcast::Receiver<()>) {
+        let client = reqwest::Client::builder()
+            .user_agent("safeclaw-federation")
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap_or_default();
+
+        info!(
+            interval_secs = self.health_check_interval.as_secs(),
+            failure_threshold = self.failure_threshold,
+            "federation health checks starting"
+        );
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(self.health_check_interval) => {}
+                _ = shutdown.recv() => break,
+            }
+            self.check_peers(&client).await;
+        }
+    }
+
     /// Apply incoming deltas from a peer (called when receiving sync).
     pub async fn apply_deltas(
         &self,
@@ -222,6 +644,7 @@ mod tests {
             started_at: "2026-01-01T00:00:00Z".to_string(),
             last_heartbeat: "2026-01-01T00:00:00Z".to_string(),
             status: NodeStatus::Online,
+            public_key: String::new(),
         }).await;
 
         let peers = mgr.list_peers().await;
@@ -240,12 +663,201 @@ mod tests {
             started_at: String::new(),
             last_heartbeat: String::new(),
             status: NodeStatus::Online,
+            public_key: String::new(),
         }).await;
 
         mgr.remove_peer("node-b").await;
         assert!(mgr.list_peers().await.is_empty());
     }
 
+    fn peer(node_id: &str, address: &str) -> NodeInfo {
+        NodeInfo {
+            node_id: node_id.to_string(),
+            name: node_id.to_string(),
+            address: address.to_string(),
+            version: "0.1.0".to_string(),
+            started_at: String::new(),
+            last_heartbeat: String::new(),
+            status: NodeStatus::Online,
+            public_key: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_peer_marked_offline() {
+        let mgr = FederationManager::new("node-a", "http://a:3030", true).with_health_checks(30, 3);
+        mgr.register_peer(peer("node-b", "http://b:3030")).await;
+
+        let err = || Err("HTTP 500 Internal Server Error".to_string());
+        assert_eq!(mgr.record_health("node-b", err()).await, Some(NodeStatus::Degraded));
+        assert_eq!(mgr.record_health("node-b", err()).await, Some(NodeStatus::Degraded));
+        assert_eq!(mgr.record_health("node-b", err()).await, Some(NodeStatus::Offline));
+
+        let statuses = mgr.peer_statuses().await;
+        assert_eq!(statuses[0].info.status, NodeStatus::Offline);
+        assert_eq!(statuses[0].health.consecutive_failures, 3);
+        assert!(statuses[0].health.last_error.as_deref().unwrap().contains("500"));
+    }
+
+    #[tokio::test]
+    async fn test_recovering_peer_reactivated() {
+        let mgr = FederationManager::new("node-a", "http://a:3030", true).with_health_checks(30, 1);
+        mgr.register_peer(peer("node-b", "http://b:3030")).await;
+
+        assert_eq!(
+            mgr.record_health("node-b", Err("timeout".into())).await,
+            Some(NodeStatus::Offline)
+        );
+        assert_eq!(mgr.record_health("node-b", Ok(())).await, Some(NodeStatus::Online));
+
+        let statuses = mgr.peer_statuses().await;
+        assert_eq!(statuses[0].health.consecutive_failures, 0);
+        assert!(statuses[0].health.last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_record_health_unknown_peer() {
+        let mgr = FederationManager::new("node-a", "http://a:3030", true);
+        assert!(mgr.record_health("ghost", Ok(())).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_peers_unreachable() {
+        let mgr = FederationManager::new("node-a", "http://a:3030", true).with_health_checks(30, 1);
+        // Port 1 on loopback refuses connections immediately.
+        mgr.register_peer(peer("node-b", "http://127.0.0.1:1")).await;
+
+        mgr.check_peers(&reqwest::Client::new()).await;
+        assert_eq!(mgr.list_peers().await[0].status, NodeStatus::Offline);
+    }
+
+    #[test]
+    fn test_sign_verify_round_trip() {
+        let key = NodeKey::generate();
+        let claim = TaskClaim {
+            task_id: "task-1".into(),
+            claimed_by: "node-a".into(),
+            claimed_at: "2026-01-01T00:00:00Z".into(),
+        };
+        let msg = SignedMessage::sign(&key, "node-a", &claim).unwrap();
+        assert!(msg.verify(&key.public_key()).is_ok());
+
+        let decoded: TaskClaim = serde_json::from_str(&msg.payload).unwrap();
+        assert_eq!(decoded.task_id, "task-1");
+    }
+
+    #[test]
+    fn test_tampered_payload_fails_verification() {
+        let key = NodeKey::generate();
+        let mut msg = SignedMessage::sign(&key, "node-a", &serde_json::json!({"task_id": "t1"})).unwrap();
+        msg.payload = r#"{"task_id":"t2"}"#.to_string();
+        assert!(msg.verify(&key.public_key()).is_err());
+    }
+
+    #[test]
+    fn test_wrong_key_fails_verification() {
+        let msg = SignedMessage::sign(&NodeKey::generate(), "node-a", &"hello").unwrap();
+        assert!(msg.verify(&NodeKey::generate().public_key()).is_err());
+        assert!(msg.verify("not-a-key").is_err());
+    }
+
+    #[test]
+    fn test_stale_message_rejected() {
+        let key = NodeKey::generate();
+        let mut msg = SignedMessage::sign(&key, "node-a", &"hello").unwrap();
+        msg.timestamp = "2020-01-01T00:00:00+00:00".into();
+        // Re-sign with the old timestamp so only the age check fails.
+        let sig = key.signing.sign(&SignedMessage::signing_input(
+            &msg.node_id,
+            &msg.timestamp,
+            &msg.nonce,
+            &msg.payload,
+        ));
+        msg.signature = BASE64.encode(&sig.to_bytes());
+        assert!(msg.verify(&key.public_key()).is_err());
+    }
+
+    #[test]
+    fn test_node_key_persisted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let first = NodeKey::ensure(tmp.path()).unwrap();
+        let second = NodeKey::ensure(tmp.path()).unwrap();
+        assert_eq!(first.public_key(), second.public_key());
+    }
+
+    #[test]
+    fn test_tampered_nonce_fails_verification() {
+        let key = NodeKey::generate();
+        let mut msg = SignedMessage::sign(&key, "node-a", &"hello").unwrap();
+        msg.nonce = "0".repeat(32);
+        assert!(msg.verify(&key.public_key()).is_err());
+        msg.nonce.clear();
+        assert!(msg.verify(&key.public_key()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_from_untrusted_key_rejected() {
+        let a = FederationManager::new("node-a", "http://a:3030", true);
+        let b = FederationManager::new("node-b", "http://b:3030", true);
+
+        assert!(b.accept_heartbeat(&a.sign(&a.local_info()).unwrap()).await.is_err());
+        assert!(b.list_peers().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_from_admin_added_peer_accepted() {
+        let a = FederationManager::new("node-a", "http://a:3030", true);
+        let b = FederationManager::new("node-b", "http://b:3030", true);
+
+        // What federation_add_peer does after an admin adds the peer.
+        b.register_peer(a.local_info()).await;
+        assert!(b.accept_heartbeat(&a.sign(&a.local_info()).unwrap()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_replayed_message_rejected() {
+        let a = FederationManager::new("node-a", "http://a:3030", true);
+        let b = FederationManager::new("node-b", "http://b:3030", true)
+            .with_trusted_keys([a.local_info().public_key]);
+        b.accept_heartbeat(&a.sign(&a.local_info()).unwrap()).await.unwrap();
+
+        let claim = a.sign(&serde_json::json!({"task_id": "t1"})).unwrap();
+        assert!(b.verify::<serde_json::Value>(&claim).await.is_ok());
+        assert!(b.verify::<serde_json::Value>(&claim).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_exchanges_keys() {
+        let a = FederationManager::new("node-a", "http://a:3030", true);
+        let b = FederationManager::new("node-b", "http://b:3030", true)
+            .with_trusted_keys([a.local_info().public_key]);
+
+        // Unsigned-by-a-known-peer messages are rejected before registration.
+        let claim = a.sign(&serde_json::json!({"task_id": "t1"})).unwrap();
+        assert!(b.verify::<serde_json::Value>(&claim).await.is_err());
+
+        let heartbeat = a.sign(&a.local_info()).unwrap();
+        b.accept_heartbeat(&heartbeat).await.unwrap();
+
+        let decoded: serde_json::Value = b.verify(&claim).await.unwrap();
+        assert_eq!(decoded["task_id"], "t1");
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_with_changed_key_rejected() {
+        let a = FederationManager::new("node-a", "http://a:3030", true);
+        let b = FederationManager::new("node-b", "http://b:3030", true)
+            .with_trusted_keys([a.local_info().public_key]);
+        b.accept_heartbeat(&a.sign(&a.local_info()).unwrap()).await.unwrap();
+
+        // Same node_id, different key — an impostor.
+        let mut forged = a.local_info();
+        let impostor_key = NodeKey::generate();
+        forged.public_key = impostor_key.public_key();
+        let msg = SignedMessage::sign(&impostor_key, &forged.node_id, &forged).unwrap();
+        assert!(b.accept_heartbeat(&msg).await.is_err());
+    }
+
     #[test]
     fn test_version_gt() {
         assert!(crate::dashboard::handlers::version_gt("0.2.0", "0.1.0"));
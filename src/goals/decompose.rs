@@ -0,0 +1,262 @@
+//! Turn a free-text objective into a goal with ordered, dependent tasks.
+//!
+//! The agent asks the LLM for a JSON plan, [`parse_plan`] validates it, and
+//! [`persist_plan`] stores it through [`GoalManager`].  Task dependencies in
+//! the plan are zero-based indices of earlier tasks; they are mapped to task
+//! IDs when persisted.
+
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{Goal, GoalManager};
+use crate::error::{Result, SafeAgentError};
+
+/// Built-in decomposition prompt.  `{objective}` and `{max_tasks}` are
+/// substituted before the call.
+pub const DEFAULT_DECOMPOSE_PROMPT: &str = r#"Break the objective below into a goal with an ordered list of concrete, actionable tasks.
+
+Return a JSON object with this exact structure:
+{
+  "title": "short goal title",
+  "description": "what done looks like",
+  "tasks": [
+    {"title": "task title", "description": "what to do", "depends_on": []},
+    {"title": "task title", "description": "what to do", "depends_on": [0]}
+  ]
+}
+
+Rules:
+- Use at most {max_tasks} tasks, in the order they should be done.
+- "depends_on" lists the zero-based indices of earlier tasks that must finish first.
+- Return ONLY valid JSON, no explanation.
+
+Objective:
+{objective}"#;
+
+/// A task in a decomposition plan.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlannedTask {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Indices of earlier tasks in the plan.
+    #[serde(default)]
+    pub depends_on: Vec<usize>,
+}
+
+/// A goal and its tasks as proposed by the LLM.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoalPlan {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tasks: Vec<PlannedTask>,
+}
+
+/// Build the decomposition prompt from a template (empty = built-in).
+pub fn build_prompt(template: &str, objective: &str, max_tasks: usize) -> String {
+    let template = if template.is_empty() {
+        DEFAULT_DECOMPOSE_PROMPT
+    } else {
+        template
+    };
+    template
+        .replace("{max_tasks}", &max_tasks.to_string())
+        .replace("{objective}", objective)
+}
+
+/// Append reflections from similar past goals to a decomposition prompt so
+/// the plan can learn from them.
+pub fn with_lessons(prompt: String, past: &[Goal]) -> String {
+    let lessons: Vec<String> = past
+        .iter()
+        .filter_map(|g| {
+            let reflection = g.reflection.as_deref()?.trim();
+            Some(format!("- {} ({}): {}", g.title, g.status.as_str(), reflection))
+        })
+        .collect();
+    if lessons.is_empty() {
+        return prompt;
+    }
+    format!(
+        "{prompt}\n\nLessons from similar past goals (take them into account):\n{}",
+        lessons.join("\n")
+    )
+}
+
+/// Parse and sanitize an LLM decomposition response.
+///
+/// Accepts bare JSON or JSON inside a markdown fence.  Untitled tasks are
+/// dropped, the list is capped at `max_tasks`, and dependencies that don't
+/// point at an earlier kept task are removed so the result is always
+/// acyclic.
+pub fn parse_plan(response: &str, max_tasks: usize) -> Result<GoalPlan> {
+    let mut plan: GoalPlan = serde_json::from_str(extract_json(response))
+        .map_err(|e| SafeAgentError::Llm(format!("unparseable goal plan: {e}")))?;
+
+    plan.title = plan.title.trim().to_string();
+    if plan.title.is_empty() {
+        return Err(SafeAgentError::Llm("goal plan has no title".into()));
+    }
+
+    // Map original indices to positions after dropping untitled tasks.
+    let mut index_map = Vec::with_capacity(plan.tasks.len());
+    let mut tasks = Vec::new();
+    for task in plan.tasks {
+        if task.title.trim().is_empty() || tasks.len() >= max_tasks {
+            index_map.push(None);
+            continue;
+        }
+        index_map.push(Some(tasks.len()));
+        tasks.push(task);
+    }
+    for (pos, task) in tasks.iter_mut().enumerate() {
+        let mut deps: Vec<usize> = task
+            .depends_on
+            .iter()
+            .filter_map(|&i| index_map.get(i).copied().flatten())
+            .filter(|&dep| dep < pos)
+            .collect();
+        deps.sort_unstable();
+        deps.dedup();
+        if deps.len() != task.depends_on.len() {
+            warn!(task = %task.title, "dropped invalid task dependencies from goal plan");
+        }
+        task.title = task.title.trim().to_string();
+        task.depends_on = deps;
+    }
+
+    if tasks.is_empty() {
+        return Err(SafeAgentError::Llm("goal plan has no tasks".into()));
+    }
+    plan.tasks = tasks;
+    Ok(plan)
+}
+
+/// Store a plan as a goal plus tasks.  Returns the goal ID and the task IDs
+/// in plan order.
+pub async fn persist_plan(
+    mgr: &GoalManager,
+    plan: &GoalPlan,
+    priority: i32,
+) -> Result<(String, Vec<String>)> {
+    let goal_id = mgr
+        .create_goal(&plan.title, &plan.description, priority, None)
+        .await?;
+    let mut task_ids: Vec<String> = Vec::with_capacity(plan.tasks.len());
+    for (i, task) in plan.tasks.iter().enumerate() {
+        let depends_on: Vec<String> = task.depends_on.iter().map(|&d| task_ids[d].clone()).collect();
+        let id = mgr
+            .add_task(&goal_id, &task.title, &task.description, None, &depends_on, i as i32)
+            .await?;
+        task_ids.push(id);
+    }
+    Ok((goal_id, task_ids))
+}
+
+fn extract_json(response: &str) -> &str {
+    let trimmed = response.trim();
+    if let Some(start) = trimmed.find("```") {
+        let after = &trimmed[start + 3..];
+        let after = after.strip_prefix("json").unwrap_or(after);
+        return after.find("```").map_or(after, |end| &after[..end]).trim();
+    }
+    match (trimmed.find('{'), trimmed.rfind('}')) {
+        (Some(start), Some(end)) if end > start => &trimmed[start..=end],
+        _ => trimmed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"Here's a plan:
+```json
+{
+  "title": "Launch the blog",
+  "description": "Blog live with first post",
+  "tasks": [
+    {"title": "Pick a static site generator", "depends_on": []},
+    {"title": "Write the first post", "description": "~800 words", "depends_on": []},
+    {"title": "Deploy", "depends_on": [0, 1]},
+    {"title": "Announce", "depends_on": [2, 3, 7]}
+  ]
+}
+```"#;
+
+    #[test]
+    fn plan_parses_tasks_and_dependencies() {
+        let plan = parse_plan(RESPONSE, 10).unwrap();
+        assert_eq!(plan.title, "Launch the blog");
+        assert_eq!(plan.tasks.len(), 4);
+        assert!(plan.tasks[0].depends_on.is_empty());
+        assert_eq!(plan.tasks[2].depends_on, vec![0, 1]);
+        // Self and out-of-range references are dropped.
+        assert_eq!(plan.tasks[3].depends_on, vec![2]);
+    }
+
+    #[test]
+    fn plan_is_capped_at_max_tasks() {
+        let plan = parse_plan(RESPONSE, 3).unwrap();
+        assert_eq!(plan.tasks.len(), 3);
+        assert_eq!(plan.tasks[2].title, "Deploy");
+    }
+
+    #[test]
+    fn invalid_plans_are_rejected() {
+        assert!(parse_plan("I can't help with that.", 5).is_err());
+        assert!(parse_plan(r#"{"title": "", "tasks": [{"title": "x"}]}"#, 5).is_err());
+        assert!(parse_plan(r#"{"title": "Empty", "tasks": []}"#, 5).is_err());
+    }
+
+    #[test]
+    fn lessons_are_appended_to_prompt() {
+        let goal = |title: &str, reflection: Option<&str>| Goal {
+            id: String::new(),
+            title: title.into(),
+            description: String::new(),
+            status: crate::goals::GoalStatus::Failed,
+            priority: 0,
+            parent_goal_id: None,
+            reflection: reflection.map(String::from),
+            created_at: String::new(),
+            updated_at: String::new(),
+            completed_at: None,
+            last_progress_at: None,
+        };
+        assert_eq!(with_lessons("plan".into(), &[]), "plan");
+        let prompt = with_lessons(
+            "plan".into(),
+            &[goal("Old blog", Some("Deploy earlier.")), goal("Other", None)],
+        );
+        assert!(prompt.starts_with("plan\n\nLessons from similar past goals"));
+        assert!(prompt.ends_with("- Old blog (failed): Deploy earlier."));
+    }
+
+    #[test]
+    fn prompt_template_substitution() {
+        let prompt = build_prompt("", "learn piano", 4);
+        assert!(prompt.contains("at most 4 tasks"));
+        assert!(prompt.ends_with("learn piano"));
+        assert_eq!(build_prompt("Plan {objective} in {max_tasks}", "x", 2), "Plan x in 2");
+    }
+
+    #[tokio::test]
+    async fn persisted_plan_wires_dependencies_to_task_ids() {
+        let mgr = GoalManager::new(crate::db::test_db());
+        let plan = parse_plan(RESPONSE, 10).unwrap();
+        let (goal_id, task_ids) = persist_plan(&mgr, &plan, 2).await.unwrap();
+
+        let goal = mgr.get_goal(&goal_id).await.unwrap();
+        assert_eq!(goal.title, "Launch the blog");
+        assert_eq!(goal.priority, 2);
+
+        let tasks = mgr.get_tasks(&goal_id).await.unwrap();
+        assert_eq!(tasks.len(), 4);
+        assert_eq!(tasks[1].description, "~800 words");
+        assert_eq!(tasks[2].depends_on, vec![task_ids[0].clone(), task_ids[1].clone()]);
+        assert_eq!(tasks[3].depends_on, vec![task_ids[2].clone()]);
+    }
+}
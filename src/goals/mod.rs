@@ -1,3 +1,5 @@
+pub mod decompose;
+
 use std::sync::Arc;
 
 use rusqlite::Connection;
@@ -23,6 +25,20 @@ pub struct Goal {
     pub created_at: String,
     pub updated_at: String,
     pub completed_at: Option<String>,
+    /// When a task of this goal last changed status; drives priority aging.
+    #[serde(default)]
+    pub last_progress_at: Option<String>,
+}
+
+impl Goal {
+    /// When one of the goal's tasks last changed status, or when the goal
+    /// was created if none has.
+    pub fn progressed_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_progress_at
+            .as_deref()
+            .and_then(parse_timestamp)
+            .or_else(|| parse_timestamp(&self.created_at))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -106,6 +122,35 @@ impl TaskStatus {
     }
 }
 
+/// Goal totals for the Prometheus endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalMetrics {
+    pub by_status: Vec<(GoalStatus, i64)>,
+    pub tasks_executed: i64,
+}
+
+impl GoalMetrics {
+    /// The `safe_agent_goals` and executed-task gauges in the Prometheus
+    /// text format.  Deleting a goal drops its tasks, so the task count can
+    /// go down and is not a counter.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::from(
+            "# HELP safe_agent_goals Goals by status.\n\
+             # TYPE safe_agent_goals gauge\n",
+        );
+        for (status, count) in &self.by_status {
+            out.push_str(&format!("safe_agent_goals{{status=\"{}\"}} {count}\n", status.as_str()));
+        }
+        out.push_str(&format!(
+            "\n# HELP safe_agent_goal_tasks_executed Goal tasks on record that ran to completion or failure.\n\
+             # TYPE safe_agent_goal_tasks_executed gauge\n\
+             safe_agent_goal_tasks_executed {}\n\n",
+            self.tasks_executed,
+        ));
+        out
+    }
+}
+
 /// Summary of a goal with task progress.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct GoalSummary {
@@ -120,13 +165,63 @@ pub struct GoalSummary {
 // GoalManager
 // ---------------------------------------------------------------------------
 
+/// Column list matching [`GoalManager::row_to_goal`].
+const GOAL_COLUMNS: &str = "id, title, description, status, priority, parent_goal_id, \
+     reflection, created_at, updated_at, completed_at, last_progress_at";
+
+/// Parse SQLite `datetime('now')` output or RFC 3339 as UTC.
+fn parse_timestamp(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+        .map(|t| t.and_utc())
+        .ok()
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|t| t.with_timezone(&chrono::Utc))
+        })
+}
+
+/// Lowercased words of four or more letters, for rough topic matching.
+fn significant_words(text: &str) -> std::collections::HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 4)
+        .map(str::to_lowercase)
+        .collect()
+}
+
 pub struct GoalManager {
     db: Arc<Mutex<Connection>>,
+    /// Effective-priority points a goal gains per hour without progress.
+    aging_per_hour: f64,
 }
 
 impl GoalManager {
     pub fn new(db: Arc<Mutex<Connection>>) -> Self {
-        Self { db }
+        Self {
+            db,
+            aging_per_hour: 0.0,
+        }
+    }
+
+    /// Enable priority aging so starved goals eventually get scheduled.
+    pub fn with_priority_aging(mut self, per_hour: f64) -> Self {
+        self.aging_per_hour = per_hour.max(0.0);
+        self
+    }
+
+    /// Priority used for scheduling: the goal's priority plus
+    /// `aging_per_hour` for every hour since one of its tasks last changed
+    /// status (or since creation, if none has).
+    pub fn effective_priority(&self, goal: &Goal) -> f64 {
+        self.effective_priority_at(goal, chrono::Utc::now())
+    }
+
+    fn effective_priority_at(&self, goal: &Goal, now: chrono::DateTime<chrono::Utc>) -> f64 {
+        let idle_hours = goal
+            .progressed_at()
+            .map(|t| (now - t).num_seconds().max(0) as f64 / 3600.0)
+            .unwrap_or(0.0);
+        goal.priority as f64 + self.aging_per_hour * idle_hours
     }
 
     // -- Goal CRUD ----------------------------------------------------------
@@ -154,13 +249,11 @@ impl GoalManager {
     pub async fn get_goal(&self, id: &str) -> Result<Goal> {
         let db = self.db.lock().await;
         db.query_row(
-            "SELECT id, title, description, status, priority, parent_goal_id,
-                    reflection, created_at, updated_at, completed_at
-             FROM goals WHERE id = ?1",
+            &format!("SELECT {GOAL_COLUMNS} FROM goals WHERE id = ?1"),
             [id],
             |row| Ok(Self::row_to_goal(row)),
         )
-        .map_err(|_| SafeAgentError::Config(format!("goal '{id}' not found")))
+        .map_err(|_| SafeAgentError::NotFound(format!("goal '{id}'")))
     }
 
     /// List goals with optional status filter.
@@ -176,12 +269,11 @@ impl GoalManager {
         let (sql, params): (String, Vec<Box<dyn rusqlite::types::ToSql>>) = if let Some(s) = status
         {
             (
-                "SELECT id, title, description, status, priority, parent_goal_id,
-                        reflection, created_at, updated_at, completed_at
-                 FROM goals WHERE status = ?1
-                 ORDER BY priority DESC, created_at DESC
-                 LIMIT ?2 OFFSET ?3"
-                    .to_string(),
+                format!(
+                    "SELECT {GOAL_COLUMNS} FROM goals WHERE status = ?1
+                     ORDER BY priority DESC, created_at DESC
+                     LIMIT ?2 OFFSET ?3"
+                ),
                 vec![
                     Box::new(s.to_string()),
                     Box::new(limit as i64),
@@ -190,12 +282,11 @@ impl GoalManager {
             )
         } else {
             (
-                "SELECT id, title, description, status, priority, parent_goal_id,
-                        reflection, created_at, updated_at, completed_at
-                 FROM goals
-                 ORDER BY priority DESC, created_at DESC
-                 LIMIT ?1 OFFSET ?2"
-                    .to_string(),
+                format!(
+                    "SELECT {GOAL_COLUMNS} FROM goals
+                     ORDER BY priority DESC, created_at DESC
+                     LIMIT ?1 OFFSET ?2"
+                ),
                 vec![Box::new(limit as i64), Box::new(offset as i64)],
             )
         };
@@ -237,6 +328,45 @@ impl GoalManager {
         Ok(())
     }
 
+    /// Delete a goal.  Its tasks go with it (`ON DELETE CASCADE`) and
+    /// sub-goals are detached.  Returns false if no such goal existed.
+    pub async fn delete_goal(&self, id: &str) -> Result<bool> {
+        let db = self.db.lock().await;
+        let deleted = db.execute("DELETE FROM goals WHERE id = ?1", [id])?;
+        if deleted > 0 {
+            info!(goal_id = %id, "goal deleted");
+        }
+        Ok(deleted > 0)
+    }
+
+    /// Finished goals with a reflection whose title or description shares
+    /// words with `text`, best match first.  Used to feed lessons from past
+    /// goals into planning.
+    pub async fn similar_reflections(&self, text: &str, limit: usize) -> Result<Vec<Goal>> {
+        let wanted = significant_words(text);
+        if wanted.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(&format!(
+            "SELECT {GOAL_COLUMNS} FROM goals
+             WHERE reflection IS NOT NULL AND reflection != ''
+             ORDER BY updated_at DESC LIMIT 200"
+        ))?;
+        let mut scored: Vec<(usize, Goal)> = stmt
+            .query_map([], |row| Ok(Self::row_to_goal(row)))?
+            .filter_map(|r| r.ok())
+            .filter_map(|goal| {
+                let words = significant_words(&format!("{} {}", goal.title, goal.description));
+                let overlap = wanted.intersection(&words).count();
+                (overlap > 0).then_some((overlap, goal))
+            })
+            .collect();
+        // Stable sort keeps the most recent first among equal scores.
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        Ok(scored.into_iter().take(limit).map(|(_, goal)| goal).collect())
+    }
+
     /// Set the self-reflection text on a goal (called after completion).
     pub async fn set_reflection(&self, id: &str, reflection: &str) -> Result<()> {
         let db = self.db.lock().await;
@@ -278,6 +408,20 @@ impl GoalManager {
         Ok(id)
     }
 
+    /// Check that every ID in `depends_on` is a task of `goal_id`.
+    pub async fn check_task_dependencies(&self, goal_id: &str, depends_on: &[String]) -> Result<()> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare("SELECT 1 FROM goal_tasks WHERE id = ?1 AND goal_id = ?2")?;
+        for dep in depends_on {
+            if !stmt.exists(rusqlite::params![dep, goal_id])? {
+                return Err(SafeAgentError::Config(format!(
+                    "task '{dep}' is not part of goal '{goal_id}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Get all tasks for a goal.
     pub async fn get_tasks(&self, goal_id: &str) -> Result<Vec<GoalTask>> {
         let db = self.db.lock().await;
@@ -316,9 +460,9 @@ impl GoalManager {
             rusqlite::params![status.as_str(), result, completed_at, task_id],
         )?;
 
-        // Also update the parent goal's updated_at
+        // Record progress on the parent goal (resets priority aging)
         db.execute(
-            "UPDATE goals SET updated_at = datetime('now')
+            "UPDATE goals SET updated_at = datetime('now'), last_progress_at = datetime('now')
              WHERE id = (SELECT goal_id FROM goal_tasks WHERE id = ?1)",
             [task_id],
         )?;
@@ -333,23 +477,29 @@ impl GoalManager {
     /// 2. Its status is pending
     /// 3. All its dependencies are completed
     ///
-    /// Returns the highest-priority goal's earliest actionable task.
+    /// Returns the earliest actionable task of the goal with the highest
+    /// [`effective_priority`](Self::effective_priority).
     pub async fn next_actionable_task(&self) -> Result<Option<(Goal, GoalTask)>> {
         let db = self.db.lock().await;
 
-        // Get active goals ordered by priority
-        let mut goal_stmt = db.prepare(
-            "SELECT id, title, description, status, priority, parent_goal_id,
-                    reflection, created_at, updated_at, completed_at
-             FROM goals WHERE status = 'active'
-             ORDER BY priority DESC, created_at ASC",
-        )?;
+        let mut goal_stmt = db.prepare(&format!(
+            "SELECT {GOAL_COLUMNS} FROM goals WHERE status = 'active'
+             ORDER BY priority DESC, created_at ASC"
+        ))?;
 
-        let goals: Vec<Goal> = goal_stmt
+        let mut goals: Vec<Goal> = goal_stmt
             .query_map([], |row| Ok(Self::row_to_goal(row)))?
             .filter_map(|r| r.ok())
             .collect();
 
+        // Order by aged priority; the sort is stable, so ties keep the
+        // priority/age order from the query.
+        let now = chrono::Utc::now();
+        goals.sort_by(|a, b| {
+            self.effective_priority_at(b, now)
+                .total_cmp(&self.effective_priority_at(a, now))
+        });
+
         for goal in goals {
             // Get pending tasks for this goal
             let mut task_stmt = db.prepare(
@@ -442,6 +592,37 @@ impl GoalManager {
         Ok(count)
     }
 
+    /// Goal counts per status (every status, zeros included) and the number
+    /// of tasks that have run to completion or failure.
+    pub async fn metrics(&self) -> Result<GoalMetrics> {
+        let db = self.db.lock().await;
+        let mut by_status: Vec<(GoalStatus, i64)> = [
+            GoalStatus::Active,
+            GoalStatus::Paused,
+            GoalStatus::Completed,
+            GoalStatus::Failed,
+            GoalStatus::Cancelled,
+        ]
+        .into_iter()
+        .map(|status| (status, 0))
+        .collect();
+        let mut stmt = db.prepare("SELECT status, COUNT(*) FROM goals GROUP BY status")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (status, count) = row?;
+            let status = GoalStatus::from_str(&status);
+            if let Some(entry) = by_status.iter_mut().find(|(s, _)| *s == status) {
+                entry.1 += count;
+            }
+        }
+        let tasks_executed: i64 = db.query_row(
+            "SELECT COUNT(*) FROM goal_tasks WHERE status IN ('completed', 'failed')",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(GoalMetrics { by_status, tasks_executed })
+    }
+
     // -- Helpers ------------------------------------------------------------
 
     fn task_counts_for(
@@ -477,6 +658,7 @@ impl GoalManager {
             created_at: row.get(7).unwrap_or_default(),
             updated_at: row.get(8).unwrap_or_default(),
             completed_at: row.get(9).unwrap_or(None),
+            last_progress_at: row.get(10).unwrap_or(None),
         }
     }
 
@@ -579,6 +761,62 @@ mod tests {
         assert_eq!(task.title, "Second");
     }
 
+    #[tokio::test]
+    async fn starved_goal_eventually_outranks_fresh_higher_priority() {
+        let db = db::test_db();
+        let mgr = GoalManager::new(db.clone()).with_priority_aging(0.1);
+
+        let low = mgr.create_goal("Low", "", 1, None).await.unwrap();
+        mgr.add_task(&low, "Low task", "", None, &[], 0).await.unwrap();
+        let high = mgr.create_goal("High", "", 5, None).await.unwrap();
+        mgr.add_task(&high, "High task", "", None, &[], 0).await.unwrap();
+
+        // Both fresh: plain priority wins.
+        let (goal, _) = mgr.next_actionable_task().await.unwrap().unwrap();
+        assert_eq!(goal.id, high);
+
+        // A day without progress isn't enough to close a 4-point gap at 0.1/h...
+        db.lock()
+            .await
+            .execute("UPDATE goals SET created_at = datetime('now', '-24 hours') WHERE id = ?1", [&low])
+            .unwrap();
+        let (goal, _) = mgr.next_actionable_task().await.unwrap().unwrap();
+        assert_eq!(goal.id, high);
+
+        // ...but two days is.
+        db.lock()
+            .await
+            .execute("UPDATE goals SET created_at = datetime('now', '-48 hours') WHERE id = ?1", [&low])
+            .unwrap();
+        let (goal, _) = mgr.next_actionable_task().await.unwrap().unwrap();
+        assert_eq!(goal.id, low);
+        assert!(mgr.effective_priority(&goal) > 5.0);
+
+        // Without aging the high-priority goal always wins.
+        let (goal, _) = GoalManager::new(db).next_actionable_task().await.unwrap().unwrap();
+        assert_eq!(goal.id, high);
+    }
+
+    #[tokio::test]
+    async fn task_progress_resets_aging() {
+        let db = db::test_db();
+        let mgr = GoalManager::new(db.clone()).with_priority_aging(1.0);
+
+        let id = mgr.create_goal("Aging", "", 0, None).await.unwrap();
+        let t1 = mgr.add_task(&id, "One", "", None, &[], 0).await.unwrap();
+        db.lock()
+            .await
+            .execute("UPDATE goals SET created_at = datetime('now', '-10 hours') WHERE id = ?1", [&id])
+            .unwrap();
+        let goal = mgr.get_goal(&id).await.unwrap();
+        assert!((mgr.effective_priority(&goal) - 10.0).abs() < 0.1);
+
+        mgr.update_task_status(&t1, TaskStatus::InProgress, None).await.unwrap();
+        let goal = mgr.get_goal(&id).await.unwrap();
+        assert!(goal.last_progress_at.is_some());
+        assert!(mgr.effective_priority(&goal) < 0.1);
+    }
+
     #[tokio::test]
     async fn goal_auto_completes_when_all_tasks_done() {
         let db = db::test_db();
@@ -597,6 +835,23 @@ mod tests {
         assert_eq!(goal.status, GoalStatus::Completed);
     }
 
+    #[tokio::test]
+    async fn similar_reflections_match_on_shared_words() {
+        let db = db::test_db();
+        let mgr = GoalManager::new(db);
+
+        let blog = mgr.create_goal("Launch the blog", "Static site with first post", 0, None).await.unwrap();
+        mgr.set_reflection(&blog, "Pick the generator first.").await.unwrap();
+        let garden = mgr.create_goal("Plant the garden", "", 0, None).await.unwrap();
+        mgr.set_reflection(&garden, "Start earlier in spring.").await.unwrap();
+        mgr.create_goal("Launch the podcast", "", 0, None).await.unwrap();
+
+        let found = mgr.similar_reflections("launch a new blog", 5).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, blog);
+        assert!(mgr.similar_reflections("the a of", 5).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn reflection() {
         let db = db::test_db();
@@ -619,4 +874,78 @@ mod tests {
         mgr.create_goal("Two", "", 0, None).await.unwrap();
         assert_eq!(mgr.active_goal_count().await.unwrap(), 2);
     }
+
+    #[tokio::test]
+    async fn task_dependencies_must_belong_to_goal() {
+        let mgr = GoalManager::new(db::test_db());
+        let goal = mgr.create_goal("Goal", "", 1, None).await.unwrap();
+        let other = mgr.create_goal("Other", "", 1, None).await.unwrap();
+        let first = mgr.add_task(&goal, "First", "", None, &[], 0).await.unwrap();
+        let foreign = mgr.add_task(&other, "Foreign", "", None, &[], 0).await.unwrap();
+
+        mgr.check_task_dependencies(&goal, std::slice::from_ref(&first)).await.unwrap();
+        let second = mgr
+            .add_task(&goal, "Second", "", None, std::slice::from_ref(&first), 1)
+            .await
+            .unwrap();
+        let tasks = mgr.get_tasks(&goal).await.unwrap();
+        assert_eq!(tasks[1].id, second);
+        assert_eq!(tasks[1].depends_on, vec![first]);
+
+        assert!(mgr.check_task_dependencies(&goal, &[foreign]).await.is_err());
+        assert!(mgr.check_task_dependencies(&goal, &["missing".into()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn delete_goal_cascades_tasks() {
+        let db = db::test_db();
+        let mgr = GoalManager::new(db.clone());
+        let goal = mgr.create_goal("Doomed", "", 1, None).await.unwrap();
+        let child = mgr.create_goal("Child", "", 1, Some(&goal)).await.unwrap();
+        mgr.add_task(&goal, "A", "", None, &[], 0).await.unwrap();
+        mgr.add_task(&goal, "B", "", None, &[], 1).await.unwrap();
+
+        assert!(mgr.delete_goal(&goal).await.unwrap());
+        assert!(mgr.get_goal(&goal).await.is_err());
+        let remaining: i64 = db
+            .lock()
+            .await
+            .query_row("SELECT COUNT(*) FROM goal_tasks WHERE goal_id = ?1", [&goal], |r| r.get(0))
+            .unwrap();
+        assert_eq!(remaining, 0);
+        assert!(mgr.get_goal(&child).await.unwrap().parent_goal_id.is_none());
+
+        assert!(!mgr.delete_goal(&goal).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn metrics_count_goals_per_status_and_executed_tasks() {
+        let mgr = GoalManager::new(db::test_db());
+        mgr.create_goal("One", "", 1, None).await.unwrap();
+        mgr.create_goal("Two", "", 1, None).await.unwrap();
+        let done = mgr.create_goal("Three", "", 1, None).await.unwrap();
+        mgr.update_goal_status(&done, GoalStatus::Completed).await.unwrap();
+        let broken = mgr.create_goal("Four", "", 1, None).await.unwrap();
+        mgr.update_goal_status(&broken, GoalStatus::Failed).await.unwrap();
+
+        let ok = mgr.add_task(&done, "ok", "", None, &[], 0).await.unwrap();
+        let bad = mgr.add_task(&done, "bad", "", None, &[], 1).await.unwrap();
+        mgr.add_task(&done, "todo", "", None, &[], 2).await.unwrap();
+        mgr.update_task_status(&ok, TaskStatus::Completed, None).await.unwrap();
+        mgr.update_task_status(&bad, TaskStatus::Failed, None).await.unwrap();
+
+        let text = mgr.metrics().await.unwrap().render_prometheus();
+        assert!(text.contains("# TYPE safe_agent_goals gauge\n"));
+        assert!(text.contains("safe_agent_goals{status=\"active\"} 2\n"));
+        assert!(text.contains("safe_agent_goals{status=\"paused\"} 0\n"));
+        assert!(text.contains("safe_agent_goals{status=\"completed\"} 1\n"));
+        assert!(text.contains("safe_agent_goals{status=\"failed\"} 1\n"));
+        assert!(text.contains("safe_agent_goals{status=\"cancelled\"} 0\n"));
+        assert!(text.contains("# TYPE safe_agent_goal_tasks_executed gauge\n"));
+        assert!(text.contains("safe_agent_goal_tasks_executed 2\n"));
+
+        // Deleting a goal takes its tasks with it
+        mgr.delete_goal(&done).await.unwrap();
+        assert_eq!(mgr.metrics().await.unwrap().tasks_executed, 0);
+    }
 }
@@ -8,6 +8,7 @@ use crate::config::Config;
 use crate::error::{Result, SafeAgentError};
 use crate::llm::context::GenerateContext;
 use crate::llm::prompts;
+use crate::security::ProcessLimits;
 
 /// LLM engine backed by the Claude Code CLI.
 ///
@@ -24,6 +25,8 @@ pub struct ClaudeEngine {
     locale: String,
     max_turns: u32,
     timeout_secs: u64,
+    /// Resource limits installed on the CLI process (None = unlimited).
+    process_limits: Option<ProcessLimits>,
     /// Working directory for the CLI process.  Set to the data directory so
     /// that the CLI picks up the managed CLAUDE.md file.
     work_dir: std::path::PathBuf,
@@ -69,14 +72,19 @@ impl ClaudeEngine {
             locale: config.locale.clone(),
             max_turns,
             timeout_secs,
+            process_limits: super::cli_process_limits(config, "claude"),
             work_dir: Config::data_dir(),
         })
     }
 
     /// Send a message to Claude and return the plain-text response.
     pub async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
-        let system_prompt = prompts::system_prompt(&self.personality, &self.agent_name, ctx.tools, Some(&self.timezone), Some(&self.locale), ctx.prompt_skills);
+        let system_prompt = prompts::SystemPrompt::new(&self.agent_name, &self.personality)
+            .timezone(&self.timezone)
+            .locale(&self.locale)
+            .build(ctx);
         let mut cmd = Command::new(&self.claude_bin);
+        super::apply_cli_limits(&mut cmd, self.process_limits.as_ref());
 
         cmd.arg("-p")
             .arg("--output-format").arg("text")
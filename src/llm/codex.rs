@@ -8,6 +8,7 @@ use crate::config::Config;
 use crate::error::{Result, SafeAgentError};
 use crate::llm::context::GenerateContext;
 use crate::llm::prompts;
+use crate::security::ProcessLimits;
 
 /// LLM engine backed by the OpenAI Codex CLI.
 ///
@@ -28,6 +29,8 @@ pub struct CodexEngine {
     timezone: String,
     locale: String,
     timeout_secs: u64,
+    /// Resource limits installed on the CLI process (None = unlimited).
+    process_limits: Option<ProcessLimits>,
     /// Working directory for the CLI process.
     work_dir: std::path::PathBuf,
 }
@@ -76,6 +79,7 @@ impl CodexEngine {
             timezone: config.timezone.clone(),
             locale: config.locale.clone(),
             timeout_secs,
+            process_limits: super::cli_process_limits(config, "codex"),
             work_dir: Config::data_dir(),
         })
     }
@@ -83,6 +87,7 @@ impl CodexEngine {
     /// Send a message to Codex and return the plain-text response.
     pub async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
         let mut cmd = Command::new(&self.codex_bin);
+        super::apply_cli_limits(&mut cmd, self.process_limits.as_ref());
 
         cmd.arg("exec")
             .arg("--sandbox").arg("danger-full-access")
@@ -107,7 +112,10 @@ impl CodexEngine {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        let system_prompt = prompts::system_prompt(&self.personality, &self.agent_name, ctx.tools, Some(&self.timezone), Some(&self.locale), ctx.prompt_skills);
+        let system_prompt = prompts::SystemPrompt::new(&self.agent_name, &self.personality)
+            .timezone(&self.timezone)
+            .locale(&self.locale)
+            .build(ctx);
         let prompt = format!(
             "{}\n\n---\n\nThe user says: {}",
             system_prompt, ctx.message
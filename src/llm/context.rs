@@ -1,5 +1,13 @@
+use std::path::PathBuf;
+
 use crate::skills::PromptSkill;
-use crate::tools::ToolRegistry;
+use crate::tools::{ToolCall, ToolRegistry};
+
+/// Rough token count used for context budgeting: about four characters per
+/// token, which errs on the high side for English prose.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
 
 /// Per-call generation context passed to every LlmBackend::generate invocation.
 ///
@@ -13,4 +21,79 @@ pub struct GenerateContext<'a> {
     pub tools: Option<&'a ToolRegistry>,
     /// Prompt skills resolved for this specific request.  May be empty.
     pub prompt_skills: &'a [PromptSkill],
+    /// Overrides the personality the backend was built with.  Set by
+    /// [`LlmEngine`](super::LlmEngine) after a config reload.
+    pub personality: Option<&'a str>,
+    /// The active persona's prompt, added to the identity section.  Set by
+    /// [`LlmEngine`](super::LlmEngine) from the persona the agent switched to.
+    pub persona: Option<&'a str>,
+    /// Images sent with the message.  Backends without vision support get
+    /// [`UNANALYZED_IMAGE_NOTE`] in the message instead.
+    pub attachments: &'a [Attachment],
+}
+
+/// Appended to the message for backends that can't look at attachments.
+pub const UNANALYZED_IMAGE_NOTE: &str = "(image attached, not analyzed)";
+
+/// An inbound image, saved to disk by the messaging handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// Absolute path of the downloaded file.
+    pub path: PathBuf,
+    /// MIME type, e.g. `image/jpeg`.
+    pub mime_type: String,
+}
+
+impl Attachment {
+    pub fn new(path: impl Into<PathBuf>, mime_type: impl Into<String>) -> Self {
+        Self { path: path.into(), mime_type: mime_type.into() }
+    }
+
+    /// The file as a `data:` URL, the form OpenAI-style APIs take images in.
+    pub fn data_url(&self) -> std::io::Result<String> {
+        Ok(format!("data:{};base64,{}", self.mime_type, self.base64()?))
+    }
+
+    /// The file's contents, base64-encoded.
+    pub fn base64(&self) -> std::io::Result<String> {
+        Ok(data_encoding::BASE64.encode(&std::fs::read(&self.path)?))
+    }
+}
+
+impl GenerateContext<'_> {
+    /// The personality to use, falling back to the backend's own.
+    pub fn personality_or<'b>(&'b self, fallback: &'b str) -> &'b str {
+        self.personality.unwrap_or(fallback)
+    }
+
+    /// Whether the caller offered any tools for this request.  Backends
+    /// with native function calling only send tool schemas when this holds.
+    pub fn tools_available(&self) -> bool {
+        self.tools.is_some_and(|t| !t.is_empty())
+    }
+}
+
+/// A backend response, optionally carrying structured tool calls.
+#[derive(Debug, Clone, Default)]
+pub struct LlmResponse {
+    /// Natural-language content of the reply.
+    pub text: String,
+    /// Tool calls returned natively by the backend (e.g. OpenAI-style
+    /// `tool_calls`).  Empty for text-only backends, whose calls are
+    /// parsed out of `text` instead.
+    pub tool_calls: Vec<ToolCall>,
+}
+
+impl LlmResponse {
+    /// A plain-text response with no structured tool calls.
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            tool_calls: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty() && self.tool_calls.is_empty()
+    }
 }
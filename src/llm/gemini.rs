@@ -8,6 +8,7 @@ use crate::config::Config;
 use crate::error::{Result, SafeAgentError};
 use crate::llm::context::GenerateContext;
 use crate::llm::prompts;
+use crate::security::ProcessLimits;
 
 /// LLM engine backed by the Google Gemini CLI.
 ///
@@ -27,6 +28,8 @@ pub struct GeminiEngine {
     timezone: String,
     locale: String,
     timeout_secs: u64,
+    /// Resource limits installed on the CLI process (None = unlimited).
+    process_limits: Option<ProcessLimits>,
     /// Working directory for the CLI process.
     work_dir: std::path::PathBuf,
 }
@@ -63,19 +66,24 @@ impl GeminiEngine {
             timezone: config.timezone.clone(),
             locale: config.locale.clone(),
             timeout_secs,
+            process_limits: super::cli_process_limits(config, "gemini"),
             work_dir: Config::data_dir(),
         })
     }
 
     /// Send a message to Gemini and return the plain-text response.
     pub async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
-        let system_prompt = prompts::system_prompt(&self.personality, &self.agent_name, ctx.tools, Some(&self.timezone), Some(&self.locale), ctx.prompt_skills);
+        let system_prompt = prompts::SystemPrompt::new(&self.agent_name, &self.personality)
+            .timezone(&self.timezone)
+            .locale(&self.locale)
+            .build(ctx);
         let prompt = format!(
             "{}\n\n---\n\nThe user says: {}",
             system_prompt, ctx.message
         );
 
         let mut cmd = Command::new(&self.gemini_bin);
+        super::apply_cli_limits(&mut cmd, self.process_limits.as_ref());
 
         cmd.arg("--prompt").arg(&prompt)
             .arg("--output-format").arg("text")
@@ -15,6 +15,7 @@ use crate::llm::prompts;
 /// every call is dispatched to Tokio's blocking thread pool.
 pub struct LocalEngine {
     chat: Arc<Mutex<ChatEngine>>,
+    system_prompt: String,
     model_path: String,
     personality: String,
     agent_name: String,
@@ -55,14 +56,18 @@ impl LocalEngine {
             SafeAgentError::Llm(format!("failed to load GGUF model: {e}"))
         })?;
 
-        let base_system_prompt = prompts::system_prompt(
-            &config.core_personality,
-            &config.agent_name,
-            None,
-            Some(&config.timezone),
-            Some(&config.locale),
-            &[],
-        );
+        // Baked in at load time, so there is no request context yet.
+        let base_system_prompt = prompts::SystemPrompt::new(&config.agent_name, &config.core_personality)
+            .timezone(&config.timezone)
+            .locale(&config.locale)
+            .build(&GenerateContext {
+                message: "",
+                tools: None,
+                prompt_skills: &[],
+                personality: None,
+                persona: None,
+                attachments: &[],
+            });
 
         info!(
             chat_template = ?engine.chat_template(),
@@ -71,10 +76,11 @@ impl LocalEngine {
             "local model loaded"
         );
 
-        let chat = ChatEngine::new(engine, Some(base_system_prompt));
+        let chat = ChatEngine::new(engine, Some(base_system_prompt.clone()));
 
         Ok(Self {
             chat: Arc::new(Mutex::new(chat)),
+            system_prompt: base_system_prompt,
             model_path,
             personality: config.core_personality.clone(),
             agent_name: config.agent_name.clone(),
@@ -92,6 +98,7 @@ impl LocalEngine {
     pub async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
         let chat = Arc::clone(&self.chat);
         let msg = ctx.message.to_string();
+        prompts::record_sent(&self.system_prompt, &msg);
 
         let response = tokio::task::spawn_blocking(move || {
             let mut engine = chat.lock().map_err(|e| {
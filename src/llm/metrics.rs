@@ -0,0 +1,120 @@
+//! LLM latency and failover metrics for the Prometheus endpoint.
+//!
+//! [`super::LlmEngine`] records every backend attempt as it walks the
+//! failover chain: how long it took, whether the backend answered with
+//! nothing, and which fallback ended up serving the request.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::metrics::{escape_label, Histogram};
+
+#[derive(Debug, Default)]
+struct Totals {
+    /// Request duration per backend key, failed attempts included.
+    durations: BTreeMap<String, Histogram>,
+    /// Requests served by a fallback, keyed by (primary, fallback).
+    failovers: BTreeMap<(String, String), u64>,
+    /// Empty responses per backend key.
+    empty_responses: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct LlmMetrics {
+    totals: Mutex<Totals>,
+}
+
+impl LlmMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one request to `backend` that took `elapsed`.
+    pub fn record_request(&self, backend: &str, elapsed: Duration) {
+        self.totals
+            .lock()
+            .unwrap()
+            .durations
+            .entry(backend.to_string())
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// Record a request that `fallback` served after `primary` failed.
+    pub fn record_failover(&self, primary: &str, fallback: &str) {
+        *self
+            .totals
+            .lock()
+            .unwrap()
+            .failovers
+            .entry((primary.to_string(), fallback.to_string()))
+            .or_default() += 1;
+    }
+
+    /// Record an empty response from `backend`.
+    pub fn record_empty_response(&self, backend: &str) {
+        *self
+            .totals
+            .lock()
+            .unwrap()
+            .empty_responses
+            .entry(backend.to_string())
+            .or_default() += 1;
+    }
+
+    /// Number of requests recorded for `backend`.
+    #[cfg(test)]
+    pub fn request_count(&self, backend: &str) -> u64 {
+        self.totals.lock().unwrap().durations.get(backend).map_or(0, Histogram::count)
+    }
+
+    /// Number of failovers from `primary` to `fallback`.
+    #[cfg(test)]
+    pub fn failover_count(&self, primary: &str, fallback: &str) -> u64 {
+        let key = (primary.to_string(), fallback.to_string());
+        self.totals.lock().unwrap().failovers.get(&key).copied().unwrap_or(0)
+    }
+
+    /// All LLM metrics in the Prometheus text format.  Empty until the
+    /// first request.
+    pub fn render_prometheus(&self) -> String {
+        let totals = self.totals.lock().unwrap();
+        if totals.durations.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from(
+            "# HELP safe_agent_llm_request_duration_seconds LLM request time per backend in seconds.\n\
+             # TYPE safe_agent_llm_request_duration_seconds histogram\n",
+        );
+        for (backend, h) in &totals.durations {
+            let labels = format!("backend=\"{}\"", escape_label(backend));
+            h.write_prometheus(&mut out, "safe_agent_llm_request_duration_seconds", &labels);
+        }
+
+        out.push_str(
+            "\n# HELP safe_agent_llm_failovers_total Requests served by a fallback backend.\n\
+             # TYPE safe_agent_llm_failovers_total counter\n",
+        );
+        for ((primary, fallback), n) in &totals.failovers {
+            let _ = writeln!(
+                out,
+                "safe_agent_llm_failovers_total{{primary=\"{}\",fallback=\"{}\"}} {n}",
+                escape_label(primary),
+                escape_label(fallback),
+            );
+        }
+
+        out.push_str(
+            "\n# HELP safe_agent_llm_empty_responses_total Empty responses per backend.\n\
+             # TYPE safe_agent_llm_empty_responses_total counter\n",
+        );
+        for (backend, n) in &totals.empty_responses {
+            let _ = writeln!(out, "safe_agent_llm_empty_responses_total{{backend=\"{}\"}} {n}", escape_label(backend));
+        }
+        out.push('\n');
+        out
+    }
+}
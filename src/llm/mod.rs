@@ -1,5 +1,6 @@
 pub mod advisor;
 pub mod context;
+pub mod metrics;
 pub mod prompts;
 
 mod aider;
@@ -15,12 +16,13 @@ mod local;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::config::Config;
 use crate::error::{Result, SafeAgentError};
+use crate::security::ProcessLimits;
 
-pub use context::GenerateContext;
+pub use context::{Attachment, GenerateContext, LlmResponse};
 
 // -- Plugin trait -----------------------------------------------------------
 
@@ -31,11 +33,26 @@ pub trait LlmBackend: Send + Sync {
     /// Human-readable name of this backend (e.g. "Claude CLI", "OpenRouter API").
     fn name(&self) -> &str;
 
+    /// Whether the backend can look at image attachments.  The engine
+    /// replaces attachments with a note for backends that can't.
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
     /// Generate a response for the given generation context.
     ///
     /// The context bundles the message, optional tool registry, and any
     /// prompt skills that should be injected into the system prompt.
     async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String>;
+
+    /// Generate a response that may carry structured tool calls.
+    ///
+    /// Backends with native function calling override this; the default
+    /// returns the text from `generate`, leaving tool calls to be parsed
+    /// out of the prose.
+    async fn generate_response(&self, ctx: &GenerateContext<'_>) -> Result<LlmResponse> {
+        Ok(LlmResponse::from_text(self.generate(ctx).await?))
+    }
 }
 
 // -- Plugin registry --------------------------------------------------------
@@ -117,14 +134,19 @@ impl LlmBackend for aider::AiderEngine {
 #[async_trait::async_trait]
 impl LlmBackend for openrouter::OpenRouterEngine {
     fn name(&self) -> &str { "OpenRouter API" }
+    fn supports_vision(&self) -> bool { self.supports_vision() }
     async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
         self.generate(ctx).await
     }
+    async fn generate_response(&self, ctx: &GenerateContext<'_>) -> Result<LlmResponse> {
+        self.generate_response(ctx).await
+    }
 }
 
 #[async_trait::async_trait]
 impl LlmBackend for ollama::OllamaEngine {
     fn name(&self) -> &str { "Ollama" }
+    fn supports_vision(&self) -> bool { self.supports_vision() }
     async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
         self.generate(ctx).await
     }
@@ -141,6 +163,12 @@ impl LlmBackend for local::LocalEngine {
 
 // -- LlmEngine (wraps active backend + plugin registry) ---------------------
 
+/// Keys of the built-in backends, as used in `llm.backend` and
+/// `llm.failover_chain`.
+pub const BACKEND_KEYS: &[&str] = &[
+    "claude", "cline", "codex", "gemini", "aider", "openrouter", "ollama", "local",
+];
+
 /// Unified LLM engine that dispatches to one of the registered backends.
 ///
 /// Built-in backends:
@@ -159,6 +187,14 @@ pub struct LlmEngine {
     chain: Vec<(String, Arc<dyn LlmBackend>)>,
     /// Registry of all available backends (built-in + plugins).
     pub plugins: LlmPluginRegistry,
+    /// Personality set by a config reload; overrides the one each backend
+    /// was built with.  The local backend bakes its system prompt into the
+    /// model context at load time and keeps the startup personality.
+    personality: std::sync::RwLock<Option<String>>,
+    /// Prompt of the persona the agent switched to, if any.
+    persona: std::sync::RwLock<Option<String>>,
+    /// Request latency, failover and empty-response totals.
+    metrics: metrics::LlmMetrics,
 }
 
 impl LlmEngine {
@@ -168,8 +204,7 @@ impl LlmEngine {
     /// non-empty, otherwise falls back to a single-element chain from
     /// `config.llm.backend` (overridable with `LLM_BACKEND` env var).
     ///
-    /// Valid backend keys: `"claude"`, `"cline"`, `"codex"`, `"gemini"`,
-    /// `"aider"`, `"openrouter"`, `"ollama"`, `"local"`.
+    /// Valid backend keys are listed in [`BACKEND_KEYS`].
     pub fn new(config: &Config) -> Result<Self> {
         let mut plugins = LlmPluginRegistry::new();
 
@@ -243,7 +278,36 @@ impl LlmEngine {
         let chain_keys: Vec<&str> = chain.iter().map(|(k, _)| k.as_str()).collect();
         info!(chain = ?chain_keys, "LLM failover chain configured");
 
-        Ok(Self { chain, plugins })
+        Ok(Self {
+            chain,
+            plugins,
+            personality: std::sync::RwLock::new(None),
+            persona: std::sync::RwLock::new(None),
+            metrics: metrics::LlmMetrics::new(),
+        })
+    }
+
+    /// Engine with a single backend and no built-ins, for tests that need
+    /// scripted LLM output.
+    #[cfg(test)]
+    pub(crate) fn with_backend(key: &str, backend: Arc<dyn LlmBackend>) -> Self {
+        Self::with_chain(vec![(key.to_string(), backend)])
+    }
+
+    /// Engine with the given failover chain and no built-ins.
+    #[cfg(test)]
+    pub(crate) fn with_chain(chain: Vec<(String, Arc<dyn LlmBackend>)>) -> Self {
+        let mut plugins = LlmPluginRegistry::new();
+        for (key, backend) in &chain {
+            plugins.register(key, backend.clone());
+        }
+        Self {
+            chain,
+            plugins,
+            personality: std::sync::RwLock::new(None),
+            persona: std::sync::RwLock::new(None),
+            metrics: metrics::LlmMetrics::new(),
+        }
     }
 
     /// List all available backend keys (built-in + plugins).
@@ -256,20 +320,73 @@ impl LlmEngine {
     /// Walks the chain in order: on success returns immediately, on failure
     /// (error or empty response) logs a warning and tries the next backend.
     pub async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
+        let (personality, persona) = (self.personality(), self.persona());
+        let ctx = &GenerateContext {
+            personality: ctx.personality.or(personality.as_deref()),
+            persona: ctx.persona.or(persona.as_deref()),
+            ..*ctx
+        };
+        self.with_failover(
+            |backend| async move {
+                let message = unanalyzed_message(backend.as_ref(), ctx);
+                match message {
+                    Some(ref message) => backend.generate(&GenerateContext { message, attachments: &[], ..*ctx }).await,
+                    None => backend.generate(ctx).await,
+                }
+            },
+            |response: &String| response.trim().is_empty(),
+        )
+        .await
+    }
+
+    /// Like [`generate`](Self::generate), but keeps structured tool calls
+    /// from backends that return them natively.
+    pub async fn generate_response(&self, ctx: &GenerateContext<'_>) -> Result<LlmResponse> {
+        let (personality, persona) = (self.personality(), self.persona());
+        let ctx = &GenerateContext {
+            personality: ctx.personality.or(personality.as_deref()),
+            persona: ctx.persona.or(persona.as_deref()),
+            ..*ctx
+        };
+        self.with_failover(
+            |backend| async move {
+                let message = unanalyzed_message(backend.as_ref(), ctx);
+                match message {
+                    Some(ref message) => {
+                        backend.generate_response(&GenerateContext { message, attachments: &[], ..*ctx }).await
+                    }
+                    None => backend.generate_response(ctx).await,
+                }
+            },
+            LlmResponse::is_empty,
+        )
+        .await
+    }
+
+    async fn with_failover<T, F, Fut>(&self, call: F, is_empty: fn(&T) -> bool) -> Result<T>
+    where
+        F: Fn(Arc<dyn LlmBackend>) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
         let mut last_err = None;
         for (key, backend) in &self.chain {
-            match backend.generate(ctx).await {
-                Ok(response) if !response.trim().is_empty() => {
+            let started = std::time::Instant::now();
+            let result = call(backend.clone()).await;
+            self.metrics.record_request(key, started.elapsed());
+            match result {
+                Ok(response) if !is_empty(&response) => {
                     if key != &self.chain[0].0 {
                         tracing::warn!(
                             primary = %self.chain[0].0,
                             fallback = %key,
                             "LLM failover: primary failed, using fallback"
                         );
+                        self.metrics.record_failover(&self.chain[0].0, key);
                     }
                     return Ok(response);
                 }
                 Ok(_empty) => {
+                    self.metrics.record_empty_response(key);
                     tracing::warn!(backend = %key, "LLM backend returned empty response, trying next");
                     last_err = Some(SafeAgentError::Llm(format!("{key} returned empty response")));
                 }
@@ -282,6 +399,41 @@ impl LlmEngine {
         Err(last_err.unwrap_or_else(|| SafeAgentError::Llm("no backends configured".into())))
     }
 
+    /// Use `prompt` as the active persona from now on, or none.
+    pub fn set_persona(&self, prompt: Option<&str>) {
+        *self.persona.write().unwrap() = prompt.map(str::to_string);
+    }
+
+    /// The active persona's prompt, if any.
+    pub fn persona(&self) -> Option<String> {
+        self.persona.read().unwrap().clone()
+    }
+
+    /// The failover chain as (key, backend), primary first.
+    pub fn chain(&self) -> impl Iterator<Item = (&str, &dyn LlmBackend)> {
+        self.chain.iter().map(|(key, backend)| (key.as_str(), backend.as_ref()))
+    }
+
+    /// Whether any backend in the failover chain can look at images.
+    pub fn supports_vision(&self) -> bool {
+        self.chain.iter().any(|(_, backend)| backend.supports_vision())
+    }
+
+    /// Replace the personality used in system prompts from now on.
+    pub fn set_personality(&self, personality: &str) {
+        *self.personality.write().unwrap() = Some(personality.to_string());
+    }
+
+    /// The personality set by the last config reload, if any.
+    pub fn personality(&self) -> Option<String> {
+        self.personality.read().unwrap().clone()
+    }
+
+    /// Request latency, failover and empty-response totals.
+    pub fn metrics(&self) -> &metrics::LlmMetrics {
+        &self.metrics
+    }
+
     /// Return a human-readable description of the primary backend.
     pub fn backend_info(&self) -> &str {
         self.chain[0].1.name()
@@ -293,6 +445,46 @@ impl LlmEngine {
     }
 }
 
+/// The message to send instead when `ctx` has attachments `backend` can't
+/// see: the original text with [`context::UNANALYZED_IMAGE_NOTE`].
+fn unanalyzed_message(backend: &dyn LlmBackend, ctx: &GenerateContext<'_>) -> Option<String> {
+    if ctx.attachments.is_empty() || backend.supports_vision() {
+        return None;
+    }
+    Some(format!("{}\n\n{}", ctx.message, context::UNANALYZED_IMAGE_NOTE))
+}
+
+/// Resolve the resource limits for a CLI backend from
+/// `llm.process_limits`, falling back to the permissive profile.
+pub(crate) fn cli_process_limits(config: &Config, backend: &str) -> Option<ProcessLimits> {
+    let profile = config
+        .llm
+        .process_limits
+        .get(backend)
+        .map(String::as_str)
+        .unwrap_or("permissive");
+    ProcessLimits::from_profile(profile).unwrap_or_else(|e| {
+        warn!(backend, "{e}; using permissive limits");
+        Some(ProcessLimits::permissive())
+    })
+}
+
+/// Install `limits` on a CLI subprocess as a `pre_exec` hook.  Returns
+/// whether a hook was installed (never on non-Unix platforms).
+pub(crate) fn apply_cli_limits(cmd: &mut tokio::process::Command, limits: Option<&ProcessLimits>) -> bool {
+    #[cfg(unix)]
+    if let Some(limits) = limits.cloned() {
+        // SAFETY: apply_process_limits only calls setrlimit.
+        unsafe {
+            cmd.pre_exec(move || crate::security::apply_process_limits(&limits));
+        }
+        return true;
+    }
+    #[cfg(not(unix))]
+    let _ = (cmd, limits);
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -303,4 +495,129 @@ mod tests {
         assert!(registry.get("test").is_none());
         assert!(registry.list().is_empty());
     }
+
+    #[test]
+    fn cli_limit_profile_is_selectable_per_backend() {
+        let mut config = Config::default();
+        config.llm.process_limits.insert("aider".into(), "skill".into());
+        config.llm.process_limits.insert("codex".into(), "none".into());
+        config.llm.process_limits.insert("gemini".into(), "bogus".into());
+
+        let permissive = ProcessLimits::permissive();
+        assert_eq!(
+            cli_process_limits(&config, "claude").unwrap().max_memory_bytes,
+            permissive.max_memory_bytes
+        );
+        assert_eq!(cli_process_limits(&config, "aider").unwrap().max_processes, 32);
+        assert!(cli_process_limits(&config, "codex").is_none());
+        // Unknown profiles fall back to permissive rather than unlimited.
+        assert_eq!(
+            cli_process_limits(&config, "gemini").unwrap().max_open_files,
+            permissive.max_open_files
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cli_limits_install_pre_exec_hook() {
+        let limits = ProcessLimits {
+            max_open_files: 77,
+            ..ProcessLimits::permissive()
+        };
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg("ulimit -n");
+        assert!(apply_cli_limits(&mut cmd, Some(&limits)));
+
+        let output = cmd.output().await.unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "77");
+
+        let mut unlimited = tokio::process::Command::new("true");
+        assert!(!apply_cli_limits(&mut unlimited, None));
+    }
+
+    /// Returns a fixed reply, or fails when it has none.
+    struct FixedLlm(Option<&'static str>);
+
+    #[async_trait::async_trait]
+    impl LlmBackend for FixedLlm {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn generate(&self, _ctx: &GenerateContext<'_>) -> Result<String> {
+            self.0.map(str::to_string).ok_or_else(|| SafeAgentError::Llm("down".into()))
+        }
+    }
+
+    fn ctx() -> GenerateContext<'static> {
+        GenerateContext { message: "hi", tools: None, prompt_skills: &[], personality: None, persona: None, attachments: &[] }
+    }
+
+    #[tokio::test]
+    async fn generation_records_duration_and_failovers() {
+        let engine = LlmEngine::with_backend("primary", Arc::new(FixedLlm(Some("hello"))));
+        assert_eq!(engine.generate(&ctx()).await.unwrap(), "hello");
+        assert_eq!(engine.metrics().request_count("primary"), 1);
+        assert!(engine
+            .metrics()
+            .render_prometheus()
+            .contains("safe_agent_llm_request_duration_seconds_count{backend=\"primary\"} 1\n"));
+
+        let engine = LlmEngine::with_chain(vec![
+            ("primary".into(), Arc::new(FixedLlm(None)) as Arc<dyn LlmBackend>),
+            ("empty".into(), Arc::new(FixedLlm(Some("  ")))),
+            ("fallback".into(), Arc::new(FixedLlm(Some("saved")))),
+        ]);
+        assert_eq!(engine.generate(&ctx()).await.unwrap(), "saved");
+        assert_eq!(engine.metrics().failover_count("primary", "fallback"), 1);
+        assert_eq!(engine.metrics().request_count("primary"), 1);
+
+        let text = engine.metrics().render_prometheus();
+        assert!(text.contains("safe_agent_llm_failovers_total{primary=\"primary\",fallback=\"fallback\"} 1\n"));
+        assert!(text.contains("safe_agent_llm_empty_responses_total{backend=\"empty\"} 1\n"));
+    }
+
+    /// Records the message and attachment count of each request.
+    struct RecordingLlm {
+        vision: bool,
+        seen: std::sync::Mutex<Vec<(String, usize)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmBackend for RecordingLlm {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn supports_vision(&self) -> bool {
+            self.vision
+        }
+
+        async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
+            self.seen.lock().unwrap().push((ctx.message.to_string(), ctx.attachments.len()));
+            Ok("a cat on a sofa".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn attachments_reach_only_vision_backends() {
+        let attachments = [Attachment::new("/tmp/photo.jpg", "image/jpeg")];
+        let ctx = GenerateContext { message: "look", attachments: &attachments, ..ctx() };
+
+        let vision = Arc::new(RecordingLlm { vision: true, seen: Default::default() });
+        let engine = LlmEngine::with_backend("vision", vision.clone());
+        assert!(engine.supports_vision());
+        engine.generate(&ctx).await.unwrap();
+        assert_eq!(vision.seen.lock().unwrap()[0], ("look".to_string(), 1));
+
+        let text_only = Arc::new(RecordingLlm { vision: false, seen: Default::default() });
+        let engine = LlmEngine::with_backend("text", text_only.clone());
+        assert!(!engine.supports_vision());
+        assert_eq!(engine.generate_response(&ctx).await.unwrap().text, "a cat on a sofa");
+        assert_eq!(
+            text_only.seen.lock().unwrap()[0],
+            ("look\n\n(image attached, not analyzed)".to_string(), 0)
+        );
+    }
 }
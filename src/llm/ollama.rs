@@ -30,6 +30,8 @@ pub struct OllamaEngine {
     locale: String,
     max_tokens: usize,
     temperature: f32,
+    /// The model accepts images.
+    vision: bool,
 }
 
 #[derive(Serialize)]
@@ -53,6 +55,9 @@ struct ChatOptions {
 struct ChatMessage {
     role: String,
     content: String,
+    /// Base64-encoded images, for vision models.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -124,20 +129,31 @@ impl OllamaEngine {
             locale: config.locale.clone(),
             max_tokens: config.llm.max_tokens,
             temperature: config.llm.temperature,
+            vision: config.llm.ollama_vision,
         })
     }
 
+    /// Whether images in the context are sent to the model.
+    pub fn supports_vision(&self) -> bool {
+        self.vision
+    }
+
     pub async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
-        let system_prompt = prompts::system_prompt(
-            &self.personality,
-            &self.agent_name,
-            ctx.tools,
-            Some(&self.timezone),
-            Some(&self.locale),
-            ctx.prompt_skills,
-        );
+        let system_prompt = prompts::SystemPrompt::new(&self.agent_name, &self.personality)
+            .timezone(&self.timezone)
+            .locale(&self.locale)
+            .build(ctx);
 
         let url = format!("{}/api/chat", self.base_url);
+        let images = ctx
+            .attachments
+            .iter()
+            .map(|a| {
+                a.base64().map_err(|e| {
+                    SafeAgentError::Llm(format!("cannot read attachment {}: {e}", a.path.display()))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let body = ChatRequest {
             model: self.model.clone(),
@@ -145,10 +161,12 @@ impl OllamaEngine {
                 ChatMessage {
                     role: "system".to_string(),
                     content: system_prompt,
+                    images: Vec::new(),
                 },
                 ChatMessage {
                     role: "user".to_string(),
                     content: ctx.message.to_string(),
+                    images,
                 },
             ],
             stream: false,
@@ -6,7 +6,8 @@ use tracing::{debug, info, warn};
 
 use crate::config::Config;
 use crate::error::{Result, SafeAgentError};
-use crate::llm::context::GenerateContext;
+use crate::llm::context::{GenerateContext, LlmResponse};
+use crate::tools::ToolCall;
 use crate::llm::prompts;
 
 const DEFAULT_BASE_URL: &str = "https://openrouter.ai/api/v1";
@@ -37,6 +38,10 @@ pub struct OpenRouterEngine {
     site_url: Option<String>,
     /// Optional app name sent as `X-Title` for OpenRouter dashboard.
     app_name: Option<String>,
+    /// The model accepts image content parts.
+    vision: bool,
+    /// The model supports OpenAI-style function calling.
+    native_tools: bool,
 }
 
 // -- OpenAI-compatible request/response types ---
@@ -51,12 +56,16 @@ struct ChatRequest {
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_p: Option<f32>,
+    /// Function definitions for native tool calling.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<serde_json::Value>>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 struct ChatMessage {
     role: String,
-    content: String,
+    /// A string, or an array of content parts when images are attached.
+    content: serde_json::Value,
 }
 
 #[derive(Deserialize)]
@@ -68,7 +77,29 @@ struct ChatResponse {
 
 #[derive(Deserialize)]
 struct ChatChoice {
-    message: ChatMessage,
+    message: ResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    /// Null when the model only returns tool calls.
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<NativeToolCall>>,
+}
+
+#[derive(Deserialize)]
+struct NativeToolCall {
+    function: NativeFunction,
+}
+
+#[derive(Deserialize)]
+struct NativeFunction {
+    name: String,
+    /// JSON-encoded arguments object.
+    #[serde(default)]
+    arguments: String,
 }
 
 #[derive(Deserialize)]
@@ -191,12 +222,38 @@ impl OpenRouterEngine {
             top_p,
             site_url,
             app_name,
+            vision: config.llm.openrouter_vision,
+            native_tools: config.llm.openrouter_native_tools,
         })
     }
 
+    /// Whether images in the context are sent to the model.
+    pub fn supports_vision(&self) -> bool {
+        self.vision
+    }
+
     /// Send a message to OpenRouter and return the plain-text response.
     pub async fn generate(&self, ctx: &GenerateContext<'_>) -> Result<String> {
-        let system_prompt = prompts::system_prompt(&self.personality, &self.agent_name, ctx.tools, Some(&self.timezone), Some(&self.locale), ctx.prompt_skills);
+        Ok(self.request(ctx, None).await?.text)
+    }
+
+    /// Send a message with the available tools declared natively, so
+    /// tool calls come back as structured `tool_calls` rather than prose.
+    /// Models without function calling get no `tools` and fall back to the
+    /// `tool_call` blocks described in the system prompt.
+    pub async fn generate_response(&self, ctx: &GenerateContext<'_>) -> Result<LlmResponse> {
+        self.request(ctx, native_tool_schemas(self.native_tools, ctx)).await
+    }
+
+    async fn request(
+        &self,
+        ctx: &GenerateContext<'_>,
+        tools: Option<Vec<serde_json::Value>>,
+    ) -> Result<LlmResponse> {
+        let system_prompt = prompts::SystemPrompt::new(&self.agent_name, &self.personality)
+            .timezone(&self.timezone)
+            .locale(&self.locale)
+            .build(ctx);
         let url = format!("{}/chat/completions", self.base_url);
 
         let body = ChatRequest {
@@ -204,16 +261,17 @@ impl OpenRouterEngine {
             messages: vec![
                 ChatMessage {
                     role: "system".to_string(),
-                    content: system_prompt,
+                    content: system_prompt.into(),
                 },
                 ChatMessage {
                     role: "user".to_string(),
-                    content: ctx.message.to_string(),
+                    content: user_content(ctx)?,
                 },
             ],
             max_tokens: Some(self.max_tokens),
             temperature: Some(self.temperature),
             top_p: Some(self.top_p),
+            tools,
         };
 
         debug!(
@@ -278,17 +336,11 @@ impl OpenRouterEngine {
             );
         }
 
-        let response = chat_resp
-            .choices
-            .into_iter()
-            .next()
-            .map(|c| c.message.content)
-            .unwrap_or_default()
-            .trim()
-            .to_string();
+        let response = into_llm_response(chat_resp);
 
         info!(
-            response_len = response.len(),
+            response_len = response.text.len(),
+            tool_calls = response.tool_calls.len(),
             model = %self.model,
             "OpenRouter response received"
         );
@@ -302,3 +354,136 @@ impl OpenRouterEngine {
         Ok(response)
     }
 }
+
+/// Tool schemas to declare natively, if the model supports function calling
+/// and the caller offered tools.
+fn native_tool_schemas(enabled: bool, ctx: &GenerateContext<'_>) -> Option<Vec<serde_json::Value>> {
+    ctx.tools
+        .filter(|_| enabled && ctx.tools_available())
+        .map(|registry| registry.function_schemas())
+}
+
+/// The user message: plain text, or text followed by `image_url` parts.
+fn user_content(ctx: &GenerateContext<'_>) -> Result<serde_json::Value> {
+    if ctx.attachments.is_empty() {
+        return Ok(ctx.message.into());
+    }
+    let mut parts = vec![serde_json::json!({ "type": "text", "text": ctx.message })];
+    for attachment in ctx.attachments {
+        let url = attachment.data_url().map_err(|e| {
+            SafeAgentError::Llm(format!("cannot read attachment {}: {e}", attachment.path.display()))
+        })?;
+        parts.push(serde_json::json!({ "type": "image_url", "image_url": { "url": url } }));
+    }
+    Ok(parts.into())
+}
+
+/// Convert the first choice of a chat completion into an [`LlmResponse`].
+/// Native tool calls whose arguments aren't a JSON object are dropped.
+fn into_llm_response(chat_resp: ChatResponse) -> LlmResponse {
+    let Some(choice) = chat_resp.choices.into_iter().next() else {
+        return LlmResponse::default();
+    };
+    let tool_calls = choice
+        .message
+        .tool_calls
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|call| {
+            let args = if call.function.arguments.trim().is_empty() {
+                "{}"
+            } else {
+                call.function.arguments.as_str()
+            };
+            match serde_json::from_str::<serde_json::Value>(args) {
+                Ok(params) if params.is_object() => Some(ToolCall {
+                    tool: call.function.name,
+                    params,
+                    reasoning: String::new(),
+                }),
+                _ => {
+                    warn!(tool = %call.function.name, args = %args, "invalid native tool call arguments");
+                    None
+                }
+            }
+        })
+        .collect();
+    LlmResponse {
+        text: choice.message.content.unwrap_or_default().trim().to_string(),
+        tool_calls,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_tool_calls_are_structured() {
+        let body = r#"{
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [
+                        {"id": "call_1", "type": "function",
+                         "function": {"name": "exec", "arguments": "{\"command\": \"ls -la\"}"}},
+                        {"id": "call_2", "type": "function",
+                         "function": {"name": "web_search", "arguments": "not json"}}
+                    ]
+                }
+            }]
+        }"#;
+        let resp = into_llm_response(serde_json::from_str(body).unwrap());
+        assert!(resp.text.is_empty());
+        assert_eq!(resp.tool_calls.len(), 1);
+        assert_eq!(resp.tool_calls[0].tool, "exec");
+        assert_eq!(resp.tool_calls[0].params["command"], "ls -la");
+    }
+
+    #[test]
+    fn plain_content_has_no_tool_calls() {
+        let body = r#"{"choices": [{"message": {"role": "assistant", "content": "  Hello!  "}}]}"#;
+        let resp = into_llm_response(serde_json::from_str(body).unwrap());
+        assert_eq!(resp.text, "Hello!");
+        assert!(resp.tool_calls.is_empty());
+    }
+
+    #[test]
+    fn tools_sent_only_when_model_supports_them() {
+        let mut registry = crate::tools::ToolRegistry::new();
+        registry.register(Box::new(crate::tools::cron::CronTool));
+        let ctx = GenerateContext {
+            message: "hi",
+            tools: Some(&registry),
+            prompt_skills: &[],
+            personality: None,
+            persona: None,
+            attachments: &[],
+        };
+        assert_eq!(native_tool_schemas(true, &ctx).map(|t| t.len()), Some(1));
+        assert!(native_tool_schemas(false, &ctx).is_none());
+
+        let empty = crate::tools::ToolRegistry::new();
+        assert!(native_tool_schemas(true, &GenerateContext { tools: Some(&empty), ..ctx }).is_none());
+    }
+
+    #[test]
+    fn attachments_become_image_parts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("photo.png");
+        std::fs::write(&path, b"png").unwrap();
+        let attachments = [crate::llm::context::Attachment::new(&path, "image/png")];
+        let ctx = GenerateContext {
+            message: "what is this?",
+            tools: None,
+            prompt_skills: &[],
+            personality: None,
+            persona: None,
+            attachments: &attachments,
+        };
+        let content = user_content(&ctx).unwrap();
+        assert_eq!(content[0]["text"], "what is this?");
+        assert_eq!(content[1]["image_url"]["url"], "data:image/png;base64,cG5n");
+    }
+}
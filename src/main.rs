@@ -1,6 +1,7 @@
 mod acme;
 mod agent;
 mod approval;
+mod backup;
 mod config;
 mod crypto;
 mod dashboard;
@@ -10,8 +11,10 @@ mod federation;
 mod goals;
 mod installer;
 mod llm;
+mod mcp;
 mod memory;
 mod messaging;
+mod metrics;
 mod security;
 mod skills;
 mod tools;
@@ -57,20 +60,47 @@ async fn main() {
         .init();
 
     // Load config
-    let config_path = args
-        .iter()
-        .position(|a| a == "--config")
-        .and_then(|i| args.get(i + 1))
-        .map(PathBuf::from);
-
-    let config = match Config::load(config_path.as_deref()) {
+    let path_arg = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(PathBuf::from)
+    };
+    let config_path = path_arg("--config");
+    let backup_path = path_arg("--backup");
+    let restore_path = path_arg("--restore");
+
+    // --check reports validation problems itself instead of refusing to
+    // start, and backups shouldn't depend on the config being valid.
+    let check = args.iter().any(|a| a == "--check");
+    let loaded = if check || backup_path.is_some() || restore_path.is_some() {
+        Config::load_unvalidated(config_path.as_deref())
+    } else {
+        Config::load(config_path.as_deref())
+    };
+    let config = match loaded {
         Ok(c) => c,
         Err(e) => {
             error!("failed to load config: {e}");
-            return;
+            std::process::exit(1);
         }
     };
 
+    // Handle --backup / --restore before anything touches the data dir
+    let config_file = config.source_path.clone().unwrap_or_else(Config::default_config_path);
+    let backup_result = match (&backup_path, &restore_path) {
+        (Some(out), _) => Some(backup::create(&Config::data_dir(), &config_file, out)),
+        (None, Some(archive)) => Some(backup::restore(archive, &Config::data_dir(), &config_file)),
+        (None, None) => None,
+    };
+    if let Some(result) = backup_result {
+        if let Err(e) = result {
+            error!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     info!(
         agent_name = %config.agent_name,
         dashboard = %config.dashboard_bind,
@@ -81,7 +111,7 @@ async fn main() {
     // Set up sandboxed filesystem
     let data_dir = Config::data_dir();
     let sandbox = match SandboxedFs::new(data_dir.clone()) {
-        Ok(s) => s,
+        Ok(s) => s.with_quota(config.security.sandbox_quota_bytes),
         Err(e) => {
             error!("failed to initialize sandbox: {e}");
             return;
@@ -89,11 +119,12 @@ async fn main() {
     };
     info!(root = %sandbox.root().display(), "sandbox initialized");
 
-    // Apply kernel-level Landlock filesystem sandbox (Linux 5.13+).
-    // Skipped when NO_JAIL=1 — the container/deployment already provides
-    // isolation so the extra restriction just blocks legitimate binaries.
+    // Apply kernel-level Landlock filesystem sandbox (Linux 5.13+) and the
+    // seccomp syscall filter.  Skipped when NO_JAIL=1 — the container/deployment
+    // already provides isolation so the extra restriction just blocks
+    // legitimate binaries.
     if std::env::var("NO_JAIL").as_deref() == Ok("1") {
-        info!("landlock sandbox skipped (NO_JAIL=1)");
+        info!("landlock and seccomp sandbox skipped (NO_JAIL=1)");
     } else {
         let config_dir = dirs::config_dir()
             .unwrap_or_else(|| std::path::PathBuf::from(".config"))
@@ -102,6 +133,14 @@ async fn main() {
             Ok(()) => {}
             Err(e) => warn!("landlock sandbox not applied: {e}"),
         }
+
+        // Seccomp syscall filter — inherited by every skill and tool subprocess.
+        if config.security.seccomp {
+            match crate::security::apply_seccomp(&config.security.seccomp_deny) {
+                Ok(()) => {}
+                Err(e) => warn!("seccomp filter not applied: {e}"),
+            }
+        }
     }
 
     // Initialize trash system
@@ -149,11 +188,21 @@ async fn main() {
     let db = Arc::new(Mutex::new(db));
 
     // Handle --check
-    if args.iter().any(|a| a == "--check") {
-        run_checks(&config, &sandbox).await;
+    if check {
+        if !run_checks(&config, &sandbox).await {
+            std::process::exit(1);
+        }
         return;
     }
 
+    // Sessions persist in the database; reap orphans left from before
+    if config.sessions.enabled {
+        match tools::sessions::restore_sessions(&db, config.sessions.max_idle_hours).await {
+            Ok(active) => info!(active, "sessions restored"),
+            Err(e) => warn!("failed to restore sessions: {e}"),
+        }
+    }
+
     // Build the tool registry
     let tool_registry = build_tool_registry(&config, &data_dir);
     info!(tools = tool_registry.len(), "tool registry initialized");
@@ -163,11 +212,20 @@ async fn main() {
 
     // ----- Build the MessagingManager -----
     let mut msg_manager = messaging::MessagingManager::new();
+    msg_manager.set_outbox(messaging::outbox::Outbox::new(db.clone()));
+    match config.quiet_hours.window() {
+        Ok(Some(quiet)) => {
+            let tz = config.timezone.parse().unwrap_or(chrono_tz::UTC);
+            msg_manager.set_quiet_hours(Some(quiet), tz);
+        }
+        Ok(None) => {}
+        Err(e) => warn!("quiet hours ignored: {e}"),
+    }
 
     // Register Telegram backend (if enabled)
     let telegram_backend: Option<Arc<messaging::telegram::TelegramBackend>> =
         if config.telegram.enabled {
-            match config::Config::telegram_bot_token() {
+            match config.telegram_bot_token().await {
                 Ok(token) => {
                     let bot = teloxide::Bot::new(token);
                     let backend = Arc::new(messaging::telegram::TelegramBackend::new(bot));
@@ -178,6 +236,11 @@ async fn main() {
                         .map(|id| id.to_string())
                         .unwrap_or_default();
                     msg_manager.register(backend.clone(), primary_channel);
+                    if config.telegram.notify_all_chats {
+                        for id in config.telegram.allowed_chat_ids.iter().skip(1) {
+                            msg_manager.register_channel("telegram", &id.to_string());
+                        }
+                    }
                     Some(backend)
                 }
                 Err(e) => {
@@ -226,7 +289,7 @@ async fn main() {
 
     // Register Twilio SMS backend (if enabled)
     if config.twilio.enabled {
-        match config::Config::twilio_credentials() {
+        match config.twilio_credentials().await {
             Ok((sid, token)) => {
                 let backend = Arc::new(messaging::twilio::TwilioBackend::new(
                     sid,
@@ -266,8 +329,8 @@ async fn main() {
     }
 
     // Register Discord backend (if enabled)
-    if config.discord.enabled {
-        match std::env::var("DISCORD_BOT_TOKEN") {
+    let discord_token = if config.discord.enabled {
+        match config.discord_bot_token().await {
             Ok(token) => {
                 let http = Arc::new(serenity::all::Http::new(&token));
                 let backend = Arc::new(messaging::discord::DiscordBackend::new(http));
@@ -279,12 +342,16 @@ async fn main() {
                     .unwrap_or_default();
                 msg_manager.register(backend, primary_channel);
                 info!("Discord backend registered");
+                Some(token)
             }
-            Err(_) => {
-                error!("DISCORD_BOT_TOKEN not set but discord.enabled = true");
+            Err(e) => {
+                error!("discord.enabled = true but no bot token: {e}");
+                None
             }
         }
-    }
+    } else {
+        None
+    };
 
     // Register Signal bridge backend (if enabled)
     if config.signal.enabled {
@@ -360,10 +427,8 @@ async fn main() {
     };
 
     // Start Discord gateway (if enabled)
-    let _discord_shutdown = if config.discord.enabled
-        && std::env::var("DISCORD_BOT_TOKEN").is_ok()
-    {
-        match messaging::discord::start(config.discord.clone(), agent.clone()).await {
+    let _discord_shutdown = if let Some(token) = discord_token {
+        match messaging::discord::start(config.discord.clone(), token, agent.clone()).await {
             Ok(tx) => {
                 info!("discord bot started");
                 Some(tx)
@@ -398,7 +463,7 @@ async fn main() {
             .and_then(|p| p.parse::<u16>().ok())
             .unwrap_or(3030);
 
-        let mgr = tunnel::TunnelManager::start(&config.tunnel, dash_port).await;
+        let mgr = tunnel::TunnelManager::start(&config.tunnel, dash_port, &data_dir).await;
         let url = tunnel::shared_url(&mgr);
 
         // Set TUNNEL_URL in the current process so skills inherit it.
@@ -407,10 +472,9 @@ async fn main() {
         tokio::spawn(async move {
             let mut rx = mgr.url_receiver();
             loop {
-                if rx.changed().await.is_err() {
-                    break;
-                }
-                if let Some(ref u) = *rx.borrow() {
+                // A static domain is published before the provider
+                // connects, so handle the current value before waiting.
+                if let Some(ref u) = *rx.borrow_and_update() {
                     // SAFETY: We set these env vars before any skill
                     // processes are spawned and only from this single
                     // task, so there are no concurrent readers.
@@ -420,6 +484,9 @@ async fn main() {
                     }
                     info!(public_url = %u, "TUNNEL_URL set");
                 }
+                if rx.changed().await.is_err() {
+                    break;
+                }
             }
             // Keep mgr alive so ngrok doesn't exit.
             drop(mgr);
@@ -480,6 +547,66 @@ async fn main() {
         })
     };
 
+    // Serve the tools to MCP clients (if enabled)
+    if config.mcp.enabled {
+        let agent = agent.clone();
+        let mcp_config = config.mcp.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            if let Err(e) = mcp::serve(mcp_config, agent, shutdown_rx).await {
+                error!("MCP server error: {e}");
+            }
+        });
+    }
+
+    // Start federation peer health checks (if enabled)
+    if agent.federation.is_enabled() {
+        let agent = agent.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            agent.federation.run_health_checks(shutdown_rx).await;
+        });
+    }
+
+    // Retry undelivered outbound messages
+    let outbox_handle = {
+        let messaging = messaging.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            messaging.run_outbox(shutdown_rx).await;
+        })
+    };
+
+    // Start scheduled memory consolidation
+    {
+        let agent = agent.clone();
+        let shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            agent.run_memory_consolidation(shutdown_rx).await;
+        });
+    }
+
+    // Reload hot-swappable config settings on SIGHUP
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let agent = agent.clone();
+        match signal(SignalKind::hangup()) {
+            Ok(mut hangups) => {
+                tokio::spawn(async move {
+                    while hangups.recv().await.is_some() {
+                        info!("SIGHUP received, reloading config");
+                        if let Err(e) = agent.reload_config().await {
+                            error!("config reload failed: {e}");
+                        }
+                    }
+                });
+            }
+            Err(e) => warn!("SIGHUP handler not installed: {e}"),
+        }
+    }
+
     // Start the agent loop
     let agent_handle = {
         let agent = agent.clone();
@@ -489,6 +616,8 @@ async fn main() {
         })
     };
 
+    messaging.announce_online(&config).await;
+
     info!("safeclaw is running — press Ctrl+C to stop");
 
     // Wait for shutdown signal
@@ -497,10 +626,30 @@ async fn main() {
         .expect("failed to listen for ctrl+c");
 
     info!("shutdown signal received, stopping...");
+
+    // Backends and the outbox are still up, so the notice can go out
+    messaging.announce_offline(&config).await;
+
+    // Stop taking new ticks and messages; give the ones in progress the
+    // grace period to finish before everything else is told to stop.
+    let grace = std::time::Duration::from_secs(config.shutdown_grace_secs);
+    agent.drain(grace).await;
     let _ = shutdown_tx.send(());
 
-    // Wait for tasks to finish
-    let _ = tokio::join!(dashboard_handle, agent_handle);
+    // Wait for tasks to finish: the agent loop stops skills, the outbox
+    // makes a final delivery pass.  Don't hang on either past the grace
+    // period.
+    let stopped = tokio::time::timeout(grace, async {
+        let _ = tokio::join!(dashboard_handle, agent_handle, outbox_handle);
+    })
+    .await;
+    if stopped.is_err() {
+        warn!("tasks still running after the grace period, exiting anyway");
+        // The agent loop never got to stop the skills itself
+        if let Ok(mut sm) = agent.skill_manager.try_lock() {
+            sm.shutdown().await;
+        }
+    }
     info!("safeclaw stopped");
 }
 
@@ -509,27 +658,40 @@ fn build_tool_registry(config: &Config, data_dir: &std::path::Path) -> ToolRegis
     use crate::tools::*;
 
     let mut registry = ToolRegistry::new();
+    registry.set_output_limits(
+        config.tools.max_output_bytes,
+        config.tools.max_output_bytes_per_tool.clone(),
+    );
 
     // Always register core tools
     if config.tools.exec.enabled {
         registry.register(Box::new(exec::ExecTool::new(config.tools.exec.timeout_secs)));
     }
 
-    registry.register(Box::new(process::ProcessTool::new()));
+    registry.register(Box::new(process::ProcessTool::new(config.tools.exec.enabled)));
     registry.register(Box::new(file::ReadFileTool));
     registry.register(Box::new(file::WriteFileTool));
     registry.register(Box::new(file::EditFileTool));
     registry.register(Box::new(file::DeleteFileTool));
     registry.register(Box::new(file::ApplyPatchTool));
+    registry.register(Box::new(structured::StructuredEditTool));
 
     if config.tools.web.enabled {
-        registry.register(Box::new(web::WebSearchTool::new(config.tools.web.max_results)));
-        registry.register(Box::new(web::WebFetchTool));
+        match search::provider_from_config(&config.tools.web) {
+            Ok(provider) => registry.register(Box::new(
+                web::WebSearchTool::new(config.tools.web.max_results, provider)
+                    .with_cache_ttl(std::time::Duration::from_secs(
+                        config.tools.web.search_cache_ttl_secs,
+                    )),
+            )),
+            Err(e) => error!("web_search disabled: {e}"),
+        }
+        registry.register(Box::new(web::WebFetchTool::new(&config.tools.web)));
     }
 
     if config.tools.browser.enabled {
         registry.register(Box::new(browser::BrowserTool::new(
-            config.tools.browser.headless,
+            &config.tools.browser,
             data_dir.to_path_buf(),
         )));
     }
@@ -547,24 +709,51 @@ fn build_tool_registry(config: &Config, data_dir: &std::path::Path) -> ToolRegis
 
     if config.tools.cron.enabled {
         registry.register(Box::new(cron::CronTool::new()));
+        registry.register(Box::new(reminder::ReminderTool::new()));
+    }
+
+    if config.tools.git.enabled {
+        registry.register(Box::new(git::GitTool::new(config.tools.git.timeout_secs)));
+    }
+
+    if config.tools.http.enabled && !config.tools.http.allowed_hosts.is_empty() {
+        registry.register(Box::new(http::HttpTool::new(&config.tools.http)));
     }
 
+    if config.tools.image.enabled {
+        registry.register(Box::new(image::ImageTool::new(&config.tools.image)));
+    }
+
+    registry.register(Box::new(capabilities::CapabilitiesTool));
     registry.register(Box::new(goal::GoalTool::new()));
-    registry.register(Box::new(image::ImageTool::new()));
     registry.register(Box::new(memory::MemorySearchTool));
     registry.register(Box::new(memory::MemoryGetTool));
+    registry.register(Box::new(memory::MemoryForgetTool));
     registry.register(Box::new(knowledge::KnowledgeGraphTool::new()));
+    registry.register(Box::new(scratch::ScratchSetTool));
+    registry.register(Box::new(scratch::ScratchGetTool));
+    registry.register(Box::new(scratch::ScratchListTool));
 
     registry
 }
 
-async fn run_checks(config: &Config, _sandbox: &SandboxedFs) {
+/// Report on the config and the selected backend.  Returns whether the
+/// config passed validation.
+async fn run_checks(config: &Config, _sandbox: &SandboxedFs) -> bool {
     info!("running pre-flight checks...");
 
     let backend = std::env::var("LLM_BACKEND")
         .unwrap_or_else(|_| config.llm.backend.clone());
 
-    info!("config: OK");
+    let issues = config.validate();
+    if issues.is_empty() {
+        info!("config: OK");
+    } else {
+        error!("config: {} problem(s)", issues.len());
+        for issue in &issues {
+            error!("  {issue}");
+        }
+    }
     info!("  agent_name: {}", config.agent_name);
     info!("  dashboard_bind: {}", config.dashboard_bind);
     info!("  llm_backend: {}", backend);
@@ -687,7 +876,7 @@ async fn run_checks(config: &Config, _sandbox: &SandboxedFs) {
     }
 
     if config.telegram.enabled {
-        match Config::telegram_bot_token() {
+        match config.telegram_bot_token().await {
             Ok(_) => info!("TELEGRAM_BOT_TOKEN: set"),
             Err(_) => error!("TELEGRAM_BOT_TOKEN: NOT SET (telegram enabled)"),
         }
@@ -822,6 +1011,7 @@ async fn run_checks(config: &Config, _sandbox: &SandboxedFs) {
         info!("ACME TLS: disabled");
     }
 
+    issues.is_empty()
 }
 
 // ---------------------------------------------------------------------------
@@ -894,6 +1084,9 @@ OPTIONS:
     --config <PATH>     Path to config file (default: ~/.config/safeclaw/config.toml)
     --default-config    Print default config to stdout and exit
     --check             Validate config and connectivity, then exit
+    --backup <PATH>     Write the database, data dir and config (secrets
+                        excluded) to a .tar.gz, then exit
+    --restore <PATH>    Unpack a backup into an empty data dir, then exit
     -h, --help          Print this help message
 
 LLM BACKEND:
@@ -0,0 +1,392 @@
+//! Model Context Protocol server: the agent's tools for MCP clients.
+//!
+//! Speaks JSON-RPC 2.0 over HTTP (`POST /mcp`, the streamable-HTTP
+//! transport without server-initiated streams).  `tools/list` advertises the
+//! [`ToolRegistry`](crate::tools::ToolRegistry) with each tool's parameter
+//! schema; `tools/call` validates the arguments against that schema and
+//! runs the call through [`Agent::call_tool_external`], so the blocked-tool,
+//! capability, rate-limit, approval and 2FA gates all apply.
+//!
+//! Requests must be `Content-Type: application/json`, and any `Origin`
+//! header must be a loopback one, so a web page can't reach the server
+//! through the user's browser — by a cross-site form post or by DNS
+//! rebinding — even when no token is required.
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::agent::{Agent, ExternalOutcome};
+use crate::config::McpConfig;
+use crate::error::{Result, SafeAgentError};
+use crate::tools::ToolCall;
+
+/// Protocol revisions we can speak, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Clone)]
+struct McpState {
+    agent: Arc<Agent>,
+    /// Bearer token clients must present, from `MCP_TOKEN`.
+    token: Option<String>,
+}
+
+/// Serve MCP on `config.bind` until shutdown.
+pub async fn serve(config: McpConfig, agent: Arc<Agent>, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+    let token = std::env::var("MCP_TOKEN").ok().filter(|t| !t.is_empty());
+    let listener = tokio::net::TcpListener::bind(&config.bind)
+        .await
+        .map_err(|e| SafeAgentError::Config(format!("failed to bind {}: {e}", config.bind)))?;
+    let loopback = listener.local_addr().map(|a| a.ip().is_loopback()).unwrap_or(false);
+    if token.is_none() && !loopback {
+        return Err(SafeAgentError::Config(format!(
+            "MCP_TOKEN must be set to serve MCP on non-loopback address {}",
+            config.bind
+        )));
+    }
+
+    info!(bind = %config.bind, auth = token.is_some(), "MCP server listening");
+    axum::serve(listener, router(agent, token))
+        .with_graceful_shutdown(async move {
+            let _ = shutdown.recv().await;
+        })
+        .await
+        .map_err(|e| SafeAgentError::Config(format!("MCP server error: {e}")))?;
+    Ok(())
+}
+
+fn router(agent: Arc<Agent>, token: Option<String>) -> Router {
+    Router::new()
+        .route("/mcp", post(handle_post))
+        .with_state(McpState { agent, token })
+}
+
+async fn handle_post(State(state): State<McpState>, headers: HeaderMap, body: axum::body::Bytes) -> Response {
+    if let Some(origin) = headers.get(axum::http::header::ORIGIN) {
+        if !origin.to_str().is_ok_and(is_loopback_origin) {
+            warn!(origin = ?origin, "MCP request refused: foreign origin");
+            return (StatusCode::FORBIDDEN, "cross-origin requests are not allowed").into_response();
+        }
+    }
+    let json = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"));
+    if !json {
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, "Content-Type must be application/json").into_response();
+    }
+
+    if let Some(ref token) = state.token {
+        let presented = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or("");
+        if !crate::skills::token::constant_time_eq(presented.as_bytes(), token.as_bytes()) {
+            return (StatusCode::UNAUTHORIZED, "invalid or missing bearer token").into_response();
+        }
+    }
+
+    let request: Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => return Json(error_response(Value::Null, PARSE_ERROR, &e.to_string())).into_response(),
+    };
+    let reply = match request {
+        Value::Array(batch) => {
+            let mut replies = Vec::new();
+            for req in batch {
+                replies.extend(handle(&state.agent, req).await);
+            }
+            (!replies.is_empty()).then_some(Value::Array(replies))
+        }
+        req => handle(&state.agent, req).await,
+    };
+    match reply {
+        Some(reply) => Json(reply).into_response(),
+        // Notifications and responses get no reply.
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// Whether `origin` (`scheme://host[:port]`) names this machine.
+fn is_loopback_origin(origin: &str) -> bool {
+    let Some((_, authority)) = origin.split_once("://") else {
+        return false;
+    };
+    let host = match authority.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Handle one JSON-RPC message.  `None` for notifications.
+async fn handle(agent: &Agent, request: Value) -> Option<Value> {
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return id.map(|id| error_response(id, INVALID_REQUEST, "missing method"));
+    };
+    // Notifications (`notifications/initialized`, cancellations) need no
+    // reply.
+    let id = id?;
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(agent, &params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(list_tools(agent)),
+        "tools/call" => call_tool(agent, &params).await,
+        _ => Err((METHOD_NOT_FOUND, format!("method '{method}' not found"))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn initialize(agent: &Agent, params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": {
+            "name": agent.config.agent_name,
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+/// Every registered tool that isn't blocked outright.
+fn list_tools(agent: &Agent) -> Value {
+    let tools: Vec<Value> = agent
+        .tools
+        .schemas()
+        .into_iter()
+        .filter(|t| !t["name"].as_str().is_some_and(|n| agent.capability_checker.is_blocked(n)))
+        .map(|t| {
+            json!({
+                "name": t["name"],
+                "description": t["description"],
+                "inputSchema": t["parameters"],
+                "annotations": { "readOnlyHint": t["read_only"] },
+            })
+        })
+        .collect();
+    json!({ "tools": tools })
+}
+
+async fn call_tool(agent: &Agent, params: &Value) -> std::result::Result<Value, (i64, String)> {
+    let Some(name) = params.get("name").and_then(|v| v.as_str()) else {
+        return Err((INVALID_PARAMS, "missing tool name".into()));
+    };
+    let Some(tool) = agent.tools.get(name) else {
+        return Err((INVALID_PARAMS, format!("unknown tool '{name}'")));
+    };
+    let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+    crate::tools::validate_params(&tool.parameters_schema(), &arguments)
+        .map_err(|e| (INVALID_PARAMS, format!("{name}: {e}")))?;
+
+    let call = ToolCall {
+        tool: name.to_string(),
+        params: arguments,
+        reasoning: "requested by MCP client".into(),
+    };
+    let (text, is_error) = match agent.call_tool_external(&call, "mcp").await {
+        Ok(ExternalOutcome::Done(output)) => (output.output, !output.success),
+        Ok(ExternalOutcome::Queued(id)) => (format!("Awaiting operator approval (action {id})."), true),
+        Ok(ExternalOutcome::Challenge(id)) => (format!("Awaiting 2FA confirmation (challenge {id})."), true),
+        Err(e) => {
+            warn!(tool = %name, err = %e, "MCP tool call refused");
+            (e.to_string(), true)
+        }
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    }))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::tools::{Tool, ToolContext, ToolOutput, ToolRegistry};
+    use std::sync::Mutex;
+
+    /// Echoes `text`, recording what it was called with.
+    struct EchoTool {
+        seen: Arc<Mutex<Vec<Value>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "echoes text"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"]
+            })
+        }
+
+        async fn execute(&self, params: Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+            self.seen.lock().unwrap().push(params.clone());
+            Ok(ToolOutput::ok(params["text"].as_str().unwrap_or_default()))
+        }
+    }
+
+    async fn test_agent(dir: &std::path::Path) -> (Agent, Arc<Mutex<Vec<Value>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut tools = ToolRegistry::new();
+        tools.register(Box::new(EchoTool { seen: seen.clone() }));
+        let config = Config {
+            auto_approve_tools: vec!["echo".into()],
+            ..Default::default()
+        };
+        let agent = crate::agent::test_agent_with_tools(
+            dir,
+            config,
+            tools,
+            crate::messaging::MessagingManager::new(),
+        )
+        .await;
+        (agent, seen)
+    }
+
+    fn rpc(id: i64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    #[tokio::test]
+    async fn tools_list_advertises_registry_with_input_schemas() {
+        let dir = tempfile::tempdir().unwrap();
+        let (agent, _) = test_agent(dir.path()).await;
+
+        let reply = handle(&agent, rpc(1, "tools/list", json!({}))).await.unwrap();
+        assert_eq!(reply["jsonrpc"], "2.0");
+        assert_eq!(reply["id"], 1);
+        let tools = reply["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "echo");
+        assert_eq!(tools[0]["description"], "echoes text");
+        assert_eq!(tools[0]["inputSchema"]["type"], "object");
+        assert_eq!(tools[0]["inputSchema"]["required"], json!(["text"]));
+
+        let init = handle(&agent, rpc(2, "initialize", json!({ "protocolVersion": "2025-03-26" }))).await.unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert!(init["result"]["capabilities"]["tools"].is_object());
+        let note = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle(&agent, note).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn tools_call_dispatches_with_validated_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let (agent, seen) = test_agent(dir.path()).await;
+
+        let call = json!({ "name": "echo", "arguments": { "text": "hello" } });
+        let reply = handle(&agent, rpc(1, "tools/call", call)).await.unwrap();
+        assert_eq!(reply["result"]["isError"], false);
+        assert_eq!(reply["result"]["content"][0]["text"], "hello");
+        assert_eq!(*seen.lock().unwrap(), vec![json!({ "text": "hello" })]);
+
+        let bad = json!({ "name": "echo", "arguments": { "text": 42 } });
+        let reply = handle(&agent, rpc(2, "tools/call", bad)).await.unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+        let unknown = json!({ "name": "nope", "arguments": {} });
+        let reply = handle(&agent, rpc(3, "tools/call", unknown)).await.unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        let reply = handle(&agent, rpc(4, "resources/list", json!({}))).await.unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn blocked_tools_are_hidden_and_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let (agent, seen) = test_agent(dir.path()).await;
+        agent.capability_checker.set_blocked_tools(&["echo".to_string()]);
+
+        let reply = handle(&agent, rpc(1, "tools/list", json!({}))).await.unwrap();
+        assert!(reply["result"]["tools"].as_array().unwrap().is_empty());
+
+        let call = json!({ "name": "echo", "arguments": { "text": "hi" } });
+        let reply = handle(&agent, rpc(2, "tools/call", call)).await.unwrap();
+        assert_eq!(reply["result"]["isError"], true);
+        assert!(reply["result"]["content"][0]["text"].as_str().unwrap().contains("blocked"));
+        assert!(seen.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn browser_requests_are_refused_without_a_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let (agent, seen) = test_agent(dir.path()).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(Arc::new(agent), None)).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let body = rpc(1, "tools/call", json!({ "name": "echo", "arguments": { "text": "hi" } })).to_string();
+        let post = |content_type: &str, origin: Option<&str>| {
+            let mut req = client.post(&url).header("Content-Type", content_type).body(body.clone());
+            if let Some(origin) = origin {
+                req = req.header("Origin", origin);
+            }
+            req.send()
+        };
+
+        // A cross-site form post can't set a JSON content type
+        let resp = post("text/plain", None).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        // A rebound DNS name still shows up as a foreign origin
+        let resp = post("application/json", Some("http://evil.example:7878")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert!(seen.lock().unwrap().is_empty());
+
+        let resp = post("application/json; charset=utf-8", Some("http://localhost:5173")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = post("application/json", None).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn loopback_origins() {
+        assert!(is_loopback_origin("http://localhost:3000"));
+        assert!(is_loopback_origin("http://127.0.0.1"));
+        assert!(is_loopback_origin("https://[::1]:8443"));
+        assert!(!is_loopback_origin("http://localhost.evil.example"));
+        assert!(!is_loopback_origin("null"));
+        assert!(!is_loopback_origin("http://10.0.0.5:3000"));
+    }
+}
@@ -23,6 +23,16 @@ impl ArchivalMemory {
         Self { db }
     }
 
+    /// Store an entry and return its id.
+    pub async fn insert(&self, content: &str, category: &str) -> Result<i64> {
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT INTO archival_memory (content, category) VALUES (?1, ?2)",
+            rusqlite::params![content, category],
+        )?;
+        Ok(db.last_insert_rowid())
+    }
+
     /// Full-text search over archival memory.
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<ArchivalEntry>> {
         let db = self.db.lock().await;
@@ -80,4 +90,13 @@ mod tests {
         let entries = arch.list(0, 10).await.unwrap();
         assert!(entries.is_empty());
     }
+
+    #[tokio::test]
+    async fn insert_is_searchable() {
+        let arch = ArchivalMemory::new(test_db());
+        let id = arch.insert("Photo of a red bicycle", "image").await.unwrap();
+        let found = arch.search("bicycle", 5).await.unwrap();
+        assert_eq!(found[0].id, id);
+        assert_eq!(found[0].category, "image");
+    }
 }
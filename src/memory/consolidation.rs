@@ -3,9 +3,15 @@
 //! Periodically finds old, unconsolidated archival memories, groups them,
 //! asks the LLM to summarize them, and replaces the originals with a single
 //! consolidated entry. This keeps the archival memory manageable over time.
+//!
+//! [`ConsolidationScheduler`] gates how often that happens: runs are spaced
+//! at least one interval apart (unless forced) and never overlap.
 
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use rusqlite::Connection;
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
@@ -88,6 +94,9 @@ pub async fn consolidate_old_memories(
         message: &prompt,
         tools: None,
         prompt_skills: &[],
+        personality: None,
+        persona: None,
+        attachments: &[],
     };
 
     let summary = match llm.generate(&gen_ctx).await {
@@ -148,10 +157,161 @@ pub async fn pending_consolidation_count(
     Ok(count)
 }
 
+/// Result of asking the scheduler to consolidate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ConsolidationOutcome {
+    /// A run completed, consolidating this many memories.
+    Ran { consolidated: usize },
+    /// The last run was less than one interval ago.
+    NotDue,
+    /// Another run is still in progress.
+    AlreadyRunning,
+}
+
+/// Decides when memory consolidation runs and keeps runs from overlapping.
+pub struct ConsolidationScheduler {
+    interval: Duration,
+    /// Held for the duration of a run.
+    running: Mutex<()>,
+    last_run: std::sync::Mutex<Option<(Instant, DateTime<Utc>)>>,
+}
+
+impl ConsolidationScheduler {
+    pub fn new(interval_secs: u64) -> Self {
+        Self {
+            interval: Duration::from_secs(interval_secs),
+            running: Mutex::new(()),
+            last_run: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Minimum time between scheduled runs.  Zero disables scheduled runs.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// When the most recent run started, if any.
+    pub fn last_run(&self) -> Option<DateTime<Utc>> {
+        self.last_run
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map(|(_, at)| at)
+    }
+
+    /// Whether a run is in progress right now.
+    pub fn is_running(&self) -> bool {
+        self.running.try_lock().is_err()
+    }
+
+    fn is_due(&self) -> bool {
+        self.last_run
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none_or(|(started, _)| started.elapsed() >= self.interval)
+    }
+
+    /// Run `job` if no other run is in progress and either `force` is set
+    /// or at least one interval has passed since the last run started.
+    ///
+    /// The run is recorded before `job` starts, so a failing run still
+    /// waits a full interval before the next scheduled attempt.
+    pub async fn run<F, Fut>(&self, force: bool, job: F) -> Result<ConsolidationOutcome>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<usize>>,
+    {
+        let Ok(_guard) = self.running.try_lock() else {
+            debug!("memory consolidation already running, skipping");
+            return Ok(ConsolidationOutcome::AlreadyRunning);
+        };
+        if !force && !self.is_due() {
+            return Ok(ConsolidationOutcome::NotDue);
+        }
+
+        *self.last_run.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), Utc::now()));
+
+        let consolidated = job().await?;
+        Ok(ConsolidationOutcome::Ran { consolidated })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::test_db;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn counted(scheduler: &ConsolidationScheduler, force: bool, runs: &AtomicUsize) -> ConsolidationOutcome {
+        scheduler
+            .run(force, || async {
+                runs.fetch_add(1, Ordering::SeqCst);
+                Ok(1)
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn scheduler_runs_at_most_once_per_interval() {
+        let scheduler = ConsolidationScheduler::new(3600);
+        let runs = AtomicUsize::new(0);
+        assert!(scheduler.last_run().is_none());
+
+        assert_eq!(counted(&scheduler, false, &runs).await, ConsolidationOutcome::Ran { consolidated: 1 });
+        assert_eq!(counted(&scheduler, false, &runs).await, ConsolidationOutcome::NotDue);
+        assert_eq!(counted(&scheduler, false, &runs).await, ConsolidationOutcome::NotDue);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(scheduler.last_run().is_some());
+    }
+
+    #[tokio::test]
+    async fn scheduler_runs_again_once_interval_elapsed() {
+        let scheduler = ConsolidationScheduler::new(0);
+        let runs = AtomicUsize::new(0);
+        counted(&scheduler, false, &runs).await;
+        counted(&scheduler, false, &runs).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn manual_trigger_bypasses_interval() {
+        let scheduler = ConsolidationScheduler::new(3600);
+        let runs = AtomicUsize::new(0);
+        counted(&scheduler, false, &runs).await;
+        let first = scheduler.last_run().unwrap();
+
+        assert_eq!(counted(&scheduler, true, &runs).await, ConsolidationOutcome::Ran { consolidated: 1 });
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert!(scheduler.last_run().unwrap() >= first);
+    }
+
+    #[tokio::test]
+    async fn scheduler_never_overlaps() {
+        let scheduler = ConsolidationScheduler::new(0);
+        let runs = AtomicUsize::new(0);
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let long_run = scheduler.run(true, || async {
+            started_tx.send(()).unwrap();
+            release_rx.await.unwrap();
+            Ok(0)
+        });
+        let second = async {
+            started_rx.await.unwrap();
+            assert!(scheduler.is_running());
+            let outcome = counted(&scheduler, true, &runs).await;
+            release_tx.send(()).unwrap();
+            outcome
+        };
+
+        let (first, second) = tokio::join!(long_run, second);
+        assert_eq!(first.unwrap(), ConsolidationOutcome::Ran { consolidated: 0 });
+        assert_eq!(second, ConsolidationOutcome::AlreadyRunning);
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+        assert!(!scheduler.is_running());
+    }
 
     #[tokio::test]
     async fn pending_count_empty_db() {
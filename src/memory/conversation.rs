@@ -1,10 +1,14 @@
 use std::sync::Arc;
 
-use rusqlite::Connection;
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
-use crate::error::Result;
+use crate::error::{Result, SafeAgentError};
+
+/// The branch every conversation starts on.
+pub const MAIN_BRANCH: &str = "main";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationMessage {
@@ -30,29 +34,38 @@ impl ConversationMemory {
     }
 
     /// Append a message with an optional user_id for multi-user isolation.
+    /// The message lands on the default conversation's active branch.
     pub async fn append_with_user(&self, role: &str, content: &str, user_id: Option<&str>) -> Result<()> {
+        self.append_in(role, content, user_id, None).await
+    }
+
+    /// Append a message to the history of `channel` (e.g.
+    /// `"telegram:12345"`), or of the default conversation with `None`.
+    /// Each channel keeps its own window on its own active branch.
+    pub async fn append_in(
+        &self,
+        role: &str,
+        content: &str,
+        user_id: Option<&str>,
+        channel: Option<&str>,
+    ) -> Result<()> {
         let db = self.db.lock().await;
+        let branch = active_branch(&db, channel)?;
         db.execute(
-            "INSERT INTO conversation_history (role, content, user_id) VALUES (?1, ?2, ?3)",
-            rusqlite::params![role, content, user_id],
+            "INSERT INTO conversation_history (role, content, user_id, branch, channel)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![role, content, user_id, branch, channel],
         )?;
 
-        // Prune old messages beyond the window (per user if user_id is set)
-        if let Some(uid) = user_id {
-            db.execute(
-                "DELETE FROM conversation_history WHERE user_id = ?2 AND id NOT IN (
-                    SELECT id FROM conversation_history WHERE user_id = ?2 ORDER BY id DESC LIMIT ?1
-                )",
-                rusqlite::params![self.window_size as i64, uid],
-            )?;
-        } else {
-            db.execute(
-                "DELETE FROM conversation_history WHERE user_id IS NULL AND id NOT IN (
-                    SELECT id FROM conversation_history WHERE user_id IS NULL ORDER BY id DESC LIMIT ?1
-                )",
-                [self.window_size as i64],
-            )?;
-        }
+        // Prune old messages beyond the window (per user and channel)
+        db.execute(
+            "DELETE FROM conversation_history
+             WHERE user_id IS ?2 AND channel IS ?3 AND branch = ?4 AND id NOT IN (
+                SELECT id FROM conversation_history
+                WHERE user_id IS ?2 AND channel IS ?3 AND branch = ?4 ORDER BY id DESC LIMIT ?1
+             )",
+            rusqlite::params![self.window_size as i64, user_id, channel, branch],
+        )?;
 
         Ok(())
     }
@@ -62,54 +75,299 @@ impl ConversationMemory {
         self.recent_for_user(None).await
     }
 
-    /// Get the most recent conversation messages for a specific user.
-    /// If `user_id` is None, returns messages with no user association
-    /// (backward-compatible single-user mode).
+    /// The most recent messages in `channel`'s history, or the default
+    /// conversation's with `None`, from every user.
+    pub async fn recent_in(&self, channel: Option<&str>) -> Result<Vec<ConversationMessage>> {
+        self.query_recent(None, channel).await
+    }
+
+    /// Get the most recent conversation messages on the default
+    /// conversation's active branch for a specific user.  If `user_id` is
+    /// None, returns every message on the branch (backward-compatible
+    /// single-user mode).  Only the default conversation is read, not
+    /// per-channel ones.
     pub async fn recent_for_user(&self, user_id: Option<&str>) -> Result<Vec<ConversationMessage>> {
+        self.query_recent(user_id, None).await
+    }
+
+    async fn query_recent(&self, user_id: Option<&str>, channel: Option<&str>) -> Result<Vec<ConversationMessage>> {
         let db = self.db.lock().await;
+        let branch = active_branch(&db, channel)?;
 
-        let (sql, messages) = if let Some(uid) = user_id {
-            let mut stmt = db.prepare(
-                "SELECT id, role, content, created_at FROM conversation_history
-                 WHERE user_id = ?1
-                 ORDER BY id DESC LIMIT ?2",
-            )?;
-            let msgs = stmt
-                .query_map(rusqlite::params![uid, self.window_size as i64], |row| {
-                    Ok(ConversationMessage {
-                        id: row.get(0)?,
-                        role: row.get(1)?,
-                        content: row.get(2)?,
-                        created_at: row.get(3)?,
-                    })
-                })?
-                .collect::<std::result::Result<Vec<_>, _>>()?;
-            ("user-filtered", msgs)
-        } else {
-            let mut stmt = db.prepare(
-                "SELECT id, role, content, created_at FROM conversation_history
-                 ORDER BY id DESC LIMIT ?1",
-            )?;
-            let msgs = stmt
-                .query_map([self.window_size as i64], |row| {
-                    Ok(ConversationMessage {
-                        id: row.get(0)?,
-                        role: row.get(1)?,
-                        content: row.get(2)?,
-                        created_at: row.get(3)?,
-                    })
-                })?
-                .collect::<std::result::Result<Vec<_>, _>>()?;
-            ("all", msgs)
-        };
-        let _ = sql; // suppress warning
+        let mut stmt = db.prepare(
+            "SELECT id, role, content, created_at FROM conversation_history
+             WHERE branch = ?1 AND (?2 IS NULL OR user_id = ?2) AND channel IS ?3
+             ORDER BY id DESC LIMIT ?4",
+        )?;
+        let mut messages = stmt
+            .query_map(rusqlite::params![branch, user_id, channel, self.window_size as i64], |row| {
+                Ok(ConversationMessage {
+                    id: row.get(0)?,
+                    role: row.get(1)?,
+                    content: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
         // Reverse so oldest is first
-        let mut messages = messages;
         messages.reverse();
         Ok(messages)
     }
 
+    /// Name of the branch `channel`'s messages go to and are read from
+    /// (`None` for the default conversation).  Each chat switches branches
+    /// independently; one that never switched is on "main".
+    pub async fn active_branch(&self, channel: Option<&str>) -> Result<String> {
+        let db = self.db.lock().await;
+        active_branch(&db, channel)
+    }
+
+    /// Fork `channel`'s active branch into a new branch called `name` and
+    /// switch that chat to it.  The fork starts with a copy of the chat's
+    /// history on the parent branch; from then on the two evolve
+    /// independently.  Other chats are unaffected.
+    pub async fn fork_conversation(&self, name: &str, channel: Option<&str>) -> Result<ConversationBranch> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(SafeAgentError::Config("branch name must not be empty".into()));
+        }
+
+        let db = self.db.lock().await;
+        let exists: bool = db.query_row(
+            "SELECT EXISTS(SELECT 1 FROM conversation_branches WHERE name = ?1)",
+            [name],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Err(SafeAgentError::Config(format!("branch '{name}' already exists")));
+        }
+
+        let parent = active_branch(&db, channel)?;
+        let tx = db.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO conversation_branches (name, parent) VALUES (?1, ?2)",
+            rusqlite::params![name, parent],
+        )?;
+        tx.execute(
+            "INSERT INTO conversation_history (role, content, user_id, created_at, channel, branch)
+             SELECT role, content, user_id, created_at, channel, ?1 FROM conversation_history
+             WHERE branch = ?2 AND channel IS ?3 ORDER BY id",
+            rusqlite::params![name, parent, channel],
+        )?;
+        set_active_branch(&tx, channel, name)?;
+        tx.commit()?;
+
+        branch(&db, name, channel)
+    }
+
+    /// Switch `channel` (the default conversation with `None`) to the
+    /// branch `name`.
+    pub async fn switch_branch(&self, name: &str, channel: Option<&str>) -> Result<ConversationBranch> {
+        let db = self.db.lock().await;
+        let exists: bool = db.query_row(
+            "SELECT EXISTS(SELECT 1 FROM conversation_branches WHERE name = ?1)",
+            [name],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(SafeAgentError::NotFound(format!("branch '{name}'")));
+        }
+        set_active_branch(&db, channel, name)?;
+        branch(&db, name, channel)
+    }
+
+    /// Render `channel`'s history on a branch (the chat's active one if
+    /// `branch` is `None`) as a Markdown transcript.  Only that chat's
+    /// messages are included; `None` is the default conversation.  `from`
+    /// is inclusive and `to` exclusive.  Tool calls are annotated and tool
+    /// results summarised rather than dumped verbatim.
+    pub async fn export_markdown(
+        &self,
+        branch: Option<&str>,
+        channel: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<String> {
+        // created_at uses SQLite's datetime('now') format, which sorts
+        // correctly as text.
+        let fmt = |t: DateTime<Utc>| t.format("%Y-%m-%d %H:%M:%S").to_string();
+        let db = self.db.lock().await;
+        let branch = match branch {
+            Some(name) => self::branch(&db, name, channel)
+                .map_err(|_| SafeAgentError::NotFound(format!("branch '{name}'")))?
+                .name,
+            None => active_branch(&db, channel)?,
+        };
+        let mut stmt = db.prepare(
+            "SELECT id, role, content, created_at FROM conversation_history
+             WHERE branch = ?1
+               AND (?2 IS NULL OR created_at >= ?2)
+               AND (?3 IS NULL OR created_at < ?3)
+               AND channel IS ?4
+             ORDER BY id",
+        )?;
+        let messages = stmt
+            .query_map(rusqlite::params![branch, from.map(fmt), to.map(fmt), channel], |row| {
+                Ok(ConversationMessage {
+                    id: row.get(0)?,
+                    role: row.get(1)?,
+                    content: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut md = format!("# Conversation: {branch}\n\n");
+        match (from, to) {
+            (None, None) => {}
+            (from, to) => md.push_str(&format!(
+                "_{} to {}_\n\n",
+                from.map(fmt).unwrap_or_else(|| "start".into()),
+                to.map(fmt).unwrap_or_else(|| "now".into()),
+            )),
+        }
+        if messages.is_empty() {
+            md.push_str("_No messages._\n");
+        }
+        for msg in &messages {
+            render_message(&mut md, msg);
+        }
+        Ok(md)
+    }
+
+    /// All branches, oldest first, as seen from `channel`: which one it is
+    /// on and how many of its messages each holds.
+    pub async fn list_branches(&self, channel: Option<&str>) -> Result<Vec<ConversationBranch>> {
+        let db = self.db.lock().await;
+        let active = active_branch(&db, channel)?;
+        let mut stmt = db.prepare(&format!("{BRANCH_SELECT} ORDER BY b.created_at, b.rowid"))?;
+        let branches = stmt
+            .query_map(rusqlite::params![active, channel], branch_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(branches)
+    }
+}
+
+/// A named line of conversation history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationBranch {
+    pub name: String,
+    /// Branch this one was forked from; `None` for "main".
+    pub parent: Option<String>,
+    /// Whether the chat it was looked up for is on this branch.
+    pub active: bool,
+    /// That chat's messages on the branch.
+    pub message_count: i64,
+    pub created_at: String,
+}
+
+/// Takes the chat's active branch as `?1` and the chat as `?2`.
+const BRANCH_SELECT: &str = "SELECT b.name, b.parent, b.name = ?1, b.created_at,
+        (SELECT COUNT(*) FROM conversation_history h WHERE h.branch = b.name AND h.channel IS ?2)
+     FROM conversation_branches b";
+
+fn branch_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ConversationBranch> {
+    Ok(ConversationBranch {
+        name: row.get(0)?,
+        parent: row.get(1)?,
+        active: row.get(2)?,
+        created_at: row.get(3)?,
+        message_count: row.get(4)?,
+    })
+}
+
+/// Prefix the agent puts on tool results it records in the history.
+const TOOL_RESULT_PREFIX: &str = "[Approved tool result: ";
+
+/// Longest tool-result excerpt kept in an export.
+const TOOL_RESULT_PREVIEW: usize = 160;
+
+fn render_message(md: &mut String, msg: &ConversationMessage) {
+    if let Some(rest) = msg.content.strip_prefix(TOOL_RESULT_PREFIX) {
+        let (header, output) = rest.split_once('\n').unwrap_or((rest, ""));
+        let header = header.trim_end_matches(']');
+        md.push_str(&format!("### Tool result · {}\n\n", msg.created_at));
+        md.push_str(&format!(
+            "> `{header}`: {} line(s), {} chars\n",
+            output.lines().count(),
+            output.chars().count()
+        ));
+        let first = output.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+        if !first.is_empty() {
+            let mut preview: String = first.chars().take(TOOL_RESULT_PREVIEW).collect();
+            if preview.len() < first.len() {
+                preview.push('…');
+            }
+            md.push_str(&format!(">\n> {preview}\n"));
+        }
+        md.push('\n');
+        return;
+    }
+
+    let role = match msg.role.as_str() {
+        "user" => "User",
+        "assistant" => "Assistant",
+        "system" => "System",
+        other => other,
+    };
+    md.push_str(&format!("### {role} · {}\n\n", msg.created_at));
+    if msg.role != "assistant" {
+        md.push_str(msg.content.trim());
+        md.push_str("\n\n");
+        return;
+    }
+
+    let parsed = crate::agent::tool_parse::parse_llm_response(&msg.content);
+    if !parsed.text.is_empty() {
+        md.push_str(&parsed.text);
+        md.push_str("\n\n");
+    }
+    for call in &parsed.tool_calls {
+        md.push_str(&format!("> Tool call: `{}`", call.tool));
+        if !call.reasoning.is_empty() {
+            md.push_str(&format!(" ({})", call.reasoning));
+        }
+        md.push('\n');
+    }
+    if !parsed.tool_calls.is_empty() {
+        md.push('\n');
+    }
+}
+
+fn branch(db: &Connection, name: &str, channel: Option<&str>) -> Result<ConversationBranch> {
+    let active = active_branch(db, channel)?;
+    Ok(db.query_row(
+        &format!("{BRANCH_SELECT} WHERE b.name = ?3"),
+        rusqlite::params![active, channel, name],
+        branch_from_row,
+    )?)
+}
+
+/// Key a chat's active branch is stored under; the default conversation
+/// uses the empty string.
+fn chat_key(channel: Option<&str>) -> &str {
+    channel.unwrap_or("")
+}
+
+fn active_branch(db: &Connection, channel: Option<&str>) -> Result<String> {
+    Ok(db
+        .query_row(
+            "SELECT branch FROM conversation_active_branches WHERE chat = ?1",
+            [chat_key(channel)],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or_else(|| MAIN_BRANCH.to_string()))
+}
+
+fn set_active_branch(db: &Connection, channel: Option<&str>, name: &str) -> Result<()> {
+    db.execute(
+        "INSERT INTO conversation_active_branches (chat, branch) VALUES (?1, ?2)
+         ON CONFLICT(chat) DO UPDATE SET branch = excluded.branch",
+        rusqlite::params![chat_key(channel), name],
+    )?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -163,4 +421,197 @@ mod tests {
         assert_eq!(msgs[0].content, "first");
         assert_eq!(msgs[2].content, "third");
     }
+
+    #[tokio::test]
+    async fn fork_is_isolated_from_main() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 50);
+        conv.append("user", "shared").await.unwrap();
+
+        let fork = conv.fork_conversation("what-if", None).await.unwrap();
+        assert_eq!(fork.parent.as_deref(), Some(MAIN_BRANCH));
+        assert!(fork.active);
+        assert_eq!(fork.message_count, 1);
+        conv.append("user", "only on the fork").await.unwrap();
+        let contents: Vec<_> = conv.recent().await.unwrap().into_iter().map(|m| m.content).collect();
+        assert_eq!(contents, ["shared", "only on the fork"]);
+
+        conv.switch_branch(MAIN_BRANCH, None).await.unwrap();
+        let contents: Vec<_> = conv.recent().await.unwrap().into_iter().map(|m| m.content).collect();
+        assert_eq!(contents, ["shared"]);
+    }
+
+    #[tokio::test]
+    async fn switching_back_restores_main() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 50);
+        assert_eq!(conv.active_branch(None).await.unwrap(), MAIN_BRANCH);
+        conv.append("user", "first").await.unwrap();
+        conv.fork_conversation("alt", None).await.unwrap();
+        conv.append("assistant", "alt reply").await.unwrap();
+
+        conv.switch_branch(MAIN_BRANCH, None).await.unwrap();
+        conv.append("assistant", "main reply").await.unwrap();
+        let contents: Vec<_> = conv.recent().await.unwrap().into_iter().map(|m| m.content).collect();
+        assert_eq!(contents, ["first", "main reply"]);
+
+        conv.switch_branch("alt", None).await.unwrap();
+        let contents: Vec<_> = conv.recent().await.unwrap().into_iter().map(|m| m.content).collect();
+        assert_eq!(contents, ["first", "alt reply"]);
+
+        let branches = conv.list_branches(None).await.unwrap();
+        let names: Vec<_> = branches.iter().map(|b| (b.name.as_str(), b.active)).collect();
+        assert_eq!(names, [(MAIN_BRANCH, false), ("alt", true)]);
+    }
+
+    #[tokio::test]
+    async fn fork_rejects_duplicates_and_switch_rejects_unknown() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 50);
+        assert!(conv.fork_conversation(MAIN_BRANCH, None).await.is_err());
+        assert!(conv.fork_conversation("  ", None).await.is_err());
+        assert!(conv.switch_branch("missing", None).await.is_err());
+        assert_eq!(conv.active_branch(None).await.unwrap(), MAIN_BRANCH);
+    }
+
+    #[tokio::test]
+    async fn export_markdown_has_role_headers_and_tool_annotations() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 50);
+        conv.append("user", "what's in the repo?").await.unwrap();
+        conv.append(
+            "assistant",
+            "Let me look.\n\n```tool_call\n{\"tool\": \"exec\", \"params\": {\"command\": \"ls\"}, \"reasoning\": \"list files\"}\n```",
+        )
+        .await
+        .unwrap();
+        let listing = (0..40).map(|i| format!("file_{i}.rs")).collect::<Vec<_>>().join("\n");
+        conv.append("system", &format!("[Approved tool result: exec (success)]\n{listing}"))
+            .await
+            .unwrap();
+
+        let md = conv.export_markdown(None, None, None, None).await.unwrap();
+        assert!(md.starts_with("# Conversation: main"));
+        assert!(md.contains("### User · "));
+        assert!(md.contains("### Assistant · "));
+        assert!(md.contains("Let me look."));
+        assert!(md.contains("> Tool call: `exec` (list files)"));
+        assert!(!md.contains("```tool_call"));
+        assert!(md.contains("### Tool result · "));
+        assert!(md.contains("`exec (success)`: 40 line(s)"));
+        assert!(md.contains("file_0.rs"));
+        assert!(!md.contains("file_39.rs"));
+    }
+
+    #[tokio::test]
+    async fn export_markdown_respects_time_bounds() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db.clone(), 50);
+        conv.append("user", "too early").await.unwrap();
+        conv.append("user", "in range").await.unwrap();
+        conv.append("user", "too late").await.unwrap();
+        {
+            let db = db.lock().await;
+            for (content, at) in [
+                ("too early", "2026-01-01 09:00:00"),
+                ("in range", "2026-01-01 10:30:00"),
+                ("too late", "2026-01-01 12:00:00"),
+            ] {
+                db.execute(
+                    "UPDATE conversation_history SET created_at = ?2 WHERE content = ?1",
+                    rusqlite::params![content, at],
+                )
+                .unwrap();
+            }
+        }
+
+        let at = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap().and_utc();
+        let md = conv
+            .export_markdown(Some(MAIN_BRANCH), None, Some(at("2026-01-01 10:00:00")), Some(at("2026-01-01 12:00:00")))
+            .await
+            .unwrap();
+        assert!(md.contains("in range"));
+        assert!(md.contains("2026-01-01 10:30:00"));
+        assert!(!md.contains("too early"));
+        assert!(!md.contains("too late"));
+        assert!(conv.export_markdown(Some("missing"), None, None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn export_markdown_covers_only_the_requested_chat() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 50);
+        conv.append_in("user", "from chat A", None, Some("telegram:1")).await.unwrap();
+        conv.append_in("user", "from chat B", None, Some("telegram:2")).await.unwrap();
+        conv.append("user", "from the default chat").await.unwrap();
+
+        let md = conv.export_markdown(None, Some("telegram:1"), None, None).await.unwrap();
+        assert!(md.contains("from chat A"));
+        assert!(!md.contains("from chat B"));
+        assert!(!md.contains("from the default chat"));
+
+        let md = conv.export_markdown(None, None, None, None).await.unwrap();
+        assert!(md.contains("from the default chat"));
+        assert!(!md.contains("from chat A"));
+    }
+
+    #[tokio::test]
+    async fn channels_keep_separate_histories() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 50);
+        conv.append("user", "default chat").await.unwrap();
+        conv.append_in("user", "in A", None, Some("telegram:1")).await.unwrap();
+        conv.append_in("user", "in B", None, Some("telegram:2")).await.unwrap();
+
+        let contents = |msgs: Vec<ConversationMessage>| msgs.into_iter().map(|m| m.content).collect::<Vec<_>>();
+        assert_eq!(contents(conv.recent_in(Some("telegram:1")).await.unwrap()), ["in A"]);
+        assert_eq!(contents(conv.recent_in(Some("telegram:2")).await.unwrap()), ["in B"]);
+        assert_eq!(contents(conv.recent().await.unwrap()), ["default chat"]);
+        assert_eq!(contents(conv.recent_in(None).await.unwrap()), ["default chat"]);
+    }
+
+    #[tokio::test]
+    async fn window_is_per_channel() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 2);
+        conv.append("user", "keep me").await.unwrap();
+        for i in 0..3 {
+            conv.append_in("user", &format!("a{i}"), None, Some("a")).await.unwrap();
+        }
+        assert_eq!(conv.recent().await.unwrap().len(), 1);
+        let a: Vec<_> = conv.recent_in(Some("a")).await.unwrap().into_iter().map(|m| m.content).collect();
+        assert_eq!(a, ["a1", "a2"]);
+    }
+
+    #[tokio::test]
+    async fn each_chat_keeps_its_own_active_branch() {
+        let db = test_db();
+        let conv = ConversationMemory::new(db, 50);
+        conv.append_in("user", "hi from A", None, Some("telegram:1")).await.unwrap();
+        conv.append_in("user", "hi from B", None, Some("telegram:2")).await.unwrap();
+
+        let fork = conv.fork_conversation("what-if", Some("telegram:1")).await.unwrap();
+        assert_eq!(fork.message_count, 1);
+        conv.append_in("user", "A on the fork", None, Some("telegram:1")).await.unwrap();
+        conv.append_in("user", "B still on main", None, Some("telegram:2")).await.unwrap();
+
+        assert_eq!(conv.active_branch(Some("telegram:1")).await.unwrap(), "what-if");
+        assert_eq!(conv.active_branch(Some("telegram:2")).await.unwrap(), MAIN_BRANCH);
+        assert_eq!(conv.active_branch(None).await.unwrap(), MAIN_BRANCH);
+        let contents = |msgs: Vec<ConversationMessage>| msgs.into_iter().map(|m| m.content).collect::<Vec<_>>();
+        assert_eq!(contents(conv.recent_in(Some("telegram:1")).await.unwrap()), ["hi from A", "A on the fork"]);
+        assert_eq!(contents(conv.recent_in(Some("telegram:2")).await.unwrap()), ["hi from B", "B still on main"]);
+
+        // B can join the branch, but only sees its own messages there
+        conv.switch_branch("what-if", Some("telegram:2")).await.unwrap();
+        assert!(conv.recent_in(Some("telegram:2")).await.unwrap().is_empty());
+        let seen_by_a: Vec<_> = conv
+            .list_branches(Some("telegram:1"))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| (b.name, b.active, b.message_count))
+            .collect();
+        assert_eq!(seen_by_a, [(MAIN_BRANCH.to_string(), false, 1), ("what-if".to_string(), true, 2)]);
+    }
 }
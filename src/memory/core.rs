@@ -1,9 +1,19 @@
 use std::sync::Arc;
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
 use tokio::sync::Mutex;
 
-use crate::error::Result;
+use crate::error::{Result, SafeAgentError};
+
+/// A named system-prompt persona (e.g. "concise", "code-reviewer") layered
+/// on top of the core personality while it is active.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Persona {
+    pub name: String,
+    pub prompt: String,
+    pub updated_at: String,
+}
 
 pub struct CoreMemory {
     db: Arc<Mutex<Connection>>,
@@ -35,6 +45,88 @@ impl CoreMemory {
         Ok(personality)
     }
 
+    /// Create or replace the persona `name`.
+    pub async fn save_persona(&self, name: &str, prompt: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(SafeAgentError::Config("persona name must not be empty".into()));
+        }
+        if prompt.trim().is_empty() {
+            return Err(SafeAgentError::Config(format!("persona '{name}' needs a prompt")));
+        }
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT INTO personas (name, prompt, updated_at) VALUES (?1, ?2, datetime('now'))
+             ON CONFLICT(name) DO UPDATE SET prompt = excluded.prompt, updated_at = excluded.updated_at",
+            rusqlite::params![name, prompt],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_persona(&self, name: &str) -> Result<Option<Persona>> {
+        let db = self.db.lock().await;
+        Ok(db
+            .query_row(
+                "SELECT name, prompt, updated_at FROM personas WHERE name = ?1",
+                [name],
+                |row| Ok(Persona { name: row.get(0)?, prompt: row.get(1)?, updated_at: row.get(2)? }),
+            )
+            .optional()?)
+    }
+
+    /// All personas, by name.
+    pub async fn list_personas(&self) -> Result<Vec<Persona>> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare("SELECT name, prompt, updated_at FROM personas ORDER BY name")?;
+        let personas = stmt
+            .query_map([], |row| Ok(Persona { name: row.get(0)?, prompt: row.get(1)?, updated_at: row.get(2)? }))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(personas)
+    }
+
+    /// Returns whether a persona was deleted.  Deleting the active persona
+    /// deactivates it.
+    pub async fn delete_persona(&self, name: &str) -> Result<bool> {
+        let db = self.db.lock().await;
+        db.execute("UPDATE core_memory SET active_persona = NULL WHERE active_persona = ?1", [name])?;
+        Ok(db.execute("DELETE FROM personas WHERE name = ?1", [name])? > 0)
+    }
+
+    /// Make `name` the active persona, or go back to the plain core
+    /// personality with `None`.  Returns the persona now active.
+    pub async fn set_active_persona(&self, name: Option<&str>) -> Result<Option<Persona>> {
+        let persona = match name {
+            Some(name) => Some(
+                self.get_persona(name)
+                    .await?
+                    .ok_or_else(|| SafeAgentError::NotFound(format!("persona '{name}'")))?,
+            ),
+            None => None,
+        };
+        let db = self.db.lock().await;
+        let updated = db.execute(
+            "UPDATE core_memory SET active_persona = ?1, updated_at = datetime('now') WHERE id = 1",
+            [persona.as_ref().map(|p| p.name.as_str())],
+        )?;
+        if updated == 0 {
+            return Err(SafeAgentError::Config("core memory is not initialized".into()));
+        }
+        Ok(persona)
+    }
+
+    /// The active persona, if any.
+    pub async fn active_persona(&self) -> Result<Option<Persona>> {
+        let db = self.db.lock().await;
+        Ok(db
+            .query_row(
+                "SELECT p.name, p.prompt, p.updated_at
+                 FROM core_memory c JOIN personas p ON p.name = c.active_persona
+                 WHERE c.id = 1",
+                [],
+                |row| Ok(Persona { name: row.get(0)?, prompt: row.get(1)?, updated_at: row.get(2)? }),
+            )
+            .optional()?)
+    }
 }
 
 #[cfg(test)]
@@ -67,4 +159,30 @@ mod tests {
         let core = CoreMemory::new(db);
         assert!(core.get().await.is_err());
     }
+
+    #[tokio::test]
+    async fn personas_are_created_and_switched() {
+        let core = CoreMemory::new(test_db());
+        core.init("You are helpful").await.unwrap();
+        core.save_persona("concise", "Answer in one sentence.").await.unwrap();
+        core.save_persona("code-reviewer", "Review code for bugs first.").await.unwrap();
+        core.save_persona("concise", "Answer in as few words as possible.").await.unwrap();
+        assert!(core.save_persona(" ", "x").await.is_err());
+
+        let names: Vec<_> = core.list_personas().await.unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["code-reviewer", "concise"]);
+        assert!(core.active_persona().await.unwrap().is_none());
+
+        let active = core.set_active_persona(Some("concise")).await.unwrap().unwrap();
+        assert_eq!(active.prompt, "Answer in as few words as possible.");
+        assert_eq!(core.active_persona().await.unwrap(), Some(active));
+        assert!(core.set_active_persona(Some("nope")).await.is_err());
+        assert_eq!(core.active_persona().await.unwrap().unwrap().name, "concise");
+
+        assert!(core.delete_persona("concise").await.unwrap());
+        assert!(core.active_persona().await.unwrap().is_none());
+        core.set_active_persona(Some("code-reviewer")).await.unwrap();
+        core.set_active_persona(None).await.unwrap();
+        assert!(core.active_persona().await.unwrap().is_none());
+    }
 }
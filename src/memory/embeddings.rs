@@ -1,20 +1,46 @@
+use std::future::Future;
 use std::sync::Arc;
 
+use futures::stream::{self, StreamExt, TryStreamExt};
 use reqwest::Client;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use tracing::{debug, warn};
 
+use super::rerank::Reranker;
 use crate::error::{Result, SafeAgentError};
 
 const DEFAULT_OLLAMA_HOST: &str = "http://localhost:11434";
+const DEFAULT_BATCH_SIZE: usize = 64;
+const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+/// `metadata` key recording the dimension of the stored embeddings.
+const DIM_METADATA_KEY: &str = "embedding_dim";
 
 pub struct EmbeddingEngine {
-    client: Client,
-    base_url: String,
+    backend: EmbeddingBackend,
     model: String,
     db: Arc<Mutex<Connection>>,
+    /// Maximum number of inputs sent in a single embedding request.
+    batch_size: usize,
+    /// Maximum number of sub-batch requests in flight at once.
+    batch_concurrency: usize,
+    /// Configured vector dimension; `None` accepts whatever the model
+    /// produces first.
+    expected_dim: Option<usize>,
+    /// Optional second pass over the vector search candidates.
+    reranker: Option<Arc<dyn Reranker>>,
+    /// Candidates fetched per requested result when reranking.
+    rerank_candidates: usize,
+}
+
+/// Where embedding vectors come from.
+enum EmbeddingBackend {
+    /// Ollama's `/api/embed` endpoint.
+    Ollama { client: Client, base_url: String },
+    /// An in-process ONNX model (requires the `local` feature).
+    #[cfg(feature = "local")]
+    Local(Arc<super::local_embeddings::LocalEmbedder>),
 }
 
 #[derive(Serialize)]
@@ -65,22 +91,158 @@ impl EmbeddingEngine {
             .ok()?;
 
         Some(Self {
-            client,
-            base_url,
+            backend: EmbeddingBackend::Ollama { client, base_url },
             model: model.to_string(),
             db,
+            batch_size: DEFAULT_BATCH_SIZE,
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            expected_dim: None,
+            reranker: None,
+            rerank_candidates: 1,
         })
     }
 
+    /// Build an engine backed by a local ONNX model, for deployments that
+    /// cannot reach an embedding API.  See [`super::local_embeddings`].
+    #[cfg(feature = "local")]
+    pub fn local(db: Arc<Mutex<Connection>>, model_path: &std::path::Path) -> Result<Self> {
+        let embedder = super::local_embeddings::LocalEmbedder::load(model_path)?;
+        Ok(Self {
+            model: embedder.name().to_string(),
+            backend: EmbeddingBackend::Local(Arc::new(embedder)),
+            db,
+            batch_size: DEFAULT_BATCH_SIZE,
+            batch_concurrency: DEFAULT_BATCH_CONCURRENCY,
+            expected_dim: None,
+            reranker: None,
+            rerank_candidates: 1,
+        })
+    }
+
+    /// Dimension of the vectors this engine produces, when known without
+    /// making a request.  Local models report it as soon as they load;
+    /// API backends return `None`.
+    pub fn dim(&self) -> Option<usize> {
+        match &self.backend {
+            EmbeddingBackend::Ollama { .. } => None,
+            #[cfg(feature = "local")]
+            EmbeddingBackend::Local(embedder) => Some(embedder.dim()),
+        }
+    }
+
+    /// Set the sub-batch size and concurrency used by [`Self::embed_batch`].
+    /// Zero values are clamped to 1.
+    pub fn with_batching(mut self, batch_size: usize, concurrency: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self.batch_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Rerank search results with `reranker`, choosing from `candidates`
+    /// times as many vector matches as requested.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>, candidates: usize) -> Self {
+        self.reranker = Some(reranker);
+        self.rerank_candidates = candidates.max(1);
+        self
+    }
+
+    /// Require vectors of exactly `dim` dimensions.  0 leaves it to the
+    /// first stored embedding.
+    pub fn with_expected_dim(mut self, dim: usize) -> Self {
+        self.expected_dim = (dim > 0).then_some(dim);
+        self
+    }
+
+    /// Check, before anything is embedded, that the configured dimension,
+    /// the model's known dimension and the dimension already stored in the
+    /// database agree.  Catches a changed embedder at startup instead of
+    /// on the first write.
+    pub async fn verify_dim(&self) -> Result<()> {
+        match (self.expected_dim, self.dim()) {
+            (Some(expected), Some(model_dim)) if expected != model_dim => {
+                return Err(SafeAgentError::Config(format!(
+                    "memory.embedding_dim is {expected} but embedding model {} produces {model_dim}-dimensional vectors",
+                    self.model
+                )));
+            }
+            _ => {}
+        }
+        let Some(wanted) = self.expected_dim.or(self.dim()) else {
+            return Ok(());
+        };
+        let db = self.db.lock().await;
+        match stored_dim(&db)? {
+            Some(stored) if stored != wanted => Err(SafeAgentError::Config(format!(
+                "stored embeddings are {stored}-dimensional but {} is configured for {wanted}; \
+                 re-embed memory or switch back to the previous embedding model",
+                self.model
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject a vector whose dimension doesn't match the configured one or
+    /// the one already stored; record the dimension on the first write.
+    fn check_dim(&self, db: &Connection, dim: usize) -> Result<()> {
+        if let Some(expected) = self.expected_dim.filter(|&e| e != dim) {
+            return Err(SafeAgentError::Config(format!(
+                "embedding model {} produced {dim}-dimensional vectors but memory.embedding_dim is {expected}",
+                self.model
+            )));
+        }
+        match stored_dim(db)? {
+            Some(stored) if stored != dim => Err(SafeAgentError::Config(format!(
+                "embedding model {} produced {dim}-dimensional vectors but stored embeddings are {stored}-dimensional",
+                self.model
+            ))),
+            Some(_) => Ok(()),
+            None => {
+                db.execute(
+                    "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+                    rusqlite::params![DIM_METADATA_KEY, dim.to_string()],
+                )?;
+                Ok(())
+            }
+        }
+    }
+
     /// Generate an embedding vector for a single text.
     pub async fn embed_text(&self, text: &str) -> Result<Vec<f32>> {
-        let url = format!("{}/api/embed", self.base_url);
+        self.request_embeddings(vec![text.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| SafeAgentError::Llm("no embedding returned".into()))
+    }
+
+    /// Generate embeddings for many texts, returned in input order.
+    ///
+    /// Inputs are split into sub-batches of at most `batch_size` so large
+    /// documents stay under the embedding API's request-size limit, and up
+    /// to `batch_concurrency` sub-batches are requested at once.  If any
+    /// sub-batch fails the whole call fails.
+    pub async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        embed_in_batches(texts, self.batch_size, self.batch_concurrency, |_, chunk| {
+            self.request_embeddings(chunk)
+        })
+        .await
+    }
+
+    /// Embed one sub-batch with the configured backend.
+    async fn request_embeddings(&self, input: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let (client, base_url) = match &self.backend {
+            EmbeddingBackend::Ollama { client, base_url } => (client, base_url),
+            #[cfg(feature = "local")]
+            EmbeddingBackend::Local(embedder) => return embedder.embed_async(input).await,
+        };
+
+        let url = format!("{base_url}/api/embed");
         let body = EmbedRequest {
             model: self.model.clone(),
-            input: vec![text.to_string()],
+            input,
         };
 
-        let resp = self.client.post(&url).json(&body).send().await.map_err(|e| {
+        let resp = client.post(&url).json(&body).send().await.map_err(|e| {
             SafeAgentError::Llm(format!("embedding request failed: {e}"))
         })?;
 
@@ -96,11 +258,7 @@ impl EmbeddingEngine {
             SafeAgentError::Llm(format!("failed to parse embedding response: {e}"))
         })?;
 
-        embed_resp
-            .embeddings
-            .into_iter()
-            .next()
-            .ok_or_else(|| SafeAgentError::Llm("no embedding returned".into()))
+        Ok(embed_resp.embeddings)
     }
 
     /// Store an embedding for a given source row.
@@ -113,6 +271,7 @@ impl EmbeddingEngine {
         let blob = embedding_to_blob(embedding);
         let model = self.model.clone();
         let db = self.db.lock().await;
+        self.check_dim(&db, embedding.len())?;
         db.execute(
             "INSERT OR REPLACE INTO memory_embeddings (source_table, source_id, embedding, model)
              VALUES (?1, ?2, ?3, ?4)",
@@ -121,6 +280,39 @@ impl EmbeddingEngine {
         Ok(())
     }
 
+    /// Embed up to `limit` archival entries and knowledge nodes that have no
+    /// stored embedding yet, in sub-batches via [`Self::embed_batch`].
+    /// Returns how many rows were indexed.
+    pub async fn index_pending(&self, limit: usize) -> Result<usize> {
+        let pending: Vec<(String, i64, String)> = {
+            let db = self.db.lock().await;
+            let mut stmt = db.prepare(
+                "SELECT 'archival_memory', a.id, a.content FROM archival_memory a
+                 WHERE NOT EXISTS (SELECT 1 FROM memory_embeddings e
+                                   WHERE e.source_table = 'archival_memory' AND e.source_id = a.id)
+                 UNION ALL
+                 SELECT 'knowledge_nodes', n.id,
+                        CASE WHEN n.content = '' THEN n.label ELSE n.label || ': ' || n.content END
+                 FROM knowledge_nodes n
+                 WHERE NOT EXISTS (SELECT 1 FROM memory_embeddings e
+                                   WHERE e.source_table = 'knowledge_nodes' AND e.source_id = n.id)
+                 LIMIT ?1",
+            )?;
+            let rows = stmt.query_map([limit as i64], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<std::result::Result<_, _>>()?
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let texts: Vec<String> = pending.iter().map(|(_, _, text)| text.clone()).collect();
+        let vectors = self.embed_batch(&texts).await?;
+        for ((table, id, _), vector) in pending.iter().zip(&vectors) {
+            self.store_embedding(table, *id, vector).await?;
+        }
+        Ok(pending.len())
+    }
+
     /// Generate and store an embedding for archival memory content.
     pub async fn embed_archival(&self, entry_id: i64, content: &str) -> Result<()> {
         match self.embed_text(content).await {
@@ -148,7 +340,8 @@ impl EmbeddingEngine {
         }
     }
 
-    /// Semantic search: find the top-N most similar entries by cosine similarity.
+    /// Semantic search: find the top-N most similar entries by cosine
+    /// similarity, reranked when a reranker is configured.
     pub async fn search(
         &self,
         query: &str,
@@ -156,47 +349,91 @@ impl EmbeddingEngine {
         limit: usize,
     ) -> Result<Vec<ScoredResult>> {
         let query_vec = self.embed_text(query).await?;
+        self.search_by_vector(query, &query_vec, source_table, limit).await
+    }
 
-        let db = self.db.lock().await;
-        let mut stmt = db.prepare(
-            "SELECT source_id, embedding FROM memory_embeddings WHERE source_table = ?1",
-        )?;
+    async fn search_by_vector(
+        &self,
+        query: &str,
+        query_vec: &[f32],
+        source_table: &str,
+        limit: usize,
+    ) -> Result<Vec<ScoredResult>> {
+        let fetch = if self.reranker.is_some() {
+            limit.saturating_mul(self.rerank_candidates)
+        } else {
+            limit
+        };
 
-        let rows: Vec<(i64, Vec<u8>)> = stmt
-            .query_map([source_table], |row| {
-                Ok((row.get(0)?, row.get(1)?))
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
-
-        let mut scored: Vec<ScoredResult> = rows
-            .iter()
-            .filter_map(|(id, blob)| {
-                let emb = blob_to_embedding(blob);
-                let score = cosine_similarity(&query_vec, &emb);
-                if score.is_finite() {
-                    Some(ScoredResult {
-                        source_table: source_table.to_string(),
-                        source_id: *id,
-                        score,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let (mut scored, texts) = {
+            let db = self.db.lock().await;
+            let mut stmt = db.prepare(
+                "SELECT source_id, embedding FROM memory_embeddings WHERE source_table = ?1",
+            )?;
+
+            let rows: Vec<(i64, Vec<u8>)> = stmt
+                .query_map([source_table], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            let mut scored: Vec<ScoredResult> = rows
+                .iter()
+                .filter_map(|(id, blob)| {
+                    let emb = blob_to_embedding(blob);
+                    let score = cosine_similarity(query_vec, &emb);
+                    if score.is_finite() {
+                        Some(ScoredResult {
+                            source_table: source_table.to_string(),
+                            source_id: *id,
+                            score,
+                        })
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+            scored.truncate(fetch);
+
+            debug!(
+                query_len = query.len(),
+                table = source_table,
+                candidates = rows.len(),
+                results = scored.len(),
+                "embedding search complete"
+            );
+
+            // The reranker reads the text, fetched while the lock is held.
+            let texts: Vec<String> = if self.reranker.is_some() {
+                scored.iter().map(|r| source_text(&db, source_table, r.source_id)).collect()
+            } else {
+                Vec::new()
+            };
+            (scored, texts)
+        };
 
-        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        let Some(ref reranker) = self.reranker else {
+            return Ok(scored);
+        };
+        match reranker.score(query, &texts).await {
+            Ok(scores) if scores.len() == scored.len() => {
+                for (result, score) in scored.iter_mut().zip(scores) {
+                    result.score = score;
+                }
+                scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+            }
+            Ok(scores) => warn!(
+                reranker = reranker.name(),
+                expected = scored.len(),
+                got = scores.len(),
+                "reranker returned the wrong number of scores, keeping vector order"
+            ),
+            Err(e) => warn!(reranker = reranker.name(), err = %e, "reranking failed, keeping vector order"),
+        }
         scored.truncate(limit);
-
-        debug!(
-            query_len = query.len(),
-            table = source_table,
-            candidates = rows.len(),
-            results = scored.len(),
-            "embedding search complete"
-        );
-
         Ok(scored)
     }
 
@@ -211,10 +448,100 @@ impl EmbeddingEngine {
     }
 
     /// Check if Ollama is reachable and the embedding model is available.
+    /// Local models are always available once loaded.
     pub async fn health_check(&self) -> bool {
-        let url = format!("{}/api/tags", self.base_url);
-        self.client.get(&url).send().await.is_ok()
+        match &self.backend {
+            EmbeddingBackend::Ollama { client, base_url } => {
+                client.get(format!("{base_url}/api/tags")).send().await.is_ok()
+            }
+            #[cfg(feature = "local")]
+            EmbeddingBackend::Local(_) => true,
+        }
+    }
+}
+
+/// The text embedded for a source row, as shown to the reranker.
+fn source_text(db: &Connection, source_table: &str, source_id: i64) -> String {
+    let text = match source_table {
+        "archival_memory" => db
+            .query_row("SELECT content FROM archival_memory WHERE id = ?1", [source_id], |row| row.get(0))
+            .ok(),
+        "knowledge_nodes" => db
+            .query_row(
+                "SELECT CASE WHEN content = '' THEN label ELSE label || ': ' || content END
+                 FROM knowledge_nodes WHERE id = ?1",
+                [source_id],
+                |row| row.get(0),
+            )
+            .ok(),
+        _ => None,
+    };
+    text.unwrap_or_default()
+}
+
+/// Dimension of the stored embeddings: the recorded one, or else inferred
+/// from an existing row (databases from before it was recorded).
+fn stored_dim(db: &Connection) -> Result<Option<usize>> {
+    let recorded: Option<String> = db
+        .query_row("SELECT value FROM metadata WHERE key = ?1", [DIM_METADATA_KEY], |row| row.get(0))
+        .optional()?;
+    if let Some(value) = recorded {
+        return Ok(value.parse().ok());
     }
+    let inferred: Option<i64> = db
+        .query_row("SELECT length(embedding) / 4 FROM memory_embeddings LIMIT 1", [], |row| row.get(0))
+        .optional()?;
+    Ok(inferred.map(|n| n as usize))
+}
+
+/// Split `texts` into sub-batches of `batch_size`, run `embed` on up to
+/// `concurrency` of them at once, and reassemble the results in input order.
+///
+/// `embed` receives the sub-batch index and its inputs and must return one
+/// vector per input.
+async fn embed_in_batches<F, Fut>(
+    texts: &[String],
+    batch_size: usize,
+    concurrency: usize,
+    embed: F,
+) -> Result<Vec<Vec<f32>>>
+where
+    F: Fn(usize, Vec<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<Vec<f32>>>>,
+{
+    if texts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let batch_size = batch_size.max(1);
+    let total = texts.len().div_ceil(batch_size);
+
+    let mut batches: Vec<(usize, Vec<Vec<f32>>)> = stream::iter(texts.chunks(batch_size).enumerate())
+        .map(|(index, chunk)| {
+            let expected = chunk.len();
+            let fut = embed(index, chunk.to_vec());
+            async move {
+                let vectors = fut.await.map_err(|e| {
+                    SafeAgentError::Llm(format!("embedding sub-batch {}/{total} failed: {e}", index + 1))
+                })?;
+                if vectors.len() != expected {
+                    return Err(SafeAgentError::Llm(format!(
+                        "embedding sub-batch {}/{total} returned {} vectors for {expected} inputs",
+                        index + 1,
+                        vectors.len(),
+                    )));
+                }
+                Ok((index, vectors))
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    batches.sort_by_key(|(index, _)| *index);
+    debug!(inputs = texts.len(), batches = total, "batched embedding complete");
+
+    Ok(batches.into_iter().flat_map(|(_, vectors)| vectors).collect())
 }
 
 fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
@@ -294,6 +621,225 @@ mod tests {
         assert_eq!(cosine_similarity(&[], &[]), 0.0);
     }
 
+    /// Fake embedder: one vector per input holding the input's number,
+    /// finishing later sub-batches first to exercise reordering.
+    async fn fake_embed(index: usize, chunk: Vec<String>, calls: &std::sync::Mutex<Vec<usize>>) -> Result<Vec<Vec<f32>>> {
+        calls.lock().unwrap().push(chunk.len());
+        tokio::time::sleep(std::time::Duration::from_millis(20u64.saturating_sub(index as u64))).await;
+        Ok(chunk.iter().map(|t| vec![t.parse::<f32>().unwrap()]).collect())
+    }
+
+    #[tokio::test]
+    async fn embed_batch_splits_and_preserves_order() {
+        let texts: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+        let calls = std::sync::Mutex::new(Vec::new());
+
+        let vectors = embed_in_batches(&texts, 64, 4, |i, chunk| fake_embed(i, chunk, &calls))
+            .await
+            .unwrap();
+
+        let sizes = calls.into_inner().unwrap();
+        assert_eq!(sizes.len(), 16);
+        assert_eq!(sizes.iter().sum::<usize>(), 1000);
+        assert!(sizes.iter().all(|&n| n <= 64));
+
+        assert_eq!(vectors.len(), 1000);
+        for (i, v) in vectors.iter().enumerate() {
+            assert_eq!(v, &vec![i as f32]);
+        }
+    }
+
+    #[tokio::test]
+    async fn embed_batch_failure_names_sub_batch() {
+        let texts: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        let err = embed_in_batches(&texts, 4, 2, |i, chunk| async move {
+            if i == 1 {
+                Err(SafeAgentError::Llm("payload too large".into()))
+            } else {
+                Ok(chunk.iter().map(|_| vec![0.0]).collect())
+            }
+        })
+        .await
+        .unwrap_err();
+
+        let msg = err.to_string();
+        assert!(msg.contains("sub-batch 2/3"), "{msg}");
+        assert!(msg.contains("payload too large"), "{msg}");
+    }
+
+    #[tokio::test]
+    async fn embed_batch_empty_input() {
+        let vectors = embed_in_batches(&[], 8, 2, |_, _| async { Ok(vec![vec![0.0]]) })
+            .await
+            .unwrap();
+        assert!(vectors.is_empty());
+    }
+
+    fn engine(db: Arc<Mutex<Connection>>, dim: usize) -> EmbeddingEngine {
+        EmbeddingEngine::new(db, "http://localhost:1", "test-embed")
+            .unwrap()
+            .with_expected_dim(dim)
+    }
+
+    async fn stored_count(db: &Arc<Mutex<Connection>>) -> i64 {
+        db.lock()
+            .await
+            .query_row("SELECT COUNT(*) FROM memory_embeddings", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn configured_dim_accepts_matching_vectors() {
+        let db = crate::db::test_db();
+        let engine = engine(db.clone(), 3);
+        engine.verify_dim().await.unwrap();
+        engine.store_embedding("archival_memory", 1, &[0.1, 0.2, 0.3]).await.unwrap();
+        assert_eq!(stored_count(&db).await, 1);
+        assert_eq!(stored_dim(&*db.lock().await).unwrap(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn configured_dim_rejects_mismatch_before_writing() {
+        let db = crate::db::test_db();
+        let err = engine(db.clone(), 4)
+            .store_embedding("archival_memory", 1, &[0.1, 0.2, 0.3])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("3-dimensional"), "{err}");
+        assert!(err.to_string().contains("embedding_dim is 4"), "{err}");
+        assert_eq!(stored_count(&db).await, 0);
+    }
+
+    #[tokio::test]
+    async fn stored_dim_rejects_a_different_embedder_after_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("embed.db");
+        let open = || Arc::new(Mutex::new(crate::db::open(&path).unwrap()));
+
+        // First run: no dimension configured, learned from the first write.
+        let db = open();
+        engine(db.clone(), 0).store_embedding("archival_memory", 1, &[0.1, 0.2, 0.3]).await.unwrap();
+        drop(db);
+
+        // Later run configured for another model: refused at startup.
+        let db = open();
+        let err = engine(db.clone(), 768).verify_dim().await.unwrap_err();
+        assert!(err.to_string().contains("stored embeddings are 3-dimensional"), "{err}");
+
+        // Unconfigured, but the new model's vectors differ: refused on write.
+        let unconfigured = engine(db.clone(), 0);
+        unconfigured.verify_dim().await.unwrap();
+        assert!(unconfigured.store_embedding("archival_memory", 2, &[0.1; 768]).await.is_err());
+        assert_eq!(stored_count(&db).await, 1);
+        unconfigured.store_embedding("archival_memory", 2, &[0.4, 0.5, 0.6]).await.unwrap();
+    }
+
+    /// Serve a fake Ollama `/api/embed` that returns `[len(text), 1]` per
+    /// input, counting requests.
+    async fn serve_embedder(requests: Arc<std::sync::atomic::AtomicUsize>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let app = axum::Router::new().route(
+            "/api/embed",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let embeddings: Vec<Vec<f32>> = body["input"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|t| vec![t.as_str().unwrap().len() as f32, 1.0])
+                    .collect();
+                axum::Json(serde_json::json!({ "embeddings": embeddings }))
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    #[tokio::test]
+    async fn index_pending_embeds_unindexed_rows_in_batches() {
+        let db = crate::db::test_db();
+        {
+            let db = db.lock().await;
+            for i in 1..=5 {
+                db.execute("INSERT INTO archival_memory (id, content) VALUES (?1, ?2)", rusqlite::params![i, "x".repeat(i as usize)])
+                    .unwrap();
+            }
+            db.execute("INSERT INTO knowledge_nodes (id, label, content) VALUES (1, 'rust', '')", []).unwrap();
+        }
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let base = serve_embedder(requests.clone()).await;
+        let engine = EmbeddingEngine::new(db.clone(), &base, "test-embed").unwrap().with_batching(2, 2);
+
+        assert_eq!(engine.index_pending(100).await.unwrap(), 6);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(stored_count(&db).await, 6);
+
+        let blob: Vec<u8> = db
+            .lock()
+            .await
+            .query_row("SELECT embedding FROM memory_embeddings WHERE source_table = 'archival_memory' AND source_id = 4", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(blob_to_embedding(&blob), vec![4.0, 1.0]);
+
+        // Already indexed rows are skipped.
+        assert_eq!(engine.index_pending(100).await.unwrap(), 0);
+    }
+
+    /// Scores documents by their position, so the last candidate by vector
+    /// similarity ranks first.
+    struct ReversingReranker;
+
+    #[async_trait::async_trait]
+    impl Reranker for ReversingReranker {
+        fn name(&self) -> &str {
+            "reverse"
+        }
+
+        async fn score(&self, _query: &str, documents: &[String]) -> Result<Vec<f32>> {
+            Ok((0..documents.len()).map(|i| i as f32).collect())
+        }
+    }
+
+    /// Archival entries 1..=4 whose vectors get steadily less similar to
+    /// `[1, 0]`.
+    async fn ranked_archival(db: &Arc<Mutex<Connection>>, engine: &EmbeddingEngine) {
+        for i in 1..=4 {
+            db.lock()
+                .await
+                .execute("INSERT INTO archival_memory (id, content) VALUES (?1, ?2)", rusqlite::params![i, format!("entry {i}")])
+                .unwrap();
+            engine.store_embedding("archival_memory", i, &[1.0, i as f32]).await.unwrap();
+        }
+    }
+
+    fn ids(results: &[ScoredResult]) -> Vec<i64> {
+        results.iter().map(|r| r.source_id).collect()
+    }
+
+    #[tokio::test]
+    async fn search_without_reranker_orders_by_similarity() {
+        let db = crate::db::test_db();
+        let engine = engine(db.clone(), 0);
+        ranked_archival(&db, &engine).await;
+
+        let results = engine.search_by_vector("q", &[1.0, 0.0], "archival_memory", 2).await.unwrap();
+        assert_eq!(ids(&results), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn reranker_reorders_overfetched_candidates() {
+        let db = crate::db::test_db();
+        let engine = engine(db.clone(), 0).with_reranker(Arc::new(ReversingReranker), 2);
+        ranked_archival(&db, &engine).await;
+
+        // Four candidates fetched (2 × 2), reversed by the reranker, top 2 kept.
+        let results = engine.search_by_vector("q", &[1.0, 0.0], "archival_memory", 2).await.unwrap();
+        assert_eq!(ids(&results), vec![4, 3]);
+        assert_eq!(results[0].score, 3.0);
+        assert_eq!(source_text(&*db.lock().await, "archival_memory", 4), "entry 4");
+    }
+
     #[test]
     fn test_cosine_similarity_mismatched_len() {
         let a = vec![1.0, 2.0];
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
@@ -14,6 +15,8 @@ pub struct Episode {
     pub actions: Vec<EpisodeAction>,
     pub outcome: String,
     pub user_id: Option<String>,
+    /// Short category such as "coding" or "scheduling".
+    pub tag: Option<String>,
     pub created_at: String,
 }
 
@@ -42,12 +45,13 @@ impl EpisodicMemory {
         actions: &[EpisodeAction],
         outcome: &str,
         user_id: Option<&str>,
+        tag: Option<&str>,
     ) -> Result<i64> {
         let actions_json = serde_json::to_string(actions).unwrap_or_else(|_| "[]".to_string());
         let db = self.db.lock().await;
         db.execute(
-            "INSERT INTO episodes (trigger, summary, actions, outcome, user_id) VALUES (?1, ?2, ?3, ?4, ?5)",
-            rusqlite::params![trigger, summary, actions_json, outcome, user_id],
+            "INSERT INTO episodes (trigger, summary, actions, outcome, user_id, tag) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![trigger, summary, actions_json, outcome, user_id, tag],
         )?;
         Ok(db.last_insert_rowid())
     }
@@ -58,7 +62,7 @@ impl EpisodicMemory {
 
         let (sql, episodes) = if let Some(uid) = user_id {
             let mut stmt = db.prepare(
-                "SELECT id, trigger, summary, actions, outcome, user_id, created_at
+                "SELECT id, trigger, summary, actions, outcome, user_id, created_at, tag
                  FROM episodes WHERE user_id = ?1 ORDER BY id DESC LIMIT ?2",
             )?;
             let eps = stmt
@@ -68,7 +72,7 @@ impl EpisodicMemory {
             ("user", eps)
         } else {
             let mut stmt = db.prepare(
-                "SELECT id, trigger, summary, actions, outcome, user_id, created_at
+                "SELECT id, trigger, summary, actions, outcome, user_id, created_at, tag
                  FROM episodes ORDER BY id DESC LIMIT ?1",
             )?;
             let eps = stmt
@@ -87,7 +91,7 @@ impl EpisodicMemory {
         let db = self.db.lock().await;
         let pattern = format!("%{query}%");
         let mut stmt = db.prepare(
-            "SELECT id, trigger, summary, actions, outcome, user_id, created_at
+            "SELECT id, trigger, summary, actions, outcome, user_id, created_at, tag
              FROM episodes WHERE summary LIKE ?1 OR outcome LIKE ?1
              ORDER BY id DESC LIMIT ?2",
         )?;
@@ -98,6 +102,37 @@ impl EpisodicMemory {
         Ok(episodes)
     }
 
+    /// Episodes created in `[from, to)` with the given tag, oldest first.
+    /// Any bound left as `None` is not applied.
+    pub async fn query(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        tag: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Episode>> {
+        // created_at uses SQLite's datetime('now') format, which sorts
+        // correctly as text.
+        let fmt = |t: DateTime<Utc>| t.format("%Y-%m-%d %H:%M:%S").to_string();
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT id, trigger, summary, actions, outcome, user_id, created_at, tag
+             FROM episodes
+             WHERE (?1 IS NULL OR created_at >= ?1)
+               AND (?2 IS NULL OR created_at < ?2)
+               AND (?3 IS NULL OR tag = ?3)
+             ORDER BY created_at, id LIMIT ?4",
+        )?;
+        let episodes = stmt
+            .query_map(
+                rusqlite::params![from.map(fmt), to.map(fmt), tag, limit as i64],
+                map_episode,
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(episodes)
+    }
+
     /// Count total episodes.
     pub async fn count(&self) -> Result<i64> {
         let db = self.db.lock().await;
@@ -117,6 +152,7 @@ fn map_episode(row: &rusqlite::Row) -> rusqlite::Result<Episode> {
         actions,
         outcome: row.get(4)?,
         user_id: row.get(5)?,
+        tag: row.get(7)?,
         created_at: row.get(6)?,
     })
 }
@@ -139,7 +175,7 @@ mod tests {
         }];
 
         let id = em
-            .record("user_message", "user asked to list files", &actions, "success", None)
+            .record("user_message", "user asked to list files", &actions, "success", None, None)
             .await
             .unwrap();
         assert!(id > 0);
@@ -156,10 +192,10 @@ mod tests {
         let db = test_db();
         let em = EpisodicMemory::new(db);
 
-        em.record("user_message", "deployed to production", &[], "success", None)
+        em.record("user_message", "deployed to production", &[], "success", None, None)
             .await
             .unwrap();
-        em.record("cron_job", "ran backup", &[], "completed", None)
+        em.record("cron_job", "ran backup", &[], "completed", None, None)
             .await
             .unwrap();
 
@@ -174,8 +210,8 @@ mod tests {
         let em = EpisodicMemory::new(db);
 
         assert_eq!(em.count().await.unwrap(), 0);
-        em.record("test", "ep1", &[], "", None).await.unwrap();
-        em.record("test", "ep2", &[], "", None).await.unwrap();
+        em.record("test", "ep1", &[], "", None, None).await.unwrap();
+        em.record("test", "ep2", &[], "", None, None).await.unwrap();
         assert_eq!(em.count().await.unwrap(), 2);
     }
 
@@ -184,10 +220,10 @@ mod tests {
         let db = test_db();
         let em = EpisodicMemory::new(db);
 
-        em.record("msg", "user1 action", &[], "ok", Some("u1"))
+        em.record("msg", "user1 action", &[], "ok", Some("u1"), None)
             .await
             .unwrap();
-        em.record("msg", "user2 action", &[], "ok", Some("u2"))
+        em.record("msg", "user2 action", &[], "ok", Some("u2"), None)
             .await
             .unwrap();
 
@@ -195,4 +231,37 @@ mod tests {
         assert_eq!(u1_eps.len(), 1);
         assert!(u1_eps[0].summary.contains("user1"));
     }
+
+    #[tokio::test]
+    async fn query_by_time_range_and_tag() {
+        let db = test_db();
+        let em = EpisodicMemory::new(db.clone());
+
+        for (summary, tag, at) in [
+            ("before window", "coding", "2025-03-01 08:00:00"),
+            ("in window", "coding", "2025-03-01 10:00:00"),
+            ("in window, other tag", "scheduling", "2025-03-01 11:00:00"),
+            ("window end", "coding", "2025-03-01 12:00:00"),
+            ("also in window", "coding", "2025-03-01 09:30:00"),
+        ] {
+            let id = em.record("msg", summary, &[], "ok", None, Some(tag)).await.unwrap();
+            db.lock()
+                .await
+                .execute("UPDATE episodes SET created_at = ?1 WHERE id = ?2", rusqlite::params![at, id])
+                .unwrap();
+        }
+
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let from = Some(at("2025-03-01T09:00:00Z"));
+        let to = Some(at("2025-03-01T12:00:00Z"));
+
+        let eps = em.query(from, to, Some("coding"), 10).await.unwrap();
+        let summaries: Vec<&str> = eps.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(summaries, ["also in window", "in window"]);
+        assert!(eps.iter().all(|e| e.tag.as_deref() == Some("coding")));
+
+        assert_eq!(em.query(from, to, None, 10).await.unwrap().len(), 3);
+        assert_eq!(em.query(None, None, Some("coding"), 10).await.unwrap().len(), 4);
+        assert_eq!(em.query(from, None, None, 2).await.unwrap().len(), 2);
+    }
 }
@@ -31,6 +31,8 @@ struct ExtractionResult {
     relations: Vec<RelationExtract>,
     #[serde(default)]
     episode_summary: String,
+    #[serde(default)]
+    episode_tag: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -72,7 +74,8 @@ Extract the following into a JSON object (NO markdown fences, ONLY raw JSON):
   "user_preferences": [{"key": "category_name", "value": "observed preference", "confidence": 0.8}],
   "entities": [{"label": "entity name", "type": "person/org/tool/concept/location", "content": "brief description"}],
   "relations": [{"source": "entity_label", "target": "entity_label", "relation": "relationship type"}],
-  "episode_summary": "one-sentence summary of what happened in this interaction"
+  "episode_summary": "one-sentence summary of what happened in this interaction",
+  "episode_tag": "one lowercase word categorising the interaction"
 }
 
 Rules:
@@ -81,6 +84,7 @@ Rules:
 - For entities, only extract proper nouns or significant concepts.
 - Relations should only reference entities you extracted.
 - Confidence 0.5-1.0 (higher = more certain).
+- For episode_tag, prefer: coding, research, scheduling, communication, planning, personal, system.
 - If nothing notable to extract, return empty arrays and an episode_summary.
 - Return ONLY valid JSON, no explanation."#;
 
@@ -101,6 +105,9 @@ pub async fn extract_from_conversation(
         message: &prompt,
         tools: None,
         prompt_skills: &[],
+        personality: None,
+        persona: None,
+        attachments: &[],
     };
 
     let response = match llm.generate(&gen_ctx).await {
@@ -141,7 +148,7 @@ pub async fn extract_from_conversation(
     // 2. Update user profile
     for pref in &extraction.user_preferences {
         if let Err(e) = user_model
-            .set(user_id, &pref.key, &pref.value, pref.confidence, "auto_extraction")
+            .observe(user_id, &pref.key, &pref.value, pref.confidence, "auto_extraction")
             .await
         {
             warn!(key = %pref.key, err = %e, "failed to update user profile");
@@ -205,8 +212,11 @@ pub async fn extract_from_conversation(
         format!("{} facts extracted", extraction.facts.len())
     };
 
+    let tag = extraction.episode_tag.trim().to_lowercase();
+    let tag = (!tag.is_empty()).then_some(tag.as_str());
+
     if let Err(e) = episodic
-        .record("user_message", summary, tool_actions, &outcome, user_id)
+        .record("user_message", summary, tool_actions, &outcome, user_id, tag)
         .await
     {
         warn!(err = %e, "failed to record episode");
@@ -259,12 +269,14 @@ mod tests {
             "user_preferences": [{"key": "language", "value": "Rust", "confidence": 0.9}],
             "entities": [{"label": "Rust", "type": "language", "content": "Systems programming"}],
             "relations": [],
-            "episode_summary": "discussed programming languages"
+            "episode_summary": "discussed programming languages",
+            "episode_tag": "coding"
         }"#;
         let result = parse_extraction_response(json).unwrap();
         assert_eq!(result.facts.len(), 1);
         assert_eq!(result.user_preferences.len(), 1);
         assert_eq!(result.entities.len(), 1);
+        assert_eq!(result.episode_tag, "coding");
     }
 
     #[test]
@@ -0,0 +1,273 @@
+//! Local sentence embeddings via an ONNX model (requires the `local` feature).
+//!
+//! Loads a sentence-transformers style export — `model.onnx` plus the
+//! matching `tokenizer.json` — and produces mean-pooled, L2-normalised
+//! vectors without any network access.  MiniLM (`all-MiniLM-L6-v2`, 384
+//! dimensions) is the intended default, but any BERT-style encoder whose
+//! first output is `[batch, seq, hidden]` works.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ort::session::{Session, SessionInputValue};
+use ort::value::Tensor;
+use tokenizers::{Tokenizer, TruncationParams};
+use tracing::info;
+
+use crate::error::{Result, SafeAgentError};
+
+/// Longest input (in tokens) fed to the model; longer text is truncated.
+const MAX_TOKENS: usize = 256;
+
+pub struct LocalEmbedder {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    /// Whether the model takes a `token_type_ids` input.
+    uses_token_types: bool,
+    dim: usize,
+    name: String,
+}
+
+impl LocalEmbedder {
+    /// Load a model from `path`: either a directory holding `model.onnx`
+    /// and `tokenizer.json`, or the `.onnx` file itself with
+    /// `tokenizer.json` alongside it.
+    ///
+    /// Runs one probe inference so [`Self::dim`] is known up front.
+    pub fn load(path: &Path) -> Result<Self> {
+        let (model_file, tokenizer_file) = resolve_files(path);
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_file).map_err(|e| {
+            SafeAgentError::Config(format!(
+                "failed to load tokenizer {}: {e}",
+                tokenizer_file.display()
+            ))
+        })?;
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_TOKENS,
+                ..Default::default()
+            }))
+            .map_err(|e| SafeAgentError::Config(format!("tokenizer truncation: {e}")))?;
+
+        let session = Session::builder()
+            .and_then(|b| b.commit_from_file(&model_file))
+            .map_err(|e| {
+                SafeAgentError::Config(format!(
+                    "failed to load ONNX model {}: {e}",
+                    model_file.display()
+                ))
+            })?;
+        let uses_token_types = session.inputs.iter().any(|i| i.name == "token_type_ids");
+
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "local".into());
+
+        let mut embedder = Self {
+            session: Mutex::new(session),
+            tokenizer,
+            uses_token_types,
+            dim: 0,
+            name,
+        };
+
+        embedder.dim = embedder
+            .embed(&["dimension probe".to_string()])?
+            .first()
+            .map(Vec::len)
+            .filter(|&d| d > 0)
+            .ok_or_else(|| SafeAgentError::Config("local embedding model produced no output".into()))?;
+
+        info!(
+            model = %model_file.display(),
+            dim = embedder.dim,
+            "local embedding model loaded"
+        );
+
+        Ok(embedder)
+    }
+
+    /// Embedding dimension, known as soon as the model is loaded.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Model identifier recorded alongside stored embeddings.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Embed `texts` synchronously.  CPU bound — call from a blocking task.
+    pub fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| SafeAgentError::Llm(format!("tokenization failed: {e}")))?;
+
+        // Pad every sequence to the longest in the batch.
+        let batch = encodings.len();
+        let seq = encodings.iter().map(|e| e.get_ids().len()).max().unwrap_or(0).max(1);
+        let mut ids = vec![0i64; batch * seq];
+        let mut mask = vec![0i64; batch * seq];
+        let mut types = vec![0i64; batch * seq];
+        for (row, enc) in encodings.iter().enumerate() {
+            let offset = row * seq;
+            for (col, ((&id, &m), &t)) in enc
+                .get_ids()
+                .iter()
+                .zip(enc.get_attention_mask())
+                .zip(enc.get_type_ids())
+                .enumerate()
+            {
+                ids[offset + col] = id as i64;
+                mask[offset + col] = m as i64;
+                types[offset + col] = t as i64;
+            }
+        }
+
+        let tensor = |data: Vec<i64>| {
+            Tensor::from_array(([batch, seq], data))
+                .map_err(|e| SafeAgentError::Llm(format!("embedding input tensor: {e}")))
+        };
+        let mut inputs: Vec<(&str, SessionInputValue<'_>)> = vec![
+            ("input_ids", tensor(ids)?.into()),
+            ("attention_mask", tensor(mask.clone())?.into()),
+        ];
+        if self.uses_token_types {
+            inputs.push(("token_type_ids", tensor(types)?.into()));
+        }
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|e| SafeAgentError::Llm(format!("embedding session lock poisoned: {e}")))?;
+        let outputs = session
+            .run(inputs)
+            .map_err(|e| SafeAgentError::Llm(format!("local embedding inference failed: {e}")))?;
+        let (shape, hidden) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| SafeAgentError::Llm(format!("unexpected embedding output: {e}")))?;
+
+        let dim = shape.last().copied().unwrap_or(0) as usize;
+        if dim == 0 || hidden.len() != batch * seq * dim {
+            return Err(SafeAgentError::Llm(format!(
+                "unexpected embedding output shape {:?}",
+                &shape[..]
+            )));
+        }
+
+        Ok((0..batch)
+            .map(|row| mean_pool(&hidden[row * seq * dim..(row + 1) * seq * dim], &mask[row * seq..(row + 1) * seq], dim))
+            .collect())
+    }
+
+    /// Embed `texts` on the blocking thread pool.
+    pub async fn embed_async(self: &Arc<Self>, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let embedder = Arc::clone(self);
+        tokio::task::spawn_blocking(move || embedder.embed(&texts))
+            .await
+            .map_err(|e| SafeAgentError::Llm(format!("blocking task join error: {e}")))?
+    }
+}
+
+/// Locate the model and tokenizer files for a configured path.
+fn resolve_files(path: &Path) -> (PathBuf, PathBuf) {
+    if path.is_dir() {
+        (path.join("model.onnx"), path.join("tokenizer.json"))
+    } else {
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        (path.to_path_buf(), dir.join("tokenizer.json"))
+    }
+}
+
+/// Average the token vectors of one sequence over its attention mask, then
+/// L2-normalise the result.
+fn mean_pool(hidden: &[f32], mask: &[i64], dim: usize) -> Vec<f32> {
+    let mut pooled = vec![0.0f32; dim];
+    let mut count = 0.0f32;
+    for (token, &m) in hidden.chunks_exact(dim).zip(mask) {
+        if m == 0 {
+            continue;
+        }
+        count += 1.0;
+        for (p, v) in pooled.iter_mut().zip(token) {
+            *p += v;
+        }
+    }
+    if count > 0.0 {
+        pooled.iter_mut().for_each(|p| *p /= count);
+    }
+
+    let norm = pooled.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        pooled.iter_mut().for_each(|p| *p /= norm);
+    }
+    pooled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load the sentence-transformers ONNX export named by
+    /// `SAFECLAW_TEST_EMBEDDING_MODEL` (e.g. a local copy of
+    /// `all-MiniLM-L6-v2/onnx`).  Model-backed tests are `#[ignore]`d and
+    /// run with `cargo test --features local -- --ignored`.
+    fn test_model() -> LocalEmbedder {
+        let path = std::env::var("SAFECLAW_TEST_EMBEDDING_MODEL")
+            .expect("set SAFECLAW_TEST_EMBEDDING_MODEL to an ONNX embedding model");
+        LocalEmbedder::load(Path::new(&path)).expect("load test embedding model")
+    }
+
+    #[test]
+    fn mean_pool_ignores_padding_and_normalises() {
+        let hidden = [1.0, 0.0, 3.0, 0.0, 100.0, 100.0];
+        let pooled = mean_pool(&hidden, &[1, 1, 0], 2);
+        assert!((pooled[0] - 1.0).abs() < 1e-6);
+        assert!(pooled[1].abs() < 1e-6);
+    }
+
+    #[test]
+    fn resolve_files_accepts_dir_or_model_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (model, tok) = resolve_files(tmp.path());
+        assert_eq!(model, tmp.path().join("model.onnx"));
+        assert_eq!(tok, tmp.path().join("tokenizer.json"));
+
+        let (model, tok) = resolve_files(&tmp.path().join("minilm.onnx"));
+        assert_eq!(model, tmp.path().join("minilm.onnx"));
+        assert_eq!(tok, tmp.path().join("tokenizer.json"));
+    }
+
+    #[test]
+    #[ignore = "needs an ONNX model at SAFECLAW_TEST_EMBEDDING_MODEL"]
+    fn embeddings_have_model_dimension() {
+        let embedder = test_model();
+        assert!(embedder.dim() > 0);
+
+        let texts = vec!["hello world".to_string(), "a somewhat longer sentence to embed".to_string()];
+        let vectors = embedder.embed(&texts).unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert!(vectors.iter().all(|v| v.len() == embedder.dim()));
+    }
+
+    #[test]
+    #[ignore = "needs an ONNX model at SAFECLAW_TEST_EMBEDDING_MODEL"]
+    fn identical_text_yields_identical_vectors() {
+        let embedder = test_model();
+        let a = embedder.embed(&["the cat sat on the mat".to_string()]).unwrap();
+        let b = embedder
+            .embed(&["the cat sat on the mat".to_string(), "padding partner text".to_string()])
+            .unwrap();
+        assert_eq!(a[0].len(), b[0].len());
+        for (x, y) in a[0].iter().zip(&b[0]) {
+            assert!((x - y).abs() < 1e-5);
+        }
+    }
+}
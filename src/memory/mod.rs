@@ -6,6 +6,9 @@ pub mod embeddings;
 pub mod episodic;
 pub mod extraction;
 pub mod knowledge;
+pub mod rerank;
+#[cfg(feature = "local")]
+pub mod local_embeddings;
 pub mod user_model;
 
 use std::sync::Arc;
@@ -39,13 +42,52 @@ impl MemoryManager {
     }
 
     /// Initialize the embedding engine from memory config.
-    pub fn init_embeddings(&mut self, ollama_host: &str, model: &str) {
-        self.embeddings = embeddings::EmbeddingEngine::new(self.db.clone(), ollama_host, model);
-        if self.embeddings.is_some() {
-            tracing::info!(model, "embedding engine initialized");
+    pub fn init_embeddings(&mut self, ollama_host: &str, config: &crate::config::MemoryConfig) {
+        let model = config.embedding_model.as_str();
+        let engine = match config.embedding_backend.as_str() {
+            "local" => self.local_embeddings(config),
+            _ => embeddings::EmbeddingEngine::new(self.db.clone(), ollama_host, model),
+        };
+        self.embeddings = engine
+            .map(|e| {
+                let e = e
+                    .with_batching(config.embedding_batch_size, config.embedding_concurrency)
+                    .with_expected_dim(config.embedding_dim);
+                if config.rerank_url.is_empty() {
+                    e
+                } else {
+                    let reranker = rerank::ApiReranker::new(&config.rerank_url, &config.rerank_model);
+                    e.with_reranker(Arc::new(reranker), config.rerank_candidates)
+                }
+            });
+        if let Some(ref engine) = self.embeddings {
+            tracing::info!(
+                backend = %config.embedding_backend,
+                model,
+                dim = ?engine.dim(),
+                rerank = !config.rerank_url.is_empty(),
+                "embedding engine initialized"
+            );
         }
     }
 
+    #[cfg(feature = "local")]
+    fn local_embeddings(&self, config: &crate::config::MemoryConfig) -> Option<embeddings::EmbeddingEngine> {
+        let path = std::path::Path::new(&config.embedding_model_path);
+        embeddings::EmbeddingEngine::local(self.db.clone(), path)
+            .map_err(|e| tracing::warn!(err = %e, "local embedding model unavailable, falling back to FTS5"))
+            .ok()
+    }
+
+    #[cfg(not(feature = "local"))]
+    fn local_embeddings(&self, config: &crate::config::MemoryConfig) -> Option<embeddings::EmbeddingEngine> {
+        tracing::warn!(
+            path = %config.embedding_model_path,
+            "embedding_backend = \"local\" requires the `local` feature, falling back to FTS5"
+        );
+        None
+    }
+
     /// Semantic search over archival memory.
     /// Falls back to FTS5 if embeddings are unavailable or fail.
     pub async fn semantic_search_archival(
@@ -187,15 +229,24 @@ impl MemoryManager {
         Ok(())
     }
 
-    /// Get recent activity log entries.
-    pub async fn recent_activity(&self, limit: usize, offset: usize) -> Result<Vec<ActivityEntry>> {
+    /// Get recent activity log entries, newest first.
+    ///
+    /// `before_id` is a keyset cursor (the last ID of the previous page);
+    /// prefer it over `offset`, which shifts as new entries are logged.
+    pub async fn recent_activity(
+        &self,
+        limit: usize,
+        offset: usize,
+        before_id: Option<i64>,
+    ) -> Result<Vec<ActivityEntry>> {
         let db = self.db.lock().await;
         let mut stmt = db.prepare(
             "SELECT id, action_type, summary, detail, status, created_at
-             FROM activity_log ORDER BY id DESC LIMIT ?1 OFFSET ?2",
+             FROM activity_log WHERE id < ?1 ORDER BY id DESC LIMIT ?2 OFFSET ?3",
         )?;
+        let before_id = before_id.unwrap_or(i64::MAX);
         let entries = stmt
-            .query_map(rusqlite::params![limit as i64, offset as i64], |row| {
+            .query_map(rusqlite::params![before_id, limit as i64, offset as i64], |row| {
                 Ok(ActivityEntry {
                     id: row.get(0)?,
                     action_type: row.get(1)?,
@@ -272,7 +323,7 @@ mod tests {
         let mm = make_manager();
         mm.log_activity("test", "did something", Some("details here"), "ok").await.unwrap();
         mm.log_activity("test", "another", None, "error").await.unwrap();
-        let entries = mm.recent_activity(10, 0).await.unwrap();
+        let entries = mm.recent_activity(10, 0, None).await.unwrap();
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[0].summary, "another");
         assert_eq!(entries[0].status, "error");
@@ -286,10 +337,33 @@ mod tests {
         for i in 0..5 {
             mm.log_activity("t", &format!("entry {i}"), None, "ok").await.unwrap();
         }
-        let page = mm.recent_activity(2, 2).await.unwrap();
+        let page = mm.recent_activity(2, 2, None).await.unwrap();
         assert_eq!(page.len(), 2);
     }
 
+    #[tokio::test]
+    async fn recent_activity_cursor_pages_are_stable() {
+        let mm = make_manager();
+        for i in 0..5 {
+            mm.log_activity("t", &format!("entry {i}"), None, "ok").await.unwrap();
+        }
+        let first = mm.recent_activity(2, 0, None).await.unwrap();
+        let summaries: Vec<&str> = first.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(summaries, ["entry 4", "entry 3"]);
+
+        // New rows arriving between requests don't shift the next page.
+        mm.log_activity("t", "late 1", None, "ok").await.unwrap();
+        mm.log_activity("t", "late 2", None, "ok").await.unwrap();
+
+        let second = mm.recent_activity(2, 0, Some(first[1].id)).await.unwrap();
+        let summaries: Vec<&str> = second.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(summaries, ["entry 2", "entry 1"]);
+
+        let third = mm.recent_activity(2, 0, Some(second[1].id)).await.unwrap();
+        let summaries: Vec<&str> = third.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(summaries, ["entry 0"]);
+    }
+
     #[tokio::test]
     async fn get_stats_has_started_at() {
         let mm = make_manager();
@@ -300,7 +374,7 @@ mod tests {
     #[tokio::test]
     async fn recent_activity_empty() {
         let mm = make_manager();
-        let activity = mm.recent_activity(10, 0).await.unwrap();
+        let activity = mm.recent_activity(10, 0, None).await.unwrap();
         assert!(activity.is_empty());
     }
 }
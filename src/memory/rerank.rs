@@ -0,0 +1,130 @@
+//! Reranking of semantic search candidates.
+//!
+//! Vector similarity finds text on the right topic, not necessarily text
+//! that answers the query.  When a reranker is configured,
+//! [`super::embeddings::EmbeddingEngine::search`] over-fetches candidates by
+//! cosine similarity and lets the reranker — typically a cross-encoder that
+//! reads query and document together — pick the final order.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SafeAgentError};
+
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Relevance of each document to `query`, in input order.  Higher is
+    /// more relevant; the scale is up to the reranker.
+    async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>>;
+}
+
+/// A reranking service speaking the common `/rerank` API (Cohere, Jina,
+/// Voyage, vLLM, llama.cpp server): `{model, query, documents}` in,
+/// `{results: [{index, relevance_score}]}` out.
+pub struct ApiReranker {
+    client: Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RerankRequest<'a> {
+    model: &'a str,
+    query: &'a str,
+    documents: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct RerankResponse {
+    results: Vec<RerankResult>,
+}
+
+#[derive(Deserialize)]
+struct RerankResult {
+    index: usize,
+    relevance_score: f32,
+}
+
+impl ApiReranker {
+    /// `url` is the full endpoint, e.g. `https://api.cohere.com/v2/rerank`.
+    /// The bearer token comes from `RERANK_API_KEY` when set.
+    pub fn new(url: &str, model: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+            model: model.to_string(),
+            api_key: std::env::var("RERANK_API_KEY").ok().filter(|k| !k.is_empty()),
+        }
+    }
+}
+
+#[async_trait]
+impl Reranker for ApiReranker {
+    fn name(&self) -> &str {
+        &self.model
+    }
+
+    async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        let mut req = self.client.post(&self.url).json(&RerankRequest {
+            model: &self.model,
+            query,
+            documents,
+        });
+        if let Some(ref key) = self.api_key {
+            req = req.bearer_auth(key);
+        }
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| SafeAgentError::Llm(format!("rerank request failed: {e}")))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err_text = resp.text().await.unwrap_or_default();
+            return Err(SafeAgentError::Llm(format!("rerank API returned {status}: {err_text}")));
+        }
+        let body: RerankResponse = resp
+            .json()
+            .await
+            .map_err(|e| SafeAgentError::Llm(format!("failed to parse rerank response: {e}")))?;
+        scores_in_input_order(documents.len(), body.results)
+    }
+}
+
+/// The API returns results sorted by relevance; put the scores back in
+/// document order.  Documents the service left out score lowest.
+fn scores_in_input_order(len: usize, results: Vec<RerankResult>) -> Result<Vec<f32>> {
+    let mut scores = vec![f32::NEG_INFINITY; len];
+    for r in results {
+        let slot = scores
+            .get_mut(r.index)
+            .ok_or_else(|| SafeAgentError::Llm(format!("rerank result index {} out of range", r.index)))?;
+        *slot = r.relevance_score;
+    }
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_results_are_mapped_back_to_document_order() {
+        let body: RerankResponse = serde_json::from_str(
+            r#"{"results": [{"index": 2, "relevance_score": 0.9}, {"index": 0, "relevance_score": 0.4}]}"#,
+        )
+        .unwrap();
+        let scores = scores_in_input_order(3, body.results).unwrap();
+        assert_eq!(scores, vec![0.4, f32::NEG_INFINITY, 0.9]);
+
+        let out_of_range = vec![RerankResult { index: 5, relevance_score: 1.0 }];
+        assert!(scores_in_input_order(3, out_of_range).is_err());
+    }
+}
@@ -1,11 +1,22 @@
 use std::sync::Arc;
 
-use rusqlite::Connection;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
 use crate::error::Result;
 
+/// Days for an unreinforced inference to lose half its confidence.
+const CONFIDENCE_HALF_LIFE_DAYS: f64 = 90.0;
+
+/// Entries whose decayed confidence falls below this are left out of the
+/// profile.
+const MIN_PROFILE_CONFIDENCE: f64 = 0.2;
+
+/// Timestamp format shared with SQLite's `datetime('now')`.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileEntry {
     pub id: i64,
@@ -16,6 +27,25 @@ pub struct ProfileEntry {
     pub source: String,
     pub created_at: String,
     pub updated_at: String,
+    /// When `confidence` was last set by an observation; decay runs from
+    /// here.  `None` for entries written with [`UserModel::set`], which
+    /// don't decay.
+    pub reinforced_at: Option<String>,
+}
+
+impl ProfileEntry {
+    /// Confidence after decaying from the last reinforcement until `now`.
+    /// Explicitly set entries keep their confidence.
+    pub fn decayed_confidence(&self, now: DateTime<Utc>) -> f64 {
+        let Some(since) = self.reinforced_at.as_deref() else {
+            return self.confidence;
+        };
+        let Ok(since) = NaiveDateTime::parse_from_str(since, TIMESTAMP_FORMAT) else {
+            return self.confidence;
+        };
+        let age_days = (now - since.and_utc()).num_seconds().max(0) as f64 / 86_400.0;
+        self.confidence * 0.5f64.powf(age_days / CONFIDENCE_HALF_LIFE_DAYS)
+    }
 }
 
 pub struct UserModel {
@@ -27,7 +57,7 @@ impl UserModel {
         Self { db }
     }
 
-    /// Set or update a user profile entry.  Uses UPSERT to merge.
+    /// Set or update a user profile entry, replacing any existing value.
     pub async fn set(
         &self,
         user_id: Option<&str>,
@@ -37,17 +67,79 @@ impl UserModel {
         source: &str,
     ) -> Result<()> {
         let db = self.db.lock().await;
-        db.execute(
-            "INSERT INTO user_profiles (user_id, key, value, confidence, source)
-             VALUES (?1, ?2, ?3, ?4, ?5)
-             ON CONFLICT(user_id, key) DO UPDATE SET
-                 value = excluded.value,
-                 confidence = excluded.confidence,
-                 source = excluded.source,
-                 updated_at = datetime('now')",
-            rusqlite::params![user_id, key, value, confidence, source],
-        )?;
-        Ok(())
+        write_entry(&db, user_id, key, value, confidence, source, None)
+    }
+
+    /// Record an inferred attribute, reconciling it with what is already
+    /// known.  The same value reinforces the existing entry; a different
+    /// value replaces it if the new observation is at least as confident
+    /// as the (decayed) old one, and otherwise weakens it.
+    pub async fn observe(
+        &self,
+        user_id: Option<&str>,
+        key: &str,
+        value: &str,
+        confidence: f64,
+        source: &str,
+    ) -> Result<()> {
+        self.observe_at(user_id, key, value, confidence, source, Utc::now()).await
+    }
+
+    async fn observe_at(
+        &self,
+        user_id: Option<&str>,
+        key: &str,
+        value: &str,
+        confidence: f64,
+        source: &str,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let confidence = confidence.clamp(0.0, 1.0);
+        let db = self.db.lock().await;
+        let existing = db
+            .query_row(
+                &format!("{SELECT_PROFILE} WHERE user_id IS ?1 AND key = ?2"),
+                rusqlite::params![user_id, key],
+                map_profile,
+            )
+            .optional()?;
+
+        let (value, confidence) = match existing {
+            None => (value.to_string(), confidence),
+            Some(old) => {
+                let current = old.decayed_confidence(now);
+                if old.value.trim().eq_ignore_ascii_case(value.trim()) {
+                    // Independent agreeing observations: 1 - (1-a)(1-b).
+                    (old.value, 1.0 - (1.0 - current) * (1.0 - confidence))
+                } else if confidence >= current {
+                    (value.to_string(), confidence)
+                } else {
+                    (old.value, current * (1.0 - confidence))
+                }
+            }
+        };
+        let now = now.format(TIMESTAMP_FORMAT).to_string();
+        write_entry(&db, user_id, key, &value, confidence, source, Some(&now))
+    }
+
+    /// The user's current best-known attributes, with confidence decayed to
+    /// now.  Entries that have faded below a minimum confidence are omitted.
+    pub async fn get_profile(&self, user_id: Option<&str>) -> Result<Vec<ProfileEntry>> {
+        self.profile_at(user_id, Utc::now()).await
+    }
+
+    async fn profile_at(&self, user_id: Option<&str>, now: DateTime<Utc>) -> Result<Vec<ProfileEntry>> {
+        let entries = self
+            .get_all(user_id)
+            .await?
+            .into_iter()
+            .map(|mut e| {
+                e.confidence = e.decayed_confidence(now);
+                e
+            })
+            .filter(|e| e.confidence >= MIN_PROFILE_CONFIDENCE)
+            .collect();
+        Ok(entries)
     }
 
     /// Get a specific profile entry by key.
@@ -55,14 +147,14 @@ impl UserModel {
         let db = self.db.lock().await;
         let result = if let Some(uid) = user_id {
             db.query_row(
-                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at
+                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at, reinforced_at
                  FROM user_profiles WHERE user_id = ?1 AND key = ?2",
                 rusqlite::params![uid, key],
                 map_profile,
             )
         } else {
             db.query_row(
-                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at
+                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at, reinforced_at
                  FROM user_profiles WHERE user_id IS NULL AND key = ?1",
                 [key],
                 map_profile,
@@ -82,7 +174,7 @@ impl UserModel {
 
         let entries = if let Some(uid) = user_id {
             let mut stmt = db.prepare(
-                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at
+                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at, reinforced_at
                  FROM user_profiles WHERE user_id = ?1 ORDER BY key",
             )?;
             stmt.query_map([uid], map_profile)?
@@ -90,7 +182,7 @@ impl UserModel {
                 .collect()
         } else {
             let mut stmt = db.prepare(
-                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at
+                "SELECT id, user_id, key, value, confidence, source, created_at, updated_at, reinforced_at
                  FROM user_profiles WHERE user_id IS NULL ORDER BY key",
             )?;
             stmt.query_map([], map_profile)?
@@ -120,7 +212,7 @@ impl UserModel {
 
     /// Format the user profile as a readable string for inclusion in LLM context.
     pub async fn as_context_string(&self, user_id: Option<&str>) -> Result<String> {
-        let entries = self.get_all(user_id).await?;
+        let entries = self.get_profile(user_id).await?;
         if entries.is_empty() {
             return Ok(String::new());
         }
@@ -133,6 +225,36 @@ impl UserModel {
     }
 }
 
+const SELECT_PROFILE: &str = "SELECT id, user_id, key, value, confidence, source, created_at, updated_at, reinforced_at
+     FROM user_profiles";
+
+/// Update the `(user_id, key)` entry or insert it.  Done by hand because a
+/// NULL `user_id` never conflicts on the table's UNIQUE constraint.
+fn write_entry(
+    db: &Connection,
+    user_id: Option<&str>,
+    key: &str,
+    value: &str,
+    confidence: f64,
+    source: &str,
+    reinforced_at: Option<&str>,
+) -> Result<()> {
+    let updated = db.execute(
+        "UPDATE user_profiles SET value = ?3, confidence = ?4, source = ?5,
+             reinforced_at = ?6, updated_at = datetime('now')
+         WHERE user_id IS ?1 AND key = ?2",
+        rusqlite::params![user_id, key, value, confidence, source, reinforced_at],
+    )?;
+    if updated == 0 {
+        db.execute(
+            "INSERT INTO user_profiles (user_id, key, value, confidence, source, reinforced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![user_id, key, value, confidence, source, reinforced_at],
+        )?;
+    }
+    Ok(())
+}
+
 fn map_profile(row: &rusqlite::Row) -> rusqlite::Result<ProfileEntry> {
     Ok(ProfileEntry {
         id: row.get(0)?,
@@ -143,6 +265,7 @@ fn map_profile(row: &rusqlite::Row) -> rusqlite::Result<ProfileEntry> {
         source: row.get(5)?,
         created_at: row.get(6)?,
         updated_at: row.get(7)?,
+        reinforced_at: row.get(8)?,
     })
 }
 
@@ -256,4 +379,68 @@ mod tests {
         let ctx = um.as_context_string(None).await.unwrap();
         assert!(ctx.is_empty());
     }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn confidence_decays_without_reinforcement() {
+        let db = test_db();
+        let um = UserModel::new(db);
+        let t0 = at("2025-01-01T00:00:00Z");
+
+        um.observe_at(Some("u1"), "editor", "vim", 0.8, "test", t0).await.unwrap();
+
+        let fresh = um.profile_at(Some("u1"), t0).await.unwrap();
+        assert!((fresh[0].confidence - 0.8).abs() < 0.001);
+
+        let later = t0 + chrono::Duration::days(CONFIDENCE_HALF_LIFE_DAYS as i64);
+        let decayed = um.profile_at(Some("u1"), later).await.unwrap();
+        assert!((decayed[0].confidence - 0.4).abs() < 0.001);
+
+        // Long enough and it drops out of the profile entirely.
+        let much_later = t0 + chrono::Duration::days(365);
+        assert!(um.profile_at(Some("u1"), much_later).await.unwrap().is_empty());
+
+        // Reinforcing with the same value restores and raises confidence.
+        um.observe_at(Some("u1"), "editor", "Vim", 0.5, "test", later).await.unwrap();
+        let reinforced = um.profile_at(Some("u1"), later).await.unwrap();
+        assert_eq!(reinforced[0].value, "vim");
+        assert!((reinforced[0].confidence - 0.7).abs() < 0.001);
+    }
+
+    #[tokio::test]
+    async fn explicitly_set_entries_do_not_decay() {
+        let db = test_db();
+        let um = UserModel::new(db);
+        um.set(Some("u1"), "name", "Alice", 0.9, "user").await.unwrap();
+
+        let much_later = Utc::now() + chrono::Duration::days(3650);
+        let profile = um.profile_at(Some("u1"), much_later).await.unwrap();
+        assert_eq!(profile.len(), 1);
+        assert!((profile[0].confidence - 0.9).abs() < 0.001);
+    }
+
+    #[tokio::test]
+    async fn contradiction_replaces_or_weakens_prior_value() {
+        let db = test_db();
+        let um = UserModel::new(db);
+        let t0 = at("2025-01-01T00:00:00Z");
+
+        um.observe_at(None, "city", "Berlin", 0.6, "test", t0).await.unwrap();
+
+        // A weaker contradiction keeps the value but lowers confidence.
+        um.observe_at(None, "city", "Paris", 0.25, "test", t0).await.unwrap();
+        let entry = um.get(None, "city").await.unwrap().unwrap();
+        assert_eq!(entry.value, "Berlin");
+        assert!((entry.confidence - 0.45).abs() < 0.001);
+
+        // A contradiction at least as confident replaces it.
+        um.observe_at(None, "city", "Paris", 0.7, "test", t0).await.unwrap();
+        let profile = um.profile_at(None, t0).await.unwrap();
+        assert_eq!(profile.len(), 1);
+        assert_eq!(profile[0].value, "Paris");
+        assert!((profile[0].confidence - 0.7).abs() < 0.001);
+    }
 }
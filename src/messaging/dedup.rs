@@ -0,0 +1,97 @@
+//! Idempotency guard for incoming platform updates.
+//!
+//! Platforms like Telegram can re-deliver an update after a dispatcher
+//! reconnect.  Each update id is claimed in the database *before* it is
+//! handled, so a redelivery — even after a crash mid-processing — is
+//! skipped instead of answered twice.
+
+use std::sync::Arc;
+
+use rusqlite::Connection;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// How long processed ids are remembered.  Telegram only keeps unconfirmed
+/// updates for 24 hours, so anything older can't be redelivered.
+const RETENTION: &str = "-2 days";
+
+#[derive(Clone)]
+pub struct ProcessedUpdates {
+    db: Arc<Mutex<Connection>>,
+}
+
+impl ProcessedUpdates {
+    pub fn new(db: Arc<Mutex<Connection>>) -> Self {
+        Self { db }
+    }
+
+    /// Claim `update_id` for processing.  Returns `false` if it was already
+    /// claimed and the update should be skipped.
+    ///
+    /// Database errors fail open: a possible duplicate reply is better than
+    /// silently dropping a message.
+    pub async fn claim(&self, platform: &str, update_id: i64) -> bool {
+        let db = self.db.lock().await;
+        let inserted = db.execute(
+            "INSERT OR IGNORE INTO processed_updates (platform, update_id) VALUES (?1, ?2)",
+            rusqlite::params![platform, update_id],
+        );
+        let _ = db.execute(
+            "DELETE FROM processed_updates WHERE processed_at < datetime('now', ?1)",
+            [RETENTION],
+        );
+        match inserted {
+            Ok(n) => n == 1,
+            Err(e) => {
+                warn!(platform, update_id, err = %e, "failed to record processed update");
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn duplicate_update_is_processed_once() {
+        let db = crate::db::test_db();
+        let dedup = ProcessedUpdates::new(db.clone());
+        let mut agent_calls = 0;
+
+        for update_id in [100, 100, 101, 100] {
+            if dedup.claim("telegram", update_id).await {
+                agent_calls += 1;
+            }
+        }
+        assert_eq!(agent_calls, 2);
+
+        // Survives a restart, and ids are scoped per platform.
+        let restarted = ProcessedUpdates::new(db);
+        assert!(!restarted.claim("telegram", 101).await);
+        assert!(restarted.claim("discord", 101).await);
+    }
+
+    #[tokio::test]
+    async fn old_entries_are_pruned() {
+        let db = crate::db::test_db();
+        db.lock()
+            .await
+            .execute(
+                "INSERT INTO processed_updates (platform, update_id, processed_at)
+                 VALUES ('telegram', 1, datetime('now', '-3 days'))",
+                [],
+            )
+            .unwrap();
+        let dedup = ProcessedUpdates::new(db.clone());
+        assert!(dedup.claim("telegram", 2).await);
+
+        let remaining: i64 = db
+            .lock()
+            .await
+            .query_row("SELECT COUNT(*) FROM processed_updates", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
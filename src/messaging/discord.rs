@@ -4,12 +4,19 @@ use async_trait::async_trait;
 use serenity::all::*;
 use tracing::{error, info};
 
+use crate::agent::attachments::Origin;
 use crate::agent::Agent;
 use crate::config::DiscordConfig;
 use crate::error::Result;
 
 use super::{split_message, MessagingBackend};
 
+/// Discord's per-message content limit.
+const MAX_MESSAGE_LEN: usize = 2000;
+
+/// Name of the slash command that forwards a prompt to the agent.
+const ASK_COMMAND: &str = "ask";
+
 // ---------------------------------------------------------------------------
 // MessagingBackend implementation
 // ---------------------------------------------------------------------------
@@ -31,7 +38,7 @@ impl MessagingBackend for DiscordBackend {
     }
 
     fn max_message_length(&self) -> usize {
-        2000
+        MAX_MESSAGE_LEN
     }
 
     async fn send_message(&self, channel: &str, text: &str) -> Result<()> {
@@ -81,22 +88,7 @@ impl EventHandler for Handler {
             return;
         }
 
-        // Guild authorization: if allowed_guild_ids is non-empty, only
-        // process messages from those guilds.
-        if !self.config.allowed_guild_ids.is_empty() {
-            match msg.guild_id {
-                Some(gid) if self.config.allowed_guild_ids.contains(&gid.get()) => {}
-                Some(_) => return,
-                // DMs have no guild_id — allow them through
-                None => {}
-            }
-        }
-
-        // Channel authorization: if allowed_channel_ids is non-empty, only
-        // process messages from those channels.
-        if !self.config.allowed_channel_ids.is_empty()
-            && !self.config.allowed_channel_ids.contains(&msg.channel_id.get())
-        {
+        if !is_allowed(&self.config, msg.guild_id.map(|g| g.get()), msg.channel_id.get()) {
             return;
         }
 
@@ -151,12 +143,16 @@ impl EventHandler for Handler {
                 }
             });
 
-            let result = agent.handle_message_as(&user_text, user_ctx.as_ref()).await;
+            let channel = channel_id.get().to_string();
+            let origin = Origin { platform: "discord", channel: &channel };
+            let result = agent
+                .handle_message_with_attachments(&user_text, &[], Some(origin), user_ctx.as_ref())
+                .await;
             typing_handle.abort();
 
             match result {
                 Ok(reply) => {
-                    for chunk in split_message(&reply, 2000) {
+                    for chunk in split_message(&reply, MAX_MESSAGE_LEN) {
                         if let Err(e) = channel_id.say(&http, chunk).await {
                             error!("failed to send discord reply: {e}");
                         }
@@ -172,11 +168,156 @@ impl EventHandler for Handler {
         });
     }
 
-    async fn ready(&self, _ctx: Context, ready: Ready) {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Command(command) = interaction else {
+            return;
+        };
+        if command.data.name != ASK_COMMAND {
+            return;
+        }
+
+        if !is_allowed(&self.config, command.guild_id.map(|g| g.get()), command.channel_id.get()) {
+            respond_ephemeral(&ctx, &command, "/ask is not enabled in this channel.").await;
+            return;
+        }
+        let Some(prompt) = ask_prompt(&command.data) else {
+            respond_ephemeral(&ctx, &command, "Usage: /ask prompt:<question>").await;
+            return;
+        };
+
+        info!(
+            channel_id = command.channel_id.get(),
+            author = %command.user.name,
+            "discord /ask received"
+        );
+
+        // Interactions must be acknowledged within three seconds; defer so
+        // the agent can take as long as it needs.
+        if let Err(e) = command.defer(&ctx.http).await {
+            error!("failed to defer discord interaction: {e}");
+            return;
+        }
+
+        let user_ctx = self
+            .agent
+            .user_manager
+            .get_by_discord_id(&command.user.id.get().to_string())
+            .await
+            .map(|u| crate::users::UserContext::from_user(&u, "discord"));
+        let agent = self.agent.clone();
+        let http = ctx.http.clone();
+
+        tokio::spawn(async move {
+            let channel = command.channel_id.get().to_string();
+            let origin = Origin { platform: "discord", channel: &channel };
+            let reply = match agent
+                .handle_message_with_attachments(&prompt, &[], Some(origin), user_ctx.as_ref())
+                .await
+            {
+                Ok(reply) if reply.trim().is_empty() => "(no response)".to_string(),
+                Ok(reply) => reply,
+                Err(e) => {
+                    error!("agent generation failed: {e}");
+                    format!("Error: {e}")
+                }
+            };
+
+            // The first chunk replaces the "thinking..." placeholder; the
+            // rest are sent as follow-ups.
+            let mut chunks = split_message(&reply, MAX_MESSAGE_LEN).into_iter();
+            if let Some(first) = chunks.next() {
+                let edit = EditInteractionResponse::new().content(first);
+                if let Err(e) = command.edit_response(&http, edit).await {
+                    error!("failed to send discord /ask reply: {e}");
+                    return;
+                }
+            }
+            for chunk in chunks {
+                let followup = CreateInteractionResponseFollowup::new().content(chunk);
+                if let Err(e) = command.create_followup(&http, followup).await {
+                    error!("failed to send discord /ask follow-up: {e}");
+                    return;
+                }
+            }
+        });
+    }
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
         info!(user = %ready.user.name, "discord bot connected");
+        register_ask_command(&ctx, &self.config).await;
     }
 }
 
+// ---------------------------------------------------------------------------
+// Slash commands
+// ---------------------------------------------------------------------------
+
+fn ask_command() -> CreateCommand {
+    CreateCommand::new(ASK_COMMAND)
+        .description("Ask the agent a question")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "prompt", "What to ask")
+                .required(true),
+        )
+}
+
+/// Register `/ask`.  With a guild allowlist the command is registered per
+/// guild (which takes effect immediately); otherwise it is registered
+/// globally.
+async fn register_ask_command(ctx: &Context, config: &DiscordConfig) {
+    if config.allowed_guild_ids.is_empty() {
+        match Command::create_global_command(&ctx.http, ask_command()).await {
+            Ok(_) => info!("registered global /ask command"),
+            Err(e) => error!("failed to register /ask command: {e}"),
+        }
+        return;
+    }
+    for &guild_id in &config.allowed_guild_ids {
+        match GuildId::new(guild_id).create_command(&ctx.http, ask_command()).await {
+            Ok(_) => info!(guild_id, "registered /ask command"),
+            Err(e) => error!(guild_id, "failed to register /ask command: {e}"),
+        }
+    }
+}
+
+/// Extract the prompt from an `/ask` invocation.
+fn ask_prompt(data: &CommandData) -> Option<String> {
+    data.options
+        .iter()
+        .find(|o| o.name == "prompt")
+        .and_then(|o| o.value.as_str())
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+}
+
+async fn respond_ephemeral(ctx: &Context, command: &CommandInteraction, text: &str) {
+    let response = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(text)
+            .ephemeral(true),
+    );
+    if let Err(e) = command.create_response(&ctx.http, response).await {
+        error!("failed to respond to discord interaction: {e}");
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Authorization
+// ---------------------------------------------------------------------------
+
+/// Whether a message or command from this guild/channel may be handled.
+///
+/// Empty allowlists allow everything.  DMs have no guild and pass the guild
+/// check, but are still subject to the channel allowlist.
+fn is_allowed(config: &DiscordConfig, guild_id: Option<u64>, channel_id: u64) -> bool {
+    let guild_ok = config.allowed_guild_ids.is_empty()
+        || guild_id.is_none_or(|g| config.allowed_guild_ids.contains(&g));
+    let channel_ok =
+        config.allowed_channel_ids.is_empty() || config.allowed_channel_ids.contains(&channel_id);
+    guild_ok && channel_ok
+}
+
 // ---------------------------------------------------------------------------
 // Start function
 // ---------------------------------------------------------------------------
@@ -185,12 +326,9 @@ impl EventHandler for Handler {
 /// dropped or sent to, triggers a graceful shutdown of the gateway.
 pub async fn start(
     config: DiscordConfig,
+    token: String,
     agent: Arc<Agent>,
 ) -> Result<tokio::sync::oneshot::Sender<()>> {
-    let token = std::env::var("DISCORD_BOT_TOKEN").map_err(|_| {
-        crate::error::SafeAgentError::Config("DISCORD_BOT_TOKEN not set".into())
-    })?;
-
     let intents = GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
@@ -237,3 +375,69 @@ fn strip_discord_mention(text: &str, bot_id: &str) -> String {
     let mention_nick = format!("<@!{bot_id}>");
     text.replace(&mention, "").replace(&mention_nick, "").trim().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_data(options: serde_json::Value) -> CommandData {
+        serde_json::from_value(serde_json::json!({
+            "id": "1100000000000000000",
+            "name": "ask",
+            "type": 1,
+            "options": options,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn ask_prompt_parses_string_option() {
+        let data = command_data(serde_json::json!([
+            {"name": "prompt", "type": 3, "value": "  what's on my calendar?  "}
+        ]));
+        assert_eq!(data.name, ASK_COMMAND);
+        assert_eq!(ask_prompt(&data).as_deref(), Some("what's on my calendar?"));
+    }
+
+    #[test]
+    fn ask_prompt_rejects_missing_or_blank() {
+        assert_eq!(ask_prompt(&command_data(serde_json::json!([]))), None);
+        let blank = command_data(serde_json::json!([{"name": "prompt", "type": 3, "value": "   "}]));
+        assert_eq!(ask_prompt(&blank), None);
+        let other = command_data(serde_json::json!([{"name": "topic", "type": 3, "value": "hi"}]));
+        assert_eq!(ask_prompt(&other), None);
+    }
+
+    #[test]
+    fn allowlists_gate_guilds_and_channels() {
+        let open = DiscordConfig::default();
+        assert!(is_allowed(&open, Some(1), 2));
+
+        let config = DiscordConfig {
+            enabled: true,
+            allowed_guild_ids: vec![10],
+            allowed_channel_ids: vec![20, 21],
+        };
+        assert!(is_allowed(&config, Some(10), 20));
+        assert!(!is_allowed(&config, Some(11), 20));
+        assert!(!is_allowed(&config, Some(10), 22));
+        // DMs skip the guild check but not the channel check.
+        assert!(is_allowed(&config, None, 21));
+        assert!(!is_allowed(&config, None, 99));
+    }
+
+    #[test]
+    fn replies_split_at_discord_limit() {
+        let reply = "word ".repeat(1000);
+        let chunks = split_message(&reply, MAX_MESSAGE_LEN);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.len() <= MAX_MESSAGE_LEN));
+        assert_eq!(chunks.concat(), reply);
+
+        // Multi-byte text must split on character boundaries.
+        let emoji = "🦀".repeat(600);
+        let chunks = split_message(&emoji, MAX_MESSAGE_LEN);
+        assert!(chunks.iter().all(|c| c.chars().count() <= MAX_MESSAGE_LEN));
+        assert_eq!(chunks.concat(), emoji);
+    }
+}
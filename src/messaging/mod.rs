@@ -1,5 +1,7 @@
 pub mod bridge;
 pub mod commands;
+pub mod dedup;
+pub mod outbox;
 pub mod discord;
 pub mod signal;
 pub mod telegram;
@@ -9,9 +11,15 @@ pub mod whatsapp;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use tracing::{error, info};
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
 
-use crate::error::Result;
+use crate::error::{Result, SafeAgentError};
+
+use self::outbox::Outbox;
+
+/// How often the outbox drainer looks for messages due for a retry.
+const OUTBOX_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
 
 // ---------------------------------------------------------------------------
 // Messaging backend trait
@@ -33,29 +41,87 @@ pub trait MessagingBackend: Send + Sync {
     /// Send a typing/composing indicator. Backends that don't support
     /// typing indicators should return Ok(()) silently.
     async fn send_typing(&self, channel: &str) -> Result<()>;
+
+    /// Send a proactive notification, i.e. one the agent starts rather than
+    /// a reply.  Defaults to [`Self::send_message`]; backends with rules
+    /// for unsolicited messages override it.
+    async fn send_notification(&self, channel: &str, text: &str) -> Result<()> {
+        self.send_message(channel, text).await
+    }
+
+    /// Called for every incoming message on `channel`, before the agent
+    /// handles it.  Backends that track conversation state override this.
+    fn note_incoming(&self, _channel: &str) {}
+
+    /// Send a file (image, document, ...) to the given channel/chat.
+    /// Backends that can't deliver attachments keep this default, which
+    /// returns an error.
+    async fn send_file(&self, _channel: &str, _data: &[u8], filename: &str, _mime: &str) -> Result<()> {
+        Err(crate::error::SafeAgentError::Messaging(format!(
+            "{} does not support file attachments (tried to send '{filename}')",
+            self.platform_name()
+        )))
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Messaging manager
 // ---------------------------------------------------------------------------
 
+/// Whether a proactive message may wait out quiet hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    /// Held during quiet hours and delivered when they end.
+    Normal,
+    /// Sent straight away: replies the user is waiting for, errors, status
+    /// notices.
+    Urgent,
+}
+
 /// Holds all active messaging backends and provides convenience methods
 /// for sending to one, some, or all of them.
 pub struct MessagingManager {
     backends: Vec<Arc<dyn MessagingBackend>>,
-    /// Primary channel per backend: platform_name -> channel_id.
-    /// Used by the message tool and notifications.
-    primary_channels: std::collections::HashMap<String, String>,
+    /// Notification channels per backend: platform_name -> channel ids.
+    /// The first is the primary channel, used by the message tool and as
+    /// the default; notifications fan out to all of them.
+    channels: std::collections::HashMap<String, Vec<String>>,
+    /// Persistent retry queue.  When unset, sends are best-effort.
+    outbox: Option<Outbox>,
+    /// Window in which [`Urgency::Normal`] messages are deferred, and the
+    /// timezone it is in.  Deferral needs the outbox.
+    /// Swapped by config reloads, hence the lock.
+    quiet_hours: std::sync::RwLock<Option<(crate::config::QuietHours, chrono_tz::Tz)>>,
 }
 
 impl MessagingManager {
     pub fn new() -> Self {
         Self {
             backends: Vec::new(),
-            primary_channels: std::collections::HashMap::new(),
+            channels: std::collections::HashMap::new(),
+            outbox: None,
+            quiet_hours: std::sync::RwLock::new(None),
         }
     }
 
+    /// Route `send_all` and [`Self::enqueue`] through a persistent outbox
+    /// so failed deliveries are retried.
+    pub fn set_outbox(&mut self, outbox: Outbox) {
+        self.outbox = Some(outbox);
+    }
+
+    /// Hold non-urgent [`Self::send_all`] messages during `quiet` (local
+    /// time in `tz`) and deliver them through the outbox when it ends.
+    /// `None` turns quiet hours off.
+    pub fn set_quiet_hours(&self, quiet: Option<crate::config::QuietHours>, tz: chrono_tz::Tz) {
+        *self.quiet_hours.write().unwrap() = quiet.map(|q| (q, tz));
+    }
+
+    /// The current quiet-hours window, if any.
+    pub fn quiet_hours(&self) -> Option<crate::config::QuietHours> {
+        self.quiet_hours.read().unwrap().map(|(quiet, _)| quiet)
+    }
+
     /// Register a backend with its primary channel (e.g. telegram chat id,
     /// whatsapp phone number).
     pub fn register(&mut self, backend: Arc<dyn MessagingBackend>, primary_channel: String) {
@@ -64,11 +130,27 @@ impl MessagingManager {
             channel = %primary_channel,
             "registered messaging backend"
         );
-        self.primary_channels
-            .insert(backend.platform_name().to_string(), primary_channel);
+        self.channels
+            .insert(backend.platform_name().to_string(), vec![primary_channel]);
         self.backends.push(backend);
     }
 
+    /// Add another channel for `platform` (e.g. a second Telegram group)
+    /// that notifications fan out to.  The first channel registered for a
+    /// platform stays its primary.
+    pub fn register_channel(&mut self, platform: &str, channel: &str) {
+        let channels = self.channels.entry(platform.to_string()).or_default();
+        if !channels.iter().any(|c| c == channel) {
+            info!(platform, channel, "registered messaging channel");
+            channels.push(channel.to_string());
+        }
+    }
+
+    /// Every channel registered for `platform`, primary first.
+    pub fn channels(&self, platform: &str) -> &[String] {
+        self.channels.get(platform).map(Vec::as_slice).unwrap_or_default()
+    }
+
     /// Get a specific backend by platform name.
     pub fn get(&self, platform: &str) -> Option<&Arc<dyn MessagingBackend>> {
         self.backends.iter().find(|b| b.platform_name() == platform)
@@ -76,33 +158,183 @@ impl MessagingManager {
 
     /// Get the primary channel for a given platform.
     pub fn primary_channel(&self, platform: &str) -> Option<&str> {
-        self.primary_channels.get(platform).map(|s| s.as_str())
+        self.channels(platform).first().map(|s| s.as_str())
     }
 
     /// Get the first backend's primary channel.
     pub fn default_channel(&self) -> Option<(&Arc<dyn MessagingBackend>, &str)> {
         let backend = self.backends.first()?;
-        let channel = self.primary_channels.get(backend.platform_name())?;
-        Some((backend, channel.as_str()))
+        let channel = self.primary_channel(backend.platform_name())?;
+        Some((backend, channel))
     }
 
-    /// Send a message to the primary channel of every registered backend.
-    pub async fn send_all(&self, text: &str) {
+    /// Send a message to every channel of every registered backend.
+    /// With an outbox configured, failed sends are queued for retry, and
+    /// [`Urgency::Normal`] messages sent during quiet hours are queued until
+    /// the window ends.
+    pub async fn send_all(&self, text: &str, urgency: Urgency) {
+        self.send_all_at(text, urgency, chrono::Utc::now()).await
+    }
+
+    async fn send_all_at(&self, text: &str, urgency: Urgency, now: chrono::DateTime<chrono::Utc>) {
+        let quiet_until = match urgency {
+            Urgency::Normal => self.quiet_until(now),
+            Urgency::Urgent => None,
+        };
+        if let Some(until) = quiet_until {
+            self.defer_all(text, now.timestamp(), until).await;
+            return;
+        }
         for backend in &self.backends {
             let platform = backend.platform_name();
-            if let Some(channel) = self.primary_channels.get(platform) {
-                if let Err(e) = backend.send_message(channel, text).await {
-                    error!(platform, err = %e, "failed to send to messaging backend");
+            for channel in self.channels(platform) {
+                let sent = if self.outbox.is_some() {
+                    self.enqueue(platform, channel, text).await
+                } else {
+                    backend.send_notification(channel, text).await
+                };
+                if let Err(e) = sent {
+                    error!(platform, channel = %channel, err = %e, "failed to send to messaging backend");
                 }
             }
         }
     }
 
-    /// Send a typing indicator to the primary channel of every registered backend.
+    /// Tell every channel the agent is up, if `[status_notifications]`
+    /// asks for it.
+    pub async fn announce_online(&self, config: &crate::config::Config) {
+        let notify = &config.status_notifications;
+        if notify.on_startup {
+            let text = notify.online_message.replace("{agent_name}", &config.agent_name);
+            self.send_all(&text, Urgency::Urgent).await;
+        }
+    }
+
+    /// Tell every channel the agent is shutting down, if
+    /// `[status_notifications]` asks for it.  Call before the outbox and
+    /// backends stop.
+    pub async fn announce_offline(&self, config: &crate::config::Config) {
+        let notify = &config.status_notifications;
+        if notify.on_shutdown {
+            let text = notify.offline_message.replace("{agent_name}", &config.agent_name);
+            self.send_all(&text, Urgency::Urgent).await;
+        }
+    }
+
+    /// When the quiet-hours window containing `now` ends, if it does and
+    /// there is an outbox to hold messages until then.
+    fn quiet_until(&self, now: chrono::DateTime<chrono::Utc>) -> Option<chrono::DateTime<chrono::Utc>> {
+        let (quiet, tz) = (*self.quiet_hours.read().unwrap())?;
+        self.outbox.as_ref()?;
+        let local = now.with_timezone(&tz);
+        quiet.contains(local.time()).then(|| quiet.end_after(&local))
+    }
+
+    async fn defer_all(&self, text: &str, now: i64, until: chrono::DateTime<chrono::Utc>) {
+        let Some(outbox) = &self.outbox else {
+            return;
+        };
+        info!(until = %until, "quiet hours, deferring message");
+        for backend in &self.backends {
+            let platform = backend.platform_name();
+            for channel in self.channels(platform) {
+                if let Err(e) = outbox.push_deferred(platform, channel, text, now, until.timestamp()).await {
+                    error!(platform, err = %e, "failed to queue message for after quiet hours");
+                }
+            }
+        }
+    }
+
+    /// Queue a notification for reliable delivery and try to send it right
+    /// away.  Only this message is attempted; if that fails it stays queued
+    /// and the drainer retries it with backoff.  Without an outbox this is
+    /// a plain best-effort send.  Delivery goes through
+    /// [`MessagingBackend::send_notification`].
+    pub async fn enqueue(&self, platform: &str, channel: &str, text: &str) -> Result<()> {
+        let Some(outbox) = &self.outbox else {
+            let backend = self
+                .get(platform)
+                .ok_or_else(|| SafeAgentError::Messaging(format!("unknown platform '{platform}'")))?;
+            return backend.send_notification(channel, text).await;
+        };
+        let now = chrono::Utc::now().timestamp();
+        let id = outbox.push(platform, channel, text, now).await?;
+        let _guard = outbox.lock().await;
+        // A concurrent drain may have sent it already.
+        if let Some(msg) = outbox.get_due(id, now).await? {
+            self.attempt(outbox, &msg, now).await?;
+        }
+        Ok(())
+    }
+
+    /// Attempt every queued message due at `now` (unix seconds).  Returns
+    /// the number delivered.
+    pub async fn drain_outbox_at(&self, now: i64) -> Result<usize> {
+        let Some(outbox) = &self.outbox else {
+            return Ok(0);
+        };
+        let _guard = outbox.lock().await;
+        let mut delivered = 0;
+        for msg in outbox.due(now).await? {
+            if self.attempt(outbox, &msg, now).await? {
+                delivered += 1;
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Send one queued message and record the outcome.  The caller holds
+    /// the drain lock.  Returns whether it was delivered.
+    async fn attempt(&self, outbox: &Outbox, msg: &outbox::PendingMessage, now: i64) -> Result<bool> {
+        let sent = match self.get(&msg.platform) {
+            Some(backend) => backend.send_notification(&msg.channel, &msg.text).await,
+            None => Err(SafeAgentError::Messaging(format!(
+                "platform '{}' is not registered",
+                msg.platform
+            ))),
+        };
+        match sent {
+            Ok(()) => {
+                outbox.delivered(msg.id).await?;
+                Ok(true)
+            }
+            Err(e) => {
+                warn!(platform = %msg.platform, attempts = msg.attempts + 1, err = %e, "queued message delivery failed");
+                outbox.failed(msg, &e.to_string(), now).await?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Retry queued messages until shutdown, then make one last pass so
+    /// replies from the final in-flight work go out.  Messages still
+    /// backing off stay queued for the next start.
+    pub async fn run_outbox(&self, mut shutdown: broadcast::Receiver<()>) {
+        if self.outbox.is_none() {
+            return;
+        }
+        let mut interval = tokio::time::interval(OUTBOX_POLL_INTERVAL);
+        loop {
+            let stopping = tokio::select! {
+                _ = interval.tick() => false,
+                _ = shutdown.recv() => true,
+            };
+            match self.drain_outbox_at(chrono::Utc::now().timestamp()).await {
+                Ok(0) => {}
+                Ok(n) => info!(delivered = n, "delivered queued messages"),
+                Err(e) => error!("outbox drain failed: {e}"),
+            }
+            if stopping {
+                return;
+            }
+        }
+    }
+
+    /// Send a typing indicator to every channel of every registered backend.
     pub async fn typing_all(&self) {
         for backend in &self.backends {
             let platform = backend.platform_name();
-            if let Some(channel) = self.primary_channels.get(platform) {
+            for channel in self.channels(platform) {
                 if let Err(e) = backend.send_typing(channel).await {
                     error!(platform, err = %e, "failed to send typing indicator");
                 }
@@ -110,6 +342,49 @@ impl MessagingManager {
         }
     }
 
+    /// Send a file to a platform's primary channel, or to the first
+    /// backend's when `platform` is `None`.  Returns the platform used.
+    pub async fn send_file(
+        &self,
+        platform: Option<&str>,
+        data: &[u8],
+        filename: &str,
+        mime: &str,
+    ) -> Result<String> {
+        let (backend, channel) = match platform {
+            Some(p) => {
+                let backend = self.get(p).ok_or_else(|| {
+                    crate::error::SafeAgentError::Messaging(format!("unknown platform '{p}'"))
+                })?;
+                let channel = self.primary_channel(p).ok_or_else(|| {
+                    crate::error::SafeAgentError::Messaging(format!("no primary channel for platform '{p}'"))
+                })?;
+                (backend, channel)
+            }
+            None => self.default_channel().ok_or_else(|| {
+                crate::error::SafeAgentError::Messaging("no messaging backends configured".into())
+            })?,
+        };
+        backend.send_file(channel, data, filename, mime).await?;
+        Ok(backend.platform_name().to_string())
+    }
+
+    /// Send a file to a specific channel, e.g. back to the chat a message
+    /// came from.
+    pub async fn send_file_to(
+        &self,
+        platform: &str,
+        channel: &str,
+        data: &[u8],
+        filename: &str,
+        mime: &str,
+    ) -> Result<()> {
+        let backend = self.get(platform).ok_or_else(|| {
+            crate::error::SafeAgentError::Messaging(format!("unknown platform '{platform}'"))
+        })?;
+        backend.send_file(channel, data, filename, mime).await
+    }
+
     /// List all registered platform names.
     pub fn platforms(&self) -> Vec<&str> {
         self.backends.iter().map(|b| b.platform_name()).collect()
@@ -134,7 +409,14 @@ pub fn split_message(text: &str, max_len: usize) -> Vec<&str> {
     let mut chunks = Vec::new();
     let mut start = 0;
     while start < text.len() {
-        let end = (start + max_len).min(text.len());
+        let mut end = (start + max_len).min(text.len());
+        // Never split inside a multi-byte character.
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == start {
+            end = start + text[start..].chars().next().map_or(1, char::len_utf8);
+        }
         let break_at = if end < text.len() {
             let chunk_len = end - start;
             text[start..end]
@@ -224,13 +506,53 @@ mod tests {
         let mut mgr = MessagingManager::new();
         mgr.register(b1, "ch1".into());
         mgr.register(b2, "ch2".into());
-        mgr.send_all("hello").await;
+        mgr.send_all("hello", Urgency::Normal).await;
         assert_eq!(sent1.lock().unwrap().len(), 1);
         assert_eq!(sent1.lock().unwrap()[0], ("ch1".to_string(), "hello".to_string()));
         assert_eq!(sent2.lock().unwrap().len(), 1);
         assert_eq!(sent2.lock().unwrap()[0], ("ch2".to_string(), "hello".to_string()));
     }
 
+    #[tokio::test]
+    async fn send_all_fans_out_to_every_telegram_channel() {
+        let (b, sent, typed) = MockBackend::new("telegram");
+        let mut mgr = MessagingManager::new();
+        mgr.register(b, "-100".into());
+        mgr.register_channel("telegram", "-200");
+        mgr.register_channel("telegram", "-200");
+        assert_eq!(mgr.channels("telegram"), ["-100", "-200"]);
+        assert_eq!(mgr.primary_channel("telegram"), Some("-100"));
+        assert!(mgr.channels("discord").is_empty());
+
+        mgr.send_all("hello", Urgency::Urgent).await;
+        assert_eq!(
+            sent.lock().unwrap().as_slice(),
+            [("-100".to_string(), "hello".to_string()), ("-200".to_string(), "hello".to_string())]
+        );
+        mgr.typing_all().await;
+        assert_eq!(typed.lock().unwrap().as_slice(), ["-100", "-200"]);
+    }
+
+    #[tokio::test]
+    async fn startup_notice_is_sent_only_when_enabled() {
+        let (b, sent, _) = MockBackend::new("tg");
+        let mut mgr = MessagingManager::new();
+        mgr.register(b, "42".into());
+
+        let mut config = crate::config::Config { agent_name: "Pixel".into(), ..Default::default() };
+        mgr.announce_online(&config).await;
+        mgr.announce_offline(&config).await;
+        assert!(sent.lock().unwrap().is_empty());
+
+        config.status_notifications.on_startup = true;
+        mgr.announce_online(&config).await;
+        assert_eq!(sent.lock().unwrap().as_slice(), [("42".to_string(), "Pixel is back online.".to_string())]);
+
+        config.status_notifications.on_shutdown = true;
+        mgr.announce_offline(&config).await;
+        assert_eq!(sent.lock().unwrap()[1].1, "Pixel is going offline.");
+    }
+
     #[tokio::test]
     async fn manager_typing_all() {
         let (b1, _, typed1) = MockBackend::new("p1");
@@ -241,6 +563,154 @@ mod tests {
         assert_eq!(typed1.lock().unwrap()[0], "ch1");
     }
 
+    /// (channel, byte count, filename, mime) per file sent.
+    type SentFiles = Arc<StdMutex<Vec<(String, usize, String, String)>>>;
+
+    // A backend that accepts attachments.
+    struct FileBackend {
+        files: SentFiles,
+    }
+
+    #[async_trait]
+    impl MessagingBackend for FileBackend {
+        fn platform_name(&self) -> &str { "files" }
+        fn max_message_length(&self) -> usize { 4096 }
+        async fn send_message(&self, _channel: &str, _text: &str) -> Result<()> { Ok(()) }
+        async fn send_typing(&self, _channel: &str) -> Result<()> { Ok(()) }
+        async fn send_file(&self, channel: &str, data: &[u8], filename: &str, mime: &str) -> Result<()> {
+            self.files.lock().unwrap().push((channel.into(), data.len(), filename.into(), mime.into()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn default_send_file_errors() {
+        let (b, _, _) = MockBackend::new("plain");
+        let err = b.send_file("ch", b"png", "chart.png", "image/png").await.unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("plain does not support file attachments"), "{msg}");
+        assert!(msg.contains("chart.png"), "{msg}");
+    }
+
+    #[tokio::test]
+    async fn manager_routes_file_to_backend() {
+        let (plain, _, _) = MockBackend::new("plain");
+        let files: SentFiles = Arc::default();
+        let mut mgr = MessagingManager::new();
+        mgr.register(plain, "p-ch".into());
+        mgr.register(Arc::new(FileBackend { files: files.clone() }), "f-ch".into());
+
+        let used = mgr.send_file(Some("files"), b"abc", "a.png", "image/png").await.unwrap();
+        assert_eq!(used, "files");
+        assert_eq!(
+            files.lock().unwrap()[0],
+            ("f-ch".to_string(), 3, "a.png".to_string(), "image/png".to_string())
+        );
+
+        // Default routing goes to the first backend, which can't send files.
+        assert!(mgr.send_file(None, b"abc", "a.png", "image/png").await.is_err());
+        assert!(mgr.send_file(Some("nope"), b"abc", "a.png", "image/png").await.is_err());
+    }
+
+    // A backend that fails a set number of sends before succeeding.
+    struct FlakyBackend {
+        failures_left: std::sync::atomic::AtomicUsize,
+        delivered: Arc<StdMutex<Vec<String>>>,
+    }
+
+    impl FlakyBackend {
+        fn new(failures: usize) -> (Arc<Self>, Arc<StdMutex<Vec<String>>>) {
+            let delivered = Arc::new(StdMutex::new(Vec::new()));
+            let backend = Arc::new(Self {
+                failures_left: failures.into(),
+                delivered: delivered.clone(),
+            });
+            (backend, delivered)
+        }
+    }
+
+    #[async_trait]
+    impl MessagingBackend for FlakyBackend {
+        fn platform_name(&self) -> &str { "flaky" }
+        fn max_message_length(&self) -> usize { 4096 }
+        async fn send_message(&self, _channel: &str, text: &str) -> Result<()> {
+            use std::sync::atomic::Ordering;
+            let failing = self
+                .failures_left
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if failing {
+                return Err(SafeAgentError::Messaging("network blip".into()));
+            }
+            self.delivered.lock().unwrap().push(text.to_string());
+            Ok(())
+        }
+        async fn send_typing(&self, _channel: &str) -> Result<()> { Ok(()) }
+    }
+
+    #[tokio::test]
+    async fn outbox_retries_until_delivered_once() {
+        let db = crate::db::test_db();
+        let (backend, delivered) = FlakyBackend::new(1);
+        let mut mgr = MessagingManager::new();
+        mgr.register(backend, "ch".into());
+        mgr.set_outbox(Outbox::new(db));
+
+        // First attempt fails; the message stays queued.
+        mgr.enqueue("flaky", "ch", "disk almost full").await.unwrap();
+        assert!(delivered.lock().unwrap().is_empty());
+
+        // Not due yet, then delivered once the backoff has passed.
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(mgr.drain_outbox_at(now).await.unwrap(), 0);
+        assert_eq!(mgr.drain_outbox_at(now + 3600).await.unwrap(), 1);
+        assert_eq!(mgr.drain_outbox_at(now + 7200).await.unwrap(), 0);
+        assert_eq!(*delivered.lock().unwrap(), vec!["disk almost full".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn outbox_survives_restart() {
+        let db = crate::db::test_db();
+        {
+            let (down, _) = FlakyBackend::new(usize::MAX);
+            let mut mgr = MessagingManager::new();
+            mgr.register(down, "ch".into());
+            mgr.set_outbox(Outbox::new(db.clone()));
+            mgr.send_all("backup finished", Urgency::Normal).await;
+        }
+
+        let (up, delivered) = FlakyBackend::new(0);
+        let mut mgr = MessagingManager::new();
+        mgr.register(up, "ch".into());
+        mgr.set_outbox(Outbox::new(db));
+        let later = chrono::Utc::now().timestamp() + 3600;
+        assert_eq!(mgr.drain_outbox_at(later).await.unwrap(), 1);
+        assert_eq!(*delivered.lock().unwrap(), vec!["backup finished".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn quiet_hours_defer_only_non_urgent_messages() {
+        let (b, sent, _) = MockBackend::new("tg");
+        let mut mgr = MessagingManager::new();
+        mgr.register(b, "42".into());
+        mgr.set_outbox(Outbox::new(crate::db::test_db()));
+        let quiet = crate::config::QuietHours::parse("22:00", "07:00").unwrap().unwrap();
+        mgr.set_quiet_hours(Some(quiet), chrono_tz::UTC);
+        // Two days out, so the deferred message can't come due mid-test.
+        let day = chrono::Utc::now().date_naive() + chrono::Days::new(2);
+        let night = day.and_hms_opt(23, 30, 0).unwrap().and_utc();
+        let morning = (day + chrono::Days::new(1)).and_hms_opt(7, 0, 0).unwrap().and_utc();
+
+        mgr.send_all_at("goal update", Urgency::Normal, night).await;
+        assert!(sent.lock().unwrap().is_empty());
+        mgr.send_all_at("tool failed", Urgency::Urgent, night).await;
+        assert_eq!(sent.lock().unwrap().as_slice(), [("42".to_string(), "tool failed".to_string())]);
+
+        assert_eq!(mgr.drain_outbox_at(morning.timestamp() - 1).await.unwrap(), 0);
+        assert_eq!(mgr.drain_outbox_at(morning.timestamp()).await.unwrap(), 1);
+        assert_eq!(sent.lock().unwrap()[1].1, "goal update");
+    }
+
     #[test]
     fn test_split_message_short() {
         let text = "Hello world";
@@ -334,4 +804,23 @@ mod tests {
         assert_eq!(chunks[2], "xxx");
         assert_eq!(chunks[3], "x");
     }
+
+    #[tokio::test]
+    async fn enqueue_attempts_only_the_new_message() {
+        let db = crate::db::test_db();
+        let (b, sent, _) = MockBackend::new("tg");
+        let mut mgr = MessagingManager::new();
+        mgr.register(b, "42".into());
+        let outbox = Outbox::new(db.clone());
+        let now = chrono::Utc::now().timestamp();
+        outbox.push("tg", "42", "older, due", now - 60).await.unwrap();
+        mgr.set_outbox(outbox);
+
+        mgr.enqueue("tg", "42", "fresh").await.unwrap();
+        assert_eq!(sent.lock().unwrap().as_slice(), [("42".to_string(), "fresh".to_string())]);
+
+        // The older row is left for the drainer.
+        assert_eq!(mgr.drain_outbox_at(now).await.unwrap(), 1);
+        assert_eq!(sent.lock().unwrap()[1].1, "older, due");
+    }
 }
@@ -0,0 +1,190 @@
+//! Persistent outbound message queue.
+//!
+//! Messages are written to `outbound_messages` before delivery and only
+//! removed once a backend accepts them.  Failed sends are retried with
+//! exponential backoff until they succeed or exceed [`MAX_AGE_SECS`], so a
+//! transient network blip (or a restart) doesn't silently drop a
+//! notification.
+
+use std::sync::Arc;
+
+use rusqlite::Connection;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::error::Result;
+
+/// Delay before the first retry; doubles with each failed attempt.
+const BASE_BACKOFF_SECS: i64 = 10;
+/// Longest delay between retries.
+const MAX_BACKOFF_SECS: i64 = 15 * 60;
+/// Undelivered messages older than this are dropped.
+const MAX_AGE_SECS: i64 = 24 * 60 * 60;
+
+/// A queued message awaiting delivery.
+#[derive(Debug, Clone)]
+pub struct PendingMessage {
+    pub id: i64,
+    pub platform: String,
+    pub channel: String,
+    pub text: String,
+    pub attempts: u32,
+    pub created_at: i64,
+}
+
+pub struct Outbox {
+    db: Arc<Mutex<Connection>>,
+    /// Held while draining so concurrent drains never send a row twice.
+    drain_lock: Mutex<()>,
+}
+
+impl Outbox {
+    pub fn new(db: Arc<Mutex<Connection>>) -> Self {
+        Self {
+            db,
+            drain_lock: Mutex::new(()),
+        }
+    }
+
+    /// Persist a message for delivery as soon as possible.
+    pub async fn push(&self, platform: &str, channel: &str, text: &str, now: i64) -> Result<i64> {
+        self.push_deferred(platform, channel, text, now, now).await
+    }
+
+    /// Persist a message that must not be sent before `not_before`.  Its
+    /// age for [`MAX_AGE_SECS`] counts from then.
+    pub async fn push_deferred(
+        &self,
+        platform: &str,
+        channel: &str,
+        text: &str,
+        now: i64,
+        not_before: i64,
+    ) -> Result<i64> {
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT INTO outbound_messages (platform, channel, text, next_attempt_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)",
+            rusqlite::params![platform, channel, text, not_before.max(now)],
+        )?;
+        Ok(db.last_insert_rowid())
+    }
+
+    /// Take the drain lock.  Callers hold the guard for the whole
+    /// fetch/send/record cycle.
+    pub async fn lock(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.drain_lock.lock().await
+    }
+
+    /// Messages whose next attempt is due, oldest first.
+    pub async fn due(&self, now: i64) -> Result<Vec<PendingMessage>> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT id, platform, channel, text, attempts, created_at
+             FROM outbound_messages WHERE next_attempt_at <= ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map([now], |row| {
+            Ok(PendingMessage {
+                id: row.get(0)?,
+                platform: row.get(1)?,
+                channel: row.get(2)?,
+                text: row.get(3)?,
+                attempts: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
+    }
+
+    /// A queued message by id, if it is still waiting and due at `now`.
+    pub async fn get_due(&self, id: i64, now: i64) -> Result<Option<PendingMessage>> {
+        use rusqlite::OptionalExtension;
+
+        let db = self.db.lock().await;
+        Ok(db
+            .query_row(
+                "SELECT id, platform, channel, text, attempts, created_at
+                 FROM outbound_messages WHERE id = ?1 AND next_attempt_at <= ?2",
+                [id, now],
+                |row| {
+                    Ok(PendingMessage {
+                        id: row.get(0)?,
+                        platform: row.get(1)?,
+                        channel: row.get(2)?,
+                        text: row.get(3)?,
+                        attempts: row.get(4)?,
+                        created_at: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    pub async fn delivered(&self, id: i64) -> Result<()> {
+        let db = self.db.lock().await;
+        db.execute("DELETE FROM outbound_messages WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// Record a failed attempt: reschedule with backoff, or drop the
+    /// message once it is too old to be worth delivering.
+    pub async fn failed(&self, msg: &PendingMessage, error: &str, now: i64) -> Result<()> {
+        let db = self.db.lock().await;
+        if now - msg.created_at >= MAX_AGE_SECS {
+            warn!(
+                platform = %msg.platform,
+                channel = %msg.channel,
+                attempts = msg.attempts + 1,
+                "dropping undeliverable message: {error}"
+            );
+            db.execute("DELETE FROM outbound_messages WHERE id = ?1", [msg.id])?;
+            return Ok(());
+        }
+        let attempts = msg.attempts + 1;
+        db.execute(
+            "UPDATE outbound_messages SET attempts = ?1, next_attempt_at = ?2, last_error = ?3 WHERE id = ?4",
+            rusqlite::params![attempts, now + backoff_secs(attempts), error, msg.id],
+        )?;
+        Ok(())
+    }
+
+    /// Number of messages still waiting for delivery.
+    #[cfg(test)]
+    pub async fn pending(&self) -> Result<i64> {
+        let db = self.db.lock().await;
+        Ok(db.query_row("SELECT COUNT(*) FROM outbound_messages", [], |r| r.get(0))?)
+    }
+}
+
+/// Delay before the next attempt after `attempts` failures.
+fn backoff_secs(attempts: u32) -> i64 {
+    let exp = attempts.saturating_sub(1).min(16);
+    (BASE_BACKOFF_SECS << exp).min(MAX_BACKOFF_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        assert_eq!(backoff_secs(1), 10);
+        assert_eq!(backoff_secs(2), 20);
+        assert_eq!(backoff_secs(4), 80);
+        assert_eq!(backoff_secs(30), MAX_BACKOFF_SECS);
+    }
+
+    #[tokio::test]
+    async fn stale_messages_are_dropped() {
+        let outbox = Outbox::new(crate::db::test_db());
+        outbox.push("p", "c", "old news", 0).await.unwrap();
+        let msg = outbox.due(0).await.unwrap().remove(0);
+
+        outbox.failed(&msg, "down", 60).await.unwrap();
+        assert_eq!(outbox.pending().await.unwrap(), 1);
+        assert!(outbox.due(60).await.unwrap().is_empty());
+
+        outbox.failed(&msg, "down", MAX_AGE_SECS).await.unwrap();
+        assert_eq!(outbox.pending().await.unwrap(), 0);
+    }
+}
@@ -2,16 +2,20 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use rusqlite::Connection;
+use teloxide::net::Download;
 use teloxide::prelude::*;
-use teloxide::types::ChatAction;
+use teloxide::types::{ChatAction, InputFile};
 use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 
+use crate::agent::attachments::{MAX_INBOUND_IMAGE_BYTES, Origin};
 use crate::agent::Agent;
 use crate::config::TelegramConfig;
 use crate::error::Result;
+use crate::llm::Attachment;
 
 use super::commands::{handle_bot_command, CommandPrefix, CommandResult};
+use super::dedup::ProcessedUpdates;
 use super::{split_message, MessagingBackend};
 
 // ---------------------------------------------------------------------------
@@ -70,6 +74,33 @@ impl MessagingBackend for TelegramBackend {
         let _ = self.bot.send_chat_action(ChatId(chat_id), ChatAction::Typing).await;
         Ok(())
     }
+
+    async fn send_file(&self, channel: &str, data: &[u8], filename: &str, mime: &str) -> Result<()> {
+        let chat_id: i64 = channel
+            .parse()
+            .map_err(|_| crate::error::SafeAgentError::Messaging(
+                format!("invalid telegram chat id: {channel}"),
+            ))?;
+        let cid = ChatId(chat_id);
+        let file = InputFile::memory(data.to_vec()).file_name(filename.to_string());
+
+        // Telegram re-encodes photos, so only still images go through
+        // sendPhoto; everything else (including GIFs) is a document.
+        let sent = if is_photo(mime) {
+            self.bot.send_photo(cid, file).await.map(|_| ())
+        } else {
+            self.bot.send_document(cid, file).await.map(|_| ())
+        };
+        sent.map_err(|e| {
+            error!(chat_id, err = %e, "failed to send telegram file");
+            crate::error::SafeAgentError::Messaging(format!("telegram file send failed: {e}"))
+        })
+    }
+}
+
+/// MIME types Telegram accepts via `sendPhoto`.
+fn is_photo(mime: &str) -> bool {
+    matches!(mime, "image/jpeg" | "image/png" | "image/webp")
 }
 
 // ---------------------------------------------------------------------------
@@ -82,6 +113,7 @@ struct TelegramState {
     db: Arc<Mutex<Connection>>,
     config: TelegramConfig,
     agent: Arc<Agent>,
+    processed: ProcessedUpdates,
 }
 
 /// Start the Telegram long-polling dispatcher. Returns the bot handle and a
@@ -95,6 +127,7 @@ pub async fn start(
     let bot = backend.bot().clone();
 
     let state = TelegramState {
+        processed: ProcessedUpdates::new(db.clone()),
         db,
         config: config.clone(),
         agent,
@@ -148,12 +181,19 @@ pub async fn start(
 
 async fn handle_message(
     bot: Bot,
+    update: Update,
     msg: Message,
     state: TelegramState,
 ) -> ResponseResult<()> {
     let chat_id = msg.chat.id.0;
     info!(chat_id, "telegram message received");
 
+    // A restarted dispatcher can re-deliver updates we already handled.
+    if !state.processed.claim("telegram", update.id.0 as i64).await {
+        info!(chat_id, update_id = update.id.0, "skipping already-processed telegram update");
+        return Ok(());
+    }
+
     // Authorization check
     if !state.config.allowed_chat_ids.is_empty()
         && !state.config.allowed_chat_ids.contains(&chat_id)
@@ -166,7 +206,8 @@ async fn handle_message(
         return Ok(());
     }
 
-    let text = msg.text().unwrap_or("");
+    // Photos carry their text as a caption
+    let text = msg.text().or(msg.caption()).unwrap_or("");
     info!(chat_id, text, "telegram message authorized");
 
     match handle_bot_command(text, CommandPrefix::Slash, &state.db, &state.agent).await {
@@ -218,6 +259,7 @@ async fn handle_message(
             };
 
             tokio::spawn(async move {
+                let attachments: Vec<Attachment> = inbound_image(&bot, &msg, &agent).await.into_iter().collect();
                 let typing_bot = bot.clone();
                 let typing_handle = tokio::spawn(async move {
                     loop {
@@ -232,7 +274,11 @@ async fn handle_message(
                     }
                 });
 
-                let result = agent.handle_message_as(&user_text, user_ctx.as_ref()).await;
+                let channel = chat.0.to_string();
+                let origin = Origin { platform: "telegram", channel: &channel };
+                let result = agent
+                    .handle_message_with_attachments(&user_text, &attachments, Some(origin), user_ctx.as_ref())
+                    .await;
                 typing_handle.abort();
 
                 match result {
@@ -257,6 +303,42 @@ async fn handle_message(
     Ok(())
 }
 
+/// Download the photo (largest size) or image document attached to `msg`
+/// into the agent's sandbox.  Failures are logged and the message handled
+/// without it.
+async fn inbound_image(bot: &Bot, msg: &Message, agent: &Agent) -> Option<Attachment> {
+    let (file_id, mime_type) = match (msg.photo(), msg.document()) {
+        (Some(sizes), _) => (sizes.last()?.file.id.clone(), "image/jpeg".to_string()),
+        (None, Some(doc)) => {
+            let mime_type = doc.mime_type.as_ref()?.essence_str().to_string();
+            if !mime_type.starts_with("image/") {
+                return None;
+            }
+            (doc.file.id.clone(), mime_type)
+        }
+        (None, None) => return None,
+    };
+
+    let file = bot
+        .get_file(file_id)
+        .await
+        .map_err(|e| warn!(err = %e, "failed to look up telegram image"))
+        .ok()?;
+    if file.size as usize > MAX_INBOUND_IMAGE_BYTES {
+        warn!(size = file.size, "telegram image too large, ignoring it");
+        return None;
+    }
+    let mut data = Vec::new();
+    bot.download_file(&file.path, &mut data)
+        .await
+        .map_err(|e| warn!(err = %e, "failed to download telegram image"))
+        .ok()?;
+    agent
+        .save_inbound_image("telegram", &data, &mime_type)
+        .map_err(|e| warn!(err = %e, "failed to save telegram image"))
+        .ok()
+}
+
 // ---------------------------------------------------------------------------
 // Mention stripping
 // ---------------------------------------------------------------------------
@@ -3,9 +3,13 @@ use tracing::debug;
 
 use crate::error::{Result, SafeAgentError};
 
-use super::MessagingBackend;
+use super::{split_message, MessagingBackend};
+
+/// Twilio concatenates up to 10 SMS segments (1600 chars).
+const MAX_SMS_LEN: usize = 1600;
 
 /// Twilio SMS backend — sends SMS directly via the Twilio REST API.
+/// Registered under the platform name `sms`.
 pub struct TwilioBackend {
     account_sid: String,
     auth_token: String,
@@ -22,47 +26,52 @@ impl TwilioBackend {
             http: reqwest::Client::new(),
         }
     }
+
+    fn messages_url(&self) -> String {
+        format!(
+            "https://api.twilio.com/2010-04-01/Accounts/{}/Messages.json",
+            self.account_sid
+        )
+    }
+
+    /// Form fields for one Messages API request.
+    fn message_form<'a>(&'a self, to: &'a str, body: &'a str) -> [(&'static str, &'a str); 3] {
+        [("From", self.from_number.as_str()), ("To", to), ("Body", body)]
+    }
 }
 
 #[async_trait]
 impl MessagingBackend for TwilioBackend {
     fn platform_name(&self) -> &str {
-        "twilio"
+        "sms"
     }
 
     fn max_message_length(&self) -> usize {
-        // Twilio concatenates up to 10 SMS segments (1600 chars).
-        1600
+        MAX_SMS_LEN
     }
 
     async fn send_message(&self, channel: &str, text: &str) -> Result<()> {
         debug!(to = channel, "sending SMS via Twilio");
 
-        let url = format!(
-            "https://api.twilio.com/2010-04-01/Accounts/{}/Messages.json",
-            self.account_sid
-        );
+        let url = self.messages_url();
+        for chunk in split_message(text, self.max_message_length()) {
+            let resp = self
+                .http
+                .post(&url)
+                .basic_auth(&self.account_sid, Some(&self.auth_token))
+                .form(&self.message_form(channel, chunk))
+                .timeout(std::time::Duration::from_secs(15))
+                .send()
+                .await
+                .map_err(|e| SafeAgentError::Messaging(format!("twilio send failed: {e}")))?;
 
-        let resp = self
-            .http
-            .post(&url)
-            .basic_auth(&self.account_sid, Some(&self.auth_token))
-            .form(&[
-                ("From", self.from_number.as_str()),
-                ("To", channel),
-                ("Body", text),
-            ])
-            .timeout(std::time::Duration::from_secs(15))
-            .send()
-            .await
-            .map_err(|e| SafeAgentError::Messaging(format!("twilio send failed: {e}")))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(SafeAgentError::Messaging(format!(
-                "twilio returned {status}: {body}"
-            )));
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                return Err(SafeAgentError::Messaging(format!(
+                    "twilio returned {status}: {body}"
+                )));
+            }
         }
 
         Ok(())
@@ -73,3 +82,48 @@ impl MessagingBackend for TwilioBackend {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend() -> TwilioBackend {
+        TwilioBackend::new("AC123".into(), "secret".into(), "+15559876543".into())
+    }
+
+    #[test]
+    fn long_messages_split_at_concatenated_sms_limit() {
+        let backend = backend();
+        assert_eq!(backend.platform_name(), "sms");
+        assert_eq!(backend.max_message_length(), 1600);
+
+        let text = "x".repeat(3500);
+        let chunks = split_message(&text, backend.max_message_length());
+        let lens: Vec<usize> = chunks.iter().map(|c| c.len()).collect();
+        assert_eq!(lens, vec![1600, 1600, 300]);
+    }
+
+    #[test]
+    fn message_form_is_url_encoded() {
+        let backend = backend();
+        assert_eq!(
+            backend.messages_url(),
+            "https://api.twilio.com/2010-04-01/Accounts/AC123/Messages.json"
+        );
+
+        let request = reqwest::Client::new()
+            .post(backend.messages_url())
+            .form(&backend.message_form("+15551234567", "Disk 90% full & rising"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.headers()["content-type"],
+            "application/x-www-form-urlencoded"
+        );
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+        assert_eq!(
+            std::str::from_utf8(body).unwrap(),
+            "From=%2B15559876543&To=%2B15551234567&Body=Disk+90%25+full+%26+rising"
+        );
+    }
+}
@@ -6,6 +6,8 @@
  *   AUTH_DIR      – Directory to persist session auth state
  *   WEBHOOK_URL   – URL to POST incoming messages to (the agent webhook)
  *   ALLOWED_NUMBERS – Comma-separated list of allowed phone numbers (E.164)
+ *   WHATSAPP_CLOUD_TOKEN, WHATSAPP_PHONE_NUMBER_ID – WhatsApp Business Cloud
+ *                   API credentials, needed only for /send-template
  */
 
 const {
@@ -14,6 +16,7 @@ const {
   DisconnectReason,
   fetchLatestBaileysVersion,
   makeCacheableSignalKeyStore,
+  downloadMediaMessage,
 } = require("@whiskeysockets/baileys");
 const express = require("express");
 const QRCode = require("qrcode");
@@ -30,6 +33,10 @@ const ALLOWED_NUMBERS = (process.env.ALLOWED_NUMBERS || "")
   .map((n) => n.trim())
   .filter(Boolean);
 
+const CLOUD_TOKEN = process.env.WHATSAPP_CLOUD_TOKEN || "";
+const CLOUD_PHONE_NUMBER_ID = process.env.WHATSAPP_PHONE_NUMBER_ID || "";
+const CLOUD_API_URL = `https://graph.facebook.com/v19.0/${CLOUD_PHONE_NUMBER_ID}/messages`;
+
 const logger = pino({ level: "info" });
 
 let sock = null;
@@ -42,7 +49,8 @@ let connectedNumber = null;
 // ---------------------------------------------------------------------------
 
 const app = express();
-app.use(express.json());
+// Large enough for base64-encoded attachments sent to /send-file.
+app.use(express.json({ limit: "25mb" }));
 
 // POST /send  { to, text }
 app.post("/send", async (req, res) => {
@@ -67,6 +75,76 @@ app.post("/send", async (req, res) => {
   }
 });
 
+// POST /send-file  { to, data (base64), filename, mimetype }
+app.post("/send-file", async (req, res) => {
+  try {
+    const { to, data, filename, mimetype } = req.body;
+    if (!to || !data || !mimetype) {
+      return res.status(400).json({ error: "missing 'to', 'data' or 'mimetype'" });
+    }
+
+    if (!sock) {
+      return res.status(503).json({ error: "not connected" });
+    }
+
+    const jid = to.includes("@") ? to : `${to.replace(/\+/g, "")}@s.whatsapp.net`;
+    const buffer = Buffer.from(data, "base64");
+
+    const content = mimetype.startsWith("image/") && mimetype !== "image/gif"
+      ? { image: buffer, mimetype }
+      : { document: buffer, mimetype, fileName: filename || "file" };
+
+    await sock.sendMessage(jid, content);
+    res.json({ ok: true });
+  } catch (err) {
+    logger.error({ err }, "send-file failed");
+    res.status(500).json({ error: err.message });
+  }
+});
+
+// POST /send-template  { to, template: { name, language, components } }
+//
+// Message templates only exist on the WhatsApp Business platform, so this
+// goes through the Cloud API rather than the linked-device session.
+app.post("/send-template", async (req, res) => {
+  try {
+    const { to, template } = req.body;
+    if (!to || !template?.name) {
+      return res.status(400).json({ error: "missing 'to' or 'template.name'" });
+    }
+
+    if (!CLOUD_TOKEN || !CLOUD_PHONE_NUMBER_ID) {
+      return res.status(501).json({
+        error: "templates need WHATSAPP_CLOUD_TOKEN and WHATSAPP_PHONE_NUMBER_ID",
+      });
+    }
+
+    const resp = await fetch(CLOUD_API_URL, {
+      method: "POST",
+      headers: {
+        "Content-Type": "application/json",
+        Authorization: `Bearer ${CLOUD_TOKEN}`,
+      },
+      body: JSON.stringify({
+        messaging_product: "whatsapp",
+        to: to.split("@")[0].replace(/\D/g, ""),
+        type: "template",
+        template,
+      }),
+    });
+
+    if (!resp.ok) {
+      const body = await resp.text();
+      logger.error({ status: resp.status, body }, "template send failed");
+      return res.status(502).json({ error: body });
+    }
+    res.json({ ok: true });
+  } catch (err) {
+    logger.error({ err }, "send-template failed");
+    res.status(500).json({ error: err.message });
+  }
+});
+
 // GET /status
 app.get("/status", (req, res) => {
   res.json({
@@ -161,12 +239,14 @@ async function connectWhatsApp() {
       if (!sender || sender.endsWith("@g.us")) continue; // skip group messages
 
       const senderNumber = "+" + sender.split("@")[0];
+      const image = msg.message?.imageMessage;
       const text =
         msg.message?.conversation ||
         msg.message?.extendedTextMessage?.text ||
+        image?.caption ||
         "";
 
-      if (!text) continue;
+      if (!text && !image) continue;
 
       // Authorization
       if (
@@ -180,7 +260,24 @@ async function connectWhatsApp() {
         continue;
       }
 
-      logger.info({ sender: senderNumber, text }, "incoming message");
+      logger.info({ sender: senderNumber, text, image: !!image }, "incoming message");
+
+      // Images are inlined so the agent can save them to its sandbox
+      const attachments = [];
+      if (image) {
+        try {
+          const buffer = await downloadMediaMessage(msg, "buffer", {}, {
+            logger,
+            reuploadRequest: sock.updateMediaMessage,
+          });
+          attachments.push({
+            data: buffer.toString("base64"),
+            mime_type: image.mimetype || "image/jpeg",
+          });
+        } catch (err) {
+          logger.error({ err }, "failed to download image");
+        }
+      }
 
       // Forward to the agent webhook
       try {
@@ -192,6 +289,7 @@ async function connectWhatsApp() {
             channel: sender,
             sender: senderNumber,
             text,
+            attachments,
           }),
         });
 
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use tokio::process::{Child, Command};
@@ -10,6 +12,11 @@ use crate::error::{Result, SafeAgentError};
 
 use super::MessagingBackend;
 
+/// WhatsApp Business only allows free-form messages within this long of
+/// the user's last message; outside it, conversations must open with an
+/// approved template.
+const SESSION_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
 // ---------------------------------------------------------------------------
 // WhatsApp backend
 // ---------------------------------------------------------------------------
@@ -19,6 +26,10 @@ pub struct WhatsAppBackend {
     http: reqwest::Client,
     bridge_url: String,
     bridge_process: Mutex<Option<Child>>,
+    /// Last incoming message per number (see [`session_key`]).  In memory
+    /// only, so after a restart notifications use the template until the
+    /// user writes again.
+    last_incoming: std::sync::Mutex<HashMap<String, Instant>>,
 }
 
 impl WhatsAppBackend {
@@ -31,9 +42,53 @@ impl WhatsAppBackend {
             http: reqwest::Client::new(),
             bridge_url,
             bridge_process: Mutex::new(None),
+            last_incoming: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Send an approved message template, filling its body parameters in
+    /// order.  Goes through the bridge's `/send-template` endpoint.
+    pub async fn send_template(&self, channel: &str, template_name: &str, params: &[&str]) -> Result<()> {
+        debug!(channel, template_name, "sending whatsapp template via bridge");
+        let payload = template_payload(channel, template_name, &self.config.template_language, params);
+        self.post_to_bridge("send-template", &payload, Duration::from_secs(15)).await
+    }
+
+    /// The template to use for a notification to `channel` at `now`, or
+    /// `None` to send free-form text.
+    fn notification_template(&self, channel: &str, now: Instant) -> Option<&str> {
+        if self.config.notification_template.is_empty() {
+            return None;
+        }
+        let last = self.last_incoming.lock().unwrap().get(&session_key(channel)).copied();
+        if in_session_window(last, now) {
+            None
+        } else {
+            Some(&self.config.notification_template)
         }
     }
 
+    async fn post_to_bridge(&self, path: &str, body: &serde_json::Value, timeout: Duration) -> Result<()> {
+        let resp = self
+            .http
+            .post(format!("{}/{path}", self.bridge_url))
+            .json(body)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| SafeAgentError::Messaging(format!("whatsapp {path} failed: {e}")))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(SafeAgentError::Messaging(format!(
+                "whatsapp bridge returned {status}: {body}"
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Spawn the Baileys bridge Node.js process.
     pub async fn start_bridge(&self, data_dir: PathBuf) -> Result<()> {
         let bridge_dir = self.find_bridge_dir()?;
@@ -111,28 +166,28 @@ impl MessagingBackend for WhatsAppBackend {
 
     async fn send_message(&self, channel: &str, text: &str) -> Result<()> {
         debug!(channel, "sending whatsapp message via bridge");
+        let body = serde_json::json!({
+            "to": channel,
+            "text": text,
+        });
+        self.post_to_bridge("send", &body, Duration::from_secs(15)).await
+    }
 
-        let resp = self
-            .http
-            .post(format!("{}/send", self.bridge_url))
-            .json(&serde_json::json!({
-                "to": channel,
-                "text": text,
-            }))
-            .timeout(std::time::Duration::from_secs(15))
-            .send()
-            .await
-            .map_err(|e| SafeAgentError::Messaging(format!("whatsapp send failed: {e}")))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(SafeAgentError::Messaging(format!(
-                "whatsapp bridge returned {status}: {body}"
-            )));
+    async fn send_notification(&self, channel: &str, text: &str) -> Result<()> {
+        match self.notification_template(channel, Instant::now()) {
+            Some(template) => {
+                let param = template_param(text);
+                self.send_template(channel, template, &[&param]).await
+            }
+            None => self.send_message(channel, text).await,
         }
+    }
 
-        Ok(())
+    fn note_incoming(&self, channel: &str) {
+        self.last_incoming
+            .lock()
+            .unwrap()
+            .insert(session_key(channel), Instant::now());
     }
 
     async fn send_typing(&self, channel: &str) -> Result<()> {
@@ -140,6 +195,17 @@ impl MessagingBackend for WhatsAppBackend {
         debug!(channel, "whatsapp typing indicator (no-op)");
         Ok(())
     }
+
+    async fn send_file(&self, channel: &str, data: &[u8], filename: &str, mime: &str) -> Result<()> {
+        debug!(channel, filename, mime, "sending whatsapp file via bridge");
+        let body = serde_json::json!({
+            "to": channel,
+            "data": data_encoding::BASE64.encode(data),
+            "filename": filename,
+            "mimetype": mime,
+        });
+        self.post_to_bridge("send-file", &body, Duration::from_secs(60)).await
+    }
 }
 
 impl Drop for WhatsAppBackend {
@@ -148,3 +214,106 @@ impl Drop for WhatsAppBackend {
         warn!("whatsapp backend dropped");
     }
 }
+
+/// Normalize a channel to its phone number digits, so the bridge's JIDs
+/// (`15551234567@s.whatsapp.net`) and configured numbers (`+15551234567`)
+/// share a session.
+fn session_key(channel: &str) -> String {
+    let number = channel.split('@').next().unwrap_or(channel);
+    number.chars().filter(char::is_ascii_digit).collect()
+}
+
+/// Whether free-form messages are still allowed after the user's last
+/// message at `last`.
+fn in_session_window(last: Option<Instant>, now: Instant) -> bool {
+    last.is_some_and(|t| now.saturating_duration_since(t) < SESSION_WINDOW)
+}
+
+/// Template parameters may not contain newlines, tabs or long runs of
+/// spaces, so collapse all whitespace.
+fn template_param(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Bridge payload for a template message, with the template object in
+/// WhatsApp Cloud API shape.
+fn template_payload(to: &str, name: &str, language: &str, params: &[&str]) -> serde_json::Value {
+    let parameters: Vec<serde_json::Value> = params
+        .iter()
+        .map(|p| serde_json::json!({ "type": "text", "text": p }))
+        .collect();
+    let components = if parameters.is_empty() {
+        Vec::new()
+    } else {
+        vec![serde_json::json!({ "type": "body", "parameters": parameters })]
+    };
+    serde_json::json!({
+        "to": to,
+        "template": {
+            "name": name,
+            "language": { "code": language },
+            "components": components,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_payload_fills_body_parameters() {
+        let payload = template_payload("+15551234567", "agent_notification", "en_US", &["Backup done", "3"]);
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "to": "+15551234567",
+                "template": {
+                    "name": "agent_notification",
+                    "language": { "code": "en_US" },
+                    "components": [{
+                        "type": "body",
+                        "parameters": [
+                            { "type": "text", "text": "Backup done" },
+                            { "type": "text", "text": "3" },
+                        ],
+                    }],
+                },
+            })
+        );
+
+        let bare = template_payload("+1", "hello_world", "en_US", &[]);
+        assert_eq!(bare["template"]["components"], serde_json::json!([]));
+        assert_eq!(template_param("Disk\n\tnearly   full"), "Disk nearly full");
+    }
+
+    #[test]
+    fn session_window_selects_template() {
+        let backend = WhatsAppBackend::new(WhatsAppConfig {
+            notification_template: "agent_notification".into(),
+            ..WhatsAppConfig::default()
+        });
+        let now = Instant::now();
+
+        // Never heard from the user: template.
+        assert_eq!(backend.notification_template("+15551234567", now), Some("agent_notification"));
+
+        // The bridge reports JIDs; the primary channel is the E.164 number.
+        backend.note_incoming("15551234567@s.whatsapp.net");
+        assert_eq!(backend.notification_template("+15551234567", Instant::now()), None);
+        assert_eq!(backend.notification_template("+15550000000", Instant::now()), Some("agent_notification"));
+
+        let later = Instant::now() + SESSION_WINDOW + Duration::from_secs(1);
+        assert_eq!(backend.notification_template("+15551234567", later), Some("agent_notification"));
+
+        assert!(in_session_window(Some(now), now + SESSION_WINDOW - Duration::from_secs(1)));
+        assert!(!in_session_window(Some(now), now + SESSION_WINDOW));
+        assert!(!in_session_window(None, now));
+    }
+
+    #[test]
+    fn no_template_configured_always_sends_text() {
+        let backend = WhatsAppBackend::new(WhatsAppConfig::default());
+        assert_eq!(backend.notification_template("+15551234567", Instant::now()), None);
+    }
+}
@@ -0,0 +1,79 @@
+//! Building blocks for the Prometheus text output served at `/metrics`.
+
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Upper bounds (seconds) of the duration histogram buckets.
+const BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0];
+
+/// A duration histogram for one label set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Histogram {
+    /// Observations per bucket in [`BUCKETS`] (not cumulative).
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum_secs: f64,
+}
+
+impl Histogram {
+    pub fn observe(&mut self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        if let Some(i) = BUCKETS.iter().position(|&le| secs <= le) {
+            self.buckets[i] += 1;
+        }
+        self.count += 1;
+        self.sum_secs += secs;
+    }
+
+    /// Number of observations.
+    #[cfg(test)]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Append the `_bucket`, `_sum` and `_count` lines for `name`.  `labels`
+    /// is the rendered label list without braces, e.g. `tool="exec"`.
+    pub fn write_prometheus(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (le, n) in BUCKETS.iter().zip(self.buckets) {
+            cumulative += n;
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{le}\"}} {cumulative}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {:.6}", self.sum_secs);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+/// Escape a Prometheus label value.
+pub fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let mut h = Histogram::default();
+        h.observe(Duration::from_millis(20));
+        h.observe(Duration::from_secs(3));
+        h.observe(Duration::from_secs(900));
+
+        let mut text = String::new();
+        h.write_prometheus(&mut text, "x_seconds", "tool=\"exec\"");
+        assert!(text.contains("x_seconds_bucket{tool=\"exec\",le=\"0.01\"} 0\n"));
+        assert!(text.contains("x_seconds_bucket{tool=\"exec\",le=\"0.025\"} 1\n"));
+        assert!(text.contains("x_seconds_bucket{tool=\"exec\",le=\"5\"} 2\n"));
+        assert!(text.contains("x_seconds_bucket{tool=\"exec\",le=\"300\"} 2\n"));
+        assert!(text.contains("x_seconds_bucket{tool=\"exec\",le=\"+Inf\"} 3\n"));
+        assert!(text.contains("x_seconds_sum{tool=\"exec\"} 903.020000\n"));
+        assert!(text.contains("x_seconds_count{tool=\"exec\"} 3\n"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+}
@@ -1,14 +1,21 @@
 use std::sync::Arc;
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tokio::sync::Mutex;
-use tracing::error;
+use tracing::{error, warn};
+
+use crate::tools::ToolCall;
 
 /// Structured audit log for every security-relevant event.
 ///
 /// Events include tool executions, approval decisions, LLM calls,
 /// rate-limit hits, PII detection, 2FA challenges, and permission denials.
+///
+/// Entries are hash-linked: each row's `entry_hash` covers its own fields
+/// plus the previous row's hash, so editing or deleting a row breaks the
+/// chain from that point on (see [`AuditLogger::verify_chain`]).
 pub struct AuditLogger {
     db: Arc<Mutex<Connection>>,
 }
@@ -28,6 +35,33 @@ pub struct AuditEntry {
     pub created_at: String,
 }
 
+impl AuditEntry {
+    /// Rebuild the tool call a `tool_call` entry recorded.  `None` for
+    /// other event types or entries without parseable params.
+    pub fn tool_call(&self) -> Option<ToolCall> {
+        if self.event_type != "tool_call" {
+            return None;
+        }
+        let params = serde_json::from_str(self.params_json.as_deref()?).ok()?;
+        Some(ToolCall {
+            tool: self.tool.clone()?,
+            params,
+            reasoning: self.reasoning.clone().unwrap_or_default(),
+        })
+    }
+}
+
+/// Result of walking the audit hash chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainStatus {
+    /// Hashed entries checked (up to the break, if any).
+    pub entries_checked: u64,
+    /// Entries written before hashing was introduced; not covered.
+    pub legacy_entries: u64,
+    /// Id of the first entry whose hash doesn't match, if any.
+    pub broken_at: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditSummary {
     pub total_events: u64,
@@ -45,7 +79,7 @@ impl AuditLogger {
         Self { db }
     }
 
-    /// Log a security-relevant event.
+    /// Log a security-relevant event.  Returns the new entry's id.
     pub async fn log(
         &self,
         event_type: &str,
@@ -57,11 +91,43 @@ impl AuditLogger {
         result: Option<&str>,
         success: Option<bool>,
         source: &str,
-    ) {
+    ) -> Option<i64> {
+        let created_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         let db = self.db.lock().await;
+
+        let prev_hash: Option<String> = match db
+            .query_row(
+                "SELECT entry_hash FROM audit_log ORDER BY id DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+        {
+            Ok(h) => h.flatten(),
+            Err(e) => {
+                error!("failed to read audit chain head: {e}");
+                return None;
+            }
+        };
+        let entry_hash = chain_hash(
+            prev_hash.as_deref().unwrap_or(""),
+            &[
+                Some(event_type),
+                tool,
+                action,
+                user_context,
+                reasoning,
+                params_json,
+                result,
+                success.map(|s| if s { "1" } else { "0" }),
+                Some(source),
+                Some(&created_at),
+            ],
+        );
+
         if let Err(e) = db.execute(
-            "INSERT INTO audit_log (event_type, tool, action, user_context, reasoning, params_json, result, success, source)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO audit_log (event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at, entry_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             rusqlite::params![
                 event_type,
                 tool,
@@ -72,10 +138,14 @@ impl AuditLogger {
                 result,
                 success,
                 source,
+                created_at,
+                entry_hash,
             ],
         ) {
             error!("failed to write audit log: {e}");
+            return None;
         }
+        Some(db.last_insert_rowid())
     }
 
     /// Convenience: log a tool execution.
@@ -88,7 +158,7 @@ impl AuditLogger {
         source: &str,
         reasoning: &str,
         user_context: &str,
-    ) {
+    ) -> Option<i64> {
         let params_str = serde_json::to_string(params).unwrap_or_default();
         self.log(
             "tool_call",
@@ -101,7 +171,35 @@ impl AuditLogger {
             Some(success),
             source,
         )
-        .await;
+        .await
+    }
+
+    /// Convenience: log a replay of the tool call recorded in entry
+    /// `original_id`.  Logged as a `tool_call` with action `replay`, and
+    /// the user context names the original entry.
+    pub async fn log_replay(
+        &self,
+        original_id: i64,
+        tool_name: &str,
+        params: &serde_json::Value,
+        result_preview: &str,
+        success: bool,
+        source: &str,
+    ) -> Option<i64> {
+        let params_str = serde_json::to_string(params).unwrap_or_default();
+        let context = format!("replay of audit entry #{original_id}");
+        self.log(
+            "tool_call",
+            Some(tool_name),
+            Some("replay"),
+            Some(&context),
+            None,
+            Some(&params_str),
+            Some(result_preview),
+            Some(success),
+            source,
+        )
+        .await
     }
 
     /// Convenience: log an approval decision.
@@ -111,7 +209,7 @@ impl AuditLogger {
         action: &str,
         reasoning: &str,
         source: &str,
-    ) {
+    ) -> Option<i64> {
         self.log(
             "approval",
             Some(tool_name),
@@ -123,11 +221,11 @@ impl AuditLogger {
             None,
             source,
         )
-        .await;
+        .await
     }
 
     /// Convenience: log a rate-limit event.
-    pub async fn log_rate_limit(&self, tool_name: &str, source: &str) {
+    pub async fn log_rate_limit(&self, tool_name: &str, source: &str) -> Option<i64> {
         self.log(
             "rate_limit",
             Some(tool_name),
@@ -139,11 +237,11 @@ impl AuditLogger {
             Some(false),
             source,
         )
-        .await;
+        .await
     }
 
     /// Convenience: log PII detection.
-    pub async fn log_pii_detected(&self, description: &str, action: &str, source: &str) {
+    pub async fn log_pii_detected(&self, description: &str, action: &str, source: &str) -> Option<i64> {
         self.log(
             "pii_detected",
             None,
@@ -155,11 +253,11 @@ impl AuditLogger {
             Some(false),
             source,
         )
-        .await;
+        .await
     }
 
     /// Convenience: log 2FA challenge.
-    pub async fn log_2fa(&self, tool_name: &str, action: &str, source: &str) {
+    pub async fn log_2fa(&self, tool_name: &str, action: &str, source: &str) -> Option<i64> {
         self.log(
             "2fa",
             Some(tool_name),
@@ -171,11 +269,11 @@ impl AuditLogger {
             None,
             source,
         )
-        .await;
+        .await
     }
 
     /// Convenience: log permission denied.
-    pub async fn log_permission_denied(&self, tool_name: &str, reason: &str, source: &str) {
+    pub async fn log_permission_denied(&self, tool_name: &str, reason: &str, source: &str) -> Option<i64> {
         self.log(
             "permission_denied",
             Some(tool_name),
@@ -187,61 +285,50 @@ impl AuditLogger {
             Some(false),
             source,
         )
-        .await;
+        .await
     }
 
-    /// Query recent audit entries with optional filtering.
+    /// Query recent audit entries with optional filtering, newest first.
+    ///
+    /// `before_id` is a keyset cursor: only entries with a smaller ID are
+    /// returned, so passing the last ID of one page fetches the next page
+    /// without skipping or repeating rows when new entries arrive.  It is
+    /// preferred over `offset`, which is kept for older clients.
     pub async fn recent(
         &self,
         limit: usize,
         offset: usize,
+        before_id: Option<i64>,
         event_type: Option<&str>,
         tool: Option<&str>,
     ) -> Vec<AuditEntry> {
         let db = self.db.lock().await;
 
-        let (sql, params_vec) = match (event_type, tool) {
-            (Some(et), Some(t)) => (
-                "SELECT id, event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at \
-                 FROM audit_log WHERE event_type = ?1 AND tool = ?2 ORDER BY id DESC LIMIT ?3 OFFSET ?4",
-                vec![
-                    Box::new(et.to_string()) as Box<dyn rusqlite::types::ToSql>,
-                    Box::new(t.to_string()),
-                    Box::new(limit as i64),
-                    Box::new(offset as i64),
-                ],
-            ),
-            (Some(et), None) => (
-                "SELECT id, event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at \
-                 FROM audit_log WHERE event_type = ?1 ORDER BY id DESC LIMIT ?2 OFFSET ?3",
-                vec![
-                    Box::new(et.to_string()) as Box<dyn rusqlite::types::ToSql>,
-                    Box::new(limit as i64),
-                    Box::new(offset as i64),
-                ],
-            ),
-            (None, Some(t)) => (
-                "SELECT id, event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at \
-                 FROM audit_log WHERE tool = ?1 ORDER BY id DESC LIMIT ?2 OFFSET ?3",
-                vec![
-                    Box::new(t.to_string()) as Box<dyn rusqlite::types::ToSql>,
-                    Box::new(limit as i64),
-                    Box::new(offset as i64),
-                ],
-            ),
-            (None, None) => (
-                "SELECT id, event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at \
-                 FROM audit_log ORDER BY id DESC LIMIT ?1 OFFSET ?2",
-                vec![
-                    Box::new(limit as i64) as Box<dyn rusqlite::types::ToSql>,
-                    Box::new(offset as i64),
-                ],
-            ),
-        };
+        let mut sql = String::from(
+            "SELECT id, event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at \
+             FROM audit_log WHERE id < ?1",
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::types::ToSql>> =
+            vec![Box::new(before_id.unwrap_or(i64::MAX))];
+        if let Some(et) = event_type {
+            params_vec.push(Box::new(et.to_string()));
+            sql.push_str(&format!(" AND event_type = ?{}", params_vec.len()));
+        }
+        if let Some(t) = tool {
+            params_vec.push(Box::new(t.to_string()));
+            sql.push_str(&format!(" AND tool = ?{}", params_vec.len()));
+        }
+        params_vec.push(Box::new(limit as i64));
+        params_vec.push(Box::new(offset as i64));
+        sql.push_str(&format!(
+            " ORDER BY id DESC LIMIT ?{} OFFSET ?{}",
+            params_vec.len() - 1,
+            params_vec.len()
+        ));
 
         let params_refs: Vec<&dyn rusqlite::types::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
 
-        let mut stmt = match db.prepare(sql) {
+        let mut stmt = match db.prepare(&sql) {
             Ok(s) => s,
             Err(e) => {
                 error!("audit query failed: {e}");
@@ -273,6 +360,22 @@ impl AuditLogger {
         }
     }
 
+    /// Fetch a single audit entry by ID.
+    pub async fn get(&self, id: i64) -> Option<AuditEntry> {
+        let db = self.db.lock().await;
+        db.query_row(
+            "SELECT id, event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at \
+             FROM audit_log WHERE id = ?1",
+            [id],
+            row_to_entry,
+        )
+        .optional()
+        .unwrap_or_else(|e| {
+            error!("audit lookup failed: {e}");
+            None
+        })
+    }
+
     /// Get aggregate statistics from the audit log.
     pub async fn summary(&self) -> AuditSummary {
         let db = self.db.lock().await;
@@ -317,6 +420,66 @@ impl AuditLogger {
         }
     }
 
+    /// Walk the whole log in id order and recompute the hash chain.
+    ///
+    /// Reports the first entry whose stored hash doesn't match — the row was
+    /// edited, or the row before it was deleted.  Rows from before hashing
+    /// was introduced are counted but not checked.  Truncating the newest
+    /// rows is not detectable from the log alone.
+    pub async fn verify_chain(&self) -> crate::error::Result<ChainStatus> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT id, event_type, tool, action, user_context, reasoning, params_json, result, success, source, created_at, entry_hash \
+             FROM audit_log ORDER BY id ASC",
+        )?;
+        let mut rows = stmt.query([])?;
+
+        let mut status = ChainStatus {
+            entries_checked: 0,
+            legacy_entries: 0,
+            broken_at: None,
+        };
+        let mut prev: Option<String> = None;
+
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let stored: Option<String> = row.get(11)?;
+            if stored.is_none() && prev.is_none() {
+                status.legacy_entries += 1;
+                continue;
+            }
+
+            let fields: [Option<String>; 7] = [
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+                row.get(7)?,
+            ];
+            let success: Option<bool> = row.get(8)?;
+            let source: String = row.get(9)?;
+            let created_at: String = row.get(10)?;
+
+            let mut parts: Vec<Option<&str>> = fields.iter().map(|f| f.as_deref()).collect();
+            parts.push(success.map(|s| if s { "1" } else { "0" }));
+            parts.push(Some(&source));
+            parts.push(Some(&created_at));
+            let expected = chain_hash(prev.as_deref().unwrap_or(""), &parts);
+
+            if stored.as_deref() != Some(expected.as_str()) {
+                warn!(id, "audit log hash chain broken");
+                status.broken_at = Some(id);
+                return Ok(status);
+            }
+            status.entries_checked += 1;
+            prev = stored;
+        }
+
+        Ok(status)
+    }
+
     /// Get reasoning chain for a specific tool call (last N audit entries
     /// that led to a given result). This powers the "explain" feature.
     pub async fn explain_action(&self, audit_id: i64) -> Vec<AuditEntry> {
@@ -336,22 +499,6 @@ impl AuditLogger {
             None => return Vec::new(),
         };
 
-        fn row_to_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<AuditEntry> {
-            Ok(AuditEntry {
-                id: row.get(0)?,
-                event_type: row.get(1)?,
-                tool: row.get(2)?,
-                action: row.get(3)?,
-                user_context: row.get(4)?,
-                reasoning: row.get(5)?,
-                params_json: row.get(6)?,
-                result: row.get(7)?,
-                success: row.get(8)?,
-                source: row.get(9)?,
-                created_at: row.get(10)?,
-            })
-        }
-
         let mut entries: Vec<AuditEntry> = Vec::new();
 
         if let Some(ref t) = tool {
@@ -383,6 +530,40 @@ impl AuditLogger {
     }
 }
 
+fn row_to_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<AuditEntry> {
+    Ok(AuditEntry {
+        id: row.get(0)?,
+        event_type: row.get(1)?,
+        tool: row.get(2)?,
+        action: row.get(3)?,
+        user_context: row.get(4)?,
+        reasoning: row.get(5)?,
+        params_json: row.get(6)?,
+        result: row.get(7)?,
+        success: row.get(8)?,
+        source: row.get(9)?,
+        created_at: row.get(10)?,
+    })
+}
+
+/// Hash one audit entry, chained to the previous entry's hash.  Fields are
+/// length-prefixed so shifting text between columns changes the digest.
+fn chain_hash(prev_hash: &str, fields: &[Option<&str>]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    for field in fields {
+        match field {
+            Some(v) => {
+                hasher.update([1u8]);
+                hasher.update((v.len() as u64).to_le_bytes());
+                hasher.update(v.as_bytes());
+            }
+            None => hasher.update([0u8]),
+        }
+    }
+    crate::crypto::hex_encode(&hasher.finalize())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -402,7 +583,7 @@ mod tests {
         logger.log_rate_limit("exec", "agent").await;
         logger.log_pii_detected("SSN found", "redact", "agent").await;
 
-        let entries = logger.recent(10, 0, None, None).await;
+        let entries = logger.recent(10, 0, None, None, None).await;
         assert_eq!(entries.len(), 3);
         assert_eq!(entries[0].event_type, "pii_detected");
         assert_eq!(entries[1].event_type, "rate_limit");
@@ -415,7 +596,7 @@ mod tests {
         logger.log_tool_call("exec", &serde_json::json!({}), "ok", true, "agent", "", "").await;
         logger.log_rate_limit("exec", "agent").await;
 
-        let entries = logger.recent(10, 0, Some("rate_limit"), None).await;
+        let entries = logger.recent(10, 0, None, Some("rate_limit"), None).await;
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].event_type, "rate_limit");
     }
@@ -426,11 +607,32 @@ mod tests {
         logger.log_tool_call("exec", &serde_json::json!({}), "ok", true, "agent", "", "").await;
         logger.log_tool_call("web_search", &serde_json::json!({}), "ok", true, "agent", "", "").await;
 
-        let entries = logger.recent(10, 0, None, Some("web_search")).await;
+        let entries = logger.recent(10, 0, None, None, Some("web_search")).await;
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].tool.as_deref(), Some("web_search"));
     }
 
+    #[tokio::test]
+    async fn cursor_pages_skip_rows_inserted_between_requests() {
+        let logger = make_logger().await;
+        for tool in ["a", "b", "c", "d", "e"] {
+            logger.log_tool_call(tool, &serde_json::json!({}), "ok", true, "agent", "", "").await;
+        }
+        let tools = |entries: &[AuditEntry]| -> Vec<String> {
+            entries.iter().map(|e| e.tool.clone().unwrap_or_default()).collect()
+        };
+
+        let first = logger.recent(2, 0, None, None, None).await;
+        assert_eq!(tools(&first), ["e", "d"]);
+
+        logger.log_tool_call("new", &serde_json::json!({}), "ok", true, "agent", "", "").await;
+
+        let second = logger.recent(2, 0, Some(first[1].id), None, None).await;
+        assert_eq!(tools(&second), ["c", "b"]);
+        let third = logger.recent(2, 0, Some(second[1].id), Some("tool_call"), None).await;
+        assert_eq!(tools(&third), ["a"]);
+    }
+
     #[tokio::test]
     async fn test_summary() {
         let logger = make_logger().await;
@@ -459,9 +661,74 @@ mod tests {
         let logger = make_logger().await;
         logger.log_tool_call("exec", &serde_json::json!({"cmd": "rm -rf /"}), "done", true, "agent", "delete all", "user said delete").await;
 
-        let entries = logger.recent(1, 0, None, None).await;
+        let entries = logger.recent(1, 0, None, None, None).await;
         let chain = logger.explain_action(entries[0].id).await;
         assert!(!chain.is_empty());
         assert_eq!(chain[0].reasoning.as_deref(), Some("delete all"));
     }
+
+    #[tokio::test]
+    async fn test_unmodified_chain_verifies() {
+        let logger = make_logger().await;
+        logger.log_tool_call("exec", &serde_json::json!({"cmd": "ls"}), "ok", true, "agent", "", "").await;
+        logger.log_rate_limit("exec", "agent").await;
+        logger.log_approval("exec", "approve", "ok", "dashboard").await;
+
+        let status = logger.verify_chain().await.unwrap();
+        assert_eq!(status.broken_at, None);
+        assert_eq!(status.entries_checked, 3);
+        assert_eq!(status.legacy_entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_row_breaks_chain() {
+        let logger = make_logger().await;
+        for i in 0..4 {
+            logger.log_2fa(&format!("tool{i}"), "challenge", "agent").await;
+        }
+        let ids: Vec<i64> = logger.recent(10, 0, None, None, None).await.iter().rev().map(|e| e.id).collect();
+
+        logger
+            .db
+            .lock()
+            .await
+            .execute("UPDATE audit_log SET action = 'confirmed' WHERE id = ?1", [ids[1]])
+            .unwrap();
+
+        let status = logger.verify_chain().await.unwrap();
+        assert_eq!(status.broken_at, Some(ids[1]));
+        assert_eq!(status.entries_checked, 1);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_row_breaks_chain() {
+        let logger = make_logger().await;
+        for i in 0..4 {
+            logger.log_2fa(&format!("tool{i}"), "challenge", "agent").await;
+        }
+        let ids: Vec<i64> = logger.recent(10, 0, None, None, None).await.iter().rev().map(|e| e.id).collect();
+
+        logger.db.lock().await.execute("DELETE FROM audit_log WHERE id = ?1", [ids[1]]).unwrap();
+
+        let status = logger.verify_chain().await.unwrap();
+        assert_eq!(status.broken_at, Some(ids[2]));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_rows_are_skipped() {
+        let logger = make_logger().await;
+        logger
+            .db
+            .lock()
+            .await
+            .execute("INSERT INTO audit_log (event_type, source) VALUES ('tool_call', 'agent')", [])
+            .unwrap();
+        logger.log_rate_limit("exec", "agent").await;
+        logger.log_rate_limit("exec", "agent").await;
+
+        let status = logger.verify_chain().await.unwrap();
+        assert_eq!(status.broken_at, None);
+        assert_eq!(status.legacy_entries, 1);
+        assert_eq!(status.entries_checked, 2);
+    }
 }
@@ -1,20 +1,26 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
 
 use tracing::warn;
 
-use crate::config::SecurityConfig;
+use crate::config::{PathPolicy, SecurityConfig};
 use crate::error::{Result, SafeAgentError};
+use super::exec_policy::ExecPolicy;
 
 /// Capability-based permission checker for tool execution.
 ///
 /// Instead of blanket tool approval, this checks fine-grained capabilities
 /// like "can read calendar but not write" or "can search web but not exec shell."
 pub struct CapabilityChecker {
-    /// Tools that are completely blocked.
-    blocked_tools: HashSet<String>,
+    /// Tools that are completely blocked.  Replaced on config reload.
+    blocked_tools: RwLock<HashSet<String>>,
     /// Per-tool capability restrictions. If a tool is listed here, only the
     /// specified operations are allowed.
     tool_capabilities: HashMap<String, HashSet<String>>,
+    /// Per-tool path globs (`*` = every tool).
+    path_policy: HashMap<String, PathPolicy>,
+    /// Binary allow/deny lists for `exec` commands.
+    exec_policy: ExecPolicy,
 }
 
 /// Result of a capability check.
@@ -30,6 +36,10 @@ pub enum CapabilityVerdict {
         operation: String,
         allowed: Vec<String>,
     },
+    /// A path parameter falls outside the tool's path policy.
+    PathDenied { tool: String, path: String },
+    /// An `exec` command launches a binary the exec policy refuses.
+    CommandDenied(String),
 }
 
 impl CapabilityChecker {
@@ -42,11 +52,18 @@ impl CapabilityChecker {
             .collect();
 
         Self {
-            blocked_tools,
+            blocked_tools: RwLock::new(blocked_tools),
             tool_capabilities,
+            path_policy: config.path_policy.clone(),
+            exec_policy: ExecPolicy::new(&config.exec_allow, &config.exec_deny),
         }
     }
 
+    /// Replace the blocked tool list (config reload).
+    pub fn set_blocked_tools(&self, tools: &[String]) {
+        *self.blocked_tools.write().unwrap() = tools.iter().cloned().collect();
+    }
+
     /// Check whether a tool call is permitted.
     ///
     /// `tool_name` is the tool being invoked.
@@ -54,9 +71,10 @@ impl CapabilityChecker {
     /// for tools that have capability restrictions.
     pub fn check(&self, tool_name: &str, params: &serde_json::Value) -> CapabilityVerdict {
         // Check if tool is entirely blocked
-        if self.blocked_tools.contains(tool_name) {
+        let gated = super::gate_name(tool_name, params);
+        if self.is_blocked(tool_name) || self.is_blocked(gated) {
             warn!(tool = %tool_name, "blocked tool invocation");
-            return CapabilityVerdict::Blocked(format!("tool '{tool_name}' is blocked by security policy"));
+            return CapabilityVerdict::Blocked(format!("tool '{gated}' is blocked by security policy"));
         }
 
         // Check fine-grained capabilities
@@ -77,9 +95,54 @@ impl CapabilityChecker {
             }
         }
 
+        if gated == "exec" && !self.exec_policy.is_empty() {
+            let command = params.get("command").and_then(|v| v.as_str()).unwrap_or("");
+            if let Err(reason) = self.exec_policy.check(command) {
+                warn!(command = %command, reason = %reason, "exec command denied");
+                return CapabilityVerdict::CommandDenied(format!("{tool_name}: {reason}"));
+            }
+        }
+
+        if let Some(path) = self.denied_path(tool_name, params) {
+            warn!(tool = %tool_name, path = %path, "path denied by policy");
+            return CapabilityVerdict::PathDenied {
+                tool: tool_name.to_string(),
+                path,
+            };
+        }
+
         CapabilityVerdict::Allowed
     }
 
+    /// The first `path`/`paths` parameter the tool's path policy (or the
+    /// `*` policy) refuses.  For `apply_patch`, the files named inside the
+    /// patch are checked too.
+    fn denied_path(&self, tool_name: &str, params: &serde_json::Value) -> Option<String> {
+        let policies: Vec<&PathPolicy> = [tool_name, "*"]
+            .iter()
+            .filter_map(|key| self.path_policy.get(*key))
+            .collect();
+        if policies.is_empty() {
+            return None;
+        }
+
+        let single = params.get("path").and_then(|v| v.as_str()).map(String::from);
+        let many = params
+            .get("paths")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(String::from));
+        let patched = patched_paths(tool_name, params);
+        single.into_iter().chain(many).chain(patched).find(|raw| {
+            let path = normalize(raw);
+            policies.iter().any(|p| {
+                p.deny.iter().any(|g| glob_covers(g, &path))
+                    || (!p.allow.is_empty() && !p.allow.iter().any(|g| glob_covers(g, &path)))
+            })
+        })
+    }
+
     /// Convert a negative verdict to an error result.
     pub fn check_or_error(&self, tool_name: &str, params: &serde_json::Value) -> Result<()> {
         match self.check(tool_name, params) {
@@ -95,12 +158,74 @@ impl CapabilityChecker {
                 "tool '{tool}' operation '{operation}' not allowed (permitted: {})",
                 allowed.join(", ")
             ))),
+            CapabilityVerdict::PathDenied { tool, path } => Err(SafeAgentError::PermissionDenied(format!(
+                "tool '{tool}' may not access '{path}' (path policy)"
+            ))),
+            CapabilityVerdict::CommandDenied(msg) => Err(SafeAgentError::PermissionDenied(msg)),
         }
     }
 
     /// Check if a tool is blocked entirely.
     pub fn is_blocked(&self, tool_name: &str) -> bool {
-        self.blocked_tools.contains(tool_name)
+        self.blocked_tools.read().unwrap().contains(tool_name)
+    }
+}
+
+/// Every file an `apply_patch` call reads or writes.  A patch that doesn't
+/// parse names none; the tool refuses it without touching anything.
+fn patched_paths(tool_name: &str, params: &serde_json::Value) -> Vec<String> {
+    if tool_name != "apply_patch" {
+        return Vec::new();
+    }
+    let patch = params.get("patch").and_then(|v| v.as_str()).unwrap_or_default();
+    crate::tools::patch::parse(patch)
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|file| [file.old_path, file.new_path])
+        .flatten()
+        .collect()
+}
+
+/// Lexically normalise a sandbox-relative path into its segments, so
+/// `./reports/../secrets/key` is checked as `secrets/key`.
+fn normalize(path: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    for seg in path.split(['/', '\\']) {
+        match seg {
+            "" | "." => {}
+            ".." => {
+                out.pop();
+            }
+            s => out.push(s),
+        }
+    }
+    out
+}
+
+/// Whether `pattern` matches `path` or one of its parent directories.
+fn glob_covers(pattern: &str, path: &[&str]) -> bool {
+    let pattern = normalize(pattern);
+    (1..=path.len()).any(|n| glob_match(&pattern, &path[..n]))
+}
+
+fn glob_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_match(rest, &path[skip..])),
+        Some((seg, rest)) => match path.split_first() {
+            Some((name, path_rest)) => segment_match(seg.as_bytes(), name.as_bytes()) && glob_match(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+/// `*` and `?` wildcards within one path segment.
+fn segment_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| segment_match(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && segment_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && segment_match(rest, &name[1..]),
     }
 }
 
@@ -126,6 +251,10 @@ fn infer_operation(tool_name: &str, params: &serde_json::Value) -> String {
         "read_file" => "read".to_string(),
         "write_file" | "edit_file" | "apply_patch" => "write".to_string(),
         "delete_file" => "delete".to_string(),
+        "structured_edit" => match params.get("action").and_then(|v| v.as_str()) {
+            Some("get") => "read".to_string(),
+            _ => "write".to_string(),
+        },
         "web_search" => "search".to_string(),
         "web_fetch" => "fetch".to_string(),
         "cron" => {
@@ -135,7 +264,7 @@ fn infer_operation(tool_name: &str, params: &serde_json::Value) -> String {
                 .unwrap_or("")
                 .to_string()
         }
-        "goal" => {
+        "goal" | "process" => {
             params
                 .get("action")
                 .and_then(|v| v.as_str())
@@ -191,6 +320,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn blocking_exec_blocks_process_start() {
+        let checker = CapabilityChecker::new(&make_config(vec!["exec"], vec![]));
+        let start = serde_json::json!({"action": "start", "command": "sleep 1"});
+        assert!(matches!(checker.check("process", &start), CapabilityVerdict::Blocked(_)));
+        assert_eq!(
+            checker.check("process", &serde_json::json!({"action": "list"})),
+            CapabilityVerdict::Allowed
+        );
+    }
+
     #[test]
     fn test_capability_allowed() {
         let config = make_config(vec![], vec![("exec", vec!["ls", "cat", "echo"])]);
@@ -239,6 +379,87 @@ mod tests {
         assert!(!checker.is_blocked("safe_tool"));
     }
 
+    fn path_config(tool: &str, allow: Vec<&str>, deny: Vec<&str>) -> SecurityConfig {
+        let policy = PathPolicy {
+            allow: allow.into_iter().map(String::from).collect(),
+            deny: deny.into_iter().map(String::from).collect(),
+        };
+        SecurityConfig {
+            path_policy: [(tool.to_string(), policy)].into_iter().collect(),
+            ..SecurityConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_denied_path_glob_blocks_read_while_sibling_passes() {
+        let checker = CapabilityChecker::new(&path_config("read_file", vec![], vec!["secrets/"]));
+        let read = |path: &str| checker.check_or_error("read_file", &serde_json::json!({ "path": path }));
+
+        assert!(read("reports/q3.md").is_ok());
+        let err = read("secrets/api.key").unwrap_err();
+        assert!(matches!(err, SafeAgentError::PermissionDenied(_)));
+        assert!(read("./reports/../secrets/api.key").is_err());
+        // Other tools are unaffected by the read_file policy.
+        assert!(checker.check_or_error("write_file", &serde_json::json!({ "path": "secrets/x" })).is_ok());
+    }
+
+    #[test]
+    fn apply_patch_is_checked_against_the_files_it_patches() {
+        let checker = CapabilityChecker::new(&path_config("apply_patch", vec![], vec!["secrets/"]));
+        let patch = |path: &str| {
+            let diff = format!("--- a/{path}\n+++ b/{path}\n@@ -1 +1 @@\n-old\n+new\n");
+            checker.check("apply_patch", &serde_json::json!({ "patch": diff }))
+        };
+
+        match patch("secrets/api.key") {
+            CapabilityVerdict::PathDenied { path, .. } => assert_eq!(path, "secrets/api.key"),
+            other => panic!("expected PathDenied, got {:?}", other),
+        }
+        assert_eq!(patch("notes/todo.md"), CapabilityVerdict::Allowed);
+    }
+
+    #[test]
+    fn test_allow_globs_restrict_to_matching_paths() {
+        let checker = CapabilityChecker::new(&path_config("*", vec!["reports/**/*.md", "notes"], vec!["**/draft-*"]));
+        let check = |params: serde_json::Value| checker.check("read_file", &params);
+
+        assert_eq!(check(serde_json::json!({ "path": "reports/2024/q3.md" })), CapabilityVerdict::Allowed);
+        assert_eq!(check(serde_json::json!({ "path": "notes/todo.txt" })), CapabilityVerdict::Allowed);
+        assert!(matches!(check(serde_json::json!({ "path": "reports/q3.csv" })), CapabilityVerdict::PathDenied { .. }));
+        assert!(matches!(check(serde_json::json!({ "path": "reports/draft-q4.md" })), CapabilityVerdict::PathDenied { .. }));
+        match check(serde_json::json!({ "paths": ["notes/a", "src/main.rs"] })) {
+            CapabilityVerdict::PathDenied { path, .. } => assert_eq!(path, "src/main.rs"),
+            other => panic!("expected PathDenied, got {:?}", other),
+        }
+        // Tools without path parameters pass.
+        assert_eq!(check(serde_json::json!({ "query": "x" })), CapabilityVerdict::Allowed);
+    }
+
+    #[test]
+    fn test_exec_binary_allow_and_deny_lists() {
+        let config = SecurityConfig {
+            exec_allow: vec!["git".into(), "ls".into(), "cat".into(), "bash".into()],
+            exec_deny: vec!["curl".into(), "nc".into(), "bash -c".into()],
+            ..SecurityConfig::default()
+        };
+        let checker = CapabilityChecker::new(&config);
+        let exec = |command: &str| checker.check_or_error("exec", &serde_json::json!({ "command": command }));
+
+        assert!(exec("git status").is_ok());
+        assert!(exec("/usr/bin/git log --oneline | cat").is_ok());
+        assert!(matches!(exec("curl https://example.com").unwrap_err(), SafeAgentError::PermissionDenied(_)));
+        assert!(exec(r#"bash -c "curl https://example.com""#).is_err());
+        assert!(exec("git status; '/usr/bin/curl' x").is_err());
+        assert!(exec("python3 -V").unwrap_err().to_string().contains("'python3' is not in the allowed list"));
+        // `bash script.sh` is allowed; only the `-c` form is denied.
+        assert!(exec("bash build.sh").is_ok());
+
+        let process = |params: serde_json::Value| checker.check_or_error("process", &params);
+        assert!(process(serde_json::json!({"action": "start", "command": "git fetch"})).is_ok());
+        assert!(process(serde_json::json!({"action": "start", "command": "nc -l 8080"})).is_err());
+        assert!(process(serde_json::json!({"action": "list"})).is_ok());
+    }
+
     #[test]
     fn test_check_or_error() {
         let config = make_config(vec!["blocked"], vec![]);
@@ -5,11 +5,27 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use tracing::error;
 
+use crate::error::{Result, SafeAgentError};
+
 /// Tracks LLM token usage and estimated costs per request.
 pub struct CostTracker {
     db: Arc<Mutex<Connection>>,
     /// Maximum daily spend in USD (0.0 = unlimited).
     daily_limit: f64,
+    /// Maximum daily spend per user in USD (0.0 = unlimited).
+    user_daily_limit: f64,
+}
+
+/// One LLM request to record.
+#[derive(Debug, Clone, Default)]
+pub struct Usage<'a> {
+    pub backend: &'a str,
+    pub model: &'a str,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub estimated_cost: f64,
+    /// What the request was for: message, goal_task, cron, follow_up.
+    pub context: &'a str,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,7 +63,74 @@ pub struct CostSummary {
 
 impl CostTracker {
     pub fn new(db: Arc<Mutex<Connection>>, daily_limit: f64) -> Self {
-        Self { db, daily_limit }
+        Self { db, daily_limit, user_daily_limit: 0.0 }
+    }
+
+    /// Cap each user's daily spend at `limit` USD (0.0 = unlimited).  The
+    /// global limit still applies on top.
+    pub fn with_user_daily_limit(mut self, limit: f64) -> Self {
+        self.user_daily_limit = limit;
+        self
+    }
+
+    /// Record one LLM request, attributed to `user_id` when it was made on
+    /// a user's behalf.
+    pub async fn record(&self, user_id: Option<&str>, usage: &Usage<'_>) -> Result<()> {
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT INTO llm_usage (user_id, backend, model, prompt_tokens, completion_tokens, total_tokens, estimated_cost, context) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                user_id,
+                usage.backend,
+                usage.model,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.prompt_tokens + usage.completion_tokens,
+                usage.estimated_cost,
+                usage.context,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Spend today in USD, for one user or (with `None`) overall.
+    pub async fn today_usd(&self, user_id: Option<&str>) -> f64 {
+        let db = self.db.lock().await;
+        db.query_row(
+            "SELECT COALESCE(SUM(estimated_cost), 0) FROM llm_usage \
+             WHERE date(created_at) = date('now') AND (?1 IS NULL OR user_id = ?1)",
+            [user_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0.0)
+    }
+
+    /// Refuse further LLM work once today's spend has reached the global
+    /// limit or, for a user, their own limit.
+    pub async fn check_budget(&self, user_id: Option<&str>) -> Result<()> {
+        if self.daily_limit > 0.0 {
+            let spent = self.today_usd(None).await;
+            if spent >= self.daily_limit {
+                return Err(SafeAgentError::QuotaExceeded(format!(
+                    "daily LLM budget of ${:.2} reached (spent ${spent:.2} today); try again tomorrow",
+                    self.daily_limit
+                )));
+            }
+        }
+        match user_id {
+            Some(user_id) if self.user_daily_limit > 0.0 => {
+                let spent = self.today_usd(Some(user_id)).await;
+                if spent >= self.user_daily_limit {
+                    return Err(SafeAgentError::QuotaExceeded(format!(
+                        "your daily LLM budget of ${:.2} is used up (spent ${spent:.2} today); try again tomorrow",
+                        self.user_daily_limit
+                    )));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Get a cost summary for the dashboard.
@@ -151,3 +234,49 @@ impl CostTracker {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(daily_limit: f64, user_daily_limit: f64) -> CostTracker {
+        CostTracker::new(crate::db::test_db(), daily_limit).with_user_daily_limit(user_daily_limit)
+    }
+
+    fn usage(cost: f64) -> Usage<'static> {
+        Usage {
+            backend: "openrouter",
+            prompt_tokens: 100,
+            completion_tokens: 50,
+            estimated_cost: cost,
+            context: "message",
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn user_over_their_cap_is_blocked_while_others_proceed() {
+        let costs = tracker(0.0, 1.0);
+        costs.record(Some("alice"), &usage(0.6)).await.unwrap();
+        costs.record(Some("alice"), &usage(0.5)).await.unwrap();
+        costs.record(Some("bob"), &usage(0.2)).await.unwrap();
+
+        let err = costs.check_budget(Some("alice")).await.unwrap_err();
+        assert!(matches!(err, SafeAgentError::QuotaExceeded(_)));
+        assert!(err.to_string().contains("your daily LLM budget of $1.00"));
+        costs.check_budget(Some("bob")).await.unwrap();
+        costs.check_budget(None).await.unwrap();
+        assert!((costs.today_usd(Some("alice")).await - 1.1).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn global_cap_applies_on_top_of_user_caps() {
+        let costs = tracker(1.0, 5.0);
+        costs.record(Some("alice"), &usage(0.7)).await.unwrap();
+        costs.record(None, &usage(0.4)).await.unwrap();
+
+        let err = costs.check_budget(Some("bob")).await.unwrap_err();
+        assert!(err.to_string().contains("daily LLM budget of $1.00 reached"));
+        assert!(costs.check_budget(None).await.is_err());
+    }
+}
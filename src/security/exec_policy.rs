@@ -0,0 +1,445 @@
+//! Binary allow/deny lists for the `exec` tool.
+//!
+//! `exec` hands its command to `sh -c`, so checking the first word is not
+//! enough: `ls; curl ...`, `env curl ...` and `bash -c "curl ..."` all run
+//! something else.  [`ExecPolicy::check`] splits the script into simple
+//! commands the way the shell would, looks through common wrappers and
+//! nested `sh -c` scripts, and validates every binary it finds.  Command
+//! substitution can't be analysed statically and is refused outright, as
+//! are wrapper options the policy doesn't know how to skip.
+
+/// A program that runs its arguments as another command, and how to find
+/// that command among its options.
+struct Wrapper {
+    name: &'static str,
+    /// Short options without an argument.
+    flags: &'static str,
+    /// Short options taking an argument, attached (`-oL`) or the next word.
+    short_args: &'static str,
+    /// Short options whose argument can only be attached (`xargs -i{}`).
+    attached_args: &'static str,
+    /// Long options without an argument (`--foo=bar` forms are always fine).
+    long_flags: &'static [&'static str],
+    /// Long options taking the next word as their argument.
+    long_args: &'static [&'static str],
+    /// Words after the options that come before the command (`timeout 5`).
+    positional: usize,
+}
+
+impl Wrapper {
+    const fn new(name: &'static str) -> Self {
+        Self { name, flags: "", short_args: "", attached_args: "", long_flags: &[], long_args: &[], positional: 0 }
+    }
+}
+
+/// Programs that run their arguments as another command.  `env -S` and
+/// other options not listed here make the check fail closed.
+const WRAPPERS: &[Wrapper] = &[
+    Wrapper::new("builtin"),
+    Wrapper { flags: "pvV", ..Wrapper::new("command") },
+    Wrapper {
+        flags: "iv0",
+        short_args: "uC",
+        long_flags: &["ignore-environment", "null", "debug"],
+        long_args: &["unset", "chdir"],
+        ..Wrapper::new("env")
+    },
+    Wrapper { flags: "cl", short_args: "a", ..Wrapper::new("exec") },
+    Wrapper {
+        flags: "t",
+        short_args: "cnpPu",
+        long_flags: &["ignore"],
+        long_args: &["class", "classdata", "pid", "pgid", "uid"],
+        ..Wrapper::new("ionice")
+    },
+    Wrapper { short_args: "n", long_args: &["adjustment"], ..Wrapper::new("nice") },
+    Wrapper::new("nohup"),
+    Wrapper { flags: "cfw", long_flags: &["ctty", "fork", "wait"], ..Wrapper::new("setsid") },
+    Wrapper { short_args: "ioe", long_args: &["input", "output", "error"], ..Wrapper::new("stdbuf") },
+    Wrapper {
+        flags: "AbEeHiKklnPSsVv",
+        short_args: "CDghpRrTtUu",
+        long_flags: &[
+            "askpass", "background", "preserve-env", "edit", "set-home", "login", "remove-timestamp",
+            "reset-timestamp", "list", "non-interactive", "preserve-groups", "stdin", "shell", "validate",
+        ],
+        long_args: &[
+            "close-from", "chdir", "group", "host", "prompt", "chroot", "role", "type", "command-timeout",
+            "other-user", "user",
+        ],
+        ..Wrapper::new("sudo")
+    },
+    Wrapper {
+        flags: "apqv",
+        short_args: "fo",
+        long_flags: &["append", "portability", "quiet", "verbose"],
+        long_args: &["format", "output"],
+        ..Wrapper::new("time")
+    },
+    Wrapper {
+        flags: "v",
+        short_args: "sk",
+        long_flags: &["preserve-status", "foreground", "verbose"],
+        long_args: &["signal", "kill-after"],
+        positional: 1,
+        ..Wrapper::new("timeout")
+    },
+    Wrapper {
+        flags: "0prtx",
+        short_args: "aEdILnPs",
+        attached_args: "eil",
+        long_flags: &["null", "interactive", "no-run-if-empty", "verbose", "exit", "show-limits", "open-tty"],
+        long_args: &["arg-file", "delimiter", "max-lines", "max-args", "max-procs", "max-chars", "process-slot-var"],
+        ..Wrapper::new("xargs")
+    },
+];
+
+/// Shell keywords that may precede a command (`if curl x`, `! curl x`,
+/// `{ curl x; }`) and are skipped to reach it.
+const RESERVED_PREFIXES: &[&str] = &[
+    "!", "{", "}", "coproc", "do", "done", "elif", "else", "esac", "fi", "if", "then", "until", "while",
+];
+
+/// Keywords that start a clause header with no command in it: the word
+/// lists of `for`/`select`/`case`, `[[` tests and `function` headers.  A
+/// body that follows on the same segment (`function f { curl x`, `for f do
+/// curl x`) is still checked.  `case` patterns end at `)`, which already
+/// starts a new segment.
+const CLAUSES: &[&str] = &["[[", "case", "for", "function", "select"];
+
+/// Words that end a clause header and start its body.
+const CLAUSE_BODIES: &[&str] = &["{", "do"];
+
+/// Shells whose `-c` argument is itself a script.
+const SHELLS: &[&str] = &["ash", "bash", "dash", "fish", "ksh", "sh", "zsh"];
+
+#[derive(Debug, Clone, Default)]
+pub struct ExecPolicy {
+    /// Binaries `exec` may launch (empty = any not denied).
+    allow: Vec<String>,
+    /// Denied binaries, or binary-plus-arguments prefixes like `bash -c`.
+    deny: Vec<Vec<String>>,
+}
+
+impl ExecPolicy {
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        Self {
+            allow: allow.iter().map(|b| basename(b.trim()).to_string()).collect(),
+            deny: deny
+                .iter()
+                .map(|d| d.split_whitespace().map(String::from).collect::<Vec<_>>())
+                .filter(|d| !d.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Validate every command in `script`.  The error names what was
+    /// refused.
+    pub fn check(&self, script: &str) -> std::result::Result<(), String> {
+        for words in split_commands(script)? {
+            self.check_command(&words)?;
+        }
+        Ok(())
+    }
+
+    fn check_command(&self, words: &[String]) -> std::result::Result<(), String> {
+        let Some(start) = words.iter().position(|w| !is_assignment(w) && !RESERVED_PREFIXES.contains(&w.as_str()))
+        else {
+            return Ok(());
+        };
+        let words = &words[start..];
+        if CLAUSES.contains(&words[0].as_str()) {
+            return match words.iter().position(|w| CLAUSE_BODIES.contains(&w.as_str())) {
+                Some(body) => self.check_command(&words[body..]),
+                None => Ok(()),
+            };
+        }
+        // `$X -c ...` runs whatever X holds, which can't be known here.
+        if words[0].contains(['$', '`']) {
+            return Err(format!("'{}' is not a fixed command name", words[0]));
+        }
+        let binary = basename(&words[0]);
+        let args = &words[1..];
+
+        if let Some(entry) = self.deny.iter().find(|d| {
+            basename(&d[0]) == binary && d[1..].iter().zip(args).all(|(a, b)| a == b) && d.len() <= words.len()
+        }) {
+            return Err(format!("'{}' is denied", entry.join(" ")));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|a| a == binary) {
+            return Err(format!("'{binary}' is not in the allowed list"));
+        }
+
+        if let Some(wrapper) = WRAPPERS.iter().find(|w| w.name == binary) {
+            return self.check_command(wrapped_command(wrapper, args)?);
+        }
+        if binary == "eval" {
+            return self.check(&args.join(" "));
+        }
+        if SHELLS.contains(&binary) {
+            let flag = args.iter().position(|a| a.starts_with('-') && !a.starts_with("--") && a.contains('c'));
+            if let Some(script) = flag.and_then(|i| args.get(i + 1)) {
+                return self.check(script);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split a shell script into simple commands on `;`, `&`, `|`, newlines
+/// and parentheses, honouring quotes and backslash escapes.  Redirection
+/// targets are dropped.
+fn split_commands(script: &str) -> std::result::Result<Vec<Vec<String>>, String> {
+    const SUBSTITUTION: &str = "command substitution is not allowed under an exec policy";
+
+    let mut commands = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word: Option<String> = None;
+    let mut redirect = false;
+    let mut chars = script.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => end_word(&mut word, &mut words, &mut redirect),
+            '\n' | ';' | '&' | '|' | '(' | ')' => {
+                end_word(&mut word, &mut words, &mut redirect);
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            '<' | '>' => {
+                if chars.peek() == Some(&'(') {
+                    return Err(SUBSTITUTION.into());
+                }
+                // A bare fd number (`2>`) belongs to the redirection.
+                if word.as_deref().is_some_and(|w| w.chars().all(|c| c.is_ascii_digit())) {
+                    word = None;
+                }
+                end_word(&mut word, &mut words, &mut redirect);
+                while chars.next_if(|c| matches!(c, '<' | '>' | '&')).is_some() {}
+                redirect = true;
+            }
+            '`' => return Err(SUBSTITUTION.into()),
+            '$' if chars.peek() == Some(&'(') => return Err(SUBSTITUTION.into()),
+            '\'' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => w.push(c),
+                        None => return Err("unterminated single quote".into()),
+                    }
+                }
+            }
+            '"' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => w.push(c),
+                            Some(c) => {
+                                w.push('\\');
+                                w.push(c);
+                            }
+                            None => return Err("unterminated double quote".into()),
+                        },
+                        Some('`') => return Err(SUBSTITUTION.into()),
+                        Some('$') if chars.peek() == Some(&'(') => return Err(SUBSTITUTION.into()),
+                        Some(c) => w.push(c),
+                        None => return Err("unterminated double quote".into()),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next().filter(|c| *c != '\n') {
+                    word.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    end_word(&mut word, &mut words, &mut redirect);
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    Ok(commands)
+}
+
+fn end_word(word: &mut Option<String>, words: &mut Vec<String>, redirect: &mut bool) {
+    if let Some(w) = word.take() {
+        if *redirect {
+            *redirect = false;
+        } else {
+            words.push(w);
+        }
+    }
+}
+
+/// `/usr/bin/curl` → `curl`.
+fn basename(word: &str) -> &str {
+    word.rsplit('/').next().unwrap_or(word)
+}
+
+/// `FOO=bar` prefix assignments.
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// The words `wrapper` runs as a command, after its own options and their
+/// arguments (`timeout -s KILL 5`, `env -u FOO BAR=1`).  Options the table
+/// doesn't know are refused rather than guessed at.
+fn wrapped_command<'a>(wrapper: &Wrapper, args: &'a [String]) -> std::result::Result<&'a [String], String> {
+    let unknown = |opt: &str| format!("unrecognised option '{opt}' to '{}' under an exec policy", wrapper.name);
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        i += 1;
+        if arg == "--" {
+            break;
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            if long.contains('=') {
+                let name = long.split('=').next().unwrap_or_default();
+                if !wrapper.long_flags.contains(&name) && !wrapper.long_args.contains(&name) {
+                    return Err(unknown(arg));
+                }
+            } else if wrapper.long_args.contains(&long) {
+                i += 1;
+            } else if !wrapper.long_flags.contains(&long) {
+                return Err(unknown(arg));
+            }
+            continue;
+        }
+        // `env -` is `env -i`
+        if arg == "-" && wrapper.name == "env" {
+            continue;
+        }
+        let Some(cluster) = arg.strip_prefix('-').filter(|c| !c.is_empty()) else {
+            i -= 1;
+            break;
+        };
+        // `nice -10`
+        if wrapper.name == "nice" && cluster.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        for (at, c) in cluster.char_indices() {
+            if wrapper.short_args.contains(c) {
+                if at + c.len_utf8() == cluster.len() {
+                    i += 1;
+                }
+                break;
+            }
+            if wrapper.attached_args.contains(c) {
+                break;
+            }
+            if !wrapper.flags.contains(c) {
+                return Err(unknown(arg));
+            }
+        }
+    }
+    i += wrapper.positional;
+    // `env FOO=1 cmd`, `sudo FOO=1 cmd`
+    while args.get(i).is_some_and(|a| is_assignment(a)) {
+        i += 1;
+    }
+    Ok(args.get(i..).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(script: &str) -> Vec<Vec<String>> {
+        split_commands(script).unwrap()
+    }
+
+    #[test]
+    fn splits_on_operators_and_honours_quotes() {
+        assert_eq!(
+            words(r#"git log --format="%h %s" | head -5 && echo 'a;b' > out.txt 2>&1"#),
+            vec![
+                vec!["git", "log", "--format=%h %s"],
+                vec!["head", "-5"],
+                vec!["echo", "a;b"],
+            ]
+        );
+        assert_eq!(words("c\\url x"), vec![vec!["curl", "x"]]);
+        assert!(split_commands("echo $(curl x)").is_err());
+        assert!(split_commands("echo `id`").is_err());
+        assert!(split_commands("echo 'open").is_err());
+    }
+
+    #[test]
+    fn wrappers_and_absolute_paths_are_seen_through() {
+        let policy = ExecPolicy::new(&[], &["curl".to_string()]);
+        assert!(policy.check("/usr/bin/curl https://x").is_err());
+        assert!(policy.check("FOO=1 env -i BAR=2 timeout 5 curl x").is_err());
+        assert!(policy.check("ls | xargs curl").is_err());
+        assert!(policy.check("eval 'curl x'").is_err());
+        assert!(policy.check("sh -ec 'ls; curl x'").is_err());
+        assert!(policy.check("ls -la /tmp").is_ok());
+    }
+
+    #[test]
+    fn reserved_words_do_not_hide_commands() {
+        let policy = ExecPolicy::new(&[], &["curl".to_string()]);
+        assert!(policy.check("if curl x; then :; fi").is_err());
+        assert!(policy.check("if true; then curl x; fi").is_err());
+        assert!(policy.check("! curl x").is_err());
+        assert!(policy.check("{ curl x; }").is_err());
+        assert!(policy.check("(curl x)").is_err());
+        assert!(policy.check("while curl x; do :; done").is_err());
+        assert!(policy.check("for f in a b; do curl $f; done").is_err());
+        assert!(policy.check("for f in curl; do echo $f; done").is_ok());
+
+        // Clause headers are skipped, not their bodies
+        assert!(policy.check("function f { curl evil; }; f").is_err());
+        assert!(policy.check("function f { ls; }; f").is_ok());
+        assert!(policy.check("for f do curl $f; done").is_err());
+
+        // Keywords aren't binaries an allow list has to name
+        let allow = ExecPolicy::new(&["ls".to_string(), "true".to_string()], &[]);
+        assert!(allow.check("if true; then ls; fi").is_ok());
+        assert!(allow.check("{ ls; } && ! true").is_ok());
+    }
+
+    #[test]
+    fn variable_command_words_fail_closed() {
+        let policy = ExecPolicy::new(&[], &["curl".to_string()]);
+        assert!(policy.check("$X -c 'curl x'").is_err());
+        assert!(policy.check("X=curl; ${X} x").is_err());
+        assert!(policy.check("env $X x").is_err());
+        assert!(policy.check("echo $HOME").is_ok());
+    }
+
+    #[test]
+    fn wrapper_option_arguments_are_skipped() {
+        let policy = ExecPolicy::new(&[], &["curl".to_string()]);
+        assert!(policy.check("timeout -s KILL 5 curl x").is_err());
+        assert!(policy.check("timeout --signal KILL --kill-after=2 5 curl x").is_err());
+        assert!(policy.check("env -u FOO curl x").is_err());
+        assert!(policy.check("env -i -u FOO BAR=1 curl x").is_err());
+        assert!(policy.check("env - curl x").is_err());
+        assert!(policy.check("stdbuf -o L curl x").is_err());
+        assert!(policy.check("stdbuf -oL -e0 curl x").is_err());
+        assert!(policy.check("nice -n 10 curl x").is_err());
+        assert!(policy.check("nice -10 curl x").is_err());
+        assert!(policy.check("sudo -u root curl x").is_err());
+        assert!(policy.check("xargs -n 1 -I {} curl {}").is_err());
+        assert!(policy.check("exec -a name curl x").is_err());
+        assert!(policy.check("time -f %e curl x").is_err());
+        assert!(policy.check("timeout -s KILL 5 ls").is_ok());
+        assert!(policy.check("stdbuf -o L ls").is_ok());
+
+        // Options the policy can't parse fail closed
+        let err = policy.check("env -S 'curl x'").unwrap_err();
+        assert!(err.contains("unrecognised option"), "{err}");
+        assert!(policy.check("timeout --bogus 5 ls").is_err());
+    }
+}
@@ -1,25 +1,53 @@
 pub mod audit;
 pub mod capabilities;
 pub mod cost_tracker;
+pub mod exec_policy;
 pub mod pii;
 pub mod rate_limiter;
+pub mod secrets;
 pub mod twofa;
 
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use reqwest::Url;
 use tracing::{info, warn};
 
 use crate::error::{Result, SafeAgentError};
 
+/// The tool name the blocked-tool, 2FA and auto-approve gates apply to a
+/// call.  `process start` launches a shell command just like `exec`, so it
+/// answers to whatever governs `exec`.
+pub fn gate_name<'a>(tool: &'a str, params: &serde_json::Value) -> &'a str {
+    if tool == "process" && params.get("action").and_then(|v| v.as_str()) == Some("start") {
+        "exec"
+    } else {
+        tool
+    }
+}
+
 // ===========================================================================
 // SandboxedFs — path-jailed filesystem access
 // ===========================================================================
 
+/// How long quota checks trust the cached usage before walking the root
+/// again, to pick up files written by skills and subprocesses.
+const USAGE_RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The database lives in the data directory but is not sandbox content;
+/// it and its WAL/SHM/journal siblings don't count toward the quota.
+const DB_FILE_PREFIX: &str = "safeclaw.db";
+
 /// Sandboxed filesystem — all file I/O is confined to the data directory.
 #[derive(Debug, Clone)]
 pub struct SandboxedFs {
     root: PathBuf,
+    /// Most bytes [`SandboxedFs::write`] lets the root hold; 0 = unlimited.
+    quota_bytes: u64,
+    /// Bytes under the root as of the last walk, plus every quota-checked
+    /// write since; shared by clones.  `None` until the first write.
+    usage_cache: Arc<Mutex<Option<(u64, Instant)>>>,
 }
 
 impl SandboxedFs {
@@ -28,7 +56,19 @@ impl SandboxedFs {
         let root = root
             .canonicalize()
             .map_err(|e| SafeAgentError::SandboxViolation(format!("cannot canonicalize root: {e}")))?;
-        Ok(Self { root })
+        Ok(Self { root, quota_bytes: 0, usage_cache: Arc::default() })
+    }
+
+    /// Cap the total size of everything under the root (0 = unlimited).
+    pub fn with_quota(mut self, quota_bytes: u64) -> Self {
+        self.quota_bytes = quota_bytes;
+        self
+    }
+
+    /// Total bytes of the regular files under the root, not counting the
+    /// database.  Symlinks are not followed.
+    pub fn usage(&self) -> Result<u64> {
+        Ok(dir_size(&self.root, true)?)
     }
 
     /// Resolve a relative path within the sandbox. Rejects any path that escapes.
@@ -39,6 +79,10 @@ impl SandboxedFs {
             ));
         }
 
+        // Vet the existing part of the path before creating any directories,
+        // so a planted symlink can't make us create them outside the root.
+        self.check_components(relative)?;
+
         let candidate = self.root.join(relative);
 
         // Create parent dirs so canonicalize works on new files
@@ -78,13 +122,88 @@ impl SandboxedFs {
         ))
     }
 
+    /// Walk `relative` from the root and reject it if any existing component
+    /// resolves outside the root — e.g. a symlinked directory planted by a
+    /// skill — or is a dangling symlink whose target can't be checked (and
+    /// which a write would otherwise follow).  Components after the first
+    /// missing one will be created fresh, so they must be plain names.
+    fn check_components(&self, relative: &Path) -> Result<()> {
+        let escape = || {
+            SafeAgentError::SandboxViolation(format!("path escapes sandbox: {}", relative.display()))
+        };
+        let mut current = self.root.clone();
+        let mut missing = false;
+        for component in relative.components() {
+            if missing {
+                if !matches!(component, Component::Normal(_) | Component::CurDir) {
+                    return Err(escape());
+                }
+                continue;
+            }
+            current.push(component);
+            if std::fs::symlink_metadata(&current).is_err() {
+                missing = true;
+                continue;
+            }
+            let canonical = current.canonicalize().map_err(|_| {
+                SafeAgentError::SandboxViolation(format!(
+                    "dangling symlink in path: {}",
+                    relative.display()
+                ))
+            })?;
+            if !canonical.starts_with(&self.root) {
+                return Err(escape());
+            }
+        }
+        Ok(())
+    }
+
     pub fn root(&self) -> &Path {
         &self.root
     }
 
+    /// Write `data` to `relative`, replacing the file if it exists.  With a
+    /// quota set, writes that would push the root past it are rejected;
+    /// overwriting a file only counts the difference in size.  Usage is
+    /// cached and updated per write rather than walked each time, and
+    /// re-walked every [`USAGE_RESCAN_INTERVAL`].
+    ///
+    /// The data goes to a temporary file in the same directory, is synced,
+    /// and then renamed over the target, so readers and crashes see either
+    /// the old contents or the new ones, never a partial file.
     pub fn write(&self, relative: &Path, data: &[u8]) -> Result<()> {
         let path = self.resolve(relative)?;
-        Ok(std::fs::write(path, data)?)
+        if self.quota_bytes == 0 {
+            return Ok(write_atomic(&path, data)?);
+        }
+
+        // Held through the write so concurrent writers can't both fit.
+        let mut cache = self.usage_cache.lock().unwrap_or_else(|e| e.into_inner());
+        let usage = match *cache {
+            Some((bytes, scanned)) if scanned.elapsed() < USAGE_RESCAN_INTERVAL => bytes,
+            _ => {
+                let bytes = self.usage()?;
+                *cache = Some((bytes, Instant::now()));
+                bytes
+            }
+        };
+        let existing = std::fs::symlink_metadata(&path)
+            .map(|m| if m.is_file() { m.len() } else { 0 })
+            .unwrap_or(0);
+        let after = usage.saturating_sub(existing) + data.len() as u64;
+        if after > self.quota_bytes {
+            return Err(SafeAgentError::QuotaExceeded(format!(
+                "sandbox: writing {} bytes to {} would use {after} of {} bytes",
+                data.len(),
+                relative.display(),
+                self.quota_bytes
+            )));
+        }
+        write_atomic(&path, data)?;
+        if let Some((bytes, _)) = cache.as_mut() {
+            *bytes = after;
+        }
+        Ok(())
     }
 
     pub fn read_to_string(&self, relative: &Path) -> Result<String> {
@@ -94,6 +213,48 @@ impl SandboxedFs {
 
 }
 
+/// Write `data` to a temporary sibling of `path`, fsync it and rename it
+/// into place.  An existing file's permissions carry over.
+fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = dir.join(format!(".{name}.{}.tmp", uuid::Uuid::new_v4().simple()));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        if let Ok(meta) = std::fs::metadata(path) {
+            file.set_permissions(meta.permissions())?;
+        }
+        file.write_all(data)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Recursive size of the regular files under `dir`, skipping the database
+/// files when `dir` is the root.  Entries that vanish mid-walk are skipped.
+fn dir_size(dir: &Path, is_root: bool) -> std::io::Result<u64> {
+    let mut total = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let Ok(entry) = entry else { continue };
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            total += dir_size(&entry.path(), false).unwrap_or(0);
+        } else if is_root && entry.file_name().to_string_lossy().starts_with(DB_FILE_PREFIX) {
+            continue;
+        } else if meta.is_file() {
+            total += meta.len();
+        }
+    }
+    Ok(total)
+}
+
 // ===========================================================================
 // PathJail — validates arbitrary paths are inside an allowed directory
 // ===========================================================================
@@ -321,6 +482,118 @@ pub fn validate_sql_readonly(sql: &str) -> std::result::Result<(), String> {
 // Environment variable allowlist for Rhai
 // ===========================================================================
 
+/// Environment variables that always hold secrets.
+const SECRET_ENV_VARS: &[&str] = &[
+    "TELEGRAM_BOT_TOKEN",
+    "JWT_SECRET",
+    "DASHBOARD_PASSWORD",
+    "OPENROUTER_API_KEY",
+    "OPENAI_API_KEY",
+    "ANTHROPIC_API_KEY",
+    "DATABASE_URL",
+    "AWS_SECRET_ACCESS_KEY",
+    "AWS_SESSION_TOKEN",
+    "GITHUB_TOKEN",
+    "GH_TOKEN",
+];
+
+/// Shortest environment value [`redact_secrets`] will look for, so short
+/// values like `1` or `true` don't blank out unrelated text.
+const MIN_REDACTED_VALUE_LEN: usize = 8;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Whether an environment variable name looks like it holds a secret.
+pub fn is_secret_env_name(key: &str) -> bool {
+    if SECRET_ENV_VARS.iter().any(|b| key.eq_ignore_ascii_case(b)) {
+        return true;
+    }
+
+    // Anything containing SECRET, TOKEN, PASSWORD, KEY (case-insensitive)
+    let upper = key.to_uppercase();
+    upper.contains("SECRET")
+        || upper.contains("TOKEN")
+        || upper.contains("PASSWORD")
+        || upper.contains("_KEY")
+        || upper.contains("CREDENTIAL")
+        || upper.contains("AUTH")
+}
+
+/// Redact secrets from text before it is shown to a user.
+///
+/// Replaces the values of secret-looking process environment variables
+/// wherever they appear, and the value of any `NAME=value` or
+/// `NAME: value` pair whose name matches [`is_secret_env_name`].
+pub fn redact_secrets(text: &str) -> String {
+    redact_secret_assignments(&redact_secret_values(text))
+}
+
+/// Replace only the values of secret-named environment variables, leaving
+/// `NAME=value` shapes alone — for text such as a system prompt, whose tool
+/// schemas are full of `"token": {...}` keys that are not secrets.
+pub fn redact_secret_values(text: &str) -> String {
+    let mut out = text.to_string();
+    for (name, value) in std::env::vars() {
+        if value.len() >= MIN_REDACTED_VALUE_LEN && is_secret_env_name(&name) {
+            out = out.replace(&value, REDACTED);
+        }
+    }
+    out
+}
+
+/// [`redact_secrets`] for structured data such as tool parameters: every
+/// string is redacted, and so is the whole value of any key that names a
+/// secret.
+pub fn redact_secrets_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => *text = redact_secrets(text),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets_json),
+        serde_json::Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                if is_secret_env_name(key) {
+                    *item = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets_json(item);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn redact_secret_assignments(text: &str) -> String {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(is_name_char) {
+        out.push_str(&rest[..start]);
+        let name_len = rest[start..].find(|c: char| !is_name_char(c)).unwrap_or(rest.len() - start);
+        let (name, after) = rest[start..].split_at(name_len);
+        out.push_str(name);
+        rest = after;
+        if !is_secret_env_name(name) {
+            continue;
+        }
+
+        // Allow a closing quote (JSON keys) and spaces around the separator.
+        let Some(value) = after.trim_start_matches(['"', '\'', ' ']).strip_prefix(['=', ':']) else {
+            continue;
+        };
+        let value = value.trim_start_matches(['"', '\'', ' ']);
+        let value_len = value
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | ';'))
+            .unwrap_or(value.len());
+        if value_len == 0 {
+            continue;
+        }
+        out.push_str(&after[..after.len() - value.len()]);
+        out.push_str(REDACTED);
+        rest = &value[value_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Check whether an environment variable name is safe to read from Rhai.
 pub fn is_safe_env_var(key: &str) -> bool {
     let allowed_prefixes = [
@@ -340,33 +613,7 @@ pub fn is_safe_env_var(key: &str) -> bool {
         "PYENV_",
     ];
 
-    let blocked_exact = [
-        "TELEGRAM_BOT_TOKEN",
-        "JWT_SECRET",
-        "DASHBOARD_PASSWORD",
-        "OPENROUTER_API_KEY",
-        "ANTHROPIC_API_KEY",
-        "DATABASE_URL",
-        "AWS_SECRET_ACCESS_KEY",
-        "AWS_SESSION_TOKEN",
-        "GITHUB_TOKEN",
-        "GH_TOKEN",
-    ];
-
-    // Exact-match blocks take precedence
-    if blocked_exact.iter().any(|b| key.eq_ignore_ascii_case(b)) {
-        return false;
-    }
-
-    // Block anything containing SECRET, TOKEN, PASSWORD, KEY (case-insensitive)
-    let upper = key.to_uppercase();
-    if upper.contains("SECRET")
-        || upper.contains("TOKEN")
-        || upper.contains("PASSWORD")
-        || upper.contains("_KEY")
-        || upper.contains("CREDENTIAL")
-        || upper.contains("AUTH")
-    {
+    if is_secret_env_name(key) {
         return false;
     }
 
@@ -416,6 +663,31 @@ impl ProcessLimits {
             max_processes: 32,
         }
     }
+
+    /// Generous limits for LLM CLI backends.  These are Node/Python programs
+    /// that reserve a lot of virtual memory and run for a long time, so the
+    /// caps only stop a runaway process from taking down the host.
+    pub fn permissive() -> Self {
+        Self {
+            max_memory_bytes: 16 * 1024 * 1024 * 1024,  // 16 GiB
+            max_file_size_bytes: 1024 * 1024 * 1024,      // 1 GiB
+            max_open_files: 4096,
+            max_cpu_secs: 3600,
+            max_processes: 1024,
+        }
+    }
+
+    /// Look up a named limit profile: "permissive", "default", "skill", or
+    /// "none" (no limits, returns `Ok(None)`).
+    pub fn from_profile(name: &str) -> std::result::Result<Option<Self>, String> {
+        match name {
+            "permissive" => Ok(Some(Self::permissive())),
+            "default" => Ok(Some(Self::default())),
+            "skill" => Ok(Some(Self::skill())),
+            "none" => Ok(None),
+            other => Err(format!("unknown process limit profile: {other}")),
+        }
+    }
 }
 
 /// Apply resource limits as a pre_exec hook on Unix systems.
@@ -569,6 +841,152 @@ pub fn apply_landlock(_data_dir: &Path, _config_dir: &Path) -> std::result::Resu
     Ok(())
 }
 
+// ===========================================================================
+// Seccomp syscall filter (Linux only)
+// ===========================================================================
+
+/// Syscalls that can be named in `security.seccomp_deny`.
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+const SECCOMP_SYSCALLS: &[(&str, libc::c_long)] = &[
+    ("ptrace", libc::SYS_ptrace),
+    ("process_vm_readv", libc::SYS_process_vm_readv),
+    ("process_vm_writev", libc::SYS_process_vm_writev),
+    ("mount", libc::SYS_mount),
+    ("umount2", libc::SYS_umount2),
+    ("pivot_root", libc::SYS_pivot_root),
+    ("keyctl", libc::SYS_keyctl),
+    ("add_key", libc::SYS_add_key),
+    ("request_key", libc::SYS_request_key),
+    ("kexec_load", libc::SYS_kexec_load),
+    ("init_module", libc::SYS_init_module),
+    ("finit_module", libc::SYS_finit_module),
+    ("delete_module", libc::SYS_delete_module),
+    ("reboot", libc::SYS_reboot),
+    ("swapon", libc::SYS_swapon),
+    ("swapoff", libc::SYS_swapoff),
+    ("acct", libc::SYS_acct),
+    ("bpf", libc::SYS_bpf),
+    ("perf_event_open", libc::SYS_perf_event_open),
+    ("userfaultfd", libc::SYS_userfaultfd),
+    ("open_by_handle_at", libc::SYS_open_by_handle_at),
+    ("unshare", libc::SYS_unshare),
+    ("setns", libc::SYS_setns),
+    ("chroot", libc::SYS_chroot),
+];
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const SECCOMP_AUDIT_ARCH: u32 = 0xC000_003E; // AUDIT_ARCH_X86_64
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+const SECCOMP_AUDIT_ARCH: u32 = 0xC000_00B7; // AUDIT_ARCH_AARCH64
+
+/// Build the BPF program for a deny list of syscall names.
+///
+/// Listed syscalls fail with `EPERM`; everything else is allowed.  Calls
+/// made under a foreign architecture (e.g. 32-bit compat) kill the process,
+/// since syscall numbers differ there and would slip past the list.
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn seccomp_filter(deny: &[String]) -> std::result::Result<Vec<libc::sock_filter>, String> {
+    let mut numbers = Vec::with_capacity(deny.len());
+    let mut unknown = Vec::new();
+    for name in deny {
+        match SECCOMP_SYSCALLS.iter().find(|(n, _)| n == name) {
+            Some(&(_, nr)) => numbers.push(nr),
+            None => unknown.push(name.as_str()),
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(format!("unknown syscalls in seccomp deny list: {}", unknown.join(", ")));
+    }
+    Ok(seccomp_program(&numbers))
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn seccomp_program(numbers: &[libc::c_long]) -> Vec<libc::sock_filter> {
+    const ARCH_OFFSET: u32 = 4;
+    const NR_OFFSET: u32 = 0;
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    fn stmt(code: u32, k: u32) -> libc::sock_filter {
+        libc::sock_filter { code: code as u16, jt: 0, jf: 0, k }
+    }
+    fn jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code: code as u16, jt, jf, k }
+    }
+
+    let load = libc::BPF_LD | libc::BPF_W | libc::BPF_ABS;
+    let jeq = libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K;
+    let ret = libc::BPF_RET | libc::BPF_K;
+    let deny = libc::SECCOMP_RET_ERRNO | libc::EPERM as u32;
+
+    let mut prog = vec![
+        stmt(load, ARCH_OFFSET),
+        jump(jeq, SECCOMP_AUDIT_ARCH, 1, 0),
+        stmt(ret, libc::SECCOMP_RET_KILL_PROCESS),
+        stmt(load, NR_OFFSET),
+    ];
+    // x32 syscalls share the x86_64 arch tag but carry their own numbers.
+    if cfg!(target_arch = "x86_64") {
+        prog.push(jump(libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K, X32_SYSCALL_BIT, 0, 1));
+        prog.push(stmt(ret, deny));
+    }
+    for &nr in numbers {
+        prog.push(jump(jeq, nr as u32, 0, 1));
+        prog.push(stmt(ret, deny));
+    }
+    prog.push(stmt(ret, libc::SECCOMP_RET_ALLOW));
+    prog
+}
+
+/// Install a filter built by [`seccomp_filter`] on every thread of the
+/// current process.  Sets `no_new_privs`, which the kernel requires for
+/// unprivileged filters.  The filter is inherited across fork and exec.
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn install_seccomp(prog: &[libc::sock_filter]) -> std::io::Result<()> {
+    let fprog = libc::sock_fprog {
+        len: prog.len() as u16,
+        filter: prog.as_ptr() as *mut libc::sock_filter,
+    };
+    // SAFETY: plain prctl/seccomp syscalls; `fprog` points at `prog`, which
+    // outlives the call, and the kernel copies the program.
+    unsafe {
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let rc = libc::syscall(
+            libc::SYS_seccomp,
+            libc::SECCOMP_SET_MODE_FILTER,
+            libc::SECCOMP_FILTER_FLAG_TSYNC,
+            &fprog as *const libc::sock_fprog,
+        );
+        if rc < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // With TSYNC a positive return is the id of a thread that could
+        // not be synchronised (it already has an incompatible filter).
+        if rc > 0 {
+            return Err(std::io::Error::from_raw_os_error(libc::ESRCH));
+        }
+    }
+    Ok(())
+}
+
+/// Apply a seccomp-bpf filter denying the given syscalls to the whole
+/// process.  Complements Landlock: Landlock limits which files can be
+/// touched, seccomp limits which kernel interfaces can be used at all.
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn apply_seccomp(deny: &[String]) -> std::result::Result<(), String> {
+    let prog = seccomp_filter(deny)?;
+    install_seccomp(&prog).map_err(|e| format!("seccomp install: {e}"))?;
+    info!(denied = deny.len(), "seccomp filter enforced");
+    Ok(())
+}
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+pub fn apply_seccomp(_deny: &[String]) -> std::result::Result<(), String> {
+    info!("seccomp not available on this platform (Linux x86_64/aarch64 only)");
+    Ok(())
+}
+
 // ===========================================================================
 // Tests
 // ===========================================================================
@@ -698,6 +1116,128 @@ mod tests {
         std::fs::remove_dir_all(&tmp).ok();
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_sandboxed_fs_rejects_writes_through_escaping_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let sandbox = SandboxedFs::new(tmp.path().to_path_buf()).unwrap();
+        symlink(outside.path(), tmp.path().join("link")).unwrap();
+
+        // New files and directories through a symlinked directory.
+        assert!(sandbox.write(Path::new("link/file.txt"), b"x").is_err());
+        assert!(sandbox.write(Path::new("link/newdir/file.txt"), b"x").is_err());
+        assert!(!outside.path().join("file.txt").exists());
+        assert!(!outside.path().join("newdir").exists());
+
+        // A dangling symlink whose target lies outside.
+        symlink(outside.path().join("target.txt"), tmp.path().join("dangling.txt")).unwrap();
+        assert!(sandbox.write(Path::new("dangling.txt"), b"x").is_err());
+        assert!(!outside.path().join("target.txt").exists());
+
+        // Symlinks that stay inside the root keep working.
+        std::fs::create_dir(tmp.path().join("real")).unwrap();
+        symlink(tmp.path().join("real"), tmp.path().join("alias")).unwrap();
+        sandbox.write(Path::new("alias/ok.txt"), b"ok").unwrap();
+        assert_eq!(std::fs::read_to_string(tmp.path().join("real/ok.txt")).unwrap(), "ok");
+
+        assert!(sandbox.resolve(Path::new("missing/../../escape.txt")).is_err());
+    }
+
+    #[test]
+    fn test_sandboxed_fs_write_is_atomic() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sandbox = SandboxedFs::new(tmp.path().to_path_buf()).unwrap();
+        let small = vec![b'a'; 1024];
+        let large = vec![b'b'; 4 * 1024 * 1024];
+        sandbox.write(Path::new("data.json"), &small).unwrap();
+
+        // A reader polling the file while it is rewritten only ever sees one
+        // complete version or the other.
+        let done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let reader = {
+            let path = tmp.path().join("data.json");
+            let done = done.clone();
+            let (small_len, large_len) = (small.len(), large.len());
+            std::thread::spawn(move || {
+                while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                    let seen = std::fs::read(&path).unwrap();
+                    assert!(seen.len() == small_len || seen.len() == large_len, "saw {} bytes", seen.len());
+                }
+            })
+        };
+        for i in 0..20 {
+            let data = if i % 2 == 0 { &large } else { &small };
+            sandbox.write(Path::new("data.json"), data).unwrap();
+        }
+        done.store(true, std::sync::atomic::Ordering::Relaxed);
+        reader.join().unwrap();
+
+        assert_eq!(std::fs::read(tmp.path().join("data.json")).unwrap(), small);
+        // No temporary files are left behind.
+        let names: Vec<_> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("data.json")]);
+    }
+
+    #[test]
+    fn test_sandboxed_fs_quota_rejects_writes_past_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sandbox = SandboxedFs::new(tmp.path().to_path_buf()).unwrap().with_quota(100);
+
+        sandbox.write(Path::new("a.txt"), &[b'a'; 60]).unwrap();
+        sandbox.write(Path::new("sub/b.txt"), &[b'b'; 30]).unwrap();
+        assert_eq!(sandbox.usage().unwrap(), 90);
+
+        let err = sandbox.write(Path::new("c.txt"), &[b'c'; 11]).unwrap_err();
+        assert!(matches!(err, SafeAgentError::QuotaExceeded(_)), "{err}");
+        assert!(!tmp.path().join("c.txt").exists());
+        assert_eq!(sandbox.usage().unwrap(), 90);
+
+        sandbox.write(Path::new("c.txt"), &[b'c'; 10]).unwrap();
+        assert_eq!(sandbox.usage().unwrap(), 100);
+    }
+
+    #[test]
+    fn test_sandboxed_fs_quota_counts_overwrite_delta() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sandbox = SandboxedFs::new(tmp.path().to_path_buf()).unwrap().with_quota(100);
+
+        sandbox.write(Path::new("a.txt"), &[b'a'; 80]).unwrap();
+        // Replacing 80 bytes with 95 only adds 15.
+        sandbox.write(Path::new("a.txt"), &[b'a'; 95]).unwrap();
+        assert_eq!(sandbox.usage().unwrap(), 95);
+        assert!(sandbox.write(Path::new("a.txt"), &[b'a'; 101]).is_err());
+
+        // Without a quota anything goes.
+        let unlimited = SandboxedFs::new(tmp.path().to_path_buf()).unwrap();
+        unlimited.write(Path::new("big.txt"), &[b'x'; 500]).unwrap();
+    }
+
+    #[test]
+    fn test_sandboxed_fs_quota_skips_database_and_caches_usage() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("safeclaw.db"), [0u8; 500]).unwrap();
+        std::fs::write(tmp.path().join("safeclaw.db-wal"), [0u8; 500]).unwrap();
+        let sandbox = SandboxedFs::new(tmp.path().to_path_buf()).unwrap().with_quota(100);
+        assert_eq!(sandbox.usage().unwrap(), 0);
+
+        sandbox.write(Path::new("a.txt"), &[b'a'; 60]).unwrap();
+        // Clones share the cached total, which each write keeps current
+        let clone = sandbox.clone();
+        clone.write(Path::new("b.txt"), &[b'b'; 30]).unwrap();
+        assert!(sandbox.write(Path::new("c.txt"), &[b'c'; 11]).is_err());
+
+        // Files written behind the sandbox's back are only seen on a rescan
+        std::fs::write(tmp.path().join("outside.txt"), [0u8; 10]).unwrap();
+        sandbox.write(Path::new("c.txt"), &[b'c'; 10]).unwrap();
+        assert_eq!(sandbox.usage().unwrap(), 110);
+    }
+
     #[test]
     fn test_sandboxed_fs_resolve_rejects_absolute_paths() {
         let tmp = std::env::temp_dir().join("test_sandbox_absolute");
@@ -736,6 +1276,18 @@ mod tests {
         assert_eq!(limits.max_processes, 32);
     }
 
+    #[test]
+    fn test_process_limits_profiles() {
+        let permissive = ProcessLimits::from_profile("permissive").unwrap().unwrap();
+        assert_eq!(permissive.max_memory_bytes, ProcessLimits::permissive().max_memory_bytes);
+        assert!(permissive.max_memory_bytes > ProcessLimits::default().max_memory_bytes);
+
+        let skill = ProcessLimits::from_profile("skill").unwrap().unwrap();
+        assert_eq!(skill.max_processes, 32);
+        assert!(ProcessLimits::from_profile("none").unwrap().is_none());
+        assert!(ProcessLimits::from_profile("huge").is_err());
+    }
+
     // -------------------------------------------------------------------------
     // validate_url edge cases
     // -------------------------------------------------------------------------
@@ -838,6 +1390,20 @@ mod tests {
         assert!(is_safe_env_var("PYTHONPATH"));
     }
 
+    #[test]
+    fn test_redact_secret_assignments() {
+        let text = "run with OPENROUTER_API_KEY=sk-or-v1-abcdef123 and DEBUG=1\n\
+                    config: {\"jwt_secret\": \"hunter2hunter2\", \"port\": 3031}";
+        let redacted = redact_secrets(text);
+        assert!(!redacted.contains("sk-or-v1-abcdef123"), "{redacted}");
+        assert!(!redacted.contains("hunter2hunter2"), "{redacted}");
+        assert!(redacted.contains("OPENROUTER_API_KEY=[REDACTED] and DEBUG=1"), "{redacted}");
+        assert!(redacted.contains("\"port\": 3031"));
+
+        // Names that merely mention a secret keep their text.
+        assert_eq!(redact_secrets("the TOKEN limit is high"), "the TOKEN limit is high");
+    }
+
     // -------------------------------------------------------------------------
     // PathJail edge cases
     // -------------------------------------------------------------------------
@@ -869,4 +1435,45 @@ mod tests {
 
         std::fs::remove_dir_all(&tmp).ok();
     }
+
+    #[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    #[test]
+    fn test_seccomp_filter_builds() {
+        let deny = crate::config::SecurityConfig::default().seccomp_deny;
+        let prog = seccomp_filter(&deny).unwrap();
+        // Two instructions per denied syscall plus the arch/allow scaffolding.
+        assert!(prog.len() > deny.len() * 2);
+        assert!(prog.len() < u16::MAX as usize);
+
+        let err = seccomp_filter(&["ptrace".into(), "not_a_syscall".into()]).unwrap_err();
+        assert!(err.contains("not_a_syscall"));
+    }
+
+    #[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    #[test]
+    fn test_seccomp_blocks_denied_syscall_in_child() {
+        use std::os::unix::process::CommandExt;
+
+        // getppid can never fail on its own, so EPERM proves the filter
+        // (and not some outer container policy) is doing the blocking.
+        let prog = seccomp_program(&[libc::SYS_getppid]);
+        let mut cmd = std::process::Command::new("true");
+        // SAFETY: the hook only issues raw syscalls between fork and exec.
+        unsafe {
+            cmd.pre_exec(move || {
+                install_seccomp(&prog)?;
+                let rc = libc::syscall(libc::SYS_getppid);
+                if rc == -1 && *libc::__errno_location() == libc::EPERM {
+                    Ok(())
+                } else {
+                    Err(std::io::Error::from_raw_os_error(libc::EINVAL))
+                }
+            });
+        }
+        let status = cmd.status().expect("filtered child failed to start");
+        assert!(status.success());
+
+        // The parent process is unaffected.
+        assert!(unsafe { libc::getppid() } > 0);
+    }
 }
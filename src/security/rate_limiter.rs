@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::{Duration, Instant};
 
 use tracing::warn;
@@ -11,8 +12,8 @@ use crate::error::{Result, SafeAgentError};
 /// Tracks tool call timestamps in memory and enforces per-minute
 /// and per-hour limits to prevent runaway tool loops.
 pub struct RateLimiter {
-    per_minute: u32,
-    per_hour: u32,
+    per_minute: AtomicU32,
+    per_hour: AtomicU32,
     /// Recent timestamps of tool calls, oldest first.
     calls: Mutex<VecDeque<Instant>>,
 }
@@ -35,17 +36,26 @@ pub struct RateLimitStatus {
 impl RateLimiter {
     pub fn new(per_minute: u32, per_hour: u32) -> Self {
         Self {
-            per_minute,
-            per_hour,
+            per_minute: AtomicU32::new(per_minute),
+            per_hour: AtomicU32::new(per_hour),
             calls: Mutex::new(VecDeque::new()),
         }
     }
 
+    /// Change the limits in place (config reload).  Already recorded calls
+    /// count against the new limits.
+    pub fn set_limits(&self, per_minute: u32, per_hour: u32) {
+        self.per_minute.store(per_minute, Ordering::Relaxed);
+        self.per_hour.store(per_hour, Ordering::Relaxed);
+    }
+
     /// Record a tool call and check if the rate limit is exceeded.
     /// Returns Ok(()) if within limits, or Err with a rate-limit message.
     pub fn check_and_record(&self) -> Result<()> {
         let mut calls = self.calls.lock().unwrap();
         let now = Instant::now();
+        let per_minute = self.per_minute.load(Ordering::Relaxed);
+        let per_hour = self.per_hour.load(Ordering::Relaxed);
 
         // Prune entries older than 1 hour
         let one_hour_ago = now - Duration::from_secs(3600);
@@ -58,29 +68,27 @@ impl RateLimiter {
         let calls_last_minute = calls.iter().filter(|t| **t >= one_minute_ago).count() as u32;
 
         // Check per-minute limit
-        if self.per_minute > 0 && calls_last_minute >= self.per_minute {
+        if per_minute > 0 && calls_last_minute >= per_minute {
             warn!(
                 calls = calls_last_minute,
-                limit = self.per_minute,
+                limit = per_minute,
                 "rate limit exceeded (per minute)"
             );
             return Err(SafeAgentError::RateLimited(format!(
-                "tool call rate limit exceeded: {calls_last_minute}/{} per minute",
-                self.per_minute
+                "tool call rate limit exceeded: {calls_last_minute}/{per_minute} per minute"
             )));
         }
 
         // Check per-hour limit
         let calls_last_hour = calls.len() as u32;
-        if self.per_hour > 0 && calls_last_hour >= self.per_hour {
+        if per_hour > 0 && calls_last_hour >= per_hour {
             warn!(
                 calls = calls_last_hour,
-                limit = self.per_hour,
+                limit = per_hour,
                 "rate limit exceeded (per hour)"
             );
             return Err(SafeAgentError::RateLimited(format!(
-                "tool call rate limit exceeded: {calls_last_hour}/{} per hour",
-                self.per_hour
+                "tool call rate limit exceeded: {calls_last_hour}/{per_hour} per hour"
             )));
         }
 
@@ -97,15 +105,17 @@ impl RateLimiter {
         let one_minute_ago = now - Duration::from_secs(60);
         let calls_last_minute = calls.iter().filter(|t| **t >= one_minute_ago).count() as u32;
         let calls_last_hour = calls.len() as u32;
+        let per_minute = self.per_minute.load(Ordering::Relaxed);
+        let per_hour = self.per_hour.load(Ordering::Relaxed);
 
-        let minute_limited = self.per_minute > 0 && calls_last_minute >= self.per_minute;
-        let hour_limited = self.per_hour > 0 && calls_last_hour >= self.per_hour;
+        let minute_limited = per_minute > 0 && calls_last_minute >= per_minute;
+        let hour_limited = per_hour > 0 && calls_last_hour >= per_hour;
 
         RateLimitStatus {
             calls_last_minute,
             calls_last_hour,
-            limit_per_minute: self.per_minute,
-            limit_per_hour: self.per_hour,
+            limit_per_minute: per_minute,
+            limit_per_hour: per_hour,
             is_limited: minute_limited || hour_limited,
         }
     }
@@ -179,6 +189,19 @@ mod tests {
         assert!(!status.is_limited);
     }
 
+    #[test]
+    fn test_set_limits_applies_to_recorded_calls() {
+        let limiter = RateLimiter::new(10, 100);
+        for _ in 0..3 {
+            limiter.check_and_record().unwrap();
+        }
+        limiter.set_limits(3, 100);
+        assert!(limiter.status().is_limited);
+        assert!(limiter.check_and_record().is_err());
+        limiter.set_limits(0, 0);
+        assert!(limiter.check_and_record().is_ok());
+    }
+
     #[test]
     fn test_reset() {
         let limiter = RateLimiter::new(5, 50);
@@ -0,0 +1,286 @@
+//! Secret lookup for bot tokens, API credentials and dashboard keys.
+//!
+//! The provider is chosen by `secrets.provider`: `env` reads process
+//! environment variables (the default), `file` reads a `KEY=VALUE` file,
+//! and `vault` reads one HashiCorp Vault KV v2 secret whose fields are the
+//! keys.  Secrets resolved here are never copied into the process
+//! environment, so skills and Rhai scripts — whose `env_get` is still
+//! filtered by [`is_safe_env_var`](super::is_safe_env_var) — can't see
+//! them.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::config::SecretsConfig;
+use crate::error::{Result, SafeAgentError};
+
+const VAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// Where secrets come from, for error messages.
+    fn describe(&self) -> String;
+
+    /// Look up `key`.  `Ok(None)` means the provider has no such secret.
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+}
+
+/// Build the provider selected in config.
+pub fn provider_from_config(config: &SecretsConfig) -> Result<Box<dyn SecretProvider>> {
+    match config.provider.as_str() {
+        "env" => Ok(Box::new(EnvSecretProvider)),
+        "file" => {
+            if config.file.is_empty() {
+                return Err(SafeAgentError::Config(
+                    "secrets.provider = \"file\" requires secrets.file".into(),
+                ));
+            }
+            Ok(Box::new(FileSecretProvider::new(&config.file)))
+        }
+        "vault" => {
+            if config.vault_addr.is_empty() {
+                return Err(SafeAgentError::Config(
+                    "secrets.provider = \"vault\" requires secrets.vault_addr".into(),
+                ));
+            }
+            let token = std::env::var("VAULT_TOKEN").map_err(|_| {
+                SafeAgentError::Config("secrets.provider = \"vault\" requires VAULT_TOKEN".into())
+            })?;
+            Ok(Box::new(VaultSecretProvider::new(
+                &config.vault_addr,
+                &config.vault_mount,
+                &config.vault_path,
+                token,
+            )))
+        }
+        other => Err(SafeAgentError::Config(format!(
+            "unknown secrets provider: {other} (expected \"env\", \"file\" or \"vault\")"
+        ))),
+    }
+}
+
+/// Look up a secret that must be present.  Empty values count as missing.
+pub async fn require(provider: &dyn SecretProvider, key: &str) -> Result<String> {
+    match provider.get(key).await? {
+        Some(value) if !value.is_empty() => Ok(value),
+        _ => Err(SafeAgentError::Config(format!(
+            "secret {key} not found in {}",
+            provider.describe()
+        ))),
+    }
+}
+
+// -- Environment ---------------------------------------------------------
+
+/// Secrets the agent itself looks up.
+const ENV_SECRET_KEYS: &[&str] = &[
+    "TELEGRAM_BOT_TOKEN",
+    "DISCORD_BOT_TOKEN",
+    "TWILIO_ACCOUNT_SID",
+    "TWILIO_AUTH_TOKEN",
+    "WHATSAPP_VERIFY_TOKEN",
+    "WHATSAPP_APP_SECRET",
+    "DASHBOARD_PASSWORD",
+    "JWT_SECRET",
+    "OIDC_CLIENT_SECRET",
+];
+
+/// Prefix for any other variable meant to be read as a secret.
+pub const ENV_SECRET_PREFIX: &str = "SAFECLAW_SECRET_";
+
+/// Process environment variables.  Only the agent's own secrets and
+/// variables named `SAFECLAW_SECRET_*` are served, so a lookup can't be
+/// pointed at an unrelated variable.
+pub struct EnvSecretProvider;
+
+#[async_trait]
+impl SecretProvider for EnvSecretProvider {
+    fn describe(&self) -> String {
+        "environment".into()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        if !ENV_SECRET_KEYS.contains(&key) && !key.starts_with(ENV_SECRET_PREFIX) {
+            return Err(SafeAgentError::PermissionDenied(format!(
+                "{key} is not a secret variable (expected one of the known secrets or {ENV_SECRET_PREFIX}*)"
+            )));
+        }
+        Ok(std::env::var(key).ok())
+    }
+}
+
+// -- File ----------------------------------------------------------------
+
+/// A `KEY=VALUE` file in dotenv syntax.  Re-read on every lookup so
+/// rotated secrets are picked up without a restart.
+pub struct FileSecretProvider {
+    path: PathBuf,
+}
+
+impl FileSecretProvider {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for FileSecretProvider {
+    fn describe(&self) -> String {
+        format!("secrets file {}", self.path.display())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let entries = dotenvy::from_path_iter(&self.path)
+            .map_err(|e| SafeAgentError::Config(format!("{}: {e}", self.describe())))?;
+        for entry in entries {
+            let (k, v) = entry
+                .map_err(|e| SafeAgentError::Config(format!("{}: {e}", self.describe())))?;
+            if k == key {
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// -- Vault ---------------------------------------------------------------
+
+/// A single HashiCorp Vault KV v2 secret; each field is one key.
+pub struct VaultSecretProvider {
+    client: reqwest::Client,
+    url: String,
+    token: String,
+}
+
+impl VaultSecretProvider {
+    pub fn new(addr: &str, mount: &str, path: &str, token: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: format!(
+                "{}/v1/{}/data/{}",
+                addr.trim_end_matches('/'),
+                mount.trim_matches('/'),
+                path.trim_matches('/')
+            ),
+            token,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VaultKvResponse {
+    data: VaultKvData,
+}
+
+#[derive(Deserialize)]
+struct VaultKvData {
+    #[serde(default)]
+    data: std::collections::HashMap<String, serde_json::Value>,
+}
+
+#[async_trait]
+impl SecretProvider for VaultSecretProvider {
+    fn describe(&self) -> String {
+        format!("Vault secret {}", self.url)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        debug!(key, url = %self.url, "reading secret from Vault");
+        let resp = self
+            .client
+            .get(&self.url)
+            .header("X-Vault-Token", &self.token)
+            .timeout(VAULT_TIMEOUT)
+            .send()
+            .await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = resp.error_for_status()?.text().await?;
+        parse_vault_kv(&body, key)
+    }
+}
+
+fn parse_vault_kv(body: &str, key: &str) -> Result<Option<String>> {
+    let parsed: VaultKvResponse = serde_json::from_str(body)?;
+    Ok(parsed.data.data.get(key).map(|v| match v {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_provider_resolves_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("secrets.env");
+        std::fs::write(
+            &path,
+            "# bot credentials\nTELEGRAM_BOT_TOKEN=123:abc\nJWT_SECRET=\"with spaces\"\n",
+        )
+        .unwrap();
+        let provider = FileSecretProvider::new(&path);
+
+        assert_eq!(require(&provider, "TELEGRAM_BOT_TOKEN").await.unwrap(), "123:abc");
+        assert_eq!(require(&provider, "JWT_SECRET").await.unwrap(), "with spaces");
+    }
+
+    #[tokio::test]
+    async fn env_provider_refuses_other_variables() {
+        for key in ["PATH", "HOME", "AWS_SECRET_ACCESS_KEY"] {
+            let err = EnvSecretProvider.get(key).await.unwrap_err();
+            assert!(matches!(err, SafeAgentError::PermissionDenied(_)), "{key}: {err}");
+        }
+        assert!(EnvSecretProvider.get("SAFECLAW_SECRET_UNSET_IN_TESTS").await.unwrap().is_none());
+        assert!(EnvSecretProvider.get("JWT_SECRET").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn missing_secret_names_key_and_provider() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("secrets.env");
+        std::fs::write(&path, "OTHER=1\nEMPTY=\n").unwrap();
+        let provider = FileSecretProvider::new(&path);
+
+        for key in ["DISCORD_BOT_TOKEN", "EMPTY"] {
+            let err = require(&provider, key).await.unwrap_err().to_string();
+            assert!(
+                err.contains(&format!("secret {key} not found in secrets file {}", path.display())),
+                "{err}"
+            );
+        }
+
+        let missing_file = FileSecretProvider::new(dir.path().join("nope.env"));
+        assert!(require(&missing_file, "OTHER").await.is_err());
+    }
+
+    #[test]
+    fn provider_selection_from_config() {
+        let config = |provider: &str, file: &str| SecretsConfig {
+            provider: provider.into(),
+            file: file.into(),
+            ..SecretsConfig::default()
+        };
+        assert_eq!(provider_from_config(&SecretsConfig::default()).unwrap().describe(), "environment");
+        assert!(provider_from_config(&config("file", "/run/secrets/safeclaw.env")).is_ok());
+        assert!(provider_from_config(&config("file", "")).is_err());
+        assert!(provider_from_config(&config("vault", "")).is_err());
+        assert!(provider_from_config(&config("aws", "")).is_err());
+    }
+
+    #[test]
+    fn parse_vault_kv_v2_response() {
+        let body = r#"{"data": {"data": {"JWT_SECRET": "s3cret", "PORT": 8200}, "metadata": {"version": 3}}}"#;
+        assert_eq!(parse_vault_kv(body, "JWT_SECRET").unwrap().as_deref(), Some("s3cret"));
+        assert_eq!(parse_vault_kv(body, "PORT").unwrap().as_deref(), Some("8200"));
+        assert_eq!(parse_vault_kv(body, "MISSING").unwrap(), None);
+        assert!(parse_vault_kv("not json", "X").is_err());
+    }
+}
@@ -4,6 +4,8 @@ use std::time::{Duration, Instant};
 
 use tracing::{info, warn};
 
+use crate::error::{Result, SafeAgentError};
+
 /// Two-factor authentication manager for dangerous operations.
 ///
 /// When a tool in the `require_2fa` list is about to execute, instead of
@@ -17,6 +19,16 @@ pub struct TwoFactorManager {
     challenges: Mutex<HashMap<String, Challenge>>,
     /// How long a challenge is valid.
     challenge_ttl: Duration,
+    /// Refuse confirmations that don't carry a TOTP code.
+    require_totp: bool,
+}
+
+/// A TOTP code offered to confirm a challenge, with the confirming user's
+/// secret to check it against.
+#[derive(Debug, Clone, Copy)]
+pub struct TotpCode<'a> {
+    pub secret: &'a str,
+    pub code: &'a str,
 }
 
 #[derive(Debug, Clone)]
@@ -64,9 +76,22 @@ impl TwoFactorManager {
             required_tools: required_tools.into_iter().collect(),
             challenges: Mutex::new(HashMap::new()),
             challenge_ttl: Duration::from_secs(300), // 5 minutes
+            require_totp: false,
         }
     }
 
+    /// Only accept confirmations backed by a TOTP code.
+    pub fn with_require_totp(mut self, require: bool) -> Self {
+        self.require_totp = require;
+        self
+    }
+
+    /// How long a challenge stays open before it is auto-rejected.
+    pub fn with_challenge_ttl(mut self, ttl: Duration) -> Self {
+        self.challenge_ttl = ttl;
+        self
+    }
+
     /// Check whether a tool requires 2FA. If so, create a challenge.
     ///
     /// Returns the verdict:
@@ -133,17 +158,44 @@ impl TwoFactorManager {
     }
 
     /// Confirm a pending challenge.
-    pub fn confirm(&self, challenge_id: &str) -> bool {
+    ///
+    /// When `totp` is given (the confirming user has TOTP set up) the code
+    /// must verify before the challenge resolves.  Without it the plain
+    /// confirm is accepted, unless TOTP is required.
+    pub fn confirm(&self, challenge_id: &str, totp: Option<TotpCode<'_>>) -> Result<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.confirm_at(challenge_id, totp, now)
+    }
+
+    fn confirm_at(&self, challenge_id: &str, totp: Option<TotpCode<'_>>, now: u64) -> Result<()> {
+        match totp {
+            Some(t) if !crate::dashboard::authn::verify_totp_at(t.secret, t.code, now) => {
+                warn!(challenge_id, "2FA confirmation refused: invalid TOTP code");
+                return Err(SafeAgentError::PermissionDenied("invalid TOTP code".into()));
+            }
+            None if self.require_totp => {
+                return Err(SafeAgentError::PermissionDenied(
+                    "a TOTP code is required to confirm; enable TOTP for your account first".into(),
+                ));
+            }
+            _ => {}
+        }
+
         let mut challenges = self.challenges.lock().unwrap();
-        if let Some(challenge) = challenges.get_mut(challenge_id) {
-            if !challenge.confirmed {
+        match challenges.get_mut(challenge_id) {
+            Some(challenge) if !challenge.confirmed => {
                 challenge.confirmed = true;
-                info!(challenge_id, tool = %challenge.tool, "2FA challenge confirmed");
-                return true;
+                info!(challenge_id, tool = %challenge.tool, totp = totp.is_some(), "2FA challenge confirmed");
+                Ok(())
+            }
+            _ => {
+                warn!(challenge_id, "2FA challenge not found or already confirmed");
+                Err(SafeAgentError::NotFound(format!("2FA challenge {challenge_id}")))
             }
         }
-        warn!(challenge_id, "2FA challenge not found or already confirmed");
-        false
     }
 
     /// Reject and remove a pending challenge.
@@ -157,6 +209,23 @@ impl TwoFactorManager {
         }
     }
 
+    /// Remove challenges older than the TTL, confirmed or not, and return
+    /// them so the caller can record the auto-rejection.
+    pub fn expire_stale(&self) -> Vec<Challenge> {
+        let mut challenges = self.challenges.lock().unwrap();
+        let now = Instant::now();
+        let stale: Vec<String> = challenges
+            .values()
+            .filter(|c| now.duration_since(c.created_at) >= self.challenge_ttl)
+            .map(|c| c.id.clone())
+            .collect();
+        stale
+            .iter()
+            .filter_map(|id| challenges.remove(id))
+            .inspect(|c| info!(challenge_id = %c.id, tool = %c.tool, "2FA challenge expired"))
+            .collect()
+    }
+
     /// List all pending (unconfirmed) challenges.
     pub fn pending(&self) -> Vec<ChallengeInfo> {
         let challenges = self.challenges.lock().unwrap();
@@ -217,7 +286,7 @@ mod tests {
         };
 
         // Confirm
-        assert!(mgr.confirm(&id));
+        mgr.confirm(&id, None).unwrap();
 
         // Now check again — should be Confirmed
         match mgr.check("exec", &params, "delete", "agent") {
@@ -256,6 +325,77 @@ mod tests {
         assert!(!mgr.requires_2fa("web_search"));
     }
 
+    #[test]
+    fn test_expire_stale_rejects_aged_challenges() {
+        let mgr = TwoFactorManager::new(vec!["exec".to_string()]).with_challenge_ttl(Duration::from_secs(60));
+        let old = match mgr.check("exec", &serde_json::json!({"a": 1}), "old", "agent") {
+            TwoFactorVerdict::ChallengeCreated(id) => id,
+            _ => panic!("expected ChallengeCreated"),
+        };
+        mgr.check("exec", &serde_json::json!({"b": 2}), "fresh", "agent");
+
+        // Age the first challenge past the TTL.
+        {
+            let mut challenges = mgr.challenges.lock().unwrap();
+            let c = challenges.get_mut(&old).unwrap();
+            c.created_at = Instant::now().checked_sub(Duration::from_secs(61)).unwrap();
+        }
+        assert!(mgr.pending().iter().all(|c| c.id != old));
+
+        let expired = mgr.expire_stale();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, old);
+        assert!(mgr.confirm(&old, None).is_err());
+        assert_eq!(mgr.pending().len(), 1);
+        assert!(mgr.expire_stale().is_empty());
+    }
+
+    fn rfc6238_secret() -> String {
+        data_encoding::BASE32_NOPAD.encode(b"12345678901234567890")
+    }
+
+    #[test]
+    fn test_confirm_with_totp_code() {
+        let mgr = TwoFactorManager::new(vec!["exec".to_string()]);
+        let params = serde_json::json!({"command": "reboot"});
+        let id = match mgr.check("exec", &params, "reboot", "agent") {
+            TwoFactorVerdict::ChallengeCreated(id) => id,
+            _ => panic!("expected ChallengeCreated"),
+        };
+
+        let secret = rfc6238_secret();
+        let totp = TotpCode { secret: &secret, code: "287082" };
+        mgr.confirm_at(&id, Some(totp), 59).unwrap();
+        assert!(matches!(mgr.check("exec", &params, "reboot", "agent"), TwoFactorVerdict::Confirmed));
+    }
+
+    #[test]
+    fn test_confirm_rejects_wrong_totp_code() {
+        let mgr = TwoFactorManager::new(vec!["exec".to_string()]);
+        let id = match mgr.check("exec", &serde_json::json!({}), "test", "agent") {
+            TwoFactorVerdict::ChallengeCreated(id) => id,
+            _ => panic!("expected ChallengeCreated"),
+        };
+
+        let secret = rfc6238_secret();
+        let wrong = TotpCode { secret: &secret, code: "123456" };
+        let err = mgr.confirm_at(&id, Some(wrong), 59).unwrap_err();
+        assert!(matches!(err, SafeAgentError::PermissionDenied(_)));
+        assert_eq!(mgr.pending().len(), 1);
+    }
+
+    #[test]
+    fn test_require_totp_refuses_plain_confirm() {
+        let mgr = TwoFactorManager::new(vec!["exec".to_string()]).with_require_totp(true);
+        let id = match mgr.check("exec", &serde_json::json!({}), "test", "agent") {
+            TwoFactorVerdict::ChallengeCreated(id) => id,
+            _ => panic!("expected ChallengeCreated"),
+        };
+
+        assert!(mgr.confirm(&id, None).is_err());
+        assert_eq!(mgr.pending().len(), 1);
+    }
+
     #[test]
     fn test_duplicate_challenge_reuses_existing() {
         let mgr = TwoFactorManager::new(vec!["exec".to_string()]);
@@ -2,10 +2,14 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use rhai::{Dynamic, Engine, Map, Scope, AST};
+use rhai::{CallFnOptions, Dynamic, Engine, Map, NativeCallContext, Scope, AST};
 use rusqlite::Connection;
 use tracing::{error, info};
 
+use super::rhai_runtime;
+use super::token::{SkillAction, SkillToken, SkillTokenStore};
+use crate::config::RhaiConfig;
+
 // ---------------------------------------------------------------------------
 // Skill route definition (collected when evaluating routes.rhai)
 // ---------------------------------------------------------------------------
@@ -26,6 +30,7 @@ pub struct SkillExtension {
     pub routes: Vec<SkillRoute>,
     pub ast: Option<Arc<AST>>,
     pub ui: SkillUiConfig,
+    pub network: SkillNetworkConfig,
 }
 
 /// UI extension configuration from skill.toml [ui] section.
@@ -48,6 +53,80 @@ pub struct SkillUiConfig {
     pub widget: Option<String>,
 }
 
+/// Network configuration from skill.toml [network] section.
+///
+/// ```toml
+/// [network]
+/// allowed_hosts = ["api.example.com", "*.githubusercontent.com"]
+/// ```
+///
+/// Without `allowed_hosts` the skill's Rhai code may reach any public host,
+/// as before.  Private networks are blocked either way.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct SkillNetworkConfig {
+    /// Hosts `http_get` / `http_post` may call.  `*.example.com` matches
+    /// subdomains of example.com.
+    #[serde(default)]
+    pub allowed_hosts: Option<Vec<String>>,
+}
+
+impl SkillNetworkConfig {
+    /// Validate `url` for an outbound call: the global SSRF block first, then
+    /// the skill's own host list.
+    pub fn check_url(&self, url: &str) -> Result<(), String> {
+        let parsed = crate::security::validate_url(url)?;
+        let Some(allowed) = &self.allowed_hosts else {
+            return Ok(());
+        };
+        let host = parsed.host_str().unwrap_or_default().to_lowercase();
+        let permitted = allowed.iter().any(|pattern| {
+            let pattern = pattern.trim().to_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.')),
+                None => host == pattern,
+            }
+        });
+        if permitted {
+            Ok(())
+        } else {
+            Err(format!("host '{host}' is not in this skill's allowed_hosts"))
+        }
+    }
+
+    /// An HTTP client that runs [`check_url`](Self::check_url) on every
+    /// redirect too, so an allowed host can't bounce a call to a private
+    /// address or a host outside the list.
+    fn http_client(&self) -> reqwest::Result<reqwest::blocking::Client> {
+        let network = self.clone();
+        reqwest::blocking::Client::builder()
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                match network.check_redirect(attempt.url().as_str(), attempt.previous().len()) {
+                    Ok(()) => attempt.follow(),
+                    Err(e) => attempt.error(e),
+                }
+            }))
+            .build()
+    }
+
+    /// Whether to follow the `hops`-th redirect, to `url`.
+    fn check_redirect(&self, url: &str, hops: usize) -> Result<(), String> {
+        if hops > MAX_REDIRECTS {
+            return Err(format!("more than {MAX_REDIRECTS} redirects"));
+        }
+        self.check_url(url).map_err(|e| format!("redirect to {url} blocked: {e}"))
+    }
+}
+
+/// Redirects `http_get` / `http_post` follow before giving up.
+const MAX_REDIRECTS: usize = 10;
+
+/// What a Rhai handler call runs under, attached as the call's tag.
+#[derive(Clone)]
+struct CallTag {
+    token: SkillToken,
+    network: SkillNetworkConfig,
+}
+
 // ---------------------------------------------------------------------------
 // Extension manager
 // ---------------------------------------------------------------------------
@@ -57,18 +136,49 @@ pub struct ExtensionManager {
     engine: Engine,
     extensions: HashMap<String, SkillExtension>,
     skills_dir: PathBuf,
+    tokens: Arc<SkillTokenStore>,
+    /// Resource limits every script evaluation runs under.
+    limits: RhaiConfig,
 }
 
 impl ExtensionManager {
-    pub fn new(skills_dir: PathBuf, db_path: PathBuf) -> Self {
-        let engine = create_engine(db_path, skills_dir.clone());
+    pub fn new(skills_dir: PathBuf, db_path: PathBuf, tokens: Arc<SkillTokenStore>) -> Self {
+        let limits = RhaiConfig::default();
+        let mut engine = create_engine(db_path, skills_dir.clone());
+        rhai_runtime::apply_limits(&mut engine, &limits);
         Self {
             engine,
             extensions: HashMap::new(),
             skills_dir,
+            tokens,
+            limits,
         }
     }
 
+    /// Replace the resource limits.  Call before [`discover`](Self::discover).
+    pub fn set_limits(&mut self, limits: RhaiConfig) {
+        rhai_runtime::apply_limits(&mut self.engine, &limits);
+        self.limits = limits;
+    }
+
+    /// Resolve the token a request to `skill_name` runs under.
+    ///
+    /// A skill calling back over HTTP presents its own token, which must
+    /// match the one issued at launch.  Dashboard requests carry no token
+    /// and run under the skill's live token.  Either way the skill must be
+    /// running — tokens are revoked when it stops.
+    pub fn authorize(&self, skill_name: &str, presented: Option<&str>) -> Option<SkillToken> {
+        match presented {
+            Some(secret) => self.tokens.validate(skill_name, secret),
+            None => self.tokens.get(skill_name),
+        }
+    }
+
+    /// Whether `skill_name` currently holds a live token, i.e. is running.
+    pub fn is_running(&self, skill_name: &str) -> bool {
+        self.tokens.get(skill_name).is_some()
+    }
+
     /// Load extensions from all skill directories that have a routes.rhai file.
     pub fn discover(&mut self) {
         let Ok(entries) = std::fs::read_dir(&self.skills_dir) else {
@@ -91,8 +201,8 @@ impl ExtensionManager {
                 continue;
             }
 
-            // Load UI config from skill.toml
-            let ui = load_ui_config(&dir);
+            // Load UI and network config from skill.toml
+            let (ui, network) = load_manifest_sections(&dir);
 
             // Load Rhai routes
             let routes_file = dir.join("routes.rhai");
@@ -128,6 +238,7 @@ impl ExtensionManager {
                     routes,
                     ast,
                     ui,
+                    network,
                 },
             );
         }
@@ -151,9 +262,10 @@ impl ExtensionManager {
         scope.push("__routes", rhai::Array::new());
         scope.push("__skill_name", skill_name.to_string());
 
-        let _ = self.engine
-            .eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
-            .map_err(|e| format!("eval error: {e}"))?;
+        let _ = rhai_runtime::run_limited(skill_name, &self.limits, || {
+            self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+        })
+        .map_err(|e| format!("eval error: {e}"))?;
 
         // Read the collected routes from the scope
         let collected: rhai::Array = scope
@@ -188,15 +300,20 @@ impl ExtensionManager {
     }
 
     /// Execute a Rhai route handler and return the response.
+    ///
+    /// The handler runs as the skill `token` was issued to, and API
+    /// functions it calls are checked against the token's scope and, for
+    /// HTTP, the skill's `[network]` host list.
     pub async fn handle_request(
         &self,
-        skill_name: &str,
+        token: &SkillToken,
         method: &str,
         path: &str,
         query_params: &HashMap<String, String>,
         body: &str,
         headers: &HashMap<String, String>,
     ) -> Result<RhaiResponse, String> {
+        let skill_name = token.skill_name.as_str();
         let ext = self.extensions.get(skill_name)
             .ok_or_else(|| format!("skill '{}' has no extensions", skill_name))?;
 
@@ -235,9 +352,13 @@ impl ExtensionManager {
 
         // Execute the handler function
         let handler_name = route.handler_name.clone();
-        let result = self.engine
-            .call_fn::<Dynamic>(&mut scope, ast, &handler_name, (Dynamic::from(req_map),))
-            .map_err(|e| format!("handler error: {e}"))?;
+        let tag = CallTag { token: token.clone(), network: ext.network.clone() };
+        let options = CallFnOptions::new().with_tag(tag);
+        let result = rhai_runtime::run_limited(skill_name, &self.limits, || {
+            self.engine
+                .call_fn_with_options::<Dynamic>(options, &mut scope, ast, &handler_name, (Dynamic::from(req_map),))
+        })
+        .map_err(|e| format!("handler error: {e}"))?;
 
         // Parse the response
         parse_rhai_response(result)
@@ -463,13 +584,17 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     });
 
     // --- HTTP client functions (with URL validation) ---
-    // Blocks file://, private networks, and localhost to prevent SSRF.
-    engine.register_fn("http_get", |url: String| -> Dynamic {
-        if let Err(e) = crate::security::validate_url(&url) {
-            tracing::warn!(url = %url, err = %e, "http_get: URL blocked");
-            return Dynamic::from(format!("error: {e}"));
-        }
-        match reqwest::blocking::get(&url) {
+    // Blocks file://, private networks, and localhost to prevent SSRF, and
+    // hosts outside the skill's [network] allowed_hosts.
+    engine.register_fn("http_get", |ctx: NativeCallContext, url: String| -> Dynamic {
+        let client = match require_http(&ctx, &url) {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!(url = %url, err = %e, "http_get: blocked");
+                return Dynamic::from(format!("error: {e}"));
+            }
+        };
+        match client.get(&url).send() {
             Ok(resp) => {
                 if let Ok(text) = resp.text() {
                     if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
@@ -485,12 +610,14 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
         }
     });
 
-    engine.register_fn("http_post", |url: String, body: String| -> Dynamic {
-        if let Err(e) = crate::security::validate_url(&url) {
-            tracing::warn!(url = %url, err = %e, "http_post: URL blocked");
-            return Dynamic::from(format!("error: {e}"));
-        }
-        let client = reqwest::blocking::Client::new();
+    engine.register_fn("http_post", |ctx: NativeCallContext, url: String, body: String| -> Dynamic {
+        let client = match require_http(&ctx, &url) {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!(url = %url, err = %e, "http_post: blocked");
+                return Dynamic::from(format!("error: {e}"));
+            }
+        };
         match client.post(&url)
             .header("Content-Type", "application/json")
             .body(body)
@@ -511,13 +638,15 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
         }
     });
 
-    engine.register_fn("http_post", |url: String, body: Map| -> Dynamic {
-        if let Err(e) = crate::security::validate_url(&url) {
-            tracing::warn!(url = %url, err = %e, "http_post: URL blocked");
-            return Dynamic::from(format!("error: {e}"));
-        }
+    engine.register_fn("http_post", |ctx: NativeCallContext, url: String, body: Map| -> Dynamic {
+        let client = match require_http(&ctx, &url) {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!(url = %url, err = %e, "http_post: blocked");
+                return Dynamic::from(format!("error: {e}"));
+            }
+        };
         let json_body = rhai_to_json(Dynamic::from(body));
-        let client = reqwest::blocking::Client::new();
         match client.post(&url)
             .json(&json_body)
             .send()
@@ -542,7 +671,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     // This prevents path traversal attacks from Rhai extensions.
     let jail_root = skills_dir.clone();
     let jail_r = jail_root.clone();
-    engine.register_fn("data_read", move |path: String| -> Dynamic {
+    engine.register_fn("data_read", move |ctx: NativeCallContext, path: String| -> Dynamic {
+        if let Err(e) = require_scope(&ctx, SkillAction::Files) {
+            tracing::warn!(err = %e, "data_read: denied by skill token");
+            return Dynamic::UNIT;
+        }
         let jail = match crate::security::PathJail::new(jail_r.clone()) {
             Some(j) => j,
             None => return Dynamic::from("error: cannot initialize path jail"),
@@ -560,7 +693,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     });
 
     let jail_w = jail_root.clone();
-    engine.register_fn("data_write", move |path: String, content: String| -> bool {
+    engine.register_fn("data_write", move |ctx: NativeCallContext, path: String, content: String| -> bool {
+        if let Err(e) = require_scope(&ctx, SkillAction::Files) {
+            tracing::warn!(err = %e, "data_write: denied by skill token");
+            return false;
+        }
         let jail = match crate::security::PathJail::new(jail_w.clone()) {
             Some(j) => j,
             None => return false,
@@ -580,7 +717,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     });
 
     let jail_l = jail_root.clone();
-    engine.register_fn("data_list", move |dir: String| -> rhai::Array {
+    engine.register_fn("data_list", move |ctx: NativeCallContext, dir: String| -> rhai::Array {
+        if let Err(e) = require_scope(&ctx, SkillAction::Files) {
+            tracing::warn!(err = %e, "data_list: denied by skill token");
+            return rhai::Array::new();
+        }
         let jail = match crate::security::PathJail::new(jail_l.clone()) {
             Some(j) => j,
             None => return rhai::Array::new(),
@@ -604,7 +745,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     });
 
     let jail_e = jail_root.clone();
-    engine.register_fn("data_exists", move |path: String| -> bool {
+    engine.register_fn("data_exists", move |ctx: NativeCallContext, path: String| -> bool {
+        if let Err(e) = require_scope(&ctx, SkillAction::Files) {
+            tracing::warn!(err = %e, "data_exists: denied by skill token");
+            return false;
+        }
         let jail = match crate::security::PathJail::new(jail_e.clone()) {
             Some(j) => j,
             None => return false,
@@ -624,7 +769,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
         .parent()
         .unwrap_or_else(|| std::path::Path::new("."))
         .to_path_buf();
-    engine.register_fn("data_delete", move |path: String| -> bool {
+    engine.register_fn("data_delete", move |ctx: NativeCallContext, path: String| -> bool {
+        if let Err(e) = require_scope(&ctx, SkillAction::Files) {
+            tracing::warn!(err = %e, "data_delete: denied by skill token");
+            return false;
+        }
         let jail = match crate::security::PathJail::new(jail_d.clone()) {
             Some(j) => j,
             None => return false,
@@ -656,6 +805,25 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
         }
     });
 
+    // --- Credentials (the calling skill's own entries only) ---
+    let credentials_path = skills_dir.join("credentials.json");
+    engine.register_fn("credential_get", move |ctx: NativeCallContext, key: String| -> Dynamic {
+        let token = match require_scope(&ctx, SkillAction::Credentials) {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!(key = %key, err = %e, "credential_get: denied by skill token");
+                return Dynamic::UNIT;
+            }
+        };
+        std::fs::read_to_string(&credentials_path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<HashMap<String, HashMap<String, String>>>(&json).ok())
+            .and_then(|mut all| all.remove(&token.skill_name))
+            .and_then(|mut creds| creds.remove(&key))
+            .map(Dynamic::from)
+            .unwrap_or(Dynamic::UNIT)
+    });
+
     // --- JSON helpers ---
     engine.register_fn("json_parse", |text: String| -> Dynamic {
         match serde_json::from_str::<serde_json::Value>(&text) {
@@ -697,7 +865,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     // db_query: read-only; only SELECT/WITH/EXPLAIN allowed.
     // db_execute: write; blocks DROP, ALTER, ATTACH, PRAGMA writes, etc.
     let db_path_query = db_path.clone();
-    engine.register_fn("db_query", move |sql: String| -> Dynamic {
+    engine.register_fn("db_query", move |ctx: NativeCallContext, sql: String| -> Dynamic {
+        if let Err(e) = require_sql_scope(&ctx, &sql, false) {
+            tracing::warn!(sql = %sql, err = %e, "db_query: denied by skill token");
+            return Dynamic::from(format!("error: {e}"));
+        }
         if let Err(e) = crate::security::validate_sql_readonly(&sql) {
             tracing::warn!(sql = %sql, err = %e, "db_query: SQL blocked");
             return Dynamic::from(format!("error: {e}"));
@@ -706,7 +878,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     });
 
     let db_path_query2 = db_path.clone();
-    engine.register_fn("db_query", move |sql: String, params: rhai::Array| -> Dynamic {
+    engine.register_fn("db_query", move |ctx: NativeCallContext, sql: String, params: rhai::Array| -> Dynamic {
+        if let Err(e) = require_sql_scope(&ctx, &sql, false) {
+            tracing::warn!(sql = %sql, err = %e, "db_query: denied by skill token");
+            return Dynamic::from(format!("error: {e}"));
+        }
         if let Err(e) = crate::security::validate_sql_readonly(&sql) {
             tracing::warn!(sql = %sql, err = %e, "db_query: SQL blocked");
             return Dynamic::from(format!("error: {e}"));
@@ -723,7 +899,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     });
 
     let db_path_exec = db_path.clone();
-    engine.register_fn("db_execute", move |sql: String| -> Dynamic {
+    engine.register_fn("db_execute", move |ctx: NativeCallContext, sql: String| -> Dynamic {
+        if let Err(e) = require_sql_scope(&ctx, &sql, true) {
+            tracing::warn!(sql = %sql, err = %e, "db_execute: denied by skill token");
+            return Dynamic::from(format!("error: {e}"));
+        }
         if let Err(e) = crate::security::validate_sql(&sql) {
             tracing::warn!(sql = %sql, err = %e, "db_execute: SQL blocked");
             return Dynamic::from(format!("error: {e}"));
@@ -732,7 +912,11 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     });
 
     let db_path_exec2 = db_path;
-    engine.register_fn("db_execute", move |sql: String, params: rhai::Array| -> Dynamic {
+    engine.register_fn("db_execute", move |ctx: NativeCallContext, sql: String, params: rhai::Array| -> Dynamic {
+        if let Err(e) = require_sql_scope(&ctx, &sql, true) {
+            tracing::warn!(sql = %sql, err = %e, "db_execute: denied by skill token");
+            return Dynamic::from(format!("error: {e}"));
+        }
         if let Err(e) = crate::security::validate_sql(&sql) {
             tracing::warn!(sql = %sql, err = %e, "db_execute: SQL blocked");
             return Dynamic::from(format!("error: {e}"));
@@ -751,6 +935,36 @@ fn create_engine(db_path: PathBuf, skills_dir: PathBuf) -> Engine {
     engine
 }
 
+/// The token the current handler runs under, if it grants `action`.
+/// Calls made outside a request (e.g. while loading routes.rhai) carry no
+/// token and are denied.
+fn require_scope(ctx: &NativeCallContext, action: SkillAction) -> Result<SkillToken, String> {
+    let token = call_tag(ctx)?.token;
+    token.check(action)?;
+    Ok(token)
+}
+
+/// Check an outbound HTTP call against the token and the skill's network
+/// config, and return a client that holds redirects to the same rules.
+fn require_http(ctx: &NativeCallContext, url: &str) -> Result<reqwest::blocking::Client, String> {
+    let tag = call_tag(ctx)?;
+    tag.token.check(SkillAction::Http)?;
+    tag.network.check_url(url)?;
+    tag.network.http_client().map_err(|e| e.to_string())
+}
+
+fn call_tag(ctx: &NativeCallContext) -> Result<CallTag, String> {
+    ctx.tag()
+        .and_then(|tag| tag.clone().try_cast::<CallTag>())
+        .ok_or_else(|| "no skill token for this call".to_string())
+}
+
+/// Check a SQL statement against the current handler's token.
+fn require_sql_scope(ctx: &NativeCallContext, sql: &str, write: bool) -> Result<(), String> {
+    let action = if write { SkillAction::DbWrite } else { SkillAction::DbRead };
+    require_scope(ctx, action)?.check_sql(sql, write)
+}
+
 /// Execute a SQL query and return results as an array of maps.
 fn db_execute_query(db_path: &Path, sql: &str, params: &[&dyn rusqlite::types::ToSql]) -> Dynamic {
     let conn = match Connection::open_with_flags(
@@ -814,25 +1028,28 @@ fn db_execute_stmt(db_path: &Path, sql: &str, params: &[&dyn rusqlite::types::To
 // Helpers
 // ---------------------------------------------------------------------------
 
-fn load_ui_config(skill_dir: &Path) -> SkillUiConfig {
+/// The `[ui]` and `[network]` sections of a skill's skill.toml.
+fn load_manifest_sections(skill_dir: &Path) -> (SkillUiConfig, SkillNetworkConfig) {
     let manifest_path = skill_dir.join("skill.toml");
     if !manifest_path.exists() {
-        return SkillUiConfig::default();
+        return Default::default();
     }
 
     let content = match std::fs::read_to_string(&manifest_path) {
         Ok(c) => c,
-        Err(_) => return SkillUiConfig::default(),
+        Err(_) => return Default::default(),
     };
 
     #[derive(serde::Deserialize)]
     struct Wrapper {
         #[serde(default)]
         ui: SkillUiConfig,
+        #[serde(default)]
+        network: SkillNetworkConfig,
     }
 
     toml::from_str::<Wrapper>(&content)
-        .map(|w| w.ui)
+        .map(|w| (w.ui, w.network))
         .unwrap_or_default()
 }
 
@@ -844,3 +1061,154 @@ pub struct SkillExtensionInfo {
     pub routes: Vec<String>,
     pub ui: SkillUiConfig,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::token::SkillPermissions;
+
+    const ROUTES: &str = r#"
+__routes.push(register_route("GET", "/read", "read"));
+__routes.push(register_route("POST", "/write", "write"));
+
+fn read(req) { json_response(db_query("SELECT body FROM notes")) }
+fn write(req) { json_response(db_execute("INSERT INTO notes (body) VALUES ('x')")) }
+"#;
+
+    fn setup(actions: Vec<SkillAction>) -> (tempfile::TempDir, ExtensionManager, SkillToken) {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        std::fs::create_dir_all(skills_dir.join("notes")).unwrap();
+        std::fs::write(skills_dir.join("notes/routes.rhai"), ROUTES).unwrap();
+
+        let db_path = tmp.path().join("test.db");
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (body TEXT); INSERT INTO notes VALUES ('hello');")
+            .unwrap();
+
+        let tokens = Arc::new(SkillTokenStore::new());
+        let perms = SkillPermissions { actions, tables: vec!["notes".into()] };
+        let token = tokens.issue("notes", &perms);
+
+        let mut mgr = ExtensionManager::new(skills_dir, db_path, tokens);
+        mgr.discover();
+        (tmp, mgr, token)
+    }
+
+    async fn call(mgr: &ExtensionManager, token: &SkillToken, method: &str, path: &str) -> String {
+        mgr.handle_request(token, method, path, &HashMap::new(), "", &HashMap::new())
+            .await
+            .unwrap()
+            .body
+    }
+
+    #[tokio::test]
+    async fn read_only_token_allows_query_denies_execute() {
+        let (_tmp, mgr, token) = setup(vec![SkillAction::DbRead]);
+
+        let body = call(&mgr, &token, "GET", "/read").await;
+        assert!(body.contains("hello"), "read should succeed: {body}");
+
+        let body = call(&mgr, &token, "POST", "/write").await;
+        assert!(body.contains("not permitted"), "write should be denied: {body}");
+    }
+
+    #[tokio::test]
+    async fn revoked_token_is_not_authorized() {
+        let (_tmp, mgr, token) = setup(vec![SkillAction::DbRead]);
+        assert!(mgr.authorize("notes", Some(&token.secret)).is_some());
+        assert!(mgr.authorize("notes", None).is_some());
+        assert!(mgr.is_running("notes"));
+
+        mgr.tokens.revoke("notes");
+        assert!(mgr.authorize("notes", Some(&token.secret)).is_none());
+        assert!(mgr.authorize("notes", None).is_none());
+        assert!(!mgr.is_running("notes"));
+    }
+
+    #[tokio::test]
+    async fn runaway_handler_is_aborted_and_blamed_on_its_skill() {
+        let (tmp, _, token) = setup(vec![SkillAction::DbRead]);
+        std::fs::write(
+            tmp.path().join("skills/notes/routes.rhai"),
+            "__routes.push(register_route(\"GET\", \"/spin\", \"spin\"));\nfn spin(req) { loop { } }\n",
+        )
+        .unwrap();
+        let tokens = Arc::new(SkillTokenStore::new());
+        let mut mgr = ExtensionManager::new(tmp.path().join("skills"), tmp.path().join("test.db"), tokens);
+        mgr.set_limits(RhaiConfig { max_operations: 50_000, ..Default::default() });
+        mgr.discover();
+
+        let err = mgr
+            .handle_request(&token, "GET", "/spin", &HashMap::new(), "", &HashMap::new())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err, "handler error: skill 'notes' exceeded its operation limit (50000)");
+    }
+
+    #[test]
+    fn allowed_hosts_limit_reach_on_top_of_the_private_network_block() {
+        let network = SkillNetworkConfig {
+            allowed_hosts: Some(vec!["api.example.com".into(), "*.cdn.example".into(), "10.0.0.5".into()]),
+        };
+        assert!(network.check_url("https://api.example.com/v1/items").is_ok());
+        assert!(network.check_url("https://API.example.com:8443/").is_ok());
+        assert!(network.check_url("https://img.cdn.example/a.png").is_ok());
+        assert!(network.check_url("https://other.com/").is_err());
+        assert!(network.check_url("https://api.example.com.evil.io/").is_err());
+        assert!(network.check_url("https://xcdn.example/").is_err());
+        // Listing a private address doesn't lift the SSRF block.
+        assert!(network.check_url("http://10.0.0.5/").is_err());
+
+        let open = SkillNetworkConfig::default();
+        assert!(open.check_url("https://other.com/").is_ok());
+        assert!(open.check_url("http://127.0.0.1:8080/").is_err());
+        assert!(open.check_url("http://localhost/").is_err());
+    }
+
+    #[test]
+    fn redirects_are_held_to_the_same_rules() {
+        let network = SkillNetworkConfig { allowed_hosts: Some(vec!["api.example.com".into()]) };
+        assert!(network.check_redirect("https://api.example.com/next", 0).is_ok());
+        let err = network.check_redirect("https://other.com/", 0).unwrap_err();
+        assert!(err.contains("redirect to https://other.com/ blocked"), "{err}");
+        assert!(network.check_redirect("http://169.254.169.254/latest/meta-data", 1).is_err());
+        assert!(network.check_redirect("https://api.example.com/loop", MAX_REDIRECTS + 1).is_err());
+
+        let open = SkillNetworkConfig::default();
+        assert!(open.check_redirect("http://127.0.0.1:8080/admin", 0).is_err());
+        assert!(open.http_client().is_ok());
+    }
+
+    #[tokio::test]
+    async fn rhai_http_calls_are_held_to_the_manifest_host_list() {
+        let (tmp, _, token) = setup(SkillAction::ALL.to_vec());
+        let skill_dir = tmp.path().join("skills/notes");
+        std::fs::write(skill_dir.join("skill.toml"), "name = \"notes\"\n\n[network]\nallowed_hosts = [\"api.example.com\"]\n").unwrap();
+        std::fs::write(
+            skill_dir.join("routes.rhai"),
+            r#"
+__routes.push(register_route("GET", "/other", "other"));
+__routes.push(register_route("GET", "/private", "loopback"));
+
+fn other(req) { text_response(http_get("https://other.com/")) }
+fn loopback(req) { text_response(http_post("http://127.0.0.1:9/", "{}")) }
+"#,
+        )
+        .unwrap();
+        let tokens = Arc::new(SkillTokenStore::new());
+        let mut mgr = ExtensionManager::new(tmp.path().join("skills"), tmp.path().join("test.db"), tokens);
+        mgr.discover();
+        assert_eq!(
+            mgr.get_extension("notes").unwrap().network.allowed_hosts,
+            Some(vec!["api.example.com".to_string()])
+        );
+
+        let body = call(&mgr, &token, "GET", "/other").await;
+        assert!(body.contains("not in this skill's allowed_hosts"), "{body}");
+        let body = call(&mgr, &token, "GET", "/private").await;
+        assert!(body.contains("blocked internal/private host"), "{body}");
+    }
+}
@@ -0,0 +1,209 @@
+//! Health probes for running skills.
+//!
+//! A skill whose process is alive but wedged would otherwise stay "running"
+//! forever.  Manifests can declare a probe, which the skill manager runs
+//! on each agent tick:
+//!
+//! ```toml
+//! [health]
+//! http = "http://127.0.0.1:8123/healthz"   # or: command = "test -f ready"
+//! interval_secs = 30
+//! failure_threshold = 3
+//! ```
+//!
+//! After `failure_threshold` consecutive failures the skill is restarted.
+//! Restarts back off exponentially while the probe keeps failing.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use tokio::process::Command;
+
+/// Backoff before the second consecutive probe-triggered restart; doubles
+/// with each further one.  The first restart is immediate.
+const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(10);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(600);
+
+/// The `[health]` section of a skill manifest.  Exactly one of `http` and
+/// `command` should be set; `http` wins if both are.  Rhai skills run
+/// in-process, so only `http` probes are accepted for them.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct HealthProbe {
+    /// URL to GET; any 2xx response is healthy.
+    #[serde(default)]
+    pub http: Option<String>,
+    /// Shell command run in the skill directory; exit status 0 is healthy.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Seconds between probes.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Seconds a single probe may take before it counts as failed.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Consecutive failures that trigger a restart.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+}
+
+impl HealthProbe {
+    /// Whether this probe runs a shell command rather than an HTTP GET.
+    pub(crate) fn runs_command(&self) -> bool {
+        self.http.is_none() && self.command.is_some()
+    }
+}
+
+fn default_interval_secs() -> u64 {
+    30
+}
+fn default_timeout_secs() -> u64 {
+    5
+}
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+/// Probe results for one skill, shown in the skill detail view.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ProbeStatus {
+    /// RFC 3339 time of the last probe.
+    pub last_checked_at: Option<String>,
+    pub last_ok: Option<bool>,
+    /// Why the last probe failed.
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    /// Restarts caused by failed probes since the probe last passed.
+    pub restarts: u32,
+    #[serde(skip)]
+    pub(crate) next_probe: Option<Instant>,
+    /// The skill is not restarted before this.
+    #[serde(skip)]
+    pub(crate) restart_after: Option<Instant>,
+}
+
+impl ProbeStatus {
+    /// Record a probe result.  Returns true when the failure threshold has
+    /// been reached and the skill should be restarted.
+    pub(crate) fn record(&mut self, probe: &HealthProbe, result: Result<(), String>) -> bool {
+        self.last_checked_at = Some(chrono::Utc::now().to_rfc3339());
+        self.next_probe = Some(Instant::now() + Duration::from_secs(probe.interval_secs));
+        match result {
+            Ok(()) => {
+                self.last_ok = Some(true);
+                self.last_error = None;
+                self.consecutive_failures = 0;
+                self.restarts = 0;
+                false
+            }
+            Err(e) => {
+                self.last_ok = Some(false);
+                self.last_error = Some(e);
+                self.consecutive_failures += 1;
+                self.consecutive_failures >= probe.failure_threshold.max(1)
+            }
+        }
+    }
+
+    /// Note a probe-triggered restart and schedule the backoff for the
+    /// next one.
+    pub(crate) fn restarted(&mut self) {
+        self.consecutive_failures = 0;
+        self.restarts += 1;
+        self.restart_after = Some(Instant::now() + restart_backoff(self.restarts));
+    }
+
+    pub(crate) fn due(&self) -> bool {
+        self.next_probe.is_none_or(|t| Instant::now() >= t)
+    }
+
+    pub(crate) fn backing_off(&self) -> bool {
+        self.restart_after.is_some_and(|t| Instant::now() < t)
+    }
+}
+
+/// Delay before a skill may start again after its `restarts`-th
+/// consecutive probe-triggered restart.
+fn restart_backoff(restarts: u32) -> Duration {
+    if restarts <= 1 {
+        return Duration::ZERO;
+    }
+    RESTART_BACKOFF_BASE
+        .saturating_mul(2u32.saturating_pow(restarts - 2))
+        .min(RESTART_BACKOFF_MAX)
+}
+
+/// Run `probe` once against the skill in `dir`.  Called without the skill
+/// manager lock held, so a slow probe doesn't stall the dashboard.
+pub(crate) async fn run(probe: &HealthProbe, dir: &Path) -> Result<(), String> {
+    let timeout = Duration::from_secs(probe.timeout_secs.max(1));
+    if let Some(url) = &probe.http {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let resp = client.get(url).send().await.map_err(|e| format!("GET {url}: {e}"))?;
+        return if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("GET {url}: HTTP {}", resp.status()))
+        };
+    }
+    let Some(command) = &probe.command else {
+        return Err("probe declares neither http nor command".into());
+    };
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+
+    // Same sandbox as the skill process itself: its own process group (so
+    // a timeout kills anything the command forked) and the skill rlimits.
+    #[cfg(unix)]
+    {
+        #[allow(unused_imports)]
+        use std::os::unix::process::CommandExt;
+        let limits = crate::security::ProcessLimits::skill();
+        unsafe {
+            cmd.pre_exec(move || {
+                libc::setpgid(0, 0);
+                crate::security::apply_process_limits(&limits)?;
+                Ok(())
+            });
+        }
+    }
+
+    let mut child = cmd.spawn().map_err(|e| format!("probe command failed to run: {e}"))?;
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(s)) if s.success() => Ok(()),
+        Ok(Ok(s)) => Err(format!("probe command exited with {s}")),
+        Ok(Err(e)) => Err(format!("probe command failed to run: {e}")),
+        Err(_) => {
+            #[cfg(unix)]
+            if let Some(pid) = child.id() {
+                unsafe {
+                    libc::kill(-(pid as i32), libc::SIGKILL);
+                }
+            }
+            let _ = child.kill().await;
+            Err(format!("probe timed out after {}s", timeout.as_secs()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_backoff_grows_and_is_capped() {
+        assert_eq!(restart_backoff(1), Duration::ZERO);
+        assert_eq!(restart_backoff(2), RESTART_BACKOFF_BASE);
+        assert_eq!(restart_backoff(3), RESTART_BACKOFF_BASE * 2);
+        assert_eq!(restart_backoff(40), RESTART_BACKOFF_MAX);
+    }
+}
@@ -5,12 +5,22 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use tokio::process::{Child, Command};
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
+use crate::config::RhaiConfig;
 use crate::error::{Result, SafeAgentError};
 use crate::tunnel::TunnelUrl;
 
+use super::health::{self, HealthProbe, ProbeStatus};
+use super::registry::SkillRegistry;
 use super::rhai_runtime;
+use super::schema;
+use super::token::{SkillPermissions, SkillTokenStore, SKILL_TOKEN_ENV};
+
+/// Directory inside a skill holding earlier versions of its manifest.
+const MANIFEST_HISTORY_DIR: &str = ".manifest-history";
+/// Manifest versions kept per skill.
+const MANIFEST_HISTORY_LIMIT: usize = 10;
 
 /// Manifest describing a skill, read from `skill.toml` in the skill directory.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -41,6 +51,16 @@ pub struct SkillManifest {
     /// by name with a human-readable description and whether it's required.
     #[serde(default)]
     pub credentials: Vec<CredentialSpec>,
+    /// Actions and tables the skill's token is scoped to when it calls back
+    /// into the agent.  Omitted means unrestricted.
+    #[serde(default)]
+    pub permissions: SkillPermissions,
+    /// Skills that must already be running before this one starts.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Liveness probe; a skill failing it repeatedly is restarted.
+    #[serde(default)]
+    pub health: Option<HealthProbe>,
 }
 
 /// Declares a credential that a skill needs.
@@ -87,6 +107,7 @@ enum SkillHandle {
 /// Tracks a running skill.
 struct RunningSkill {
     manifest: SkillManifest,
+    dir: PathBuf,
     handle: SkillHandle,
 }
 
@@ -107,6 +128,18 @@ pub struct SkillManager {
     /// Skills that were manually stopped via API and should not be
     /// auto-restarted by `reconcile()` until explicitly started again.
     manually_stopped: std::collections::HashSet<String>,
+    /// Capability-scoped tokens for running skills.  Issued at launch and
+    /// revoked when the skill stops or exits.
+    tokens: Arc<SkillTokenStore>,
+    /// Resource limits for embedded Rhai skills.
+    rhai_limits: RhaiConfig,
+    /// Skills that can't start because of their `depends_on`: unknown
+    /// dependencies or cycles.  skill_name -> problem.
+    dependency_problems: HashMap<String, String>,
+    /// Health probe results for skills declaring `[health]`.
+    probes: HashMap<String, ProbeStatus>,
+    /// Curated registry for `import_skill` with source "registry".
+    registry: Option<SkillRegistry>,
 }
 
 impl SkillManager {
@@ -138,15 +171,36 @@ impl SkillManager {
             credentials_path,
             tunnel_url: None,
             manually_stopped: std::collections::HashSet::new(),
+            tokens: Arc::new(SkillTokenStore::new()),
+            rhai_limits: RhaiConfig::default(),
+            dependency_problems: HashMap::new(),
+            probes: HashMap::new(),
+            registry: None,
         }
     }
 
+    /// Shared handle to the skill token store, used by the extension API to
+    /// validate requests made on behalf of a skill.
+    pub fn tokens(&self) -> Arc<SkillTokenStore> {
+        self.tokens.clone()
+    }
+
     /// Set the ngrok tunnel URL receiver so running (and future) skills
     /// receive `TUNNEL_URL` / `PUBLIC_URL` in their environment.
     pub fn set_tunnel_url(&mut self, url: TunnelUrl) {
         self.tunnel_url = Some(url);
     }
 
+    /// Set the resource limits embedded Rhai skills run under.
+    pub fn set_rhai_limits(&mut self, limits: RhaiConfig) {
+        self.rhai_limits = limits;
+    }
+
+    /// Set the registry `import_skill` resolves "registry" imports from.
+    pub fn set_registry(&mut self, registry: Option<SkillRegistry>) {
+        self.registry = registry;
+    }
+
     /// Register an additional directory to scan for subprocess skills.
     ///
     /// Called during startup after the plugin registry discovers subprocess
@@ -200,9 +254,8 @@ impl SkillManager {
 
     /// Scan the skills directory (and any plugin-contributed directories),
     /// start new enabled skills, restart crashed ones, and stop skills whose
-    /// directories have been deleted.
-    ///
-    /// Called every tick from the agent loop.
+    /// directories have been deleted.  Health probes are run separately,
+    /// by [`SkillManager::reconcile_probed`] on each agent tick.
     pub async fn reconcile(&mut self) -> Result<()> {
         // Reap finished processes first
         self.reap_finished().await;
@@ -211,9 +264,11 @@ impl SkillManager {
         // detect deletions after the scan.
         let mut on_disk: std::collections::HashSet<String> =
             std::collections::HashSet::new();
+        // Enabled skills found on disk, started below in dependency order.
+        let mut enabled = Vec::new();
 
         // Scan the primary user-managed skills directory
-        self.scan_skill_dir(&self.skills_dir.clone(), &mut on_disk).await;
+        self.scan_skill_dir(&self.skills_dir.clone(), &mut on_disk, &mut enabled).await;
 
         // Scan plugin-contributed subprocess skill directories.
         // Each entry is a single skill directory (not a parent of many),
@@ -238,13 +293,11 @@ impl SkillManager {
                 }
                 continue;
             }
-            if !self.running.contains_key(&manifest.name)
-                && !self.manually_stopped.contains(&manifest.name)
-            {
-                self.start_skill(manifest, dir).await;
-            }
+            enabled.push((manifest, dir));
         }
 
+        self.start_in_dependency_order(enabled).await;
+
         // Stop any running skills whose directories were deleted
         let orphaned: Vec<String> = self
             .running
@@ -262,11 +315,13 @@ impl SkillManager {
     }
 
     /// Scan a parent directory for skill subdirectories (each containing
-    /// `skill.toml`) and start/stop them as appropriate.
+    /// `skill.toml`), stopping disabled skills and collecting enabled ones
+    /// into `enabled`.
     async fn scan_skill_dir(
         &mut self,
         dir: &Path,
         on_disk: &mut std::collections::HashSet<String>,
+        enabled: &mut Vec<(SkillManifest, PathBuf)>,
     ) {
         let entries = match std::fs::read_dir(dir) {
             Ok(e) => e,
@@ -305,11 +360,53 @@ impl SkillManager {
                 continue;
             }
 
-            if !self.running.contains_key(&manifest.name)
-                && !self.manually_stopped.contains(&manifest.name)
-            {
-                self.start_skill(manifest, path).await;
+            enabled.push((manifest, path));
+        }
+    }
+
+    /// Start the enabled skills that aren't running, dependencies first.
+    ///
+    /// A skill waits until everything in its `depends_on` was already
+    /// running before this pass, so each dependency gets a reconcile
+    /// interval to come up (or crash) before its dependents launch.  Skills
+    /// depending on unknown skills or caught in a cycle never start; the
+    /// problem is logged once and shown in [`SkillStatus`].
+    async fn start_in_dependency_order(&mut self, enabled: Vec<(SkillManifest, PathBuf)>) {
+        let manifests: Vec<&SkillManifest> = enabled.iter().map(|(m, _)| m).collect();
+        let plan = plan_start_order(&manifests);
+
+        let problems: HashMap<String, String> = plan.problems.into_iter().collect();
+        for (name, problem) in &problems {
+            if self.dependency_problems.get(name) != Some(problem) {
+                warn!(skill = %name, problem = %problem, "skill not started");
+            }
+        }
+        self.dependency_problems = problems;
+
+        let healthy: std::collections::HashSet<String> = self.running.keys().cloned().collect();
+        let mut slots: Vec<Option<(SkillManifest, PathBuf)>> = enabled.into_iter().map(Some).collect();
+        for i in plan.order {
+            let Some((manifest, dir)) = slots[i].take() else {
+                continue;
+            };
+            if self.running.contains_key(&manifest.name) || self.manually_stopped.contains(&manifest.name) {
+                continue;
+            }
+            if self.probes.get(&manifest.name).is_some_and(ProbeStatus::backing_off) {
+                debug!(skill = %manifest.name, "backing off restart after failed health probes");
+                continue;
+            }
+            let waiting: Vec<&str> = manifest
+                .depends_on
+                .iter()
+                .filter(|dep| !healthy.contains(*dep))
+                .map(String::as_str)
+                .collect();
+            if !waiting.is_empty() {
+                debug!(skill = %manifest.name, waiting_on = ?waiting, "deferring skill until its dependencies are running");
+                continue;
             }
+            self.start_skill(manifest, dir).await;
         }
     }
 
@@ -326,11 +423,22 @@ impl SkillManager {
             return;
         }
 
+        // A Rhai skill has no process to sandbox a probe command alongside.
+        if manifest.entrypoint.ends_with(".rhai")
+            && manifest.health.as_ref().is_some_and(HealthProbe::runs_command)
+        {
+            warn!(skill = %manifest.name, "not starting Rhai skill: command probes are not supported for Rhai skills");
+            return;
+        }
+
         // Create skill data directory
         let _ = std::fs::create_dir_all(dir.join("data"));
 
-        // Collect environment variables that apply to every skill type.
-        let env_vars = self.collect_skill_env(&manifest, &dir);
+        // Collect environment variables that apply to every skill type,
+        // including a freshly issued capability token.
+        let token = self.tokens.issue(&manifest.name, &manifest.permissions);
+        let mut env_vars = self.collect_skill_env(&manifest, &dir);
+        env_vars.insert(SKILL_TOKEN_ENV.into(), token.secret);
 
         // ── Rhai scripts: run in-process ──────────────────────────────────
         if manifest.entrypoint.ends_with(".rhai") {
@@ -388,6 +496,7 @@ impl SkillManager {
             Ok(f) => f,
             Err(e) => {
                 error!(skill = %manifest.name, err = %e, "failed to open skill log");
+                self.tokens.revoke(&manifest.name);
                 return;
             }
         };
@@ -395,6 +504,7 @@ impl SkillManager {
             Ok(f) => f,
             Err(e) => {
                 error!(skill = %manifest.name, err = %e, "failed to clone log file handle");
+                self.tokens.revoke(&manifest.name);
                 return;
             }
         };
@@ -449,12 +559,14 @@ impl SkillManager {
                     manifest.name.clone(),
                     RunningSkill {
                         manifest,
+                        dir,
                         handle: SkillHandle::Process(child),
                     },
                 );
             }
             Err(e) => {
                 error!(skill = %manifest.name, err = %e, "failed to start skill");
+                self.tokens.revoke(&manifest.name);
             }
         }
     }
@@ -600,6 +712,7 @@ impl SkillManager {
             Ok(f) => f,
             Err(e) => {
                 error!(skill = %manifest.name, err = %e, "failed to open skill log for Rhai");
+                self.tokens.revoke(&manifest.name);
                 return;
             }
         };
@@ -615,8 +728,9 @@ impl SkillManager {
         });
 
         let skill_name = manifest.name.clone();
+        let limits = self.rhai_limits.clone();
         let task = tokio::task::spawn_blocking(move || {
-            let engine = rhai_runtime::build_engine(ctx.clone());
+            let engine = rhai_runtime::build_engine(ctx.clone(), &limits);
             if let Err(e) = rhai_runtime::run_script(&engine, &script_path) {
                 if !ctx.cancel.load(Ordering::Relaxed) {
                     eprintln!("[rhai-skill:{skill_name}] {e}");
@@ -634,6 +748,7 @@ impl SkillManager {
             manifest.name.clone(),
             RunningSkill {
                 manifest,
+                dir,
                 handle: SkillHandle::Embedded { task, cancel },
             },
         );
@@ -688,6 +803,7 @@ impl SkillManager {
 
     /// Stop a running skill by name, killing the entire process group.
     pub async fn stop_skill(&mut self, name: &str) {
+        self.tokens.revoke(name);
         if let Some(skill) = self.running.remove(name) {
             match skill.handle {
                 SkillHandle::Process(mut child) => {
@@ -737,8 +853,9 @@ impl SkillManager {
     /// `Ok(true)` if the skill was started, `Ok(false)` if it was already
     /// running, or an error if the skill was not found or is disabled.
     pub async fn start_skill_by_name(&mut self, name: &str) -> Result<bool> {
-        // Clear manual-stop flag regardless
+        // Clear manual-stop flag and any probe backoff regardless
         self.manually_stopped.remove(name);
+        self.probes.remove(name);
 
         // Already running?
         if self.running.contains_key(name) {
@@ -746,7 +863,7 @@ impl SkillManager {
         }
 
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
 
         let manifest = self.read_manifest(&dir.join("skill.toml"))?;
@@ -765,18 +882,75 @@ impl SkillManager {
     /// Clears any manual-stop flag.
     pub async fn restart_skill_by_name(&mut self, name: &str) -> Result<()> {
         self.manually_stopped.remove(name);
+        self.probes.remove(name);
         self.stop_skill(name).await;
         // Brief pause for process cleanup
         tokio::time::sleep(std::time::Duration::from_millis(300)).await;
 
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
         let manifest = self.read_manifest(&dir.join("skill.toml"))?;
         self.start_skill(manifest, dir).await;
         Ok(())
     }
 
+    /// Probe the running skills behind `manager`, then reconcile.  The lock
+    /// is held only to collect the due probes and to record their results,
+    /// never while a probe runs, so a slow probe can't stall the dashboard
+    /// or message handling.
+    pub async fn reconcile_probed(manager: &tokio::sync::Mutex<SkillManager>) -> Result<()> {
+        let due = manager.lock().await.due_probes().await;
+        let mut results = Vec::with_capacity(due.len());
+        for (name, probe, dir) in due {
+            let result = health::run(&probe, &dir).await;
+            results.push((name, probe, result));
+        }
+        let mut sm = manager.lock().await;
+        sm.record_probes(results).await;
+        sm.reconcile().await
+    }
+
+    /// Reap finished skills and collect the due health probes of those
+    /// still running.  Manually stopped skills aren't running, so they are
+    /// never probed.
+    async fn due_probes(&mut self) -> Vec<(String, HealthProbe, PathBuf)> {
+        self.reap_finished().await;
+        self.running
+            .iter()
+            .filter(|(name, skill)| {
+                skill.manifest.skill_type != "oneshot" && !self.manually_stopped.contains(name.as_str())
+            })
+            .filter_map(|(name, skill)| Some((name, skill.manifest.health.clone()?, &skill.dir)))
+            .filter(|(name, _, _)| self.probes.get(name.as_str()).is_none_or(ProbeStatus::due))
+            .map(|(name, probe, dir)| (name.clone(), probe, dir.clone()))
+            .collect()
+    }
+
+    /// Record probe results, stopping skills that reach their failure
+    /// threshold so the start pass brings them back (subject to backoff).
+    /// Skills stopped while their probe ran are left alone.
+    async fn record_probes(&mut self, results: Vec<(String, HealthProbe, std::result::Result<(), String>)>) {
+        for (name, probe, result) in results {
+            if !self.running.contains_key(&name) {
+                continue;
+            }
+            if let Err(e) = &result {
+                warn!(skill = %name, err = %e, "skill health probe failed");
+            }
+            let status = self.probes.entry(name.clone()).or_default();
+            if status.record(&probe, result) {
+                warn!(
+                    skill = %name,
+                    failures = status.consecutive_failures,
+                    "skill failed its health probe threshold, restarting"
+                );
+                status.restarted();
+                self.stop_skill(&name).await;
+            }
+        }
+    }
+
     /// Check running skills for any that have exited, and remove them so
     /// they can be restarted on the next reconcile.
     async fn reap_finished(&mut self) {
@@ -826,6 +1000,7 @@ impl SkillManager {
 
         for name in &finished {
             self.running.remove(name);
+            self.tokens.revoke(name);
         }
     }
 
@@ -889,6 +1064,7 @@ impl SkillManager {
 
                 let stopped = self.manually_stopped.contains(&name);
                 let has_venv = path.join(".venv").join("bin").join("python").exists();
+                let dependency_problem = self.dependency_problems.get(&name).cloned();
                 result.push(SkillStatus {
                     name,
                     description: manifest.description,
@@ -899,6 +1075,7 @@ impl SkillManager {
                     manually_stopped: stopped,
                     has_venv,
                     credentials: credential_status,
+                    dependency_problem,
                 });
             }
         }
@@ -929,7 +1106,7 @@ impl SkillManager {
     /// Get detailed information about a skill.
     pub fn detail(&self, name: &str) -> Result<SkillDetail> {
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
 
         let manifest_path = dir.join("skill.toml");
@@ -985,6 +1162,7 @@ impl SkillManager {
                 manually_stopped: stopped,
                 has_venv,
                 credentials: credential_status,
+                dependency_problem: self.dependency_problems.get(name).cloned(),
             },
             manifest_raw,
             env: manifest.env.clone(),
@@ -992,6 +1170,7 @@ impl SkillManager {
             dir: dir.to_string_lossy().to_string(),
             entrypoint: manifest.entrypoint.clone(),
             venv_path,
+            health: self.probes.get(name).cloned(),
         })
     }
 
@@ -1009,21 +1188,33 @@ impl SkillManager {
     /// Read skill log (last N lines).
     pub fn read_log(&self, name: &str, max_lines: usize) -> Result<String> {
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
         Ok(Self::tail_file(&dir.join("skill.log"), max_lines))
     }
 
+    /// Path of a skill's log file, which may not exist until it first runs.
+    pub fn log_path(&self, name: &str) -> Result<PathBuf> {
+        let dir = self.find_skill_dir(name).ok_or_else(|| {
+            SafeAgentError::NotFound(format!("skill '{name}'"))
+        })?;
+        Ok(dir.join("skill.log"))
+    }
+
+    /// Whether a skill is currently running.
+    pub fn is_running(&self, name: &str) -> bool {
+        self.running.contains_key(name)
+    }
+
     /// Update a skill's manifest with new TOML contents. Validates before writing.
     pub fn update_manifest(&self, name: &str, new_toml: &str) -> Result<()> {
-        // Parse the new TOML to validate it
-        let _parsed: SkillManifest = toml::from_str(new_toml)
-            .map_err(|e| SafeAgentError::Config(format!("invalid skill manifest TOML: {e}")))?;
+        schema::validate_manifest(new_toml)?;
 
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
 
+        Self::save_manifest_version(&dir)?;
         std::fs::write(dir.join("skill.toml"), new_toml)
             .map_err(|e| SafeAgentError::Io(e))?;
 
@@ -1031,10 +1222,85 @@ impl SkillManager {
         Ok(())
     }
 
+    /// Earlier manifest versions kept by `update_manifest`, newest first.
+    pub fn manifest_history(&self, name: &str) -> Result<Vec<ManifestVersion>> {
+        let dir = self.find_skill_dir(name).ok_or_else(|| {
+            SafeAgentError::NotFound(format!("skill '{name}'"))
+        })?;
+        let mut versions = Vec::new();
+        for (version, path) in Self::history_files(&dir).into_iter().rev() {
+            let content = std::fs::read_to_string(&path).map_err(SafeAgentError::Io)?;
+            let saved_at = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+                .unwrap_or_default();
+            versions.push(ManifestVersion { version, saved_at, content });
+        }
+        Ok(versions)
+    }
+
+    /// Undo the last `update_manifest`: restore the newest saved version and
+    /// drop it from the history, so repeated reverts step further back.
+    /// Returns the restored content.
+    pub fn revert_manifest(&self, name: &str) -> Result<String> {
+        let dir = self.find_skill_dir(name).ok_or_else(|| {
+            SafeAgentError::NotFound(format!("skill '{name}'"))
+        })?;
+        let (_, newest) = Self::history_files(&dir).pop().ok_or_else(|| {
+            SafeAgentError::NotFound(format!("earlier manifest for skill '{name}'"))
+        })?;
+        let content = std::fs::read_to_string(&newest).map_err(SafeAgentError::Io)?;
+        std::fs::write(dir.join("skill.toml"), &content).map_err(SafeAgentError::Io)?;
+        std::fs::remove_file(&newest).map_err(SafeAgentError::Io)?;
+
+        info!(skill = %name, "manifest reverted to previous version");
+        Ok(content)
+    }
+
+    /// Copy the current manifest into the skill's history directory,
+    /// dropping the oldest versions beyond `MANIFEST_HISTORY_LIMIT`.
+    fn save_manifest_version(dir: &Path) -> Result<()> {
+        let current = match std::fs::read_to_string(dir.join("skill.toml")) {
+            Ok(c) => c,
+            Err(_) => return Ok(()),
+        };
+        let history_dir = dir.join(MANIFEST_HISTORY_DIR);
+        std::fs::create_dir_all(&history_dir).map_err(SafeAgentError::Io)?;
+
+        let mut files = Self::history_files(dir);
+        let next = files.last().map_or(1, |(version, _)| version + 1);
+        let path = history_dir.join(format!("{next:06}.toml"));
+        std::fs::write(&path, current).map_err(SafeAgentError::Io)?;
+        files.push((next, path));
+
+        let excess = files.len().saturating_sub(MANIFEST_HISTORY_LIMIT);
+        for (_, old) in files.drain(..excess) {
+            let _ = std::fs::remove_file(old);
+        }
+        Ok(())
+    }
+
+    /// Saved manifest versions in `dir`, oldest first.
+    fn history_files(dir: &Path) -> Vec<(u64, PathBuf)> {
+        let Ok(entries) = std::fs::read_dir(dir.join(MANIFEST_HISTORY_DIR)) else {
+            return Vec::new();
+        };
+        let mut files: Vec<(u64, PathBuf)> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter_map(|path| {
+                let version = path.file_name()?.to_str()?.strip_suffix(".toml")?.parse().ok()?;
+                Some((version, path))
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
     /// Toggle a skill's enabled state. Returns the new enabled value.
     pub fn set_enabled(&self, name: &str, enabled: bool) -> Result<bool> {
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
 
         let manifest_path = dir.join("skill.toml");
@@ -1062,7 +1328,7 @@ impl SkillManager {
     /// Update a single environment variable in the skill manifest.
     pub fn set_env_var(&self, name: &str, key: &str, value: &str) -> Result<()> {
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
 
         let manifest_path = dir.join("skill.toml");
@@ -1094,7 +1360,7 @@ impl SkillManager {
     /// Delete an environment variable from the skill manifest.
     pub fn delete_env_var(&self, name: &str, key: &str) -> Result<()> {
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
 
         let manifest_path = dir.join("skill.toml");
@@ -1139,6 +1405,7 @@ impl SkillManager {
         source: &str,
         location: &str,
         name_override: Option<&str>,
+        force: bool,
     ) -> Result<(String, PathBuf)> {
         let dest_name = match name_override {
             Some(n) if !n.is_empty() => sanitize_skill_name(n),
@@ -1163,9 +1430,10 @@ impl SkillManager {
             "git" => self.import_from_git(location, &dest).await?,
             "path" => self.import_from_path(location, &dest)?,
             "url" => self.import_from_url(location, &dest).await?,
+            "registry" => self.import_from_registry(location, &dest, force).await?,
             other => {
                 return Err(SafeAgentError::Config(format!(
-                    "unknown import source type: '{other}' (expected git, path, or url)"
+                    "unknown import source type: '{other}' (expected git, path, url, or registry)"
                 )));
             }
         }
@@ -1207,8 +1475,15 @@ impl SkillManager {
             }
         }
 
-        // Read the final manifest name
-        let manifest = self.read_manifest(&dest.join("skill.toml"))?;
+        // Validate the final manifest strictly before accepting the skill
+        let contents = std::fs::read_to_string(dest.join("skill.toml")).map_err(SafeAgentError::Io)?;
+        let manifest = match schema::validate_manifest(&contents) {
+            Ok(m) => m,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&dest);
+                return Err(e);
+            }
+        };
         info!(
             skill = %manifest.name,
             source,
@@ -1274,16 +1549,34 @@ impl SkillManager {
             .await
             .map_err(|e| SafeAgentError::Config(format!("failed to read response: {e}")))?;
 
+        Self::unpack_archive(&bytes, url, dest)
+    }
+
+    /// Install `spec` (`name` or `name@version`) from the configured
+    /// registry once its archive's signature checks out.
+    async fn import_from_registry(&self, spec: &str, dest: &Path, force: bool) -> Result<()> {
+        let registry = self.registry.as_ref().ok_or_else(|| {
+            SafeAgentError::Config("no skill registry configured ([skill_registry] index_url)".into())
+        })?;
+        let index = registry.fetch_index().await?;
+        let entry = index.resolve(spec)?;
+        let archive = registry.download(entry, force).await?;
+        Self::unpack_archive(&archive, &entry.download_url, dest)
+    }
+
+    /// Extract a downloaded archive into `dest`, picking the format from
+    /// `url`'s extension and trying both when it has none.
+    fn unpack_archive(bytes: &[u8], url: &str, dest: &Path) -> Result<()> {
         std::fs::create_dir_all(dest).map_err(SafeAgentError::Io)?;
 
         if url.ends_with(".tar.gz") || url.ends_with(".tgz") {
-            Self::extract_tar_gz(&bytes, dest)?;
+            Self::extract_tar_gz(bytes, dest)?;
         } else if url.ends_with(".zip") {
-            Self::extract_zip(&bytes, dest)?;
+            Self::extract_zip(bytes, dest)?;
         } else {
             // Try tar.gz first, fall back to zip
-            if Self::extract_tar_gz(&bytes, dest).is_err() {
-                if Self::extract_zip(&bytes, dest).is_err() {
+            if Self::extract_tar_gz(bytes, dest).is_err() {
+                if Self::extract_zip(bytes, dest).is_err() {
                     let _ = std::fs::remove_dir_all(dest);
                     return Err(SafeAgentError::Config(
                         "could not extract archive — unsupported format (expected .tar.gz or .zip)".into(),
@@ -1353,7 +1646,7 @@ impl SkillManager {
         }
 
         let dir = self.find_skill_dir(name).ok_or_else(|| {
-            SafeAgentError::Config(format!("skill '{name}' not found"))
+            SafeAgentError::NotFound(format!("skill '{name}'"))
         })?;
 
         std::fs::remove_dir_all(&dir).map_err(SafeAgentError::Io)?;
@@ -1376,6 +1669,80 @@ impl SkillManager {
     }
 }
 
+/// Order in which to consider skills for starting.
+#[derive(Debug, Default)]
+struct StartPlan {
+    /// Indices into the manifests, each after its dependencies.  Skills in
+    /// a cycle are left out.
+    order: Vec<usize>,
+    /// (skill, problem) for unknown dependencies and cycles.
+    problems: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+    Cyclic,
+}
+
+/// Topologically sort `manifests` by `depends_on`, reporting dependencies
+/// that aren't among them and cycles.
+fn plan_start_order(manifests: &[&SkillManifest]) -> StartPlan {
+    let index: HashMap<&str, usize> = manifests.iter().enumerate().map(|(i, m)| (m.name.as_str(), i)).collect();
+    let mut visits = vec![Visit::New; manifests.len()];
+    let mut plan = StartPlan::default();
+    for i in 0..manifests.len() {
+        visit(i, manifests, &index, &mut visits, &mut Vec::new(), &mut plan);
+    }
+    plan
+}
+
+fn visit(
+    i: usize,
+    manifests: &[&SkillManifest],
+    index: &HashMap<&str, usize>,
+    visits: &mut [Visit],
+    path: &mut Vec<usize>,
+    plan: &mut StartPlan,
+) {
+    if visits[i] != Visit::New {
+        return;
+    }
+    visits[i] = Visit::InProgress;
+    path.push(i);
+    for dep in &manifests[i].depends_on {
+        let Some(&j) = index.get(dep.as_str()) else {
+            let problem = format!("depends on unknown or disabled skill '{dep}'");
+            plan.problems.push((manifests[i].name.clone(), problem));
+            continue;
+        };
+        match visits[j] {
+            Visit::New => visit(j, manifests, index, visits, path, plan),
+            Visit::InProgress => {
+                let start = path.iter().position(|&k| k == j).unwrap_or(0);
+                let names: Vec<&str> = path[start..]
+                    .iter()
+                    .chain([&j])
+                    .map(|&k| manifests[k].name.as_str())
+                    .collect();
+                let problem = format!("dependency cycle: {}", names.join(" -> "));
+                for &k in &path[start..] {
+                    visits[k] = Visit::Cyclic;
+                    plan.problems.push((manifests[k].name.clone(), problem.clone()));
+                }
+            }
+            Visit::Done | Visit::Cyclic => {}
+        }
+    }
+    path.pop();
+    if visits[i] == Visit::InProgress {
+        visits[i] = Visit::Done;
+        plan.order.push(i);
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct SkillStatus {
     pub name: String,
@@ -1389,6 +1756,18 @@ pub struct SkillStatus {
     /// Whether a Python venv exists for this skill.
     pub has_venv: bool,
     pub credentials: Vec<CredentialStatus>,
+    /// Why the skill can't start (unknown dependency, dependency cycle).
+    pub dependency_problem: Option<String>,
+}
+
+/// An earlier version of a skill's manifest.
+#[derive(Debug, serde::Serialize)]
+pub struct ManifestVersion {
+    /// Increases with every saved version.
+    pub version: u64,
+    /// When the version was replaced (RFC 3339).
+    pub saved_at: String,
+    pub content: String,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -1417,6 +1796,9 @@ pub struct SkillDetail {
     pub entrypoint: String,
     /// Path to the Python venv directory, if one exists.
     pub venv_path: Option<String>,
+    /// Health probe results, if the skill declares a probe and has been
+    /// probed.
+    pub health: Option<ProbeStatus>,
 }
 
 // -- Free helpers --------------------------------------------------------
@@ -1444,6 +1826,7 @@ fn infer_name_from_source(source: &str, location: &str) -> String {
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_default()
         }
+        "registry" => location.split('@').next().unwrap_or(location).to_string(),
         "url" => {
             let base = location.rsplit('/').next().unwrap_or(location);
             base.trim_end_matches(".tar.gz")
@@ -1481,3 +1864,280 @@ fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<()
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, depends_on: &[&str]) -> SkillManifest {
+        let deps: Vec<String> = depends_on.iter().map(|d| format!("\"{d}\"")).collect();
+        toml::from_str(&format!("name = \"{name}\"\ndepends_on = [{}]", deps.join(", "))).unwrap()
+    }
+
+    /// A Rhai daemon skill that idles until stopped.
+    fn write_skill(skills_dir: &Path, name: &str, depends_on: &[&str]) {
+        let dir = skills_dir.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let deps: Vec<String> = depends_on.iter().map(|d| format!("\"{d}\"")).collect();
+        std::fs::write(
+            dir.join("skill.toml"),
+            format!("name = \"{name}\"\nentrypoint = \"main.rhai\"\ndepends_on = [{}]\n", deps.join(", ")),
+        )
+        .unwrap();
+        std::fs::write(dir.join("main.rhai"), "loop { sleep_ms(20); }").unwrap();
+    }
+
+    #[test]
+    fn start_order_puts_dependencies_first_and_reports_cycles() {
+        let skills = [
+            manifest("app", &["proxy"]),
+            manifest("proxy", &["dns"]),
+            manifest("dns", &[]),
+            manifest("a", &["b"]),
+            manifest("b", &["a"]),
+            manifest("orphan", &["missing"]),
+        ];
+        let refs: Vec<&SkillManifest> = skills.iter().collect();
+        let plan = plan_start_order(&refs);
+
+        let order: Vec<&str> = plan.order.iter().map(|&i| skills[i].name.as_str()).collect();
+        assert_eq!(order, ["dns", "proxy", "app", "orphan"]);
+        let problems: HashMap<String, String> = plan.problems.into_iter().collect();
+        assert_eq!(problems["a"], "dependency cycle: a -> b -> a");
+        assert_eq!(problems["b"], "dependency cycle: a -> b -> a");
+        assert_eq!(problems["orphan"], "depends on unknown or disabled skill 'missing'");
+        assert!(!problems.contains_key("app"));
+    }
+
+    #[tokio::test]
+    async fn dependent_skill_waits_for_its_dependency() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        write_skill(&skills_dir, "app", &["proxy"]);
+        write_skill(&skills_dir, "proxy", &[]);
+        write_skill(&skills_dir, "loop-a", &["loop-b"]);
+        write_skill(&skills_dir, "loop-b", &["loop-a"]);
+        let mut mgr = SkillManager::new(skills_dir, None, None);
+
+        mgr.reconcile().await.unwrap();
+        assert!(mgr.running.contains_key("proxy"));
+        assert!(!mgr.running.contains_key("app"));
+
+        mgr.reconcile().await.unwrap();
+        assert!(mgr.running.contains_key("app"));
+        assert!(!mgr.running.contains_key("loop-a") && !mgr.running.contains_key("loop-b"));
+        let blocked = mgr.list().into_iter().find(|s| s.name == "loop-a").unwrap();
+        assert!(blocked.dependency_problem.unwrap().starts_with("dependency cycle"));
+
+        mgr.shutdown().await;
+    }
+
+    /// A shell daemon whose health probe logs each run and always fails.
+    fn write_unhealthy_skill(skills_dir: &Path, name: &str, failure_threshold: u32) -> PathBuf {
+        let dir = skills_dir.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("skill.toml"),
+            format!(
+                "name = \"{name}\"\nentrypoint = \"main.sh\"\n\n[health]\n\
+                 command = \"echo probe >> probes.log; exit 1\"\ninterval_secs = 0\n\
+                 failure_threshold = {failure_threshold}\n"
+            ),
+        )
+        .unwrap();
+        std::fs::write(dir.join("main.sh"), "while true; do sleep 0.02; done\n").unwrap();
+        dir.join("probes.log")
+    }
+
+    fn probe_count(log: &Path) -> usize {
+        std::fs::read_to_string(log).map(|s| s.lines().count()).unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn skill_failing_its_probe_threshold_is_restarted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        let log = write_unhealthy_skill(&skills_dir, "wedged", 2);
+        let mgr = tokio::sync::Mutex::new(SkillManager::new(skills_dir, None, None));
+
+        SkillManager::reconcile_probed(&mgr).await.unwrap();
+        assert!(mgr.lock().await.running.contains_key("wedged"));
+        assert_eq!(probe_count(&log), 0);
+
+        SkillManager::reconcile_probed(&mgr).await.unwrap();
+        let health = mgr.lock().await.detail("wedged").unwrap().health.unwrap();
+        assert_eq!(health.last_ok, Some(false));
+        assert_eq!(health.consecutive_failures, 1);
+        assert_eq!(health.restarts, 0);
+        assert!(health.last_error.unwrap().contains("exited"));
+
+        // Second failure hits the threshold: stopped and started again
+        // straight away, since the first restart has no backoff.
+        SkillManager::reconcile_probed(&mgr).await.unwrap();
+        assert_eq!(probe_count(&log), 2);
+        assert!(mgr.lock().await.running.contains_key("wedged"));
+        let health = mgr.lock().await.detail("wedged").unwrap().health.unwrap();
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.restarts, 1);
+
+        // The next restart backs off, leaving the skill down for now.
+        SkillManager::reconcile_probed(&mgr).await.unwrap();
+        SkillManager::reconcile_probed(&mgr).await.unwrap();
+        let mut mgr = mgr.into_inner();
+        assert!(!mgr.running.contains_key("wedged"));
+        assert_eq!(mgr.detail("wedged").unwrap().health.unwrap().restarts, 2);
+
+        mgr.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn manually_stopped_skill_is_not_probed() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        let log = write_unhealthy_skill(&skills_dir, "paused", 1);
+        let mgr = tokio::sync::Mutex::new(SkillManager::new(skills_dir, None, None));
+
+        SkillManager::reconcile_probed(&mgr).await.unwrap();
+        mgr.lock().await.stop_skill_manual("paused").await;
+        for _ in 0..3 {
+            SkillManager::reconcile_probed(&mgr).await.unwrap();
+        }
+
+        let mgr = mgr.into_inner();
+        assert_eq!(probe_count(&log), 0);
+        assert!(!mgr.running.contains_key("paused"));
+        assert!(mgr.detail("paused").unwrap().health.is_none());
+    }
+
+    #[tokio::test]
+    async fn rhai_skill_with_a_command_probe_is_not_started() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        let dir = skills_dir.join("inline");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("skill.toml"),
+            "name = \"inline\"\nentrypoint = \"main.rhai\"\n\n[health]\ncommand = \"true\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("main.rhai"), "loop { sleep_ms(20); }").unwrap();
+        let mut mgr = SkillManager::new(skills_dir, None, None);
+
+        mgr.reconcile().await.unwrap();
+        assert!(!mgr.running.contains_key("inline"));
+    }
+
+    #[tokio::test]
+    async fn import_rejects_manifest_outside_the_schema() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let source = tmp.path().join("src");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("skill.toml"), "name = \"typo\"\nentrypiont = \"main.py\"\n").unwrap();
+        let mgr = SkillManager::new(tmp.path().join("skills"), None, None);
+
+        let err = mgr.import_skill("path", source.to_str().unwrap(), Some("typo"), false).await.unwrap_err();
+        assert!(err.to_string().contains("unknown key `entrypiont`"), "{err}");
+        assert!(!tmp.path().join("skills").join("typo").exists());
+    }
+
+    #[test]
+    fn revert_restores_the_manifest_before_the_last_edit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        write_skill(&skills_dir, "notes", &[]);
+        let mgr = SkillManager::new(skills_dir.clone(), None, None);
+        let original = std::fs::read_to_string(skills_dir.join("notes/skill.toml")).unwrap();
+
+        let edited = "name = \"notes\"\nentrypoint = \"main.rhai\"\ndescription = \"v2\"\n";
+        mgr.update_manifest("notes", edited).unwrap();
+        mgr.update_manifest("notes", &edited.replace("v2", "v3")).unwrap();
+        let history = mgr.manifest_history("notes").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].content, edited);
+        assert_eq!(history[1].content, original);
+
+        assert_eq!(mgr.revert_manifest("notes").unwrap(), edited);
+        assert_eq!(mgr.revert_manifest("notes").unwrap(), original);
+        assert_eq!(std::fs::read_to_string(skills_dir.join("notes/skill.toml")).unwrap(), original);
+        assert!(matches!(mgr.revert_manifest("notes"), Err(SafeAgentError::NotFound(_))));
+    }
+
+    #[test]
+    fn manifest_history_is_capped() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        write_skill(&skills_dir, "notes", &[]);
+        let mgr = SkillManager::new(skills_dir, None, None);
+
+        for i in 0..MANIFEST_HISTORY_LIMIT + 5 {
+            mgr.update_manifest("notes", &format!("name = \"notes\"\ndescription = \"v{i}\"\n")).unwrap();
+        }
+        let history = mgr.manifest_history("notes").unwrap();
+        assert_eq!(history.len(), MANIFEST_HISTORY_LIMIT);
+        let last_saved = MANIFEST_HISTORY_LIMIT + 3;
+        assert!(history[0].content.contains(&format!("\"v{last_saved}\"")));
+        assert!(history.windows(2).all(|w| w[0].version > w[1].version));
+    }
+
+    /// A `.tar.gz` holding `weather/skill.toml`.
+    fn skill_archive() -> Vec<u8> {
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        let manifest = b"name = \"weather\"\nentrypoint = \"main.rhai\"\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        tar.append_data(&mut header, "weather/skill.toml", &manifest[..]).unwrap();
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// Serve a registry index with one `weather` entry signed by `signer`,
+    /// returning the index URL.
+    async fn serve_registry(signer: &ed25519_dalek::SigningKey) -> String {
+        use ed25519_dalek::Signer;
+        use sha2::Digest;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let archive = skill_archive();
+        let sha256 = crate::crypto::hex_encode(&sha2::Sha256::digest(&archive));
+        let signature = signer.sign(&super::super::registry::signing_payload("weather", "1.0.0", &sha256));
+        let index = serde_json::json!({ "skills": [{
+            "name": "weather",
+            "version": "1.0.0",
+            "download_url": format!("{base}/weather-1.0.0.tar.gz"),
+            "sha256": sha256,
+            "publisher": "pegasus",
+            "signature": data_encoding::BASE64.encode(&signature.to_bytes()),
+        }]});
+        let app = axum::Router::new()
+            .route("/index.json", axum::routing::get(move || async move { axum::Json(index) }))
+            .route("/weather-1.0.0.tar.gz", axum::routing::get(move || async move { archive }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("{base}/index.json")
+    }
+
+    #[tokio::test]
+    async fn registry_import_installs_only_verified_skills() {
+        let trusted = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let config = |index_url: String| crate::config::SkillRegistryConfig {
+            index_url,
+            trusted_publishers: HashMap::from([(
+                "pegasus".to_string(),
+                data_encoding::BASE64.encode(trusted.verifying_key().as_bytes()),
+            )]),
+        };
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut mgr = SkillManager::new(tmp.path().join("skills"), None, None);
+        let forged = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+        mgr.set_registry(SkillRegistry::from_config(&config(serve_registry(&forged).await)));
+        let err = mgr.import_skill("registry", "weather", None, true).await.unwrap_err();
+        assert!(err.to_string().contains("signature does not verify"), "{err}");
+        assert!(!tmp.path().join("skills").join("weather").exists());
+
+        mgr.set_registry(SkillRegistry::from_config(&config(serve_registry(&trusted).await)));
+        let (name, dir) = mgr.import_skill("registry", "weather@1.0.0", None, false).await.unwrap();
+        assert_eq!(name, "weather");
+        assert!(dir.join("skill.toml").exists());
+    }
+}
@@ -1,12 +1,17 @@
 pub mod extensions;
+pub mod health;
 pub mod manager;
 pub mod plugin;
 pub mod prompt_skill;
+pub mod registry;
 pub mod resolver;
 pub mod rhai_runtime;
+pub mod schema;
+pub mod token;
 
 pub use extensions::ExtensionManager;
 pub use manager::SkillManager;
 pub use plugin::PluginRegistry;
 pub use prompt_skill::PromptSkill;
 pub use resolver::{always_on_skills, resolve_skills};
+pub use token::SkillTokenStore;
@@ -0,0 +1,310 @@
+//! Curated skill registry.
+//!
+//! Instead of importing from an arbitrary git URL, a skill can be installed
+//! by name from a registry index configured in `[skill_registry]`.  The
+//! index is JSON:
+//!
+//! ```json
+//! { "skills": [ {
+//!     "name": "weather", "version": "1.2.0", "description": "...",
+//!     "download_url": "https://skills.example.com/weather-1.2.0.tar.gz",
+//!     "sha256": "<hex of the archive>",
+//!     "publisher": "pegasus",
+//!     "signature": "<base64 Ed25519 signature>"
+//! } ] }
+//! ```
+//!
+//! The publisher signs [`signing_payload`] — the name, version and archive
+//! hash — so the signature covers exactly the bytes that get installed.
+//! Archives whose hash or signature doesn't check out are always rejected;
+//! unsigned entries and publishers missing from `trusted_publishers` are
+//! rejected unless the import is forced.  Archives over 50 MiB are refused
+//! while they download.
+
+use std::collections::HashMap;
+
+use data_encoding::BASE64;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::config::SkillRegistryConfig;
+use crate::error::{Result, SafeAgentError};
+
+/// Largest skill archive a registry download may be.
+const MAX_ARCHIVE_BYTES: usize = 50 * 1024 * 1024;
+/// Largest registry index accepted.
+const MAX_INDEX_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegistryIndex {
+    #[serde(default)]
+    pub skills: Vec<RegistryEntry>,
+}
+
+/// One published version of a skill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// `.tar.gz` or `.zip` archive of the skill directory.
+    pub download_url: String,
+    /// Hex SHA-256 of the archive.
+    pub sha256: String,
+    #[serde(default)]
+    pub publisher: Option<String>,
+    /// Base64 Ed25519 signature over [`signing_payload`].
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// The bytes a publisher signs for a registry entry.
+pub fn signing_payload(name: &str, version: &str, sha256: &str) -> Vec<u8> {
+    format!("safeclaw-skill\n{name}\n{version}\n{}", sha256.to_ascii_lowercase()).into_bytes()
+}
+
+/// A configured registry and the publishers it trusts.
+pub struct SkillRegistry {
+    index_url: String,
+    trusted_publishers: HashMap<String, String>,
+}
+
+impl SkillRegistry {
+    /// `None` when no index URL is configured.
+    pub fn from_config(config: &SkillRegistryConfig) -> Option<Self> {
+        if config.index_url.trim().is_empty() {
+            return None;
+        }
+        Some(Self {
+            index_url: config.index_url.clone(),
+            trusted_publishers: config.trusted_publishers.clone(),
+        })
+    }
+
+    pub async fn fetch_index(&self) -> Result<RegistryIndex> {
+        let resp = reqwest::get(&self.index_url)
+            .await
+            .map_err(|e| SafeAgentError::Config(format!("fetch skill registry index: {e}")))?;
+        if !resp.status().is_success() {
+            return Err(SafeAgentError::Config(format!(
+                "skill registry index returned HTTP {}",
+                resp.status()
+            )));
+        }
+        let body = read_capped(resp, MAX_INDEX_BYTES, "skill registry index").await?;
+        serde_json::from_slice(&body)
+            .map_err(|e| SafeAgentError::Config(format!("parse skill registry index: {e}")))
+    }
+
+    /// Download and verify the archive for `entry`.  `force` admits
+    /// unsigned entries and untrusted publishers, never a bad signature.
+    pub async fn download(&self, entry: &RegistryEntry, force: bool) -> Result<Vec<u8>> {
+        info!(skill = %entry.name, version = %entry.version, "downloading skill from registry");
+        let resp = reqwest::get(&entry.download_url)
+            .await
+            .map_err(|e| SafeAgentError::Config(format!("download failed: {e}")))?;
+        if !resp.status().is_success() {
+            return Err(SafeAgentError::Config(format!("download returned HTTP {}", resp.status())));
+        }
+        let archive = read_capped(resp, MAX_ARCHIVE_BYTES, "skill archive").await?;
+        self.verify(entry, &archive, force)?;
+        Ok(archive)
+    }
+
+    /// Check `archive` against the entry's hash and publisher signature.
+    pub fn verify(&self, entry: &RegistryEntry, archive: &[u8], force: bool) -> Result<()> {
+        let denied = |why: String| SafeAgentError::PermissionDenied(format!("skill '{}': {why}", entry.name));
+
+        let actual = crate::crypto::hex_encode(&Sha256::digest(archive));
+        if !actual.eq_ignore_ascii_case(entry.sha256.trim()) {
+            return Err(denied(format!("archive SHA-256 {actual} does not match the registry's {}", entry.sha256)));
+        }
+
+        let (Some(publisher), Some(signature)) = (&entry.publisher, &entry.signature) else {
+            if force {
+                warn!(skill = %entry.name, "installing unsigned registry skill (forced)");
+                return Ok(());
+            }
+            return Err(denied("is not signed (force the import to install it anyway)".into()));
+        };
+        let Some(key) = self.trusted_publishers.get(publisher) else {
+            if force {
+                warn!(skill = %entry.name, publisher = %publisher, "installing skill from untrusted publisher (forced)");
+                return Ok(());
+            }
+            return Err(denied(format!(
+                "publisher '{publisher}' is not trusted (force the import to install it anyway)"
+            )));
+        };
+
+        let key: [u8; 32] = BASE64
+            .decode(key.as_bytes())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| SafeAgentError::Config(format!("trusted key for publisher '{publisher}' is malformed")))?;
+        let key = VerifyingKey::from_bytes(&key)
+            .map_err(|_| SafeAgentError::Config(format!("trusted key for publisher '{publisher}' is malformed")))?;
+        let signature: [u8; 64] = BASE64
+            .decode(signature.as_bytes())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| denied("signature is malformed".into()))?;
+        key.verify(
+            &signing_payload(&entry.name, &entry.version, &entry.sha256),
+            &Signature::from_bytes(&signature),
+        )
+        .map_err(|_| denied(format!("signature does not verify against publisher '{publisher}'")))?;
+
+        info!(skill = %entry.name, publisher = %publisher, "registry skill signature verified");
+        Ok(())
+    }
+}
+
+/// Read the body of `resp`, giving up as soon as it passes `limit` bytes
+/// instead of buffering whatever the server sends.
+async fn read_capped(mut resp: reqwest::Response, limit: usize, what: &str) -> Result<Vec<u8>> {
+    let too_large = || SafeAgentError::QuotaExceeded(format!("{what} is larger than {limit} bytes"));
+    if resp.content_length().is_some_and(|len| len > limit as u64) {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| SafeAgentError::Config(format!("failed to read {what}: {e}")))?
+    {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+impl RegistryIndex {
+    /// Find `spec`, either `name` or `name@version`.  Without a version the
+    /// last matching entry wins, since indexes append new releases.
+    pub fn resolve(&self, spec: &str) -> Result<&RegistryEntry> {
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (spec, None),
+        };
+        self.skills
+            .iter()
+            .rev()
+            .find(|e| e.name == name && version.is_none_or(|v| e.version == v))
+            .ok_or_else(|| SafeAgentError::NotFound(format!("skill '{spec}' in the registry")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn publisher_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn signed_entry(archive: &[u8], key: &SigningKey) -> RegistryEntry {
+        let sha256 = crate::crypto::hex_encode(&Sha256::digest(archive));
+        let signature = key.sign(&signing_payload("weather", "1.2.0", &sha256));
+        RegistryEntry {
+            name: "weather".into(),
+            version: "1.2.0".into(),
+            description: String::new(),
+            download_url: "https://skills.example.com/weather-1.2.0.tar.gz".into(),
+            sha256,
+            publisher: Some("pegasus".into()),
+            signature: Some(BASE64.encode(&signature.to_bytes())),
+        }
+    }
+
+    fn registry() -> SkillRegistry {
+        let config = SkillRegistryConfig {
+            index_url: "https://skills.example.com/index.json".into(),
+            trusted_publishers: HashMap::from([(
+                "pegasus".to_string(),
+                BASE64.encode(publisher_key().verifying_key().as_bytes()),
+            )]),
+        };
+        SkillRegistry::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn resolves_latest_or_pinned_version() {
+        let index: RegistryIndex = serde_json::from_value(serde_json::json!({ "skills": [
+            { "name": "weather", "version": "1.0.0", "download_url": "u1", "sha256": "00" },
+            { "name": "notes", "version": "0.1.0", "download_url": "u2", "sha256": "00" },
+            { "name": "weather", "version": "1.1.0", "download_url": "u3", "sha256": "00" },
+        ]}))
+        .unwrap();
+        assert_eq!(index.resolve("weather").unwrap().version, "1.1.0");
+        assert_eq!(index.resolve("weather@1.0.0").unwrap().download_url, "u1");
+        assert!(matches!(index.resolve("weather@2.0.0"), Err(SafeAgentError::NotFound(_))));
+        assert!(index.resolve("missing").is_err());
+    }
+
+    #[test]
+    fn signed_archive_from_trusted_publisher_verifies() {
+        let archive = b"archive bytes";
+        let entry = signed_entry(archive, &publisher_key());
+        registry().verify(&entry, archive, false).unwrap();
+    }
+
+    #[test]
+    fn rejects_bad_signatures_even_when_forced() {
+        let archive = b"archive bytes";
+        let registry = registry();
+
+        let forged = signed_entry(archive, &SigningKey::from_bytes(&[9u8; 32]));
+        let err = registry.verify(&forged, archive, true).unwrap_err().to_string();
+        assert!(err.contains("signature does not verify"), "{err}");
+
+        let entry = signed_entry(archive, &publisher_key());
+        let err = registry.verify(&entry, b"tampered bytes", true).unwrap_err().to_string();
+        assert!(err.contains("does not match"), "{err}");
+    }
+
+    #[test]
+    fn unsigned_and_untrusted_need_force() {
+        let archive = b"archive bytes";
+        let registry = registry();
+
+        let mut unsigned = signed_entry(archive, &publisher_key());
+        unsigned.signature = None;
+        assert!(registry.verify(&unsigned, archive, false).unwrap_err().to_string().contains("not signed"));
+        registry.verify(&unsigned, archive, true).unwrap();
+
+        let mut untrusted = signed_entry(archive, &publisher_key());
+        untrusted.publisher = Some("stranger".into());
+        assert!(registry.verify(&untrusted, archive, false).unwrap_err().to_string().contains("not trusted"));
+        registry.verify(&untrusted, archive, true).unwrap();
+    }
+
+    #[tokio::test]
+    async fn downloads_are_capped_while_streaming() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let app = axum::Router::new()
+            .route("/sized", axum::routing::get(|| async { vec![b'x'; 64] }))
+            .route(
+                "/streamed",
+                axum::routing::get(|| async {
+                    let chunks = (0..8).map(|_| Ok::<_, std::io::Error>(vec![b'x'; 8]));
+                    axum::body::Body::from_stream(tokio_stream::iter(chunks))
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        for path in ["sized", "streamed"] {
+            let get = || reqwest::get(format!("{base}/{path}"));
+            let err = read_capped(get().await.unwrap(), 32, "skill archive").await.unwrap_err();
+            assert!(matches!(err, SafeAgentError::QuotaExceeded(_)), "{path}: {err}");
+            assert_eq!(read_capped(get().await.unwrap(), 64, "skill archive").await.unwrap().len(), 64);
+        }
+    }
+}
@@ -3,7 +3,11 @@
 //! Rhai scripts run in-process on a blocking thread (via `spawn_blocking`)
 //! with a rich API surface: HTTP, file I/O, environment, Telegram, sleep
 //! with cooperative cancellation, and logging to the skill's log file.
+//!
+//! This module also owns the resource limits for all Rhai code, including
+//! skill extension handlers: see [`apply_limits`] and [`run_limited`].
 
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::PathBuf;
@@ -13,6 +17,16 @@ use std::time::{Duration, Instant};
 
 use rhai::{Dynamic, Engine, EvalAltResult, Map};
 
+use crate::config::RhaiConfig;
+
+/// How often (in operations) the progress hook checks the clock.
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+thread_local! {
+    /// Deadline of the [`run_limited`] call running on this thread.
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
 /// Shared context passed into every registered Rhai function.
 #[derive(Clone)]
 pub struct RhaiSkillCtx {
@@ -31,8 +45,13 @@ pub struct RhaiSkillCtx {
 }
 
 /// Build a Rhai `Engine` with the skill API registered.
-pub fn build_engine(ctx: Arc<RhaiSkillCtx>) -> Engine {
+///
+/// Skill scripts are long-running daemons, so only the call-depth and size
+/// limits apply; an operation budget or deadline would eventually stop
+/// every healthy skill.
+pub fn build_engine(ctx: Arc<RhaiSkillCtx>, limits: &RhaiConfig) -> Engine {
     let mut engine = Engine::new();
+    apply_size_limits(&mut engine, limits);
 
     // Redirect `print` and `debug` to the skill log file.
     {
@@ -261,6 +280,64 @@ pub fn run_script(engine: &Engine, script_path: &std::path::Path) -> Result<(),
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Resource limits
+// ---------------------------------------------------------------------------
+
+/// Cap call depth, string length, and array / map size.
+pub fn apply_size_limits(engine: &mut Engine, limits: &RhaiConfig) {
+    engine
+        .set_max_call_levels(limits.max_call_depth)
+        .set_max_string_size(limits.max_string_size)
+        .set_max_array_size(limits.max_collection_size)
+        .set_max_map_size(limits.max_collection_size);
+}
+
+/// Apply every limit: sizes, the operation budget, and the wall-clock
+/// deadline that [`run_limited`] sets for each call.
+pub fn apply_limits(engine: &mut Engine, limits: &RhaiConfig) {
+    apply_size_limits(engine, limits);
+    engine.set_max_operations(limits.max_operations);
+    engine.on_progress(|ops| {
+        if ops % DEADLINE_CHECK_INTERVAL != 0 {
+            return None;
+        }
+        DEADLINE
+            .with(Cell::get)
+            .filter(|deadline| Instant::now() >= *deadline)
+            .map(|_| Dynamic::from("timeout"))
+    });
+}
+
+/// Run one script invocation under `limits.timeout_secs` on an engine set
+/// up by [`apply_limits`].  A limit being hit is reported against `skill`.
+pub fn run_limited<T>(
+    skill: &str,
+    limits: &RhaiConfig,
+    f: impl FnOnce() -> Result<T, Box<EvalAltResult>>,
+) -> Result<T, String> {
+    let deadline = (limits.timeout_secs > 0).then(|| Instant::now() + Duration::from_secs(limits.timeout_secs));
+    let outer = DEADLINE.with(|d| d.replace(deadline));
+    let result = f();
+    DEADLINE.with(|d| d.set(outer));
+
+    result.map_err(|e| match e.unwrap_inner() {
+        EvalAltResult::ErrorTooManyOperations(_) => {
+            format!("skill '{skill}' exceeded its operation limit ({})", limits.max_operations)
+        }
+        EvalAltResult::ErrorStackOverflow(_) => {
+            format!("skill '{skill}' exceeded its call depth limit ({})", limits.max_call_depth)
+        }
+        EvalAltResult::ErrorDataTooLarge(what, _) => {
+            format!("skill '{skill}' exceeded its size limit: {what} too large")
+        }
+        EvalAltResult::ErrorTerminated(..) => {
+            format!("skill '{skill}' timed out after {}s", limits.timeout_secs)
+        }
+        _ => e.to_string(),
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
@@ -343,4 +420,40 @@ mod tests {
         let back = rhai_dynamic_to_json(&dyn_val);
         assert_eq!(json, back);
     }
+
+    fn limited_engine(limits: &RhaiConfig) -> Engine {
+        let mut engine = Engine::new();
+        apply_limits(&mut engine, limits);
+        engine
+    }
+
+    #[test]
+    fn infinite_loop_is_stopped_by_the_operation_limit() {
+        let limits = RhaiConfig { max_operations: 10_000, timeout_secs: 0, ..Default::default() };
+        let engine = limited_engine(&limits);
+        let err = run_limited("spinner", &limits, || engine.run("loop { }")).unwrap_err();
+        assert_eq!(err, "skill 'spinner' exceeded its operation limit (10000)");
+        assert!(run_limited("spinner", &limits, || engine.eval::<i64>("40 + 2")).is_ok_and(|v| v == 42));
+    }
+
+    #[test]
+    fn oversized_string_errors() {
+        let limits = RhaiConfig { max_string_size: 100, ..Default::default() };
+        let engine = limited_engine(&limits);
+        let err = run_limited("hoarder", &limits, || engine.run(r#"let s = "ab"; for i in 0..10 { s += s; }"#))
+            .unwrap_err();
+        assert!(err.starts_with("skill 'hoarder' exceeded its size limit"), "{err}");
+    }
+
+    #[test]
+    fn deadline_stops_a_script_within_its_operation_budget() {
+        let limits = RhaiConfig { max_operations: 0, timeout_secs: 1, ..Default::default() };
+        let engine = limited_engine(&limits);
+        let start = Instant::now();
+        let err = run_limited("sleeper", &limits, || engine.run("loop { }")).unwrap_err();
+        assert_eq!(err, "skill 'sleeper' timed out after 1s");
+        assert!(start.elapsed() < Duration::from_secs(5));
+        // The deadline doesn't outlive the call.
+        assert!(DEADLINE.with(Cell::get).is_none());
+    }
 }
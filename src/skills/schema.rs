@@ -0,0 +1,316 @@
+//! Strict validation of `skill.toml` manifests.
+//!
+//! The runtime reads manifests leniently (unknown keys are ignored, missing
+//! ones defaulted) so existing skills keep working.  Manifests arriving
+//! through import or the dashboard editor are checked against the schema
+//! below instead, and every problem is reported with the key it concerns,
+//! e.g. `` `health.interval_secs`: expected an integer, got string``.
+
+use crate::error::{Result, SafeAgentError};
+
+use super::manager::SkillManifest;
+use super::token::SkillAction;
+
+/// What a manifest value must look like.
+enum Kind {
+    Str,
+    /// A string that isn't empty or only whitespace.
+    NonEmpty,
+    /// A relative path that stays inside the skill directory.
+    RelPath,
+    Bool,
+    /// An integer no smaller than `min`.
+    Int { min: i64 },
+    OneOf(&'static [&'static str]),
+    /// An environment variable name: `[A-Za-z_][A-Za-z0-9_]*`.
+    EnvName,
+    /// A [`SkillAction`] name.
+    Action,
+    Array(&'static Kind),
+    Table(&'static [Field]),
+    /// A table of string values keyed by environment variable names.
+    EnvMap,
+}
+
+struct Field {
+    key: &'static str,
+    kind: Kind,
+    required: bool,
+}
+
+const fn field(key: &'static str, kind: Kind) -> Field {
+    Field { key, kind, required: false }
+}
+
+const fn required(key: &'static str, kind: Kind) -> Field {
+    Field { key, kind, required: true }
+}
+
+const MANIFEST: &[Field] = &[
+    required("name", Kind::NonEmpty),
+    field("description", Kind::Str),
+    field("skill_type", Kind::OneOf(&["daemon", "oneshot"])),
+    field("enabled", Kind::Bool),
+    field("entrypoint", Kind::RelPath),
+    field("venv", Kind::OneOf(&["auto", "always", "never"])),
+    field("env", Kind::EnvMap),
+    field("credentials", Kind::Array(&Kind::Table(CREDENTIAL))),
+    field("permissions", Kind::Table(PERMISSIONS)),
+    field("depends_on", Kind::Array(&Kind::NonEmpty)),
+    field("health", Kind::Table(HEALTH)),
+    field("ui", Kind::Table(UI)),
+    field("network", Kind::Table(NETWORK)),
+];
+
+const CREDENTIAL: &[Field] = &[
+    required("name", Kind::EnvName),
+    field("label", Kind::Str),
+    field("description", Kind::Str),
+    field("required", Kind::Bool),
+];
+
+const PERMISSIONS: &[Field] = &[
+    field("actions", Kind::Array(&Kind::Action)),
+    field("tables", Kind::Array(&Kind::NonEmpty)),
+];
+
+const HEALTH: &[Field] = &[
+    field("http", Kind::NonEmpty),
+    field("command", Kind::NonEmpty),
+    field("interval_secs", Kind::Int { min: 0 }),
+    field("timeout_secs", Kind::Int { min: 1 }),
+    field("failure_threshold", Kind::Int { min: 1 }),
+];
+
+const UI: &[Field] = &[
+    field("panel", Kind::RelPath),
+    field("page", Kind::RelPath),
+    field("style", Kind::RelPath),
+    field("script", Kind::RelPath),
+    field("widget", Kind::Str),
+];
+
+const NETWORK: &[Field] = &[field("allowed_hosts", Kind::Array(&Kind::NonEmpty))];
+
+/// Parse `source` as a skill manifest, rejecting anything outside the
+/// schema.  The error lists every problem found.
+pub fn validate_manifest(source: &str) -> Result<SkillManifest> {
+    let doc: toml::Table = toml::from_str(source)
+        .map_err(|e| SafeAgentError::Config(format!("invalid skill manifest TOML: {e}")))?;
+
+    let mut problems = Vec::new();
+    check_table(&doc, MANIFEST, "", &mut problems);
+    let health = doc.get("health").and_then(toml::Value::as_table);
+    if health.is_some_and(|h| !h.contains_key("http") && !h.contains_key("command")) {
+        problems.push("`health`: needs either `http` or `command`".to_string());
+    }
+    let rhai = doc.get("entrypoint").and_then(toml::Value::as_str).is_some_and(|e| e.ends_with(".rhai"));
+    if rhai && health.is_some_and(|h| h.contains_key("command") && !h.contains_key("http")) {
+        problems.push("`health.command`: command probes are not supported for Rhai skills, use `http`".to_string());
+    }
+    if !problems.is_empty() {
+        return Err(SafeAgentError::Config(format!("invalid skill manifest: {}", problems.join("; "))));
+    }
+
+    toml::from_str(source).map_err(|e| SafeAgentError::Config(format!("invalid skill manifest: {e}")))
+}
+
+fn check_table(table: &toml::Table, fields: &[Field], prefix: &str, problems: &mut Vec<String>) {
+    for f in fields {
+        let path = format!("{prefix}{}", f.key);
+        match table.get(f.key) {
+            Some(value) => check_value(value, &f.kind, &path, problems),
+            None if f.required => problems.push(format!("missing required key `{path}`")),
+            None => {}
+        }
+    }
+    for key in table.keys() {
+        if fields.iter().any(|f| f.key == key) {
+            continue;
+        }
+        let mut problem = format!("unknown key `{prefix}{key}`");
+        if let Some(f) = fields.iter().find(|f| edit_distance(f.key, key) <= 2) {
+            problem.push_str(&format!(" (did you mean `{prefix}{}`?)", f.key));
+        }
+        problems.push(problem);
+    }
+}
+
+fn check_value(value: &toml::Value, kind: &Kind, path: &str, problems: &mut Vec<String>) {
+    match kind {
+        Kind::Array(item) => match value.as_array() {
+            None => problems.push(format!("`{path}`: expected an array, got {}", value.type_str())),
+            Some(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    check_value(v, item, &format!("{path}[{i}]"), problems);
+                }
+            }
+        },
+        Kind::Table(fields) => match value.as_table() {
+            None => problems.push(format!("`{path}`: expected a table, got {}", value.type_str())),
+            Some(t) => check_table(t, fields, &format!("{path}."), problems),
+        },
+        Kind::EnvMap => match value.as_table() {
+            None => problems.push(format!("`{path}`: expected a table, got {}", value.type_str())),
+            Some(t) => {
+                for (key, v) in t {
+                    if !is_env_name(key) {
+                        problems.push(format!("`{path}.{key}`: not a valid environment variable name"));
+                    }
+                    if !v.is_str() {
+                        problems.push(format!("`{path}.{key}`: expected a string, got {}", v.type_str()));
+                    }
+                }
+            }
+        },
+        _ => {
+            if let Some(problem) = scalar_problem(value, kind) {
+                problems.push(format!("`{path}`: {problem}"));
+            }
+        }
+    }
+}
+
+/// What's wrong with a non-container value, if anything.
+fn scalar_problem(value: &toml::Value, kind: &Kind) -> Option<String> {
+    match kind {
+        Kind::Bool if !value.is_bool() => return Some(format!("expected a boolean, got {}", value.type_str())),
+        Kind::Int { min } => {
+            return match value.as_integer() {
+                None => Some(format!("expected an integer, got {}", value.type_str())),
+                Some(n) if n < *min => Some(format!("must be at least {min}, got {n}")),
+                Some(_) => None,
+            };
+        }
+        Kind::Bool | Kind::Array(_) | Kind::Table(_) | Kind::EnvMap => return None,
+        _ => {}
+    }
+    let Some(s) = value.as_str() else {
+        return Some(format!("expected a string, got {}", value.type_str()));
+    };
+    match kind {
+        Kind::NonEmpty | Kind::RelPath if s.trim().is_empty() => Some("must not be empty".into()),
+        Kind::RelPath if s.starts_with('/') || s.split(['/', '\\']).any(|part| part == "..") => {
+            Some(format!("\"{s}\" must be a path inside the skill directory"))
+        }
+        Kind::OneOf(allowed) if !allowed.contains(&s) => {
+            let allowed: Vec<String> = allowed.iter().map(|a| format!("\"{a}\"")).collect();
+            Some(format!("expected one of {}, got \"{s}\"", allowed.join(", ")))
+        }
+        Kind::EnvName if !is_env_name(s) => Some(format!("\"{s}\" is not a valid environment variable name")),
+        Kind::Action if !SkillAction::ALL.iter().any(|a| a.to_string() == s) => {
+            let allowed: Vec<String> = SkillAction::ALL.iter().map(|a| format!("\"{a}\"")).collect();
+            Some(format!("unknown action \"{s}\", expected one of {}", allowed.join(", ")))
+        }
+        _ => None,
+    }
+}
+
+fn is_env_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Levenshtein distance, for suggesting the key a typo was meant to be.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != cb);
+            cur[j + 1] = substitute.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(source: &str) -> String {
+        validate_manifest(source).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn complete_manifest_is_accepted() {
+        let manifest = validate_manifest(
+            r#"
+            name = "weather"
+            skill_type = "oneshot"
+            entrypoint = "src/main.py"
+            depends_on = ["proxy"]
+
+            [env]
+            API_BASE = "https://api.example.com"
+
+            [[credentials]]
+            name = "API_KEY"
+            required = true
+
+            [permissions]
+            actions = ["http", "db_read"]
+
+            [health]
+            command = "test -f ready"
+            failure_threshold = 2
+
+            [ui]
+            panel = "panel.html"
+
+            [network]
+            allowed_hosts = ["api.example.com"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(manifest.name, "weather");
+        assert_eq!(manifest.health.unwrap().failure_threshold, 2);
+    }
+
+    #[test]
+    fn missing_required_field_is_named() {
+        let err = problems("description = \"no name\"\n\n[[credentials]]\nlabel = \"Key\"\n");
+        assert!(err.contains("missing required key `name`"), "{err}");
+        assert!(err.contains("missing required key `credentials[0].name`"), "{err}");
+    }
+
+    #[test]
+    fn unknown_keys_are_flagged_with_suggestions() {
+        let err = problems("name = \"x\"\nentrypiont = \"main.py\"\ncolour = \"red\"\n[ui]\nicon = \"x.png\"\n");
+        assert!(err.contains("unknown key `entrypiont` (did you mean `entrypoint`?)"), "{err}");
+        assert!(err.contains("unknown key `colour`"), "{err}");
+        assert!(err.contains("unknown key `ui.icon`"), "{err}");
+    }
+
+    #[test]
+    fn invalid_values_point_at_their_key() {
+        let err = problems(
+            r#"
+            name = "x"
+            skill_type = "deamon"
+            entrypoint = "../escape.py"
+            env = { "BAD-NAME" = "1", PORT = 8080 }
+            permissions = { actions = ["root"] }
+            health = { interval_secs = "30" }
+            "#,
+        );
+        assert!(err.contains("`skill_type`: expected one of \"daemon\", \"oneshot\", got \"deamon\""), "{err}");
+        assert!(err.contains("`entrypoint`: \"../escape.py\" must be a path inside the skill directory"), "{err}");
+        assert!(err.contains("`env.BAD-NAME`: not a valid environment variable name"), "{err}");
+        assert!(err.contains("`env.PORT`: expected a string, got integer"), "{err}");
+        assert!(err.contains("`permissions.actions[0]`: unknown action \"root\""), "{err}");
+        assert!(err.contains("`health.interval_secs`: expected an integer, got string"), "{err}");
+        assert!(err.contains("`health`: needs either `http` or `command`"), "{err}");
+    }
+
+    #[test]
+    fn rhai_skills_only_take_http_probes() {
+        let err = problems("name = \"x\"\nentrypoint = \"main.rhai\"\n[health]\ncommand = \"true\"\n");
+        assert!(err.contains("command probes are not supported for Rhai skills"), "{err}");
+        validate_manifest("name = \"x\"\nentrypoint = \"main.rhai\"\n[health]\nhttp = \"http://127.0.0.1:1/\"\n")
+            .unwrap();
+    }
+}
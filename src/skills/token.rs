@@ -0,0 +1,433 @@
+//! Capability-scoped tokens for skills calling back into the agent.
+//!
+//! Every skill is issued a [`SkillToken`] when it is launched.  The token
+//! records which actions the skill may perform through the extension API
+//! (SQL reads, SQL writes, outbound HTTP, file I/O, its own credentials) and,
+//! optionally, which database tables it may touch.  Tokens live in a shared
+//! [`SkillTokenStore`]; the skill manager revokes them when the skill stops,
+//! so a stale token can no longer be used once its skill is gone.
+//!
+//! Scopes are declared in the skill's `skill.toml`:
+//!
+//! ```toml
+//! [permissions]
+//! actions = ["db_read", "credentials"]
+//! tables = ["notes"]
+//! ```
+//!
+//! A manifest without a `[permissions]` section keeps the legacy behaviour
+//! of every action on every table.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use tracing::{debug, warn};
+
+/// Environment variable a launched skill receives its token in.
+pub const SKILL_TOKEN_ENV: &str = "SKILL_TOKEN";
+
+/// Request header a skill presents its token in when calling the
+/// extension API over HTTP.
+pub const SKILL_TOKEN_HEADER: &str = "x-skill-token";
+
+/// An action a skill may perform through the extension API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillAction {
+    /// `db_query` — read-only SQL.
+    DbRead,
+    /// `db_execute` — INSERT / UPDATE / DELETE.
+    DbWrite,
+    /// `http_get` / `http_post`.
+    Http,
+    /// `data_read` / `data_write` / `data_list` / `data_exists` / `data_delete`.
+    Files,
+    /// `credential_get` — the skill's own entries in the credential store.
+    Credentials,
+}
+
+impl SkillAction {
+    pub const ALL: [SkillAction; 5] = [
+        SkillAction::DbRead,
+        SkillAction::DbWrite,
+        SkillAction::Http,
+        SkillAction::Files,
+        SkillAction::Credentials,
+    ];
+}
+
+impl std::fmt::Display for SkillAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SkillAction::DbRead => "db_read",
+            SkillAction::DbWrite => "db_write",
+            SkillAction::Http => "http",
+            SkillAction::Files => "files",
+            SkillAction::Credentials => "credentials",
+        };
+        f.write_str(s)
+    }
+}
+
+/// The `[permissions]` section of a skill manifest.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SkillPermissions {
+    /// Actions the skill may perform.
+    #[serde(default = "default_actions")]
+    pub actions: Vec<SkillAction>,
+    /// Tables the skill's SQL may reference.  Empty means unrestricted.
+    #[serde(default)]
+    pub tables: Vec<String>,
+}
+
+fn default_actions() -> Vec<SkillAction> {
+    SkillAction::ALL.to_vec()
+}
+
+impl Default for SkillPermissions {
+    fn default() -> Self {
+        Self {
+            actions: default_actions(),
+            tables: Vec::new(),
+        }
+    }
+}
+
+/// A token issued to a single running skill.
+#[derive(Debug, Clone)]
+pub struct SkillToken {
+    pub skill_name: String,
+    pub secret: String,
+    actions: HashSet<SkillAction>,
+    /// Lower-cased table allowlist; `None` when unrestricted.
+    tables: Option<HashSet<String>>,
+}
+
+impl SkillToken {
+    /// Mint a fresh token for `skill_name` scoped to `permissions`.
+    pub fn issue(skill_name: &str, permissions: &SkillPermissions) -> Self {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let tables = if permissions.tables.is_empty() {
+            None
+        } else {
+            Some(permissions.tables.iter().map(|t| t.to_lowercase()).collect())
+        };
+        Self {
+            skill_name: skill_name.to_string(),
+            secret: crate::crypto::hex_encode(&bytes),
+            actions: permissions.actions.iter().copied().collect(),
+            tables,
+        }
+    }
+
+    /// Whether the token grants `action`.
+    pub fn allows(&self, action: SkillAction) -> bool {
+        self.actions.contains(&action)
+    }
+
+    /// Check `action`, returning a message suitable for handing back to the
+    /// script when it is not granted.
+    pub fn check(&self, action: SkillAction) -> std::result::Result<(), String> {
+        if self.allows(action) {
+            Ok(())
+        } else {
+            Err(format!(
+                "skill '{}' is not permitted to perform '{action}'",
+                self.skill_name
+            ))
+        }
+    }
+
+    /// Check a SQL statement against the token: `db_read` for reads,
+    /// `db_write` for writes, and every referenced table against the
+    /// table allowlist.
+    pub fn check_sql(&self, sql: &str, write: bool) -> std::result::Result<(), String> {
+        self.check(if write { SkillAction::DbWrite } else { SkillAction::DbRead })?;
+
+        if let Some(ref allowed) = self.tables {
+            for table in referenced_tables(sql) {
+                if !allowed.contains(&table) {
+                    return Err(format!(
+                        "skill '{}' is not permitted to access table '{table}'",
+                        self.skill_name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tokens for all currently running skills, keyed by skill name.
+#[derive(Default)]
+pub struct SkillTokenStore {
+    tokens: RwLock<HashMap<String, SkillToken>>,
+}
+
+impl SkillTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issue a new token for `skill_name`, replacing any previous one.
+    pub fn issue(&self, skill_name: &str, permissions: &SkillPermissions) -> SkillToken {
+        let token = SkillToken::issue(skill_name, permissions);
+        debug!(skill = %skill_name, actions = ?permissions.actions, "issued skill token");
+        self.tokens
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(skill_name.to_string(), token.clone());
+        token
+    }
+
+    /// Revoke the token held by `skill_name`, if any.
+    pub fn revoke(&self, skill_name: &str) {
+        if self
+            .tokens
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(skill_name)
+            .is_some()
+        {
+            debug!(skill = %skill_name, "revoked skill token");
+        }
+    }
+
+    /// The live token for `skill_name`, if the skill is running.
+    pub fn get(&self, skill_name: &str) -> Option<SkillToken> {
+        self.tokens
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(skill_name)
+            .cloned()
+    }
+
+    /// Validate a token presented on behalf of `skill_name`.
+    pub fn validate(&self, skill_name: &str, secret: &str) -> Option<SkillToken> {
+        let token = self.get(skill_name)?;
+        if constant_time_eq(token.secret.as_bytes(), secret.as_bytes()) {
+            Some(token)
+        } else {
+            warn!(skill = %skill_name, "invalid skill token presented");
+            None
+        }
+    }
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ---------------------------------------------------------------------------
+// SQL table extraction
+// ---------------------------------------------------------------------------
+
+/// Words that end a table list or can never be a table name.
+const SQL_KEYWORDS: &[&str] = &[
+    "select", "from", "where", "join", "inner", "left", "right", "outer", "cross", "natural",
+    "on", "using", "group", "order", "by", "having", "limit", "offset", "union", "all",
+    "intersect", "except", "values", "set", "returning", "as", "into", "update", "delete",
+    "insert", "or", "replace", "ignore", "default", "with", "recursive", "window",
+];
+
+/// Best-effort list of the table names a SQL statement references (lower-cased).
+///
+/// Looks at identifiers after `FROM`, `JOIN`, `INTO` and `UPDATE`, including
+/// comma-separated `FROM a, b` lists, and skips CTE names declared with
+/// `WITH name AS (...)`.
+pub fn referenced_tables(sql: &str) -> Vec<String> {
+    let tokens = tokenize_sql(sql);
+
+    // Names introduced by a CTE are not real tables.
+    let ctes: HashSet<&str> = tokens
+        .windows(3)
+        .filter(|w| w[1] == "as" && w[2] == "(" && is_identifier(&w[0]))
+        .map(|w| w[0].as_str())
+        .collect();
+
+    let mut tables = Vec::new();
+    let mut push = |name: &str| {
+        let name = name.rsplit('.').next().unwrap_or(name);
+        if !ctes.contains(name) && !tables.iter().any(|t| t == name) {
+            tables.push(name.to_string());
+        }
+    };
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let word = tokens[i].as_str();
+        i += 1;
+        match word {
+            "join" | "into" | "update" => {
+                if let Some(name) = tokens.get(i).filter(|t| is_identifier(t)) {
+                    push(name);
+                    i += 1;
+                }
+            }
+            "from" => {
+                while let Some(name) = tokens.get(i).filter(|t| is_identifier(t)) {
+                    push(name);
+                    i += 1;
+                    // Optional alias: `t`, `AS t`.
+                    if tokens.get(i).is_some_and(|t| t == "as") {
+                        i += 1;
+                    }
+                    if tokens.get(i).is_some_and(|t| is_identifier(t)) {
+                        i += 1;
+                    }
+                    if tokens.get(i).is_some_and(|t| t == ",") {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tables
+}
+
+fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && !SQL_KEYWORDS.contains(&token)
+}
+
+/// Split SQL into lower-cased words and the punctuation `,` `(` `)`.
+/// Quoted identifiers are unquoted; string literals and comments are dropped.
+fn tokenize_sql(sql: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // String literal — skip, handling '' escapes.
+                while let Some(n) = chars.next() {
+                    if n == '\'' {
+                        if chars.peek() == Some(&'\'') {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let ident: String = chars.by_ref().take_while(|&n| n != close).collect();
+                tokens.push(ident.to_lowercase());
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for n in chars.by_ref() {
+                    if n == '\n' {
+                        break;
+                    }
+                }
+            }
+            ',' | '(' | ')' => tokens.push(c.to_string()),
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&n) = chars.peek() {
+                    if n.is_alphanumeric() || n == '_' || n == '.' {
+                        word.push(n);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(word.to_lowercase());
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_only(tables: &[&str]) -> SkillPermissions {
+        SkillPermissions {
+            actions: vec![SkillAction::DbRead, SkillAction::Credentials],
+            tables: tables.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn read_only_token_allows_read() {
+        let token = SkillToken::issue("notes", &read_only(&[]));
+        assert!(token.check_sql("SELECT * FROM notes", false).is_ok());
+    }
+
+    #[test]
+    fn read_only_token_denies_write() {
+        let token = SkillToken::issue("notes", &read_only(&[]));
+        let err = token
+            .check_sql("INSERT INTO notes (body) VALUES ('x')", true)
+            .unwrap_err();
+        assert!(err.contains("db_write"));
+        assert!(token.check(SkillAction::Http).is_err());
+        assert!(token.check(SkillAction::Credentials).is_ok());
+    }
+
+    #[test]
+    fn table_allowlist_enforced() {
+        let token = SkillToken::issue("notes", &read_only(&["notes"]));
+        assert!(token.check_sql("SELECT n.body FROM notes n WHERE n.id = 1", false).is_ok());
+        assert!(token.check_sql("SELECT * FROM notes JOIN users ON 1", false).is_err());
+        assert!(token.check_sql("SELECT * FROM notes, api_tokens", false).is_err());
+        assert!(token
+            .check_sql("WITH recent AS (SELECT * FROM notes) SELECT * FROM recent", false)
+            .is_ok());
+    }
+
+    #[test]
+    fn default_permissions_allow_everything() {
+        let token = SkillToken::issue("legacy", &SkillPermissions::default());
+        for action in SkillAction::ALL {
+            assert!(token.allows(action));
+        }
+        assert!(token.check_sql("DELETE FROM anything", true).is_ok());
+    }
+
+    #[test]
+    fn referenced_tables_extraction() {
+        assert_eq!(
+            referenced_tables("select * from \"Notes\" as n left join tags t on n.id = t.note_id"),
+            vec!["notes", "tags"]
+        );
+        assert_eq!(referenced_tables("UPDATE main.notes SET body = 'from x'"), vec!["notes"]);
+        assert!(referenced_tables("SELECT 1 -- from secrets").is_empty());
+    }
+
+    #[test]
+    fn store_issue_validate_revoke() {
+        let store = SkillTokenStore::new();
+        let token = store.issue("notes", &read_only(&[]));
+        assert!(store.validate("notes", &token.secret).is_some());
+        assert!(store.validate("notes", "wrong").is_none());
+        assert!(store.validate("other", &token.secret).is_none());
+
+        store.revoke("notes");
+        assert!(store.get("notes").is_none());
+        assert!(store.validate("notes", &token.secret).is_none());
+    }
+
+    #[test]
+    fn permissions_parse_from_manifest() {
+        let perms: SkillPermissions =
+            toml::from_str("actions = [\"db_read\", \"credentials\"]\ntables = [\"notes\"]").unwrap();
+        assert_eq!(perms.actions, vec![SkillAction::DbRead, SkillAction::Credentials]);
+        assert_eq!(perms.tables, vec!["notes"]);
+    }
+}
@@ -1,20 +1,26 @@
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use chromiumoxide::browser::{Browser, BrowserConfig};
 use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
 use chromiumoxide::page::ScreenshotParams;
 use futures::StreamExt;
-use tokio::sync::OnceCell;
+use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
 use super::{Tool, ToolContext, ToolOutput};
+use crate::config::BrowserToolConfig;
 use crate::error::Result;
 
-struct BrowserState {
-    browser: Browser,
-    _handler: tokio::task::JoinHandle<()>,
+/// Generic over the browser handle only so tests can stand in a fake one.
+struct BrowserState<B = Browser> {
+    browser: B,
+    /// PID of the launched Chrome, for a hard kill on timeout.
+    pid: Option<u32>,
+    handler: tokio::task::JoinHandle<()>,
 }
 
 /// Headless browser automation tool via Chrome DevTools Protocol.
@@ -29,61 +35,79 @@ struct BrowserState {
 /// - `evaluate` — run arbitrary JavaScript
 /// - `scrape` — structured data extraction via CSS selectors
 /// - `bookmark` — save current page to knowledge graph
-pub struct BrowserTool {
+pub struct BrowserTool<B = Browser> {
     headless: bool,
     data_dir: PathBuf,
-    state: OnceCell<Arc<BrowserState>>,
+    /// Upper bound on each browser operation.
+    timeout: Duration,
+    /// Upper bound on text returned from the page, in characters.
+    max_content_chars: usize,
+    /// Launched lazily; cleared when the browser is killed so the next
+    /// call starts a fresh one.
+    state: Mutex<Option<Arc<BrowserState<B>>>>,
 }
 
 impl BrowserTool {
-    pub fn new(headless: bool, data_dir: PathBuf) -> Self {
+    pub fn new(config: &BrowserToolConfig, data_dir: PathBuf) -> Self {
         Self {
-            headless,
+            headless: config.headless,
             data_dir,
-            state: OnceCell::new(),
+            timeout: Duration::from_secs(config.timeout_secs),
+            max_content_chars: config.max_content_chars,
+            state: Mutex::new(None),
         }
     }
 
     async fn get_or_init(&self) -> std::result::Result<Arc<BrowserState>, String> {
-        self.state
-            .get_or_try_init(|| async {
-                let mut builder = BrowserConfig::builder()
-                    .no_sandbox()
-                    .arg("--disable-gpu")
-                    .arg("--disable-dev-shm-usage");
-
-                if !self.headless {
-                    builder = builder.with_head();
-                }
+        let mut slot = self.state.lock().await;
+        if let Some(state) = slot.as_ref() {
+            return Ok(state.clone());
+        }
+        let state = self.launch().await?;
+        *slot = Some(state.clone());
+        Ok(state)
+    }
 
-                let config = builder.build().map_err(|e| {
-                    format!("Chrome/Chromium not found. Install via the binary installer or ensure google-chrome/chromium is on PATH. ({e})")
-                })?;
+    async fn launch(&self) -> std::result::Result<Arc<BrowserState>, String> {
+        let mut builder = BrowserConfig::builder()
+            .no_sandbox()
+            .arg("--disable-gpu")
+            .arg("--disable-dev-shm-usage");
 
-                let (browser, mut handler) =
-                    Browser::launch(config).await.map_err(|e| {
-                        format!("Chrome/Chromium not found. Install via the binary installer or ensure google-chrome/chromium is on PATH. ({e})")
-                    })?;
+        if !self.headless {
+            builder = builder.with_head();
+        }
 
-                let handle = tokio::spawn(async move {
-                    while let Some(event) = handler.next().await {
-                        if event.is_err() {
-                            warn!("browser handler error: {:?}", event.err());
-                            break;
-                        }
-                    }
-                });
+        let config = builder.build().map_err(|e| {
+            format!("Chrome/Chromium not found. Install via the binary installer or ensure google-chrome/chromium is on PATH. ({e})")
+        })?;
 
-                Ok(Arc::new(BrowserState {
-                    browser,
-                    _handler: handle,
-                }))
-            })
-            .await
-            .cloned()
+        let (mut browser, mut handler) =
+            Browser::launch(config).await.map_err(|e| {
+                format!("Chrome/Chromium not found. Install via the binary installer or ensure google-chrome/chromium is on PATH. ({e})")
+            })?;
+
+        let handle = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    warn!("browser handler error: {:?}", event.err());
+                    break;
+                }
+            }
+        });
+
+        let pid = browser.get_mut_child().and_then(|c| c.inner.id());
+
+        Ok(Arc::new(BrowserState {
+            browser,
+            pid,
+            handler: handle,
+        }))
     }
 
-    /// Get the first open page or create a blank one.
+    /// Get the first open page or create a blank one.  A page that has
+    /// wandered off to an internal address since the last action is
+    /// refused.
     async fn current_page(
         state: &BrowserState,
     ) -> std::result::Result<chromiumoxide::Page, String> {
@@ -93,6 +117,7 @@ impl BrowserTool {
             .await
             .map_err(|e| format!("Browser error: {e}"))?;
         if let Some(p) = pages.into_iter().next() {
+            ensure_public(&p).await?;
             Ok(p)
         } else {
             state
@@ -104,6 +129,71 @@ impl BrowserTool {
     }
 }
 
+impl<B> BrowserTool<B> {
+    /// Run one browser operation under the configured timeout.  On expiry
+    /// the browser is killed outright: a page that stalls CDP tends to wedge
+    /// every later call too.
+    async fn bounded<T>(
+        &self,
+        what: &str,
+        op: impl Future<Output = std::result::Result<T, String>>,
+    ) -> std::result::Result<T, String> {
+        match tokio::time::timeout(self.timeout, op).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.kill_browser().await;
+                Err(format!(
+                    "Browser error: {what} timed out after {} seconds; the browser was restarted",
+                    self.timeout.as_secs()
+                ))
+            }
+        }
+    }
+
+    /// Kill the running browser, if any.  Chrome is spawned with
+    /// kill-on-drop, so dropping the last handle finishes the job on
+    /// platforms without signals.
+    async fn kill_browser(&self) {
+        let Some(state) = self.state.lock().await.take() else {
+            return;
+        };
+        warn!(pid = ?state.pid, "killing unresponsive browser");
+        #[cfg(unix)]
+        if let Some(pid) = state.pid {
+            unsafe {
+                libc::kill(pid as i32, libc::SIGKILL);
+            }
+        }
+        state.handler.abort();
+    }
+
+    /// Cap page-derived text at `max_content_chars`.
+    fn cap(&self, text: String) -> String {
+        truncate_content(text, self.max_content_chars)
+    }
+}
+
+/// The page's URL, unless it fails [`crate::security::validate_url`]: the
+/// model's URL is checked before navigating, but a server redirect, a click
+/// or a script can still land the page on an internal host.  Such a page is
+/// closed so no later action reads it.
+async fn ensure_public(page: &chromiumoxide::Page) -> std::result::Result<String, String> {
+    let url = page
+        .url()
+        .await
+        .map_err(|e| format!("Browser error: {e}"))?
+        .unwrap_or_default();
+    if url.is_empty() || url == "about:blank" {
+        return Ok(url);
+    }
+    if let Err(e) = crate::security::validate_url(&url) {
+        warn!(url = %url, "closing browser page that reached a refused address");
+        let _ = page.clone().close().await;
+        return Err(format!("refusing page at {url}: {e}"));
+    }
+    Ok(url)
+}
+
 #[async_trait]
 impl Tool for BrowserTool {
     fn name(&self) -> &str {
@@ -181,6 +271,18 @@ impl Tool for BrowserTool {
 
         debug!(action, headless = self.headless, "browser action");
 
+        // Refuse internal targets before a browser is even launched.
+        if matches!(action, "navigate" | "auth_navigate") {
+            let url = params.get("url").and_then(|v| v.as_str()).unwrap_or_default();
+            let checked = match url {
+                "" => Ok(()),
+                url => crate::security::validate_url(url).map(|_| ()),
+            };
+            if let Err(e) = checked {
+                return Ok(ToolOutput::error(format!("refusing to navigate to {url}: {e}")));
+            }
+        }
+
         let state = match self.get_or_init().await {
             Ok(s) => s,
             Err(e) => return Ok(ToolOutput::error(e)),
@@ -197,31 +299,26 @@ impl Tool for BrowserTool {
                     return Ok(ToolOutput::error("url is required for navigate"));
                 }
 
-                let nav_result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let nav_result = self.bounded(
+                    "navigation",
                     async {
                         let page = state.browser.new_page(url).await.map_err(|e| {
                             format!("Browser error: {e}")
                         })?;
+                        let current_url = ensure_public(&page).await?;
                         let title = page.get_title().await
                             .map_err(|e| format!("Browser error: {e}"))?
                             .unwrap_or_default();
-                        let current_url = page.url().await
-                            .map_err(|e| format!("Browser error: {e}"))?
-                            .unwrap_or_default();
                         Ok::<_, String>((title, current_url))
                     },
                 )
                 .await;
 
                 match nav_result {
-                    Ok(Ok((title, current_url))) => Ok(ToolOutput::ok(format!(
+                    Ok((title, current_url)) => Ok(ToolOutput::ok(format!(
                         "Navigated to {current_url}\nTitle: {title}"
                     ))),
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: navigation timed out after 30 seconds",
-                    )),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
@@ -269,12 +366,14 @@ impl Tool for BrowserTool {
                     }
                 };
 
-                let nav_result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let nav_result = self.bounded(
+                    "auth_navigate",
                     async {
                         let page = state.browser.new_page(url).await.map_err(|e| {
                             format!("Browser error: {e}")
                         })?;
+                        // Don't hand the token to wherever a redirect led.
+                        ensure_public(&page).await?;
 
                         // Inject the OAuth token as an Authorization header via CDP
                         // fetch.enable + requestPaused interception.
@@ -321,11 +420,7 @@ impl Tool for BrowserTool {
                             .await
                             .map_err(|e| format!("Browser error: {e}"))?
                             .unwrap_or_default();
-                        let current_url = page
-                            .url()
-                            .await
-                            .map_err(|e| format!("Browser error: {e}"))?
-                            .unwrap_or_default();
+                        let current_url = ensure_public(&page).await?;
 
                         Ok::<_, String>((title, current_url))
                     },
@@ -333,16 +428,13 @@ impl Tool for BrowserTool {
                 .await;
 
                 match nav_result {
-                    Ok(Ok((title, current_url))) => Ok(ToolOutput::ok(format!(
+                    Ok((title, current_url)) => Ok(ToolOutput::ok(format!(
                         "Authenticated navigation to {current_url}\n\
                          Title: {title}\n\
                          Provider: {provider}\n\
                          OAuth token injected into fetch/XHR requests."
                     ))),
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: auth_navigate timed out after 30 seconds",
-                    )),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
@@ -359,8 +451,8 @@ impl Tool for BrowserTool {
                 let filename = format!("{timestamp}.png");
                 let path = screenshot_dir.join(&filename);
 
-                let result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let result = self.bounded(
+                    "screenshot",
                     async {
                         let page = Self::current_page(&state).await?;
                         let png_bytes = page
@@ -383,14 +475,11 @@ impl Tool for BrowserTool {
                 .await;
 
                 match result {
-                    Ok(Ok(p)) => Ok(ToolOutput::ok(format!(
+                    Ok(p) => Ok(ToolOutput::ok(format!(
                         "Screenshot saved to {}",
                         p.display()
                     ))),
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: screenshot timed out after 30 seconds",
-                    )),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
@@ -407,8 +496,8 @@ impl Tool for BrowserTool {
                 let filename = format!("{timestamp}.png");
                 let path = screenshot_dir.join(&filename);
 
-                let result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let result = self.bounded(
+                    "screenshot_describe",
                     async {
                         let page = Self::current_page(&state).await?;
 
@@ -452,12 +541,8 @@ impl Tool for BrowserTool {
                 .await;
 
                 match result {
-                    Ok(Ok((p, elements, title, url))) => {
-                        let truncated = if elements.len() > 6000 {
-                            format!("{}...\n[truncated]", &elements[..6000])
-                        } else {
-                            elements
-                        };
+                    Ok((p, elements, title, url)) => {
+                        let truncated = self.cap(elements);
                         Ok(ToolOutput::ok(format!(
                             "Screenshot saved to {}\n\
                              URL: {url}\n\
@@ -468,10 +553,7 @@ impl Tool for BrowserTool {
                             p.display()
                         )))
                     }
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: screenshot_describe timed out after 30 seconds",
-                    )),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
@@ -491,8 +573,8 @@ impl Tool for BrowserTool {
                     ));
                 }
 
-                let result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let result = self.bounded(
+                    "click_element",
                     async {
                         let page = Self::current_page(&state).await?;
 
@@ -536,11 +618,7 @@ impl Tool for BrowserTool {
                         // Wait briefly for any navigation or DOM updates
                         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
 
-                        let new_url = page
-                            .url()
-                            .await
-                            .map_err(|e| format!("Browser error: {e}"))?
-                            .unwrap_or_default();
+                        let new_url = ensure_public(&page).await?;
 
                         Ok::<_, String>(format!("{result}\nCurrent URL: {new_url}"))
                     },
@@ -548,18 +626,15 @@ impl Tool for BrowserTool {
                 .await;
 
                 match result {
-                    Ok(Ok(output)) => Ok(ToolOutput::ok(output)),
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: click_element timed out after 30 seconds",
-                    )),
+                    Ok(output) => Ok(ToolOutput::ok(output)),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
             // ── snapshot ────────────────────────────────────────────────
             "snapshot" => {
-                let result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let result = self.bounded(
+                    "snapshot",
                     async {
                         let pages = state.browser.pages().await
                             .map_err(|e| format!("Browser error: {e}"))?;
@@ -568,6 +643,7 @@ impl Tool for BrowserTool {
                         } else {
                             return Ok::<_, String>("No page open. Use navigate first.".to_string());
                         };
+                        ensure_public(&page).await?;
 
                         let text: String = page
                             .evaluate("document.body.innerText")
@@ -576,23 +652,14 @@ impl Tool for BrowserTool {
                             .into_value()
                             .map_err(|e| format!("Browser error: {e}"))?;
 
-                        let truncated = if text.len() > 8000 {
-                            format!("{}...\n[truncated at 8000 chars]", &text[..8000])
-                        } else {
-                            text
-                        };
-
-                        Ok(truncated)
+                        Ok(self.cap(text))
                     },
                 )
                 .await;
 
                 match result {
-                    Ok(Ok(text)) => Ok(ToolOutput::ok(text)),
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: snapshot timed out after 30 seconds",
-                    )),
+                    Ok(text) => Ok(ToolOutput::ok(text)),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
@@ -606,8 +673,8 @@ impl Tool for BrowserTool {
                     return Ok(ToolOutput::error("script is required for evaluate"));
                 }
 
-                let result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let result = self.bounded(
+                    "evaluation",
                     async {
                         let page = Self::current_page(&state).await?;
 
@@ -619,20 +686,19 @@ impl Tool for BrowserTool {
                         let value: serde_json::Value = eval_result
                             .into_value()
                             .unwrap_or(serde_json::Value::Null);
+                        // The script may have navigated the page.
+                        ensure_public(&page).await?;
 
-                        Ok::<_, String>(serde_json::to_string_pretty(&value).unwrap_or_else(|_| {
+                        Ok::<_, String>(self.cap(serde_json::to_string_pretty(&value).unwrap_or_else(|_| {
                             value.to_string()
-                        }))
+                        })))
                     },
                 )
                 .await;
 
                 match result {
-                    Ok(Ok(output)) => Ok(ToolOutput::ok(output)),
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: evaluation timed out after 30 seconds",
-                    )),
+                    Ok(output) => Ok(ToolOutput::ok(output)),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
@@ -655,8 +721,8 @@ impl Tool for BrowserTool {
                     })
                     .unwrap_or_default();
 
-                let result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let result = self.bounded(
+                    "scrape",
                     async {
                         let page = Self::current_page(&state).await?;
 
@@ -708,23 +774,14 @@ impl Tool for BrowserTool {
                         let pretty =
                             serde_json::to_string_pretty(&parsed).unwrap_or_else(|_| parsed.to_string());
 
-                        let truncated = if pretty.len() > 10000 {
-                            format!("{}...\n[truncated at 10000 chars]", &pretty[..10000])
-                        } else {
-                            pretty
-                        };
-
-                        Ok::<_, String>(truncated)
+                        Ok::<_, String>(self.cap(pretty))
                     },
                 )
                 .await;
 
                 match result {
-                    Ok(Ok(output)) => Ok(ToolOutput::ok(output)),
-                    Ok(Err(e)) => Ok(ToolOutput::error(e)),
-                    Err(_) => Ok(ToolOutput::error(
-                        "Browser error: scrape timed out after 30 seconds",
-                    )),
+                    Ok(output) => Ok(ToolOutput::ok(output)),
+                    Err(e) => Ok(ToolOutput::error(e)),
                 }
             }
 
@@ -744,8 +801,8 @@ impl Tool for BrowserTool {
                     })
                     .unwrap_or_default();
 
-                let result = tokio::time::timeout(
-                    std::time::Duration::from_secs(30),
+                let result = self.bounded(
+                    "bookmark",
                     async {
                         let page = Self::current_page(&state).await?;
 
@@ -793,13 +850,8 @@ impl Tool for BrowserTool {
                 .await;
 
                 let (url, title, description, tag_list) = match result {
-                    Ok(Ok(data)) => data,
-                    Ok(Err(e)) => return Ok(ToolOutput::error(e)),
-                    Err(_) => {
-                        return Ok(ToolOutput::error(
-                            "Browser error: bookmark timed out after 30 seconds",
-                        ))
-                    }
+                    Ok(data) => data,
+                    Err(e) => return Ok(ToolOutput::error(e)),
                 };
 
                 // Store in knowledge graph
@@ -877,6 +929,14 @@ impl Tool for BrowserTool {
     }
 }
 
+/// Cut `text` to at most `max_chars` characters, marking the cut.
+fn truncate_content(text: String, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...\n[truncated at {max_chars} chars]", &text[..end]),
+        None => text,
+    }
+}
+
 /// JavaScript that builds a numbered map of interactive elements with their
 /// bounding boxes, text, and attributes. Used by `screenshot_describe` for
 /// visual grounding — the LLM can reference elements by index.
@@ -910,3 +970,97 @@ const ELEMENT_MAP_SCRIPT: &str = r#"
     return lines.join('\n');
 })()
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::MessagingManager;
+    use crate::security::SandboxedFs;
+    use crate::trash::TrashManager;
+
+    fn test_ctx(base: &std::path::Path) -> ToolContext {
+        ToolContext {
+            sandbox: SandboxedFs::new(base.join("sandbox")).unwrap(),
+            db: crate::db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(&base.join("trash")).unwrap()),
+        }
+    }
+
+    fn tool(base: &std::path::Path) -> BrowserTool {
+        BrowserTool::new(&BrowserToolConfig::default(), base.to_path_buf())
+    }
+
+    #[tokio::test]
+    async fn navigate_refuses_internal_urls() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        let tool = tool(tmp.path());
+
+        for url in ["http://127.0.0.1:8080/admin", "http://localhost/", "file:///etc/passwd"] {
+            let r = tool
+                .execute(serde_json::json!({"action": "navigate", "url": url}), &ctx)
+                .await
+                .unwrap();
+            assert!(!r.success);
+            assert!(r.output.contains("refusing to navigate"), "{}", r.output);
+        }
+        let r = tool
+            .execute(
+                serde_json::json!({"action": "auth_navigate", "url": "http://10.0.0.1/", "provider": "github"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(r.output.contains("refusing to navigate"));
+        // Refused before any browser was launched.
+        assert!(tool.state.lock().await.is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn operation_past_timeout_is_aborted() {
+        // A stand-in browser: a process to kill and a handler task.
+        let mut chrome = tokio::process::Command::new("sleep")
+            .arg("30")
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        let handler = tokio::spawn(std::future::pending::<()>());
+        let tool = BrowserTool::<()> {
+            headless: true,
+            data_dir: std::env::temp_dir(),
+            timeout: Duration::from_millis(20),
+            max_content_chars: 1_000,
+            state: Mutex::new(Some(Arc::new(BrowserState { browser: (), pid: chrome.id(), handler }))),
+        };
+
+        let started = tokio::time::Instant::now();
+        let result = tool
+            .bounded("navigation", std::future::pending::<std::result::Result<(), String>>())
+            .await;
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let err = result.unwrap_err();
+        assert!(err.contains("navigation timed out"), "{err}");
+        assert!(tool.state.lock().await.is_none());
+        let status = tokio::time::timeout(Duration::from_secs(5), chrome.wait()).await.unwrap().unwrap();
+        assert!(!status.success());
+
+        let ok = tool.bounded("snapshot", async { Ok::<_, String>(7) }).await;
+        assert_eq!(ok, Ok(7));
+    }
+
+    #[test]
+    fn oversized_content_is_truncated() {
+        let text = "é".repeat(50);
+        let capped = truncate_content(text, 10);
+        assert!(capped.starts_with(&"é".repeat(10)));
+        assert!(!capped.starts_with(&"é".repeat(11)));
+        assert!(capped.ends_with("[truncated at 10 chars]"));
+
+        assert_eq!(truncate_content("short".into(), 10), "short");
+        assert_eq!(truncate_content("exactly10!".into(), 10), "exactly10!");
+    }
+}
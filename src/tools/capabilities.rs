@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+
+use super::{Tool, ToolContext, ToolOutput};
+use crate::error::Result;
+
+/// Lets the LLM answer "what can you do?" from the live capability
+/// manifest.  The agent answers the call itself, since tools can't see the
+/// registries (see [`crate::agent::capabilities`]).
+pub struct CapabilitiesTool;
+
+#[async_trait]
+impl Tool for CapabilitiesTool {
+    fn name(&self) -> &str {
+        "capabilities"
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Report what the agent can currently do: enabled tools with descriptions, skills, \
+         configured LLM backends, and whether it is paused or in safe mode. Use this when \
+         the user asks what you can do."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object", "properties": {} })
+    }
+
+    async fn execute(&self, _params: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        Ok(ToolOutput::error("capabilities is only available to the agent"))
+    }
+}
@@ -122,7 +122,7 @@ impl Tool for ExecTool {
 
 /// Build a Command with platform-appropriate shell, trash-aware PATH, and
 /// resource limits.
-fn build_sandboxed_command(
+pub(super) fn build_sandboxed_command(
     shell_cmd: &str,
     work_dir: &std::path::Path,
     trash: &crate::trash::TrashManager,
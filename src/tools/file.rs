@@ -14,8 +14,12 @@ impl Tool for ReadFileTool {
         "read_file"
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
-        "Read a file from the sandboxed data directory. Returns the file contents as text."
+        "Read a file from the sandboxed data directory. Returns the file contents as text; use offset/limit to read large files in pieces."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -26,6 +30,14 @@ impl Tool for ReadFileTool {
                 "path": {
                     "type": "string",
                     "description": "Relative path within the sandbox"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "First line to return, starting at 1 (default 1)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of lines to return (default: all)"
                 }
             }
         })
@@ -44,8 +56,17 @@ impl Tool for ReadFileTool {
         let rel = std::path::Path::new(path);
         debug!(?rel, "reading file");
 
+        let offset = params.get("offset").and_then(|v| v.as_u64());
+        let limit = params.get("limit").and_then(|v| v.as_u64());
+
         match ctx.sandbox.read_to_string(rel) {
-            Ok(contents) => Ok(ToolOutput::ok(contents)),
+            Ok(contents) if offset.is_none() && limit.is_none() => Ok(ToolOutput::ok(contents)),
+            Ok(contents) => {
+                let skip = offset.unwrap_or(1).saturating_sub(1) as usize;
+                let take = limit.map_or(usize::MAX, |l| l as usize);
+                let lines: Vec<&str> = contents.lines().skip(skip).take(take).collect();
+                Ok(ToolOutput::ok(lines.join("\n")))
+            }
             Err(e) => Ok(ToolOutput::error(format!("failed to read: {e}"))),
         }
     }
@@ -265,6 +286,24 @@ mod tests {
         std::fs::remove_dir_all(&base).ok();
     }
 
+    #[tokio::test]
+    async fn read_file_offset_limit() {
+        let base = std::env::temp_dir().join(format!("sa-test-readpage-{}", std::process::id()));
+        let ctx = test_ctx(&base);
+        ctx.sandbox.write(std::path::Path::new("lines.txt"), b"one\ntwo\nthree\nfour\n").unwrap();
+        let result = ReadFileTool
+            .execute(serde_json::json!({"path": "lines.txt", "offset": 2, "limit": 2}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.output, "two\nthree");
+        let result = ReadFileTool
+            .execute(serde_json::json!({"path": "lines.txt", "offset": 4}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.output, "four");
+        std::fs::remove_dir_all(&base).ok();
+    }
+
     #[tokio::test]
     async fn read_file_not_found() {
         let base = std::env::temp_dir().join(format!("sa-test-readnf-{}", std::process::id()));
@@ -402,6 +441,45 @@ mod tests {
         assert_eq!(schema["type"], "object");
         assert!(schema["required"].as_array().unwrap().contains(&serde_json::json!("path")));
     }
+
+    const ORIGINAL: &str = "fn main() {\n    let a = 1;\n    let b = 2;\n}\n\nfn helper() {\n    todo!()\n}\n";
+
+    #[tokio::test]
+    async fn apply_patch_multi_hunk() {
+        let base = std::env::temp_dir().join(format!("sa-test-patch-{}", std::process::id()));
+        let ctx = test_ctx(&base);
+        ctx.sandbox.write(std::path::Path::new("src/main.rs"), ORIGINAL.as_bytes()).unwrap();
+        let patch = "--- a/src/main.rs\n+++ b/src/main.rs\n\
+                     @@ -1,3 +1,3 @@\n fn main() {\n-    let a = 1;\n+    let a = 10;\n     let b = 2;\n\
+                     @@ -6,3 +6,3 @@\n fn helper() {\n-    todo!()\n+    println!(\"hi\");\n }\n";
+        let result = ApplyPatchTool.execute(serde_json::json!({"patch": patch}), &ctx).await.unwrap();
+        assert!(result.success, "{}", result.output);
+        assert_eq!(
+            ctx.sandbox.read_to_string(std::path::Path::new("src/main.rs")).unwrap(),
+            "fn main() {\n    let a = 10;\n    let b = 2;\n}\n\nfn helper() {\n    println!(\"hi\");\n}\n"
+        );
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[tokio::test]
+    async fn apply_patch_mismatch_changes_nothing() {
+        let base = std::env::temp_dir().join(format!("sa-test-patchbad-{}", std::process::id()));
+        let ctx = test_ctx(&base);
+        ctx.sandbox.write(std::path::Path::new("src/main.rs"), ORIGINAL.as_bytes()).unwrap();
+        ctx.sandbox.write(std::path::Path::new("notes.txt"), b"one\ntwo\n").unwrap();
+        // The first file and first hunk apply; the last hunk's context is stale
+        let patch = "--- a/notes.txt\n+++ b/notes.txt\n@@ -1,2 +1,2 @@\n-one\n+uno\n two\n\
+                     --- a/src/main.rs\n+++ b/src/main.rs\n\
+                     @@ -1,2 +1,2 @@\n fn main() {\n-    let a = 1;\n+    let a = 10;\n\
+                     @@ -6,3 +6,3 @@\n fn helper() {\n-    unimplemented!()\n+    println!(\"hi\");\n }\n";
+        let result = ApplyPatchTool.execute(serde_json::json!({"patch": patch}), &ctx).await.unwrap();
+        assert!(!result.success);
+        assert!(result.output.contains("no files changed"), "{}", result.output);
+        assert!(result.output.contains("src/main.rs: hunk 2 (@@ -6,3 +6,3 @@)"), "{}", result.output);
+        assert_eq!(ctx.sandbox.read_to_string(std::path::Path::new("src/main.rs")).unwrap(), ORIGINAL);
+        assert_eq!(ctx.sandbox.read_to_string(std::path::Path::new("notes.txt")).unwrap(), "one\ntwo\n");
+        std::fs::remove_dir_all(&base).ok();
+    }
 }
 
 // -- ApplyPatch ----------------------------------------------------------
@@ -415,7 +493,8 @@ impl Tool for ApplyPatchTool {
     }
 
     fn description(&self) -> &str {
-        "Apply a unified diff patch to files in the sandbox."
+        "Apply a unified diff patch to files in the sandbox. Every hunk is checked against the current \
+         files first; if any doesn't match, the whole patch is rejected and nothing changes."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -441,31 +520,88 @@ impl Tool for ApplyPatchTool {
             return Ok(ToolOutput::error("patch content is required"));
         }
 
-        // Write patch to temp file and apply with `patch` command
-        let patch_path = ctx.sandbox.resolve(std::path::Path::new(".tmp_patch"))?;
-        std::fs::write(&patch_path, patch)?;
-
-        let output = tokio::process::Command::new("patch")
-            .arg("-p1")
-            .arg("-i")
-            .arg(&patch_path)
-            .current_dir(ctx.sandbox.root())
-            .output()
-            .await;
-
-        let _ = std::fs::remove_file(&patch_path);
-
-        match output {
-            Ok(out) => {
-                let text = String::from_utf8_lossy(&out.stdout);
-                let err = String::from_utf8_lossy(&out.stderr);
-                if out.status.success() {
-                    Ok(ToolOutput::ok(format!("{text}{err}")))
-                } else {
-                    Ok(ToolOutput::error(format!("patch failed: {text}{err}")))
+        let files = match super::patch::parse(patch) {
+            Ok(f) => f,
+            Err(e) => return Ok(ToolOutput::error(format!("invalid patch, no files changed: {e}"))),
+        };
+
+        // Apply every hunk in memory first; nothing is written unless the
+        // whole patch applies.  `None` contents mean the file is deleted.
+        let mut results: Vec<(String, Option<String>)> = Vec::new();
+        for file in &files {
+            let path = file.path().to_string();
+            let pending = results.iter().position(|(p, _)| *p == path);
+            let current = match (pending, &file.old_path) {
+                (Some(i), _) => results[i].1.clone().unwrap_or_default(),
+                (None, None) if ctx.sandbox.resolve(std::path::Path::new(&path))?.exists() => {
+                    return Ok(ToolOutput::error(format!(
+                        "patch rejected, no files changed: {path} already exists"
+                    )));
+                }
+                (None, None) => String::new(),
+                (None, Some(_)) => match ctx.sandbox.read_to_string(std::path::Path::new(&path)) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        return Ok(ToolOutput::error(format!(
+                            "patch rejected, no files changed: failed to read {path}: {e}"
+                        )));
+                    }
+                },
+            };
+            let updated = match super::patch::apply(&current, file) {
+                Ok(u) => u,
+                Err(e) => return Ok(ToolOutput::error(format!("patch rejected, no files changed: {e}"))),
+            };
+            let updated = match file.new_path {
+                Some(_) => Some(updated),
+                None if updated.is_empty() => None,
+                None => {
+                    return Ok(ToolOutput::error(format!(
+                        "patch rejected, no files changed: {path} is deleted but lines would remain"
+                    )));
+                }
+            };
+            match pending {
+                Some(i) => results[i].1 = updated,
+                None => results.push((path, updated)),
+            }
+        }
+
+        // Write, restoring the files already written if one fails
+        let mut written: Vec<(&str, Option<String>)> = Vec::new();
+        for (path, contents) in &results {
+            let rel = std::path::Path::new(path);
+            let abs = ctx.sandbox.resolve(rel)?;
+            let previous = std::fs::read_to_string(&abs).ok();
+            let outcome = match contents {
+                Some(c) => abs
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .map_err(crate::error::SafeAgentError::from)
+                    .and_then(|()| ctx.sandbox.write(rel, c.as_bytes())),
+                None => ctx.trash.trash(&abs, "tool:apply_patch").map(drop),
+            };
+            if let Err(e) = outcome {
+                for (path, previous) in written.into_iter().rev() {
+                    let rel = std::path::Path::new(path);
+                    let _ = match previous {
+                        Some(p) => ctx.sandbox.write(rel, p.as_bytes()),
+                        None => ctx.sandbox.resolve(rel).and_then(|abs| Ok(std::fs::remove_file(abs)?)),
+                    };
                 }
+                return Ok(ToolOutput::error(format!("failed to write {path}, patch rolled back: {e}")));
             }
-            Err(e) => Ok(ToolOutput::error(format!("failed to run patch: {e}"))),
+            written.push((path, previous));
         }
+
+        let summary: Vec<String> = results
+            .iter()
+            .map(|(path, contents)| match contents {
+                Some(_) => format!("patched {path}"),
+                None => format!("deleted {path} (moved to trash)"),
+            })
+            .collect();
+        info!(files = results.len(), "patch applied");
+        Ok(ToolOutput::ok(summary.join("\n")))
     }
 }
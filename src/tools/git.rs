@@ -0,0 +1,668 @@
+//! Structured git operations on repositories inside the sandbox.
+//!
+//! Inspection (status, diff, log, branch listing) and local writes (add,
+//! commit, branch creation) behave like any other tool call.  `push`,
+//! `clean` and `reset --hard` can lose or publish work, so they always go
+//! to the approval queue, even when `git` is in `auto_approve_tools`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::process::Command;
+use tracing::debug;
+
+use super::{Tool, ToolContext, ToolOutput};
+use crate::error::{Result, SafeAgentError};
+use crate::security::SandboxedFs;
+
+const DEFAULT_LOG_LIMIT: u64 = 20;
+const MAX_LOG_LIMIT: u64 = 200;
+
+pub struct GitTool {
+    timeout_secs: u64,
+}
+
+impl GitTool {
+    pub fn new(timeout_secs: u64) -> Self {
+        Self { timeout_secs }
+    }
+
+    /// Run git in `repo`.  Returns stdout, or an error message built from
+    /// stderr when git fails.
+    async fn run(&self, repo: &Path, args: &[&str]) -> std::result::Result<String, String> {
+        debug!(?repo, ?args, "running git");
+        let mut cmd = self.command(repo);
+        for name in self.filter_drivers(repo).await? {
+            for key in ["clean", "smudge", "process"] {
+                cmd.arg("-c").arg(format!("filter.{name}.{key}="));
+            }
+        }
+        cmd.args(args);
+
+        let output = self.output(cmd).await?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(format!(
+                "git {} failed (exit code {}): {}",
+                args.first().unwrap_or(&""),
+                output.status.code().unwrap_or(-1),
+                stderr.trim()
+            ))
+        }
+    }
+
+    /// A git command in `repo` with every config setting that would let the
+    /// repo run a program of its choosing overridden: hooks, fsmonitor,
+    /// signing, SSH and `ext::` transports.  External diff and textconv
+    /// drivers are turned off per command (`--no-ext-diff --no-textconv`)
+    /// and filter drivers by [`Self::run`].
+    fn command(&self, repo: &Path) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.arg("-C").arg(repo);
+        for setting in [
+            "core.hooksPath=/dev/null",
+            "core.fsmonitor=false",
+            "core.sshCommand=ssh",
+            "core.askPass=",
+            "credential.helper=",
+            "commit.gpgSign=false",
+            "tag.gpgSign=false",
+            "protocol.ext.allow=never",
+            "diff.external=",
+        ] {
+            cmd.arg("-c").arg(setting);
+        }
+        cmd.env("GIT_TERMINAL_PROMPT", "0").kill_on_drop(true);
+        if let Some(parent) = repo.parent() {
+            cmd.env("GIT_CEILING_DIRECTORIES", parent);
+        }
+        cmd
+    }
+
+    async fn output(&self, mut cmd: Command) -> std::result::Result<std::process::Output, String> {
+        tokio::time::timeout(Duration::from_secs(self.timeout_secs), cmd.output())
+            .await
+            .map_err(|_| format!("git timed out after {}s", self.timeout_secs))?
+            .map_err(|e| format!("failed to run git: {e}"))
+    }
+
+    /// Names of the filter drivers configured for `repo` (any scope), whose
+    /// clean/smudge/process commands `.gitattributes` could trigger.
+    /// Reading config runs nothing.
+    async fn filter_drivers(&self, repo: &Path) -> std::result::Result<Vec<String>, String> {
+        let mut cmd = self.command(repo);
+        cmd.args(["config", "--name-only", "--get-regexp", r"^filter\..+\.(clean|smudge|process)$"]);
+        let output = self.output(cmd).await?;
+        // Exit code 1 means no such keys.
+        let mut names: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|key| key.strip_prefix("filter."))
+            .filter_map(|rest| rest.rsplit_once('.').map(|(name, _)| name.to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    fn description(&self) -> &str {
+        "Run git on a repository inside the sandbox. Actions: status, diff, log, add, commit, branch, push, reset, clean. push, clean and reset with mode=hard require operator approval."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["action"],
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["status", "diff", "log", "add", "commit", "branch", "push", "reset", "clean"]
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Repository root, relative to the sandbox (default: sandbox root)"
+                },
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Paths relative to the repository (add, diff, clean)"
+                },
+                "staged": {
+                    "type": "boolean",
+                    "description": "diff: show staged changes instead of the working tree"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "log: number of commits (default 20)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "commit: commit message"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "branch: create this branch (omit to list branches)"
+                },
+                "checkout": {
+                    "type": "boolean",
+                    "description": "branch: switch to the new branch"
+                },
+                "remote": {
+                    "type": "string",
+                    "description": "push: remote name (default origin)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "push: branch to push (default: current)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["soft", "mixed", "hard"],
+                    "description": "reset: reset mode (default mixed)"
+                },
+                "target": {
+                    "type": "string",
+                    "description": "reset: commit to reset to (default HEAD)"
+                }
+            }
+        })
+    }
+
+    fn requires_approval(&self, params: &serde_json::Value) -> bool {
+        let str_param = |name: &str| params.get(name).and_then(|v| v.as_str());
+        match str_param("action") {
+            Some("push") | Some("clean") => true,
+            Some("reset") => str_param("mode") == Some("hard"),
+            _ => false,
+        }
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let str_param = |name: &str| params.get(name).and_then(|v| v.as_str());
+        let action = str_param("action").unwrap_or_default();
+        let repo_rel = str_param("repo").unwrap_or(".");
+        let repo = resolve_repo(&ctx.sandbox, repo_rel)?;
+        let paths = repo_paths(&ctx.sandbox, &repo, &params)?;
+
+        let result = match action {
+            "status" => self
+                .run(&repo, &["status", "--porcelain=v1", "--branch"])
+                .await
+                .map(|out| structured(&parse_status(&out))),
+            "diff" => {
+                let mut args = vec!["diff", "--no-ext-diff", "--no-textconv"];
+                if params.get("staged").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    args.push("--cached");
+                }
+                self.run(&repo, &with_paths(args, &paths)).await.map(|out| {
+                    if out.is_empty() {
+                        ToolOutput::ok("No changes.")
+                    } else {
+                        ToolOutput::ok(out)
+                    }
+                })
+            }
+            "log" => {
+                let limit = params
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(DEFAULT_LOG_LIMIT)
+                    .clamp(1, MAX_LOG_LIMIT)
+                    .to_string();
+                self.run(&repo, &["log", "-n", &limit, "--format=%H%x1f%an%x1f%aI%x1f%s%x1e"])
+                    .await
+                    .map(|out| structured(&parse_log(&out)))
+            }
+            "add" => {
+                if paths.is_empty() {
+                    return Ok(ToolOutput::error("paths is required for add"));
+                }
+                self.run(&repo, &with_paths(vec!["add"], &paths))
+                    .await
+                    .map(|_| ToolOutput::ok(format!("Staged {} path(s)", paths.len())))
+            }
+            "commit" => {
+                let message = str_param("message").unwrap_or_default().trim();
+                if message.is_empty() {
+                    return Ok(ToolOutput::error("message is required for commit"));
+                }
+                let committed = match self.run(&repo, &["commit", "-q", "-m", message]).await {
+                    Ok(_) => self.run(&repo, &["rev-parse", "HEAD"]).await,
+                    Err(e) => Err(e),
+                };
+                committed.map(|hash| {
+                    let hash = hash.trim();
+                    ToolOutput::ok_with_meta(
+                        format!("Committed {hash}"),
+                        serde_json::json!({ "commit": hash }),
+                    )
+                })
+            }
+            "branch" => match str_param("name") {
+                Some(name) => {
+                    if !is_valid_ref(name) {
+                        return Ok(ToolOutput::error(format!("invalid branch name: {name}")));
+                    }
+                    let checkout = params.get("checkout").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let args = if checkout {
+                        vec!["switch", "-c", name]
+                    } else {
+                        vec!["branch", name]
+                    };
+                    self.run(&repo, &args)
+                        .await
+                        .map(|_| ToolOutput::ok(format!("Created branch {name}")))
+                }
+                None => self
+                    .run(&repo, &["branch", "--format=%(HEAD)%(refname:short)"])
+                    .await
+                    .map(|out| structured(&parse_branches(&out))),
+            },
+            "push" => {
+                let remote = str_param("remote").unwrap_or("origin");
+                let mut args = vec!["push", remote];
+                args.extend(str_param("branch"));
+                if !args[1..].iter().all(|r| is_valid_ref(r)) {
+                    return Ok(ToolOutput::error("invalid remote or branch name"));
+                }
+                self.run(&repo, &args)
+                    .await
+                    .map(|_| ToolOutput::ok(format!("Pushed to {remote}")))
+            }
+            "reset" => {
+                let mode = match str_param("mode").unwrap_or("mixed") {
+                    "soft" => "--soft",
+                    "mixed" => "--mixed",
+                    "hard" => "--hard",
+                    other => return Ok(ToolOutput::error(format!("unknown reset mode: {other}"))),
+                };
+                let target = str_param("target").unwrap_or("HEAD");
+                if !is_valid_ref(target) {
+                    return Ok(ToolOutput::error(format!("invalid reset target: {target}")));
+                }
+                self.run(&repo, &["reset", "-q", mode, target])
+                    .await
+                    .map(|_| ToolOutput::ok(format!("Reset ({}) to {target}", &mode[2..])))
+            }
+            "clean" => {
+                self.run(&repo, &with_paths(vec!["clean", "-fd"], &paths)).await.map(|out| {
+                    let removed: Vec<&str> = out
+                        .lines()
+                        .filter_map(|l| l.strip_prefix("Removing "))
+                        .collect();
+                    ToolOutput::ok_with_meta(
+                        format!("Removed {} untracked path(s)", removed.len()),
+                        serde_json::json!({ "removed": removed }),
+                    )
+                })
+            }
+            "" => return Ok(ToolOutput::error("action is required")),
+            other => return Ok(ToolOutput::error(format!("unknown git action: {other}"))),
+        };
+
+        Ok(result.unwrap_or_else(ToolOutput::error))
+    }
+}
+
+/// Resolve `rel` to a repository root inside the sandbox.  The directory
+/// must hold `.git` itself, so git never walks up into a repository
+/// outside the sandbox.
+fn resolve_repo(sandbox: &SandboxedFs, rel: &str) -> Result<PathBuf> {
+    let dir = sandbox.resolve(Path::new(rel))?;
+    if !dir.join(".git").exists() {
+        return Err(SafeAgentError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{rel} is not a git repository (no .git directory)"),
+        )));
+    }
+    Ok(dir)
+}
+
+/// The `paths` parameter as repo-relative paths, each checked to stay
+/// inside both the sandbox and the repository.
+fn repo_paths(sandbox: &SandboxedFs, repo: &Path, params: &serde_json::Value) -> Result<Vec<String>> {
+    let Some(items) = params.get("paths").and_then(|v| v.as_array()) else {
+        return Ok(Vec::new());
+    };
+    let repo_rel = repo.strip_prefix(sandbox.root()).unwrap_or(Path::new(""));
+    items
+        .iter()
+        .filter_map(|v| v.as_str())
+        .map(|p| {
+            let resolved = sandbox.resolve(&repo_rel.join(p))?;
+            let inside = resolved.strip_prefix(repo).map_err(|_| {
+                SafeAgentError::SandboxViolation(format!("path is outside the repository: {p}"))
+            })?;
+            Ok(match inside.to_string_lossy().into_owned() {
+                s if s.is_empty() => ".".to_string(),
+                s => s,
+            })
+        })
+        .collect()
+}
+
+/// Append `paths` to a git command line after `--`.
+fn with_paths<'a>(mut args: Vec<&'a str>, paths: &'a [String]) -> Vec<&'a str> {
+    if !paths.is_empty() {
+        args.push("--");
+        args.extend(paths.iter().map(String::as_str));
+    }
+    args
+}
+
+/// Branch, remote and commit names passed as arguments; rejects anything
+/// git could read as an option.
+fn is_valid_ref(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('-') && !name.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+fn structured<T: Serialize>(value: &T) -> ToolOutput {
+    let json = serde_json::to_value(value).unwrap_or_default();
+    ToolOutput::ok_with_meta(serde_json::to_string_pretty(&json).unwrap_or_default(), json)
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct RepoStatus {
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead: u32,
+    behind: u32,
+    entries: Vec<StatusEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct StatusEntry {
+    /// Staged state (porcelain X column), e.g. "M", "A", "?".
+    index: String,
+    /// Working tree state (porcelain Y column).
+    worktree: String,
+    path: String,
+    /// Source path of a rename or copy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orig_path: Option<String>,
+}
+
+/// Parse `git status --porcelain=v1 --branch`.
+fn parse_status(out: &str) -> RepoStatus {
+    let mut status = RepoStatus::default();
+    for line in out.lines() {
+        if let Some(header) = line.strip_prefix("## ") {
+            let (refs, tracking) = match header.split_once(" [") {
+                Some((refs, rest)) => (refs, rest.trim_end_matches(']')),
+                None => (header, ""),
+            };
+            let (branch, upstream) = match refs.split_once("...") {
+                Some((b, u)) => (b, Some(u.to_string())),
+                None => (refs, None),
+            };
+            status.branch = Some(branch.trim_start_matches("No commits yet on ").to_string());
+            status.upstream = upstream;
+            for part in tracking.split(", ") {
+                if let Some(n) = part.strip_prefix("ahead ") {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix("behind ") {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+            continue;
+        }
+        if line.len() < 4 {
+            continue;
+        }
+        let (codes, path) = line.split_at(3);
+        let (orig_path, path) = match path.split_once(" -> ") {
+            Some((from, to)) => (Some(from.to_string()), to.to_string()),
+            None => (None, path.to_string()),
+        };
+        status.entries.push(StatusEntry {
+            index: codes[..1].trim().to_string(),
+            worktree: codes[1..2].trim().to_string(),
+            path,
+            orig_path,
+        });
+    }
+    status
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct LogEntry {
+    hash: String,
+    author: String,
+    date: String,
+    subject: String,
+}
+
+/// Parse `git log` output in the unit/record-separated format used above.
+fn parse_log(out: &str) -> Vec<LogEntry> {
+    out.split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').split('\x1f');
+            Some(LogEntry {
+                hash: fields.next().filter(|h| !h.is_empty())?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Branches {
+    current: Option<String>,
+    branches: Vec<String>,
+}
+
+/// Parse `git branch --format=%(HEAD)%(refname:short)`.
+fn parse_branches(out: &str) -> Branches {
+    let mut current = None;
+    let mut branches = Vec::new();
+    for line in out.lines() {
+        let (head, name) = line.split_at(line.len().min(1));
+        if name.is_empty() {
+            continue;
+        }
+        if head == "*" {
+            current = Some(name.to_string());
+        }
+        branches.push(name.to_string());
+    }
+    Branches { current, branches }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::messaging::MessagingManager;
+    use crate::trash::TrashManager;
+    use std::sync::Arc;
+
+    fn test_ctx(base: &Path) -> ToolContext {
+        let sandbox_dir = base.join("sandbox");
+        let trash_dir = base.join("trash");
+        std::fs::create_dir_all(&sandbox_dir).unwrap();
+        std::fs::create_dir_all(&trash_dir).unwrap();
+
+        ToolContext {
+            sandbox: SandboxedFs::new(sandbox_dir).unwrap(),
+            db: db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(&trash_dir).unwrap()),
+        }
+    }
+
+    fn init_repo(dir: &Path) {
+        std::fs::create_dir_all(dir).unwrap();
+        let ok = std::process::Command::new("git")
+            .arg("init")
+            .arg("-q")
+            .arg(dir)
+            .status()
+            .unwrap()
+            .success();
+        assert!(ok);
+    }
+
+    #[test]
+    fn resolves_repo_inside_sandbox() {
+        let base = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(base.path());
+        init_repo(&ctx.sandbox.root().join("projects/app"));
+
+        let repo = resolve_repo(&ctx.sandbox, "projects/app").unwrap();
+        assert_eq!(repo, ctx.sandbox.root().join("projects/app"));
+
+        let paths = repo_paths(&ctx.sandbox, &repo, &serde_json::json!({ "paths": ["src/main.rs", "."] })).unwrap();
+        assert_eq!(paths, ["src/main.rs", "."]);
+
+        // A plain directory is refused even though a parent might be a repo.
+        std::fs::create_dir_all(ctx.sandbox.root().join("projects/app/src")).unwrap();
+        assert!(resolve_repo(&ctx.sandbox, "projects/app/src").is_err());
+    }
+
+    #[test]
+    fn rejects_paths_outside_sandbox() {
+        let base = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(base.path());
+        init_repo(&base.path().join("outside"));
+        init_repo(&ctx.sandbox.root().join("app"));
+
+        let err = resolve_repo(&ctx.sandbox, "../outside").unwrap_err();
+        assert!(matches!(err, SafeAgentError::SandboxViolation(_)), "{err}");
+        assert!(resolve_repo(&ctx.sandbox, base.path().join("outside").to_str().unwrap()).is_err());
+
+        let repo = resolve_repo(&ctx.sandbox, "app").unwrap();
+        for escape in ["../../outside/file", "../other"] {
+            let params = serde_json::json!({ "paths": [escape] });
+            assert!(repo_paths(&ctx.sandbox, &repo, &params).is_err(), "{escape}");
+        }
+    }
+
+    #[test]
+    fn destructive_actions_require_approval() {
+        let tool = GitTool::new(30);
+        for params in [
+            serde_json::json!({ "action": "push" }),
+            serde_json::json!({ "action": "clean" }),
+            serde_json::json!({ "action": "reset", "mode": "hard" }),
+        ] {
+            assert!(tool.requires_approval(&params), "{params}");
+        }
+        for params in [
+            serde_json::json!({ "action": "status" }),
+            serde_json::json!({ "action": "commit", "message": "x" }),
+            serde_json::json!({ "action": "reset", "mode": "soft" }),
+        ] {
+            assert!(!tool.requires_approval(&params), "{params}");
+        }
+        assert!(!is_valid_ref("--upload-pack=evil"));
+        assert!(is_valid_ref("feature/x"));
+    }
+
+    #[tokio::test]
+    async fn repo_config_cannot_run_programs() {
+        let base = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(base.path());
+        let repo = ctx.sandbox.root().join("app");
+        init_repo(&repo);
+        let marker = base.path().join("pwned");
+        let evil = format!("sh -c 'touch {}; cat'", marker.display());
+        for (key, value) in [
+            ("diff.external", evil.as_str()),
+            ("diff.evil.textconv", evil.as_str()),
+            ("filter.evil.clean", evil.as_str()),
+            ("filter.evil.smudge", evil.as_str()),
+        ] {
+            let ok = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["config", key, value])
+                .status()
+                .unwrap()
+                .success();
+            assert!(ok);
+        }
+        std::fs::write(repo.join(".gitattributes"), "*.txt diff=evil filter=evil\n").unwrap();
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+
+        let tool = GitTool::new(30);
+        for params in [
+            serde_json::json!({ "action": "add", "repo": "app", "paths": ["."] }),
+            serde_json::json!({ "action": "diff", "repo": "app", "staged": true }),
+            serde_json::json!({ "action": "status", "repo": "app" }),
+        ] {
+            let out = tool.execute(params.clone(), &ctx).await.unwrap();
+            assert!(out.success, "{params}: {}", out.output);
+        }
+        assert!(!marker.exists(), "repo config ran a program");
+    }
+
+    #[test]
+    fn parses_porcelain_status() {
+        let out = "## main...origin/main [ahead 2, behind 1]\n M src/lib.rs\nA  new.rs\nR  old.rs -> renamed.rs\n?? notes.txt\n";
+        let status = parse_status(out);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!(
+            status.entries,
+            vec![
+                StatusEntry { index: "".into(), worktree: "M".into(), path: "src/lib.rs".into(), orig_path: None },
+                StatusEntry { index: "A".into(), worktree: "".into(), path: "new.rs".into(), orig_path: None },
+                StatusEntry {
+                    index: "R".into(),
+                    worktree: "".into(),
+                    path: "renamed.rs".into(),
+                    orig_path: Some("old.rs".into()),
+                },
+                StatusEntry { index: "?".into(), worktree: "?".into(), path: "notes.txt".into(), orig_path: None },
+            ]
+        );
+
+        let fresh = parse_status("## No commits yet on main\n");
+        assert_eq!(fresh.branch.as_deref(), Some("main"));
+        assert!(fresh.upstream.is_none());
+    }
+
+    #[tokio::test]
+    async fn status_and_add_on_sandbox_repo() {
+        let base = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(base.path());
+        init_repo(&ctx.sandbox.root().join("app"));
+        ctx.sandbox.write(Path::new("app/readme.md"), b"hi").unwrap();
+        let tool = GitTool::new(30);
+
+        let out = tool
+            .execute(serde_json::json!({ "action": "status", "repo": "app" }), &ctx)
+            .await
+            .unwrap();
+        assert!(out.success, "{}", out.output);
+        let meta = out.metadata.unwrap();
+        assert_eq!(meta["entries"][0]["path"], "readme.md");
+        assert_eq!(meta["entries"][0]["index"], "?");
+
+        let out = tool
+            .execute(serde_json::json!({ "action": "add", "repo": "app", "paths": ["readme.md"] }), &ctx)
+            .await
+            .unwrap();
+        assert!(out.success, "{}", out.output);
+        let out = tool
+            .execute(serde_json::json!({ "action": "status", "repo": "app" }), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(out.metadata.unwrap()["entries"][0]["index"], "A");
+    }
+}
@@ -21,8 +21,9 @@ impl Tool for GoalTool {
     }
 
     fn description(&self) -> &str {
-        "Manage background goals and tasks. Actions: create, list, get, add_task, update_status, \
-         complete_task, fail_task, cancel, pause, resume. \
+        "Manage background goals and tasks. Actions: create, decompose, list, get, add_task, \
+         update_status, complete_task, fail_task, cancel, pause, resume. \
+         decompose turns a free-text objective into a goal with ordered, dependent tasks. \
          Goals persist across restarts and are worked on autonomously between conversations."
     }
 
@@ -34,7 +35,7 @@ impl Tool for GoalTool {
                 "action": {
                     "type": "string",
                     "enum": [
-                        "create", "list", "get", "add_task", "update_status",
+                        "create", "decompose", "list", "get", "add_task", "update_status",
                         "complete_task", "fail_task", "cancel", "pause", "resume"
                     ],
                     "description": "Goal action to perform"
@@ -51,6 +52,10 @@ impl Tool for GoalTool {
                     "type": "string",
                     "description": "Goal or task title"
                 },
+                "objective": {
+                    "type": "string",
+                    "description": "Free-text objective to break into a goal and tasks (for decompose)"
+                },
                 "description": {
                     "type": "string",
                     "description": "Goal or task description"
@@ -264,6 +269,9 @@ impl Tool for GoalTool {
                 )))
             }
 
+            // Needs the LLM, so the agent intercepts it before dispatch.
+            "decompose" => Ok(ToolOutput::error("decompose is only available to the agent")),
+
             other => Ok(ToolOutput::error(format!("unknown goal action: {other}"))),
         }
     }
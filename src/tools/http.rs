@@ -0,0 +1,305 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use super::{Tool, ToolContext, ToolOutput};
+use crate::config::HttpToolConfig;
+use crate::error::Result;
+
+/// Headers that carry credentials.  The agent may only set these for hosts
+/// listed in `auth_hosts`.
+const AUTH_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "x-api-key"];
+
+/// Headers the client manages itself and never takes from the agent.
+const RESERVED_HEADERS: &[&str] = &["host", "content-length", "transfer-encoding", "connection"];
+
+/// Most bytes of the response body read and returned to the agent.
+const MAX_BODY_BYTES: usize = 50_000;
+
+/// Generic HTTP requests, restricted to allowlisted hosts.
+///
+/// Every URL must pass the same SSRF guard as Rhai extensions and name a
+/// host from `allowed_hosts`.  Redirects are not followed, so an allowed
+/// host can't bounce a request onto an internal address.
+pub struct HttpTool {
+    client: reqwest::Client,
+    allowed_hosts: Vec<String>,
+    auth_hosts: Vec<String>,
+}
+
+impl HttpTool {
+    pub fn new(config: &HttpToolConfig) -> Self {
+        let normalize = |hosts: &[String]| -> Vec<String> {
+            hosts.iter().map(|h| h.trim().to_ascii_lowercase()).collect()
+        };
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .user_agent("SafeClaw/0.1")
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            allowed_hosts: normalize(&config.allowed_hosts),
+            auth_hosts: normalize(&config.auth_hosts),
+        }
+    }
+
+    /// Parse `url` and check it against the SSRF guard and the allowlist.
+    fn check_url(&self, url: &str) -> std::result::Result<reqwest::Url, String> {
+        let parsed = crate::security::validate_url(url)?;
+        let host = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+        if !host_matches(&self.allowed_hosts, &host) {
+            return Err(format!("host '{host}' is not in tools.http.allowed_hosts"));
+        }
+        Ok(parsed)
+    }
+
+    /// Build the request described by `params` without sending it.
+    fn build_request(&self, params: &serde_json::Value) -> std::result::Result<reqwest::Request, String> {
+        let url = params.get("url").and_then(|v| v.as_str()).unwrap_or_default();
+        if url.is_empty() {
+            return Err("url is required".into());
+        }
+        let url = self.check_url(url)?;
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+
+        let method = match params
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("GET")
+            .to_ascii_uppercase()
+            .as_str()
+        {
+            "GET" => reqwest::Method::GET,
+            "POST" => reqwest::Method::POST,
+            "PUT" => reqwest::Method::PUT,
+            "DELETE" => reqwest::Method::DELETE,
+            other => return Err(format!("unsupported method '{other}' (use GET, POST, PUT or DELETE)")),
+        };
+
+        let mut req = self.client.request(method, url);
+        if let Some(headers) = params.get("headers").and_then(|v| v.as_object()) {
+            for (name, value) in headers {
+                let lower = name.to_ascii_lowercase();
+                if RESERVED_HEADERS.contains(&lower.as_str()) {
+                    return Err(format!("header '{name}' cannot be set"));
+                }
+                if AUTH_HEADERS.contains(&lower.as_str()) && !host_matches(&self.auth_hosts, &host) {
+                    return Err(format!(
+                        "header '{name}' is only allowed for hosts in tools.http.auth_hosts"
+                    ));
+                }
+                let Some(value) = value.as_str() else {
+                    return Err(format!("header '{name}' must be a string"));
+                };
+                req = req.header(name.as_str(), value);
+            }
+        }
+        match params.get("body") {
+            None | Some(serde_json::Value::Null) => {}
+            Some(serde_json::Value::String(s)) => req = req.body(s.clone()),
+            Some(other) => req = req.json(other),
+        }
+        req.build().map_err(|e| format!("invalid request: {e}"))
+    }
+}
+
+/// Whether `host` is one of `hosts` or a subdomain of one.
+fn host_matches(hosts: &[String], host: &str) -> bool {
+    hosts
+        .iter()
+        .any(|h| host == h || host.strip_suffix(h.as_str()).is_some_and(|sub| sub.ends_with('.')))
+}
+
+#[async_trait]
+impl Tool for HttpTool {
+    fn name(&self) -> &str {
+        "http_request"
+    }
+
+    fn description(&self) -> &str {
+        "Send an HTTP request (GET, POST, PUT or DELETE) to an allowlisted API host and return the status and response body."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["url"],
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "Request URL (http or https, allowlisted host only)"
+                },
+                "method": {
+                    "type": "string",
+                    "enum": ["GET", "POST", "PUT", "DELETE"],
+                    "description": "HTTP method (default GET)"
+                },
+                "headers": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Extra request headers"
+                },
+                "body": {
+                    "description": "Request body: a string is sent as-is, anything else as JSON"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+        let req = match self.build_request(&params) {
+            Ok(r) => r,
+            Err(msg) => return Ok(ToolOutput::error(msg)),
+        };
+        debug!(method = %req.method(), url = %req.url(), "http request");
+
+        let resp = match self.client.execute(req).await {
+            Ok(r) => r,
+            Err(e) => return Ok(ToolOutput::error(format!("request failed: {e}"))),
+        };
+        let status = resp.status();
+        let body = match read_capped(resp, MAX_BODY_BYTES).await {
+            Ok((body, false)) => body,
+            Ok((body, true)) => format!("{body}...\n[truncated at {MAX_BODY_BYTES} bytes]"),
+            Err(e) => return Ok(ToolOutput::error(format!("failed to read response: {e}"))),
+        };
+
+        Ok(ToolOutput::ok_with_meta(
+            format!("HTTP {status}\n\n{body}"),
+            serde_json::json!({ "status": status.as_u16() }),
+        ))
+    }
+}
+
+/// Read at most `cap` bytes of the body, chunk by chunk, and stop there
+/// rather than buffering the rest.  Returns the text (cut back to a char
+/// boundary) and whether it was truncated.
+async fn read_capped(mut resp: reqwest::Response, cap: usize) -> reqwest::Result<(String, bool)> {
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = resp.chunk().await? {
+        let room = cap - body.len();
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+    // Drop a character split by the cap; other invalid bytes are replaced.
+    let split_at = match std::str::from_utf8(&body) {
+        Err(e) if e.error_len().is_none() => Some(e.valid_up_to()),
+        _ => None,
+    };
+    if let Some(len) = split_at {
+        body.truncate(len);
+    }
+    Ok((String::from_utf8_lossy(&body).into_owned(), truncated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool() -> HttpTool {
+        HttpTool::new(&HttpToolConfig {
+            enabled: true,
+            allowed_hosts: vec!["api.example.com".into(), "Example.org".into()],
+            auth_hosts: vec!["api.example.com".into()],
+            timeout_secs: 5,
+        })
+    }
+
+    #[test]
+    fn internal_hosts_are_blocked() {
+        let tool = tool();
+        for url in [
+            "http://127.0.0.1/admin",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost:8080/",
+            "file:///etc/passwd",
+        ] {
+            assert!(tool.check_url(url).is_err(), "{url}");
+        }
+        let err = tool.check_url("https://evil.test/").unwrap_err();
+        assert!(err.contains("allowed_hosts"));
+    }
+
+    #[test]
+    fn allowlisted_hosts_and_subdomains_pass() {
+        let tool = tool();
+        assert!(tool.check_url("https://api.example.com/v1/items").is_ok());
+        assert!(tool.check_url("https://docs.example.org/").is_ok());
+        assert!(tool.check_url("https://notexample.org/").is_err());
+    }
+
+    #[test]
+    fn builds_each_method_with_headers_and_body() {
+        let tool = tool();
+        for (name, method) in [
+            ("get", reqwest::Method::GET),
+            ("POST", reqwest::Method::POST),
+            ("put", reqwest::Method::PUT),
+            ("DELETE", reqwest::Method::DELETE),
+        ] {
+            let req = tool
+                .build_request(&serde_json::json!({
+                    "url": "https://api.example.com/items",
+                    "method": name,
+                }))
+                .unwrap();
+            assert_eq!(req.method(), method);
+        }
+
+        let req = tool
+            .build_request(&serde_json::json!({
+                "url": "https://api.example.com/items",
+                "method": "POST",
+                "headers": { "Authorization": "Bearer t", "X-Trace": "1" },
+                "body": { "name": "x" },
+            }))
+            .unwrap();
+        assert_eq!(req.headers()["authorization"], "Bearer t");
+        assert_eq!(req.headers()["content-type"], "application/json");
+        assert_eq!(req.body().and_then(|b| b.as_bytes()), Some(&br#"{"name":"x"}"#[..]));
+
+        let err = tool
+            .build_request(&serde_json::json!({ "url": "https://api.example.com/", "method": "PATCH" }))
+            .unwrap_err();
+        assert!(err.contains("unsupported method"));
+    }
+
+    #[test]
+    fn auth_headers_need_an_auth_host() {
+        let tool = tool();
+        let err = tool
+            .build_request(&serde_json::json!({
+                "url": "https://example.org/",
+                "headers": { "Cookie": "session=1" },
+            }))
+            .unwrap_err();
+        assert!(err.contains("auth_hosts"));
+        let err = tool
+            .build_request(&serde_json::json!({
+                "url": "https://api.example.com/",
+                "headers": { "Host": "internal" },
+            }))
+            .unwrap_err();
+        assert!(err.contains("cannot be set"));
+    }
+
+    #[tokio::test]
+    async fn body_is_cut_at_the_byte_cap() {
+        let resp = |body: &str| reqwest::Response::from(axum::http::Response::new(body.to_string()));
+
+        let (body, truncated) = read_capped(resp("short"), 16).await.unwrap();
+        assert_eq!((body.as_str(), truncated), ("short", false));
+
+        // Cut inside the two-byte 'é', which is dropped rather than mangled.
+        let (body, truncated) = read_capped(resp("abcé and more"), 4).await.unwrap();
+        assert_eq!((body.as_str(), truncated), ("abc", true));
+    }
+}
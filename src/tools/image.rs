@@ -1,16 +1,112 @@
+use std::path::Path;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use tracing::info;
 
 use super::{Tool, ToolContext, ToolOutput};
+use crate::config::ImageToolConfig;
 use crate::error::Result;
 
-/// Image analysis tool — uses the LLM engine to describe/analyze images.
+/// Sandbox directory generated images are saved to.
+const IMAGES_DIR: &str = "images";
+
+/// Largest generated image accepted from the API.
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+const DEFAULT_SIZE: &str = "1024x1024";
+
+/// Image generation through an OpenAI-compatible images API.
 ///
-/// Scaffold — requires a vision-capable model or a separate image model.
-pub struct ImageTool;
+/// The image is saved under `images/` in the sandbox and reported as an
+/// artifact, so when the tool runs for a chat message the agent sends the
+/// picture back to that chat.
+pub struct ImageTool {
+    client: reqwest::Client,
+    api_url: String,
+    model: String,
+    api_key_env: String,
+}
 
 impl ImageTool {
-    pub fn new() -> Self {
-        Self
+    pub fn new(config: &ImageToolConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .user_agent("SafeClaw/0.1")
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            api_url: config.api_url.clone(),
+            model: config.model.clone(),
+            api_key_env: config.api_key_env.clone(),
+        }
+    }
+
+    /// Request one image and return its bytes.  The API answers with either
+    /// inline base64 (`b64_json`) or a URL to fetch it from.
+    async fn generate(&self, prompt: &str, size: &str, api_key: &str) -> std::result::Result<Vec<u8>, String> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "prompt": prompt,
+            "n": 1,
+            "size": size,
+        });
+        let resp = self
+            .client
+            .post(&self.api_url)
+            .bearer_auth(api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("image request failed: {e}"))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let detail = resp.text().await.unwrap_or_default();
+            return Err(format!("image API returned HTTP {status}: {}", detail.trim()));
+        }
+        let json: serde_json::Value = resp.json().await.map_err(|e| format!("invalid image API response: {e}"))?;
+        let image = json.get("data").and_then(|d| d.get(0)).ok_or("image API returned no image")?;
+
+        if let Some(b64) = image.get("b64_json").and_then(|v| v.as_str()) {
+            let data = data_encoding::BASE64
+                .decode(b64.as_bytes())
+                .map_err(|e| format!("undecodable image data: {e}"))?;
+            if data.len() > MAX_IMAGE_BYTES {
+                return Err(format!("generated image is larger than {MAX_IMAGE_BYTES} bytes"));
+            }
+            return Ok(data);
+        }
+        let url = image.get("url").and_then(|v| v.as_str()).ok_or("image API returned neither b64_json nor url")?;
+        let url = crate::security::validate_url(url)?;
+        let mut resp = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("image download failed: {e}"))?;
+        if !resp.status().is_success() {
+            return Err(format!("image download returned HTTP {}", resp.status()));
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = resp.chunk().await.map_err(|e| format!("image download failed: {e}"))? {
+            if data.len() + chunk.len() > MAX_IMAGE_BYTES {
+                return Err(format!("generated image is larger than {MAX_IMAGE_BYTES} bytes"));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+}
+
+/// MIME type and file extension for image bytes, from their signature.
+fn image_kind(data: &[u8]) -> (&'static str, &'static str) {
+    if data.starts_with(b"\xFF\xD8\xFF") {
+        ("image/jpeg", "jpg")
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(&b"WEBP"[..]) {
+        ("image/webp", "webp")
+    } else {
+        ("image/png", "png")
     }
 }
 
@@ -21,40 +117,139 @@ impl Tool for ImageTool {
     }
 
     fn description(&self) -> &str {
-        "Analyze an image and return a description. Provide either a file path (relative to sandbox) or a URL."
+        "Generate an image from a text prompt. The image is saved to the sandbox and sent to the chat the request came from."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
         serde_json::json!({
             "type": "object",
-            "required": ["image"],
+            "required": ["prompt"],
             "properties": {
-                "image": {
+                "prompt": {
                     "type": "string",
-                    "description": "Path to image file (sandbox-relative) or URL"
+                    "description": "Description of the image to generate"
                 },
-                "prompt": {
+                "size": {
                     "type": "string",
-                    "description": "What to analyze (default: 'Describe the image.')"
+                    "description": "Image size as WIDTHxHEIGHT (default 1024x1024)"
                 }
             }
         })
     }
 
-    async fn execute(&self, params: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
-        let image = params.get("image").and_then(|v| v.as_str()).unwrap_or_default();
-        let _prompt = params
-            .get("prompt")
-            .and_then(|v| v.as_str())
-            .unwrap_or("Describe the image.");
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let prompt = params.get("prompt").and_then(|v| v.as_str()).unwrap_or_default().trim();
+        if prompt.is_empty() {
+            return Ok(ToolOutput::error("prompt is required"));
+        }
+        let size = params.get("size").and_then(|v| v.as_str()).unwrap_or(DEFAULT_SIZE);
+        let api_key = match std::env::var(&self.api_key_env) {
+            Ok(key) if !key.is_empty() => key,
+            _ => {
+                return Ok(ToolOutput::error(format!(
+                    "image generation needs an API key in the {} environment variable",
+                    self.api_key_env
+                )))
+            }
+        };
+
+        let data = match self.generate(prompt, size, &api_key).await {
+            Ok(data) => data,
+            Err(e) => return Ok(ToolOutput::error(e)),
+        };
+        let (mime_type, ext) = image_kind(&data);
+        let name = format!(
+            "{}-{}.{ext}",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8],
+        );
+        let relative = Path::new(IMAGES_DIR).join(name);
+        std::fs::create_dir_all(ctx.sandbox.resolve(Path::new(IMAGES_DIR))?)?;
+        ctx.sandbox.write(&relative, &data)?;
+        let path = relative.display().to_string();
+        info!(image = %path, bytes = data.len(), "generated image");
+
+        Ok(ToolOutput::ok(format!("Generated image saved to {path}")).with_image(&path, mime_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::MessagingManager;
+    use crate::security::SandboxedFs;
+    use crate::trash::TrashManager;
+    use std::sync::Arc;
 
-        if image.is_empty() {
-            return Ok(ToolOutput::error("image path or URL is required"));
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nimage";
+
+    fn test_ctx(base: &Path) -> ToolContext {
+        ToolContext {
+            sandbox: SandboxedFs::new(base.join("sandbox")).unwrap(),
+            db: crate::db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(base).unwrap()),
         }
+    }
+
+    /// Serve an images endpoint that checks the key and answers with PNG
+    /// as base64; returns its URL.
+    async fn serve_images_api() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1/images/generations", listener.local_addr().unwrap());
+        let app = axum::Router::new().route(
+            "/v1/images/generations",
+            axum::routing::post(|headers: axum::http::HeaderMap, axum::Json(body): axum::Json<serde_json::Value>| async move {
+                assert_eq!(headers["authorization"], "Bearer sk-test");
+                assert_eq!(body["prompt"], "a ginger cat");
+                assert_eq!(body["size"], DEFAULT_SIZE);
+                axum::Json(serde_json::json!({ "data": [{ "b64_json": data_encoding::BASE64.encode(PNG) }] }))
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn generated_image_is_saved_and_reported_as_an_artifact() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        // Only this test reads the variable
+        unsafe { std::env::set_var("SAFECLAW_TEST_IMAGE_KEY", "sk-test"); }
+        let tool = ImageTool::new(&ImageToolConfig {
+            enabled: true,
+            api_url: serve_images_api().await,
+            api_key_env: "SAFECLAW_TEST_IMAGE_KEY".into(),
+            ..ImageToolConfig::default()
+        });
+
+        let out = tool.execute(serde_json::json!({ "prompt": "a ginger cat" }), &ctx).await.unwrap();
+        assert!(out.success, "{}", out.output);
+        let artifacts = out.image_artifacts();
+        assert_eq!(artifacts.len(), 1);
+        let (path, mime_type) = &artifacts[0];
+        assert!(path.starts_with("images/") && path.ends_with(".png"), "{path}");
+        assert_eq!(mime_type, "image/png");
+        assert_eq!(std::fs::read(ctx.sandbox.resolve(Path::new(path)).unwrap()).unwrap(), PNG);
+    }
+
+    #[tokio::test]
+    async fn missing_api_key_is_reported() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tool = ImageTool::new(&ImageToolConfig {
+            api_key_env: "SAFECLAW_TEST_IMAGE_KEY_UNSET".into(),
+            ..ImageToolConfig::default()
+        });
+        let out = tool.execute(serde_json::json!({ "prompt": "a cat" }), &test_ctx(tmp.path())).await.unwrap();
+        assert!(!out.success);
+        assert!(out.output.contains("SAFECLAW_TEST_IMAGE_KEY_UNSET"), "{}", out.output);
+    }
 
-        // TODO: Integrate with a vision-capable model
-        Ok(ToolOutput::ok(
-            "Image analysis requires a vision-capable model — integration pending".to_string(),
-        ))
+    #[test]
+    fn image_kind_follows_the_signature() {
+        assert_eq!(image_kind(PNG), ("image/png", "png"));
+        assert_eq!(image_kind(b"\xFF\xD8\xFF\xE0jfif"), ("image/jpeg", "jpg"));
+        assert_eq!(image_kind(b"RIFF\0\0\0\0WEBPVP8 "), ("image/webp", "webp"));
     }
 }
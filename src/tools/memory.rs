@@ -12,6 +12,10 @@ impl Tool for MemorySearchTool {
         "memory_search"
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Search the agent's archival memory using full-text search. Returns matching entries with category and timestamp."
     }
@@ -79,6 +83,10 @@ impl Tool for MemoryGetTool {
         "memory_get"
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Retrieve a specific archival memory entry by ID."
     }
@@ -123,6 +131,98 @@ impl Tool for MemoryGetTool {
     }
 }
 
+/// Delete archival memory entries, and their embeddings, by ID or by
+/// full-text query.
+pub struct MemoryForgetTool;
+
+#[async_trait]
+impl Tool for MemoryForgetTool {
+    fn name(&self) -> &str {
+        "memory_forget"
+    }
+
+    fn description(&self) -> &str {
+        "Permanently delete archival memory entries, by ID or by full-text search query. Use when the user asks you to forget something. Reports what was removed."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "description": "Memory entry ID to delete"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Delete entries matching this search query (when no id is given)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max entries to delete by query (default 10)"
+                }
+            }
+        })
+    }
+
+    /// Deletion can't be undone, so it always needs a human to sign off.
+    fn requires_approval(&self, _params: &serde_json::Value) -> bool {
+        true
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let id = params.get("id").and_then(|v| v.as_i64()).unwrap_or(0);
+        let query = params.get("query").and_then(|v| v.as_str()).unwrap_or_default();
+        let limit = params.get("limit").and_then(|v| v.as_i64()).unwrap_or(10);
+
+        if id == 0 && query.is_empty() {
+            return Ok(ToolOutput::error("id or query is required"));
+        }
+
+        let db = ctx.db.lock().await;
+        let matches: Vec<(i64, String, String)> = if id != 0 {
+            let mut stmt = db.prepare("SELECT id, category, content FROM archival_memory WHERE id = ?1")?;
+            stmt.query_map([id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            let mut stmt = db.prepare(
+                "SELECT am.id, am.category, am.content
+                 FROM archival_memory_fts fts
+                 JOIN archival_memory am ON am.id = fts.rowid
+                 WHERE archival_memory_fts MATCH ?1
+                 ORDER BY rank
+                 LIMIT ?2",
+            )?;
+            stmt.query_map(rusqlite::params![query, limit], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        if matches.is_empty() {
+            return Ok(ToolOutput::ok_with_meta(
+                "No matching memories found; nothing removed.",
+                serde_json::json!({ "removed": 0 }),
+            ));
+        }
+
+        let tx = db.unchecked_transaction()?;
+        for (id, _, _) in &matches {
+            tx.execute(
+                "DELETE FROM memory_embeddings WHERE source_table = 'archival_memory' AND source_id = ?1",
+                [id],
+            )?;
+            tx.execute("DELETE FROM archival_memory WHERE id = ?1", [id])?;
+        }
+        tx.commit()?;
+
+        let mut out = format!("Removed {} memor{}:", matches.len(), if matches.len() == 1 { "y" } else { "ies" });
+        for (id, category, content) in &matches {
+            out.push_str(&format!("\n[{id}] [{category}] {content}"));
+        }
+        let ids: Vec<i64> = matches.iter().map(|(id, _, _)| *id).collect();
+        Ok(ToolOutput::ok_with_meta(out, serde_json::json!({ "removed": ids.len(), "ids": ids })))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,6 +319,62 @@ mod tests {
         assert!(result.output.contains("notes"));
     }
 
+    #[tokio::test]
+    async fn memory_forget_by_id_removes_entry_and_embedding() {
+        let ctx = test_ctx();
+        let id: i64;
+        {
+            let db = ctx.db.lock().await;
+            db.execute(
+                "INSERT INTO archival_memory (content, category) VALUES (?1, ?2)",
+                rusqlite::params!["my old address is 12 Elm St", "personal"],
+            ).unwrap();
+            id = db.last_insert_rowid();
+            db.execute(
+                "INSERT INTO memory_embeddings (source_table, source_id, embedding) VALUES ('archival_memory', ?1, x'00')",
+                [id],
+            ).unwrap();
+        }
+
+        let result = MemoryForgetTool.execute(serde_json::json!({"id": id}), &ctx).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Removed 1 memory"));
+        assert!(result.output.contains("12 Elm St"));
+        assert_eq!(result.metadata.as_ref().unwrap()["removed"], 1);
+
+        let db = ctx.db.lock().await;
+        let left: i64 = db
+            .query_row("SELECT COUNT(*) FROM archival_memory WHERE id = ?1", [id], |r| r.get(0))
+            .unwrap();
+        let embeddings: i64 = db
+            .query_row("SELECT COUNT(*) FROM memory_embeddings WHERE source_id = ?1", [id], |r| r.get(0))
+            .unwrap();
+        assert_eq!((left, embeddings), (0, 0));
+        let fts: i64 = db
+            .query_row("SELECT COUNT(*) FROM archival_memory_fts WHERE archival_memory_fts MATCH 'Elm'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(fts, 0);
+    }
+
+    #[tokio::test]
+    async fn memory_forget_no_match_removes_nothing() {
+        let ctx = test_ctx();
+        let result = MemoryForgetTool
+            .execute(serde_json::json!({"query": "zebra"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("nothing removed"));
+        assert_eq!(result.metadata.as_ref().unwrap()["removed"], 0);
+    }
+
+    #[tokio::test]
+    async fn memory_forget_always_requires_approval() {
+        assert!(MemoryForgetTool.requires_approval(&serde_json::json!({"id": 1})));
+        let result = MemoryForgetTool.execute(serde_json::json!({}), &test_ctx()).await.unwrap();
+        assert!(!result.success);
+    }
+
     #[tokio::test]
     async fn tool_metadata() {
         assert_eq!(MemorySearchTool.name(), "memory_search");
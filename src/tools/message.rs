@@ -1,9 +1,20 @@
+use std::path::{Path, PathBuf};
+
 use async_trait::async_trait;
 use tracing::{debug, info};
 
 use super::{Tool, ToolContext, ToolOutput};
 use crate::error::Result;
 
+/// Largest file the tool will attach.
+const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// `attachment` value that selects the newest image in the sandbox.
+const LATEST_IMAGE: &str = "latest_image";
+
+/// How deep `latest_image` searches below the sandbox root.
+const LATEST_IMAGE_DEPTH: usize = 3;
+
 /// Messaging tool — sends messages via the primary messaging backend
 /// (Telegram, WhatsApp, or whatever is configured first).
 pub struct MessageTool;
@@ -21,21 +32,24 @@ impl Tool for MessageTool {
     }
 
     fn description(&self) -> &str {
-        "Send a message to the operator via the primary messaging platform. Params: {\"text\": \"your message\", \"platform\": \"telegram|whatsapp (optional)\"}"
+        "Send a message to the operator via the primary messaging platform, optionally with a file attached. Params: {\"text\": \"your message\", \"platform\": \"telegram|whatsapp (optional)\", \"attachment\": \"sandbox path or 'latest_image' (optional)\"}"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
         serde_json::json!({
             "type": "object",
-            "required": ["text"],
             "properties": {
                 "text": {
                     "type": "string",
-                    "description": "Message text to send"
+                    "description": "Message text to send (optional when attaching a file)"
                 },
                 "platform": {
                     "type": "string",
                     "description": "Target platform (optional, defaults to primary)"
+                },
+                "attachment": {
+                    "type": "string",
+                    "description": "Sandbox-relative path of a file to attach, or 'latest_image' for the most recently generated image"
                 }
             }
         })
@@ -47,7 +61,12 @@ impl Tool for MessageTool {
             .and_then(|v| v.as_str())
             .unwrap_or_default();
 
-        if text.is_empty() {
+        let attachment = params
+            .get("attachment")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty());
+
+        if text.is_empty() && attachment.is_none() {
             return Ok(ToolOutput::error("Missing 'text' parameter"));
         }
 
@@ -57,6 +76,41 @@ impl Tool for MessageTool {
             return Ok(ToolOutput::error("No messaging backends configured"));
         }
 
+        let Some(attachment) = attachment else {
+            return self.send_text(ctx, platform, text).await;
+        };
+
+        let path = match resolve_attachment(ctx, attachment) {
+            Ok(p) => p,
+            Err(msg) => return Ok(ToolOutput::error(msg)),
+        };
+        let size = std::fs::metadata(&path)?.len();
+        if size > MAX_ATTACHMENT_BYTES {
+            return Ok(ToolOutput::error(format!(
+                "Attachment is {size} bytes; the limit is {MAX_ATTACHMENT_BYTES}"
+            )));
+        }
+        let data = std::fs::read(&path)?;
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "attachment".into());
+        let mime = mime_for(&path);
+
+        if !text.is_empty() {
+            let output = self.send_text(ctx, platform, text).await?;
+            if !output.success {
+                return Ok(output);
+            }
+        }
+        let platform_name = ctx.messaging.send_file(platform, &data, &filename, mime).await?;
+        info!(platform = %platform_name, filename = %filename, "attachment sent successfully");
+        Ok(ToolOutput::ok(format!("Sent {filename} via {platform_name}")))
+    }
+}
+
+impl MessageTool {
+    async fn send_text(&self, ctx: &ToolContext, platform: Option<&str>, text: &str) -> Result<ToolOutput> {
         match platform {
             Some(p) => {
                 // Send to a specific platform
@@ -98,3 +152,125 @@ impl Tool for MessageTool {
         }
     }
 }
+
+/// Resolve the `attachment` parameter to a file inside the sandbox.
+fn resolve_attachment(ctx: &ToolContext, attachment: &str) -> std::result::Result<PathBuf, String> {
+    if attachment == LATEST_IMAGE {
+        return latest_image(ctx.sandbox.root(), LATEST_IMAGE_DEPTH)
+            .ok_or_else(|| "No image found in the sandbox".to_string());
+    }
+    let path = ctx
+        .sandbox
+        .resolve(Path::new(attachment))
+        .map_err(|e| e.to_string())?;
+    if !path.is_file() {
+        return Err(format!("Attachment not found: {attachment}"));
+    }
+    Ok(path)
+}
+
+/// The most recently modified image file under `dir`, skipping hidden
+/// directories.
+fn latest_image(dir: &Path, depth: usize) -> Option<PathBuf> {
+    let mut newest: Option<(std::time::SystemTime, PathBuf)> = None;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else { continue };
+        let candidate = if meta.is_dir() {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if hidden || depth == 0 {
+                continue;
+            }
+            latest_image(&path, depth - 1)
+                .and_then(|p| std::fs::metadata(&p).and_then(|m| m.modified()).ok().map(|t| (t, p)))
+        } else if mime_for(&path).starts_with("image/") {
+            meta.modified().ok().map(|t| (t, path))
+        } else {
+            None
+        };
+        let is_newer = |(time, _): &(std::time::SystemTime, PathBuf)| {
+            newest.as_ref().is_none_or(|(t, _)| time > t)
+        };
+        if let Some(found) = candidate.filter(is_newer) {
+            newest = Some(found);
+        }
+    }
+    newest.map(|(_, p)| p)
+}
+
+/// MIME type from a file extension.
+fn mime_for(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        Some("txt" | "md") => "text/plain",
+        Some("csv") => "text/csv",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::messaging::MessagingManager;
+    use crate::security::SandboxedFs;
+    use crate::trash::TrashManager;
+    use std::sync::Arc;
+
+    fn test_ctx(base: &Path) -> ToolContext {
+        let sandbox_dir = base.join("sandbox");
+        let trash_dir = base.join("trash");
+        std::fs::create_dir_all(&sandbox_dir).unwrap();
+        std::fs::create_dir_all(&trash_dir).unwrap();
+
+        ToolContext {
+            sandbox: SandboxedFs::new(sandbox_dir).unwrap(),
+            db: db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(&trash_dir).unwrap()),
+        }
+    }
+
+    #[test]
+    fn latest_image_picks_newest_image() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        let root = ctx.sandbox.root();
+        assert!(latest_image(root, LATEST_IMAGE_DEPTH).is_none());
+
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(60);
+        ctx.sandbox.write(Path::new("old.png"), b"a").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(root.join("old.png"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        ctx.sandbox.write(Path::new("images/new.jpg"), b"b").unwrap();
+        ctx.sandbox.write(Path::new("notes.txt"), b"c").unwrap();
+        ctx.sandbox.write(Path::new(".cache/hidden.png"), b"d").unwrap();
+
+        assert_eq!(latest_image(root, LATEST_IMAGE_DEPTH), Some(root.join("images/new.jpg")));
+        assert_eq!(resolve_attachment(&ctx, LATEST_IMAGE).unwrap(), root.join("images/new.jpg"));
+    }
+
+    #[test]
+    fn attachment_must_exist_inside_sandbox() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        assert!(resolve_attachment(&ctx, "missing.png").is_err());
+        assert!(resolve_attachment(&ctx, "/etc/passwd").is_err());
+        assert_eq!(mime_for(Path::new("chart.PNG")), "image/png");
+        assert_eq!(mime_for(Path::new("report.pdf")), "application/pdf");
+    }
+}
@@ -0,0 +1,106 @@
+//! Per-tool execution metrics for the Prometheus endpoint.
+//!
+//! [`crate::agent::actions::execute_tool_call`] records how long every call
+//! took and whether it succeeded; `/metrics` renders the totals as a
+//! histogram plus an outcome counter, both labelled by tool.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::metrics::{escape_label, Histogram};
+
+/// Running totals for one tool.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolStats {
+    pub duration: Histogram,
+    pub successes: u64,
+    pub failures: u64,
+}
+
+/// Execution metrics for all tools, keyed by tool name.
+#[derive(Debug, Default)]
+pub struct ToolMetrics {
+    stats: Mutex<BTreeMap<String, ToolStats>>,
+}
+
+impl ToolMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one call of `tool` that ran for `elapsed`.
+    pub fn record(&self, tool: &str, elapsed: Duration, success: bool) {
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(tool.to_string()).or_default();
+        entry.duration.observe(elapsed);
+        if success {
+            entry.successes += 1;
+        } else {
+            entry.failures += 1;
+        }
+    }
+
+    /// Totals for `tool`, if it has been called.
+    #[cfg(test)]
+    pub fn get(&self, tool: &str) -> Option<ToolStats> {
+        self.stats.lock().unwrap().get(tool).cloned()
+    }
+
+    /// The duration histogram and outcome counter in the Prometheus text
+    /// format.  Empty until a tool has run.
+    pub fn render_prometheus(&self) -> String {
+        let stats = self.stats.lock().unwrap();
+        if stats.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from(
+            "# HELP safe_agent_tool_duration_seconds Tool execution time in seconds.\n\
+             # TYPE safe_agent_tool_duration_seconds histogram\n",
+        );
+        for (tool, s) in stats.iter() {
+            let labels = format!("tool=\"{}\"", escape_label(tool));
+            s.duration.write_prometheus(&mut out, "safe_agent_tool_duration_seconds", &labels);
+        }
+
+        out.push_str(
+            "\n# HELP safe_agent_tool_executions_total Tool executions by outcome.\n\
+             # TYPE safe_agent_tool_executions_total counter\n",
+        );
+        for (tool, s) in stats.iter() {
+            let tool = escape_label(tool);
+            let _ = writeln!(out, "safe_agent_tool_executions_total{{tool=\"{tool}\",outcome=\"success\"}} {}", s.successes);
+            let _ = writeln!(out, "safe_agent_tool_executions_total{{tool=\"{tool}\",outcome=\"failure\"}} {}", s.failures);
+        }
+        out.push('\n');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_histogram_and_outcomes_per_tool() {
+        let metrics = ToolMetrics::new();
+        assert_eq!(metrics.render_prometheus(), "");
+
+        metrics.record("exec", Duration::from_millis(20), true);
+        metrics.record("exec", Duration::from_secs(3), false);
+        metrics.record("read_file", Duration::from_millis(1), true);
+
+        let stats = metrics.get("exec").unwrap();
+        assert_eq!((stats.duration.count(), stats.successes, stats.failures), (2, 1, 1));
+
+        let text = metrics.render_prometheus();
+        assert_eq!(text.matches("# TYPE safe_agent_tool_duration_seconds histogram\n").count(), 1);
+        assert!(text.contains("safe_agent_tool_duration_seconds_bucket{tool=\"exec\",le=\"0.025\"} 1\n"));
+        assert!(text.contains("safe_agent_tool_duration_seconds_count{tool=\"exec\"} 2\n"));
+        assert!(text.contains("safe_agent_tool_duration_seconds_count{tool=\"read_file\"} 1\n"));
+        assert!(text.contains("safe_agent_tool_executions_total{tool=\"exec\",outcome=\"success\"} 1\n"));
+        assert!(text.contains("safe_agent_tool_executions_total{tool=\"exec\",outcome=\"failure\"} 1\n"));
+    }
+}
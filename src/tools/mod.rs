@@ -1,14 +1,24 @@
 pub mod browser;
+pub mod capabilities;
 pub mod cron;
 pub mod exec;
 pub mod file;
+pub mod git;
 pub mod goal;
+pub mod http;
 pub mod image;
 pub mod knowledge;
 pub mod memory;
 pub mod message;
+pub mod metrics;
+pub mod patch;
 pub mod process;
+pub mod reminder;
+pub mod robots;
+pub mod scratch;
+pub mod search;
 pub mod sessions;
+pub mod structured;
 pub mod web;
 
 use std::collections::HashMap;
@@ -56,6 +66,32 @@ impl ToolOutput {
             metadata: Some(meta),
         }
     }
+
+    /// Record an image the tool produced (sandbox-relative path) under the
+    /// metadata's `artifacts` list.  When the tool ran for a chat message,
+    /// the agent sends the image to the chat along with its reply.
+    pub fn with_image(mut self, path: &str, mime_type: &str) -> Self {
+        let meta = self.metadata.get_or_insert_with(|| serde_json::json!({}));
+        if let Some(obj) = meta.as_object_mut() {
+            let artifacts = obj.entry("artifacts").or_insert_with(|| serde_json::json!([]));
+            if let Some(list) = artifacts.as_array_mut() {
+                list.push(serde_json::json!({ "path": path, "mime_type": mime_type }));
+            }
+        }
+        self
+    }
+
+    /// Image artifacts in the metadata, as (sandbox-relative path, MIME type).
+    pub fn image_artifacts(&self) -> Vec<(String, String)> {
+        let Some(list) = self.metadata.as_ref().and_then(|m| m.get("artifacts")).and_then(|a| a.as_array()) else {
+            return Vec::new();
+        };
+        list.iter()
+            .filter_map(|a| Some((a.get("path")?.as_str()?, a.get("mime_type")?.as_str()?)))
+            .filter(|(_, mime)| mime.starts_with("image/"))
+            .map(|(path, mime)| (path.to_string(), mime.to_string()))
+            .collect()
+    }
 }
 
 /// A tool call proposed by the LLM.
@@ -75,6 +111,13 @@ pub struct ToolContext {
     pub trash: Arc<TrashManager>,
 }
 
+impl ToolContext {
+    /// The key-value scratchpad shared by all tools.
+    pub fn scratchpad(&self) -> scratch::Scratchpad<'_> {
+        scratch::Scratchpad::new(&self.db)
+    }
+}
+
 /// The trait all tools implement.
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -89,20 +132,61 @@ pub trait Tool: Send + Sync {
 
     /// Execute the tool with the given parameters.
     async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput>;
+
+    /// Whether a call with these parameters must go through human approval
+    /// even when the tool is auto-approved (e.g. destructive actions).
+    fn requires_approval(&self, _params: &serde_json::Value) -> bool {
+        false
+    }
+
+    /// Whether the tool only reads state and never changes anything outside
+    /// the agent.  Everything else is treated as side-effecting and needs
+    /// approval while the agent is in safe mode.
+    fn is_read_only(&self) -> bool {
+        false
+    }
 }
 
 /// Registry of all available tools.
 pub struct ToolRegistry {
     tools: HashMap<String, Box<dyn Tool>>,
+    /// Output byte cap for tools without an override; 0 means unlimited.
+    max_output_bytes: usize,
+    max_output_bytes_per_tool: HashMap<String, usize>,
+    metrics: metrics::ToolMetrics,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            max_output_bytes: 0,
+            max_output_bytes_per_tool: HashMap::new(),
+            metrics: metrics::ToolMetrics::new(),
         }
     }
 
+    /// Set the global and per-tool output byte caps (0 = unlimited).
+    pub fn set_output_limits(&mut self, default: usize, per_tool: HashMap<String, usize>) {
+        self.max_output_bytes = default;
+        self.max_output_bytes_per_tool = per_tool;
+    }
+
+    /// Output byte cap for `tool`, or `None` when its output is unlimited.
+    pub fn output_limit(&self, tool: &str) -> Option<usize> {
+        let limit = self
+            .max_output_bytes_per_tool
+            .get(tool)
+            .copied()
+            .unwrap_or(self.max_output_bytes);
+        (limit > 0).then_some(limit)
+    }
+
+    /// Execution time and outcome totals per tool.
+    pub fn metrics(&self) -> &metrics::ToolMetrics {
+        &self.metrics
+    }
+
     /// Register a tool. Panics on duplicate names.
     pub fn register(&mut self, tool: Box<dyn Tool>) {
         let name = tool.name().to_string();
@@ -118,6 +202,17 @@ impl ToolRegistry {
         self.tools.get(name).map(|t| t.as_ref())
     }
 
+    /// Whether the tool itself insists on human approval for `call`.
+    pub fn requires_approval(&self, call: &ToolCall) -> bool {
+        self.get(&call.tool)
+            .is_some_and(|t| t.requires_approval(&call.params))
+    }
+
+    /// Whether `tool` is registered and declares itself read-only.
+    pub fn is_read_only(&self, tool: &str) -> bool {
+        self.get(tool).is_some_and(|t| t.is_read_only())
+    }
+
     /// List all registered tools as (name, description) pairs.
     pub fn list(&self) -> Vec<(&str, &str)> {
         let mut items: Vec<_> = self
@@ -129,18 +224,66 @@ impl ToolRegistry {
         items
     }
 
-    /// Execute a tool by name.
+    /// Full catalog for external callers: name, description, the JSON
+    /// Schema of the parameters and whether the tool is read-only, sorted
+    /// by name.
+    pub fn schemas(&self) -> Vec<serde_json::Value> {
+        let mut tools: Vec<_> = self.tools.values().collect();
+        tools.sort_by_key(|t| t.name());
+        tools
+            .into_iter()
+            .map(|t| {
+                serde_json::json!({
+                    "name": t.name(),
+                    "description": t.description(),
+                    "parameters": t.parameters_schema(),
+                    "read_only": t.is_read_only(),
+                })
+            })
+            .collect()
+    }
+
+    /// OpenAI-style function definitions for every tool, sorted by name,
+    /// for backends with native function calling.
+    pub fn function_schemas(&self) -> Vec<serde_json::Value> {
+        let mut tools: Vec<_> = self.tools.values().collect();
+        tools.sort_by_key(|t| t.name());
+        tools
+            .into_iter()
+            .map(|t| {
+                serde_json::json!({
+                    "type": "function",
+                    "function": {
+                        "name": t.name(),
+                        "description": t.description(),
+                        "parameters": t.parameters_schema(),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Execute a tool by name.  The scratchpad hooks (`save_to`,
+    /// `from_scratch`) are applied around the call for every tool.
     pub async fn execute(
         &self,
         name: &str,
-        params: serde_json::Value,
+        mut params: serde_json::Value,
         ctx: &ToolContext,
     ) -> Result<ToolOutput> {
         let tool = self
             .tools
             .get(name)
             .ok_or_else(|| SafeAgentError::ToolNotFound(name.to_string()))?;
-        tool.execute(params, ctx).await
+        let save_to = match scratch::take_hooks(&mut params, ctx).await {
+            Ok(key) => key,
+            Err(e) => return Ok(ToolOutput::error(e.to_string())),
+        };
+        let output = tool.execute(params, ctx).await?;
+        match save_to {
+            Some(key) => scratch::save_output(ctx, &key, output).await,
+            None => Ok(output),
+        }
     }
 
     /// Number of registered tools.
@@ -153,6 +296,43 @@ impl ToolRegistry {
     }
 }
 
+/// Check `params` against the top level of a tool's parameter schema:
+/// an object, with every `required` key present and known properties of
+/// the declared `type` (and `enum`, if any).  Deeper structure is left to
+/// the tool.
+pub fn validate_params(schema: &serde_json::Value, params: &serde_json::Value) -> std::result::Result<(), String> {
+    let Some(obj) = params.as_object() else {
+        return Err("arguments must be an object".into());
+    };
+    for key in schema["required"].as_array().into_iter().flatten().filter_map(|k| k.as_str()) {
+        if !obj.contains_key(key) {
+            return Err(format!("missing required argument '{key}'"));
+        }
+    }
+    let props = schema["properties"].as_object();
+    for (key, value) in obj {
+        let Some(prop) = props.and_then(|p| p.get(key)) else {
+            continue;
+        };
+        let type_ok = match prop["type"].as_str() {
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("number") => value.is_number(),
+            Some("boolean") => value.is_boolean(),
+            Some("array") => value.is_array(),
+            Some("object") => value.is_object(),
+            _ => true,
+        };
+        if !type_ok {
+            return Err(format!("argument '{key}' must be of type {}", prop["type"]));
+        }
+        if let Some(allowed) = prop["enum"].as_array().filter(|a| !a.contains(value)) {
+            return Err(format!("argument '{key}' must be one of {}", serde_json::Value::from(allowed.clone())));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,6 +392,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn output_limit_per_tool_override() {
+        let mut reg = ToolRegistry::new();
+        assert_eq!(reg.output_limit("exec"), None);
+
+        let per_tool = HashMap::from([("exec".to_string(), 500), ("read_file".to_string(), 0)]);
+        reg.set_output_limits(1000, per_tool);
+        assert_eq!(reg.output_limit("exec"), Some(500));
+        assert_eq!(reg.output_limit("read_file"), None);
+        assert_eq!(reg.output_limit("web_fetch"), Some(1000));
+    }
+
     #[test]
     fn test_tool_output_ok() {
         let out = ToolOutput::ok("success");
@@ -237,6 +429,16 @@ mod tests {
         assert_eq!(out.metadata, Some(meta));
     }
 
+    #[test]
+    fn test_tool_output_image_artifacts() {
+        let out = ToolOutput::ok_with_meta("drew it", serde_json::json!({"seed": 7}))
+            .with_image("out/cat.png", "image/png")
+            .with_image("out/notes.txt", "text/plain");
+        assert_eq!(out.metadata.as_ref().unwrap()["seed"], 7);
+        assert_eq!(out.image_artifacts(), vec![("out/cat.png".to_string(), "image/png".to_string())]);
+        assert!(ToolOutput::ok("none").image_artifacts().is_empty());
+    }
+
     #[test]
     fn test_tool_registry_new() {
         let reg = ToolRegistry::new();
@@ -271,6 +473,62 @@ mod tests {
         assert_eq!(list[1].0, "mock_b");
     }
 
+    /// The subset of JSON Schema callers rely on: an object schema whose
+    /// properties are schemas and whose required keys exist.
+    fn assert_object_schema(name: &str, schema: &serde_json::Value) {
+        assert_eq!(schema["type"], "object", "{name}: parameters must be an object schema");
+        let props = schema["properties"].as_object().unwrap_or_else(|| panic!("{name}: properties missing"));
+        for (key, prop) in props {
+            assert!(prop.is_object(), "{name}.{key}: property schema must be an object");
+        }
+        for key in schema["required"].as_array().into_iter().flatten() {
+            let key = key.as_str().unwrap_or_else(|| panic!("{name}: required entries must be strings"));
+            assert!(props.contains_key(key), "{name}: required '{key}' is not a property");
+        }
+    }
+
+    #[test]
+    fn test_tool_registry_schemas_include_parameters() {
+        let mut reg = ToolRegistry::new();
+        reg.register(Box::new(MockTool { name: "mock", description: "A mock" }));
+        reg.register(Box::new(file::ReadFileTool));
+        reg.register(Box::new(file::WriteFileTool));
+        reg.register(Box::new(memory::MemoryForgetTool));
+        reg.register(Box::new(scratch::ScratchSetTool));
+
+        let schemas = reg.schemas();
+        assert_eq!(schemas.len(), 5);
+        let mock = schemas.iter().find(|s| s["name"] == "mock").unwrap();
+        assert_eq!(mock["description"], "A mock");
+        assert_eq!(mock["parameters"]["properties"]["input"]["type"], "string");
+
+        // Round-trips as JSON and every schema is well-formed.
+        let text = serde_json::to_string(&schemas).unwrap();
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&text).unwrap();
+        for entry in &parsed {
+            assert_object_schema(entry["name"].as_str().unwrap(), &entry["parameters"]);
+        }
+    }
+
+    #[test]
+    fn test_validate_params_against_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "limit": { "type": "integer" },
+                "mode": { "type": "string", "enum": ["fast", "full"] }
+            },
+            "required": ["path"]
+        });
+        assert!(validate_params(&schema, &serde_json::json!({ "path": "a", "limit": 3, "extra": true })).is_ok());
+        assert!(validate_params(&schema, &serde_json::json!({ "limit": 3 })).unwrap_err().contains("'path'"));
+        assert!(validate_params(&schema, &serde_json::json!({ "path": 1 })).unwrap_err().contains("type \"string\""));
+        assert!(validate_params(&schema, &serde_json::json!({ "path": "a", "limit": 1.5 })).is_err());
+        assert!(validate_params(&schema, &serde_json::json!({ "path": "a", "mode": "slow" })).is_err());
+        assert!(validate_params(&schema, &serde_json::json!(["a"])).is_err());
+    }
+
     #[tokio::test]
     async fn test_tool_registry_execute_unknown_tool() {
         let reg = ToolRegistry::new();
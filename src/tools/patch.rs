@@ -0,0 +1,286 @@
+//! Unified diff parsing and application for the `apply_patch` tool.
+//!
+//! Patches are applied in memory: every hunk of every file must match the
+//! current contents before anything is written, so a stale or malformed
+//! patch is rejected whole instead of leaving files half-patched.  Context
+//! must match exactly; like `patch`, a hunk may be found at an offset from
+//! the line its header names.
+
+/// The changes to one file.
+#[derive(Debug)]
+pub struct FilePatch {
+    /// `None` when the file is being created (`--- /dev/null`).
+    pub old_path: Option<String>,
+    /// `None` when the file is being deleted (`+++ /dev/null`).
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// The file the patch reads or writes.
+    pub fn path(&self) -> &str {
+        self.new_path.as_deref().or(self.old_path.as_deref()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+pub struct Hunk {
+    /// The `@@ ... @@` line, for error messages.
+    header: String,
+    /// 1-based first line of the old side (the line after which to insert
+    /// when the old side is empty).
+    old_start: usize,
+    lines: Vec<Line>,
+    /// `\ No newline at end of file` followed the old / new side's last line.
+    old_no_newline: bool,
+    new_no_newline: bool,
+}
+
+#[derive(Debug)]
+enum Line {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                Line::Context(s) | Line::Remove(s) => Some(s.as_str()),
+                Line::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                Line::Context(s) | Line::Add(s) => Some(s.as_str()),
+                Line::Remove(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Parse a unified diff, which may cover several files.
+pub fn parse(patch: &str) -> Result<Vec<FilePatch>, String> {
+    let mut files = Vec::new();
+    let mut lines = patch.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(old) = line.strip_prefix("--- ") else {
+            // `diff --git`, `index`, and other preamble
+            continue;
+        };
+        let new = lines
+            .next()
+            .and_then(|l| l.strip_prefix("+++ "))
+            .ok_or_else(|| format!("expected a '+++' line after '{line}'"))?;
+        let mut file = FilePatch { old_path: patch_path(old)?, new_path: patch_path(new)?, hunks: Vec::new() };
+        if file.old_path.is_none() && file.new_path.is_none() {
+            return Err(format!("'{line}' names no file"));
+        }
+
+        while let Some(header) = lines.next_if(|l| l.starts_with("@@")) {
+            let number = file.hunks.len() + 1;
+            let (old_start, old_count, new_count) =
+                parse_header(header).ok_or_else(|| format!("{}: hunk {number}: malformed header '{header}'", file.path()))?;
+            let mut hunk = Hunk {
+                header: header.to_string(),
+                old_start,
+                lines: Vec::new(),
+                old_no_newline: false,
+                new_no_newline: false,
+            };
+            let (mut old_seen, mut new_seen) = (0, 0);
+            while old_seen < old_count || new_seen < new_count {
+                let Some(line) = lines.next() else {
+                    break;
+                };
+                // Some editors strip the single space from empty context lines
+                let (tag, text) = match line.chars().next() {
+                    None => (' ', ""),
+                    Some(c) => (c, &line[c.len_utf8()..]),
+                };
+                match tag {
+                    ' ' => {
+                        old_seen += 1;
+                        new_seen += 1;
+                        hunk.lines.push(Line::Context(text.to_string()));
+                    }
+                    '-' => {
+                        old_seen += 1;
+                        hunk.lines.push(Line::Remove(text.to_string()));
+                    }
+                    '+' => {
+                        new_seen += 1;
+                        hunk.lines.push(Line::Add(text.to_string()));
+                    }
+                    '\\' => mark_no_newline(&mut hunk),
+                    _ => {
+                        return Err(format!(
+                            "{}: hunk {number} ({header}): unexpected line '{line}'",
+                            file.path()
+                        ));
+                    }
+                }
+            }
+            if old_seen != old_count || new_seen != new_count {
+                return Err(format!(
+                    "{}: hunk {number} ({header}): expected {old_count} old and {new_count} new lines, found {old_seen} and {new_seen}",
+                    file.path()
+                ));
+            }
+            if lines.next_if(|l| l.starts_with('\\')).is_some() {
+                mark_no_newline(&mut hunk);
+            }
+            file.hunks.push(hunk);
+        }
+        if file.hunks.is_empty() {
+            return Err(format!("{}: no hunks", file.path()));
+        }
+        files.push(file);
+    }
+
+    if files.is_empty() {
+        return Err("no file changes found; expected a unified diff with '---' and '+++' lines".into());
+    }
+    Ok(files)
+}
+
+/// `\ No newline at end of file` applies to the line just before it.
+fn mark_no_newline(hunk: &mut Hunk) {
+    match hunk.lines.last() {
+        Some(Line::Remove(_)) => hunk.old_no_newline = true,
+        Some(Line::Add(_)) => hunk.new_no_newline = true,
+        Some(Line::Context(_)) => {
+            hunk.old_no_newline = true;
+            hunk.new_no_newline = true;
+        }
+        None => {}
+    }
+}
+
+/// The path in a `---`/`+++` line, without a timestamp or the `a/`/`b/`
+/// prefix git adds.
+fn patch_path(field: &str) -> Result<Option<String>, String> {
+    let path = field.split('\t').next().unwrap_or_default().trim();
+    if path == "/dev/null" {
+        return Ok(None);
+    }
+    let path = path.strip_prefix("a/").or_else(|| path.strip_prefix("b/")).unwrap_or(path);
+    if path.is_empty() {
+        return Err("patch has an empty file name".into());
+    }
+    Ok(Some(path.to_string()))
+}
+
+/// `@@ -l[,c] +l[,c] @@` -> (old start, old count, new count).
+fn parse_header(header: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = header.strip_prefix("@@ ")?.split(' ');
+    let range = |s: &str| -> Option<(usize, usize)> {
+        match s.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((s.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = range(parts.next()?.strip_prefix('-')?)?;
+    let (_, new_count) = range(parts.next()?.strip_prefix('+')?)?;
+    (parts.next()? == "@@").then_some((old_start, old_count, new_count))
+}
+
+/// Apply `file`'s hunks to `original`.  Fails, naming the hunk, if any of
+/// them doesn't match.
+pub fn apply(original: &str, file: &FilePatch) -> Result<String, String> {
+    let lines: Vec<&str> = original.lines().collect();
+    let mut ends_with_newline = original.is_empty() || original.ends_with('\n');
+    let mut out: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut cursor = 0;
+
+    for (i, hunk) in file.hunks.iter().enumerate() {
+        let old = hunk.old_lines();
+        let expected = if old.is_empty() { hunk.old_start } else { hunk.old_start.saturating_sub(1) };
+        let pos = find_hunk(&lines, &old, cursor, expected).ok_or_else(|| {
+            format!(
+                "{}: hunk {} ({}) does not match the file near line {}",
+                file.path(),
+                i + 1,
+                hunk.header,
+                hunk.old_start
+            )
+        })?;
+        out.extend_from_slice(&lines[cursor..pos]);
+        out.extend(hunk.new_lines());
+        cursor = pos + old.len();
+        if cursor == lines.len() && (hunk.old_no_newline || hunk.new_no_newline || !old.is_empty()) {
+            ends_with_newline = !hunk.new_no_newline;
+        }
+    }
+    out.extend_from_slice(&lines[cursor..]);
+
+    let mut result = out.join("\n");
+    if ends_with_newline && !out.is_empty() {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// Where `old` occurs in `lines` at or after `from`, closest to `expected`.
+fn find_hunk(lines: &[&str], old: &[&str], from: usize, expected: usize) -> Option<usize> {
+    let last = lines.len().checked_sub(old.len())?;
+    if from > last {
+        return None;
+    }
+    let matches = |pos: usize| lines[pos..pos + old.len()] == *old;
+    let expected = expected.clamp(from, last);
+    (0..=last - from).find_map(|offset| {
+        [expected.checked_add(offset), expected.checked_sub(offset)]
+            .into_iter()
+            .flatten()
+            .find(|&pos| pos >= from && pos <= last && matches(pos))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply_one(original: &str, patch: &str) -> Result<String, String> {
+        let files = parse(patch)?;
+        apply(original, &files[0])
+    }
+
+    #[test]
+    fn parses_git_style_paths_and_creations() {
+        let files = parse(
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n\
+             --- /dev/null\n+++ b/new.txt\n@@ -0,0 +1,2 @@\n+one\n+two\n",
+        )
+        .unwrap();
+        assert_eq!(files[0].path(), "src/lib.rs");
+        assert!(files[1].old_path.is_none());
+        assert_eq!(apply("", &files[1]).unwrap(), "one\ntwo\n");
+    }
+
+    #[test]
+    fn hunk_found_at_an_offset() {
+        let original = "intro\nextra\na\nb\nc\n";
+        let patch = "--- a/f\n+++ b/f\n@@ -2,3 +2,3 @@\n a\n-b\n+B\n c\n";
+        assert_eq!(apply_one(original, patch).unwrap(), "intro\nextra\na\nB\nc\n");
+    }
+
+    #[test]
+    fn handles_missing_trailing_newline() {
+        let patch = "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n";
+        assert_eq!(apply_one("a\nb", patch).unwrap(), "a\nc\n");
+    }
+
+    #[test]
+    fn miscounted_hunk_is_a_parse_error() {
+        let err = parse("--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n-b\n+c\n").unwrap_err();
+        assert!(err.contains("hunk 1") && err.contains("expected 3 old"), "{err}");
+    }
+}
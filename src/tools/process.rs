@@ -1,31 +1,301 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use serde::Serialize;
+use tokio::process::Child;
 use tokio::sync::Mutex;
 use tracing::debug;
 
+use super::exec::build_sandboxed_command;
 use super::{Tool, ToolContext, ToolOutput};
 use crate::error::Result;
 
-/// Tracks background processes spawned by the exec tool.
-#[derive(Debug)]
+/// How long `stop` waits after SIGTERM before killing the process group.
+const STOP_GRACE: Duration = Duration::from_secs(2);
+
+/// Lifecycle of a tracked background process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessStatus {
+    Running,
+    /// Exited on its own.
+    Exited,
+    /// Terminated through `stop`.
+    Stopped,
+}
+
+/// A background process started by the tool.  The child handle is dropped
+/// once the process is no longer running.
 struct ProcessEntry {
     pid: u32,
     command: String,
     started_at: chrono::DateTime<chrono::Utc>,
+    status: ProcessStatus,
+    exit_code: Option<i32>,
+    child: Option<Child>,
+}
+
+impl ProcessEntry {
+    /// Pick up an exit that happened since the last look.
+    fn refresh(&mut self) {
+        let Some(child) = self.child.as_mut() else {
+            return;
+        };
+        if let Ok(Some(status)) = child.try_wait() {
+            self.status = ProcessStatus::Exited;
+            self.exit_code = status.code();
+            self.child = None;
+        }
+    }
+
+    fn info(&self, handle: u64) -> ProcessInfo {
+        let usage = if self.status == ProcessStatus::Running {
+            resource_usage(self.pid)
+        } else {
+            ResourceUsage::default()
+        };
+        ProcessInfo {
+            handle: format!("p{handle}"),
+            pid: self.pid,
+            command: self.command.clone(),
+            status: self.status,
+            exit_code: self.exit_code,
+            started_at: self.started_at,
+            cpu_secs: usage.cpu_secs,
+            memory_kb: usage.memory_kb,
+        }
+    }
+}
+
+/// Structured view of a tracked process, returned in `ToolOutput.metadata`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub handle: String,
+    pub pid: u32,
+    pub command: String,
+    pub status: ProcessStatus,
+    pub exit_code: Option<i32>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// User + system CPU time, where the platform exposes it.
+    pub cpu_secs: Option<f64>,
+    /// Resident set size, where the platform exposes it.
+    pub memory_kb: Option<u64>,
+}
+
+impl ProcessInfo {
+    fn summary(&self) -> String {
+        let mut line = format!("[{}] PID {} — {} ({}", self.handle, self.pid, self.command, status_label(self.status));
+        if let Some(code) = self.exit_code {
+            line.push_str(&format!(", exit code {code}"));
+        }
+        line.push_str(&format!(", started {})", self.started_at));
+        line
+    }
+}
+
+fn status_label(status: ProcessStatus) -> &'static str {
+    match status {
+        ProcessStatus::Running => "running",
+        ProcessStatus::Exited => "exited",
+        ProcessStatus::Stopped => "stopped",
+    }
+}
+
+#[derive(Default)]
+struct ResourceUsage {
+    cpu_secs: Option<f64>,
+    memory_kb: Option<u64>,
+}
+
+/// CPU time and RSS from procfs.
+#[cfg(target_os = "linux")]
+fn resource_usage(pid: u32) -> ResourceUsage {
+    let memory_kb = std::fs::read_to_string(format!("/proc/{pid}/status"))
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|l| l.strip_prefix("VmRSS:"))
+                .and_then(|v| v.trim().trim_end_matches("kB").trim().parse().ok())
+        });
+    // Fields after the parenthesised command name start at `state` (field
+    // 3), so utime and stime (fields 14 and 15) are at indexes 11 and 12.
+    let cpu_secs = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|stat| {
+            let rest = &stat[stat.rfind(')')? + 1..];
+            let fields: Vec<&str> = rest.split_whitespace().collect();
+            let utime: u64 = fields.get(11)?.parse().ok()?;
+            let stime: u64 = fields.get(12)?.parse().ok()?;
+            let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+            (ticks > 0).then(|| (utime + stime) as f64 / ticks as f64)
+        });
+    ResourceUsage { cpu_secs, memory_kb }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resource_usage(_pid: u32) -> ResourceUsage {
+    ResourceUsage::default()
 }
 
 pub struct ProcessTool {
-    processes: Arc<Mutex<HashMap<String, ProcessEntry>>>,
+    processes: Arc<Mutex<BTreeMap<u64, ProcessEntry>>>,
+    next_handle: std::sync::atomic::AtomicU64,
+    /// Whether `start` may launch commands; off when `tools.exec` is.
+    allow_start: bool,
 }
 
 impl ProcessTool {
-    pub fn new() -> Self {
+    pub fn new(allow_start: bool) -> Self {
         Self {
-            processes: Arc::new(Mutex::new(HashMap::new())),
+            processes: Arc::new(Mutex::new(BTreeMap::new())),
+            next_handle: std::sync::atomic::AtomicU64::new(1),
+            allow_start,
         }
     }
+
+    async fn start(&self, params: &serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        if !self.allow_start {
+            return Ok(ToolOutput::error("start is disabled because the exec tool is disabled"));
+        }
+        let command = params.get("command").and_then(|v| v.as_str()).unwrap_or_default();
+        if command.is_empty() {
+            return Ok(ToolOutput::error("command is required for start action"));
+        }
+        let work_dir = match params.get("cwd").and_then(|v| v.as_str()) {
+            Some(rel) => ctx.sandbox.resolve(std::path::Path::new(rel))?,
+            None => ctx.sandbox.root().to_path_buf(),
+        };
+
+        let mut cmd = build_sandboxed_command(command, &work_dir, &ctx.trash);
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        // Own process group so `stop` reaches the shell's children too.
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => return Ok(ToolOutput::error(format!("failed to start: {e}"))),
+        };
+        let Some(pid) = child.id() else {
+            return Ok(ToolOutput::error("process exited before it could be tracked"));
+        };
+        let handle = self.next_handle.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        debug!(handle, pid, command, "started background process");
+
+        let entry = ProcessEntry {
+            pid,
+            command: command.to_string(),
+            started_at: chrono::Utc::now(),
+            status: ProcessStatus::Running,
+            exit_code: None,
+            child: Some(child),
+        };
+        let info = entry.info(handle);
+        self.processes.lock().await.insert(handle, entry);
+        Ok(single(format!("Started {}", info.summary()), &info))
+    }
+
+    async fn stop(&self, params: &serde_json::Value) -> Result<ToolOutput> {
+        let (handle, pid, child) = {
+            let mut procs = self.processes.lock().await;
+            let handle = match find(&procs, params) {
+                Ok(handle) => handle,
+                Err(msg) => return Ok(ToolOutput::error(msg)),
+            };
+            let entry = procs.get_mut(&handle).expect("handle just looked up");
+            entry.refresh();
+            (handle, entry.pid, entry.child.take())
+        };
+
+        // The grace period is waited out without the lock, so other
+        // actions aren't held up behind it.
+        if let Some(mut child) = child {
+            debug!(handle, pid, "stopping background process");
+            #[cfg(unix)]
+            {
+                unsafe {
+                    libc::kill(-(pid as i32), libc::SIGTERM);
+                }
+                if tokio::time::timeout(STOP_GRACE, child.wait()).await.is_err() {
+                    unsafe {
+                        libc::kill(-(pid as i32), libc::SIGKILL);
+                    }
+                }
+            }
+            let _ = child.kill().await;
+            if let Some(entry) = self.processes.lock().await.get_mut(&handle) {
+                entry.status = ProcessStatus::Stopped;
+            }
+        }
+
+        let procs = self.processes.lock().await;
+        let entry = procs.get(&handle).expect("entries are never removed");
+        let info = entry.info(handle);
+        Ok(single(format!("Stopped {}", info.summary()), &info))
+    }
+
+    async fn status(&self, params: &serde_json::Value) -> Result<ToolOutput> {
+        let mut procs = self.processes.lock().await;
+        let handle = match find(&procs, params) {
+            Ok(handle) => handle,
+            Err(msg) => return Ok(ToolOutput::error(msg)),
+        };
+        let entry = procs.get_mut(&handle).expect("handle just looked up");
+        entry.refresh();
+        let info = entry.info(handle);
+        Ok(single(info.summary(), &info))
+    }
+
+    async fn list(&self) -> Result<ToolOutput> {
+        let mut procs = self.processes.lock().await;
+        let infos: Vec<ProcessInfo> = procs
+            .iter_mut()
+            .map(|(handle, entry)| {
+                entry.refresh();
+                entry.info(*handle)
+            })
+            .collect();
+        let meta = serde_json::json!({ "processes": infos });
+        if infos.is_empty() {
+            return Ok(ToolOutput::ok_with_meta("No background processes running.", meta));
+        }
+        let mut out = String::from("Background processes:\n");
+        for info in &infos {
+            out.push_str(&format!("  {}\n", info.summary()));
+        }
+        Ok(ToolOutput::ok_with_meta(out, meta))
+    }
+}
+
+/// Output carrying one process as `metadata.process`.
+fn single(text: String, info: &ProcessInfo) -> ToolOutput {
+    ToolOutput::ok_with_meta(text, serde_json::json!({ "process": info }))
+}
+
+/// Resolve the `handle` (e.g. "p3") or `pid` parameter to a tracked entry.
+fn find(procs: &BTreeMap<u64, ProcessEntry>, params: &serde_json::Value) -> std::result::Result<u64, String> {
+    if let Some(handle) = params.get("handle").and_then(|v| v.as_str()) {
+        return handle
+            .strip_prefix('p')
+            .and_then(|n| n.parse().ok())
+            .filter(|n| procs.contains_key(n))
+            .ok_or_else(|| format!("no tracked process with handle {handle}"));
+    }
+    match params.get("pid").and_then(|v| v.as_u64()) {
+        Some(pid) => procs
+            .iter()
+            .find(|(_, e)| u64::from(e.pid) == pid)
+            .map(|(h, _)| *h)
+            .ok_or_else(|| format!("no tracked process with PID {pid}")),
+        None => Err("handle or pid is required".to_string()),
+    }
 }
 
 #[async_trait]
@@ -35,7 +305,7 @@ impl Tool for ProcessTool {
     }
 
     fn description(&self) -> &str {
-        "Manage background processes. Actions: list, kill."
+        "Manage long-running background processes. Actions: start (run a shell command in the background and return a handle), list, status, stop. Results include structured process data (pid, status, exit code, CPU and memory where available)."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -45,60 +315,40 @@ impl Tool for ProcessTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["list", "kill"],
-                    "description": "Action to perform"
+                    "enum": ["start", "list", "status", "stop", "kill"],
+                    "description": "Action to perform (kill is an alias for stop)"
+                },
+                "command": {
+                    "type": "string",
+                    "description": "Shell command to run (required for start)"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory for start (relative to sandbox root)"
+                },
+                "handle": {
+                    "type": "string",
+                    "description": "Process handle returned by start, e.g. \"p1\" (status/stop)"
                 },
                 "pid": {
                     "type": "integer",
-                    "description": "Process ID (required for kill)"
+                    "description": "Process ID, as an alternative to handle (status/stop)"
                 }
             }
         })
     }
 
-    async fn execute(&self, params: serde_json::Value, _ctx: &ToolContext) -> Result<ToolOutput> {
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
         let action = params
             .get("action")
             .and_then(|v| v.as_str())
             .unwrap_or_default();
 
         match action {
-            "list" => {
-                let procs = self.processes.lock().await;
-                if procs.is_empty() {
-                    return Ok(ToolOutput::ok("No background processes running."));
-                }
-                let mut out = String::from("Background processes:\n");
-                for (id, entry) in procs.iter() {
-                    out.push_str(&format!(
-                        "  [{}] PID {} — {} (started {})\n",
-                        id, entry.pid, entry.command, entry.started_at
-                    ));
-                }
-                Ok(ToolOutput::ok(out))
-            }
-            "kill" => {
-                let pid = params
-                    .get("pid")
-                    .and_then(|v| v.as_u64())
-                    .map(|p| p as u32);
-
-                match pid {
-                    Some(pid) => {
-                        debug!(pid, "killing process");
-                        // Send SIGTERM via kill command
-                        let _ = tokio::process::Command::new("kill")
-                            .arg("-TERM")
-                            .arg(pid.to_string())
-                            .output()
-                            .await;
-                        let mut procs = self.processes.lock().await;
-                        procs.retain(|_, e| e.pid != pid);
-                        Ok(ToolOutput::ok(format!("Sent SIGTERM to PID {pid}")))
-                    }
-                    None => Ok(ToolOutput::error("pid is required for kill action")),
-                }
-            }
+            "start" => self.start(&params, ctx).await,
+            "list" => self.list().await,
+            "status" => self.status(&params).await,
+            "stop" | "kill" => self.stop(&params).await,
             other => Ok(ToolOutput::error(format!("unknown action: {other}"))),
         }
     }
@@ -131,7 +381,7 @@ mod tests {
     #[tokio::test]
     async fn list_empty() {
         let ctx = test_ctx();
-        let tool = ProcessTool::new();
+        let tool = ProcessTool::new(true);
         let r = tool.execute(serde_json::json!({"action": "list"}), &ctx).await.unwrap();
         assert!(r.success);
         assert!(r.output.contains("No background processes"));
@@ -140,7 +390,7 @@ mod tests {
     #[tokio::test]
     async fn kill_missing_pid() {
         let ctx = test_ctx();
-        let tool = ProcessTool::new();
+        let tool = ProcessTool::new(true);
         let r = tool.execute(serde_json::json!({"action": "kill"}), &ctx).await.unwrap();
         assert!(!r.success);
         assert!(r.output.contains("pid is required"));
@@ -149,15 +399,115 @@ mod tests {
     #[tokio::test]
     async fn unknown_action() {
         let ctx = test_ctx();
-        let tool = ProcessTool::new();
+        let tool = ProcessTool::new(true);
         let r = tool.execute(serde_json::json!({"action": "restart"}), &ctx).await.unwrap();
         assert!(!r.success);
         assert!(r.output.contains("unknown action"));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn list_returns_structured_entries() {
+        let ctx = test_ctx();
+        let tool = ProcessTool::new(true);
+        let started = tool
+            .execute(serde_json::json!({"action": "start", "command": "sleep 30"}), &ctx)
+            .await
+            .unwrap();
+        assert!(started.success, "{}", started.output);
+        assert_eq!(started.metadata.as_ref().unwrap()["process"]["handle"], "p1");
+
+        let r = tool.execute(serde_json::json!({"action": "list"}), &ctx).await.unwrap();
+        let procs = r.metadata.unwrap()["processes"].as_array().unwrap().clone();
+        assert_eq!(procs.len(), 1);
+        assert_eq!(procs[0]["handle"], "p1");
+        assert_eq!(procs[0]["command"], "sleep 30");
+        assert_eq!(procs[0]["status"], "running");
+        assert!(procs[0]["pid"].as_u64().unwrap() > 0);
+        assert!(procs[0]["exit_code"].is_null());
+
+        tool.execute(serde_json::json!({"action": "stop", "handle": "p1"}), &ctx).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn start_and_stop_update_tracked_state() {
+        let ctx = test_ctx();
+        let tool = ProcessTool::new(true);
+        let started = tool
+            .execute(serde_json::json!({"action": "start", "command": "sleep 30"}), &ctx)
+            .await
+            .unwrap();
+        let pid = started.metadata.unwrap()["process"]["pid"].as_u64().unwrap();
+
+        let stopped = tool
+            .execute(serde_json::json!({"action": "stop", "pid": pid}), &ctx)
+            .await
+            .unwrap();
+        assert!(stopped.success);
+        assert_eq!(stopped.metadata.unwrap()["process"]["status"], "stopped");
+
+        let status = tool
+            .execute(serde_json::json!({"action": "status", "handle": "p1"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(status.metadata.unwrap()["process"]["status"], "stopped");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn status_reports_exit_code() {
+        let ctx = test_ctx();
+        let tool = ProcessTool::new(true);
+        tool.execute(serde_json::json!({"action": "start", "command": "exit 3"}), &ctx)
+            .await
+            .unwrap();
+
+        let mut process = serde_json::Value::Null;
+        for _ in 0..50 {
+            let r = tool
+                .execute(serde_json::json!({"action": "status", "handle": "p1"}), &ctx)
+                .await
+                .unwrap();
+            process = r.metadata.unwrap()["process"].clone();
+            if process["status"] != "running" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(process["status"], "exited");
+        assert_eq!(process["exit_code"], 3);
+    }
+
+    #[tokio::test]
+    async fn stop_unknown_handle() {
+        let ctx = test_ctx();
+        let tool = ProcessTool::new(true);
+        let r = tool
+            .execute(serde_json::json!({"action": "stop", "handle": "p9"}), &ctx)
+            .await
+            .unwrap();
+        assert!(!r.success);
+        assert!(r.output.contains("no tracked process"));
+    }
+
+    #[tokio::test]
+    async fn start_refused_when_exec_is_disabled() {
+        let ctx = test_ctx();
+        let tool = ProcessTool::new(false);
+        let r = tool
+            .execute(serde_json::json!({"action": "start", "command": "sleep 30"}), &ctx)
+            .await
+            .unwrap();
+        assert!(!r.success);
+        assert!(r.output.contains("disabled"), "{}", r.output);
+        let r = tool.execute(serde_json::json!({"action": "list"}), &ctx).await.unwrap();
+        assert!(r.output.contains("No background processes"));
+    }
+
     #[test]
     fn tool_metadata() {
-        let tool = ProcessTool::new();
+        let tool = ProcessTool::new(true);
         assert_eq!(tool.name(), "process");
         assert!(!tool.description().is_empty());
     }
@@ -0,0 +1,404 @@
+//! Reminders — one-shot and recurring notifications stored as cron jobs.
+//!
+//! Each reminder is a `cron_jobs` row whose tool call is this tool's
+//! `notify` action, so the cron runner delivers it when it comes due.
+//! One-shot reminders use a cron expression pinned to a single year; the
+//! runner disables them once they have no further occurrences.
+
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use super::{Tool, ToolContext, ToolOutput};
+use crate::error::Result;
+use crate::messaging::Urgency;
+
+/// Name of the tool, also used to recognise reminder rows in `cron_jobs`.
+pub const TOOL_NAME: &str = "reminder";
+
+pub struct ReminderTool;
+
+impl ReminderTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn add(&self, params: &serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let str_param = |name: &str| {
+            params
+                .get(name)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+        let Some(message) = str_param("message") else {
+            return Ok(ToolOutput::error("message is required for add"));
+        };
+
+        let now = Utc::now();
+        let schedule = match (str_param("at"), str_param("schedule")) {
+            (Some(at), None) => match DateTime::parse_from_rfc3339(at) {
+                Ok(at) if at.with_timezone(&Utc) > now => one_shot_schedule(at.with_timezone(&Utc)),
+                Ok(_) => return Ok(ToolOutput::error(format!("{at} is in the past"))),
+                Err(e) => return Ok(ToolOutput::error(format!("invalid 'at' time (expected ISO 8601): {e}"))),
+            },
+            (None, Some(expr)) => match cron::Schedule::from_str(expr) {
+                Ok(_) => expr.to_string(),
+                Err(e) => return Ok(ToolOutput::error(format!("invalid cron expression: {e}"))),
+            },
+            _ => return Ok(ToolOutput::error("exactly one of 'at' or 'schedule' is required")),
+        };
+        let Some(next) = next_run(&schedule, now) else {
+            return Ok(ToolOutput::error("schedule has no upcoming occurrences"));
+        };
+
+        let mut notify = serde_json::json!({ "action": "notify", "message": message });
+        for key in ["platform", "channel"] {
+            if let Some(value) = str_param(key) {
+                notify[key] = value.into();
+            }
+        }
+        let tool_call = serde_json::json!({ "tool": TOOL_NAME, "params": notify });
+
+        let id = Uuid::new_v4().to_string();
+        debug!(id, schedule, "adding reminder");
+
+        // Seed last_run_at with the creation time; the cron runner treats
+        // jobs that have never run as immediately due.
+        let db = ctx.db.lock().await;
+        db.execute(
+            "INSERT INTO cron_jobs (id, name, schedule, tool_call, last_run_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                id,
+                format!("Reminder: {message}"),
+                schedule,
+                serde_json::to_string(&tool_call)?,
+                now.to_rfc3339(),
+            ],
+        )?;
+
+        Ok(ToolOutput::ok_with_meta(
+            format!("Reminder set for {}", next.to_rfc3339()),
+            serde_json::json!({ "reminder_id": id, "next_run": next.to_rfc3339() }),
+        ))
+    }
+
+    async fn list(&self, ctx: &ToolContext) -> Result<ToolOutput> {
+        let db = ctx.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT id, schedule, tool_call FROM cron_jobs
+             WHERE enabled = 1 AND json_extract(tool_call, '$.tool') = ?1
+             ORDER BY created_at",
+        )?;
+        let now = Utc::now();
+        let reminders: Vec<String> = stmt
+            .query_map([TOOL_NAME], |row| {
+                let id: String = row.get(0)?;
+                let schedule: String = row.get(1)?;
+                let call: String = row.get(2)?;
+                Ok((id, schedule, call))
+            })?
+            .filter_map(|r| r.ok())
+            .map(|(id, schedule, call)| {
+                let message = serde_json::from_str::<serde_json::Value>(&call)
+                    .ok()
+                    .and_then(|v| v["params"]["message"].as_str().map(String::from))
+                    .unwrap_or_default();
+                let next = next_run(&schedule, now)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_else(|| "none".into());
+                format!("[{id}] {message} — schedule={schedule} next={next}")
+            })
+            .collect();
+
+        if reminders.is_empty() {
+            Ok(ToolOutput::ok("No reminders scheduled."))
+        } else {
+            Ok(ToolOutput::ok(reminders.join("\n")))
+        }
+    }
+
+    async fn cancel(&self, params: &serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let id = params.get("reminder_id").and_then(|v| v.as_str()).unwrap_or_default();
+        if id.is_empty() {
+            return Ok(ToolOutput::error("reminder_id is required for cancel"));
+        }
+        let db = ctx.db.lock().await;
+        let rows = db.execute(
+            "DELETE FROM cron_jobs WHERE id = ?1 AND json_extract(tool_call, '$.tool') = ?2",
+            rusqlite::params![id, TOOL_NAME],
+        )?;
+        if rows > 0 {
+            Ok(ToolOutput::ok(format!("Cancelled reminder {id}")))
+        } else {
+            Ok(ToolOutput::error(format!("Reminder {id} not found")))
+        }
+    }
+
+    /// Deliver a due reminder.  Called by the cron runner.  Reminders fire
+    /// at the time the user asked for, so they go out during quiet hours.
+    async fn notify(&self, params: &serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let message = params.get("message").and_then(|v| v.as_str()).unwrap_or_default();
+        let text = format!("Reminder: {message}");
+        let Some(platform) = params.get("platform").and_then(|v| v.as_str()) else {
+            if ctx.messaging.is_empty() {
+                return Ok(ToolOutput::error("No messaging backends configured"));
+            }
+            ctx.messaging.send_all(&text, Urgency::Urgent).await;
+            return Ok(ToolOutput::ok("Reminder sent"));
+        };
+
+        let channel = match params.get("channel").and_then(|v| v.as_str()) {
+            Some(c) => c.to_string(),
+            None => match ctx.messaging.primary_channel(platform) {
+                Some(c) => c.to_string(),
+                None => {
+                    return Ok(ToolOutput::error(format!(
+                        "No primary channel for platform '{platform}'"
+                    )));
+                }
+            },
+        };
+        if ctx.messaging.get(platform).is_none() {
+            return Ok(ToolOutput::error(format!("Unknown platform '{platform}'")));
+        }
+        ctx.messaging.enqueue(platform, &channel, &text).await?;
+        info!(platform, channel = %channel, "reminder sent");
+        Ok(ToolOutput::ok(format!("Reminder sent via {platform}")))
+    }
+}
+
+/// A cron expression that fires once, at `at` (to the second).
+fn one_shot_schedule(at: DateTime<Utc>) -> String {
+    format!(
+        "{} {} {} {} {} * {}",
+        at.second(),
+        at.minute(),
+        at.hour(),
+        at.day(),
+        at.month(),
+        at.year()
+    )
+}
+
+/// The next time `schedule` fires after `after`.
+fn next_run(schedule: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    cron::Schedule::from_str(schedule).ok()?.after(&after).next()
+}
+
+#[async_trait]
+impl Tool for ReminderTool {
+    fn name(&self) -> &str {
+        TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Schedule reminders that are sent to the user as messages. Actions: add (one-shot with 'at' as an ISO 8601 time, or recurring with a cron 'schedule' in UTC), list, cancel."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["action"],
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["add", "list", "cancel"],
+                    "description": "Reminder action to perform"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "What to remind the user about (for add)"
+                },
+                "at": {
+                    "type": "string",
+                    "description": "When to send a one-shot reminder, ISO 8601 with offset, e.g. '2025-06-01T09:00:00+02:00' (for add)"
+                },
+                "schedule": {
+                    "type": "string",
+                    "description": "Cron expression in UTC for a recurring reminder, e.g. '0 0 9 * * Mon-Fri' (for add)"
+                },
+                "platform": {
+                    "type": "string",
+                    "description": "Platform to send on (optional; defaults to every configured platform)"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Channel on that platform (optional; defaults to its primary channel)"
+                },
+                "reminder_id": {
+                    "type": "string",
+                    "description": "Reminder ID (for cancel)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        match params.get("action").and_then(|v| v.as_str()).unwrap_or_default() {
+            "add" => self.add(&params, ctx).await,
+            "list" => self.list(ctx).await,
+            "cancel" => self.cancel(&params, ctx).await,
+            "notify" => self.notify(&params, ctx).await,
+            other => Ok(ToolOutput::error(format!("unknown action: {other}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::MessagingManager;
+    use crate::security::SandboxedFs;
+    use crate::trash::TrashManager;
+    use std::sync::Arc;
+
+    fn test_ctx(base: &std::path::Path) -> ToolContext {
+        ToolContext {
+            sandbox: SandboxedFs::new(base.join("sandbox")).unwrap(),
+            db: crate::db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(&base.join("trash")).unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn schedules_one_shot_reminder() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_ctx(dir.path());
+        let tool = ReminderTool::new();
+        let at = (Utc::now() + chrono::Duration::days(1)).with_nanosecond(0).unwrap();
+
+        let r = tool
+            .execute(
+                serde_json::json!({
+                    "action": "add",
+                    "message": "stand-up",
+                    "at": at.to_rfc3339(),
+                    "platform": "telegram",
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(r.success, "{}", r.output);
+        let meta = r.metadata.unwrap();
+        assert_eq!(meta["next_run"], at.to_rfc3339());
+
+        // Stored as a cron job that fires exactly once, and not before `at`.
+        let (schedule, call, last_run): (String, String, String) = {
+            let db = ctx.db.lock().await;
+            db.query_row(
+                "SELECT schedule, tool_call, last_run_at FROM cron_jobs WHERE id = ?1",
+                [meta["reminder_id"].as_str().unwrap()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap()
+        };
+        assert_eq!(next_run(&schedule, Utc::now()), Some(at));
+        assert_eq!(next_run(&schedule, at), None);
+        assert!(DateTime::parse_from_rfc3339(&last_run).unwrap() < at);
+        let call: serde_json::Value = serde_json::from_str(&call).unwrap();
+        assert_eq!(call["tool"], "reminder");
+        assert_eq!(call["params"]["action"], "notify");
+        assert_eq!(call["params"]["platform"], "telegram");
+
+        let list = tool.execute(serde_json::json!({"action": "list"}), &ctx).await.unwrap();
+        assert!(list.output.contains("stand-up"));
+
+        let past = tool
+            .execute(
+                serde_json::json!({"action": "add", "message": "x", "at": "2001-01-01T00:00:00Z"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!past.success);
+    }
+
+    #[tokio::test]
+    async fn cancels_reminders_but_not_other_cron_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_ctx(dir.path());
+        let tool = ReminderTool::new();
+
+        let r = tool
+            .execute(
+                serde_json::json!({"action": "add", "message": "water plants", "schedule": "0 0 9 * * *"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(r.success, "{}", r.output);
+        let id = r.metadata.unwrap()["reminder_id"].as_str().unwrap().to_string();
+
+        {
+            let db = ctx.db.lock().await;
+            db.execute(
+                "INSERT INTO cron_jobs (id, name, schedule, tool_call) VALUES ('job', 'backup', '0 0 * * * *', '{\"tool\":\"exec\"}')",
+                [],
+            )
+            .unwrap();
+        }
+        let other = tool
+            .execute(serde_json::json!({"action": "cancel", "reminder_id": "job"}), &ctx)
+            .await
+            .unwrap();
+        assert!(!other.success);
+
+        let cancel = tool
+            .execute(serde_json::json!({"action": "cancel", "reminder_id": id}), &ctx)
+            .await
+            .unwrap();
+        assert!(cancel.success);
+        let list = tool.execute(serde_json::json!({"action": "list"}), &ctx).await.unwrap();
+        assert!(list.output.contains("No reminders"));
+    }
+
+    struct RecordingBackend {
+        sent: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl crate::messaging::MessagingBackend for RecordingBackend {
+        fn platform_name(&self) -> &str { "tg" }
+        fn max_message_length(&self) -> usize { 4096 }
+        async fn send_message(&self, _channel: &str, text: &str) -> Result<()> {
+            self.sent.lock().unwrap().push(text.to_string());
+            Ok(())
+        }
+        async fn send_typing(&self, _channel: &str) -> Result<()> { Ok(()) }
+    }
+
+    #[tokio::test]
+    async fn notify_is_not_held_by_quiet_hours() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = crate::db::test_db();
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut messaging = MessagingManager::new();
+        messaging.register(Arc::new(RecordingBackend { sent: sent.clone() }), "42".into());
+        messaging.set_outbox(crate::messaging::outbox::Outbox::new(db.clone()));
+        // A window around the current time, so any Normal send is deferred.
+        let now = Utc::now();
+        let hhmm = |t: DateTime<Utc>| t.format("%H:%M").to_string();
+        let quiet = crate::config::QuietHours::parse(
+            &hhmm(now - chrono::Duration::hours(1)),
+            &hhmm(now + chrono::Duration::hours(1)),
+        )
+        .unwrap();
+        messaging.set_quiet_hours(quiet, chrono_tz::UTC);
+        let ctx = ToolContext { messaging: Arc::new(messaging), db, ..test_ctx(dir.path()) };
+
+        let r = ReminderTool::new()
+            .execute(serde_json::json!({"action": "notify", "message": "take meds"}), &ctx)
+            .await
+            .unwrap();
+        assert!(r.success, "{}", r.output);
+        assert_eq!(*sent.lock().unwrap(), vec!["Reminder: take meds".to_string()]);
+    }
+}
@@ -0,0 +1,256 @@
+//! robots.txt support for `web_fetch`.
+//!
+//! Rules are fetched once per origin and cached for [`CACHE_TTL`].  Matching
+//! follows RFC 9309: the group naming our product token wins over `*`, the
+//! longest matching rule decides, and `Allow` wins ties.  A missing or
+//! unreachable robots.txt allows everything.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::Url;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Product token matched against `User-agent` lines.
+pub const ROBOTS_AGENT: &str = "safeclaw";
+
+/// How long fetched rules are reused.
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// robots.txt bodies beyond this size are truncated (RFC 9309 minimum).
+const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+
+/// Crawl delays above this are capped.
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
+/// Parsed rules from the group that applies to us.
+#[derive(Debug, Default, Clone)]
+pub struct RobotsRules {
+    /// `(allow, pattern)` pairs.
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    /// Parse a robots.txt body, keeping the group for `agent` (or `*`).
+    pub fn parse(body: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut specific: Option<RobotsRules> = None;
+        let mut wildcard: Option<RobotsRules> = None;
+
+        // Current group: which agents it names, and its rules.
+        let mut agents: Vec<String> = Vec::new();
+        let mut group = RobotsRules::default();
+        let mut in_rules = false;
+
+        let mut finish = |agents: &[String], group: &RobotsRules| {
+            if agents.iter().any(|a| agent.contains(a.as_str()) && a != "*") {
+                specific.get_or_insert_with(Default::default).merge(group);
+            } else if agents.iter().any(|a| a == "*") {
+                wildcard.get_or_insert_with(Default::default).merge(group);
+            }
+        };
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        finish(&agents, &group);
+                        agents.clear();
+                        group = RobotsRules::default();
+                        in_rules = false;
+                    }
+                    agents.push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow means "allow everything".
+                    if !value.is_empty() {
+                        group.rules.push((key == "allow", value.to_string()));
+                    }
+                }
+                "crawl-delay" => {
+                    in_rules = true;
+                    group.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|d| d.is_finite() && *d >= 0.0)
+                        .map(|d| Duration::from_secs_f64(d).min(MAX_CRAWL_DELAY));
+                }
+                _ => {}
+            }
+        }
+        finish(&agents, &group);
+
+        specific.or(wildcard).unwrap_or_default()
+    }
+
+    fn merge(&mut self, other: &RobotsRules) {
+        self.rules.extend(other.rules.iter().cloned());
+        self.crawl_delay = self.crawl_delay.max(other.crawl_delay);
+    }
+
+    /// Whether `path` (including any query string) may be fetched.
+    pub fn allows(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if !pattern_matches(pattern, path) {
+                continue;
+            }
+            let len = pattern.len();
+            let better = match best {
+                None => true,
+                Some((best_len, best_allow)) => len > best_len || (len == best_len && *allow && !best_allow),
+            };
+            if better {
+                best = Some((len, *allow));
+            }
+        }
+        best.is_none_or(|(_, allow)| allow)
+    }
+
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+/// Match a robots.txt path pattern: `*` matches any run of characters and
+/// a trailing `$` anchors the end; otherwise it is a prefix match.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// Per-origin cache of robots.txt rules.
+#[derive(Default)]
+pub struct RobotsCache {
+    entries: Mutex<HashMap<String, (Instant, Arc<RobotsRules>)>>,
+}
+
+impl RobotsCache {
+    /// Rules for the origin of `url`, fetching robots.txt when not cached.
+    pub async fn rules_for(&self, client: &reqwest::Client, url: &Url) -> Arc<RobotsRules> {
+        let origin = url.origin().ascii_serialization();
+        let cached = self
+            .entries
+            .lock()
+            .await
+            .get(&origin)
+            .filter(|(fetched, _)| fetched.elapsed() < CACHE_TTL)
+            .map(|(_, rules)| rules.clone());
+        if let Some(rules) = cached {
+            return rules;
+        }
+
+        let rules = Arc::new(fetch_rules(client, &origin).await);
+        self.entries
+            .lock()
+            .await
+            .insert(origin, (Instant::now(), rules.clone()));
+        rules
+    }
+}
+
+async fn fetch_rules(client: &reqwest::Client, origin: &str) -> RobotsRules {
+    let robots_url = format!("{origin}/robots.txt");
+    let mut resp = match client
+        .get(&robots_url)
+        .header("User-Agent", "Mozilla/5.0 (compatible; SafeClaw/0.1)")
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            warn!(url = %robots_url, err = %e, "robots.txt unreachable, allowing all");
+            return RobotsRules::default();
+        }
+    };
+    if !resp.status().is_success() {
+        debug!(url = %robots_url, status = %resp.status(), "no robots.txt, allowing all");
+        return RobotsRules::default();
+    }
+    let mut body = Vec::new();
+    while let Ok(Some(chunk)) = resp.chunk().await {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_ROBOTS_BYTES {
+            body.truncate(MAX_ROBOTS_BYTES);
+            break;
+        }
+    }
+    RobotsRules::parse(&String::from_utf8_lossy(&body), ROBOTS_AGENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# example
+User-agent: *
+Disallow: /private
+Allow: /private/public
+Disallow: /*.pdf$
+
+User-agent: OtherBot
+Disallow: /
+
+User-agent: SafeClaw
+User-agent: Friendly
+Disallow: /admin
+Crawl-delay: 2
+";
+
+    #[test]
+    fn specific_group_wins_over_wildcard() {
+        let rules = RobotsRules::parse(ROBOTS, ROBOTS_AGENT);
+        assert!(!rules.allows("/admin/users"));
+        assert!(rules.allows("/private"));
+        assert_eq!(rules.crawl_delay(), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn wildcard_group_longest_match() {
+        let rules = RobotsRules::parse(ROBOTS, "somebot");
+        assert!(rules.allows("/"));
+        assert!(!rules.allows("/private/data"));
+        assert!(rules.allows("/private/public/page"));
+        assert!(!rules.allows("/docs/report.pdf"));
+        assert!(rules.allows("/docs/report.pdf?download=1"));
+        assert_eq!(rules.crawl_delay(), None);
+    }
+
+    #[test]
+    fn empty_or_missing_rules_allow_everything() {
+        assert!(RobotsRules::default().allows("/anything"));
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n", ROBOTS_AGENT);
+        assert!(rules.allows("/anything"));
+    }
+}
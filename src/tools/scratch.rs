@@ -0,0 +1,488 @@
+//! Scratchpad — a small key-value store shared by all tools.
+//!
+//! Lets the agent stash an intermediate result under a key and read it
+//! back later without pushing it through the LLM context again.  Entries
+//! live in SQLite, are grouped by scope (`"global"` unless the caller
+//! names one, e.g. a conversation id) and may expire after a TTL.
+//!
+//! Besides the `scratch_*` tools, any tool call can use the store through
+//! two reserved parameters handled by the registry: `save_to` stores the
+//! call's output under a key instead of returning it, and `from_scratch`
+//! fills parameters from stored values (see [`take_hooks`]).
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rusqlite::{Connection, OptionalExtension};
+use tokio::sync::Mutex;
+
+use super::{Tool, ToolContext, ToolOutput};
+use crate::error::{Result, SafeAgentError};
+
+/// Largest value the scratchpad accepts.
+pub const MAX_VALUE_BYTES: usize = 256 * 1024;
+
+/// Most live entries one scope may hold.
+pub const MAX_ENTRIES_PER_SCOPE: i64 = 1_000;
+
+/// Reserved parameter naming the key a call's output is stored under.
+pub const SAVE_TO_PARAM: &str = "save_to";
+
+/// Reserved parameter mapping parameter names to the keys they are read
+/// from, e.g. `{"content": "report"}`.
+pub const FROM_SCRATCH_PARAM: &str = "from_scratch";
+
+/// Parameters the security gates inspect before a call runs.  Filling
+/// them from the scratchpad would slip values past those checks.
+const GATED_PARAMS: &[&str] = &["action", "command", "patch", "path", "paths"];
+
+/// Scope used when the caller doesn't name one.
+const GLOBAL_SCOPE: &str = "global";
+
+/// A stored scratchpad entry, without its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScratchEntry {
+    pub key: String,
+    pub size: usize,
+    pub expires_at: Option<i64>,
+}
+
+/// Handle onto the `scratchpad` table.  All timestamps are unix seconds.
+pub struct Scratchpad<'a> {
+    db: &'a Arc<Mutex<Connection>>,
+}
+
+impl<'a> Scratchpad<'a> {
+    pub fn new(db: &'a Arc<Mutex<Connection>>) -> Self {
+        Self { db }
+    }
+
+    /// Store `value` under `key`, replacing any previous value.  Expired
+    /// entries are purged on every write.  A new key in a scope that
+    /// already holds [`MAX_ENTRIES_PER_SCOPE`] entries is refused.
+    pub async fn set(&self, scope: &str, key: &str, value: &str, ttl_secs: Option<u64>, now: i64) -> Result<()> {
+        let expires_at = ttl_secs.map(|ttl| now.saturating_add(ttl as i64));
+        let db = self.db.lock().await;
+        db.execute(
+            "DELETE FROM scratchpad WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+            [now],
+        )?;
+        let others: i64 = db.query_row(
+            "SELECT COUNT(*) FROM scratchpad WHERE scope = ?1 AND key != ?2",
+            rusqlite::params![scope, key],
+            |row| row.get(0),
+        )?;
+        if others >= MAX_ENTRIES_PER_SCOPE {
+            return Err(SafeAgentError::QuotaExceeded(format!(
+                "scratchpad scope '{scope}' already holds {MAX_ENTRIES_PER_SCOPE} entries"
+            )));
+        }
+        db.execute(
+            "INSERT INTO scratchpad (scope, key, value, expires_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(scope, key) DO UPDATE SET
+                value = excluded.value,
+                expires_at = excluded.expires_at,
+                updated_at = excluded.updated_at",
+            rusqlite::params![scope, key, value, expires_at, now],
+        )?;
+        Ok(())
+    }
+
+    /// The live value stored under `key`, if any.
+    pub async fn get(&self, scope: &str, key: &str, now: i64) -> Result<Option<String>> {
+        let db = self.db.lock().await;
+        let value = db
+            .query_row(
+                "SELECT value FROM scratchpad
+                 WHERE scope = ?1 AND key = ?2 AND (expires_at IS NULL OR expires_at > ?3)",
+                rusqlite::params![scope, key, now],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    /// Live entries in `scope`, ordered by key.
+    pub async fn list(&self, scope: &str, now: i64) -> Result<Vec<ScratchEntry>> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT key, length(CAST(value AS BLOB)), expires_at FROM scratchpad
+             WHERE scope = ?1 AND (expires_at IS NULL OR expires_at > ?2)
+             ORDER BY key",
+        )?;
+        let entries = stmt
+            .query_map(rusqlite::params![scope, now], |row| {
+                Ok(ScratchEntry {
+                    key: row.get(0)?,
+                    size: row.get::<_, i64>(1)? as usize,
+                    expires_at: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+}
+
+/// Remove the `save_to` and `from_scratch` hooks from a call's parameters,
+/// filling in the parameters `from_scratch` names from the global scope.
+/// Returns the `save_to` key, if any.  A parameter the call already sets,
+/// or one the security gates inspect, can't be filled this way.
+pub async fn take_hooks(params: &mut serde_json::Value, ctx: &ToolContext) -> Result<Option<String>> {
+    let Some(obj) = params.as_object_mut() else {
+        return Ok(None);
+    };
+    let save_to = match obj.remove(SAVE_TO_PARAM) {
+        None => None,
+        Some(serde_json::Value::String(key)) if !key.is_empty() => Some(key),
+        Some(_) => return Err(SafeAgentError::Config(format!("{SAVE_TO_PARAM} must be a non-empty key"))),
+    };
+    let Some(sources) = obj.remove(FROM_SCRATCH_PARAM) else {
+        return Ok(save_to);
+    };
+    let Some(sources) = sources.as_object() else {
+        return Err(SafeAgentError::Config(format!(
+            "{FROM_SCRATCH_PARAM} must map parameter names to scratchpad keys"
+        )));
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let pad = ctx.scratchpad();
+    for (param, key) in sources {
+        if GATED_PARAMS.contains(&param.as_str()) || obj.contains_key(param) {
+            return Err(SafeAgentError::PermissionDenied(format!(
+                "parameter '{param}' can't be filled from the scratchpad"
+            )));
+        }
+        let key = key.as_str().unwrap_or_default();
+        let value = pad
+            .get(GLOBAL_SCOPE, key, now)
+            .await?
+            .ok_or_else(|| SafeAgentError::NotFound(format!("scratchpad key '{key}'")))?;
+        obj.insert(param.clone(), serde_json::Value::String(value));
+    }
+    Ok(save_to)
+}
+
+/// Store a successful call's output under `key` in the global scope and
+/// tell the model where it went instead of returning it.  Failed output,
+/// and output too large to store, is passed through with a note.
+pub async fn save_output(ctx: &ToolContext, key: &str, mut output: ToolOutput) -> Result<ToolOutput> {
+    if !output.success {
+        return Ok(output);
+    }
+    if output.output.len() > MAX_VALUE_BYTES {
+        output.output.push_str(&format!(
+            "\n(not saved to scratchpad key '{key}': {} bytes exceeds the {MAX_VALUE_BYTES}-byte limit)",
+            output.output.len()
+        ));
+        return Ok(output);
+    }
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = ctx.scratchpad().set(GLOBAL_SCOPE, key, &output.output, None, now).await {
+        output.output.push_str(&format!("\n(not saved to scratchpad key '{key}': {e})"));
+        return Ok(output);
+    }
+    output.output = format!("Saved {} bytes of output to scratchpad key '{key}'", output.output.len());
+    Ok(output)
+}
+
+fn str_param<'p>(params: &'p serde_json::Value, name: &str) -> &'p str {
+    params.get(name).and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+fn scope_param(params: &serde_json::Value) -> &str {
+    match str_param(params, "scope") {
+        "" => GLOBAL_SCOPE,
+        scope => scope,
+    }
+}
+
+fn scope_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "string",
+        "description": "Namespace for the key, e.g. a conversation id (default 'global')"
+    })
+}
+
+/// `scratch_set` — store a value.
+pub struct ScratchSetTool;
+
+#[async_trait]
+impl Tool for ScratchSetTool {
+    fn name(&self) -> &str {
+        "scratch_set"
+    }
+
+    fn description(&self) -> &str {
+        "Store a value in the shared scratchpad under a key, so later tool calls can read it with scratch_get. Optional ttl_secs expires it."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["key", "value"],
+            "properties": {
+                "key": { "type": "string", "description": "Key to store under" },
+                "value": { "type": "string", "description": "Value to store" },
+                "ttl_secs": { "type": "integer", "description": "Seconds until the value expires (optional)" },
+                "scope": scope_schema()
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let key = str_param(&params, "key");
+        if key.is_empty() {
+            return Ok(ToolOutput::error("key is required"));
+        }
+        let Some(value) = params.get("value").and_then(|v| v.as_str()) else {
+            return Ok(ToolOutput::error("value is required"));
+        };
+        if value.len() > MAX_VALUE_BYTES {
+            return Ok(ToolOutput::error(format!(
+                "value is {} bytes; the limit is {MAX_VALUE_BYTES}",
+                value.len()
+            )));
+        }
+        let ttl = params.get("ttl_secs").and_then(|v| v.as_u64());
+        let scope = scope_param(&params);
+
+        let now = chrono::Utc::now().timestamp();
+        match ctx.scratchpad().set(scope, key, value, ttl, now).await {
+            Ok(()) => Ok(ToolOutput::ok(format!("Stored {} bytes under '{key}'", value.len()))),
+            Err(SafeAgentError::QuotaExceeded(msg)) => Ok(ToolOutput::error(msg)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// `scratch_get` — read a value back.
+pub struct ScratchGetTool;
+
+#[async_trait]
+impl Tool for ScratchGetTool {
+    fn name(&self) -> &str {
+        "scratch_get"
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Read a value previously stored in the shared scratchpad with scratch_set."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["key"],
+            "properties": {
+                "key": { "type": "string", "description": "Key to read" },
+                "scope": scope_schema()
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let key = str_param(&params, "key");
+        if key.is_empty() {
+            return Ok(ToolOutput::error("key is required"));
+        }
+        let now = chrono::Utc::now().timestamp();
+        match ctx.scratchpad().get(scope_param(&params), key, now).await? {
+            Some(value) => Ok(ToolOutput::ok(value)),
+            None => Ok(ToolOutput::error(format!("No scratchpad value for '{key}'"))),
+        }
+    }
+}
+
+/// `scratch_list` — list stored keys.
+pub struct ScratchListTool;
+
+#[async_trait]
+impl Tool for ScratchListTool {
+    fn name(&self) -> &str {
+        "scratch_list"
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "List the keys in the shared scratchpad with their sizes and expiry times."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "scope": scope_schema()
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let now = chrono::Utc::now().timestamp();
+        let entries = ctx.scratchpad().list(scope_param(&params), now).await?;
+        if entries.is_empty() {
+            return Ok(ToolOutput::ok("Scratchpad is empty."));
+        }
+        let lines: Vec<String> = entries
+            .iter()
+            .map(|e| match e.expires_at {
+                Some(at) => format!("{} ({} bytes, expires in {}s)", e.key, e.size, at - now),
+                None => format!("{} ({} bytes)", e.key, e.size),
+            })
+            .collect();
+        Ok(ToolOutput::ok(lines.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::MessagingManager;
+    use crate::security::SandboxedFs;
+    use crate::trash::TrashManager;
+
+    fn test_ctx(base: &std::path::Path) -> ToolContext {
+        ToolContext {
+            sandbox: SandboxedFs::new(base.join("sandbox")).unwrap(),
+            db: crate::db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(&base.join("trash")).unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn set_get_round_trip_through_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_ctx(dir.path());
+
+        let set = ScratchSetTool
+            .execute(serde_json::json!({"key": "report", "value": "line 1\nline 2"}), &ctx)
+            .await
+            .unwrap();
+        assert!(set.success, "{}", set.output);
+
+        let get = ScratchGetTool.execute(serde_json::json!({"key": "report"}), &ctx).await.unwrap();
+        assert!(get.success);
+        assert_eq!(get.output, "line 1\nline 2");
+
+        // Scopes are separate namespaces.
+        let other = ScratchGetTool
+            .execute(serde_json::json!({"key": "report", "scope": "conv-1"}), &ctx)
+            .await
+            .unwrap();
+        assert!(!other.success);
+
+        let list = ScratchListTool.execute(serde_json::json!({}), &ctx).await.unwrap();
+        assert_eq!(list.output, "report (13 bytes)");
+
+        let big = "x".repeat(MAX_VALUE_BYTES + 1);
+        let r = ScratchSetTool.execute(serde_json::json!({"key": "big", "value": big}), &ctx).await.unwrap();
+        assert!(!r.success);
+    }
+
+    #[tokio::test]
+    async fn set_overwrites_value_and_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_ctx(dir.path());
+        let pad = ctx.scratchpad();
+
+        pad.set("global", "k", "first", Some(10), 1_000).await.unwrap();
+        pad.set("global", "k", "second", None, 1_001).await.unwrap();
+
+        assert_eq!(pad.get("global", "k", 5_000).await.unwrap().as_deref(), Some("second"));
+        let entries = pad.list("global", 5_000).await.unwrap();
+        assert_eq!(
+            entries,
+            vec![ScratchEntry { key: "k".into(), size: 6, expires_at: None }]
+        );
+    }
+
+    #[tokio::test]
+    async fn entries_expire_after_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_ctx(dir.path());
+        let pad = ctx.scratchpad();
+
+        pad.set("global", "tmp", "v", Some(60), 1_000).await.unwrap();
+        assert_eq!(pad.get("global", "tmp", 1_059).await.unwrap().as_deref(), Some("v"));
+        assert_eq!(pad.get("global", "tmp", 1_060).await.unwrap(), None);
+        assert!(pad.list("global", 1_060).await.unwrap().is_empty());
+
+        // The next write purges the expired row.
+        pad.set("global", "other", "v", None, 1_061).await.unwrap();
+        let rows: i64 = ctx
+            .db
+            .lock()
+            .await
+            .query_row("SELECT COUNT(*) FROM scratchpad WHERE key = 'tmp'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+
+    #[tokio::test]
+    async fn registry_hooks_save_output_and_fill_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_ctx(dir.path());
+        let mut reg = super::super::ToolRegistry::new();
+        reg.register(Box::new(ScratchSetTool));
+        reg.register(Box::new(ScratchGetTool));
+        ctx.scratchpad().set(GLOBAL_SCOPE, "src", "payload", None, 1_000).await.unwrap();
+
+        // save_to: the output lands in the scratchpad, not the reply.
+        let out = reg
+            .execute("scratch_get", serde_json::json!({"key": "src", "save_to": "copy"}), &ctx)
+            .await
+            .unwrap();
+        assert!(out.success, "{}", out.output);
+        assert!(!out.output.contains("payload"), "{}", out.output);
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(ctx.scratchpad().get(GLOBAL_SCOPE, "copy", now).await.unwrap().as_deref(), Some("payload"));
+
+        // from_scratch: a later call reads its parameter from the store.
+        let out = reg
+            .execute(
+                "scratch_set",
+                serde_json::json!({"key": "filled", "from_scratch": {"value": "copy"}}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(out.success, "{}", out.output);
+        assert_eq!(ctx.scratchpad().get(GLOBAL_SCOPE, "filled", now).await.unwrap().as_deref(), Some("payload"));
+
+        // Gated parameters and ones the call already sets are refused.
+        for params in [
+            serde_json::json!({"key": "x", "from_scratch": {"command": "copy", "value": "copy"}}),
+            serde_json::json!({"key": "x", "value": "v", "from_scratch": {"value": "copy"}}),
+        ] {
+            let out = reg.execute("scratch_set", params, &ctx).await.unwrap();
+            assert!(!out.success);
+        }
+        assert_eq!(ctx.scratchpad().get(GLOBAL_SCOPE, "x", now).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn set_refuses_new_keys_past_the_scope_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = test_ctx(dir.path());
+        let pad = ctx.scratchpad();
+
+        for i in 0..MAX_ENTRIES_PER_SCOPE {
+            pad.set("conv-1", &format!("k{i}"), "v", None, 1_000).await.unwrap();
+        }
+        let err = pad.set("conv-1", "one-more", "v", None, 1_000).await.unwrap_err();
+        assert!(matches!(err, SafeAgentError::QuotaExceeded(_)), "{err}");
+
+        // Overwriting an existing key and other scopes still work.
+        pad.set("conv-1", "k0", "new", None, 1_000).await.unwrap();
+        pad.set("conv-2", "one-more", "v", None, 1_000).await.unwrap();
+    }
+}
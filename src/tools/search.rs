@@ -0,0 +1,398 @@
+//! Search backends for `web_search`.
+//!
+//! The provider is chosen by `tools.web.search_provider`: `duckduckgo`
+//! scrapes DuckDuckGo's HTML endpoint (no API key needed) and `searxng`
+//! queries a self-hosted SearxNG instance through its JSON API.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::config::WebToolConfig;
+use crate::error::{Result, SafeAgentError};
+
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; SafeClaw/0.1)";
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A single search result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+#[async_trait]
+pub trait SearchProvider: Send + Sync {
+    /// Short name shown in logs and the tool description.
+    fn name(&self) -> &str;
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchHit>>;
+}
+
+/// Build the provider selected in config.  The SearxNG base URL goes
+/// through the same SSRF checks as extension HTTP requests.
+pub fn provider_from_config(config: &WebToolConfig) -> Result<Box<dyn SearchProvider>> {
+    match config.search_provider.as_str() {
+        "duckduckgo" => Ok(Box::new(DuckDuckGoProvider::default())),
+        "searxng" => {
+            if config.searxng_url.is_empty() {
+                return Err(SafeAgentError::Config(
+                    "search_provider = \"searxng\" requires searxng_url".into(),
+                ));
+            }
+            let url = crate::security::validate_url(&config.searxng_url)
+                .map_err(|e| SafeAgentError::Config(format!("searxng_url: {e}")))?;
+            Ok(Box::new(SearxngProvider::new(url.as_str())))
+        }
+        other => Err(SafeAgentError::Config(format!(
+            "unknown search_provider: {other} (expected \"duckduckgo\" or \"searxng\")"
+        ))),
+    }
+}
+
+// -- DuckDuckGo ----------------------------------------------------------
+
+#[derive(Default)]
+pub struct DuckDuckGoProvider {
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl SearchProvider for DuckDuckGoProvider {
+    fn name(&self) -> &str {
+        "DuckDuckGo"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchHit>> {
+        debug!(query, max_results, "searching DuckDuckGo");
+        let url = format!("https://html.duckduckgo.com/html/?q={}", urlencoding(query));
+        let body = self
+            .client
+            .get(&url)
+            .header("User-Agent", USER_AGENT)
+            .timeout(SEARCH_TIMEOUT)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(parse_ddg_html(&body, max_results))
+    }
+}
+
+/// Parse DuckDuckGo HTML search results page.
+fn parse_ddg_html(html: &str, limit: usize) -> Vec<SearchHit> {
+    let mut results = Vec::new();
+
+    // Simple extraction of result blocks from DDG HTML
+    for chunk in html.split("class=\"result__a\"").skip(1).take(limit) {
+        let title = extract_between(chunk, ">", "</a>")
+            .map(|s| strip_tags(&s))
+            .unwrap_or_default();
+        let url = extract_between(chunk, "href=\"", "\"").unwrap_or_default();
+        let snippet = if let Some(s_start) = chunk.find("class=\"result__snippet\"") {
+            let after = &chunk[s_start..];
+            extract_between(after, ">", "</")
+                .map(|s| strip_tags(&s))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+
+        if !title.is_empty() {
+            // DDG redirects through their URL; extract the actual URL
+            let actual_url = if url.contains("uddg=") {
+                url.split("uddg=")
+                    .nth(1)
+                    .and_then(|s| s.split('&').next())
+                    .map(urldecoding)
+                    .unwrap_or(url)
+            } else {
+                url
+            };
+            results.push(SearchHit {
+                title,
+                url: actual_url,
+                snippet,
+            });
+        }
+    }
+
+    results
+}
+
+fn extract_between(text: &str, start: &str, end: &str) -> Option<String> {
+    let s = text.find(start)?;
+    let after = &text[s + start.len()..];
+    let e = after.find(end)?;
+    Some(after[..e].to_string())
+}
+
+fn strip_tags(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+fn urlencoding(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
+            ' ' => "+".to_string(),
+            _ => format!("%{:02X}", c as u32),
+        })
+        .collect()
+}
+
+fn urldecoding(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let hex: String = chars.by_ref().take(2).collect();
+            if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                out.push(byte as char);
+            }
+        } else if c == '+' {
+            out.push(' ');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// -- SearxNG -------------------------------------------------------------
+
+/// A self-hosted SearxNG instance.  The instance must have the `json`
+/// format enabled under `search.formats` in its settings.
+pub struct SearxngProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl SearxngProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SearxngResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+}
+
+#[derive(Deserialize)]
+struct SearxngResult {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    content: String,
+}
+
+#[async_trait]
+impl SearchProvider for SearxngProvider {
+    fn name(&self) -> &str {
+        "SearxNG"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchHit>> {
+        debug!(query, max_results, base = %self.base_url, "searching SearxNG");
+        let url = format!(
+            "{}/search?q={}&format=json",
+            self.base_url,
+            urlencoding(query)
+        );
+        let resp = self
+            .client
+            .get(&url)
+            .header("User-Agent", USER_AGENT)
+            .timeout(SEARCH_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        let body = resp.text().await?;
+        parse_searxng_json(&body, max_results)
+    }
+}
+
+fn parse_searxng_json(body: &str, limit: usize) -> Result<Vec<SearchHit>> {
+    let parsed: SearxngResponse = serde_json::from_str(body)?;
+    Ok(parsed
+        .results
+        .into_iter()
+        .filter(|r| !r.url.is_empty())
+        .take(limit)
+        .map(|r| SearchHit {
+            title: if r.title.is_empty() { r.url.clone() } else { r.title },
+            url: r.url,
+            snippet: r.content.trim().to_string(),
+        })
+        .collect())
+}
+
+// -- Result cache -------------------------------------------------------
+
+/// `web_search` results stored in SQLite, keyed by provider, normalised
+/// query and result limit.  All timestamps are unix seconds.
+pub struct SearchCache<'a> {
+    db: &'a Arc<Mutex<Connection>>,
+}
+
+impl<'a> SearchCache<'a> {
+    pub fn new(db: &'a Arc<Mutex<Connection>>) -> Self {
+        Self { db }
+    }
+
+    /// Results cached for `query` no more than `ttl_secs` before `now`.
+    pub async fn get(
+        &self,
+        provider: &str,
+        query: &str,
+        max_results: usize,
+        ttl_secs: u64,
+        now: i64,
+    ) -> Result<Option<Vec<SearchHit>>> {
+        let db = self.db.lock().await;
+        let results: Option<String> = db
+            .query_row(
+                "SELECT results FROM search_cache
+                 WHERE provider = ?1 AND query = ?2 AND max_results = ?3 AND fetched_at > ?4",
+                rusqlite::params![
+                    provider,
+                    normalize_query(query),
+                    max_results as i64,
+                    now.saturating_sub(ttl_secs as i64)
+                ],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(match results {
+            Some(json) => Some(serde_json::from_str(&json)?),
+            None => None,
+        })
+    }
+
+    /// Store fresh results for `query`.  Entries older than `ttl_secs` are
+    /// purged on every write.
+    pub async fn put(
+        &self,
+        provider: &str,
+        query: &str,
+        max_results: usize,
+        hits: &[SearchHit],
+        ttl_secs: u64,
+        now: i64,
+    ) -> Result<()> {
+        let results = serde_json::to_string(hits)?;
+        let db = self.db.lock().await;
+        db.execute(
+            "DELETE FROM search_cache WHERE fetched_at <= ?1",
+            [now.saturating_sub(ttl_secs as i64)],
+        )?;
+        db.execute(
+            "INSERT OR REPLACE INTO search_cache (provider, query, max_results, results, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![provider, normalize_query(query), max_results as i64, results, now],
+        )?;
+        Ok(())
+    }
+}
+
+/// Case- and whitespace-insensitive form of a query, so trivially
+/// different spellings share a cache entry.
+pub fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: &str, searxng_url: &str) -> WebToolConfig {
+        WebToolConfig {
+            search_provider: provider.to_string(),
+            searxng_url: searxng_url.to_string(),
+            ..WebToolConfig::default()
+        }
+    }
+
+    #[test]
+    fn provider_selection_from_config() {
+        let default = provider_from_config(&WebToolConfig::default()).unwrap();
+        assert_eq!(default.name(), "DuckDuckGo");
+
+        let searx = provider_from_config(&config("searxng", "https://search.example.com/")).unwrap();
+        assert_eq!(searx.name(), "SearxNG");
+
+        assert!(provider_from_config(&config("searxng", "")).is_err());
+        assert!(provider_from_config(&config("bing", "")).is_err());
+    }
+
+    #[test]
+    fn searxng_url_goes_through_ssrf_guard() {
+        for url in [
+            "http://localhost:8888",
+            "http://169.254.169.254/",
+            "file:///etc/passwd",
+        ] {
+            assert!(
+                provider_from_config(&config("searxng", url)).is_err(),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_searxng_response() {
+        let body = r#"{
+            "query": "rust",
+            "number_of_results": 0,
+            "results": [
+                {"url": "https://www.rust-lang.org/", "title": "Rust", "content": " A language empowering everyone ", "engine": "duckduckgo"},
+                {"url": "", "title": "no url"},
+                {"url": "https://doc.rust-lang.org/book/", "title": ""},
+                {"url": "https://crates.io/", "title": "crates.io", "content": "registry"}
+            ],
+            "answers": []
+        }"#;
+        let hits = parse_searxng_json(body, 2).unwrap();
+        assert_eq!(
+            hits,
+            vec![
+                SearchHit {
+                    title: "Rust".into(),
+                    url: "https://www.rust-lang.org/".into(),
+                    snippet: "A language empowering everyone".into(),
+                },
+                SearchHit {
+                    title: "https://doc.rust-lang.org/book/".into(),
+                    url: "https://doc.rust-lang.org/book/".into(),
+                    snippet: String::new(),
+                },
+            ]
+        );
+
+        assert!(parse_searxng_json("{}", 5).unwrap().is_empty());
+        assert!(parse_searxng_json("<html>", 5).is_err());
+    }
+}
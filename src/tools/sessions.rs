@@ -1,10 +1,47 @@
 use async_trait::async_trait;
-use tracing::debug;
+use rusqlite::Connection;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
 use uuid::Uuid;
 
 use super::{Tool, ToolContext, ToolOutput};
 use crate::error::Result;
 
+// -- Persistence ---------------------------------------------------------
+//
+// Sessions and their messages live in the `sessions` / `session_messages`
+// tables, so spawned sessions carry over restarts.  `updated_at` tracks the
+// last activity and drives reaping of orphaned sessions.
+
+/// Delete sessions (and, via cascade, their messages) with no activity for
+/// more than `max_idle_hours`.  0 disables reaping.
+pub async fn reap_idle_sessions(db: &Mutex<Connection>, max_idle_hours: u64) -> Result<usize> {
+    if max_idle_hours == 0 {
+        return Ok(0);
+    }
+    let db = db.lock().await;
+    let reaped = db.execute(
+        "DELETE FROM sessions WHERE updated_at < datetime('now', ?1)",
+        [format!("-{max_idle_hours} hours")],
+    )?;
+    Ok(reaped)
+}
+
+/// Reap orphaned sessions at startup and report how many active sessions
+/// were restored from the database.
+pub async fn restore_sessions(db: &Mutex<Connection>, max_idle_hours: u64) -> Result<usize> {
+    let reaped = reap_idle_sessions(db, max_idle_hours).await?;
+    let active: i64 = db.lock().await.query_row(
+        "SELECT COUNT(*) FROM sessions WHERE status = 'active'",
+        [],
+        |row| row.get(0),
+    )?;
+    if reaped > 0 {
+        info!(count = reaped, "reaped idle sessions");
+    }
+    Ok(active as usize)
+}
+
 /// Multi-agent session coordination tool.
 pub struct SessionsListTool;
 
@@ -14,6 +51,10 @@ impl Tool for SessionsListTool {
         "sessions_list"
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "List all active agent sessions with their status, labels, and recent messages."
     }
@@ -43,12 +84,13 @@ impl Tool for SessionsListTool {
         let sessions: Vec<String> = stmt
             .query_map([limit], |row| {
                 Ok(format!(
-                    "[{}] label={} agent={} status={} created={}",
+                    "[{}] label={} agent={} status={} created={} last_active={}",
                     row.get::<_, String>(0)?,
                     row.get::<_, String>(1)?,
                     row.get::<_, String>(2)?,
                     row.get::<_, String>(3)?,
                     row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
                 ))
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -70,6 +112,10 @@ impl Tool for SessionsHistoryTool {
         "sessions_history"
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Get the message history of a specific session."
     }
@@ -232,6 +278,10 @@ mod tests {
     use std::sync::Arc;
 
     fn test_ctx() -> ToolContext {
+        ctx_with_db(db::test_db())
+    }
+
+    fn ctx_with_db(db: Arc<tokio::sync::Mutex<rusqlite::Connection>>) -> ToolContext {
         let base = std::env::temp_dir().join(format!("sa-sesstest-{}", std::process::id()));
         let sandbox_dir = base.join("sandbox");
         let trash_dir = base.join("trash");
@@ -240,7 +290,7 @@ mod tests {
 
         ToolContext {
             sandbox: SandboxedFs::new(sandbox_dir).unwrap(),
-            db: db::test_db(),
+            db,
             http_client: reqwest::Client::new(),
             messaging: Arc::new(MessagingManager::new()),
             trash: Arc::new(TrashManager::new(&trash_dir).unwrap()),
@@ -339,4 +389,73 @@ mod tests {
         assert_eq!(SessionsSendTool.name(), "sessions_send");
         assert_eq!(SessionsSpawnTool.name(), "sessions_spawn");
     }
+
+    #[tokio::test]
+    async fn spawned_session_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.db");
+
+        let sid = {
+            let ctx = ctx_with_db(Arc::new(tokio::sync::Mutex::new(db::open(&path).unwrap())));
+            let r = SessionsSpawnTool
+                .execute(serde_json::json!({"task": "Watch the build", "label": "ci-watch"}), &ctx)
+                .await
+                .unwrap();
+            let sid = r.metadata.unwrap()["session_id"].as_str().unwrap().to_string();
+
+            let db = ctx.db.lock().await;
+            let status: String = db
+                .query_row("SELECT status FROM sessions WHERE id = ?1", [&sid], |r| r.get(0))
+                .unwrap();
+            assert_eq!(status, "active");
+            sid
+        };
+
+        // Simulated restart: a fresh connection to the same database.
+        let ctx = ctx_with_db(Arc::new(tokio::sync::Mutex::new(db::open(&path).unwrap())));
+        assert_eq!(restore_sessions(&ctx.db, 72).await.unwrap(), 1);
+
+        let list = SessionsListTool.execute(serde_json::json!({}), &ctx).await.unwrap();
+        assert!(list.output.contains(&sid));
+        assert!(list.output.contains("label=ci-watch"));
+        assert!(list.output.contains("last_active="));
+        let hist = SessionsHistoryTool
+            .execute(serde_json::json!({"session_id": &sid}), &ctx)
+            .await
+            .unwrap();
+        assert!(hist.output.contains("Watch the build"));
+    }
+
+    #[tokio::test]
+    async fn idle_sessions_are_reaped() {
+        let ctx = test_ctx();
+        SessionsSpawnTool
+            .execute(serde_json::json!({"task": "fresh"}), &ctx)
+            .await
+            .unwrap();
+        {
+            let db = ctx.db.lock().await;
+            db.execute(
+                "INSERT INTO sessions (id, label, updated_at) VALUES ('old', 'stale', datetime('now', '-5 days'))",
+                [],
+            )
+            .unwrap();
+            db.execute(
+                "INSERT INTO session_messages (session_id, role, content) VALUES ('old', 'system', 'x')",
+                [],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(reap_idle_sessions(&ctx.db, 0).await.unwrap(), 0);
+        assert_eq!(reap_idle_sessions(&ctx.db, 24).await.unwrap(), 1);
+
+        let db = ctx.db.lock().await;
+        let orphans: i64 = db
+            .query_row("SELECT COUNT(*) FROM session_messages WHERE session_id = 'old'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(orphans, 0);
+        let remaining: i64 = db.query_row("SELECT COUNT(*) FROM sessions", [], |r| r.get(0)).unwrap();
+        assert_eq!(remaining, 1);
+    }
 }
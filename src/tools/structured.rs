@@ -0,0 +1,382 @@
+//! Key-level reads and writes of JSON, TOML and YAML files.
+//!
+//! Text edits to config files are easy to get wrong; this tool parses the
+//! file (format chosen by extension), gets or sets one dotted key path such
+//! as `server.ports.0`, and writes it back.  TOML keeps its comments and
+//! layout.  JSON and YAML keep their key order but are re-serialized, so
+//! YAML comments are lost.  Nothing is written unless the file parsed and
+//! the result re-parses.
+
+use async_trait::async_trait;
+use serde_yaml::{Mapping, Value as Node};
+
+use super::{Tool, ToolContext, ToolOutput};
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    fn from_path(path: &str) -> Option<Self> {
+        let ext = std::path::Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+pub struct StructuredEditTool;
+
+#[async_trait]
+impl Tool for StructuredEditTool {
+    fn name(&self) -> &str {
+        "structured_edit"
+    }
+
+    fn description(&self) -> &str {
+        "Get or set one key in a JSON, TOML or YAML file within the sandbox, addressed by a dotted path \
+         (e.g. \"server.port\" or \"items.0.name\"). Safer than text edits for config files."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["action", "path", "key"],
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["get", "set"]
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Relative path within the sandbox (.json, .toml, .yaml or .yml)"
+                },
+                "key": {
+                    "type": "string",
+                    "description": "Dotted key path; numeric segments index arrays"
+                },
+                "value": {
+                    "description": "New value for set (any JSON value); missing tables along the path are created"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: serde_json::Value, ctx: &ToolContext) -> Result<ToolOutput> {
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or_default();
+        let path = params.get("path").and_then(|v| v.as_str()).unwrap_or_default();
+        let key = params.get("key").and_then(|v| v.as_str()).unwrap_or_default();
+
+        if path.is_empty() {
+            return Ok(ToolOutput::error("path is required"));
+        }
+        let Some(format) = Format::from_path(path) else {
+            return Ok(ToolOutput::error(format!(
+                "{path}: unsupported file type (expected .json, .toml, .yaml or .yml)"
+            )));
+        };
+        let segments = match parse_key(key) {
+            Ok(s) => s,
+            Err(e) => return Ok(ToolOutput::error(e)),
+        };
+        let rel = std::path::Path::new(path);
+        let contents = match ctx.sandbox.read_to_string(rel) {
+            Ok(c) => c,
+            Err(e) => return Ok(ToolOutput::error(format!("failed to read: {e}"))),
+        };
+
+        match action {
+            "get" => Ok(match get(format, &contents, &segments) {
+                Ok(value) => ToolOutput::ok(serde_json::to_string_pretty(&value).unwrap_or_default()),
+                Err(e) => ToolOutput::error(format!("{path}: {e}")),
+            }),
+            "set" => {
+                let Some(value) = params.get("value") else {
+                    return Ok(ToolOutput::error("value is required for set"));
+                };
+                let updated = match set(format, &contents, &segments, value) {
+                    Ok(u) => u,
+                    Err(e) => return Ok(ToolOutput::error(format!("{path}: {e}; file not changed"))),
+                };
+                match ctx.sandbox.write(rel, updated.as_bytes()) {
+                    Ok(()) => Ok(ToolOutput::ok(format!("Set {key} in {path}"))),
+                    Err(e) => Ok(ToolOutput::error(format!("failed to write: {e}"))),
+                }
+            }
+            other => Ok(ToolOutput::error(format!("unknown action '{other}' (expected get or set)"))),
+        }
+    }
+}
+
+fn parse_key(key: &str) -> std::result::Result<Vec<&str>, String> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if key.is_empty() || segments.iter().any(|s| s.is_empty()) {
+        return Err(format!("invalid key path '{key}'"));
+    }
+    Ok(segments)
+}
+
+fn get(format: Format, contents: &str, key: &[&str]) -> std::result::Result<serde_json::Value, String> {
+    let doc = match format {
+        Format::Toml => {
+            let table: toml::Table = toml::from_str(contents).map_err(|e| format!("invalid TOML: {e}"))?;
+            serde_yaml::to_value(table).map_err(|e| e.to_string())?
+        }
+        _ => parse_tree(format, contents)?,
+    };
+    let mut node = &doc;
+    for (i, seg) in key.iter().enumerate() {
+        node = child(node, seg).ok_or_else(|| format!("key '{}' not found", key[..=i].join(".")))?;
+    }
+    serde_json::to_value(node).map_err(|e| format!("value at '{}' can't be shown as JSON: {e}", key.join(".")))
+}
+
+/// The updated file contents with `key` set to `value`.
+fn set(format: Format, contents: &str, key: &[&str], value: &serde_json::Value) -> std::result::Result<String, String> {
+    let updated = match format {
+        Format::Toml => set_toml(contents, key, value)?,
+        _ => {
+            let mut doc = parse_tree(format, contents)?;
+            let value = serde_yaml::to_value(value).map_err(|e| e.to_string())?;
+            set_node(&mut doc, key, value)?;
+            match format {
+                Format::Json => {
+                    let mut out = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?;
+                    if contents.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out
+                }
+                _ => serde_yaml::to_string(&doc).map_err(|e| e.to_string())?,
+            }
+        }
+    };
+    // Never write something that doesn't read back
+    match format {
+        Format::Toml => updated.parse::<toml_edit::DocumentMut>().map(drop).map_err(|e| e.to_string())?,
+        _ => parse_tree(format, &updated).map(drop)?,
+    }
+    Ok(updated)
+}
+
+/// JSON and YAML parsed into an order-preserving tree.
+fn parse_tree(format: Format, contents: &str) -> std::result::Result<Node, String> {
+    match format {
+        Format::Json => serde_json::from_str(contents).map_err(|e| format!("invalid JSON: {e}")),
+        _ => serde_yaml::from_str(contents).map_err(|e| format!("invalid YAML: {e}")),
+    }
+}
+
+fn child<'a>(node: &'a Node, seg: &str) -> Option<&'a Node> {
+    match node {
+        Node::Mapping(m) => m.get(seg),
+        Node::Sequence(s) => s.get(seg.parse::<usize>().ok()?),
+        _ => None,
+    }
+}
+
+fn set_node(doc: &mut Node, key: &[&str], value: Node) -> std::result::Result<(), String> {
+    let (last, parents) = key.split_last().expect("key paths are never empty");
+    let mut node = doc;
+    for (i, seg) in parents.iter().enumerate() {
+        let at = key[..=i].join(".");
+        if node.is_null() {
+            *node = Node::Mapping(Mapping::new());
+        }
+        node = match node {
+            Node::Mapping(m) => m
+                .entry(Node::String(seg.to_string()))
+                .or_insert_with(|| Node::Mapping(Mapping::new())),
+            Node::Sequence(s) => {
+                let len = s.len();
+                seg.parse::<usize>()
+                    .ok()
+                    .and_then(|n| s.get_mut(n))
+                    .ok_or_else(|| format!("'{at}' is not an index of an array of length {len}"))?
+            }
+            _ => return Err(format!("'{}' is not a table", key[..i].join("."))),
+        };
+    }
+    if node.is_null() {
+        *node = Node::Mapping(Mapping::new());
+    }
+    match node {
+        Node::Mapping(m) => {
+            m.insert(Node::String(last.to_string()), value);
+        }
+        Node::Sequence(s) => match last.parse::<usize>() {
+            Ok(n) if n < s.len() => s[n] = value,
+            Ok(n) if n == s.len() => s.push(value),
+            _ => return Err(format!("'{}' is not an index of an array of length {}", key.join("."), s.len())),
+        },
+        _ => return Err(format!("'{}' is not a table", parents.join("."))),
+    }
+    Ok(())
+}
+
+fn set_toml(contents: &str, key: &[&str], value: &serde_json::Value) -> std::result::Result<String, String> {
+    let mut doc: toml_edit::DocumentMut = contents.parse().map_err(|e| format!("invalid TOML: {e}"))?;
+    let mut new = toml_value(value)?;
+    let (last, parents) = key.split_last().expect("key paths are never empty");
+
+    let mut item = doc.as_item_mut();
+    for (i, seg) in parents.iter().enumerate() {
+        let at = key[..=i].join(".");
+        if item.is_table_like() {
+            let table = item.as_table_like_mut().expect("checked above");
+            if !table.contains_key(seg) {
+                table.insert(seg, toml_edit::table());
+            }
+            item = table.get_mut(seg).expect("just inserted");
+        } else if item.is_array() || item.is_array_of_tables() {
+            item = seg
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| item.get_mut(n))
+                .ok_or_else(|| format!("'{at}' is not an index of the array"))?;
+        } else {
+            return Err(format!("'{}' is not a table", key[..i].join(".")));
+        }
+    }
+
+    if let Some(table) = item.as_table_like_mut() {
+        // Keep comments attached to a value being replaced
+        if let Some(old) = table.get(last).and_then(toml_edit::Item::as_value) {
+            *new.decor_mut() = old.decor().clone();
+        }
+        table.insert(last, toml_edit::Item::Value(new));
+    } else if let Some(array) = item.as_array_mut() {
+        match last.parse::<usize>() {
+            Ok(n) if n < array.len() => {
+                array.replace(n, new);
+            }
+            Ok(n) if n == array.len() => array.push(new),
+            _ => return Err(format!("'{}' is not an index of an array of length {}", key.join("."), array.len())),
+        }
+    } else {
+        return Err(format!("'{}' is not a table", parents.join(".")));
+    }
+    Ok(doc.to_string())
+}
+
+fn toml_value(value: &serde_json::Value) -> std::result::Result<toml_edit::Value, String> {
+    use serde_json::Value as J;
+    Ok(match value {
+        J::Null => return Err("TOML has no null value".into()),
+        J::Bool(b) => (*b).into(),
+        J::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().ok_or_else(|| format!("{n} is out of range for TOML"))?.into(),
+        },
+        J::String(s) => s.as_str().into(),
+        J::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for v in items {
+                array.push(toml_value(v)?);
+            }
+            toml_edit::Value::Array(array)
+        }
+        J::Object(fields) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (k, v) in fields {
+                table.insert(k, toml_value(v)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::messaging::MessagingManager;
+    use crate::security::SandboxedFs;
+    use crate::trash::TrashManager;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    fn test_ctx(base: &Path) -> ToolContext {
+        let sandbox_dir = base.join("sandbox");
+        let trash_dir = base.join("trash");
+        std::fs::create_dir_all(&sandbox_dir).unwrap();
+        std::fs::create_dir_all(&trash_dir).unwrap();
+
+        ToolContext {
+            sandbox: SandboxedFs::new(sandbox_dir).unwrap(),
+            db: db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(&trash_dir).unwrap()),
+        }
+    }
+
+    async fn run(ctx: &ToolContext, params: serde_json::Value) -> ToolOutput {
+        StructuredEditTool.execute(params, ctx).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn sets_nested_json_key_keeping_order() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(dir.path());
+        let file = Path::new("app.json");
+        ctx.sandbox.write(file, b"{\"name\": \"app\", \"server\": {\"port\": 80, \"hosts\": [\"a\"]}}\n").unwrap();
+
+        let out = run(&ctx, serde_json::json!({"action": "set", "path": "app.json", "key": "server.port", "value": 8080})).await;
+        assert!(out.success, "{}", out.output);
+        let out = run(&ctx, serde_json::json!({"action": "set", "path": "app.json", "key": "server.tls.enabled", "value": true})).await;
+        assert!(out.success, "{}", out.output);
+        let out = run(&ctx, serde_json::json!({"action": "set", "path": "app.json", "key": "server.hosts.1", "value": "b"})).await;
+        assert!(out.success, "{}", out.output);
+
+        let written = ctx.sandbox.read_to_string(file).unwrap();
+        assert!(written.find("\"name\"").unwrap() < written.find("\"server\"").unwrap(), "{written}");
+        let parsed: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!({"name": "app", "server": {"port": 8080, "hosts": ["a", "b"], "tls": {"enabled": true}}})
+        );
+
+        let out = run(&ctx, serde_json::json!({"action": "get", "path": "app.json", "key": "server.tls"})).await;
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&out.output).unwrap(), serde_json::json!({"enabled": true}));
+        let out = run(&ctx, serde_json::json!({"action": "set", "path": "app.json", "key": "name.first", "value": 1})).await;
+        assert!(!out.success && out.output.contains("'name' is not a table"), "{}", out.output);
+    }
+
+    #[tokio::test]
+    async fn toml_edit_keeps_comments() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(dir.path());
+        let file = Path::new("config.toml");
+        ctx.sandbox.write(file, b"# main settings\n[server]\nport = 80 # http\n").unwrap();
+
+        let out = run(&ctx, serde_json::json!({"action": "set", "path": "config.toml", "key": "server.port", "value": 8080})).await;
+        assert!(out.success, "{}", out.output);
+        assert_eq!(ctx.sandbox.read_to_string(file).unwrap(), "# main settings\n[server]\nport = 8080 # http\n");
+    }
+
+    #[tokio::test]
+    async fn malformed_file_is_rejected_without_writing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(dir.path());
+        for (name, broken) in [("bad.json", "{\"a\": 1,"), ("bad.toml", "a = [1,"), ("bad.yaml", "a: [1,")] {
+            ctx.sandbox.write(Path::new(name), broken.as_bytes()).unwrap();
+            let out = run(&ctx, serde_json::json!({"action": "set", "path": name, "key": "a", "value": 2})).await;
+            assert!(!out.success, "{name}");
+            assert!(out.output.contains("invalid") && out.output.contains("file not changed"), "{}", out.output);
+            assert_eq!(ctx.sandbox.read_to_string(Path::new(name)).unwrap(), broken);
+        }
+
+        let out = run(&ctx, serde_json::json!({"action": "get", "path": "notes.txt", "key": "a"})).await;
+        assert!(out.output.contains("unsupported file type"));
+        let out = run(&ctx, serde_json::json!({"action": "get", "path": "bad.json", "key": "a..b"})).await;
+        assert!(out.output.contains("invalid key path"));
+    }
+}
@@ -1,18 +1,83 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
-use tracing::debug;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{debug, warn};
 
+use super::robots::RobotsCache;
+use super::search::{SearchCache, SearchHit, SearchProvider};
 use super::{Tool, ToolContext, ToolOutput};
+use crate::config::WebToolConfig;
 use crate::error::Result;
 
-// -- WebSearch (DuckDuckGo) ----------------------------------------------
+// -- WebSearch -----------------------------------------------------------
 
 pub struct WebSearchTool {
     max_results: usize,
+    provider: Box<dyn SearchProvider>,
+    description: String,
+    /// How long results are reused for an identical query; zero disables
+    /// the cache.
+    cache_ttl: Duration,
 }
 
 impl WebSearchTool {
-    pub fn new(max_results: usize) -> Self {
-        Self { max_results }
+    pub fn new(max_results: usize, provider: Box<dyn SearchProvider>) -> Self {
+        let description = format!(
+            "Search the web using {}. Returns a list of results with titles, URLs, and snippets.",
+            provider.name()
+        );
+        Self {
+            max_results,
+            provider,
+            description,
+            cache_ttl: Duration::ZERO,
+        }
+    }
+
+    /// Reuse results for an identical query for `ttl`.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Results for `query`, from the cache when a fresh entry exists and
+    /// `no_cache` isn't set.  Returns whether they came from the cache.
+    async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        no_cache: bool,
+        ctx: &ToolContext,
+    ) -> Result<(Vec<SearchHit>, bool)> {
+        let ttl = self.cache_ttl.as_secs();
+        if ttl == 0 {
+            return Ok((self.provider.search(query, limit).await?, false));
+        }
+        let cache = SearchCache::new(&ctx.db);
+        let provider = self.provider.name();
+        let now = chrono::Utc::now().timestamp();
+        if !no_cache {
+            match cache.get(provider, query, limit, ttl, now).await {
+                Ok(Some(hits)) => {
+                    debug!(query, "web_search cache hit");
+                    return Ok((hits, true));
+                }
+                Ok(None) => {}
+                Err(e) => warn!(err = %e, "web_search cache lookup failed"),
+            }
+        }
+        let hits = self.provider.search(query, limit).await?;
+        // An empty result may be a provider hiccup; don't pin it for the TTL.
+        if !hits.is_empty() {
+            if let Err(e) = cache.put(provider, query, limit, &hits, ttl, now).await {
+                warn!(err = %e, "failed to cache web_search results");
+            }
+        }
+        Ok((hits, false))
     }
 }
 
@@ -22,8 +87,12 @@ impl Tool for WebSearchTool {
         "web_search"
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
-        "Search the web using DuckDuckGo. Returns a list of results with titles, URLs, and snippets."
+        &self.description
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -38,6 +107,10 @@ impl Tool for WebSearchTool {
                 "max_results": {
                     "type": "integer",
                     "description": "Maximum number of results (default 10)"
+                },
+                "no_cache": {
+                    "type": "boolean",
+                    "description": "Search live even if recent results for this query are cached"
                 }
             }
         })
@@ -57,136 +130,140 @@ impl Tool for WebSearchTool {
             .get("max_results")
             .and_then(|v| v.as_u64())
             .unwrap_or(self.max_results as u64) as usize;
-
-        debug!(query, limit, "searching DuckDuckGo");
-
-        // Use DuckDuckGo HTML search (no API key needed)
-        let url = format!(
-            "https://html.duckduckgo.com/html/?q={}",
-            urlencoding(query)
-        );
-
-        let resp = ctx
-            .http_client
-            .get(&url)
-            .header("User-Agent", "Mozilla/5.0 (compatible; SafeClaw/0.1)")
-            .send()
-            .await;
-
-        match resp {
-            Ok(r) => {
-                let body = r.text().await.unwrap_or_default();
-                let results = parse_ddg_html(&body, limit);
-                if results.is_empty() {
-                    Ok(ToolOutput::ok("No results found."))
-                } else {
-                    let mut out = String::new();
-                    for (i, (title, url, snippet)) in results.iter().enumerate() {
-                        out.push_str(&format!(
-                            "{}. {}\n   {}\n   {}\n\n",
-                            i + 1,
-                            title,
-                            url,
-                            snippet,
-                        ));
-                    }
-                    Ok(ToolOutput::ok(out))
+        let no_cache = params.get("no_cache").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        match self.search(query, limit, no_cache, ctx).await {
+            Ok((results, _)) if results.is_empty() => Ok(ToolOutput::ok("No results found.")),
+            Ok((results, cached)) => {
+                let mut out = String::new();
+                for (i, hit) in results.iter().enumerate() {
+                    out.push_str(&format!(
+                        "{}. {}\n   {}\n   {}\n\n",
+                        i + 1,
+                        hit.title,
+                        hit.url,
+                        hit.snippet,
+                    ));
                 }
+                Ok(ToolOutput::ok_with_meta(out, serde_json::json!({ "cached": cached })))
             }
             Err(e) => Ok(ToolOutput::error(format!("search failed: {e}"))),
         }
     }
 }
 
-/// Parse DuckDuckGo HTML search results page.
-fn parse_ddg_html(html: &str, limit: usize) -> Vec<(String, String, String)> {
-    let mut results = Vec::new();
-
-    // Simple extraction of result blocks from DDG HTML
-    for chunk in html.split("class=\"result__a\"").skip(1).take(limit) {
-        let title = extract_between(chunk, ">", "</a>")
-            .map(|s| strip_tags(&s))
-            .unwrap_or_default();
-        let url = extract_between(chunk, "href=\"", "\"").unwrap_or_default();
-        let snippet = if let Some(s_start) = chunk.find("class=\"result__snippet\"") {
-            let after = &chunk[s_start..];
-            extract_between(after, ">", "</")
-                .map(|s| strip_tags(&s))
-                .unwrap_or_default()
-        } else {
-            String::new()
-        };
-
-        if !title.is_empty() {
-            // DDG redirects through their URL; extract the actual URL
-            let actual_url = if url.contains("uddg=") {
-                url.split("uddg=")
-                    .nth(1)
-                    .and_then(|s| s.split('&').next())
-                    .map(|s| urldecoding(s))
-                    .unwrap_or(url)
-            } else {
-                url
-            };
-            results.push((title, actual_url, snippet));
-        }
-    }
+// -- WebFetch ------------------------------------------------------------
 
-    results
+pub struct WebFetchTool {
+    max_bytes: usize,
+    content_types: Vec<String>,
+    html_to_text: bool,
+    respect_robots: bool,
+    robots_exempt_hosts: Vec<String>,
+    per_host_delay: Duration,
+    robots: RobotsCache,
+    throttle: HostThrottle,
 }
 
-fn extract_between(text: &str, start: &str, end: &str) -> Option<String> {
-    let s = text.find(start)?;
-    let after = &text[s + start.len()..];
-    let e = after.find(end)?;
-    Some(after[..e].to_string())
+/// Spaces out requests to the same host.  Each host has its own lock, so
+/// concurrent fetches to one host queue up while other hosts proceed.
+#[derive(Default)]
+struct HostThrottle {
+    hosts: Mutex<HashMap<String, Arc<Mutex<Option<Instant>>>>>,
 }
 
-fn strip_tags(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    let mut in_tag = false;
-    for c in s.chars() {
-        match c {
-            '<' => in_tag = true,
-            '>' => in_tag = false,
-            _ if !in_tag => out.push(c),
-            _ => {}
+impl HostThrottle {
+    /// Wait until at least `delay` has passed since the previous request
+    /// to `host` started.
+    async fn wait(&self, host: &str, delay: Duration) {
+        let slot = self.hosts.lock().await.entry(host.to_string()).or_default().clone();
+        let mut last = slot.lock().await;
+        if let Some(prev) = *last {
+            tokio::time::sleep_until(prev + delay).await;
         }
+        *last = Some(Instant::now());
     }
-    out.trim().to_string()
 }
 
-pub fn urlencoding(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-            ' ' => "+".to_string(),
-            _ => format!("%{:02X}", c as u32),
-        })
-        .collect()
-}
+impl WebFetchTool {
+    pub fn new(config: &WebToolConfig) -> Self {
+        Self {
+            max_bytes: config.max_fetch_bytes,
+            content_types: config
+                .fetch_content_types
+                .iter()
+                .map(|t| t.trim().to_ascii_lowercase())
+                .collect(),
+            html_to_text: config.html_to_text,
+            respect_robots: config.respect_robots,
+            robots_exempt_hosts: config
+                .robots_exempt_hosts
+                .iter()
+                .map(|h| h.trim().to_ascii_lowercase())
+                .collect(),
+            per_host_delay: Duration::from_millis(config.per_host_delay_ms),
+            robots: RobotsCache::default(),
+            throttle: HostThrottle::default(),
+        }
+    }
+
+    fn robots_exempt(&self, host: &str) -> bool {
+        self.robots_exempt_hosts
+            .iter()
+            .any(|h| host == h || host.strip_suffix(h.as_str()).is_some_and(|sub| sub.ends_with('.')))
+    }
 
-fn urldecoding(s: &str) -> String {
-    let mut out = String::new();
-    let mut chars = s.chars();
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            let hex: String = chars.by_ref().take(2).collect();
-            if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                out.push(byte as char);
+    /// Check robots.txt and wait out the per-host delay.  Returns an error
+    /// message if the URL must not be fetched.
+    async fn be_polite(&self, client: &reqwest::Client, url: &reqwest::Url) -> std::result::Result<(), String> {
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        let mut delay = self.per_host_delay;
+
+        if self.respect_robots && !self.robots_exempt(&host) {
+            let rules = self.robots.rules_for(client, url).await;
+            let path = match url.query() {
+                Some(q) => format!("{}?{q}", url.path()),
+                None => url.path().to_string(),
+            };
+            if !rules.allows(&path) {
+                return Err(format!("{url} is disallowed by the site's robots.txt"));
             }
-        } else if c == '+' {
-            out.push(' ');
-        } else {
-            out.push(c);
+            delay = delay.max(rules.crawl_delay().unwrap_or_default());
         }
+
+        self.throttle.wait(&host, delay).await;
+        Ok(())
     }
-    out
-}
 
-// -- WebFetch ------------------------------------------------------------
+    /// Reject responses whose content type isn't allowlisted.
+    fn check_content_type(&self, content_type: &str) -> std::result::Result<(), String> {
+        let essence = mime_essence(content_type);
+        if self.content_types.contains(&essence) {
+            return Ok(());
+        }
+        let shown = if essence.is_empty() { "none" } else { essence.as_str() };
+        Err(format!(
+            "unsupported content type '{shown}' (allowed: {})",
+            self.content_types.join(", ")
+        ))
+    }
 
-pub struct WebFetchTool;
+    /// Read the body in chunks, aborting as soon as it exceeds the limit.
+    async fn read_limited(&self, mut resp: reqwest::Response) -> std::result::Result<Vec<u8>, String> {
+        let too_large = || format!("response exceeds the {} byte limit", self.max_bytes);
+        if resp.content_length().is_some_and(|len| len > self.max_bytes as u64) {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = resp.chunk().await.map_err(|e| format!("fetch failed: {e}"))? {
+            if body.len() + chunk.len() > self.max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+}
 
 #[async_trait]
 impl Tool for WebFetchTool {
@@ -194,6 +271,10 @@ impl Tool for WebFetchTool {
         "web_fetch"
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Fetch a URL and return its content as readable text/markdown. Useful for reading web pages."
     }
@@ -229,6 +310,14 @@ impl Tool for WebFetchTool {
             return Ok(ToolOutput::error("url is required"));
         }
 
+        let parsed = match reqwest::Url::parse(url) {
+            Ok(u) => u,
+            Err(e) => return Ok(ToolOutput::error(format!("invalid url: {e}"))),
+        };
+        if let Err(msg) = self.be_polite(&ctx.http_client, &parsed).await {
+            return Ok(ToolOutput::error(msg));
+        }
+
         debug!(url, max_chars, "fetching URL");
 
         let resp = ctx
@@ -238,32 +327,389 @@ impl Tool for WebFetchTool {
             .send()
             .await;
 
-        match resp {
-            Ok(r) => {
-                let content_type = r
-                    .headers()
-                    .get("content-type")
-                    .and_then(|v| v.to_str().ok())
-                    .unwrap_or("")
-                    .to_string();
-
-                let body = r.text().await.unwrap_or_default();
-
-                let text = if content_type.contains("text/html") {
-                    htmd::convert(&body).unwrap_or(body)
-                } else {
-                    body
-                };
-
-                let truncated = if text.len() > max_chars {
-                    format!("{}...\n[truncated at {} chars]", &text[..max_chars], max_chars)
-                } else {
-                    text
-                };
-
-                Ok(ToolOutput::ok(truncated))
+        let r = match resp {
+            Ok(r) => r,
+            Err(e) => return Ok(ToolOutput::error(format!("fetch failed: {e}"))),
+        };
+
+        let content_type = r
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        if let Err(msg) = self.check_content_type(&content_type) {
+            return Ok(ToolOutput::error(msg));
+        }
+
+        let body = match self.read_limited(r).await {
+            Ok(b) => String::from_utf8_lossy(&b).into_owned(),
+            Err(msg) => return Ok(ToolOutput::error(msg)),
+        };
+
+        let text = if mime_essence(&content_type) == "text/html" {
+            if self.html_to_text {
+                html_to_text(&body)
+            } else {
+                htmd::convert(&body).unwrap_or(body)
+            }
+        } else {
+            body
+        };
+
+        let truncated = if text.len() > max_chars {
+            let mut end = max_chars;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            format!("{}...\n[truncated at {} chars]", &text[..end], max_chars)
+        } else {
+            text
+        };
+
+        Ok(ToolOutput::ok(truncated))
+    }
+}
+
+/// The `type/subtype` part of a Content-Type header, lowercased.
+fn mime_essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Elements whose contents are never readable text.
+const SKIPPED_ELEMENTS: &[&str] = &["script", "style", "noscript", "template", "svg", "head"];
+
+/// Elements that start a new line in the extracted text.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p", "div", "br", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6", "ul", "ol", "table",
+    "section", "article", "header", "footer", "nav", "main", "aside", "pre", "blockquote", "hr",
+    "title",
+];
+
+/// Reduce an HTML document to readable plain text: drops scripts, styles
+/// and markup, decodes common entities, and keeps block elements on
+/// separate lines.
+fn html_to_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&decode_entities(&rest[..lt]));
+        let tag_src = &rest[lt + 1..];
+        if tag_src.starts_with("!--") {
+            rest = tag_src.find("-->").map_or("", |end| &tag_src[end + 3..]);
+            continue;
+        }
+        let Some(gt) = tag_src.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &tag_src[..gt];
+        rest = &tag_src[gt + 1..];
+
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        if !closing && !tag.ends_with('/') && SKIPPED_ELEMENTS.contains(&name.as_str()) {
+            // Jump straight to the matching close tag; the contents may
+            // contain '<' (e.g. comparisons in scripts).
+            let close = format!("</{name}");
+            rest = match rest.to_ascii_lowercase().find(&close) {
+                Some(pos) => rest[pos..].find('>').map_or("", |gt| &rest[pos + gt + 1..]),
+                None => "",
+            };
+            continue;
+        }
+        if BLOCK_ELEMENTS.contains(&name.as_str()) {
+            out.push('\n');
+        } else if name == "td" || name == "th" {
+            out.push(' ');
+        }
+    }
+    out.push_str(&decode_entities(rest));
+
+    // Collapse runs of whitespace and drop blank lines.
+    out.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Decode the HTML entities that commonly appear in page text.
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &after[..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            ch.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
             }
-            Err(e) => Ok(ToolOutput::error(format!("fetch failed: {e}"))),
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::MessagingManager;
+    use crate::security::SandboxedFs;
+    use crate::trash::TrashManager;
+    use axum::http::header;
+    use axum::routing::get;
+    use std::sync::Arc;
+
+    fn test_ctx(base: &std::path::Path) -> ToolContext {
+        ToolContext {
+            sandbox: SandboxedFs::new(base.join("sandbox")).unwrap(),
+            db: crate::db::test_db(),
+            http_client: reqwest::Client::new(),
+            messaging: Arc::new(MessagingManager::new()),
+            trash: Arc::new(TrashManager::new(&base.join("trash")).unwrap()),
+        }
+    }
+
+    /// Serve a few canned responses on an ephemeral local port.
+    async fn serve() -> String {
+        let app = axum::Router::new()
+            .route("/robots.txt", get(|| async { "User-agent: *\nDisallow: /private\n" }))
+            .route("/private", get(|| async { "secret" }))
+            .route("/big", get(|| async { ([(header::CONTENT_TYPE, "text/plain")], "x".repeat(64 * 1024)) }))
+            .route("/image", get(|| async { ([(header::CONTENT_TYPE, "image/png")], vec![0x89u8, b'P', b'N', b'G']) }))
+            .route(
+                "/page",
+                get(|| async {
+                    (
+                        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                        "<html><body><h1>Title</h1><p>Hello &amp; welcome</p></body></html>",
+                    )
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn fetch_tool(max_bytes: usize, html_to_text: bool) -> WebFetchTool {
+        WebFetchTool::new(&WebToolConfig {
+            max_fetch_bytes: max_bytes,
+            html_to_text,
+            per_host_delay_ms: 0,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn fetch_refuses_robots_disallowed_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        let base = serve().await;
+        let tool = fetch_tool(1024, false);
+
+        let out = tool.execute(serde_json::json!({"url": format!("{base}/private")}), &ctx).await.unwrap();
+        assert!(!out.success);
+        assert!(out.output.contains("disallowed by the site's robots.txt"), "{}", out.output);
+        let out = tool.execute(serde_json::json!({"url": format!("{base}/page")}), &ctx).await.unwrap();
+        assert!(out.success);
+
+        // Exempt hosts skip the check.
+        let exempt = WebFetchTool::new(&WebToolConfig {
+            robots_exempt_hosts: vec!["127.0.0.1".into()],
+            per_host_delay_ms: 0,
+            ..Default::default()
+        });
+        let out = exempt.execute(serde_json::json!({"url": format!("{base}/private")}), &ctx).await.unwrap();
+        assert!(out.success);
+        assert_eq!(out.output, "secret");
+    }
+
+    #[tokio::test]
+    async fn fetch_waits_between_requests_to_same_host() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        let base = serve().await;
+        let tool = WebFetchTool::new(&WebToolConfig {
+            per_host_delay_ms: 300,
+            ..Default::default()
+        });
+        let page = serde_json::json!({"url": format!("{base}/page")});
+
+        let start = std::time::Instant::now();
+        let (a, b) = tokio::join!(tool.execute(page.clone(), &ctx), tool.execute(page.clone(), &ctx));
+        assert!(a.unwrap().success && b.unwrap().success);
+        assert!(start.elapsed() >= Duration::from_millis(300), "{:?}", start.elapsed());
+    }
+
+    #[tokio::test]
+    async fn fetch_aborts_when_body_exceeds_limit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let base = serve().await;
+        let out = fetch_tool(1024, false)
+            .execute(serde_json::json!({"url": format!("{base}/big")}), &test_ctx(tmp.path()))
+            .await
+            .unwrap();
+        assert!(!out.success);
+        assert!(out.output.contains("exceeds the 1024 byte limit"), "{}", out.output);
+
+        let out = fetch_tool(1024 * 1024, false)
+            .execute(serde_json::json!({"url": format!("{base}/big")}), &test_ctx(tmp.path()))
+            .await
+            .unwrap();
+        assert!(out.success);
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_disallowed_content_type() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let base = serve().await;
+        let out = fetch_tool(1024, false)
+            .execute(serde_json::json!({"url": format!("{base}/image")}), &test_ctx(tmp.path()))
+            .await
+            .unwrap();
+        assert!(!out.success);
+        assert!(out.output.contains("unsupported content type 'image/png'"), "{}", out.output);
+    }
+
+    #[tokio::test]
+    async fn fetch_reduces_html_to_text() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let base = serve().await;
+        let out = fetch_tool(1024, true)
+            .execute(serde_json::json!({"url": format!("{base}/page")}), &test_ctx(tmp.path()))
+            .await
+            .unwrap();
+        assert!(out.success);
+        assert_eq!(out.output, "Title\nHello & welcome");
+    }
+
+    #[test]
+    fn html_to_text_drops_scripts_and_markup() {
+        let html = r#"<!DOCTYPE html><html><head><title>T</title><style>p{color:red}</style></head>
+            <body><!-- nav --><script>alert("x<y")</script>
+            <div>First&nbsp;line</div><ul><li>one</li><li>two &#8212; &#x41;</li></ul>
+            <table><tr><td>a</td><td>b</td></tr></table><p>5 &lt; 6 &bogus; done</p></body></html>"#;
+        assert_eq!(
+            html_to_text(html),
+            "First line\none\ntwo \u{2014} A\na b\n5 < 6 &bogus; done"
+        );
+    }
+
+    /// Provider that counts calls and returns a single fixed hit.
+    struct CountingProvider(Arc<std::sync::atomic::AtomicUsize>);
+
+    #[async_trait]
+    impl SearchProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn search(&self, query: &str, _max_results: usize) -> Result<Vec<SearchHit>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(vec![SearchHit {
+                title: format!("About {query}"),
+                url: "https://example.com/".into(),
+                snippet: "snippet".into(),
+            }])
         }
     }
+
+    fn counting_search(ttl: Duration) -> (WebSearchTool, Arc<std::sync::atomic::AtomicUsize>) {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let tool = WebSearchTool::new(5, Box::new(CountingProvider(calls.clone()))).with_cache_ttl(ttl);
+        (tool, calls)
+    }
+
+    fn was_cached(out: &ToolOutput) -> bool {
+        out.metadata.as_ref().and_then(|m| m["cached"].as_bool()).unwrap_or(false)
+    }
+
+    #[tokio::test]
+    async fn search_reuses_cached_results_within_ttl() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        let (tool, calls) = counting_search(Duration::from_secs(3600));
+
+        let first = tool.execute(serde_json::json!({"query": "rust async"}), &ctx).await.unwrap();
+        let second = tool.execute(serde_json::json!({"query": "  Rust   ASYNC "}), &ctx).await.unwrap();
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(!was_cached(&first));
+        assert!(was_cached(&second));
+        assert_eq!(first.output, second.output);
+    }
+
+    #[tokio::test]
+    async fn search_fetches_live_when_entry_expired() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        let (tool, calls) = counting_search(Duration::from_secs(60));
+        let stale = chrono::Utc::now().timestamp() - 120;
+        SearchCache::new(&ctx.db)
+            .put("counting", "rust async", 5, &[], 60, stale)
+            .await
+            .unwrap();
+
+        let out = tool.execute(serde_json::json!({"query": "rust async"}), &ctx).await.unwrap();
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(!was_cached(&out));
+        assert!(out.output.contains("About rust async"));
+    }
+
+    #[tokio::test]
+    async fn search_no_cache_forces_live_fetch() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ctx = test_ctx(tmp.path());
+        let (tool, calls) = counting_search(Duration::from_secs(3600));
+
+        tool.execute(serde_json::json!({"query": "rust"}), &ctx).await.unwrap();
+        let out = tool
+            .execute(serde_json::json!({"query": "rust", "no_cache": true}), &ctx)
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(!was_cached(&out));
+    }
 }
@@ -282,7 +282,7 @@ impl TrashManager {
 
         let meta_path = self.meta_dir.join(format!("{id}.json"));
         let content = std::fs::read_to_string(&meta_path).map_err(|_| {
-            SafeAgentError::Config(format!("trash entry not found: {id}"))
+            SafeAgentError::NotFound(format!("trash entry {id}"))
         })?;
 
         serde_json::from_str(&content)
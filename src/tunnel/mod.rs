@@ -1,8 +1,9 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use tokio::process::Child;
 use tokio::sync::watch;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::config::TunnelConfig;
 
@@ -10,6 +11,9 @@ mod cloudflare;
 mod ngrok;
 mod tailscale;
 
+/// File in the data directory holding the last published tunnel URL.
+const PERSISTED_URL_FILE: &str = "tunnel_url";
+
 pub struct TunnelManager {
     child: Option<Child>,
     _url_tx: watch::Sender<Option<String>>,
@@ -17,8 +21,16 @@ pub struct TunnelManager {
 }
 
 impl TunnelManager {
-    pub async fn start(config: &TunnelConfig, local_port: u16) -> Self {
-        let (url_tx, url_rx) = watch::channel(None);
+    /// Start the configured tunnel provider.
+    ///
+    /// When the provider has a static domain (`NGROK_DOMAIN`, a Cloudflare
+    /// named tunnel hostname, or an explicit static URL) that URL is
+    /// published immediately, so skills see a stable `TUNNEL_URL` before
+    /// the provider finishes connecting.  Every URL the provider reports is
+    /// written to `$DATA_DIR/tunnel_url` for the next startup.
+    pub async fn start(config: &TunnelConfig, local_port: u16, data_dir: &Path) -> Self {
+        let initial = initial_url(static_url(config), persisted_url(data_dir));
+        let (url_tx, url_rx) = watch::channel(initial);
 
         let provider = config.provider.as_str();
         info!(provider, "starting tunnel");
@@ -33,6 +45,28 @@ impl TunnelManager {
             }
         };
 
+        // Persist every URL the provider publishes.
+        let mut rx = url_rx.clone();
+        let data_dir = data_dir.to_path_buf();
+        tokio::spawn(async move {
+            let mut last: Option<String> = None;
+            loop {
+                let current = rx
+                    .borrow_and_update()
+                    .clone()
+                    .filter(|url| last.as_deref() != Some(url.as_str()));
+                if let Some(url) = current {
+                    if let Err(e) = persist_url(&data_dir, &url) {
+                        warn!(err = %e, "failed to persist tunnel URL");
+                    }
+                    last = Some(url);
+                }
+                if rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+
         Self {
             child,
             _url_tx: url_tx,
@@ -58,3 +92,186 @@ pub type TunnelUrl = Arc<watch::Receiver<Option<String>>>;
 pub fn shared_url(mgr: &TunnelManager) -> TunnelUrl {
     Arc::new(mgr.url_receiver())
 }
+
+fn persisted_url_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(PERSISTED_URL_FILE)
+}
+
+/// Read the tunnel URL persisted by a previous run, if any.
+pub fn persisted_url(data_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(persisted_url_path(data_dir))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Write the current tunnel URL to the data directory.
+pub fn persist_url(data_dir: &Path, url: &str) -> std::io::Result<()> {
+    std::fs::write(persisted_url_path(data_dir), format!("{url}\n"))
+}
+
+/// The stable public URL for the configured provider, if it has one.
+///
+/// Environment overrides take precedence over config values, matching the
+/// provider start functions.
+pub fn static_url(config: &TunnelConfig) -> Option<String> {
+    static_url_with(config, |key| std::env::var(key).ok())
+}
+
+fn static_url_with(
+    config: &TunnelConfig,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let env_or = |key: &str, fallback: &str| -> Option<String> {
+        env(key)
+            .filter(|v| !v.is_empty())
+            .or_else(|| (!fallback.is_empty()).then(|| fallback.to_string()))
+    };
+
+    match config.provider.as_str() {
+        "ngrok" => env_or("NGROK_DOMAIN", &config.ngrok.domain).map(|d| with_scheme(&d)),
+        "cloudflare" => env_or("CLOUDFLARE_TUNNEL_URL", &config.cloudflare.url).or_else(|| {
+            if !config.cloudflare.tunnel_id.is_empty() && !config.cloudflare.hostname.is_empty() {
+                Some(with_scheme(&config.cloudflare.hostname))
+            } else {
+                None
+            }
+        }),
+        "tailscale" => env_or("TAILSCALE_TUNNEL_URL", &config.tailscale.url),
+        _ => None,
+    }
+}
+
+fn with_scheme(domain: &str) -> String {
+    if domain.starts_with("https://") || domain.starts_with("http://") {
+        domain.to_string()
+    } else {
+        format!("https://{domain}")
+    }
+}
+
+/// Pick the URL to publish before the provider reports one.
+///
+/// A configured static domain always wins.  A persisted URL is only
+/// trusted when it matches that domain — ephemeral URLs (free ngrok,
+/// Cloudflare quick tunnels) are stale after a restart.
+fn initial_url(configured: Option<String>, persisted: Option<String>) -> Option<String> {
+    match (configured, persisted) {
+        (Some(configured), Some(persisted)) => {
+            if configured == persisted {
+                info!(url = %configured, "restored persisted tunnel URL");
+            } else {
+                warn!(
+                    configured = %configured,
+                    persisted = %persisted,
+                    "persisted tunnel URL differs from configured domain — using configured domain"
+                );
+            }
+            Some(configured)
+        }
+        (Some(configured), None) => Some(configured),
+        (None, Some(persisted)) => {
+            info!(
+                previous = %persisted,
+                "no static tunnel domain configured — previous URL will change"
+            );
+            None
+        }
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn persisted_url_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        assert!(persisted_url(tmp.path()).is_none());
+
+        persist_url(tmp.path(), "https://abc.ngrok-free.app").unwrap();
+        assert_eq!(
+            persisted_url(tmp.path()).as_deref(),
+            Some("https://abc.ngrok-free.app")
+        );
+
+        persist_url(tmp.path(), "https://def.ngrok-free.app").unwrap();
+        assert_eq!(
+            persisted_url(tmp.path()).as_deref(),
+            Some("https://def.ngrok-free.app")
+        );
+    }
+
+    #[test]
+    fn persisted_url_ignores_empty_file() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(PERSISTED_URL_FILE), "  \n").unwrap();
+        assert!(persisted_url(tmp.path()).is_none());
+    }
+
+    #[test]
+    fn static_url_from_ngrok_domain() {
+        let mut config = TunnelConfig::default();
+        assert!(static_url_with(&config, no_env).is_none());
+
+        config.ngrok.domain = "myapp.ngrok-free.app".into();
+        assert_eq!(
+            static_url_with(&config, no_env).as_deref(),
+            Some("https://myapp.ngrok-free.app")
+        );
+    }
+
+    #[test]
+    fn static_url_env_overrides_config() {
+        let mut config = TunnelConfig::default();
+        config.ngrok.domain = "config.ngrok-free.app".into();
+        let env = |key: &str| (key == "NGROK_DOMAIN").then(|| "env.ngrok-free.app".to_string());
+        assert_eq!(
+            static_url_with(&config, env).as_deref(),
+            Some("https://env.ngrok-free.app")
+        );
+    }
+
+    #[test]
+    fn static_url_from_cloudflare_named_tunnel() {
+        let mut config = TunnelConfig {
+            provider: "cloudflare".into(),
+            ..Default::default()
+        };
+        config.cloudflare.hostname = "agent.example.com".into();
+        // Quick tunnels have no stable hostname.
+        assert!(static_url_with(&config, no_env).is_none());
+
+        config.cloudflare.tunnel_id = "abc-123".into();
+        assert_eq!(
+            static_url_with(&config, no_env).as_deref(),
+            Some("https://agent.example.com")
+        );
+    }
+
+    #[test]
+    fn initial_url_prefers_configured_domain() {
+        let configured = Some("https://stable.example.com".to_string());
+        let persisted = Some("https://old.ngrok-free.app".to_string());
+        assert_eq!(
+            initial_url(configured.clone(), persisted).as_deref(),
+            Some("https://stable.example.com")
+        );
+        assert_eq!(
+            initial_url(configured, None).as_deref(),
+            Some("https://stable.example.com")
+        );
+    }
+
+    #[test]
+    fn initial_url_drops_ephemeral_persisted_url() {
+        let persisted = Some("https://random.ngrok-free.app".to_string());
+        assert!(initial_url(None, persisted).is_none());
+    }
+}
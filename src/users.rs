@@ -51,6 +51,20 @@ impl UserRole {
         matches!(self, Self::Admin | Self::User)
     }
 
+    /// Whether this role has every permission of `other`
+    /// (admin ⊃ user ⊃ viewer).
+    pub fn at_least(&self, other: UserRole) -> bool {
+        self.rank() >= other.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Viewer => 0,
+            Self::User => 1,
+            Self::Admin => 2,
+        }
+    }
+
 }
 
 impl std::fmt::Display for UserRole {
@@ -117,6 +131,11 @@ impl UserContext {
 // User Manager
 // ---------------------------------------------------------------------------
 
+/// Prefix of issued API keys, so they're recognisable in configs and logs.
+const API_KEY_PREFIX: &str = "sa-";
+/// Random bytes in an API key.
+const API_KEY_BYTES: usize = 24;
+
 /// Manages user CRUD and lookup operations.
 pub struct UserManager {
     pub(crate) db: Arc<Mutex<Connection>>,
@@ -170,7 +189,7 @@ impl UserManager {
             row_to_user_raw,
         )
         .map(|raw| raw.decrypt(&self.enc))
-        .map_err(|_| SafeAgentError::Config(format!("user not found: {user_id}")))
+        .map_err(|_| SafeAgentError::NotFound(format!("user {user_id}")))
     }
 
     /// Get a user by username.
@@ -488,6 +507,53 @@ impl UserManager {
         Ok(())
     }
 
+    /// Issue a new API key for a user, replacing any previous one.  Only
+    /// the key's blind index is stored, so the plaintext is returned once.
+    pub async fn issue_api_key(&self, user_id: &str) -> Result<String> {
+        let mut bytes = [0u8; API_KEY_BYTES];
+        rand::RngExt::fill(&mut rand::rng(), &mut bytes[..]);
+        let key = format!("{API_KEY_PREFIX}{}", crate::crypto::hex_encode(&bytes));
+
+        let db = self.db.lock().await;
+        let updated = db.execute(
+            "UPDATE users SET api_key_blind = ?1, updated_at = datetime('now') WHERE id = ?2",
+            rusqlite::params![self.enc.blind_index(&key), user_id],
+        )?;
+        if updated == 0 {
+            return Err(SafeAgentError::NotFound(format!("user {user_id}")));
+        }
+        info!(user_id, "API key issued");
+        Ok(key)
+    }
+
+    /// Revoke a user's API key.
+    pub async fn revoke_api_key(&self, user_id: &str) -> Result<()> {
+        let db = self.db.lock().await;
+        db.execute(
+            "UPDATE users SET api_key_blind = NULL, updated_at = datetime('now') WHERE id = ?1",
+            [user_id],
+        )?;
+        info!(user_id, "API key revoked");
+        Ok(())
+    }
+
+    /// Look up the user an API key belongs to.
+    pub async fn get_by_api_key(&self, key: &str) -> Option<User> {
+        if !key.starts_with(API_KEY_PREFIX) {
+            return None;
+        }
+        let blind = self.enc.blind_index(key);
+        let db = self.db.lock().await;
+        db.query_row(
+            "SELECT id, username, display_name, role, email, password_hash, telegram_id, whatsapp_id, imessage_id, twilio_number, android_sms_id, discord_id, signal_id, timezone, locale, enabled, last_seen_at, created_at, updated_at
+             FROM users WHERE api_key_blind = ?1",
+            [&blind],
+            row_to_user_raw,
+        )
+        .ok()
+        .map(|raw| raw.decrypt(&self.enc))
+    }
+
     /// Delete a user.
     pub async fn delete(&self, user_id: &str) -> Result<()> {
         let db = self.db.lock().await;
@@ -761,6 +827,15 @@ mod tests {
         assert_eq!(fetched.username, "alice");
     }
 
+    #[test]
+    fn roles_are_ordered() {
+        assert!(UserRole::Admin.at_least(UserRole::User));
+        assert!(UserRole::User.at_least(UserRole::User));
+        assert!(UserRole::User.at_least(UserRole::Viewer));
+        assert!(!UserRole::Viewer.at_least(UserRole::User));
+        assert!(!UserRole::User.at_least(UserRole::Admin));
+    }
+
     #[tokio::test]
     async fn duplicate_username_fails() {
         let db = test_db();
@@ -849,6 +924,25 @@ mod tests {
         assert!(mgr.get_by_username("ivan").await.is_none());
     }
 
+    #[tokio::test]
+    async fn api_keys_identify_their_user_until_replaced() {
+        let db = test_db();
+        let mgr = UserManager::new(db, test_encryptor());
+        let user = mgr.create("judy", "Judy", UserRole::User, "pw").await.unwrap();
+        let key = mgr.issue_api_key(&user.id).await.unwrap();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(mgr.get_by_api_key(&key).await.unwrap().id, user.id);
+        assert!(mgr.get_by_api_key("sa-nope").await.is_none());
+
+        let rotated = mgr.issue_api_key(&user.id).await.unwrap();
+        assert!(mgr.get_by_api_key(&key).await.is_none());
+        assert_eq!(mgr.get_by_api_key(&rotated).await.unwrap().username, "judy");
+
+        mgr.revoke_api_key(&user.id).await.unwrap();
+        assert!(mgr.get_by_api_key(&rotated).await.is_none());
+        assert!(mgr.issue_api_key("missing").await.is_err());
+    }
+
     #[test]
     fn user_role_display_and_parse() {
         assert_eq!(UserRole::Admin.as_str(), "admin");